@@ -0,0 +1,124 @@
+//! Generates the static country table of the `static-table` feature from
+//! `vat_rates.json`.
+
+use std::env;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    if env::var_os("CARGO_FEATURE_STATIC_TABLE").is_none() {
+        return;
+    }
+    println!("cargo:rerun-if-changed=vat_rates.json");
+
+    let data = fs::read_to_string("vat_rates.json").expect("vat_rates.json is readable");
+    let data: serde_json::Value = serde_json::from_str(&data).expect("vat_rates.json is valid");
+
+    let mut codes: Vec<&String> = data
+        .as_object()
+        .expect("vat_rates.json is an object")
+        .iter()
+        .filter(|(_, country)| country.is_object())
+        .map(|(code, _)| code)
+        .collect();
+    codes.sort();
+
+    let mut out = String::from("&[\n");
+    for code in codes {
+        let country = &data[code];
+        let mut states: Vec<(&String, &serde_json::Value)> = country
+            .get("states")
+            .and_then(|states| states.as_object())
+            .map(|states| states.iter().collect())
+            .unwrap_or_default();
+        states.sort_by_key(|(code, _)| *code);
+
+        writeln!(out, "    CountryRecord {{").unwrap();
+        writeln!(out, "        code: {:?},", code).unwrap();
+        writeln!(out, "        tax_type: {},", tax_type(&country["type"])).unwrap();
+        writeln!(
+            out,
+            "        currency: {:?},",
+            country["currency"].as_str().unwrap()
+        )
+        .unwrap();
+        writeln!(
+            out,
+            "        standard_rate: {},",
+            rate(&country["standard_rate"])
+        )
+        .unwrap();
+        for field in [
+            "reduced_rate",
+            "reduced_rate_alt",
+            "super_reduced_rate",
+            "parking_rate",
+        ] {
+            writeln!(
+                out,
+                "        {}: {},",
+                field,
+                optional_rate(country.get(field))
+            )
+            .unwrap();
+        }
+        for field in ["vat_name", "vat_abbr"] {
+            let value = country.get(field).and_then(|value| value.as_str());
+            writeln!(out, "        {}: {:?},", field, value).unwrap();
+        }
+        writeln!(out, "        states: &[").unwrap();
+        for (code, state) in states {
+            let flag = |field: &str| state.get(field).and_then(|v| v.as_bool()).unwrap_or(false);
+            writeln!(
+                out,
+                "            StateRecord {{ code: {:?}, tax_type: {}, standard_rate: {}, local_taxes: {}, notice_and_report: {} }},",
+                code,
+                tax_type(&state["type"]),
+                rate(&state["standard_rate"]),
+                flag("local_taxes"),
+                flag("notice_and_report"),
+            )
+            .unwrap();
+        }
+        writeln!(out, "        ],").unwrap();
+        writeln!(out, "    }},").unwrap();
+    }
+    out.push(']');
+
+    let path = Path::new(&env::var("OUT_DIR").unwrap()).join("countries.rs");
+    fs::write(path, out).expect("Generated table is writable");
+}
+
+fn tax_type(value: &serde_json::Value) -> &'static str {
+    match value.as_str().expect("type is a string") {
+        "vat" => "TaxSystemType::Vat",
+        "gst" => "TaxSystemType::Gst",
+        "pst" => "TaxSystemType::Pst",
+        "hst" => "TaxSystemType::Hst",
+        "qst" => "TaxSystemType::Qst",
+        "icms" => "TaxSystemType::Icms",
+        "none" => "TaxSystemType::None",
+        other => panic!("unknown tax system type {}", other),
+    }
+}
+
+/// A rate as a `Percentage` constructor, from the shortest decimal form of the
+/// number (0.055 is mantissa 55, scale 3).
+fn rate(value: &serde_json::Value) -> String {
+    let rate = value.as_f64().expect("rate is a number");
+    let text = rate.to_string();
+    let (whole, fraction) = text.split_once('.').unwrap_or((&text, ""));
+    let mantissa: u32 = format!("{}{}", whole, fraction)
+        .parse()
+        .expect("rate fits the table");
+    format!("Percentage::from_parts({}, {})", mantissa, fraction.len())
+}
+
+fn optional_rate(value: Option<&serde_json::Value>) -> String {
+    match value {
+        Some(value) if value.is_number() => format!("Some({})", rate(value)),
+        _ => "None".to_string(),
+    }
+}
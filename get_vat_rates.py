@@ -7,6 +7,15 @@ from typing import Dict
 FORMAT1_URL = "https://github.com/valeriansaliou/node-sales-tax/raw/master/res/sales_tax_rates.json"
 FORMAT2_URL = "https://github.com/benbucksch/eu-vat-rates/raw/master/rates.json"
 OUTPUTT_FILE = "vat_rates.json"
+# Dataset schema version written to the output file (see src/migration.rs)
+SCHEMA_VERSION = 2
+# Curated state keys that aren't in the sources, kept from the existing output file
+CURATED_STATE_KEYS = ["fees", "local_taxes", "notice_and_report", "legal_reference", "economic_nexus"]
+# Curated country keys that aren't in the sources, kept from the existing output file
+CURATED_COUNTRY_KEYS = ["import_rules", "legal_reference", "rate_history", "category_rates"]
+# Countries curated by hand (EFTA, EU candidates, GCC members without VAT, Brazil, Japan), kept from
+# the existing output file over the sources
+CURATED_COUNTRIES = ["AL", "BA", "BR", "CH", "GE", "IS", "JP", "KW", "LI", "MD", "ME", "MK", "NO", "QA", "RS", "TR", "UA"]
 
 def fetch_to_temp(url: str) -> str:
     """Fetch JSON from URL and save to temp file"""
@@ -37,6 +46,13 @@ def convert_format1(data: Dict) -> Dict:
         result[country_code] = converted
     return result
 
+def rate(country_data: Dict, key: str):
+    """Convert a percentage to a fraction; boolean sentinels ("no rate") become None"""
+    value = country_data.get(key, 0)
+    if isinstance(value, bool):
+        return None
+    return value / 100
+
 def convert_format2(data: Dict) -> Dict:
     """Convert Format 2 to unified format"""
     result = {}
@@ -45,15 +61,39 @@ def convert_format2(data: Dict) -> Dict:
             "type": "vat",
             "currency": "EUR",
             "standard_rate": country_data.get("standard_rate", 0) / 100,
-            "reduced_rate": country_data.get("reduced_rate", 0) / 100,
-            "reduced_rate_alt": country_data.get("reduced_rate_alt", 0) / 100,
-            "super_reduced_rate": country_data.get("super_reduced_rate", 0) / 100,
-            "parking_rate": country_data.get("parking_rate", 0) / 100,
+            "reduced_rate": rate(country_data, "reduced_rate"),
+            "reduced_rate_alt": rate(country_data, "reduced_rate_alt"),
+            "super_reduced_rate": rate(country_data, "super_reduced_rate"),
+            "parking_rate": rate(country_data, "parking_rate"),
             "vat_name": country_data.get("vat_name", ""),
             "vat_abbr": country_data.get("vat_abbr", "")
         }
     return result
 
+def keep_curated(merged: Dict, existing: Dict) -> None:
+    """Copy curated countries and state keys from the existing output file"""
+    for country_code, country_data in existing.items():
+        if not isinstance(country_data, dict):
+            continue
+        if country_code in CURATED_COUNTRIES:
+            merged[country_code] = country_data
+            continue
+        for key in CURATED_COUNTRY_KEYS:
+            if key in country_data and country_code in merged:
+                merged[country_code][key] = country_data[key]
+        states = merged.get(country_code, {}).get("states")
+        if states is None:
+            continue
+        for state_code, state_data in (country_data.get("states") or {}).items():
+            if state_code not in states:
+                # Curated states without a state rate in the sources (e.g. Alaska)
+                if any(key in state_data for key in CURATED_STATE_KEYS):
+                    states[state_code] = state_data
+                continue
+            for key in CURATED_STATE_KEYS:
+                if key in state_data:
+                    states[state_code][key] = state_data[key]
+
 def main():
     # 1. Fetch from URLs to temp files
     temp_file1 = fetch_to_temp(FORMAT1_URL)
@@ -70,8 +110,15 @@ def main():
     converted2 = convert_format2(data2)
     
     # 3. Merge and deduplicate (Format 2 overwrites Format 1)
-    merged = {**converted1, **converted2}
+    merged = {"schema_version": SCHEMA_VERSION, **converted1, **converted2}
     
+    # Keep curated data of the previous run
+    try:
+        with open(OUTPUTT_FILE) as f:
+            keep_curated(merged, json.load(f))
+    except FileNotFoundError:
+        pass
+
     # 4. Write to output file
     with open(OUTPUTT_FILE, "w") as f:
         json.dump(merged, f, indent=2)
@@ -0,0 +1,168 @@
+//! Aliases of country and subdivision codes.
+//!
+//! Frontends send region codes in many forms: lower case, with other separators
+//! ("US_CA", "US.CA"), with the EU's VAT prefixes instead of ISO 3166-1 codes ("EL"
+//! for Greece), or with subdivision codes ISO 3166-2 has since renamed ("CA-PQ" for
+//! Quebec, "IN-OR" for Odisha). `Region::normalize` maps all of them to the ISO codes
+//! the dataset uses.
+//!
+//! ISO 3166-1 alpha-3 ("DEU") and numeric ("276") country codes are accepted by
+//! `Region::new` and `Region::parse` too, also as the prefix of a subdivision
+//! ("USA-CA").
+
+use crate::errors::InputValidationError;
+use crate::Region;
+
+/// Country codes that aren't ISO 3166-1, with the ISO code they stand for.
+pub const COUNTRY_ALIASES: [(&str, &str); 2] = [
+    ("EL", "GR"), // Greece, as used for EU VAT
+    ("UK", "GB"), // United Kingdom
+];
+
+/// Renamed or legacy ISO 3166-2 codes, with the current code.
+pub const SUBDIVISION_ALIASES: [(&str, &str); 56] = [
+    // Canada
+    ("CA-NF", "CA-NL"), // Newfoundland and Labrador, renamed 2002
+    ("CA-PQ", "CA-QC"), // Quebec, postal abbreviation before 1990
+    ("CA-YK", "CA-YT"), // Yukon, abbreviation before 1990
+    // Czechia: regions by letter until 2016
+    ("CZ-JC", "CZ-31"),
+    ("CZ-JM", "CZ-64"),
+    ("CZ-KA", "CZ-41"),
+    ("CZ-KR", "CZ-52"),
+    ("CZ-LI", "CZ-51"),
+    ("CZ-MO", "CZ-80"),
+    ("CZ-OL", "CZ-71"),
+    ("CZ-PA", "CZ-53"),
+    ("CZ-PL", "CZ-32"),
+    ("CZ-PR", "CZ-10"),
+    ("CZ-ST", "CZ-20"),
+    ("CZ-US", "CZ-42"),
+    ("CZ-VY", "CZ-63"),
+    ("CZ-ZL", "CZ-72"),
+    // Spain
+    ("ES-GE", "ES-GI"), // Girona
+    // France: regions before the 2016 merger, and codes changed by ISO
+    ("FR-75", "FR-75C"), // Paris
+    ("FR-A", "FR-GES"),
+    ("FR-B", "FR-NAQ"),
+    ("FR-C", "FR-ARA"),
+    ("FR-D", "FR-BFC"),
+    ("FR-E", "FR-BRE"),
+    ("FR-F", "FR-CVL"),
+    ("FR-G", "FR-GES"),
+    ("FR-GF", "FR-973"), // French Guiana
+    ("FR-GP", "FR-971"), // Guadeloupe
+    ("FR-H", "FR-20R"),
+    ("FR-I", "FR-BFC"),
+    ("FR-J", "FR-IDF"),
+    ("FR-K", "FR-OCC"),
+    ("FR-L", "FR-NAQ"),
+    ("FR-M", "FR-GES"),
+    ("FR-MQ", "FR-972"), // Martinique
+    ("FR-N", "FR-OCC"),
+    ("FR-O", "FR-HDF"),
+    ("FR-P", "FR-NOR"),
+    ("FR-Q", "FR-NOR"),
+    ("FR-R", "FR-PDL"),
+    ("FR-RE", "FR-974"), // Réunion
+    ("FR-S", "FR-HDF"),
+    ("FR-T", "FR-NAQ"),
+    ("FR-U", "FR-PAC"),
+    ("FR-V", "FR-ARA"),
+    ("FR-YT", "FR-976"), // Mayotte
+    // Greece
+    ("GR-A1", "GR-I"), // Attica
+    // India
+    ("IN-CT", "IN-CG"), // Chhattisgarh
+    ("IN-DD", "IN-DH"), // Daman and Diu, merged 2020
+    ("IN-DN", "IN-DH"), // Dadra and Nagar Haveli, merged 2020
+    ("IN-OR", "IN-OD"), // Odisha, renamed 2011
+    ("IN-TG", "IN-TS"), // Telangana
+    ("IN-UL", "IN-UK"), // Uttarakhand, formerly Uttaranchal
+    ("IN-UT", "IN-UK"), // Uttarakhand
+    // Mexico
+    ("MX-DIF", "MX-CMX"), // Mexico City, renamed 2016
+    // United States
+    ("US-CM", "US-MP"), // Northern Mariana Islands, postal abbreviation before 1988
+];
+
+/// The ISO 3166-1 alpha-2 code of an alpha-3 ("DEU") or numeric ("276") country code,
+/// in any case.
+///
+/// # Examples
+///
+/// ```
+/// # use world_tax::alias::alpha2;
+/// assert_eq!(alpha2("DEU"), Some("DE"));
+/// assert_eq!(alpha2("usa"), Some("US"));
+/// assert_eq!(alpha2("040"), Some("AT"));
+/// assert_eq!(alpha2("DE"), None);
+/// ```
+pub fn alpha2(code: &str) -> Option<&'static str> {
+    let code = code.trim();
+    if code.len() != 3 {
+        return None;
+    }
+    let country = if code.bytes().all(|byte| byte.is_ascii_digit()) {
+        rust_iso3166::from_numeric_str(code)
+    } else {
+        rust_iso3166::from_alpha3(&code.to_ascii_uppercase())
+    };
+    country.map(|country| country.alpha2)
+}
+
+/// The current ISO 3166-2 code of a subdivision code, if it's an alias.
+pub(crate) fn subdivision_alias(code: &str) -> Option<&'static str> {
+    SUBDIVISION_ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == code)
+        .map(|(_, current)| *current)
+}
+
+/// The ISO 3166-1 code of a country code, if it's an alias.
+fn country_alias(code: &str) -> Option<&'static str> {
+    COUNTRY_ALIASES
+        .iter()
+        .find(|(alias, _)| *alias == code)
+        .map(|(_, current)| *current)
+}
+
+impl Region {
+    /// Creates a region from a code in any common convention.
+    ///
+    /// The code is trimmed and upper cased, `_`, `.`, `/` and spaces separate the
+    /// country from the subdivision, and country and subdivision aliases (see
+    /// `COUNTRY_ALIASES` and `SUBDIVISION_ALIASES`) are replaced by their ISO codes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use world_tax::Region;
+    /// assert_eq!(Region::normalize("ca_pq").unwrap().to_string(), "CA-QC");
+    /// assert_eq!(Region::normalize("EL").unwrap().country, "GR");
+    /// assert_eq!(Region::normalize(" us.ca ").unwrap().region.as_deref(), Some("US-CA"));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the normalized code isn't an ISO 3166 code.
+    pub fn normalize(input: &str) -> Result<Self, InputValidationError> {
+        let code: String = input
+            .trim()
+            .chars()
+            .map(|c| match c {
+                '_' | '.' | '/' | ' ' => '-',
+                c => c.to_ascii_uppercase(),
+            })
+            .collect();
+        let (country, subdivision) = match code.split_once('-') {
+            Some((country, subdivision)) => (country, Some(subdivision)),
+            None => (code.as_str(), None),
+        };
+        let country = country_alias(country).unwrap_or(country);
+
+        let region = subdivision.map(|subdivision| format!("{}-{}", country, subdivision));
+        Self::new(country.to_string(), region)
+    }
+}
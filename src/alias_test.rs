@@ -0,0 +1,129 @@
+#[cfg(test)]
+mod tests {
+    use crate::alias::{COUNTRY_ALIASES, SUBDIVISION_ALIASES};
+    use crate::{Region, TaxDatabase, TaxScenario, TransactionType};
+
+    #[test]
+    fn test_iso_codes_normalize_to_themselves() {
+        // Frontend libraries (e.g. the iso-3166 npm packages) list subdivisions by
+        // their ISO 3166-2 codes, which must pass through unchanged
+        for country in rust_iso3166::ALL {
+            let region = Region::normalize(country.alpha2).expect("ISO country code");
+            assert_eq!(region.country, country.alpha2);
+            assert_eq!(region.region, None);
+
+            for subdivision in country.subdivisions().unwrap_or_default() {
+                let region = Region::normalize(subdivision.code).expect("ISO subdivision code");
+                assert_eq!(region.country, country.alpha2, "{}", subdivision.code);
+                assert_eq!(
+                    region.region.as_deref(),
+                    Some(subdivision.code),
+                    "{}",
+                    subdivision.code
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_aliases_map_to_iso_codes() {
+        for (alias, code) in COUNTRY_ALIASES {
+            assert!(rust_iso3166::from_alpha2(alias).is_none(), "{}", alias);
+            assert!(rust_iso3166::from_alpha2(code).is_some(), "{}", code);
+        }
+        for (alias, code) in SUBDIVISION_ALIASES {
+            assert!(
+                rust_iso3166::iso3166_2::from_code(alias).is_none(),
+                "{} is a current code",
+                alias
+            );
+            assert!(
+                rust_iso3166::iso3166_2::from_code(code).is_some(),
+                "{} isn't an ISO code",
+                code
+            );
+            assert_eq!(
+                alias.split_once('-').unwrap().0,
+                code.split_once('-').unwrap().0
+            );
+        }
+    }
+
+    #[test]
+    fn test_frontend_conventions() {
+        let cases = [
+            ("us-ca", "US", Some("US-CA")),
+            ("US_CA", "US", Some("US-CA")),
+            ("ca.qc", "CA", Some("CA-QC")),
+            ("CA/BC", "CA", Some("CA-BC")),
+            (" de ", "DE", None),
+            ("CA-PQ", "CA", Some("CA-QC")),
+            ("in_or", "IN", Some("IN-OD")),
+            ("FR-J", "FR", Some("FR-IDF")),
+            ("UK", "GB", None),
+            ("uk-eng", "GB", Some("GB-ENG")),
+            ("EL", "GR", None),
+        ];
+        for (input, country, region) in cases {
+            let normalized = Region::normalize(input).expect(input);
+            assert_eq!(normalized.country, country, "{}", input);
+            assert_eq!(normalized.region.as_deref(), region, "{}", input);
+        }
+
+        assert!(Region::normalize("XX").is_err());
+        assert!(Region::normalize("CA-XX").is_err());
+    }
+
+    #[test]
+    fn test_alpha3_and_numeric_codes() {
+        for country in rust_iso3166::ALL {
+            for code in [
+                country.alpha3.to_string(),
+                format!("{:03}", country.numeric),
+            ] {
+                let region = Region::parse(&code).expect("ISO country code");
+                assert_eq!(region.country, country.alpha2, "{}", code);
+            }
+        }
+
+        let cases = [
+            ("deu", "DE", None),
+            ("276", "DE", None),
+            ("USA-CA", "US", Some("US-CA")),
+            ("usa-ca", "US", Some("US-CA")),
+            ("124-BC", "CA", Some("CA-BC")),
+        ];
+        for (input, country, region) in cases {
+            for parsed in [Region::parse(input), Region::normalize(input)] {
+                let parsed = parsed.expect(input);
+                assert_eq!(parsed.country, country, "{}", input);
+                assert_eq!(parsed.region.as_deref(), region, "{}", input);
+            }
+        }
+
+        let region = Region::new("840".to_string(), Some("ca".to_string())).expect("Valid region");
+        assert_eq!(region.region.as_deref(), Some("US-CA"));
+        assert!(Region::parse("XXX").is_err());
+        assert!(Region::parse("999").is_err());
+        assert!(Region::parse("DEU-CA").is_err());
+    }
+
+    #[test]
+    fn test_legacy_codes_find_rates() {
+        let db = TaxDatabase::fixture();
+        let scenario = |destination: Region| {
+            TaxScenario::new(
+                Region::normalize("CA-ON").unwrap(),
+                destination,
+                TransactionType::B2C,
+            )
+        };
+        let legacy = scenario(Region::new("CA".to_string(), Some("PQ".to_string())).unwrap());
+        assert_eq!(legacy.destination_region.region.as_deref(), Some("CA-QC"));
+
+        let quebec = scenario(Region::parse("CA-QC").unwrap());
+        let rates = legacy.get_rates(100.0, &db).unwrap();
+        assert!(!rates.is_empty());
+        assert_eq!(rates, quebec.get_rates(100.0, &db).unwrap());
+    }
+}
@@ -0,0 +1,402 @@
+//! Asynchronous rate lookups.
+//!
+//! With the `async` feature, `TaxScenario::calculate_tax_async` and `get_rates_async`
+//! look up countries, rates and trade agreements through the `AsyncRateProvider`
+//! trait, so lookups can hit a remote service or a database pool from async handlers
+//! without blocking. The data a scenario may need (the countries and rates of both
+//! regions, the agreements between them, and the exchange rates to the currencies of
+//! their thresholds) is fetched first, then the calculation
+//! runs as with a `RateProvider`.
+//!
+//! The returned futures are `Send`, and don't depend on a particular runtime.
+
+use std::borrow::Cow;
+use std::future::Future;
+
+use crate::currency::CurrencyCode;
+use crate::errors::DatabaseError;
+use crate::profile::CalculationProfile;
+use crate::rate_provider::RateProvider;
+use crate::types::{Country, TaxRate, TradeAgreement, VatRate};
+use crate::{ProcessingError, TaxDatabase, TaxScenario, TradeAgreementOverride};
+
+/// Looks up the data tax calculations need, asynchronously.
+///
+/// # Examples
+///
+/// ```
+/// use world_tax::async_provider::AsyncRateProvider;
+/// use world_tax::types::{Country, TaxRate, TradeAgreement, VatRate};
+/// use world_tax::{
+///     DatabaseError, ProcessingError, RateProvider, Region, TaxDatabase, TaxScenario,
+///     TransactionType,
+/// };
+///
+/// /// Fetches rates from a remote service; here, the embedded dataset.
+/// struct Remote {
+///     db: TaxDatabase,
+/// }
+///
+/// impl AsyncRateProvider for Remote {
+///     async fn get_country(&self, code: &str) -> Result<Country, DatabaseError> {
+///         RateProvider::get_country(&self.db, code).map(|country| country.into_owned())
+///     }
+///
+///     async fn get_rate(
+///         &self,
+///         country: &str,
+///         region: Option<&str>,
+///         vat_rate: Option<&VatRate>,
+///     ) -> Result<Vec<TaxRate>, DatabaseError> {
+///         self.db.get_rate(country, region, vat_rate)
+///     }
+///
+///     async fn get_rule(&self, rule_id: &str) -> Result<TradeAgreement, DatabaseError> {
+///         self.db.get_rule(rule_id)
+///     }
+///
+///     async fn get_federal_rule(&self, country: &str) -> Option<TradeAgreement> {
+///         self.db.get_federal_rule(country)
+///     }
+///
+///     async fn get_international_rule(&self, source: &str, dest: &str) -> Option<TradeAgreement> {
+///         self.db.get_international_rule(source, dest)
+///     }
+/// }
+///
+/// async fn handle(remote: &Remote) -> Result<f64, ProcessingError> {
+///     let scenario = TaxScenario::new(
+///         Region::parse("DE").unwrap(),
+///         Region::parse("FR").unwrap(),
+///         TransactionType::B2C,
+///     );
+///     scenario.calculate_tax_async(100.0, remote).await
+/// }
+/// ```
+pub trait AsyncRateProvider {
+    /// Retrieves the tax information of a country.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::CountryNotFound` if the country isn't known.
+    fn get_country(
+        &self,
+        code: &str,
+    ) -> impl Future<Output = Result<Country, DatabaseError>> + Send;
+
+    /// Retrieves the tax rates of a jurisdiction (see `TaxDatabase::get_rate`).
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::CountryNotFound` if the country isn't known, and
+    /// `DatabaseError::VatRateNotFound` if the requested VAT rate doesn't exist.
+    fn get_rate(
+        &self,
+        country: &str,
+        region: Option<&str>,
+        vat_rate: Option<&VatRate>,
+    ) -> impl Future<Output = Result<Vec<TaxRate>, DatabaseError>> + Send;
+
+    /// Retrieves a trade agreement by identifier (e.g. "EU").
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::TradeAgreementNotFound` if the agreement isn't known.
+    fn get_rule(
+        &self,
+        rule_id: &str,
+    ) -> impl Future<Output = Result<TradeAgreement, DatabaseError>> + Send;
+
+    /// Finds the federal agreement between the states of a country (e.g. "US").
+    fn get_federal_rule(
+        &self,
+        country: &str,
+    ) -> impl Future<Output = Option<TradeAgreement>> + Send;
+
+    /// Finds the international agreement between two countries, if both implemented
+    /// its tax rules.
+    fn get_international_rule(
+        &self,
+        source: &str,
+        dest: &str,
+    ) -> impl Future<Output = Option<TradeAgreement>> + Send;
+
+    /// Retrieves a calculation profile by name; no profiles by default.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::ProfileNotFound` if no profile has that name.
+    fn get_profile(
+        &self,
+        name: &str,
+    ) -> impl Future<Output = Result<CalculationProfile, DatabaseError>> + Send {
+        let error = DatabaseError::ProfileNotFound(name.to_string());
+        async move { Err(error) }
+    }
+
+    /// The rate an amount in `from` is multiplied by to state it in `to`, to compare
+    /// it against thresholds; none by default.
+    fn exchange_rate(
+        &self,
+        _from: CurrencyCode,
+        _to: CurrencyCode,
+    ) -> impl Future<Output = Option<f64>> + Send {
+        async { None }
+    }
+}
+
+impl AsyncRateProvider for TaxDatabase {
+    async fn get_country(&self, code: &str) -> Result<Country, DatabaseError> {
+        self.country(code).cloned()
+    }
+
+    async fn get_rate(
+        &self,
+        country: &str,
+        region: Option<&str>,
+        vat_rate: Option<&VatRate>,
+    ) -> Result<Vec<TaxRate>, DatabaseError> {
+        TaxDatabase::get_rate(self, country, region, vat_rate)
+    }
+
+    async fn get_rule(&self, rule_id: &str) -> Result<TradeAgreement, DatabaseError> {
+        TaxDatabase::get_rule(self, rule_id)
+    }
+
+    async fn get_federal_rule(&self, country: &str) -> Option<TradeAgreement> {
+        TaxDatabase::get_federal_rule(self, country)
+    }
+
+    async fn get_international_rule(&self, source: &str, dest: &str) -> Option<TradeAgreement> {
+        TaxDatabase::get_international_rule(self, source, dest)
+    }
+
+    async fn get_profile(&self, name: &str) -> Result<CalculationProfile, DatabaseError> {
+        TaxDatabase::get_profile(self, name).copied()
+    }
+
+    async fn exchange_rate(&self, from: CurrencyCode, to: CurrencyCode) -> Option<f64> {
+        TaxDatabase::exchange_rate(self, from, to)
+    }
+}
+
+/// The country and region of a rate lookup.
+type RateKey = (String, Option<String>);
+
+/// The data of a scenario, fetched from an `AsyncRateProvider`.
+pub(crate) struct Prefetched {
+    countries: Vec<(String, Result<Country, DatabaseError>)>,
+    rates: Vec<(RateKey, Result<Vec<TaxRate>, DatabaseError>)>,
+    rules: Vec<(String, Result<TradeAgreement, DatabaseError>)>,
+    federal_rule: Option<(String, TradeAgreement)>,
+    international_rule: Option<((String, String), TradeAgreement)>,
+    profile: Option<(String, CalculationProfile)>,
+    exchange_rates: Vec<(CurrencyCode, f64)>,
+}
+
+impl Prefetched {
+    pub(crate) async fn fetch<P: AsyncRateProvider + ?Sized>(
+        scenario: &TaxScenario,
+        db: &P,
+    ) -> Result<Self, DatabaseError> {
+        let (source, destination) = (&scenario.source_region, &scenario.destination_region);
+        let mut prefetched = Prefetched {
+            countries: Vec::new(),
+            rates: Vec::new(),
+            rules: Vec::new(),
+            federal_rule: None,
+            international_rule: None,
+            profile: None,
+            exchange_rates: Vec::new(),
+        };
+
+        for region in [source, destination] {
+            if prefetched
+                .countries
+                .iter()
+                .all(|(code, _)| *code != region.country)
+            {
+                let country = db.get_country(&region.country).await;
+                prefetched.countries.push((region.country.clone(), country));
+            }
+            let key = (region.country.clone(), region.region.clone());
+            if prefetched.rates.iter().all(|(fetched, _)| *fetched != key) {
+                let rates = db
+                    .get_rate(
+                        &region.country,
+                        region.region.as_deref(),
+                        scenario.vat_rate.as_ref(),
+                    )
+                    .await;
+                prefetched.rates.push((key, rates));
+            }
+        }
+
+        let mut rule_ids = vec!["EU".to_string()];
+        if let Some(TradeAgreementOverride::UseAgreement(id)) = &scenario.trade_agreement_override {
+            rule_ids.push(id.clone());
+        }
+        for id in rule_ids {
+            let rule = db.get_rule(&id).await;
+            prefetched.rules.push((id, rule));
+        }
+
+        if source.country == destination.country {
+            prefetched.federal_rule = db
+                .get_federal_rule(&source.country)
+                .await
+                .map(|rule| (source.country.clone(), rule));
+        } else {
+            prefetched.international_rule = db
+                .get_international_rule(&source.country, &destination.country)
+                .await
+                .map(|rule| ((source.country.clone(), destination.country.clone()), rule));
+        }
+
+        if let Some(name) = &scenario.profile {
+            prefetched.profile = Some((name.clone(), db.get_profile(name).await?));
+        }
+
+        if let Some(currency) = scenario.currency {
+            let rules = prefetched
+                .rules
+                .iter()
+                .filter_map(|(_, rule)| rule.as_ref().ok())
+                .chain(prefetched.federal_rule.iter().map(|(_, rule)| rule))
+                .chain(prefetched.international_rule.iter().map(|(_, rule)| rule));
+            let mut currencies: Vec<CurrencyCode> = Vec::new();
+            for rule in rules {
+                let tax_rules = &rule.tax_rules;
+                let configs = [
+                    &tax_rules.internal_b2b,
+                    &tax_rules.internal_b2c,
+                    &tax_rules.fallback,
+                ];
+                let thresholds = configs
+                    .into_iter()
+                    .flatten()
+                    .flat_map(|config| [config.threshold, config.threshold_digital_products]);
+                for to in thresholds
+                    .flatten()
+                    .filter_map(|threshold| threshold.currency)
+                {
+                    if to != currency && !currencies.contains(&to) {
+                        currencies.push(to);
+                    }
+                }
+            }
+            for to in currencies {
+                if let Some(rate) = db.exchange_rate(currency, to).await {
+                    prefetched.exchange_rates.push((to, rate));
+                }
+            }
+        }
+        Ok(prefetched)
+    }
+}
+
+impl RateProvider for Prefetched {
+    fn get_country(&self, code: &str) -> Result<Cow<'_, Country>, DatabaseError> {
+        match self.countries.iter().find(|(fetched, _)| fetched == code) {
+            Some((_, country)) => country.as_ref().map(Cow::Borrowed).map_err(Clone::clone),
+            None => Err(DatabaseError::CountryNotFound {
+                code: code.to_string(),
+                suggestions: Vec::new(),
+            }),
+        }
+    }
+
+    fn get_rate(
+        &self,
+        country: &str,
+        region: Option<&str>,
+        _vat_rate: Option<&VatRate>,
+    ) -> Result<Vec<TaxRate>, DatabaseError> {
+        let fetched = self
+            .rates
+            .iter()
+            .find(|((fetched_country, fetched_region), _)| {
+                fetched_country == country && fetched_region.as_deref() == region
+            });
+        match fetched {
+            Some((_, rates)) => rates.clone(),
+            None => Err(DatabaseError::CountryNotFound {
+                code: country.to_string(),
+                suggestions: Vec::new(),
+            }),
+        }
+    }
+
+    fn get_rule(&self, rule_id: &str) -> Result<TradeAgreement, DatabaseError> {
+        match self.rules.iter().find(|(id, _)| id == rule_id) {
+            Some((_, rule)) => rule.clone(),
+            None => Err(DatabaseError::TradeAgreementNotFound(rule_id.to_string())),
+        }
+    }
+
+    fn get_federal_rule(&self, country: &str) -> Option<TradeAgreement> {
+        self.federal_rule
+            .as_ref()
+            .filter(|(fetched, _)| fetched == country)
+            .map(|(_, rule)| rule.clone())
+    }
+
+    fn get_international_rule(&self, source: &str, dest: &str) -> Option<TradeAgreement> {
+        self.international_rule
+            .as_ref()
+            .filter(|((fetched_source, fetched_dest), _)| {
+                fetched_source == source && fetched_dest == dest
+            })
+            .map(|(_, rule)| rule.clone())
+    }
+
+    fn exchange_rate(&self, from: CurrencyCode, to: CurrencyCode) -> Option<f64> {
+        if from == to {
+            return Some(1.0);
+        }
+        self.exchange_rates
+            .iter()
+            .find(|(fetched, _)| *fetched == to)
+            .map(|(_, rate)| *rate)
+    }
+
+    fn get_profile(&self, name: &str) -> Result<&CalculationProfile, DatabaseError> {
+        self.profile
+            .as_ref()
+            .filter(|(fetched, _)| fetched == name)
+            .map(|(_, profile)| profile)
+            .ok_or_else(|| DatabaseError::ProfileNotFound(name.to_string()))
+    }
+}
+
+impl TaxScenario {
+    /// Calculates the tax amount with data of an `AsyncRateProvider`; see
+    /// `calculate_tax`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of `calculate_tax`, and of the provider's lookups.
+    pub async fn calculate_tax_async<P: AsyncRateProvider + ?Sized>(
+        &self,
+        amount: f64,
+        db: &P,
+    ) -> Result<f64, ProcessingError> {
+        let prefetched = Prefetched::fetch(self, db).await?;
+        self.calculate_tax(amount, &prefetched)
+    }
+
+    /// Gets the applicable tax rates with data of an `AsyncRateProvider`; see
+    /// `get_rates`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of `get_rates`, and of the provider's lookups.
+    pub async fn get_rates_async<P: AsyncRateProvider + ?Sized>(
+        &self,
+        amount: f64,
+        db: &P,
+    ) -> Result<Vec<TaxRate>, ProcessingError> {
+        let prefetched = Prefetched::fetch(self, db).await?;
+        self.get_rates(amount, &prefetched)
+    }
+}
@@ -0,0 +1,207 @@
+#[cfg(all(test, feature = "async"))]
+mod tests {
+    use std::future::Future;
+    use std::pin::pin;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::task::{Context, Poll, Wake, Waker};
+    use std::thread::{self, Thread};
+
+    use crate::async_provider::AsyncRateProvider;
+    use crate::currency::CurrencyCode;
+    use crate::profile::{CalculationProfile, Strictness};
+    use crate::types::{AnnualTurnover, Country, TradeAgreement};
+    use crate::{
+        DatabaseError, ProcessingError, Region, TaxDatabase, TaxRate, TaxScenario, TransactionType,
+        VatRate,
+    };
+
+    struct ThreadWaker(Thread);
+
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+        let mut context = Context::from_waker(&waker);
+        let mut future = pin!(future);
+        loop {
+            match future.as_mut().poll(&mut context) {
+                Poll::Ready(output) => return output,
+                Poll::Pending => thread::park(),
+            }
+        }
+    }
+
+    /// Yields once before each lookup, like a remote call, and counts the lookups.
+    struct Remote {
+        db: TaxDatabase,
+        lookups: AtomicUsize,
+    }
+
+    impl Remote {
+        async fn lookup(&self) {
+            self.lookups.fetch_add(1, Ordering::Relaxed);
+            let mut yielded = false;
+            std::future::poll_fn(|context| {
+                if yielded {
+                    Poll::Ready(())
+                } else {
+                    yielded = true;
+                    context.waker().wake_by_ref();
+                    Poll::Pending
+                }
+            })
+            .await;
+        }
+    }
+
+    impl AsyncRateProvider for Remote {
+        async fn get_country(&self, code: &str) -> Result<Country, DatabaseError> {
+            self.lookup().await;
+            self.db.country(code).cloned()
+        }
+
+        async fn get_rate(
+            &self,
+            country: &str,
+            region: Option<&str>,
+            vat_rate: Option<&VatRate>,
+        ) -> Result<Vec<TaxRate>, DatabaseError> {
+            self.lookup().await;
+            self.db.get_rate(country, region, vat_rate)
+        }
+
+        async fn get_rule(&self, rule_id: &str) -> Result<TradeAgreement, DatabaseError> {
+            self.lookup().await;
+            self.db.get_rule(rule_id)
+        }
+
+        async fn get_federal_rule(&self, country: &str) -> Option<TradeAgreement> {
+            self.lookup().await;
+            self.db.get_federal_rule(country)
+        }
+
+        async fn get_international_rule(&self, source: &str, dest: &str) -> Option<TradeAgreement> {
+            self.lookup().await;
+            self.db.get_international_rule(source, dest)
+        }
+    }
+
+    fn scenario(source: &str, destination: &str, transaction_type: TransactionType) -> TaxScenario {
+        TaxScenario::new(
+            Region::parse(source).unwrap(),
+            Region::parse(destination).unwrap(),
+            transaction_type,
+        )
+    }
+
+    #[test]
+    fn test_async_matches_sync() {
+        let db = TaxDatabase::new().expect("Embedded data should load");
+        let remote = Remote {
+            db: TaxDatabase::new().expect("Embedded data should load"),
+            lookups: AtomicUsize::new(0),
+        };
+
+        let scenarios = [
+            scenario("DE", "FR", TransactionType::B2C),
+            scenario("DE", "FR", TransactionType::B2B),
+            scenario("US-CA", "US-CA", TransactionType::B2C),
+            scenario("CA-ON", "CA-QC", TransactionType::B2C),
+            scenario("NO", "DE", TransactionType::B2C),
+        ];
+        for scenario in scenarios {
+            for amount in [100.0, 150_000.0] {
+                assert_eq!(
+                    block_on(scenario.calculate_tax_async(amount, &remote)).unwrap(),
+                    scenario.calculate_tax(amount, &db).unwrap()
+                );
+                assert_eq!(
+                    block_on(scenario.get_rates_async(amount, &remote)).unwrap(),
+                    scenario.get_rates(amount, &db).unwrap()
+                );
+            }
+        }
+        assert!(remote.lookups.load(Ordering::Relaxed) > 0);
+
+        // The database serves its own data asynchronously
+        let scenario = scenario("DE", "DE", TransactionType::B2C);
+        assert_eq!(
+            block_on(scenario.calculate_tax_async(100.0, &db)).unwrap(),
+            19.0
+        );
+    }
+
+    #[test]
+    fn test_async_errors_and_profiles() {
+        let mut db = TaxDatabase::new().expect("Embedded data should load");
+        db.register_profile(
+            "strict",
+            CalculationProfile {
+                strictness: Strictness::Strict,
+                ..Default::default()
+            },
+        );
+        let remote = Remote {
+            db: TaxDatabase::new().expect("Embedded data should load"),
+            lookups: AtomicUsize::new(0),
+        };
+
+        // Lookup errors of the provider are returned
+        assert!(matches!(
+            block_on(
+                scenario("DE", "AQ", TransactionType::B2C).calculate_tax_async(100.0, &remote)
+            ),
+            Err(ProcessingError::DatabaseError(
+                DatabaseError::CountryNotFound { .. }
+            ))
+        ));
+
+        let strict = scenario("DE", "DE", TransactionType::B2C).with_profile("strict");
+        assert_eq!(
+            block_on(strict.calculate_tax_async(100.0, &db)).unwrap(),
+            19.0
+        );
+        // Remote doesn't serve profiles
+        assert!(matches!(
+            block_on(strict.calculate_tax_async(100.0, &remote)),
+            Err(ProcessingError::DatabaseError(
+                DatabaseError::ProfileNotFound(_)
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_async_exchange_rates() {
+        let mut db = TaxDatabase::fixture();
+        db.set_exchange_rates(|from: CurrencyCode, to: CurrencyCode| {
+            (from.code() == "USD" && to.code() == "EUR").then_some(0.9)
+        });
+        let scenario = scenario("DE", "FR", TransactionType::B2C)
+            .with_currency(CurrencyCode::parse("USD").unwrap())
+            .with_turnover(AnnualTurnover::default());
+
+        // $10,500 are below the EU threshold of €10,000; taxed at the German rate
+        assert_eq!(
+            block_on(scenario.calculate_tax_async(10_500.0, &db)).unwrap(),
+            scenario.calculate_tax(10_500.0, &db).unwrap()
+        );
+        assert_eq!(
+            block_on(scenario.calculate_tax_async(10_500.0, &db)).unwrap(),
+            1995.0
+        );
+    }
+
+    #[test]
+    fn test_async_futures_are_send() {
+        fn assert_send<T: Send>(_: T) {}
+        let db = TaxDatabase::fixture();
+        let scenario = scenario("DE", "FR", TransactionType::B2C);
+        assert_send(scenario.calculate_tax_async(100.0, &db));
+        assert_send(scenario.get_rates_async(100.0, &db));
+    }
+}
@@ -0,0 +1,237 @@
+//! Audit file export of recorded transactions.
+//!
+//! Record each quoted transaction as a `TransactionRecord`, and export the records
+//! of a period as an `AuditFile`, so accountants can pull period data for audits.
+//! The file is a neutral JSON format; `SAFT_MAPPING` maps its fields to the
+//! elements of the OECD Standard Audit File for Tax (SAF-T 2.0), for conversion to
+//! a national SAF-T flavour.
+
+use serde::Serialize;
+
+use crate::calculation::rounded_rate_taxes;
+use crate::calculator::TaxQuote;
+use crate::certificate::ExemptionCertificate;
+use crate::percentage::Percentage;
+use crate::vat_id::VatIdCheck;
+use crate::{TaxCalculationType, TaxScenario, TaxType, TransactionType};
+
+/// Fields of the audit file, and the SAF-T 2.0 elements they map to.
+pub const SAFT_MAPPING: [(&str, &str); 13] = [
+    (
+        "records[].id",
+        "SourceDocuments/SalesInvoices/Invoice/InvoiceNo",
+    ),
+    (
+        "records[].date",
+        "SourceDocuments/SalesInvoices/Invoice/InvoiceDate",
+    ),
+    (
+        "records[].transaction_type",
+        "SourceDocuments/SalesInvoices/Invoice/InvoiceType",
+    ),
+    (
+        "records[].source",
+        "SourceDocuments/SalesInvoices/Invoice/ShipFrom/Address/Country",
+    ),
+    (
+        "records[].destination",
+        "SourceDocuments/SalesInvoices/Invoice/ShipTo/Address/Country",
+    ),
+    (
+        "records[].net",
+        "SourceDocuments/SalesInvoices/Invoice/DocumentTotals/NetTotal",
+    ),
+    (
+        "records[].tax",
+        "SourceDocuments/SalesInvoices/Invoice/DocumentTotals/TaxPayable",
+    ),
+    (
+        "records[].gross",
+        "SourceDocuments/SalesInvoices/Invoice/DocumentTotals/GrossTotal",
+    ),
+    (
+        "records[].taxes[].tax_type",
+        "SourceDocuments/SalesInvoices/Invoice/Line/TaxInformation/TaxType",
+    ),
+    (
+        "records[].taxes[].rate",
+        "SourceDocuments/SalesInvoices/Invoice/Line/TaxInformation/TaxPercentage",
+    ),
+    (
+        "records[].taxes[].base",
+        "SourceDocuments/SalesInvoices/Invoice/Line/TaxInformation/TaxBase",
+    ),
+    (
+        "records[].taxes[].amount",
+        "SourceDocuments/SalesInvoices/Invoice/Line/TaxInformation/TaxAmount/Amount",
+    ),
+    (
+        "records[].exemption_certificate.number",
+        "SourceDocuments/SalesInvoices/Invoice/Line/TaxExemptionReason",
+    ),
+];
+
+/// The tax of a single rate on a recorded transaction.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TaxLine {
+    /// The type of tax
+    pub tax_type: TaxType,
+    /// The tax rate
+    pub rate: Percentage,
+    /// The amount the rate was applied to
+    pub base: f64,
+    /// The tax amount, rounded to 2 decimal places
+    pub amount: f64,
+}
+
+/// A quoted transaction, with its tax-relevant fields.
+#[derive(Debug, Clone, Serialize)]
+pub struct TransactionRecord {
+    /// Invoice or transaction number
+    pub id: String,
+    /// Date of the transaction (ISO 8601, e.g. "2025-03-31")
+    pub date: String,
+    /// Where the seller is located
+    pub source: String,
+    /// Where the buyer is located
+    pub destination: String,
+    /// Type of transaction
+    pub transaction_type: TransactionType,
+    /// The calculation type that was applied
+    pub calculation_type: TaxCalculationType,
+    /// Net amount
+    pub net: f64,
+    /// Tax amount
+    pub tax: f64,
+    /// Gross amount
+    pub gross: f64,
+    /// Tax per rate
+    pub taxes: Vec<TaxLine>,
+    /// The buyer's resale or exemption certificate, if given
+    pub exemption_certificate: Option<ExemptionCertificate>,
+    /// The check of the buyer's VAT number, if given
+    pub buyer_vat_id: Option<VatIdCheck>,
+}
+
+impl TransactionRecord {
+    /// Records a quoted transaction.
+    pub fn new(
+        id: impl Into<String>,
+        date: impl Into<String>,
+        scenario: &TaxScenario,
+        quote: &TaxQuote,
+    ) -> Self {
+        let taxes: Vec<TaxLine> = quote
+            .rates
+            .iter()
+            .zip(rounded_rate_taxes(quote.amount, &quote.rates))
+            .map(|(rate, (base, amount))| TaxLine {
+                tax_type: rate.tax_type,
+                rate: rate.rate,
+                base,
+                amount,
+            })
+            .collect();
+        Self {
+            id: id.into(),
+            date: date.into(),
+            source: scenario.source_region.to_string(),
+            destination: scenario.destination_region.to_string(),
+            transaction_type: scenario.transaction_type.clone(),
+            calculation_type: quote.calculation_type.clone(),
+            net: quote.amount,
+            tax: quote.tax,
+            gross: quote.total,
+            taxes,
+            exemption_certificate: scenario.exemption_certificate.clone(),
+            buyer_vat_id: scenario.buyer_vat_id.clone(),
+        }
+    }
+}
+
+/// Totals of a tax type and rate over a period.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TaxSummary {
+    /// The type of tax
+    pub tax_type: TaxType,
+    /// The tax rate
+    pub rate: Percentage,
+    /// Sum of the bases
+    pub base: f64,
+    /// Sum of the tax amounts
+    pub amount: f64,
+}
+
+/// The recorded transactions of a period.
+///
+/// # Examples
+///
+/// ```
+/// # use world_tax::audit::{AuditFile, TransactionRecord};
+/// # use world_tax::calculator::{TaxCalculator, TaxRequest};
+/// # use world_tax::{Region, TaxDatabase, TaxScenario, TransactionType};
+/// let db = TaxDatabase::fixture();
+/// let germany = Region::new("DE".to_string(), None).unwrap();
+/// let scenario = TaxScenario::new(germany.clone(), germany, TransactionType::B2C);
+/// let quote = db.quote(TaxRequest::new(scenario.clone(), 100.0)).unwrap();
+///
+/// let records = vec![TransactionRecord::new("INV-1", "2025-03-02", &scenario, &quote)];
+/// let file = AuditFile::new("2025-03-01", "2025-03-31", &records);
+/// assert_eq!(file.summary[0].amount, 19.0);
+/// println!("{}", file.to_json());
+/// ```
+#[derive(Debug, Clone, Serialize)]
+pub struct AuditFile {
+    /// First day of the period (inclusive)
+    pub period_start: String,
+    /// Last day of the period (inclusive)
+    pub period_end: String,
+    /// The transactions of the period
+    pub records: Vec<TransactionRecord>,
+    /// Totals per tax type and rate
+    pub summary: Vec<TaxSummary>,
+}
+
+impl AuditFile {
+    /// Collects the records dated within the period (ISO 8601 dates, inclusive).
+    pub fn new(period_start: &str, period_end: &str, records: &[TransactionRecord]) -> Self {
+        let records: Vec<TransactionRecord> = records
+            .iter()
+            .filter(|record| {
+                record.date.as_str() >= period_start && record.date.as_str() <= period_end
+            })
+            .cloned()
+            .collect();
+
+        let mut summary: Vec<TaxSummary> = Vec::new();
+        for line in records.iter().flat_map(|record| &record.taxes) {
+            match summary
+                .iter_mut()
+                .find(|total| total.tax_type == line.tax_type && total.rate == line.rate)
+            {
+                Some(total) => {
+                    total.base += line.base;
+                    total.amount = ((total.amount + line.amount) * 100.0).round() / 100.0;
+                }
+                None => summary.push(TaxSummary {
+                    tax_type: line.tax_type,
+                    rate: line.rate,
+                    base: line.base,
+                    amount: line.amount,
+                }),
+            }
+        }
+
+        Self {
+            period_start: period_start.to_string(),
+            period_end: period_end.to_string(),
+            records,
+            summary,
+        }
+    }
+
+    /// Serializes the audit file as JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("Audit file serializes to JSON")
+    }
+}
@@ -0,0 +1,86 @@
+#[cfg(test)]
+mod tests {
+    use crate::audit::{AuditFile, TransactionRecord, SAFT_MAPPING};
+    use crate::calculator::{TaxCalculator, TaxRequest};
+    use crate::certificate::{CertificateKind, ExemptionCertificate};
+    use crate::{Region, TaxDatabase, TaxScenario, TaxType, ThresholdPolicy, TransactionType};
+
+    fn record(db: &TaxDatabase, id: &str, date: &str, scenario: &TaxScenario) -> TransactionRecord {
+        let quote = db
+            .quote(TaxRequest::new(scenario.clone(), 100.0))
+            .expect("Quote should succeed");
+        TransactionRecord::new(id, date, scenario, &quote)
+    }
+
+    fn germany() -> TaxScenario {
+        let germany = Region::parse("DE").expect("Valid region");
+        TaxScenario::new(germany.clone(), germany, TransactionType::B2C)
+    }
+
+    #[test]
+    fn test_record_compound_tax_lines() {
+        let db = TaxDatabase::fixture();
+        let british_columbia = Region::parse("CA-BC").expect("Valid region");
+        let mut scenario = TaxScenario::new(
+            british_columbia.clone(),
+            british_columbia,
+            TransactionType::B2C,
+        );
+        scenario.threshold_policy = ThresholdPolicy::AssumeAbove;
+
+        let record = record(&db, "INV-1", "2025-01-15", &scenario);
+        assert_eq!(record.tax, 12.35);
+        assert_eq!(record.taxes.len(), 2);
+        assert_eq!(record.taxes[0].tax_type, TaxType::GST);
+        assert_eq!(record.taxes[0].amount, 5.0);
+        // PST is compounded on GST
+        assert_eq!(record.taxes[1].tax_type, TaxType::PST);
+        assert_eq!(record.taxes[1].base, 105.0);
+        assert_eq!(record.taxes[1].amount, 7.35);
+    }
+
+    #[test]
+    fn test_audit_file_period() {
+        let db = TaxDatabase::fixture();
+        let records = vec![
+            record(&db, "INV-1", "2025-01-15", &germany()),
+            record(&db, "INV-2", "2025-01-31", &germany()),
+            record(&db, "INV-3", "2025-02-01", &germany()),
+        ];
+
+        let file = AuditFile::new("2025-01-01", "2025-01-31", &records);
+        assert_eq!(file.records.len(), 2);
+        assert_eq!(file.summary.len(), 1);
+        assert_eq!(file.summary[0].base, 200.0);
+        assert_eq!(file.summary[0].amount, 38.0);
+
+        let json: serde_json::Value =
+            serde_json::from_str(&file.to_json()).expect("Audit file is valid JSON");
+        assert_eq!(json["records"][1]["id"], "INV-2");
+    }
+
+    #[test]
+    fn test_saft_mapping_covers_record_fields() {
+        let db = TaxDatabase::fixture();
+        let scenario = germany().with_exemption_certificate(ExemptionCertificate::new(
+            "DE",
+            "EX-1",
+            CertificateKind::Exemption,
+        ));
+        let records = [record(&db, "INV-1", "2025-01-15", &scenario)];
+        let file = AuditFile::new("2025-01-01", "2025-01-31", &records);
+        let json: serde_json::Value =
+            serde_json::from_str(&file.to_json()).expect("Audit file is valid JSON");
+
+        for (field, _) in SAFT_MAPPING {
+            let mut value = &json;
+            for key in field.split('.') {
+                value = match key.strip_suffix("[]") {
+                    Some(key) => &value[key][0],
+                    None => &value[key],
+                };
+            }
+            assert!(!value.is_null(), "{} is missing", field);
+        }
+    }
+}
@@ -0,0 +1,115 @@
+//! Brazilian taxes on sales.
+//!
+//! A sale in Brazil carries several taxes at once: ICMS, the state tax on goods; ISS,
+//! the municipal tax on services; and the federal contributions PIS and COFINS on
+//! both (at their non-cumulative rates of 1.65% and 7.6%). The dataset has the
+//! internal ICMS rate of each state, and the federal and municipal taxes as the
+//! country's `additional_taxes`.
+//!
+//! Goods sold to another state are charged ICMS at the interstate rate, for the
+//! seller's state: 12%, or 7% from the South and Southeast (except Espírito Santo) to
+//! the other states (`interstate_rates`). On sales to consumers, the seller also
+//! charges the amount by which the buyer's state's internal rate exceeds it (DIFAL,
+//! diferencial de alíquotas), for the buyer's state.
+//!
+//! ISS is owed to the seller's municipality, at 2% to 5%. With local rates loaded for
+//! the seller's state (`TaxDatabase::load_local_rates`), the record of the seller's
+//! postal code (CEP) or municipality applies; without one, 5% is assumed.
+//!
+//! All rates apply to the net amount; ICMS included in its own base ("por dentro")
+//! isn't modelled.
+
+use rust_decimal::Decimal;
+
+use crate::explain::Trace;
+use crate::percentage::Percentage;
+use crate::rate_provider::{self, RateProvider};
+use crate::types::{Country, SupplyKind};
+use crate::{RateSource, TaxRate, TaxScenario, TaxType, TransactionType};
+
+impl TaxScenario {
+    /// Adds the rates of a sale within Brazil (or another country with ICMS).
+    pub(crate) fn icms_rates<P: RateProvider + ?Sized>(
+        &self,
+        country: &Country,
+        db: &P,
+        rates: &mut Vec<TaxRate>,
+        trace: &mut Trace,
+    ) {
+        let code = &self.source_region.country;
+        let internal = |state: Option<&str>| match state {
+            Some(state) => rate_provider::state(country, code, state)
+                .map_or((country.standard_rate, RateSource::Fallback), |state| {
+                    (state.standard_rate, RateSource::StateSpecific)
+                }),
+            None => (country.standard_rate, RateSource::CountryDefault),
+        };
+
+        let kind = self.supply_kind();
+        if kind != SupplyKind::Services {
+            let source = self.source_region.region.as_deref();
+            let destination = self.destination_region.region.as_deref();
+            match (source, destination, &country.interstate_rates) {
+                (Some(source), Some(destination), Some(interstate)) if source != destination => {
+                    let rate = interstate.between(source, destination);
+                    rates.push(TaxRate::new(
+                        rate,
+                        TaxType::ICMS,
+                        RateSource::CountryDefault,
+                    ));
+
+                    let (internal_rate, rate_source) = internal(Some(destination));
+                    let difference = internal_rate.fraction() - rate.fraction();
+                    let difal =
+                        self.transaction_type == TransactionType::B2C && difference > Decimal::ZERO;
+                    if difal {
+                        rates.push(TaxRate::new(
+                            Percentage::from_fraction(difference),
+                            TaxType::ICMS,
+                            rate_source,
+                        ));
+                    }
+                    trace.record("icms", || {
+                        format!(
+                            "{} to {}: interstate rate {}{}",
+                            source,
+                            destination,
+                            rate,
+                            if difal {
+                                format!(", DIFAL {}", Percentage::from_fraction(difference))
+                            } else {
+                                String::new()
+                            }
+                        )
+                    });
+                }
+                _ => {
+                    let (rate, rate_source) = internal(source.or(destination));
+                    rates.push(TaxRate::new(rate, TaxType::ICMS, rate_source));
+                    trace.record("icms", || format!("internal rate {}", rate));
+                }
+            }
+        }
+
+        for tax in country
+            .additional_taxes
+            .iter()
+            .filter(|tax| tax.covers(kind))
+        {
+            let local = if tax.local {
+                self.source_region.region.as_deref().and_then(|state| {
+                    let table = db.local_table(state)?;
+                    self.source_region
+                        .local_record(&table)
+                        .map(|record| record.rate)
+                })
+            } else {
+                None
+            };
+            rates.push(match local {
+                Some(rate) => TaxRate::new(Percentage::from_f64(rate), tax.tax, RateSource::Local),
+                None => TaxRate::new(tax.rate, tax.tax, RateSource::CountryDefault),
+            });
+        }
+    }
+}
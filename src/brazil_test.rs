@@ -0,0 +1,123 @@
+#[cfg(test)]
+mod tests {
+    use crate::sst::BoundaryTable;
+    use crate::types::SupplyKind;
+    use crate::{RateSource, Region, TaxDatabase, TaxScenario, TaxType, TransactionType};
+
+    fn setup() -> TaxDatabase {
+        TaxDatabase::from_files("vat_rates.json", "trade_agreements.json")
+            .expect("Tax database should load")
+    }
+
+    fn scenario(source: &str, destination: &str, transaction_type: TransactionType) -> TaxScenario {
+        TaxScenario::new(
+            Region::parse(source).expect("Valid region"),
+            Region::parse(destination).expect("Valid region"),
+            transaction_type,
+        )
+    }
+
+    fn rates(scenario: &TaxScenario, db: &TaxDatabase) -> Vec<(TaxType, String)> {
+        scenario
+            .get_rates(100.0, db)
+            .unwrap()
+            .into_iter()
+            .map(|rate| (rate.tax_type, rate.rate.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_intrastate_goods() {
+        let db = setup();
+        let sale = scenario("BR-SP", "BR-SP", TransactionType::B2C);
+        assert_eq!(
+            rates(&sale, &db),
+            vec![
+                (TaxType::ICMS, "18%".to_string()),
+                (TaxType::PIS, "1.65%".to_string()),
+                (TaxType::COFINS, "7.6%".to_string()),
+            ]
+        );
+        assert_eq!(sale.calculate_tax(100.0, &db).unwrap(), 27.25);
+    }
+
+    #[test]
+    fn test_interstate_goods() {
+        let db = setup();
+
+        // From the Southeast to the Northeast: 7%, and DIFAL up to Bahia's 20.5%
+        let to_consumer = scenario("BR-SP", "BR-BA", TransactionType::B2C);
+        assert_eq!(
+            rates(&to_consumer, &db)[..2],
+            [
+                (TaxType::ICMS, "7%".to_string()),
+                (TaxType::ICMS, "13.5%".to_string()),
+            ]
+        );
+        assert_eq!(to_consumer.calculate_tax(100.0, &db).unwrap(), 29.75);
+        let explanation = to_consumer.explain(100.0, &db).unwrap();
+        assert!(explanation
+            .steps
+            .iter()
+            .any(|step| step.check == "icms" && step.outcome.contains("DIFAL 13.5%")));
+
+        // Businesses account for the difference themselves
+        let to_business = scenario("BR-SP", "BR-BA", TransactionType::B2B);
+        assert_eq!(to_business.calculate_tax(100.0, &db).unwrap(), 16.25);
+
+        // 12% in the other direction, and between southern states
+        let northeast = scenario("BR-BA", "BR-SP", TransactionType::B2B);
+        assert_eq!(northeast.calculate_tax(100.0, &db).unwrap(), 21.25);
+        let south = scenario("BR-RS", "BR-SP", TransactionType::B2B);
+        assert_eq!(south.calculate_tax(100.0, &db).unwrap(), 21.25);
+
+        // 12% from the Northeast, with DIFAL up to Espírito Santo's 17%
+        let espirito_santo = scenario("BR-BA", "BR-ES", TransactionType::B2C);
+        assert_eq!(
+            rates(&espirito_santo, &db)[..2],
+            [
+                (TaxType::ICMS, "12%".to_string()),
+                (TaxType::ICMS, "5%".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_services() {
+        let mut db = setup();
+        let service =
+            scenario("BR-SP", "BR-RJ", TransactionType::B2B).with_supply_kind(SupplyKind::Services);
+        assert_eq!(
+            rates(&service, &db),
+            vec![
+                (TaxType::PIS, "1.65%".to_string()),
+                (TaxType::COFINS, "7.6%".to_string()),
+                (TaxType::ISS, "5%".to_string()),
+            ]
+        );
+
+        // The rate of the seller's municipality
+        db.load_local_rates(
+            "BR-SP",
+            BoundaryTable::parse("01000,05999,0.02,São Paulo\n13000,13149,0.05,Campinas").unwrap(),
+        );
+        let mut local = service.clone();
+        local.source_region = local.source_region.with_postal_code("01310-100");
+        let iss = local.get_rates(100.0, &db).unwrap().pop().unwrap();
+        assert_eq!(iss.rate.to_string(), "2%");
+        assert_eq!(iss.source, RateSource::Local);
+        assert_eq!(local.calculate_tax(100.0, &db).unwrap(), 11.25);
+
+        // Unknown municipalities pay the maximum rate
+        assert_eq!(service.calculate_tax(100.0, &db).unwrap(), 14.25);
+    }
+
+    #[test]
+    fn test_state_rates() {
+        let db = setup();
+        let rates = db.get_rate("BR", Some("BR-MA"), None).unwrap();
+        assert_eq!(rates[0].tax_type, TaxType::ICMS);
+        assert_eq!(rates[0].rate.to_string(), "23%");
+        assert_eq!(rates.len(), 3);
+    }
+}
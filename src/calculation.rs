@@ -5,14 +5,219 @@
 //! based on various scenarios and trade agreements.
 use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
 use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::iter::Sum;
 
-use crate::types::TaxSystemType;
+use crate::certificate::{is_iso_date, ExemptionCertificate};
+use crate::currency::CurrencyCode;
+use crate::errors::suggestions;
+use crate::explain::Trace;
+use crate::overrides::OverrideAction;
+use crate::percentage::Percentage;
+use crate::profile::{CalculationProfile, FallbackPolicy, Rounding, Strictness};
+use crate::rate_provider::{self, RateProvider};
+use crate::types::{
+    AnnualTurnover, AppliedRate, BuyerType, GrossSplit, Incoterm, RateBasis, RateSource,
+    SupplyKind, TaxCalculationResult, TaxSystemType, Threshold, ThresholdBasis, ThresholdPolicy,
+};
 
 use super::{
-    DatabaseError, ProcessingError, Region, TaxCalculationType, TaxDatabase, TaxRate, TaxScenario,
-    TaxType, TradeAgreement, TradeAgreementOverride, TransactionType, VatRate,
+    DatabaseError, InputValidationError, ProcessingError, Region, TaxCalculationType, TaxRate,
+    TaxScenario, TaxType, TradeAgreement, TradeAgreementOverride, TransactionType, VatRate,
 };
 
+/// Combines the percentage rates into a single effective rate, applying compounding;
+/// flat and per-unit amounts aren't included.
+pub(crate) fn combined_rate(rates: &[TaxRate]) -> f64 {
+    let mut parts: Vec<(TaxType, f64)> = Vec::with_capacity(rates.len());
+    for rate in rates
+        .iter()
+        .filter(|rate| rate.basis == RateBasis::Percentage)
+    {
+        let part = (1.0 + compounded(rate, &parts)) * rate.rate.to_f64();
+        parts.push((rate.tax_type, part));
+    }
+    parts.iter().map(|(_, part)| part).sum()
+}
+
+/// Combines the percentage rates into a single effective rate like `combined_rate`, as
+/// a decimal fraction.
+pub(crate) fn combined_rate_decimal(rates: &[TaxRate]) -> Decimal {
+    let mut parts: Vec<(TaxType, Decimal)> = Vec::with_capacity(rates.len());
+    for rate in rates
+        .iter()
+        .filter(|rate| rate.basis == RateBasis::Percentage)
+    {
+        let part = (Decimal::ONE + compounded(rate, &parts)) * rate.rate.fraction();
+        parts.push((rate.tax_type, part));
+    }
+    parts.iter().map(|(_, part)| part).sum()
+}
+
+/// The unrounded tax of each rate on an amount, applying compounding.
+fn rate_taxes(amount: Decimal, rates: &[TaxRate]) -> Vec<(TaxType, Decimal)> {
+    let mut taxes: Vec<(TaxType, Decimal)> = Vec::with_capacity(rates.len());
+    for rate in rates {
+        taxes.push((
+            rate.tax_type,
+            rate_tax_decimal(rate, amount, Decimal::ONE, &taxes),
+        ));
+    }
+    taxes
+}
+
+/// The base and tax of each rate on an amount, with the tax rounded to cents, and
+/// compounding on the rounded taxes before it (as booked per rate).
+pub(crate) fn rounded_rate_taxes(amount: f64, rates: &[TaxRate]) -> Vec<(f64, f64)> {
+    let amount = Decimal::from_f64(amount).unwrap_or_default();
+    let mut preceding: Vec<(TaxType, Decimal)> = Vec::with_capacity(rates.len());
+    rates
+        .iter()
+        .map(|rate| {
+            let base = amount + compounded(rate, &preceding);
+            let tax =
+                Rounding::default().apply(rate_tax_decimal(rate, amount, Decimal::ONE, &preceding));
+            preceding.push((rate.tax_type, tax));
+            (
+                base.to_f64().unwrap_or_default(),
+                tax.to_f64().unwrap_or_default(),
+            )
+        })
+        .collect()
+}
+
+/// The part of the preceding taxes a rate compounds on; nothing if it doesn't compound.
+pub(crate) fn compounded<T: Copy + Sum<T>>(rate: &TaxRate, preceding: &[(TaxType, T)]) -> T {
+    preceding
+        .iter()
+        .filter(|(tax_type, _)| {
+            rate.compound && rate.compounds_on.is_none_or(|target| target == *tax_type)
+        })
+        .map(|(_, tax)| *tax)
+        .sum()
+}
+
+/// Sums the tax of the given rates on a decimal amount, applying compounding and caps.
+pub(crate) fn sum_rates_decimal(amount: Decimal, rates: &[TaxRate]) -> Decimal {
+    sum_item_rates_decimal(amount, Decimal::ONE, rates)
+}
+
+/// Sums the tax of the given rates on `quantity` items of `unit_price`; caps per item
+/// apply to each unit, other caps to the whole amount.
+pub(crate) fn sum_item_rates_decimal(
+    unit_price: Decimal,
+    quantity: Decimal,
+    rates: &[TaxRate],
+) -> Decimal {
+    let amount = unit_price * quantity;
+    let mut taxes: Vec<(TaxType, Decimal)> = Vec::with_capacity(rates.len());
+
+    for rate in rates {
+        let tax_amount = match rate.cap {
+            Some(cap) if cap.per_item && !quantity.is_zero() => {
+                let per_unit: Vec<(TaxType, Decimal)> = taxes
+                    .iter()
+                    .map(|(tax_type, tax)| (*tax_type, tax / quantity))
+                    .collect();
+                rate_tax_decimal(rate, unit_price, Decimal::ONE, &per_unit) * quantity
+            }
+            _ => rate_tax_decimal(rate, amount, quantity, &taxes),
+        };
+        taxes.push((rate.tax_type, tax_amount));
+    }
+
+    taxes.iter().map(|(_, tax)| tax).sum()
+}
+
+/// The tax of a single rate on `amount` of `quantity` units, after the preceding taxes.
+fn rate_tax_decimal(
+    rate: &TaxRate,
+    amount: Decimal,
+    quantity: Decimal,
+    preceding: &[(TaxType, Decimal)],
+) -> Decimal {
+    let cap = rate.cap.unwrap_or_default();
+    let limit = |value: Decimal, max: Option<f64>| match max.and_then(Decimal::from_f64) {
+        Some(max) => value.max(-max).min(max),
+        None => value,
+    };
+    let fixed = |value: f64| {
+        let value = Decimal::from_f64(value).unwrap_or_default();
+        match amount.cmp(&Decimal::ZERO) {
+            std::cmp::Ordering::Less => -value,
+            std::cmp::Ordering::Equal => Decimal::ZERO,
+            std::cmp::Ordering::Greater => value,
+        }
+    };
+
+    let tax = match rate.basis {
+        RateBasis::Percentage => {
+            (limit(amount, cap.max_taxable_amount) + compounded(rate, preceding))
+                * rate.rate.fraction()
+        }
+        RateBasis::Flat(value) => fixed(value),
+        RateBasis::PerUnit(value) => fixed(value) * quantity.abs(),
+    };
+    limit(tax, cap.max_tax)
+}
+
+/// The unrounded net amount of a gross amount at the given rates.
+fn net_of_gross(gross: f64, rates: &[TaxRate]) -> Result<f64, ProcessingError> {
+    if is_linear(rates) {
+        return Ok(gross / (1.0 + combined_rate(rates)));
+    }
+    item_net_of_gross_decimal(decimal(gross)?, Decimal::ONE, rates)
+        .to_f64()
+        .ok_or(ProcessingError::InvalidAmount)
+}
+
+/// The unrounded net amount of `quantity` items of the gross `unit_price` at the given
+/// rates; caps and fixed amounts apply like in `sum_item_rates_decimal`.
+pub(crate) fn item_net_of_gross_decimal(
+    unit_price: Decimal,
+    quantity: Decimal,
+    rates: &[TaxRate],
+) -> Decimal {
+    let gross = unit_price * quantity;
+    let mut net = gross / (Decimal::ONE + combined_rate_decimal(rates));
+    if !is_linear(rates) && !quantity.is_zero() {
+        // Caps and fixed amounts make the tax non-linear; converge on the net amount whose tax adds up
+        // to the gross amount (the tax grows slower than the amount, so this converges)
+        for _ in 0..32 {
+            net = gross - sum_item_rates_decimal(net / quantity, quantity, rates);
+        }
+    }
+    net
+}
+
+/// Whether the tax of the rates is proportional to the amount: percentage rates without caps.
+fn is_linear(rates: &[TaxRate]) -> bool {
+    rates
+        .iter()
+        .all(|rate| rate.cap.is_none() && rate.basis == RateBasis::Percentage)
+}
+
+/// Converts an amount to a decimal, for calculation.
+fn decimal(amount: f64) -> Result<Decimal, ProcessingError> {
+    Decimal::from_f64(amount).ok_or(ProcessingError::InvalidAmount)
+}
+
+/// Adds two amounts in decimal arithmetic, so the sum has no floating point artifacts
+/// (0.06 minus 0.01 is 0.05, not 0.049999999999999996).
+pub(crate) fn add_amounts(amount: f64, other: f64) -> Result<f64, ProcessingError> {
+    (decimal(amount)? + decimal(other)?)
+        .to_f64()
+        .ok_or(ProcessingError::InvalidAmount)
+}
+
+/// Notes a threshold policy other than `Respect` in a trace.
+pub(crate) fn policy_note(policy: ThresholdPolicy) -> String {
+    match policy {
+        ThresholdPolicy::Respect => String::new(),
+        policy => format!(" ({:?})", policy),
+    }
+}
+
 impl TaxScenario {
     /// Creates a new tax calculation scenario with default settings.
     ///
@@ -44,12 +249,72 @@ impl TaxScenario {
             transaction_type,
             trade_agreement_override: None,
             is_digital_product_or_service: false,
-            has_resale_certificate: false,
-            ignore_threshold: false,
+            exemption_certificate: None,
+            buyer_vat_id: None,
+            threshold_policy: ThresholdPolicy::Respect,
             vat_rate: None,
+            profile: None,
+            incoterm: None,
+            currency: None,
+            turnover: None,
+            date: None,
+            supply_kind: None,
+            attributes: HashMap::new(),
         }
     }
 
+    /// Creates a scenario for a buyer, with the transaction type and certificate that
+    /// follow from the buyer type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use world_tax::certificate::{CertificateKind, ExemptionCertificate};
+    /// # use world_tax::{BuyerType, Region, TaxScenario, TransactionType};
+    /// let certificate = ExemptionCertificate::new("US-WA", "RS-1", CertificateKind::Resale);
+    /// let scenario = TaxScenario::for_buyer(
+    ///     Region::parse("US-TX").unwrap(),
+    ///     Region::parse("US-WA").unwrap(),
+    ///     BuyerType::Reseller,
+    ///     Some(certificate.clone()),
+    /// )
+    /// .unwrap();
+    /// assert_eq!(scenario.transaction_type, TransactionType::B2B);
+    ///
+    /// // Non-profits present an exemption certificate, not a resale certificate
+    /// assert!(TaxScenario::for_buyer(
+    ///     Region::parse("US-TX").unwrap(),
+    ///     Region::parse("US-WA").unwrap(),
+    ///     BuyerType::NonProfit,
+    ///     Some(certificate),
+    /// )
+    /// .is_err());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `InputValidationError::CertificateMismatch` if the buyer type can't
+    /// present the certificate.
+    pub fn for_buyer(
+        source_region: Region,
+        destination_region: Region,
+        buyer: BuyerType,
+        certificate: Option<ExemptionCertificate>,
+    ) -> Result<Self, InputValidationError> {
+        if let Some(certificate) = &certificate {
+            if buyer.certificate_kind() != Some(certificate.kind) {
+                return Err(InputValidationError::CertificateMismatch(format!(
+                    "{:?} buyer with {:?} certificate {}",
+                    buyer, certificate.kind, certificate.number
+                )));
+            }
+        }
+        let mut scenario =
+            TaxScenario::new(source_region, destination_region, buyer.transaction_type());
+        scenario.exemption_certificate = certificate;
+        Ok(scenario)
+    }
+
     /// Sets a trade agreement override for the scenario.
     ///
     /// # Arguments
@@ -60,6 +325,139 @@ impl TaxScenario {
         self
     }
 
+    /// References a calculation profile registered on the database.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name the profile was registered under
+    pub fn with_profile(mut self, name: impl Into<String>) -> Self {
+        self.profile = Some(name.into());
+        self
+    }
+
+    /// Sets the delivery terms of the scenario.
+    ///
+    /// # Arguments
+    ///
+    /// * `incoterm` - The incoterm of the shipment
+    pub fn with_incoterm(mut self, incoterm: Incoterm) -> Self {
+        self.incoterm = Some(incoterm);
+        self
+    }
+
+    /// Sets the currency the amounts are stated in.
+    ///
+    /// # Arguments
+    ///
+    /// * `currency` - The currency of the amounts
+    pub fn with_currency(mut self, currency: CurrencyCode) -> Self {
+        self.currency = Some(currency);
+        self
+    }
+
+    /// Sets what is supplied.
+    ///
+    /// # Arguments
+    ///
+    /// * `supply_kind` - Physical goods, digital goods or services
+    pub fn with_supply_kind(mut self, supply_kind: SupplyKind) -> Self {
+        self.supply_kind = Some(supply_kind);
+        self
+    }
+
+    /// What is supplied: the scenario's `supply_kind`, or else physical or digital
+    /// goods by `is_digital_product_or_service`.
+    pub fn supply_kind(&self) -> SupplyKind {
+        self.supply_kind
+            .unwrap_or(if self.is_digital_product_or_service {
+                SupplyKind::DigitalGoods
+            } else {
+                SupplyKind::PhysicalGoods
+            })
+    }
+
+    /// Sets the seller's turnover in the jurisdiction of the sale's threshold.
+    ///
+    /// # Arguments
+    ///
+    /// * `turnover` - The turnover, before the transaction
+    pub fn with_turnover(mut self, turnover: AnnualTurnover) -> Self {
+        self.turnover = Some(turnover);
+        self
+    }
+
+    /// Sets the date of the supply, to calculate with the agreement rules in effect then.
+    ///
+    /// # Arguments
+    ///
+    /// * `date` - The date of the supply (ISO 8601)
+    ///
+    /// # Errors
+    ///
+    /// Returns `InputValidationError::InvalidDate` if the date isn't an ISO 8601 date (YYYY-MM-DD).
+    pub fn with_date(mut self, date: &str) -> Result<Self, InputValidationError> {
+        if !is_iso_date(date) {
+            return Err(InputValidationError::InvalidDate(date.to_string()));
+        }
+        self.date = Some(date.to_string());
+        Ok(self)
+    }
+
+    /// Sets the buyer's resale or exemption certificate.
+    ///
+    /// # Arguments
+    ///
+    /// * `certificate` - The buyer's certificate
+    pub fn with_exemption_certificate(mut self, certificate: ExemptionCertificate) -> Self {
+        self.exemption_certificate = Some(certificate);
+        self
+    }
+
+    /// Returns true if the buyer's certificate covers the destination and isn't expired
+    /// on the date of the supply (today, without one).
+    pub fn has_valid_certificate(&self) -> bool {
+        self.exemption_certificate.is_some() && self.certificate_rejection().is_none()
+    }
+
+    /// Why the buyer's certificate isn't honored, if one is given and it isn't.
+    fn certificate_rejection(&self) -> Option<String> {
+        self.exemption_certificate.as_ref().and_then(|certificate| {
+            certificate.rejection(&self.destination_region, self.date.as_deref())
+        })
+    }
+
+    /// Returns true if the seller pays the destination's import taxes: physical goods
+    /// shipped delivered duty paid into another customs territory, or between members
+    /// of a customs union one of which hasn't implemented its tax rules.
+    pub fn is_delivered_duty_paid<P: RateProvider + ?Sized>(&self, db: &P) -> bool {
+        let (source, destination) = (
+            &self.source_region.country,
+            &self.destination_region.country,
+        );
+        self.incoterm
+            .is_some_and(|incoterm| incoterm.seller_pays_import_charges())
+            && !self.is_digital_product_or_service
+            && source != destination
+            && db.get_international_rule(source, destination).is_none()
+    }
+
+    /// Resolves the calculation profile referenced by the scenario.
+    ///
+    /// Returns the default profile if the scenario doesn't reference one.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::ProfileNotFound` if the referenced profile isn't registered.
+    pub fn resolve_profile<P: RateProvider + ?Sized>(
+        &self,
+        db: &P,
+    ) -> Result<CalculationProfile, DatabaseError> {
+        match &self.profile {
+            Some(name) => db.get_profile(name).copied(),
+            None => Ok(CalculationProfile::default()),
+        }
+    }
+
     /// Checks if the source and destination are in the same country.
     pub fn is_same_country(&self) -> bool {
         self.source_region.country == self.destination_region.country
@@ -76,48 +474,118 @@ impl TaxScenario {
     ///
     /// * `agreement` - The trade agreement to evaluate
     /// * `amount` - The transaction amount
+    /// * `db` - The tax database
+    /// * `profile` - The calculation profile in effect
+    /// * `trace` - Records the decisions taken
     ///
     /// # Returns
     ///
     /// Returns the appropriate tax calculation type based on the agreement rules.
-    fn get_calculation_type_from_agreement(
+    fn get_calculation_type_from_agreement<P: RateProvider + ?Sized>(
         &self,
         agreement: &TradeAgreement,
         amount: f64,
+        db: &P,
+        profile: &CalculationProfile,
+        trace: &mut Trace,
     ) -> Result<TaxCalculationType, ProcessingError> {
         if agreement.is_international() {
             // Custom union like EU
             match self.transaction_type {
                 TransactionType::B2B => {
                     let rule = &agreement.tax_rules.internal_b2b;
-                    if rule.is_some() {
+                    if let Some(rule) = rule {
                         // In the EU, likely to be reverse charge
-                        Ok(rule
-                            .clone()
-                            .unwrap()
-                            .by_threshold(amount as u32, self.ignore_threshold)
-                            .clone())
+                        let policy = self.unknown_turnover_policy(
+                            rule.threshold,
+                            self.threshold_policy,
+                            trace,
+                        );
+                        let (sales, currency) =
+                            self.threshold_amount(rule.threshold, amount, policy, db, trace)?;
+                        let calc_type = rule.by_threshold(sales, currency, policy)?.clone();
+                        trace.cite(rule.legal_reference.as_deref(), || {
+                            format!("{} internal_b2b", agreement.name)
+                        });
+                        trace.record("internal_b2b", || format!("{:?}", calc_type));
+                        if calc_type == TaxCalculationType::ReverseCharge {
+                            if let Some(rejection) =
+                                self.reverse_charge_rejection(agreement, profile)
+                            {
+                                // Taxed like a sale to a consumer
+                                trace.record("buyer_vat_id", || {
+                                    format!("{}: no reverse charge", rejection)
+                                });
+                                let mut consumer = self.clone();
+                                consumer.transaction_type = TransactionType::B2C;
+                                return consumer.get_calculation_type_from_agreement(
+                                    agreement, amount, db, profile, trace,
+                                );
+                            }
+                        }
+                        Ok(calc_type)
                     } else {
-                        // Fallback to a safe option
-                        Ok(TaxCalculationType::Destination)
+                        self.fallback_calculation_type(agreement, amount, db, profile, trace)
                     }
                 }
+                // A valid VAT number of the buyer makes the sale B2B
+                TransactionType::B2C
+                    if self.buyer_vat_id.is_some()
+                        && self.reverse_charge_rejection(agreement, profile).is_none() =>
+                {
+                    trace.record("buyer_vat_id", || "valid: taxed as B2B".into());
+                    let mut business = self.clone();
+                    business.transaction_type = TransactionType::B2B;
+                    business
+                        .get_calculation_type_from_agreement(agreement, amount, db, profile, trace)
+                }
                 TransactionType::B2C => {
                     let rule = &agreement.tax_rules.internal_b2c;
                     if rule.is_some() {
+                        // OSS sellers who opted into destination taxation are above the threshold
+                        let policy = match self.threshold_policy {
+                            ThresholdPolicy::Respect if profile.schemes.oss => {
+                                ThresholdPolicy::AssumeAbove
+                            }
+                            policy => policy,
+                        };
                         // In the EU, by threshold, likely to be origin or destination based
-                        Ok(rule
-                            .clone()
-                            .unwrap()
+                        let rule = rule.clone().unwrap();
+                        let threshold = rule.threshold_for(self.is_digital_product_or_service);
+                        let policy = self.unknown_turnover_policy(threshold, policy, trace);
+                        let (amount, currency) =
+                            self.threshold_amount(threshold, amount, policy, db, trace)?;
+                        let calc_type = rule
                             .by_threshold_or_digital_product_threshold(
-                                amount as u32,
+                                amount,
+                                currency,
                                 self.is_digital_product_or_service,
-                                self.ignore_threshold,
+                                policy,
+                            )?
+                            .clone();
+                        trace.cite(rule.legal_reference.as_deref(), || {
+                            format!("{} internal_b2c", agreement.name)
+                        });
+                        trace.record("internal_b2c", || {
+                            let threshold = rule
+                                .threshold_for(self.is_digital_product_or_service)
+                                .map(|threshold| threshold.amount);
+                            format!(
+                                "{} threshold {:?}, amount {}{}: {:?}",
+                                if self.is_digital_product_or_service {
+                                    "digital product"
+                                } else {
+                                    "standard"
+                                },
+                                threshold,
+                                amount,
+                                policy_note(policy),
+                                calc_type
                             )
-                            .clone())
+                        });
+                        Ok(calc_type)
                     } else {
-                        // Fallback to a safe option
-                        Ok(TaxCalculationType::Destination)
+                        self.fallback_calculation_type(agreement, amount, db, profile, trace)
                     }
                 }
             }
@@ -127,10 +595,16 @@ impl TaxScenario {
                 if let Some(region) = &self.destination_region.region {
                     // HST provinces should always charge HST
                     if ["CA-NS", "CA-NB", "CA-NL", "CA-ON", "CA-PE"].contains(&region.as_str()) {
+                        trace.record("province", || {
+                            format!("{} charges HST: Destination", region)
+                        });
                         return Ok(TaxCalculationType::Destination);
                     }
                     // QC should always charge GST+QST
                     if region == "CA-QC" {
+                        trace.record("province", || {
+                            format!("{} charges GST+QST: Destination", region)
+                        });
                         return Ok(TaxCalculationType::Destination);
                     }
                 }
@@ -142,74 +616,328 @@ impl TaxScenario {
                     let rule = &agreement.tax_rules.internal_b2b;
                     if rule.is_some() {
                         let u_rule = rule.clone().unwrap();
-                        if u_rule.is_reseller(self.has_resale_certificate) {
+                        if let Some(rejection) = self.certificate_rejection() {
+                            trace.record("exemption_certificate", || rejection);
+                        }
+                        if u_rule.is_reseller(self.has_valid_certificate()) {
+                            trace.cite(u_rule.legal_reference.as_deref(), || {
+                                format!("{} internal_b2b", agreement.name)
+                            });
+                            trace.record("resale_certificate", || "accepted: ZeroRated".into());
                             return Ok(TaxCalculationType::ZeroRated);
                         }
-                        Ok(rule
-                            .clone()
-                            .unwrap()
-                            .by_threshold(amount as u32, self.ignore_threshold)
-                            .clone())
+                        let policy = self.unknown_turnover_policy(
+                            u_rule.threshold,
+                            self.threshold_policy,
+                            trace,
+                        );
+                        let (amount, currency) =
+                            self.threshold_amount(u_rule.threshold, amount, policy, db, trace)?;
+                        let calc_type = u_rule.by_threshold(amount, currency, policy)?.clone();
+                        trace.cite(u_rule.legal_reference.as_deref(), || {
+                            format!("{} internal_b2b", agreement.name)
+                        });
+                        trace.record("internal_b2b", || format!("{:?}", calc_type));
+                        Ok(calc_type)
                     } else {
-                        Ok(TaxCalculationType::Destination)
+                        self.fallback_calculation_type(agreement, amount, db, profile, trace)
                     }
                 }
                 TransactionType::B2C => {
                     let rule = &agreement.tax_rules.internal_b2c;
+                    if let Some(has_nexus) = rule
+                        .as_ref()
+                        .map(|_| self.has_economic_nexus(amount, db, trace))
+                        .transpose()?
+                        .flatten()
+                    {
+                        // The destination state's own nexus test replaces the threshold
+                        let rule = rule.as_ref().unwrap();
+                        let calc_type = if has_nexus {
+                            rule.above_threshold.clone().unwrap_or(rule.r#type.clone())
+                        } else {
+                            self.below_nexus_calculation_type(db)
+                        };
+                        trace.cite(rule.legal_reference.as_deref(), || {
+                            format!("{} internal_b2c", agreement.name)
+                        });
+                        trace.record("internal_b2c", || {
+                            format!(
+                                "economic nexus{}: {:?}",
+                                policy_note(self.threshold_policy),
+                                calc_type
+                            )
+                        });
+                        return Ok(calc_type);
+                    }
                     if rule.is_some() {
+                        let threshold = rule.as_ref().and_then(|rule| rule.threshold);
+                        let policy =
+                            self.unknown_turnover_policy(threshold, self.threshold_policy, trace);
+                        let (amount, currency) =
+                            self.threshold_amount(threshold, amount, policy, db, trace)?;
                         // Check threshold except for HST/QST provinces
-                        if !self.ignore_threshold
-                            && amount < rule.clone().unwrap().threshold.unwrap_or(u32::MAX) as f64
-                        {
-                            return Ok(TaxCalculationType::ZeroRated);
+                        let is_below = match threshold {
+                            Some(threshold) => threshold.is_below(amount, currency, policy)?,
+                            None => policy.is_below(amount, Decimal::MAX),
+                        };
+                        let threshold = threshold.map(|threshold| threshold.amount);
+                        if is_below {
+                            let calc_type = self.below_nexus_calculation_type(db);
+                            trace.cite(
+                                rule.as_ref()
+                                    .and_then(|rule| rule.legal_reference.as_deref()),
+                                || format!("{} internal_b2c", agreement.name),
+                            );
+                            trace.record("internal_b2c", || {
+                                format!(
+                                    "below threshold {:?}, amount {}: {:?}",
+                                    threshold, amount, calc_type
+                                )
+                            });
+                            return Ok(calc_type);
                         }
-                        Ok(rule
+                        let calc_type = rule
                             .clone()
                             .unwrap()
-                            .by_threshold(amount as u32, self.ignore_threshold)
-                            .clone())
+                            .by_threshold(amount, currency, policy)?
+                            .clone();
+                        trace.cite(
+                            rule.as_ref()
+                                .and_then(|rule| rule.legal_reference.as_deref()),
+                            || format!("{} internal_b2c", agreement.name),
+                        );
+                        trace.record("internal_b2c", || {
+                            format!(
+                                "threshold {:?}, amount {}{}: {:?}",
+                                threshold,
+                                amount,
+                                policy_note(policy),
+                                calc_type
+                            )
+                        });
+                        Ok(calc_type)
                     } else {
-                        Ok(TaxCalculationType::Destination)
+                        self.fallback_calculation_type(agreement, amount, db, profile, trace)
                     }
                 }
             }
         } else {
+            trace.record("agreement_type", || "unknown: Destination".into());
             Ok(TaxCalculationType::Destination)
         }
     }
 
+    /// The amount to compare against a threshold, with its currency: the seller's
+    /// turnover for annual turnover thresholds, if known, converted to the threshold's
+    /// currency if the scenario states its amounts in another one, and the comparison
+    /// matters.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::ExchangeRateNotFound` if the provider has no exchange rate
+    /// between the currencies.
+    pub(crate) fn threshold_amount<P: RateProvider + ?Sized>(
+        &self,
+        threshold: Option<Threshold>,
+        amount: f64,
+        policy: ThresholdPolicy,
+        db: &P,
+        trace: &mut Trace,
+    ) -> Result<(Decimal, Option<CurrencyCode>), DatabaseError> {
+        let as_given = |amount: f64| (Decimal::from_f64(amount).unwrap_or_default(), self.currency);
+        let Some(threshold) = threshold.filter(|threshold| threshold.amount > Decimal::ZERO) else {
+            return Ok(as_given(amount));
+        };
+        let amount = match (threshold.basis, self.turnover) {
+            (ThresholdBasis::AnnualTurnover, Some(turnover)) => {
+                let cumulative = turnover.with_transaction(amount);
+                trace.record("turnover", || {
+                    format!(
+                        "{} this year, {} last year, {} with the transaction",
+                        turnover.current_year, turnover.previous_year, cumulative
+                    )
+                });
+                cumulative
+            }
+            _ => amount,
+        };
+        let (Some(from), Some(to)) = (self.currency, threshold.currency) else {
+            return Ok(as_given(amount));
+        };
+        if from == to || policy != ThresholdPolicy::Respect {
+            return Ok(as_given(amount));
+        }
+        let rate =
+            db.exchange_rate(from, to)
+                .ok_or_else(|| DatabaseError::ExchangeRateNotFound {
+                    from: from.to_string(),
+                    to: to.to_string(),
+                })?;
+        let converted = amount * rate;
+        trace.record("exchange_rate", || {
+            format!("{} {} = {} {} at {}", amount, from, converted, to, rate)
+        });
+        Ok((Decimal::from_f64(converted).unwrap_or_default(), Some(to)))
+    }
+
+    /// The threshold policy of a threshold on the seller's annual turnover, if the
+    /// turnover isn't known: a single sale says nothing about the year's sales, so the
+    /// sale is taxed as above the threshold.
+    pub(crate) fn unknown_turnover_policy(
+        &self,
+        threshold: Option<Threshold>,
+        policy: ThresholdPolicy,
+        trace: &mut Trace,
+    ) -> ThresholdPolicy {
+        let unknown = self.turnover.is_none()
+            && threshold.is_some_and(|threshold| {
+                threshold.basis == ThresholdBasis::AnnualTurnover
+                    && threshold.amount > Decimal::ZERO
+            });
+        if !unknown || policy != ThresholdPolicy::Respect {
+            return policy;
+        }
+        trace.record("turnover", || {
+            "unknown: taxed as above the annual turnover threshold".into()
+        });
+        ThresholdPolicy::AssumeAbove
+    }
+
+    /// Determines the calculation type of a B2C sale below the federal threshold, where
+    /// the seller has no nexus in the destination.
+    fn below_nexus_calculation_type<P: RateProvider + ?Sized>(&self, db: &P) -> TaxCalculationType {
+        let destination = &self.destination_region;
+        let notice_and_report = destination.country == "US"
+            && !self.is_same_state()
+            && destination.region.as_deref().is_some_and(|state| {
+                rate_provider::state_flag(db, &destination.country, state, |state| {
+                    state.notice_and_report
+                })
+            });
+        if notice_and_report {
+            TaxCalculationType::NoticeAndReport
+        } else {
+            TaxCalculationType::ZeroRated
+        }
+    }
+
+    /// Determines the calculation type when an agreement has no rule for the transaction
+    /// type: the agreement's `fallback` rule, or else the profile's fallback policy.
+    fn fallback_calculation_type<P: RateProvider + ?Sized>(
+        &self,
+        agreement: &TradeAgreement,
+        amount: f64,
+        db: &P,
+        profile: &CalculationProfile,
+        trace: &mut Trace,
+    ) -> Result<TaxCalculationType, ProcessingError> {
+        if let Some(rule) = &agreement.tax_rules.fallback {
+            let threshold = rule.threshold_for(self.is_digital_product_or_service);
+            let policy = self.unknown_turnover_policy(threshold, self.threshold_policy, trace);
+            let (amount, currency) = self.threshold_amount(threshold, amount, policy, db, trace)?;
+            let calc_type = rule
+                .by_threshold_or_digital_product_threshold(
+                    amount,
+                    currency,
+                    self.is_digital_product_or_service,
+                    policy,
+                )?
+                .clone();
+            trace.cite(rule.legal_reference.as_deref(), || {
+                format!("{} fallback", agreement.name)
+            });
+            trace.record("fallback", || {
+                format!(
+                    "{} has no {:?} rule: agreement fallback {:?}",
+                    agreement.name, self.transaction_type, calc_type
+                )
+            });
+            return Ok(calc_type);
+        }
+        trace.record("fallback", || {
+            format!(
+                "{} has no {:?} rule: default {:?}",
+                agreement.name, self.transaction_type, profile.fallback
+            )
+        });
+        match profile.fallback {
+            FallbackPolicy::Destination => Ok(TaxCalculationType::Destination),
+            FallbackPolicy::Origin => Ok(TaxCalculationType::Origin),
+            FallbackPolicy::Error => Err(DatabaseError::TaxRuleNotFound(format!(
+                "{} ({:?})",
+                agreement.name, self.transaction_type
+            ))
+            .into()),
+        }
+    }
+
     /// Determines which trade agreement rules apply to the scenario.
     ///
     /// # Arguments
     ///
     /// * `db` - The tax database containing trade agreements
+    /// * `trace` - Records the decisions taken
     ///
     /// # Returns
     ///
     /// Returns the applicable trade agreement, if any.
-    fn determine_rule(&self, db: &TaxDatabase) -> Result<Option<TradeAgreement>, DatabaseError> {
+    fn determine_rule<P: RateProvider + ?Sized>(
+        &self,
+        db: &P,
+        trace: &mut Trace,
+    ) -> Result<Option<TradeAgreement>, DatabaseError> {
         if self.trade_agreement_override.is_some() {
             let overwrite = self.trade_agreement_override.clone().unwrap();
+            trace.record("trade_agreement_override", || format!("{:?}", overwrite));
             match overwrite {
                 TradeAgreementOverride::UseAgreement(agreement) => {
                     let rule = db.get_rule(agreement.as_str())?;
-                    return Ok(Some(rule));
+                    return Ok(Some(self.rules_as_of(rule, trace)));
                 }
                 TradeAgreementOverride::NoAgreement => {
                     return Ok(None);
                 }
             }
         }
-        if self.is_same_country() {
+        let rule = if self.is_same_country() {
             // Same country; Federal agreement (for ex. USA)
-            Ok(db.get_federal_rule(self.source_region.country.as_str()))
+            db.get_federal_rule(self.source_region.country.as_str())
         } else {
             // Different countries; Customs union agreement (for ex. EU)
-            Ok(db.get_international_rule(
+            db.get_international_rule(
                 self.source_region.country.as_str(),
                 self.destination_region.country.as_str(),
-            ))
+            )
+        };
+        trace.record("agreement", || match &rule {
+            Some(rule) => format!("{} ({:?})", rule.name, rule.r#type),
+            None if self.is_same_country() => "none".into(),
+            None => match db.no_agreement_reason(
+                &self.source_region.country,
+                &self.destination_region.country,
+            ) {
+                Some(reason) => format!("none: {}", reason),
+                None => "none".into(),
+            },
+        });
+        Ok(rule.map(|rule| self.rules_as_of(rule, trace)))
+    }
+
+    /// Replaces the rules of an agreement with those in effect on the scenario's date.
+    fn rules_as_of(&self, mut agreement: TradeAgreement, trace: &mut Trace) -> TradeAgreement {
+        if let Some(date) = &self.date {
+            let rules = agreement.tax_rules.as_of(date);
+            trace.record("rules_as_of", || {
+                format!(
+                    "{}: rules valid from {}",
+                    date,
+                    rules.valid_from.as_deref().unwrap_or("the start")
+                )
+            });
+            agreement.tax_rules = rules.clone();
         }
+        agreement
     }
 
     /// Determines the appropriate tax calculation type for the scenario.
@@ -236,27 +964,76 @@ impl TaxScenario {
     /// # );
     /// let calc_type = scenario.determine_calculation_type(&db, 1000.0).unwrap();
     /// ```
-    pub fn determine_calculation_type(
+    pub fn determine_calculation_type<P: RateProvider + ?Sized>(
+        &self,
+        db: &P,
+        amount: f64,
+    ) -> Result<TaxCalculationType, ProcessingError> {
+        self.trace_calculation_type(db, amount, &mut Trace::disabled())
+    }
+
+    /// Determines the calculation type, recording each decision in `trace`.
+    pub(crate) fn trace_calculation_type<P: RateProvider + ?Sized>(
         &self,
-        db: &TaxDatabase,
+        db: &P,
         amount: f64,
+        trace: &mut Trace,
     ) -> Result<TaxCalculationType, ProcessingError> {
+        // Override rules take precedence over the dataset's rules
+        if let Some(OverrideAction::Exempt(_)) = self.trace_override(db, trace) {
+            return Ok(TaxCalculationType::Exempt);
+        }
+
         // Check if there's a trade rule
-        let agreement = self.determine_rule(db)?;
+        let agreement = self.determine_rule(db, trace)?;
 
         if agreement.is_none() {
             // No agreement found, use default rules
             if self.is_same_country() {
+                trace.record("no_agreement", || "same country: Origin".into());
                 match self.transaction_type {
                     TransactionType::B2B => return Ok(TaxCalculationType::Origin),
                     TransactionType::B2C => return Ok(TaxCalculationType::Origin),
                 }
-            } else {
-                return Ok(TaxCalculationType::ZeroRated);
             }
+            if self.is_ioss_sale(amount, &self.resolve_profile(db)?, db) {
+                trace.record("no_agreement", || {
+                    "EU import under IOSS: Destination".into()
+                });
+                return Ok(TaxCalculationType::Destination);
+            }
+            if let Some((sale, calc_type)) = self.import_rule(amount, db, trace)? {
+                trace.record("import_rules", || {
+                    format!(
+                        "{} {} from abroad: {:?}",
+                        self.destination_region.country, sale, calc_type
+                    )
+                });
+                return Ok(calc_type);
+            }
+            if self.is_delivered_duty_paid(db) {
+                trace.record("incoterm", || {
+                    "DDP: seller pays import taxes: Destination".into()
+                });
+                return Ok(TaxCalculationType::Destination);
+            }
+            if let Some(incoterm) = self.incoterm {
+                trace.record("incoterm", || {
+                    format!("{:?}: buyer pays import taxes", incoterm)
+                });
+            }
+            trace.record("no_agreement", || "different countries: ZeroRated".into());
+            return Ok(TaxCalculationType::ZeroRated);
         }
 
-        let calc_type = self.get_calculation_type_from_agreement(&agreement.unwrap(), amount)?;
+        let profile = self.resolve_profile(db)?;
+        let calc_type = self.get_calculation_type_from_agreement(
+            &agreement.unwrap(),
+            amount,
+            db,
+            &profile,
+            trace,
+        )?;
         Ok(calc_type)
     }
 
@@ -284,19 +1061,100 @@ impl TaxScenario {
     /// # );
     /// let rates = scenario.get_rates(1000.0, &db).unwrap();
     /// ```
-    pub fn get_rates(
+    pub fn get_rates<P: RateProvider + ?Sized>(
         &self,
         amount: f64,
-        db: &TaxDatabase,
+        db: &P,
     ) -> Result<Vec<TaxRate>, ProcessingError> {
-        let calculation_type = self.determine_calculation_type(db, amount)?;
+        let mut rates = Vec::new();
+        self.get_rates_into(amount, db, &mut rates)?;
+        Ok(rates)
+    }
+
+    /// Gets the applicable tax rates into `rates`, replacing its contents.
+    ///
+    /// Unlike `get_rates`, this doesn't allocate once the buffer has grown to the
+    /// number of rates (at most a few), so a buffer can be reused across requests.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use world_tax::{Region, TaxDatabase, TaxScenario, TransactionType};
+    /// let db = TaxDatabase::fixture();
+    /// let scenario = TaxScenario::new(
+    ///     Region::parse("CA-QC").unwrap(),
+    ///     Region::parse("CA-QC").unwrap(),
+    ///     TransactionType::B2B,
+    /// );
+    ///
+    /// let mut rates = Vec::new();
+    /// for amount in [100.0, 200.0] {
+    ///     scenario.get_rates_into(amount, &db, &mut rates).unwrap();
+    ///     assert_eq!(rates.len(), 2); // GST and QST
+    /// }
+    /// ```
+    pub fn get_rates_into<P: RateProvider + ?Sized>(
+        &self,
+        amount: f64,
+        db: &P,
+        rates: &mut Vec<TaxRate>,
+    ) -> Result<(), ProcessingError> {
+        self.trace_rates_into(amount, db, rates, &mut Trace::disabled())
+    }
+
+    /// Gets the applicable tax rates, recording each decision in `trace`.
+    pub(crate) fn trace_rates<P: RateProvider + ?Sized>(
+        &self,
+        amount: f64,
+        db: &P,
+        trace: &mut Trace,
+    ) -> Result<Vec<TaxRate>, ProcessingError> {
+        let mut rates = Vec::new();
+        self.trace_rates_into(amount, db, &mut rates, trace)?;
+        Ok(rates)
+    }
+
+    fn trace_rates_into<P: RateProvider + ?Sized>(
+        &self,
+        amount: f64,
+        db: &P,
+        rates: &mut Vec<TaxRate>,
+        trace: &mut Trace,
+    ) -> Result<(), ProcessingError> {
+        let action = self.override_rule(db).map(|rule| &rule.then);
+        if let Some(OverrideAction::Rate(vat_rate)) = action {
+            if self.vat_rate.as_ref() != Some(vat_rate) {
+                let scenario = TaxScenario {
+                    vat_rate: Some(*vat_rate),
+                    ..self.clone()
+                };
+                return scenario.trace_rates_into(amount, db, rates, trace);
+            }
+        }
+
+        rates.clear();
+        let calculation_type = self.trace_calculation_type(db, amount, trace)?;
+
+        // Sales exempted by an override rule are taxed at zero whatever the tax system
+        if let Some(OverrideAction::Exempt(_)) = action {
+            let country = db.get_country(&self.destination_region.country)?;
+            if country.tax_type == TaxSystemType::Vat {
+                rates.push(TaxRate::new(
+                    Percentage::ZERO,
+                    TaxType::VAT(VatRate::Exempt),
+                    RateSource::Override,
+                ));
+            }
+            return Ok(());
+        }
 
         // Special handling for US B2B with resale certificate
         if self.source_region.country == "US"
             && self.transaction_type == TransactionType::B2B
-            && self.has_resale_certificate
+            && self.has_valid_certificate()
         {
-            return Ok(vec![]);
+            trace.record("exemption_certificate", || "US B2B resale: no tax".into());
+            return Ok(());
         }
 
         // Get the country's tax system type
@@ -305,79 +1163,195 @@ impl TaxScenario {
         match calculation_type {
             TaxCalculationType::ReverseCharge => {
                 match country.tax_type {
-                    TaxSystemType::Vat => Ok(vec![TaxRate {
-                        tax_type: TaxType::VAT(VatRate::ReverseCharge),
-                        compound: false,
-                        rate: 0.0,
-                    }]),
+                    TaxSystemType::Vat => {
+                        rates.push(TaxRate::new(
+                            Percentage::ZERO,
+                            TaxType::VAT(VatRate::ReverseCharge),
+                            RateSource::AgreementImplied,
+                        ));
+                        Ok(())
+                    }
                     _ => {
                         // For non-VAT systems, proceed with normal rate lookup
-                        self.get_regional_rates(calculation_type, db)
+                        self.get_regional_rates(calculation_type, db, rates, trace)
                     }
                 }
             }
             TaxCalculationType::ZeroRated => {
                 match country.tax_type {
-                    TaxSystemType::Vat => Ok(vec![TaxRate {
-                        tax_type: TaxType::VAT(VatRate::Zero),
-                        compound: false,
-                        rate: 0.0,
-                    }]),
-                    _ => Ok(vec![]), // For non-VAT systems, no tax
+                    TaxSystemType::Vat => {
+                        rates.push(TaxRate::new(
+                            Percentage::ZERO,
+                            TaxType::VAT(VatRate::Zero),
+                            RateSource::AgreementImplied,
+                        ));
+                        Ok(())
+                    }
+                    _ => Ok(()), // For non-VAT systems, no tax
                 }
             }
             TaxCalculationType::Exempt => {
                 // Only apply Exempt for VAT systems
                 match country.tax_type {
-                    TaxSystemType::Vat => Ok(vec![TaxRate {
-                        tax_type: TaxType::VAT(VatRate::Exempt),
-                        compound: false,
-                        rate: 0.0,
-                    }]),
-                    _ => self.get_regional_rates(calculation_type, db), // For non-VAT systems, proceed with normal lookup
+                    TaxSystemType::Vat => {
+                        rates.push(TaxRate::new(
+                            Percentage::ZERO,
+                            TaxType::VAT(VatRate::Exempt),
+                            RateSource::AgreementImplied,
+                        ));
+                        Ok(())
+                    }
+                    _ => self.get_regional_rates(calculation_type, db, rates, trace), // For non-VAT systems, proceed with normal lookup
                 }
             }
-            _ => self.get_regional_rates(calculation_type, db),
+            TaxCalculationType::NoticeAndReport => Ok(()),
+            _ => self.get_regional_rates(calculation_type, db, rates, trace),
         }
     }
 
     // Helper method to get regional rates
-    fn get_regional_rates(
+    pub(crate) fn get_regional_rates<P: RateProvider + ?Sized>(
         &self,
         calculation_type: TaxCalculationType,
-        db: &TaxDatabase,
-    ) -> Result<Vec<TaxRate>, ProcessingError> {
+        db: &P,
+        rates: &mut Vec<TaxRate>,
+        trace: &mut Trace,
+    ) -> Result<(), ProcessingError> {
         let region = match calculation_type {
             TaxCalculationType::Origin => &self.source_region,
-            TaxCalculationType::ZeroRated => return Ok(vec![]),
+            TaxCalculationType::ZeroRated => return Ok(()),
             _ => &self.destination_region,
         };
 
+        if self.resolve_profile(db)?.strictness == Strictness::Strict {
+            Self::ensure_region_known(region, db)?;
+        }
+
+        // Sales within Brazil combine state, interstate, federal and municipal taxes
+        if self.is_same_country() {
+            let country = db.get_country(&region.country)?;
+            if country.tax_type == TaxSystemType::Icms {
+                self.icms_rates(&country, db, rates, trace);
+                return Ok(());
+            }
+        }
+
+        let start = rates.len();
+        self.get_state_rates(&calculation_type, region, db, rates)?;
+        Self::cite_rates(region, &rates[start..], db, trace)?;
+
+        // Local rates, where tax is collected; states without a state rate (like
+        // Alaska) may still have local taxes
+        if region.country == "US" {
+            if let Some(state) = &region.region {
+                let collects = rates.len() > start
+                    || (rate_provider::state_flag(db, &region.country, state, |state| {
+                        state.local_taxes
+                    }) && matches!(
+                        calculation_type,
+                        TaxCalculationType::Destination | TaxCalculationType::Origin
+                    ));
+                if collects {
+                    rates.extend(self.local_rate(state, db));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Records the legal basis of the country and state rates applied in a region.
+    fn cite_rates<P: RateProvider + ?Sized>(
+        region: &Region,
+        rates: &[TaxRate],
+        db: &P,
+        trace: &mut Trace,
+    ) -> Result<(), DatabaseError> {
+        if rates.is_empty() {
+            return Ok(());
+        }
+        let country = db.get_country(&region.country)?;
+        if rates
+            .iter()
+            .any(|rate| rate.source != RateSource::StateSpecific)
+        {
+            trace.cite(country.legal_reference.as_deref(), || {
+                format!("{} rate", region.country)
+            });
+        }
+        if let Some(code) = &region.region {
+            let state = rate_provider::state(&country, &region.country, code);
+            if rates
+                .iter()
+                .any(|rate| rate.source == RateSource::StateSpecific)
+            {
+                trace.cite(
+                    state.and_then(|state| state.legal_reference.as_deref()),
+                    || format!("{} rate", code),
+                );
+            }
+        }
+        Ok(())
+    }
+
+    // Helper method to get country and state rates
+    fn get_state_rates<P: RateProvider + ?Sized>(
+        &self,
+        calculation_type: &TaxCalculationType,
+        region: &Region,
+        db: &P,
+        rates: &mut Vec<TaxRate>,
+    ) -> Result<(), ProcessingError> {
         // For US interstate commerce and Canadian provinces, handle thresholds
         if (region.country == "US" || region.country == "CA") && !self.is_same_state() {
             match calculation_type {
-                TaxCalculationType::Origin => Ok(vec![]),
-                TaxCalculationType::ZeroRated => Ok(vec![]),
+                TaxCalculationType::Origin => Ok(()),
+                TaxCalculationType::ZeroRated => Ok(()),
                 TaxCalculationType::Destination => db
-                    .get_rate(
+                    .get_rate_into(
                         &region.country,
                         region.region.as_deref(),
                         self.vat_rate.as_ref(),
+                        rates,
                     )
                     .map_err(ProcessingError::from),
-                _ => Ok(vec![]),
+                _ => Ok(()),
             }
         } else {
             // Normal rate lookup for other cases
-            db.get_rate(
+            db.get_rate_into(
                 &region.country,
                 region.region.as_deref(),
                 self.vat_rate.as_ref(),
+                rates,
             )
             .map_err(ProcessingError::from)
         }
     }
 
+    /// Fails if the region is not present in a country that has regional rates.
+    fn ensure_region_known<P: RateProvider + ?Sized>(
+        region: &Region,
+        db: &P,
+    ) -> Result<(), DatabaseError> {
+        if let Some(region_code) = &region.region {
+            let country = db.get_country(&region.country)?;
+            if country.states.is_some()
+                && rate_provider::state(&country, &region.country, region_code).is_none()
+            {
+                let states = country
+                    .states
+                    .iter()
+                    .flatten()
+                    .map(|(code, _)| code.as_str());
+                return Err(DatabaseError::RegionNotFound {
+                    code: region_code.clone(),
+                    suggestions: suggestions(region_code, states),
+                });
+            }
+        }
+        Ok(())
+    }
+
     /// Calculates the total tax amount for the scenario.
     ///
     /// # Arguments
@@ -387,7 +1361,10 @@ impl TaxScenario {
     ///
     /// # Returns
     ///
-    /// Returns the calculated tax amount, rounded to 2 decimal places.
+    /// Returns the calculated tax amount, rounded to 2 decimal places, or according
+    /// to the rounding of the scenario's calculation profile. The tax is calculated
+    /// with decimal arithmetic (see `calculate_tax_decimal`), and only the result is
+    /// converted to `f64`.
     ///
     /// # Examples
     ///
@@ -402,43 +1379,166 @@ impl TaxScenario {
     /// # );
     /// let tax_amount = scenario.calculate_tax(1000.0, &db).unwrap();
     /// ```
-    pub fn calculate_tax(&self, amount: f64, db: &TaxDatabase) -> Result<f64, ProcessingError> {
+    pub fn calculate_tax<P: RateProvider + ?Sized>(
+        &self,
+        amount: f64,
+        db: &P,
+    ) -> Result<f64, ProcessingError> {
+        let tax = self.calculate_tax_decimal(decimal(amount)?, db)?;
+        self.round(tax, db)
+    }
+
+    /// Splits a tax-inclusive (gross) amount into its net amount and the tax it contains.
+    ///
+    /// Compound rates (e.g. QST on top of GST) are unwound with the combined rate, and
+    /// thresholds are checked against the net amount. The tax is rounded like
+    /// `calculate_tax`, and the net amount is the gross amount minus the tax, so both
+    /// add up to the gross amount.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use world_tax::types::{Region, TransactionType, TaxScenario};
+    /// # use world_tax::provider::TaxDatabase;
+    /// let db = TaxDatabase::fixture();
+    /// let scenario = TaxScenario::new(
+    ///     Region::parse("CA-QC").unwrap(),
+    ///     Region::parse("CA-QC").unwrap(),
+    ///     TransactionType::B2C,
+    /// );
+    ///
+    /// // 5% GST, then 9.975% QST on the amount including GST
+    /// let split = scenario.calculate_tax_from_gross(115.47, &db).unwrap();
+    /// assert_eq!(split.tax, 15.47);
+    /// assert_eq!(split.net, 100.0);
+    /// ```
+    pub fn calculate_tax_from_gross<P: RateProvider + ?Sized>(
+        &self,
+        amount: f64,
+        db: &P,
+    ) -> Result<GrossSplit, ProcessingError> {
+        // The rates depend on the net amount (thresholds); resolve once more with the
+        // net amount of the first resolution.
         let rates = self.get_rates(amount, db)?;
+        let rates = self.get_rates(net_of_gross(amount, &rates)?, db)?;
+        let tax = self.sum_rates(net_of_gross(amount, &rates)?, &rates, db)?;
 
-        let mut total_tax = 0.0;
-        let base_amount = amount;
+        Ok(GrossSplit {
+            gross: amount,
+            net: add_amounts(amount, -tax)?,
+            tax,
+        })
+    }
 
-        for rate in rates {
-            let tax_amount = if rate.compound {
-                (base_amount + total_tax) * rate.rate
-            } else {
-                base_amount * rate.rate
-            };
-            total_tax += tax_amount;
-        }
+    /// Calculates the tax for the scenario, broken down by the applied rates.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use world_tax::types::{Region, TransactionType, TaxScenario, TaxType};
+    /// # use world_tax::provider::TaxDatabase;
+    /// let db = TaxDatabase::fixture();
+    /// let scenario = TaxScenario::new(
+    ///     Region::parse("CA-QC").unwrap(),
+    ///     Region::parse("CA-QC").unwrap(),
+    ///     TransactionType::B2C,
+    /// );
+    ///
+    /// let result = scenario.calculate_tax_detailed(100.0, &db).unwrap();
+    /// assert_eq!(result.rates[0].rate.tax_type, TaxType::GST);
+    /// assert_eq!(result.rates[0].tax, 5.0);
+    /// // QST compounds on the amount including GST
+    /// assert_eq!(result.rates[1].base, 105.0);
+    /// assert_eq!(result.rates[1].tax, 10.47);
+    /// assert_eq!(result.tax, 15.47);
+    /// assert_eq!(result.total, 115.47);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of `calculate_tax`.
+    pub fn calculate_tax_detailed<P: RateProvider + ?Sized>(
+        &self,
+        amount: f64,
+        db: &P,
+    ) -> Result<TaxCalculationResult, ProcessingError> {
+        let calculation_type = self.determine_calculation_type(db, amount)?;
+        let rates = self.get_rates(amount, db)?;
+        let taxes = rate_taxes(decimal(amount)?, &rates);
+        let applied = rates
+            .iter()
+            .zip(&taxes)
+            .enumerate()
+            .map(|(index, (rate, (_, tax)))| {
+                let base = decimal(amount)? + compounded(rate, &taxes[..index]);
+                Ok(AppliedRate {
+                    rate: *rate,
+                    base: base.to_f64().ok_or(ProcessingError::InvalidAmount)?,
+                    tax: self.round(*tax, db)?,
+                })
+            })
+            .collect::<Result<Vec<_>, ProcessingError>>()?;
+        let tax = self.sum_rates(amount, &rates, db)?;
 
-        Ok((total_tax * 100.0).round() / 100.0)
+        Ok(TaxCalculationResult {
+            calculation_type,
+            rates: applied,
+            amount,
+            tax,
+            total: add_amounts(amount, tax)?,
+        })
     }
 
-    pub fn calculate_tax_decimal(
+    /// Sums the tax of the given rates, applying compounding and rounding.
+    pub(crate) fn sum_rates<P: RateProvider + ?Sized>(
+        &self,
+        amount: f64,
+        rates: &[TaxRate],
+        db: &P,
+    ) -> Result<f64, ProcessingError> {
+        self.round(sum_rates_decimal(decimal(amount)?, rates), db)
+    }
+
+    /// Rounds a tax amount to 2 decimal places, or according to the rounding of the
+    /// scenario's calculation profile.
+    fn round<P: RateProvider + ?Sized>(
+        &self,
+        tax: Decimal,
+        db: &P,
+    ) -> Result<f64, ProcessingError> {
+        let rounding = match self.profile {
+            Some(_) => self.resolve_profile(db)?.rounding,
+            None => Rounding::default(),
+        };
+        rounding
+            .apply(tax)
+            .to_f64()
+            .ok_or(ProcessingError::InvalidAmount)
+    }
+
+    /// Calculates the total tax amount for the scenario using decimal arithmetic.
+    ///
+    /// # Arguments
+    ///
+    /// * `amount` - The transaction amount
+    /// * `db` - The tax database
+    ///
+    /// # Returns
+    ///
+    /// Returns the calculated tax amount. The amount is not rounded, unless the
+    /// scenario references a calculation profile.
+    pub fn calculate_tax_decimal<P: RateProvider + ?Sized>(
         &self,
         amount: Decimal,
-        db: &TaxDatabase,
+        db: &P,
     ) -> Result<Decimal, ProcessingError> {
         // Accuracy doesn't matter as much here, because we're looking for the treshold only
         let amount_f64 = amount.to_f64().ok_or(ProcessingError::InvalidAmount)?;
         let rates = self.get_rates(amount_f64, db)?;
+        let total_tax = sum_rates_decimal(amount, &rates);
 
-        let mut total_tax = Decimal::from(0);
-        let base_amount = amount;
-
-        for rate in rates {
-            let tax_amount = if rate.compound {
-                (base_amount + total_tax) * Decimal::from_f64(rate.rate).unwrap()
-            } else {
-                base_amount * Decimal::from_f64(rate.rate).unwrap()
-            };
-            total_tax += tax_amount;
+        if self.profile.is_some() {
+            return Ok(self.resolve_profile(db)?.rounding.apply(total_tax));
         }
 
         Ok(total_tax)
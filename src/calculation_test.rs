@@ -1,9 +1,26 @@
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
+
+    use rust_decimal_macros::dec;
+
+    use crate::calculation::{combined_rate, sum_item_rates_decimal, sum_rates_decimal};
+    use crate::calculator::{TaxCalculator, TaxQuote, TaxRequest};
+    use crate::certificate::{CertificateKind, ExemptionCertificate};
+    use crate::confidence::Confidence;
+    use crate::currency::CurrencyCode;
+    use crate::profile::{
+        CalculationProfile, NoNexusPolicy, Rounding, RoundingStrategy, SchemeFlags, Strictness,
+    };
+    use crate::types::{
+        AnnualTurnover, Incoterm, RateBasis, TaxCap, TaxRate, TaxRuleConfig, Threshold,
+    };
+    use crate::vat_id::VatId;
     use crate::{
-        Region, TaxDatabase, TaxScenario, TaxType, TradeAgreementOverride, TransactionType, VatRate,
+        DatabaseError, Percentage, ProcessingError, RateSource, Region, TaxCalculationType,
+        TaxDatabase, TaxScenario, TaxType, ThresholdPolicy, TradeAgreementOverride,
+        TransactionType, VatRate, Warning,
     };
-    use rust_decimal_macros::dec;
 
     fn setup() -> TaxDatabase {
         let _ = env_logger::builder()
@@ -31,11 +48,72 @@ mod tests {
             .get_rates(100.0, &db)
             .expect("Rates should be found");
         assert_eq!(rates.len(), 1);
-        assert_eq!(rates[0].rate, 0.19);
+        assert_eq!(rates[0].rate, Percentage::from_fraction(dec!(0.19)));
         assert_eq!(rates[0].tax_type, TaxType::VAT(VatRate::Standard));
         assert!(!rates[0].compound);
     }
 
+    #[test]
+    fn test_decimal_rounding() {
+        let db = TaxDatabase::fixture();
+        let germany = Region::parse("DE").unwrap();
+        let scenario = TaxScenario::new(germany.clone(), germany, TransactionType::B2C);
+
+        // 19% of 42.50 is 8.075, but 8.07499... in floating point
+        assert_eq!(scenario.calculate_tax(42.5, &db).unwrap(), 8.08);
+        assert_eq!(
+            scenario.calculate_tax_decimal(dec!(42.50), &db).unwrap(),
+            dec!(8.075)
+        );
+        let quote = db.quote(TaxRequest::new(scenario.clone(), 42.5)).unwrap();
+        assert_eq!(quote.tax, 8.08);
+        assert_eq!(quote.total, 50.58);
+
+        // Quote totals of cent amounts are cent amounts
+        let quote = db.quote(TaxRequest::new(scenario.clone(), 0.05)).unwrap();
+        assert_eq!((quote.tax, quote.total), (0.01, 0.06));
+        for cents in 1..20_000 {
+            let quote = db
+                .quote(TaxRequest::new(scenario.clone(), cents as f64 / 100.0))
+                .unwrap();
+            assert_eq!(
+                quote.total,
+                (quote.total * 100.0).round() / 100.0,
+                "{cents}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_threshold_amounts() {
+        let db = setup();
+        let eu = db.get_rule("EU").unwrap();
+        let threshold = eu
+            .tax_rules
+            .internal_b2c
+            .as_ref()
+            .unwrap()
+            .threshold
+            .unwrap();
+        assert_eq!(threshold.amount, dec!(10000));
+        // Whole amounts stay integers in the dataset format
+        assert_eq!(serde_json::to_value(threshold).unwrap()["amount"], 10000);
+
+        // Numbers with cents are read to float precision, without their written scale
+        let threshold: Threshold =
+            serde_json::from_str(r#"{ "amount": 10000.10, "basis": "annual_turnover" }"#).unwrap();
+        assert_eq!(threshold.amount, dec!(10000.10));
+        assert_eq!(threshold.amount.to_string(), "10000.1");
+        assert_eq!(serde_json::to_value(threshold).unwrap()["amount"], 10000.1);
+        // Strings exactly as written
+        let threshold: Threshold = serde_json::from_str(r#"{ "amount": "8500.07" }"#).unwrap();
+        assert_eq!(threshold.amount, dec!(8500.07));
+        let threshold: Threshold =
+            serde_json::from_str(r#"{ "amount": "1000000000000000.01" }"#).unwrap();
+        assert_eq!(threshold.amount, dec!(1000000000000000.01));
+        assert!(serde_json::from_str::<Threshold>(r#"{ "amount": "ten" }"#).is_err());
+    }
+
     #[test]
     fn test_canadian_gst_bc_pst_below_threshold() {
         let db = setup();
@@ -45,7 +123,9 @@ mod tests {
             Region::new("CA".to_string(), Some("CA-BC".to_string()))
                 .expect("Valid Canadian BC region"),
             TransactionType::B2C,
-        );
+        )
+        // No earlier sales this year
+        .with_turnover(AnnualTurnover::default());
 
         let tax = scenario
             .calculate_tax(100.0, &db)
@@ -63,7 +143,7 @@ mod tests {
                 .expect("Valid Canadian BC region"),
             TransactionType::B2C,
         );
-        scenario.ignore_threshold = true;
+        scenario.threshold_policy = ThresholdPolicy::AssumeAbove;
 
         let tax = scenario
             .calculate_tax(100.0, &db)
@@ -95,7 +175,8 @@ mod tests {
             Region::new("DE".to_string(), None).expect("Valid German region"),
             Region::new("FR".to_string(), None).expect("Valid French region"),
             TransactionType::B2B,
-        );
+        )
+        .with_buyer_vat_id(VatId::parse("FR40303265045").expect("Valid VAT ID"));
 
         let tax = scenario
             .calculate_tax(100.0, &db)
@@ -110,7 +191,8 @@ mod tests {
             Region::new("DE".to_string(), None).expect("Valid German region"),
             Region::new("FR".to_string(), None).expect("Valid French region"),
             TransactionType::B2B,
-        );
+        )
+        .with_buyer_vat_id(VatId::parse("FR40303265045").expect("Valid VAT ID"));
         scenario.is_digital_product_or_service = true;
 
         let tax = scenario
@@ -128,10 +210,98 @@ mod tests {
             TransactionType::B2C,
         );
 
+        // Without the turnover, a sale of goods isn't taken for the year's sales either
         let tax = scenario
             .calculate_tax(100.0, &db)
             .expect("Tax calculation should succeed");
-        assert_eq!(tax, 19.0); // EU reverse charge mechanism
+        assert_eq!(tax, 20.0); // French VAT
+        assert_eq!(
+            scenario.determine_calculation_type(&db, 100.0).unwrap(),
+            TaxCalculationType::Destination
+        );
+
+        // Below the €10,000 threshold: German VAT
+        let below = scenario.with_turnover(AnnualTurnover {
+            current_year: 2_000.0,
+            ..Default::default()
+        });
+        assert_eq!(below.calculate_tax(100.0, &db).unwrap(), 19.0);
+    }
+
+    #[test]
+    fn test_eu_cross_border_b2c_digital_threshold() {
+        let db = setup();
+        let mut scenario = TaxScenario::new(
+            Region::parse("DE").unwrap(),
+            Region::parse("FR").unwrap(),
+            TransactionType::B2C,
+        );
+        scenario.is_digital_product_or_service = true;
+
+        // The €10,000 threshold counts goods and digital products sold into the EU
+        let below = scenario.clone().with_turnover(AnnualTurnover {
+            current_year: 2_000.0,
+            ..Default::default()
+        });
+        assert_eq!(below.calculate_tax(100.0, &db).unwrap(), 19.0);
+        let above = scenario.clone().with_turnover(AnnualTurnover {
+            current_year: 9_950.0,
+            ..Default::default()
+        });
+        assert_eq!(above.calculate_tax(100.0, &db).unwrap(), 20.0);
+
+        // Without the turnover, a sale isn't taken for the year's sales
+        assert_eq!(
+            scenario.determine_calculation_type(&db, 100.0).unwrap(),
+            TaxCalculationType::Destination
+        );
+        assert_eq!(scenario.calculate_tax(100.0, &db).unwrap(), 20.0);
+        let explanation = scenario.explain(100.0, &db).unwrap();
+        assert!(explanation
+            .steps
+            .iter()
+            .any(|step| step.check == "turnover"));
+        scenario.threshold_policy = ThresholdPolicy::AssumeBelow;
+        assert_eq!(scenario.calculate_tax(100.0, &db).unwrap(), 19.0);
+    }
+
+    #[test]
+    fn test_digital_product_threshold_amounts() {
+        let rule: TaxRuleConfig = serde_json::from_str(
+            r#"{
+                "type": "threshold_based",
+                "below_threshold_digital_products": "origin",
+                "above_threshold_digital_products": "destination",
+                "threshold_digital_products": { "amount": 150.5, "currency": "EUR" }
+            }"#,
+        )
+        .unwrap();
+        let eur = CurrencyCode::parse("EUR").ok();
+        let policy = ThresholdPolicy::Respect;
+
+        // Cents count: 150.70 isn't truncated to 150
+        assert_eq!(
+            rule.by_digital_product_threshold(dec!(150.70), eur, policy)
+                .unwrap(),
+            &TaxCalculationType::Destination
+        );
+        assert_eq!(
+            rule.by_digital_product_threshold(dec!(150.49), None, policy)
+                .unwrap(),
+            &TaxCalculationType::Origin
+        );
+
+        // Amounts in another currency have to be converted first
+        let usd = CurrencyCode::parse("USD").ok();
+        assert!(matches!(
+            rule.by_digital_product_threshold(dec!(100), usd, policy),
+            Err(DatabaseError::CurrencyMismatch { .. })
+        ));
+        assert_eq!(
+            rule.by_digital_product_threshold(dec!(100), usd, ThresholdPolicy::AssumeAbove)
+                .unwrap(),
+            &TaxCalculationType::Destination
+        );
     }
 
     #[test]
@@ -195,6 +365,35 @@ mod tests {
         assert_eq!(tax, 0.0); // Export from EU to non-EU country is zero-rated for B2C too
     }
 
+    #[test]
+    fn test_germany_thailand_b2c_incoterm() {
+        let db = setup();
+        let dap = TaxScenario::new(
+            Region::new("DE".to_string(), None).expect("Valid German region"),
+            Region::new("TH".to_string(), None).expect("Valid Thai region"),
+            TransactionType::B2C,
+        )
+        .with_incoterm(Incoterm::Dap);
+        let ddp = dap.clone().with_incoterm(Incoterm::Ddp);
+
+        let tax = dap
+            .calculate_tax(100.0, &db)
+            .expect("Tax calculation should succeed");
+        assert_eq!(tax, 0.0); // Buyer pays import VAT
+        let tax = ddp
+            .calculate_tax(100.0, &db)
+            .expect("Tax calculation should succeed");
+        assert_eq!(tax, 10.0); // Thai VAT, paid by the seller
+
+        let difference = dap
+            .explain_difference(&ddp, 100.0, &db)
+            .expect("Explanation should succeed");
+        assert_eq!(difference.changes[0].field, "incoterm");
+        let (left, right) = difference.first_divergence.expect("Steps should diverge");
+        assert_eq!(left.expect("Left step").check, "incoterm");
+        assert!(right.expect("Right step").outcome.contains("DDP"));
+    }
+
     #[test]
     fn test_us_interstate_b2c_below_threshold() {
         let db = setup();
@@ -202,7 +401,9 @@ mod tests {
             Region::new("US".to_string(), Some("US-CA".to_string())).expect("Valid US-CA region"),
             Region::new("US".to_string(), Some("US-WA".to_string())).expect("Valid US-WA region"),
             TransactionType::B2C,
-        );
+        )
+        // No earlier sales this year
+        .with_turnover(AnnualTurnover::default());
 
         let tax = scenario
             .calculate_tax(100.0, &db)
@@ -218,7 +419,7 @@ mod tests {
             Region::new("US".to_string(), Some("US-WA".to_string())).expect("Valid US-WA region"),
             TransactionType::B2C,
         );
-        scenario.ignore_threshold = true;
+        scenario.threshold_policy = ThresholdPolicy::AssumeAbove;
 
         let tax = scenario
             .calculate_tax(100.0, &db)
@@ -226,6 +427,43 @@ mod tests {
         assert_eq!(tax, 6.5); // Washington state sales tax rate for remote sellers
     }
 
+    #[test]
+    fn test_eu_b2c_assume_below_threshold() {
+        let db = TaxDatabase::fixture();
+        let mut scenario = TaxScenario::new(
+            Region::parse("DE").expect("Valid German region"),
+            Region::parse("FR").expect("Valid French region"),
+            TransactionType::B2C,
+        );
+        assert_eq!(scenario.calculate_tax(20000.0, &db).unwrap(), 4000.0);
+
+        // Seller opted out of destination registration: taxed at origin
+        scenario.threshold_policy = ThresholdPolicy::AssumeBelow;
+        assert_eq!(scenario.calculate_tax(20000.0, &db).unwrap(), 3800.0);
+
+        let explanation = scenario.explain(20000.0, &db).unwrap();
+        assert!(explanation
+            .steps
+            .iter()
+            .any(|step| step.check == "internal_b2c" && step.outcome.contains("AssumeBelow")));
+    }
+
+    #[test]
+    fn test_us_interstate_b2c_assume_below_threshold() {
+        let db = setup();
+        let mut scenario = TaxScenario::new(
+            Region::new("US".to_string(), Some("US-CA".to_string())).expect("Valid US-CA region"),
+            Region::new("US".to_string(), Some("US-WA".to_string())).expect("Valid US-WA region"),
+            TransactionType::B2C,
+        );
+        scenario.threshold_policy = ThresholdPolicy::AssumeBelow;
+
+        let tax = scenario
+            .calculate_tax(1_000_000.0, &db)
+            .expect("Tax calculation should succeed");
+        assert_eq!(tax, 0.0); // No nexus in Washington
+    }
+
     #[test]
     fn test_us_interstate_b2c_above_threshold() {
         let db = setup();
@@ -249,7 +487,11 @@ mod tests {
             Region::new("US".to_string(), Some("US-WA".to_string())).expect("Valid US-WA region"),
             TransactionType::B2B,
         );
-        scenario.has_resale_certificate = true;
+        scenario.exemption_certificate = Some(ExemptionCertificate::new(
+            "US-WA",
+            "RS-1234",
+            CertificateKind::Resale,
+        ));
 
         let tax = scenario
             .calculate_tax(100.0, &db)
@@ -265,7 +507,11 @@ mod tests {
             Region::new("US".to_string(), Some("US-TX".to_string())).expect("Valid US-TX region"),
             TransactionType::B2B,
         );
-        scenario.has_resale_certificate = true;
+        scenario.exemption_certificate = Some(ExemptionCertificate::new(
+            "US-TX",
+            "RS-1234",
+            CertificateKind::Resale,
+        ));
 
         let tax = scenario
             .calculate_tax(100.0, &db)
@@ -293,14 +539,16 @@ mod tests {
         let db = setup();
         let scenario = TaxScenario::new(
             Region::new("AE".to_string(), None).expect("Valid UAE region"),
-            Region::new("QA".to_string(), None).expect("Valid Qatar region"),
+            Region::new("SA".to_string(), None).expect("Valid Saudi region"),
             TransactionType::B2C,
-        );
+        )
+        // No earlier sales this year
+        .with_turnover(AnnualTurnover::default());
 
         let tax = scenario
             .calculate_tax(100.0, &db)
             .expect("Tax calculation should succeed");
-        assert_eq!(tax, 5.0); // GCC countries have no VAT
+        assert_eq!(tax, 5.0); // Below the registration threshold: UAE VAT
     }
 
     #[test]
@@ -308,19 +556,27 @@ mod tests {
         let db = setup();
         let scenario = TaxScenario {
             source_region: Region::new("AE".to_string(), None).expect("Valid UAE region"),
-            destination_region: Region::new("QA".to_string(), None).expect("Valid Qatar region"),
+            destination_region: Region::new("SA".to_string(), None).expect("Valid Saudi region"),
             transaction_type: TransactionType::B2C,
             trade_agreement_override: None,
             is_digital_product_or_service: false,
-            has_resale_certificate: false,
-            ignore_threshold: false,
+            exemption_certificate: None,
+            buyer_vat_id: None,
+            threshold_policy: ThresholdPolicy::Respect,
             vat_rate: None,
+            profile: None,
+            incoterm: None,
+            currency: None,
+            turnover: Some(AnnualTurnover::default()),
+            date: None,
+            supply_kind: None,
+            attributes: HashMap::new(),
         };
 
         let tax = scenario
             .calculate_tax(100.0, &db)
             .expect("Tax calculation should succeed");
-        assert_eq!(tax, 5.0); // GCC countries have no VAT
+        assert_eq!(tax, 5.0); // Below the registration threshold: UAE VAT
     }
 
     #[test]
@@ -328,19 +584,312 @@ mod tests {
         let db = setup();
         let scenario = TaxScenario {
             source_region: Region::new("AE".to_string(), None).expect("Valid UAE region"),
-            destination_region: Region::new("QA".to_string(), None).expect("Valid Qatar region"),
+            destination_region: Region::new("SA".to_string(), None).expect("Valid Saudi region"),
             transaction_type: TransactionType::B2C,
             trade_agreement_override: Some(TradeAgreementOverride::NoAgreement),
             is_digital_product_or_service: false,
-            has_resale_certificate: false,
-            ignore_threshold: false,
+            exemption_certificate: None,
+            buyer_vat_id: None,
+            threshold_policy: ThresholdPolicy::Respect,
             vat_rate: None,
+            profile: None,
+            incoterm: None,
+            currency: None,
+            turnover: None,
+            date: None,
+            supply_kind: None,
+            attributes: HashMap::new(),
         };
 
         let tax = scenario
             .calculate_tax(100.0, &db)
             .expect("Tax calculation should succeed");
-        assert_eq!(tax, 0.0); // GCC countries have no VAT
+        assert_eq!(tax, 0.0); // Export
+    }
+
+    #[test]
+    fn test_gcc_cross_border_b2c_above_threshold() {
+        let db = setup();
+        let scenario = TaxScenario::new(
+            Region::new("AE".to_string(), None).expect("Valid UAE region"),
+            Region::new("SA".to_string(), None).expect("Valid Saudi region"),
+            TransactionType::B2C,
+        );
+
+        let tax = scenario
+            .calculate_tax(200000.0, &db)
+            .expect("Tax calculation should succeed");
+        assert_eq!(tax, 30000.0); // Saudi VAT
+
+        let digital = TaxScenario {
+            is_digital_product_or_service: true,
+            ..scenario
+        };
+        let tax = digital
+            .calculate_tax(100.0, &db)
+            .expect("Tax calculation should succeed");
+        assert_eq!(tax, 15.0);
+    }
+
+    #[test]
+    fn test_gcc_non_implementing_members() {
+        let db = setup();
+        for (source, destination, transaction_type) in [
+            ("AE", "QA", TransactionType::B2B),
+            ("AE", "QA", TransactionType::B2C),
+            ("KW", "SA", TransactionType::B2C),
+        ] {
+            let scenario = TaxScenario::new(
+                Region::parse(source).unwrap(),
+                Region::parse(destination).unwrap(),
+                transaction_type,
+            );
+            assert_eq!(
+                scenario.determine_calculation_type(&db, 100.0).unwrap(),
+                TaxCalculationType::ZeroRated,
+                "{} to {}",
+                source,
+                destination
+            );
+            assert_eq!(scenario.calculate_tax(100.0, &db).unwrap(), 0.0);
+        }
+
+        // Qatar and Kuwait levy no VAT
+        let domestic = TaxScenario::new(
+            Region::parse("QA").unwrap(),
+            Region::parse("QA").unwrap(),
+            TransactionType::B2C,
+        );
+        assert_eq!(domestic.calculate_tax(100.0, &db).unwrap(), 0.0);
+
+        // The seller pays Saudi import VAT on goods shipped duty paid
+        let imported = TaxScenario::new(
+            Region::parse("KW").unwrap(),
+            Region::parse("SA").unwrap(),
+            TransactionType::B2C,
+        )
+        .with_incoterm(Incoterm::Ddp);
+        assert!(imported.is_delivered_duty_paid(&db));
+        assert_eq!(imported.calculate_tax(100.0, &db).unwrap(), 15.0);
+    }
+
+    #[test]
+    fn test_eu_non_members() {
+        let db = setup();
+        let scenario = TaxScenario::new(
+            Region::parse("DE").unwrap(),
+            Region::parse("NO").unwrap(),
+            TransactionType::B2C,
+        );
+        // Above the limit of Norway's low-value goods rule
+        let explanation = scenario
+            .explain(5000.0, &db)
+            .expect("Explanation should succeed");
+        assert_eq!(explanation.calculation_type, TaxCalculationType::ZeroRated);
+        assert_eq!(explanation.tax, 0.0);
+        assert_eq!(
+            explanation.warnings,
+            vec![Warning::NotAgreementMember {
+                country: "NO".to_string(),
+                agreement: "EU".to_string(),
+            }]
+        );
+        assert!(explanation
+            .steps
+            .iter()
+            .any(|step| step.outcome == "none: NO isn't a member of EU"));
+        assert_eq!(explanation.confidence, Confidence::Exact);
+
+        // The seller pays Norwegian import VAT on goods shipped duty paid
+        let imported = scenario.clone().with_incoterm(Incoterm::Ddp);
+        assert_eq!(imported.calculate_tax(5000.0, &db).unwrap(), 1250.0);
+
+        // Swiss sellers zero rate exports into the EU; candidates too
+        for source in ["CH", "RS", "TR"] {
+            let export = TaxScenario::new(
+                Region::parse(source).unwrap(),
+                Region::parse("FR").unwrap(),
+                TransactionType::B2B,
+            );
+            let quote = db.quote(TaxRequest::new(export, 100.0)).unwrap();
+            assert_eq!(quote.calculation_type, TaxCalculationType::ZeroRated);
+            assert!(matches!(
+                quote.warnings.as_slice(),
+                [Warning::NotAgreementMember { country, .. }] if country == source
+            ));
+        }
+
+        // Neither is a member: no warning
+        let efta = TaxScenario::new(
+            Region::parse("NO").unwrap(),
+            Region::parse("CH").unwrap(),
+            TransactionType::B2C,
+        );
+        assert!(db
+            .quote(TaxRequest::new(efta, 100.0))
+            .unwrap()
+            .warnings
+            .is_empty());
+    }
+
+    #[test]
+    fn test_import_rules() {
+        let db = setup();
+        let sale = |source: &str, destination: &str, digital: bool| {
+            let mut scenario = TaxScenario::new(
+                Region::parse(source).unwrap(),
+                Region::parse(destination).unwrap(),
+                TransactionType::B2C,
+            );
+            scenario.is_digital_product_or_service = digital;
+            scenario
+        };
+
+        // Non-EU sellers of digital services charge the member state's VAT
+        let digital = sale("US", "DE", true);
+        assert_eq!(
+            digital.determine_calculation_type(&db, 100.0).unwrap(),
+            TaxCalculationType::Destination
+        );
+        assert_eq!(digital.calculate_tax(100.0, &db).unwrap(), 19.0);
+        let explanation = digital.explain(100.0, &db).unwrap();
+        assert!(explanation
+            .steps
+            .iter()
+            .any(|step| step.check == "import_rules"
+                && step.outcome == "DE B2C digital from abroad: Destination"));
+
+        // Australian GST on goods up to AUD 1000
+        assert_eq!(
+            sale("DE", "AU", false).calculate_tax(500.0, &db).unwrap(),
+            50.0
+        );
+        assert_eq!(
+            sale("DE", "AU", false).calculate_tax(1500.0, &db).unwrap(),
+            0.0
+        );
+
+        // Without a rule, exports are zero rated
+        assert_eq!(
+            sale("DE", "US", true).calculate_tax(100.0, &db).unwrap(),
+            0.0
+        );
+        assert_eq!(
+            sale("US", "DE", false).calculate_tax(100.0, &db).unwrap(),
+            0.0
+        );
+    }
+
+    #[test]
+    fn test_low_value_goods_threshold() {
+        let db = setup();
+        let sale = |destination: &str| {
+            TaxScenario::new(
+                Region::parse("DE").unwrap(),
+                Region::parse(destination).unwrap(),
+                TransactionType::B2C,
+            )
+        };
+        let turnover = |current_year| AnnualTurnover {
+            current_year,
+            previous_year: 0.0,
+            ..Default::default()
+        };
+
+        // Below the VOEC threshold, goods are taxed on import
+        let first = sale("NO").with_turnover(turnover(0.0));
+        assert_eq!(first.calculate_tax(1000.0, &db).unwrap(), 0.0);
+        assert!(first
+            .explain(1000.0, &db)
+            .unwrap()
+            .steps
+            .iter()
+            .any(|step| step.check == "import_threshold"));
+
+        // Registered sellers charge Norwegian VAT on goods up to NOK 3000
+        let registered = sale("NO").with_turnover(turnover(60_000.0));
+        assert_eq!(registered.calculate_tax(1000.0, &db).unwrap(), 250.0);
+        assert_eq!(registered.calculate_tax(5000.0, &db).unwrap(), 0.0);
+
+        // Swiss mail-order rule: from CHF 100,000, on all goods
+        let registered = sale("CH").with_turnover(turnover(99_950.0));
+        assert_eq!(registered.calculate_tax(100.0, &db).unwrap(), 8.1);
+        let below = sale("CH").with_turnover(turnover(50_000.0));
+        assert_eq!(below.calculate_tax(100.0, &db).unwrap(), 0.0);
+        let mut assumed = below.clone();
+        assumed.threshold_policy = ThresholdPolicy::AssumeAbove;
+        assert_eq!(assumed.calculate_tax(100.0, &db).unwrap(), 8.1);
+
+        // Digital services are taxed regardless of turnover
+        let mut digital = sale("CH");
+        digital.is_digital_product_or_service = true;
+        assert_eq!(digital.calculate_tax(100.0, &db).unwrap(), 8.1);
+    }
+
+    #[test]
+    fn test_import_rules_b2b_services() {
+        let mut db = setup();
+        let sale = |destination: &str, digital: bool| {
+            let mut scenario = TaxScenario::new(
+                Region::parse("US").unwrap(),
+                Region::parse(destination).unwrap(),
+                TransactionType::B2B,
+            );
+            scenario.is_digital_product_or_service = digital;
+            scenario
+        };
+
+        // EU buyers reverse charge imported services
+        assert_eq!(
+            sale("DE", true)
+                .determine_calculation_type(&db, 100.0)
+                .unwrap(),
+            TaxCalculationType::ReverseCharge
+        );
+        assert_eq!(sale("DE", true).calculate_tax(100.0, &db).unwrap(), 0.0);
+        assert_eq!(
+            sale("DE", false)
+                .determine_calculation_type(&db, 100.0)
+                .unwrap(),
+            TaxCalculationType::ZeroRated
+        );
+        assert_eq!(
+            sale("AU", true)
+                .determine_calculation_type(&db, 100.0)
+                .unwrap(),
+            TaxCalculationType::ZeroRated
+        );
+
+        // Without a reverse charge, the seller charges the destination's tax
+        db.countries
+            .get_mut("AU")
+            .unwrap()
+            .import_rules
+            .as_mut()
+            .unwrap()
+            .b2b_services = Some(TaxCalculationType::Destination);
+        assert_eq!(sale("AU", true).calculate_tax(100.0, &db).unwrap(), 10.0);
+        assert_eq!(sale("AU", false).calculate_tax(100.0, &db).unwrap(), 0.0);
+    }
+
+    #[test]
+    fn test_efta_and_candidate_rates() {
+        let db = setup();
+        let domestic = |country: &str, vat_rate: Option<VatRate>| {
+            let mut scenario = TaxScenario::new(
+                Region::parse(country).unwrap(),
+                Region::parse(country).unwrap(),
+                TransactionType::B2C,
+            );
+            scenario.vat_rate = vat_rate;
+            scenario.calculate_tax(100.0, &db).unwrap()
+        };
+        assert_eq!(domestic("NO", None), 25.0);
+        assert_eq!(domestic("NO", Some(VatRate::Reduced)), 15.0);
+        assert_eq!(domestic("CH", Some(VatRate::Reduced)), 2.6);
+        assert_eq!(domestic("LI", None), 8.1);
+        assert_eq!(domestic("IS", Some(VatRate::Reduced)), 11.0);
+        assert_eq!(domestic("ME", None), 21.0);
     }
 
     #[test]
@@ -363,13 +912,13 @@ mod tests {
             .iter()
             .find(|r| matches!(r.tax_type, TaxType::GST))
             .expect("Should have GST");
-        assert_eq!(gst_rate.rate, 0.05); // 5% GST
+        assert_eq!(gst_rate.rate, Percentage::from_fraction(dec!(0.05))); // 5% GST
 
         let qst_rate = rates
             .iter()
             .find(|r| matches!(r.tax_type, TaxType::QST))
             .expect("Should have QST");
-        assert_eq!(qst_rate.rate, 0.09975); // 9.975% QST
+        assert_eq!(qst_rate.rate, Percentage::from_fraction(dec!(0.09975))); // 9.975% QST
         assert!(qst_rate.compound); // QST should compound on GST
     }
 
@@ -389,7 +938,7 @@ mod tests {
             .expect("Rates should be found");
         assert_eq!(rates.len(), 1); // Should only have HST
         assert_eq!(rates[0].tax_type, TaxType::HST);
-        assert_eq!(rates[0].rate, 0.09); // Nova Scotia HST provincial rate 9% (14% combined since Apr 2025)
+        assert_eq!(rates[0].rate, Percentage::from_fraction(dec!(0.09))); // Nova Scotia HST provincial rate 9% (14% combined since Apr 2025)
         assert!(!rates[0].compound); // HST should not compound
     }
 
@@ -442,7 +991,7 @@ mod tests {
             .get_rates(100.0, &db)
             .expect("Rates should be found");
         assert_eq!(rates.len(), 1);
-        assert_eq!(rates[0].rate, 0.0);
+        assert_eq!(rates[0].rate, Percentage::from_fraction(dec!(0.0)));
         assert!(matches!(
             rates[0].tax_type,
             TaxType::VAT(VatRate::ReverseCharge)
@@ -472,11 +1021,11 @@ mod tests {
             Region::new("US".to_string(), Some("US-CA".to_string())).expect("Valid US-CA region"),
             TransactionType::B2C,
         );
-        scenario.ignore_threshold = true;
+        scenario.threshold_policy = ThresholdPolicy::AssumeAbove;
 
         let rates = scenario.get_rates(1.0, &db).expect("Rates should be found");
         assert_eq!(rates.len(), 1);
-        assert_eq!(rates[0].rate, 0.0825); // California sales tax rate
+        assert_eq!(rates[0].rate, Percentage::from_fraction(dec!(0.0825))); // California sales tax rate
     }
 
     #[test]
@@ -508,7 +1057,7 @@ mod tests {
             .get_rates(100.0, &db)
             .expect("Rates should be found");
         assert_eq!(rates.len(), 1);
-        assert_eq!(rates[0].rate, 0.0);
+        assert_eq!(rates[0].rate, Percentage::from_fraction(dec!(0.0)));
         assert!(matches!(rates[0].tax_type, TaxType::VAT(VatRate::Exempt)));
     }
 
@@ -550,8 +1099,770 @@ mod tests {
         assert_eq!(float_tax, 1237900.0); // Should show difference from float calculation
     }
 
+    #[test]
+    fn test_profile_rounding() {
+        let mut db = setup();
+        db.register_profile(
+            "no-cents",
+            CalculationProfile {
+                rounding: Rounding {
+                    decimal_places: 0,
+                    strategy: RoundingStrategy::Down,
+                },
+                ..Default::default()
+            },
+        );
+        let scenario = TaxScenario::new(
+            Region::new("DE".to_string(), None).expect("Valid German region"),
+            Region::new("DE".to_string(), None).expect("Valid German region"),
+            TransactionType::B2C,
+        )
+        .with_profile("no-cents");
+
+        let tax = scenario
+            .calculate_tax(10.5, &db)
+            .expect("Tax calculation should succeed");
+        assert_eq!(tax, 1.0); // 1.995 rounded down
+
+        let tax = scenario
+            .calculate_tax_decimal(dec!(10.50), &db)
+            .expect("Tax calculation should succeed");
+        assert_eq!(tax, dec!(1));
+    }
+
+    #[test]
+    fn test_profile_strict_region() {
+        let mut db = setup();
+        db.register_profile(
+            "strict",
+            CalculationProfile {
+                strictness: Strictness::Strict,
+                ..Default::default()
+            },
+        );
+        let mut scenario = TaxScenario::new(
+            Region::new("CA".to_string(), Some("CA-YT".to_string()))
+                .expect("Valid Canadian YT region"),
+            Region::new("CA".to_string(), Some("CA-YT".to_string()))
+                .expect("Valid Canadian YT region"),
+            TransactionType::B2C,
+        );
+        scenario.threshold_policy = ThresholdPolicy::AssumeAbove;
+
+        // Lenient by default: Yukon isn't in the database, so only GST applies
+        let rates = scenario
+            .get_rates(100.0, &db)
+            .expect("Rates should be found");
+        assert_eq!(rates.len(), 1);
+        assert_eq!(rates[0].tax_type, TaxType::GST);
+        assert_eq!(rates[0].source, RateSource::Fallback);
+
+        let result = scenario.with_profile("strict").get_rates(100.0, &db);
+        assert!(matches!(
+            result,
+            Err(ProcessingError::DatabaseError(
+                DatabaseError::RegionNotFound { .. }
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_profile_oss_destination_below_threshold() {
+        let mut db = setup();
+        db.register_profile(
+            "oss",
+            CalculationProfile {
+                schemes: SchemeFlags {
+                    oss: true,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
+        let scenario = TaxScenario::new(
+            Region::new("DE".to_string(), None).expect("Valid German region"),
+            Region::new("FR".to_string(), None).expect("Valid French region"),
+            TransactionType::B2C,
+        )
+        .with_profile("oss");
+
+        let tax = scenario
+            .calculate_tax(100.0, &db)
+            .expect("Tax calculation should succeed");
+        assert_eq!(tax, 20.0); // French VAT, despite being below the EU threshold
+    }
+
+    #[test]
+    fn test_us_notice_and_report_below_threshold() {
+        let db = setup();
+        let scenario = TaxScenario::new(
+            Region::new("US".to_string(), Some("US-CA".to_string())).expect("Valid US-CA region"),
+            Region::new("US".to_string(), Some("US-CO".to_string())).expect("Valid US-CO region"),
+            TransactionType::B2C,
+        )
+        // No earlier sales this year
+        .with_turnover(AnnualTurnover::default());
+
+        let quote = db
+            .quote(TaxRequest::new(scenario.clone(), 100.0))
+            .expect("Quote should succeed");
+        assert_eq!(quote.calculation_type, TaxCalculationType::NoticeAndReport);
+        assert!(quote.rates.is_empty());
+        assert_eq!(quote.tax, 0.0);
+
+        let quote = db
+            .quote(TaxRequest::new(scenario, 150000.0))
+            .expect("Quote should succeed");
+        assert_eq!(quote.calculation_type, TaxCalculationType::Destination);
+    }
+
+    #[test]
+    fn test_profile_use_tax_notice() {
+        let mut db = setup();
+        db.register_profile(
+            "notice",
+            CalculationProfile {
+                no_nexus: NoNexusPolicy::UseTaxNotice,
+                ..Default::default()
+            },
+        );
+        let scenario = TaxScenario::new(
+            Region::new("US".to_string(), Some("US-CA".to_string())).expect("Valid US-CA region"),
+            Region::new("US".to_string(), Some("US-WA".to_string())).expect("Valid US-WA region"),
+            TransactionType::B2C,
+        )
+        // No earlier sales this year
+        .with_turnover(AnnualTurnover::default());
+
+        let quote = db
+            .quote(TaxRequest::new(scenario.clone(), 100.0))
+            .expect("Quote should succeed");
+        assert!(quote.warnings.is_empty());
+
+        let quote = db
+            .quote(TaxRequest::new(scenario.with_profile("notice"), 100.0))
+            .expect("Quote should succeed");
+        assert_eq!(quote.calculation_type, TaxCalculationType::ZeroRated);
+        assert_eq!(quote.tax, 0.0);
+        assert_eq!(
+            quote.warnings,
+            vec![Warning::UseTaxNotice {
+                region: "US-WA".to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn test_profile_not_found() {
+        let db = setup();
+        let scenario = TaxScenario::new(
+            Region::new("DE".to_string(), None).expect("Valid German region"),
+            Region::new("DE".to_string(), None).expect("Valid German region"),
+            TransactionType::B2C,
+        )
+        .with_profile("missing");
+
+        let result = scenario.calculate_tax(100.0, &db);
+        assert!(matches!(
+            result,
+            Err(ProcessingError::DatabaseError(
+                DatabaseError::ProfileNotFound(_)
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_explain_legal_references() {
+        let db = setup();
+        let scenario = TaxScenario::new(
+            Region::parse("DE").expect("Valid region"),
+            Region::parse("FR").expect("Valid region"),
+            TransactionType::B2C,
+        )
+        // No earlier sales this year
+        .with_turnover(AnnualTurnover::default());
+        let explanation = scenario
+            .explain(100.0, &db)
+            .expect("Explanation should succeed");
+        let subjects: Vec<&str> = explanation
+            .legal_references
+            .iter()
+            .map(|reference| reference.subject.as_str())
+            .collect();
+        // Below the threshold: taxed at the German rate
+        assert_eq!(subjects, ["European Union internal_b2c", "DE rate"]);
+        assert_eq!(explanation.legal_references[1].citation, "\u{a7} 12 UStG");
+
+        // Rules and rates without a reference aren't listed
+        let scenario = TaxScenario::new(
+            Region::parse("IT").expect("Valid region"),
+            Region::parse("IT").expect("Valid region"),
+            TransactionType::B2C,
+        );
+        let explanation = scenario
+            .explain(100.0, &db)
+            .expect("Explanation should succeed");
+        assert!(explanation.legal_references.is_empty());
+    }
+
+    #[test]
+    fn test_explain_difference_digital_flag() {
+        let db = setup();
+        let physical = TaxScenario::new(
+            Region::new("DE".to_string(), None).expect("Valid German region"),
+            Region::new("FR".to_string(), None).expect("Valid French region"),
+            TransactionType::B2C,
+        )
+        // Before the €10,000 threshold covered digital products
+        .with_date("2021-06-30")
+        .unwrap()
+        .with_turnover(AnnualTurnover::default());
+        let mut digital = physical.clone();
+        digital.is_digital_product_or_service = true;
+
+        let difference = physical
+            .explain_difference(&digital, 100.0, &db)
+            .expect("Explanation should succeed");
+        assert_eq!(difference.changes.len(), 1);
+        assert_eq!(difference.changes[0].field, "is_digital_product_or_service");
+        assert_eq!(difference.left.tax, 19.0); // German VAT, below threshold
+        assert_eq!(difference.right.tax, 20.0); // French VAT, no digital threshold
+
+        let (left, right) = difference.first_divergence.expect("Steps should diverge");
+        // The goods count towards the threshold, the digital product has none
+        assert_eq!(left.expect("Left step").check, "turnover");
+        assert!(right
+            .expect("Right step")
+            .outcome
+            .contains("digital product"));
+    }
+
+    #[test]
+    fn test_diff_identical_scenarios() {
+        let db = setup();
+        let scenario = TaxScenario::new(
+            Region::new("US".to_string(), Some("US-CA".to_string())).expect("Valid US-CA region"),
+            Region::new("US".to_string(), Some("US-WA".to_string())).expect("Valid US-WA region"),
+            TransactionType::B2C,
+        );
+        assert!(scenario.diff(&scenario.clone()).is_empty());
+
+        let difference = scenario
+            .explain_difference(&scenario.clone(), 100.0, &db)
+            .expect("Explanation should succeed");
+        assert!(difference.is_same_tax());
+        assert!(difference.first_divergence.is_none());
+    }
+
+    #[test]
+    fn test_quote_from_database() {
+        let db = setup();
+        let scenario = TaxScenario::new(
+            Region::new("DE".to_string(), None).expect("Valid German region"),
+            Region::new("FR".to_string(), None).expect("Valid French region"),
+            TransactionType::B2B,
+        )
+        .with_buyer_vat_id(VatId::parse("FR40303265045").expect("Valid VAT ID"));
+
+        let quote = db
+            .quote(TaxRequest::new(scenario, 100.0))
+            .expect("Quote should succeed");
+        assert_eq!(quote.calculation_type, TaxCalculationType::ReverseCharge);
+        assert_eq!(quote.tax, 0.0);
+        assert_eq!(quote.total, 100.0);
+        assert!(quote.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_quote_warns_about_defaulted_region() {
+        let db = setup();
+        let mut scenario = TaxScenario::new(
+            Region::new("CA".to_string(), Some("CA-BC".to_string())).expect("Valid BC region"),
+            Region::new("CA".to_string(), Some("CA-AB".to_string())).expect("Valid AB region"),
+            TransactionType::B2B,
+        );
+
+        let quote = db
+            .quote(TaxRequest::new(scenario.clone(), 100.0))
+            .expect("Quote should succeed");
+        assert_eq!(quote.tax, 5.0); // GST only
+        assert_eq!(
+            quote.warnings,
+            vec![Warning::RegionDefaulted {
+                region: "CA-AB".to_string(),
+                country: "CA".to_string(),
+            }]
+        );
+
+        scenario.destination_region =
+            Region::new("CA".to_string(), Some("CA-BC".to_string())).expect("Valid BC region");
+        let quote = db
+            .quote(TaxRequest::new(scenario, 100.0))
+            .expect("Quote should succeed");
+        assert!(quote.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_quote_from_mock() {
+        struct FixedTax(f64);
+
+        impl TaxCalculator for FixedTax {
+            fn quote(&self, request: TaxRequest) -> Result<TaxQuote, ProcessingError> {
+                Ok(TaxQuote {
+                    calculation_type: TaxCalculationType::Origin,
+                    rates: vec![],
+                    amount: request.amount,
+                    tax: self.0,
+                    total: request.amount + self.0,
+                    warnings: vec![],
+                    confidence: Confidence::Exact,
+                    e_invoicing: vec![],
+                    qualified_invoice: false,
+                    self_assessment: None,
+                })
+            }
+        }
+
+        fn checkout_total(calculator: &dyn TaxCalculator, request: TaxRequest) -> f64 {
+            calculator
+                .quote(request)
+                .expect("Quote should succeed")
+                .total
+        }
+
+        let scenario = TaxScenario::new(
+            Region::new("DE".to_string(), None).expect("Valid German region"),
+            Region::new("DE".to_string(), None).expect("Valid German region"),
+            TransactionType::B2C,
+        );
+        let total = checkout_total(&FixedTax(5.0), TaxRequest::new(scenario, 100.0));
+        assert_eq!(total, 105.0);
+    }
+
+    #[test]
+    fn test_agreement_fallback_rule() {
+        let countries = r#"{
+            "DE": { "type": "vat", "currency": "EUR", "standard_rate": 0.19 },
+            "FR": { "type": "vat", "currency": "EUR", "standard_rate": 0.2 }
+        }"#;
+        let agreement = |fallback: &str| {
+            format!(
+                r#"{{ "XU": {{
+                    "name": "Example Union",
+                    "type": "customs_union",
+                    "members": ["DE", "FR"],
+                    "default_applicable": true,
+                    "applies_to": {{ "physical_goods": true, "digital_goods": true, "services": true }},
+                    "tax_rules": {{
+                        "internal_b2b": {{ "type": "reverse_charge" }},
+                        "external_export": {{ "type": "zero_rated" }}{}
+                    }}
+                }} }}"#,
+                fallback
+            )
+        };
+        let scenario = TaxScenario::new(
+            Region::parse("DE").expect("Valid German region"),
+            Region::parse("FR").expect("Valid French region"),
+            TransactionType::B2C,
+        );
+
+        // Without a fallback rule, the profile's fallback policy applies (destination)
+        let db = TaxDatabase::from_json(countries, &agreement("")).expect("Data should load");
+        assert_eq!(scenario.calculate_tax(100.0, &db).unwrap(), 20.0);
+        let explanation = scenario.explain(100.0, &db).unwrap();
+        assert!(explanation
+            .steps
+            .iter()
+            .any(|step| step.check == "fallback" && step.outcome.contains("default Destination")));
+
+        let db = TaxDatabase::from_json(
+            countries,
+            &agreement(r#", "fallback": { "type": "origin" }"#),
+        )
+        .expect("Data should load");
+        assert_eq!(scenario.calculate_tax(100.0, &db).unwrap(), 19.0);
+        let explanation = scenario.explain(100.0, &db).unwrap();
+        assert!(explanation
+            .steps
+            .iter()
+            .any(|step| step.check == "fallback" && step.outcome.contains("agreement fallback")));
+    }
+
+    #[test]
+    fn test_get_rates_into_reuses_buffer() {
+        let db = setup();
+        let quebec = TaxScenario::new(
+            Region::parse("CA-QC").expect("Valid Quebec region"),
+            Region::parse("CA-QC").expect("Valid Quebec region"),
+            TransactionType::B2B,
+        );
+        let export = TaxScenario::new(
+            Region::parse("DE").expect("Valid German region"),
+            Region::parse("TH").expect("Valid Thai region"),
+            TransactionType::B2B,
+        );
+
+        let mut rates = Vec::new();
+        quebec.get_rates_into(100.0, &db, &mut rates).unwrap();
+        assert_eq!(rates.len(), 2);
+        let capacity = rates.capacity();
+
+        // Previous rates are replaced, and the buffer is kept
+        export.get_rates_into(100.0, &db, &mut rates).unwrap();
+        assert_eq!(rates.len(), 1);
+        assert_eq!(rates[0].tax_type, TaxType::VAT(VatRate::Zero));
+        assert_eq!(rates.capacity(), capacity);
+
+        quebec.get_rates_into(100.0, &db, &mut rates).unwrap();
+        assert_eq!(rates, quebec.get_rates(100.0, &db).unwrap());
+    }
+
     #[test]
     fn load_included_db() {
         let _ = TaxDatabase::new();
     }
+
+    #[test]
+    fn test_state_rate_caps() {
+        let mut db = setup();
+        let mut scenario = TaxScenario::new(
+            Region::new("US".to_string(), Some("US-CA".to_string())).expect("Valid US-CA region"),
+            Region::new("US".to_string(), Some("US-WA".to_string())).expect("Valid US-WA region"),
+            TransactionType::B2C,
+        );
+        scenario.threshold_policy = ThresholdPolicy::AssumeAbove;
+        assert_eq!(scenario.calculate_tax(10000.0, &db).unwrap(), 650.0);
+
+        let state = db.countries.get_mut("US").unwrap().states.as_mut().unwrap();
+        state.get_mut("US-WA").unwrap().cap = Some(TaxCap {
+            max_taxable_amount: Some(5000.0),
+            ..Default::default()
+        });
+        assert_eq!(scenario.calculate_tax(10000.0, &db).unwrap(), 325.0);
+        assert_eq!(scenario.calculate_tax(1000.0, &db).unwrap(), 65.0);
+        assert_eq!(
+            scenario.calculate_tax_decimal(dec!(10000), &db).unwrap(),
+            dec!(325)
+        );
+
+        let state = db.countries.get_mut("US").unwrap().states.as_mut().unwrap();
+        state.get_mut("US-WA").unwrap().cap = Some(TaxCap {
+            max_tax: Some(100.0),
+            ..Default::default()
+        });
+        assert_eq!(scenario.calculate_tax(10000.0, &db).unwrap(), 100.0);
+        let rates = scenario.get_rates(10000.0, &db).unwrap();
+        assert_eq!(rates[0].cap.and_then(|cap| cap.max_tax), Some(100.0));
+    }
+
+    #[test]
+    fn test_calculate_tax_from_gross() {
+        let db = TaxDatabase::fixture();
+        let scenario = |source: &str, destination: &str| {
+            TaxScenario::new(
+                Region::parse(source).unwrap(),
+                Region::parse(destination).unwrap(),
+                TransactionType::B2C,
+            )
+        };
+
+        let split = scenario("DE", "DE")
+            .calculate_tax_from_gross(119.0, &db)
+            .unwrap();
+        assert_eq!((split.net, split.tax), (100.0, 19.0));
+
+        // GST 5%, then PST 7% on the amount including GST
+        let mut bc = scenario("CA-ON", "CA-BC");
+        bc.threshold_policy = ThresholdPolicy::AssumeAbove;
+        let split = bc.calculate_tax_from_gross(112.35, &db).unwrap();
+        assert_eq!(split.tax, 12.35);
+        assert_eq!(split.net + split.tax, split.gross);
+
+        // 10,500 gross is above the EU threshold, but 8,823.53 net at 19% is not
+        let split = scenario("DE", "FR")
+            .with_turnover(AnnualTurnover::default())
+            .calculate_tax_from_gross(10500.0, &db)
+            .unwrap();
+        assert_eq!(split.tax, 1676.47);
+
+        // Net amounts of cent amounts are cent amounts
+        let de = scenario("DE", "DE");
+        let split = de.calculate_tax_from_gross(0.06, &db).unwrap();
+        assert_eq!((split.net, split.tax), (0.05, 0.01));
+        for cents in 1..20_000 {
+            let split = de
+                .calculate_tax_from_gross(cents as f64 / 100.0, &db)
+                .unwrap();
+            assert_eq!(split.net, (split.net * 100.0).round() / 100.0, "{cents}");
+        }
+    }
+
+    #[test]
+    fn test_calculate_tax_from_gross_with_cap() {
+        let mut db = setup();
+        let state = db.countries.get_mut("US").unwrap().states.as_mut().unwrap();
+        state.get_mut("US-WA").unwrap().cap = Some(TaxCap {
+            max_taxable_amount: Some(5000.0),
+            ..Default::default()
+        });
+        let mut scenario = TaxScenario::new(
+            Region::parse("US-CA").unwrap(),
+            Region::parse("US-WA").unwrap(),
+            TransactionType::B2C,
+        );
+        scenario.threshold_policy = ThresholdPolicy::AssumeAbove;
+
+        let split = scenario.calculate_tax_from_gross(10325.0, &db).unwrap();
+        assert_eq!(split.tax, 325.0);
+        assert_eq!(split.net, 10000.0);
+    }
+
+    #[test]
+    fn test_calculate_tax_detailed() {
+        let mut db = TaxDatabase::fixture();
+        db.register_profile(
+            "up",
+            CalculationProfile {
+                rounding: Rounding {
+                    decimal_places: 1,
+                    strategy: RoundingStrategy::Up,
+                },
+                ..Default::default()
+            },
+        );
+        let scenario = TaxScenario::new(
+            Region::parse("CA-QC").unwrap(),
+            Region::parse("CA-QC").unwrap(),
+            TransactionType::B2C,
+        )
+        .with_profile("up");
+
+        let result = scenario.calculate_tax_detailed(100.0, &db).unwrap();
+        assert_eq!(result.calculation_type, TaxCalculationType::Destination);
+        let taxes: Vec<(TaxType, f64, f64)> = result
+            .rates
+            .iter()
+            .map(|applied| (applied.rate.tax_type, applied.base, applied.tax))
+            .collect();
+        assert_eq!(
+            taxes,
+            vec![(TaxType::GST, 100.0, 5.0), (TaxType::QST, 105.0, 10.5)]
+        );
+        assert_eq!(result.tax, scenario.calculate_tax(100.0, &db).unwrap());
+        assert_eq!(result.tax, 15.5);
+        assert_eq!(result.total, 115.5);
+
+        let reverse_charge = TaxScenario::new(
+            Region::parse("DE").unwrap(),
+            Region::parse("FR").unwrap(),
+            TransactionType::B2B,
+        )
+        .with_buyer_vat_id(VatId::parse("FR40303265045").unwrap());
+        let result = reverse_charge.calculate_tax_detailed(100.0, &db).unwrap();
+        assert_eq!(result.calculation_type, TaxCalculationType::ReverseCharge);
+        // Invoices state the reverse charge as a line without tax
+        assert_eq!(result.rates.len(), 1);
+        assert_eq!(
+            result.rates[0].rate.tax_type,
+            TaxType::VAT(VatRate::ReverseCharge)
+        );
+        assert_eq!(result.rates[0].tax, 0.0);
+        assert_eq!(result.total, 100.0);
+
+        // Totals of cent amounts are cent amounts
+        let germany = TaxScenario::new(
+            Region::parse("DE").unwrap(),
+            Region::parse("DE").unwrap(),
+            TransactionType::B2C,
+        );
+        let result = germany.calculate_tax_detailed(0.05, &db).unwrap();
+        assert_eq!((result.tax, result.total), (0.01, 0.06));
+        for cents in 1..20_000 {
+            let result = germany
+                .calculate_tax_detailed(cents as f64 / 100.0, &db)
+                .unwrap();
+            assert_eq!(
+                result.total,
+                (result.total * 100.0).round() / 100.0,
+                "{cents}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_composite_rates() {
+        let percent = |fraction| Percentage::from_fraction(fraction);
+        let gst = TaxRate::new(
+            percent(dec!(0.05)),
+            TaxType::GST,
+            RateSource::CountryDefault,
+        );
+        let pst = TaxRate::new(percent(dec!(0.07)), TaxType::PST, RateSource::StateSpecific);
+        let qst = TaxRate::new(percent(dec!(0.1)), TaxType::QST, RateSource::StateSpecific);
+
+        // Compounds on all preceding taxes, or only on those of a type
+        let all = [gst, pst, qst.compounding()];
+        assert_eq!(sum_rates_decimal(dec!(100), &all), dec!(23.2));
+        let on_gst = [gst, pst, qst.compounding_on(TaxType::GST)];
+        assert_eq!(sum_rates_decimal(dec!(100), &on_gst), dec!(22.5));
+        assert!((combined_rate(&on_gst) - 0.225).abs() < 1e-9);
+
+        // Flat and per-unit amounts
+        let flat = TaxRate::flat(1.5, TaxType::LocalSalesTax, RateSource::Local);
+        let per_unit = TaxRate::per_unit(0.25, TaxType::LocalSalesTax, RateSource::Local);
+        let rates = [gst, flat, per_unit];
+        assert_eq!(sum_item_rates_decimal(dec!(10), dec!(4), &rates), dec!(4.5));
+        assert_eq!(
+            sum_item_rates_decimal(dec!(-10), dec!(4), &rates),
+            dec!(-4.5)
+        );
+        assert_eq!(combined_rate(&rates), 0.05);
+
+        let db = TaxDatabase::fixture();
+        let scenario = TaxScenario::new(
+            Region::parse("DE").unwrap(),
+            Region::parse("DE").unwrap(),
+            TransactionType::B2C,
+        );
+        assert_eq!(scenario.sum_rates(40.0, &rates, &db).unwrap(), 3.75);
+    }
+
+    #[test]
+    fn test_tax_rate_serde_compatibility() {
+        let old = r#"{ "rate": 0.09975, "tax_type": { "type": "q_s_t" }, "compound": true }"#;
+        let rate: TaxRate = serde_json::from_str(old).expect("Old rates should load");
+        assert_eq!(rate.basis, RateBasis::Percentage);
+        assert_eq!(rate.compounds_on, None);
+        assert_eq!(rate.source, RateSource::CountryDefault);
+        assert!(rate.compound);
+
+        // New fields are only written when set
+        let json = serde_json::to_value(rate).unwrap();
+        assert!(json.get("basis").is_none());
+        assert!(json.get("compounds_on").is_none());
+
+        let levy = TaxRate::per_unit(0.5, TaxType::LocalSalesTax, RateSource::Local)
+            .compounding_on(TaxType::GST);
+        let json = serde_json::to_string(&levy).unwrap();
+        assert_eq!(serde_json::from_str::<TaxRate>(&json).unwrap(), levy);
+    }
+
+    #[test]
+    fn test_rules_as_of_scenario_date() {
+        let db = setup();
+        let scenario = TaxScenario::new(
+            Region::parse("DE").unwrap(),
+            Region::parse("FR").unwrap(),
+            TransactionType::B2C,
+        )
+        .with_turnover(AnnualTurnover {
+            current_year: 20_000.0,
+            previous_year: 0.0,
+            ..Default::default()
+        });
+
+        // Above the EU-wide €10,000 threshold in effect since 2021-07-01
+        assert_eq!(
+            scenario.determine_calculation_type(&db, 100.0).unwrap(),
+            TaxCalculationType::Destination
+        );
+        let current = scenario.clone().with_date("2021-07-01").unwrap();
+        assert_eq!(
+            current.determine_calculation_type(&db, 100.0).unwrap(),
+            TaxCalculationType::Destination
+        );
+
+        // Below the €35,000 threshold that applied before
+        let historical = scenario.with_date("2021-06-30").unwrap();
+        assert_eq!(
+            historical.determine_calculation_type(&db, 100.0).unwrap(),
+            TaxCalculationType::Origin
+        );
+        assert_eq!(historical.calculate_tax(100.0, &db).unwrap(), 19.0);
+        assert!(historical
+            .explain(100.0, &db)
+            .unwrap()
+            .steps
+            .iter()
+            .any(|step| step.check == "rules_as_of" && step.outcome.contains("2021-06-30")));
+
+        assert!(TaxScenario::new(
+            Region::parse("DE").unwrap(),
+            Region::parse("FR").unwrap(),
+            TransactionType::B2C,
+        )
+        .with_date("30.06.2021")
+        .is_err());
+    }
+
+    #[test]
+    fn test_scenario_serde() {
+        let db = setup();
+        let mut scenario = TaxScenario::new(
+            Region::parse("US-CA").unwrap().with_postal_code("94103"),
+            Region::parse("CA-QC").unwrap(),
+            TransactionType::B2B,
+        )
+        .with_trade_agreement_override(TradeAgreementOverride::UseAgreement("CA".to_string()))
+        .with_exemption_certificate(ExemptionCertificate::new(
+            "CA-QC",
+            "R-1",
+            CertificateKind::Resale,
+        ))
+        .with_incoterm(Incoterm::Ddp);
+        scenario.vat_rate = Some(VatRate::Reduced);
+        scenario.threshold_policy = ThresholdPolicy::AssumeAbove;
+
+        let json = serde_json::to_value(&scenario).unwrap();
+        assert_eq!(json["source_region"]["region"], "US-CA");
+        assert_eq!(json["source_region"]["postal_code"], "94103");
+        assert_eq!(json["trade_agreement_override"]["content"], "CA");
+        assert!(json.get("profile").is_none());
+
+        let restored: TaxScenario = serde_json::from_value(json).unwrap();
+        assert_eq!(restored.source_region.to_string(), "US-CA 94103");
+        assert_eq!(restored.destination_region.region.as_deref(), Some("CA-QC"));
+        assert!(matches!(
+            restored.trade_agreement_override,
+            Some(TradeAgreementOverride::UseAgreement(ref id)) if id == "CA"
+        ));
+        assert_eq!(restored.exemption_certificate.unwrap().number, "R-1");
+        assert_eq!(restored.vat_rate, Some(VatRate::Reduced));
+        assert_eq!(restored.threshold_policy, ThresholdPolicy::AssumeAbove);
+        assert_eq!(restored.incoterm, Some(Incoterm::Ddp));
+
+        // Regions as codes, normalized like `Region::new`
+        let minimal: TaxScenario = serde_json::from_str(
+            r#"{ "source_region": "DE", "destination_region": { "country": "CA", "region": "qc" }, "transaction_type": "b2c" }"#,
+        )
+        .unwrap();
+        assert_eq!(minimal.destination_region.region.as_deref(), Some("CA-QC"));
+        assert!(!minimal.is_digital_product_or_service);
+        assert_eq!(minimal.threshold_policy, ThresholdPolicy::Respect);
+        assert_eq!(
+            minimal.calculate_tax(100.0, &db).unwrap(),
+            TaxScenario::new(
+                Region::parse("DE").unwrap(),
+                Region::parse("CA-QC").unwrap(),
+                TransactionType::B2C,
+            )
+            .calculate_tax(100.0, &db)
+            .unwrap()
+        );
+
+        // Invalid codes are rejected
+        for region in [
+            r#""XX""#,
+            r#""US-XX""#,
+            r#"{ "country": "US", "region": "US-XX" }"#,
+        ] {
+            let json = format!(
+                r#"{{ "source_region": {}, "destination_region": "DE", "transaction_type": "b2c" }}"#,
+                region
+            );
+            let error = serde_json::from_str::<TaxScenario>(&json).unwrap_err();
+            assert!(error.to_string().contains("XX"), "{}", error);
+        }
+    }
 }
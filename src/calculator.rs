@@ -0,0 +1,135 @@
+//! Calculation trait for consumers.
+//!
+//! `TaxCalculator` decouples applications from the concrete `TaxScenario` and
+//! `TaxDatabase` types: code that needs tax quotes can depend on the trait, and
+//! tests can inject a mock implementation instead of the real dataset.
+
+use serde::Serialize;
+
+use crate::calculation::add_amounts;
+use crate::confidence::Confidence;
+use crate::einvoicing::EInvoicingMandate;
+use crate::rate_provider::RateProvider;
+use crate::self_assessment::SelfAssessment;
+use crate::{ProcessingError, TaxCalculationType, TaxDatabase, TaxRate, TaxScenario, Warning};
+
+/// A request for a tax quote.
+#[derive(Debug, Clone)]
+pub struct TaxRequest {
+    /// The scenario to calculate
+    pub scenario: TaxScenario,
+    /// The net transaction amount
+    pub amount: f64,
+}
+
+impl TaxRequest {
+    /// Creates a new tax request.
+    pub fn new(scenario: TaxScenario, amount: f64) -> Self {
+        Self { scenario, amount }
+    }
+}
+
+/// The result of a tax request.
+#[derive(Debug, Clone, Serialize)]
+pub struct TaxQuote {
+    /// The calculation type that was applied
+    pub calculation_type: TaxCalculationType,
+    /// The applied tax rates
+    pub rates: Vec<TaxRate>,
+    /// The net transaction amount
+    pub amount: f64,
+    /// The calculated tax amount
+    pub tax: f64,
+    /// The gross amount (net amount plus tax)
+    pub total: f64,
+    /// Issues with the result that didn't stop the calculation
+    pub warnings: Vec<Warning>,
+    /// How precisely the tax reflects the tax due, derived from the warnings
+    pub confidence: Confidence,
+    /// E-invoicing mandates of the seller's country that cover the transaction
+    pub e_invoicing: Vec<EInvoicingMandate>,
+    /// Whether the invoice must show the seller's registration number for the buyer
+    /// to deduct the tax (Japan's qualified invoices)
+    pub qualified_invoice: bool,
+    /// Set if the sale is reverse charged and the buyer must self-assess the tax,
+    /// with an estimate of it
+    pub self_assessment: Option<SelfAssessment>,
+}
+
+/// Calculates tax quotes.
+///
+/// Implemented by `TaxDatabase`, which resolves the request's scenario against
+/// its data. Implement it yourself to mock tax calculation in tests.
+///
+/// # Examples
+///
+/// ```
+/// use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+/// use rust_decimal::Decimal;
+/// use rust_decimal_macros::dec;
+/// use world_tax::calculator::{TaxCalculator, TaxQuote, TaxRequest};
+/// use world_tax::confidence::Confidence;
+/// use world_tax::{ProcessingError, TaxCalculationType};
+///
+/// struct FlatTax;
+///
+/// impl TaxCalculator for FlatTax {
+///     fn quote(&self, request: TaxRequest) -> Result<TaxQuote, ProcessingError> {
+///         // Calculate in decimal arithmetic, so 0.05 plus 0.01 is 0.06
+///         let amount = Decimal::from_f64(request.amount).ok_or(ProcessingError::InvalidAmount)?;
+///         let tax = (amount * dec!(0.1)).round_dp(2);
+///         Ok(TaxQuote {
+///             calculation_type: TaxCalculationType::Origin,
+///             rates: vec![],
+///             amount: request.amount,
+///             tax: tax.to_f64().unwrap_or_default(),
+///             total: (amount + tax).to_f64().unwrap_or_default(),
+///             warnings: vec![],
+///             confidence: Confidence::Exact,
+///             e_invoicing: vec![],
+///             qualified_invoice: false,
+///             self_assessment: None,
+///         })
+///     }
+/// }
+/// ```
+pub trait TaxCalculator {
+    /// Calculates a tax quote for the request.
+    fn quote(&self, request: TaxRequest) -> Result<TaxQuote, ProcessingError>;
+}
+
+impl TaxCalculator for TaxDatabase {
+    fn quote(&self, request: TaxRequest) -> Result<TaxQuote, ProcessingError> {
+        quote(self, self, request)
+    }
+}
+
+/// Quotes a request with the data of `provider`, and the warnings of `db`.
+pub(crate) fn quote<P: RateProvider + ?Sized>(
+    provider: &P,
+    db: &TaxDatabase,
+    request: TaxRequest,
+) -> Result<TaxQuote, ProcessingError> {
+    let scenario = &request.scenario;
+    let calculation_type = scenario.determine_calculation_type(provider, request.amount)?;
+    let rates = scenario.get_rates(request.amount, provider)?;
+    let tax = scenario.sum_rates(request.amount, &rates, provider)?;
+    let warnings = scenario.warnings(&calculation_type, &rates, db);
+    let confidence = Confidence::from_warnings(&warnings);
+    let qualified_invoice = scenario.qualified_invoice(&calculation_type, tax != 0.0);
+    let self_assessment =
+        scenario.self_assessment(&calculation_type, tax, request.amount, provider);
+
+    Ok(TaxQuote {
+        calculation_type,
+        rates,
+        amount: request.amount,
+        tax,
+        total: add_amounts(request.amount, tax)?,
+        warnings,
+        confidence,
+        e_invoicing: scenario.e_invoicing_mandates(),
+        qualified_invoice,
+        self_assessment,
+    })
+}
@@ -0,0 +1,243 @@
+//! Incremental cart recalculation.
+//!
+//! A `Cart` resolves the applicable tax rates once per destination and keeps
+//! running totals, so quantity changes and line updates in a checkout UI don't
+//! re-run trade agreement resolution on every keystroke.
+
+use std::collections::HashMap;
+
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+
+use crate::calculation::sum_item_rates_decimal;
+use crate::rate_provider::RateProvider;
+use crate::{ProcessingError, Region, TaxRate, TaxScenario};
+
+/// A line in a cart.
+#[derive(Debug, Clone)]
+pub struct CartLine {
+    /// Identifier of the line, unique within the cart
+    pub id: String,
+    /// Where the line is delivered to
+    pub destination: Region,
+    /// Net price per unit
+    pub unit_price: Decimal,
+    /// Number of units
+    pub quantity: u32,
+    /// Tax on the line (unrounded)
+    pub tax: Decimal,
+}
+
+impl CartLine {
+    /// Net amount of the line (unit price times quantity).
+    pub fn amount(&self) -> Decimal {
+        self.unit_price * Decimal::from(self.quantity)
+    }
+
+    /// Tax on the line at the given rates; caps per item apply to each unit.
+    fn tax_at(&self, rates: &[TaxRate]) -> Decimal {
+        sum_item_rates_decimal(self.unit_price, Decimal::from(self.quantity), rates)
+    }
+}
+
+/// A basket of lines with incrementally maintained totals.
+///
+/// Rates are resolved the first time a destination is added, using that line's
+/// amount for threshold checks. Call `refresh` to resolve them again, for example
+/// after the cart crossed a threshold. Rates come from any `RateProvider`, such as
+/// a `DatedView` for a cart priced as of a past date.
+///
+/// # Examples
+///
+/// ```
+/// # use world_tax::cart::Cart;
+/// # use world_tax::{Region, TaxDatabase, TaxScenario, TransactionType};
+/// # use rust_decimal_macros::dec;
+/// let db = TaxDatabase::fixture();
+/// let scenario = TaxScenario::new(
+///     Region::new("DE".to_string(), None).unwrap(),
+///     Region::new("DE".to_string(), None).unwrap(),
+///     TransactionType::B2C,
+/// );
+///
+/// let mut cart = Cart::new(scenario);
+/// cart.add_line("book", dec!(10.00), 1, &db).unwrap();
+/// cart.set_quantity("book", 3);
+/// assert_eq!(cart.tax(), dec!(5.70));
+/// ```
+#[derive(Debug, Clone)]
+pub struct Cart {
+    /// Template for all lines; lines may override the destination
+    scenario: TaxScenario,
+    /// Lines in insertion order
+    lines: Vec<CartLine>,
+    /// Resolved rates by destination
+    rates: HashMap<String, Vec<TaxRate>>,
+    /// Running net total
+    subtotal: Decimal,
+    /// Running tax total
+    tax: Decimal,
+}
+
+impl Cart {
+    /// Creates an empty cart for the scenario.
+    pub fn new(scenario: TaxScenario) -> Self {
+        Self {
+            scenario,
+            lines: Vec::new(),
+            rates: HashMap::new(),
+            subtotal: Decimal::ZERO,
+            tax: Decimal::ZERO,
+        }
+    }
+
+    /// Adds a line delivered to the scenario's destination, replacing any line with the same id.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the rates for the destination can't be resolved.
+    pub fn add_line<P: RateProvider + ?Sized>(
+        &mut self,
+        id: impl Into<String>,
+        unit_price: Decimal,
+        quantity: u32,
+        db: &P,
+    ) -> Result<(), ProcessingError> {
+        let destination = self.scenario.destination_region.clone();
+        self.add_line_to(id, destination, unit_price, quantity, db)
+    }
+
+    /// Adds a line delivered to a specific destination, replacing any line with the same id.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the rates for the destination can't be resolved.
+    pub fn add_line_to<P: RateProvider + ?Sized>(
+        &mut self,
+        id: impl Into<String>,
+        destination: Region,
+        unit_price: Decimal,
+        quantity: u32,
+        db: &P,
+    ) -> Result<(), ProcessingError> {
+        let key = destination.to_string();
+        let amount = unit_price * Decimal::from(quantity);
+        if !self.rates.contains_key(&key) {
+            let rates = self.resolve(&destination, amount, db)?;
+            self.rates.insert(key.clone(), rates);
+        }
+
+        let id = id.into();
+        self.remove_line(&id);
+
+        let mut line = CartLine {
+            id,
+            destination,
+            unit_price,
+            quantity,
+            tax: Decimal::ZERO,
+        };
+        line.tax = line.tax_at(&self.rates[&key]);
+        self.subtotal += amount;
+        self.tax += line.tax;
+        self.lines.push(line);
+        Ok(())
+    }
+
+    /// Changes the quantity of a line, using the already resolved rates.
+    ///
+    /// Returns false if there's no line with that id.
+    pub fn set_quantity(&mut self, id: &str, quantity: u32) -> bool {
+        let Some(line) = self.lines.iter_mut().find(|line| line.id == id) else {
+            return false;
+        };
+        let rates = &self.rates[&line.destination.to_string()];
+
+        self.subtotal -= line.amount();
+        self.tax -= line.tax;
+        line.quantity = quantity;
+        line.tax = line.tax_at(rates);
+        self.subtotal += line.amount();
+        self.tax += line.tax;
+        true
+    }
+
+    /// Removes a line; returns false if there's no line with that id.
+    pub fn remove_line(&mut self, id: &str) -> bool {
+        let Some(index) = self.lines.iter().position(|line| line.id == id) else {
+            return false;
+        };
+        let line = self.lines.remove(index);
+        self.subtotal -= line.amount();
+        self.tax -= line.tax;
+        true
+    }
+
+    /// Resolves the rates of all destinations again, using each destination's
+    /// current subtotal for threshold checks, and recomputes all lines.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the rates for a destination can't be resolved.
+    pub fn refresh<P: RateProvider + ?Sized>(&mut self, db: &P) -> Result<(), ProcessingError> {
+        let mut subtotals: HashMap<String, (Region, Decimal)> = HashMap::new();
+        for line in &self.lines {
+            subtotals
+                .entry(line.destination.to_string())
+                .or_insert_with(|| (line.destination.clone(), Decimal::ZERO))
+                .1 += line.amount();
+        }
+
+        self.rates.clear();
+        for (key, (destination, amount)) in subtotals {
+            let rates = self.resolve(&destination, amount, db)?;
+            self.rates.insert(key, rates);
+        }
+
+        self.tax = Decimal::ZERO;
+        for line in &mut self.lines {
+            line.tax = line.tax_at(&self.rates[&line.destination.to_string()]);
+            self.tax += line.tax;
+        }
+        Ok(())
+    }
+
+    /// The lines in the cart, in insertion order.
+    pub fn lines(&self) -> &[CartLine] {
+        &self.lines
+    }
+
+    /// The resolved rates for a destination, if a line was added for it.
+    pub fn rates_for(&self, destination: &Region) -> Option<&[TaxRate]> {
+        self.rates
+            .get(&destination.to_string())
+            .map(|rates| rates.as_slice())
+    }
+
+    /// Net total of all lines.
+    pub fn subtotal(&self) -> Decimal {
+        self.subtotal
+    }
+
+    /// Tax total of all lines (unrounded).
+    pub fn tax(&self) -> Decimal {
+        self.tax
+    }
+
+    /// Gross total of all lines (unrounded).
+    pub fn total(&self) -> Decimal {
+        self.subtotal + self.tax
+    }
+
+    fn resolve<P: RateProvider + ?Sized>(
+        &self,
+        destination: &Region,
+        amount: Decimal,
+        db: &P,
+    ) -> Result<Vec<TaxRate>, ProcessingError> {
+        let mut scenario = self.scenario.clone();
+        scenario.destination_region = destination.clone();
+        let amount = amount.to_f64().ok_or(ProcessingError::InvalidAmount)?;
+        scenario.get_rates(amount, db)
+    }
+}
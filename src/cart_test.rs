@@ -0,0 +1,128 @@
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+
+    use crate::cart::Cart;
+    use crate::types::{AnnualTurnover, TaxCap};
+    use crate::{Percentage, Region, TaxDatabase, TaxScenario, ThresholdPolicy, TransactionType};
+
+    fn scenario() -> TaxScenario {
+        let mut scenario = TaxScenario::new(
+            Region::new("DE".to_string(), None).expect("Valid German region"),
+            Region::new("DE".to_string(), None).expect("Valid German region"),
+            TransactionType::B2C,
+        );
+        scenario.is_digital_product_or_service = true;
+        scenario
+    }
+
+    #[test]
+    fn test_cart_incremental_totals() {
+        let db = TaxDatabase::fixture();
+        let mut cart = Cart::new(scenario());
+        cart.add_line("a", dec!(10.00), 2, &db)
+            .expect("Line should be added");
+        cart.add_line("b", dec!(5.00), 1, &db)
+            .expect("Line should be added");
+        assert_eq!(cart.subtotal(), dec!(25.00));
+        assert_eq!(cart.tax(), dec!(4.75));
+
+        assert!(cart.set_quantity("a", 1));
+        assert_eq!(cart.subtotal(), dec!(15.00));
+        assert_eq!(cart.tax(), dec!(2.85));
+
+        assert!(cart.remove_line("b"));
+        assert!(!cart.remove_line("b"));
+        assert!(!cart.set_quantity("b", 3));
+        assert_eq!(cart.total(), dec!(11.90));
+        assert_eq!(cart.lines().len(), 1);
+    }
+
+    #[test]
+    fn test_cart_multiple_destinations() {
+        let db = TaxDatabase::fixture();
+        let mut cart = Cart::new(scenario());
+        cart.add_line("de", dec!(100), 1, &db)
+            .expect("Line should be added");
+        cart.add_line_to(
+            "fr",
+            Region::new("FR".to_string(), None).expect("Valid French region"),
+            dec!(100),
+            1,
+            &db,
+        )
+        .expect("Line should be added");
+
+        // Digital products are taxed at destination within the EU
+        assert_eq!(cart.tax(), dec!(39.00));
+        let fr = Region::new("FR".to_string(), None).expect("Valid French region");
+        assert_eq!(
+            cart.rates_for(&fr).expect("FR rates resolved")[0].rate,
+            Percentage::from_fraction(dec!(0.2))
+        );
+    }
+
+    #[test]
+    fn test_cart_refresh_after_threshold() {
+        let db = TaxDatabase::fixture();
+        let mut scenario = scenario();
+        scenario.is_digital_product_or_service = false;
+        scenario.destination_region =
+            Region::new("FR".to_string(), None).expect("Valid French region");
+        scenario.turnover = Some(AnnualTurnover::default());
+
+        let mut cart = Cart::new(scenario);
+        cart.add_line("a", dec!(5000), 1, &db)
+            .expect("Line should be added");
+        assert_eq!(cart.tax(), dec!(950)); // German VAT, below the EU threshold
+
+        cart.set_quantity("a", 3);
+        assert_eq!(cart.tax(), dec!(2850)); // Still the resolved German rate
+
+        cart.refresh(&db).expect("Refresh should succeed");
+        assert_eq!(cart.tax(), dec!(3000)); // French VAT, above the EU threshold
+    }
+
+    #[test]
+    fn test_cart_per_item_cap() {
+        let mut db = TaxDatabase::new().expect("Embedded data should load");
+        let state = db.countries.get_mut("US").unwrap().states.as_mut().unwrap();
+        state.get_mut("US-WA").unwrap().cap = Some(TaxCap {
+            max_taxable_amount: Some(100.0),
+            per_item: true,
+            ..Default::default()
+        });
+        let mut scenario = TaxScenario::new(
+            Region::parse("US-CA").unwrap(),
+            Region::parse("US-WA").unwrap(),
+            TransactionType::B2C,
+        );
+        scenario.threshold_policy = ThresholdPolicy::AssumeAbove;
+
+        let mut cart = Cart::new(scenario);
+        // Each unit is taxed on at most 100
+        cart.add_line("a", dec!(150.00), 2, &db)
+            .expect("Line should be added");
+        assert_eq!(cart.tax(), dec!(13.00));
+        cart.add_line("b", dec!(50.00), 3, &db)
+            .expect("Line should be added");
+        assert_eq!(cart.tax(), dec!(22.75));
+    }
+
+    #[test]
+    fn test_cart_with_dated_view() {
+        let db = TaxDatabase::new().expect("Embedded data should load");
+        let mut scenario = scenario();
+        scenario.is_digital_product_or_service = false;
+
+        // Germany charged 16% in the second half of 2020
+        let past = db.as_of("2020-10-01").expect("Valid date");
+        let mut cart = Cart::new(scenario);
+        cart.add_line("a", dec!(100.00), 1, &past)
+            .expect("Line should be added");
+        assert_eq!(cart.tax(), dec!(16.00));
+
+        cart.refresh(&db).expect("Refresh should succeed");
+        assert_eq!(cart.tax(), dec!(19.00));
+    }
+}
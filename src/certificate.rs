@@ -0,0 +1,163 @@
+//! Resale and exemption certificates.
+//!
+//! A buyer's certificate exempts a US sale from sales tax, but only in the
+//! jurisdiction it was issued for, and only until it expires. Certificates are
+//! checked against the destination of the sale and the current date; rejected
+//! certificates are recorded in the explanation.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use typeshare::typeshare;
+
+use crate::errors::InputValidationError;
+use crate::Region;
+
+/// What a certificate exempts the buyer for.
+#[typeshare]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(rename_all = "snake_case")]
+pub enum CertificateKind {
+    /// The buyer purchases the goods for resale
+    Resale,
+    /// The buyer is exempt (e.g. non-profit, government, manufacturing use)
+    Exemption,
+}
+
+/// A buyer's resale or exemption certificate.
+///
+/// # Examples
+///
+/// ```
+/// # use world_tax::certificate::{CertificateKind, ExemptionCertificate};
+/// # use world_tax::Region;
+/// let certificate = ExemptionCertificate::new("US-WA", "RS-1234", CertificateKind::Resale)
+///     .with_expiry("2030-12-31")
+///     .unwrap();
+///
+/// let washington = Region::parse("US-WA").unwrap();
+/// assert!(certificate.is_valid_on(&washington, "2030-06-30"));
+/// assert!(!certificate.is_valid_on(&washington, "2031-01-01"));
+/// assert!(!certificate.is_valid_on(&Region::parse("US-TX").unwrap(), "2030-06-30"));
+/// ```
+#[typeshare]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct ExemptionCertificate {
+    /// Where the certificate is valid: a state (e.g. "US-WA"), or a country for
+    /// certificates accepted in all its states
+    pub jurisdiction: String,
+    /// Number of the certificate, as issued
+    pub number: String,
+    /// What the certificate exempts the buyer for
+    pub kind: CertificateKind,
+    /// Last day the certificate is valid (ISO 8601, e.g. "2030-12-31"), if it expires
+    pub expires: Option<String>,
+}
+
+impl ExemptionCertificate {
+    /// Creates a certificate without expiry.
+    pub fn new(
+        jurisdiction: impl Into<String>,
+        number: impl Into<String>,
+        kind: CertificateKind,
+    ) -> Self {
+        Self {
+            jurisdiction: jurisdiction.into(),
+            number: number.into(),
+            kind,
+            expires: None,
+        }
+    }
+
+    /// Sets the last day the certificate is valid.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InputValidationError::InvalidDate` if the date isn't an ISO 8601 date (YYYY-MM-DD).
+    pub fn with_expiry(mut self, date: &str) -> Result<Self, InputValidationError> {
+        if !is_iso_date(date) {
+            return Err(InputValidationError::InvalidDate(date.to_string()));
+        }
+        self.expires = Some(date.to_string());
+        Ok(self)
+    }
+
+    /// Returns true if the certificate was issued for the region or its country.
+    pub fn covers(&self, region: &Region) -> bool {
+        self.jurisdiction == region.country || region.region.as_ref() == Some(&self.jurisdiction)
+    }
+
+    /// Returns true if the certificate is expired on the date (ISO 8601).
+    pub fn is_expired_on(&self, date: &str) -> bool {
+        self.expires
+            .as_deref()
+            .is_some_and(|expires| expires < date)
+    }
+
+    /// Returns true if the certificate covers the region and isn't expired on the date.
+    pub fn is_valid_on(&self, region: &Region, date: &str) -> bool {
+        self.covers(region) && !self.is_expired_on(date)
+    }
+
+    /// Why the certificate can't be honored for a sale into the region on the date of
+    /// the sale (ISO 8601), or today without one, if it can't.
+    pub(crate) fn rejection(&self, region: &Region, date: Option<&str>) -> Option<String> {
+        if !self.covers(region) {
+            let destination = region.region.as_ref().unwrap_or(&region.country);
+            return Some(format!(
+                "{} is issued for {}, not {}",
+                self.number, self.jurisdiction, destination
+            ));
+        }
+        if self.is_expired_on(&date.map_or_else(today, str::to_string)) {
+            return Some(format!(
+                "{} expired on {}",
+                self.number,
+                self.expires.as_deref().unwrap_or_default()
+            ));
+        }
+        None
+    }
+}
+
+/// Returns true if the text is a date in the form YYYY-MM-DD.
+pub(crate) fn is_iso_date(date: &str) -> bool {
+    let parts: Vec<&str> = date.split('-').collect();
+    let [year, month, day] = parts.as_slice() else {
+        return false;
+    };
+    let digits =
+        |part: &str, len: usize| part.len() == len && part.bytes().all(|b| b.is_ascii_digit());
+    digits(year, 4)
+        && digits(month, 2)
+        && digits(day, 2)
+        && (1..=12).contains(&month.parse::<u8>().unwrap_or(0))
+        && (1..=31).contains(&day.parse::<u8>().unwrap_or(0))
+}
+
+/// The current date (UTC) as YYYY-MM-DD.
+pub(crate) fn today() -> String {
+    let days = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_secs() / 86_400)
+        .unwrap_or_default() as i64;
+
+    // Civil date from days since 1970-01-01 (Howard Hinnant's algorithm)
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let shifted_month = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * shifted_month + 2) / 5 + 1;
+    let month = if shifted_month < 10 {
+        shifted_month + 3
+    } else {
+        shifted_month - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
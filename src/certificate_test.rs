@@ -0,0 +1,149 @@
+#[cfg(test)]
+mod tests {
+    use crate::certificate::{today, CertificateKind, ExemptionCertificate};
+    use crate::{
+        BuyerType, InputValidationError, Region, TaxCalculationType, TaxDatabase, TaxScenario,
+        TransactionType,
+    };
+
+    fn setup() -> TaxDatabase {
+        TaxDatabase::from_files("vat_rates.json", "trade_agreements.json")
+            .expect("Tax database should load")
+    }
+
+    fn scenario(certificate: ExemptionCertificate) -> TaxScenario {
+        TaxScenario::new(
+            Region::parse("US-TX").expect("Valid region"),
+            Region::parse("US-WA").expect("Valid region"),
+            TransactionType::B2B,
+        )
+        .with_exemption_certificate(certificate)
+    }
+
+    #[test]
+    fn test_certificate_for_destination_is_honored() {
+        let db = setup();
+        let certificate = ExemptionCertificate::new("US-WA", "RS-1", CertificateKind::Resale)
+            .with_expiry("9999-12-31")
+            .expect("Valid date");
+        let scenario = scenario(certificate);
+        assert!(scenario.has_valid_certificate());
+        let explanation = scenario.explain(1_000_000.0, &db).unwrap();
+        assert_eq!(explanation.calculation_type, TaxCalculationType::ZeroRated);
+        assert_eq!(explanation.tax, 0.0);
+
+        // Country-wide certificates cover all states
+        let multistate = ExemptionCertificate::new("US", "MTC-1", CertificateKind::Resale);
+        assert!(multistate.covers(&Region::parse("US-WA").unwrap()));
+    }
+
+    #[test]
+    fn test_certificate_of_other_state_is_rejected() {
+        let db = setup();
+        let scenario = scenario(ExemptionCertificate::new(
+            "US-OR",
+            "RS-1",
+            CertificateKind::Resale,
+        ));
+        assert!(!scenario.has_valid_certificate());
+
+        let explanation = scenario.explain(1_000_000.0, &db).unwrap();
+        let step = explanation
+            .steps
+            .iter()
+            .find(|step| step.check == "exemption_certificate")
+            .expect("Rejection is explained");
+        // Without a valid certificate, the agreement's B2B rule applies
+        assert_eq!(explanation.calculation_type, TaxCalculationType::Exempt);
+        assert_eq!(step.outcome, "RS-1 is issued for US-OR, not US-WA");
+    }
+
+    #[test]
+    fn test_expired_certificate_is_rejected() {
+        let db = setup();
+        let certificate = ExemptionCertificate::new("US-WA", "RS-1", CertificateKind::Resale)
+            .with_expiry("2020-01-31")
+            .expect("Valid date");
+        assert!(certificate.is_expired_on("2020-02-01"));
+        assert!(!certificate.is_expired_on("2020-01-31"));
+
+        let scenario = scenario(certificate);
+        assert!(!scenario.has_valid_certificate());
+        let explanation = scenario.explain(1_000_000.0, &db).unwrap();
+        assert_eq!(explanation.calculation_type, TaxCalculationType::Exempt);
+        assert!(explanation
+            .steps
+            .iter()
+            .any(|step| step.outcome == "RS-1 expired on 2020-01-31"));
+
+        // Sales dated before the expiry are still covered
+        let sold = scenario.clone().with_date("2020-01-31").unwrap();
+        assert!(sold.has_valid_certificate());
+        let explanation = sold.explain(1_000_000.0, &db).unwrap();
+        assert_eq!(explanation.calculation_type, TaxCalculationType::ZeroRated);
+        assert_eq!(explanation.tax, 0.0);
+        let sold = scenario.with_date("2020-02-01").unwrap();
+        assert!(!sold.has_valid_certificate());
+    }
+
+    #[test]
+    fn test_expiry_dates() {
+        let certificate = ExemptionCertificate::new("US-WA", "RS-1", CertificateKind::Resale);
+        for date in ["2030-13-01", "2030-1-01", "31.12.2030", ""] {
+            assert!(matches!(
+                certificate.clone().with_expiry(date),
+                Err(InputValidationError::InvalidDate(_))
+            ));
+        }
+
+        let today = today();
+        assert_eq!(today.len(), 10);
+        assert!(today.as_str() > "2025-01-01");
+    }
+
+    #[test]
+    fn test_buyer_types() {
+        let db = setup();
+        let texas = Region::parse("US-TX").expect("Valid region");
+        let washington = Region::parse("US-WA").expect("Valid region");
+
+        let individual = TaxScenario::for_buyer(
+            texas.clone(),
+            washington.clone(),
+            BuyerType::Individual,
+            None,
+        )
+        .expect("Valid buyer");
+        assert_eq!(individual.transaction_type, TransactionType::B2C);
+
+        let exemption = ExemptionCertificate::new("US-WA", "EX-1", CertificateKind::Exemption);
+        let government = TaxScenario::for_buyer(
+            texas.clone(),
+            washington.clone(),
+            BuyerType::Government,
+            Some(exemption.clone()),
+        )
+        .expect("Valid buyer");
+        assert_eq!(government.transaction_type, TransactionType::B2B);
+        assert_eq!(
+            government.explain(100.0, &db).unwrap().calculation_type,
+            TaxCalculationType::ZeroRated
+        );
+
+        for buyer in [
+            BuyerType::Individual,
+            BuyerType::Business,
+            BuyerType::Reseller,
+        ] {
+            assert!(matches!(
+                TaxScenario::for_buyer(
+                    texas.clone(),
+                    washington.clone(),
+                    buyer,
+                    Some(exemption.clone())
+                ),
+                Err(InputValidationError::CertificateMismatch(_))
+            ));
+        }
+    }
+}
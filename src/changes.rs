@@ -0,0 +1,243 @@
+//! Rate changes between dataset versions.
+//!
+//! `RateChangelog::between` compares the rates of two datasets (e.g. the data of the
+//! running release and of an update) by jurisdiction, so merchants selling into an
+//! affected country or state can be notified before the change takes effect. The
+//! changelog serializes to JSON and displays one line per change.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use serde::Serialize;
+use typeshare::typeshare;
+
+use crate::certificate::is_iso_date;
+use crate::errors::InputValidationError;
+use crate::types::{Country, State};
+use crate::{Percentage, TaxDatabase};
+
+/// Which rate of a jurisdiction changed.
+#[typeshare]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RateKind {
+    /// Standard rate
+    Standard,
+    /// Reduced rate
+    Reduced,
+    /// Alternative reduced rate
+    ReducedAlt,
+    /// Super-reduced rate
+    SuperReduced,
+    /// Parking rate
+    Parking,
+}
+
+impl fmt::Display for RateKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            RateKind::Standard => "standard",
+            RateKind::Reduced => "reduced",
+            RateKind::ReducedAlt => "alternative reduced",
+            RateKind::SuperReduced => "super-reduced",
+            RateKind::Parking => "parking",
+        })
+    }
+}
+
+/// How a rate changed.
+#[typeshare]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChangeKind {
+    /// The rate didn't exist before
+    Introduced,
+    /// The rate was raised
+    Increased,
+    /// The rate was lowered
+    Decreased,
+    /// The rate no longer exists
+    Removed,
+}
+
+/// A changed rate of a jurisdiction.
+#[typeshare]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct RateChange {
+    /// The country (e.g. "DE") or state (e.g. "US-CA") of the rate
+    pub jurisdiction: String,
+    /// Which rate changed
+    pub rate: RateKind,
+    /// How the rate changed
+    pub change: ChangeKind,
+    /// The rate before the change
+    pub old: Option<Percentage>,
+    /// The rate after the change
+    pub new: Option<Percentage>,
+    /// Date the change takes effect (ISO 8601), if known
+    pub effective: Option<String>,
+}
+
+impl fmt::Display for RateChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rate = |rate: Option<Percentage>| rate.map_or("none".to_string(), |r| r.to_string());
+        write!(
+            f,
+            "{} {} rate: {} -> {}",
+            self.jurisdiction,
+            self.rate,
+            rate(self.old),
+            rate(self.new)
+        )?;
+        if let Some(effective) = &self.effective {
+            write!(f, " (effective {})", effective)?;
+        }
+        Ok(())
+    }
+}
+
+/// The rate changes between two datasets.
+///
+/// # Examples
+///
+/// ```
+/// # use world_tax::changes::{ChangeKind, RateChangelog, RateKind};
+/// # use world_tax::TaxDatabase;
+/// let current = TaxDatabase::fixture();
+/// let update = TaxDatabase::from_json(
+///     r#"{ "DE": { "type": "vat", "currency": "EUR", "standard_rate": 0.2, "reduced_rate": 0.07 } }"#,
+///     "{}",
+/// )
+/// .unwrap();
+///
+/// let changelog = RateChangelog::between(&current, &update)
+///     .effective_from("2026-11-01")
+///     .unwrap();
+/// let germany: Vec<_> = changelog.for_country("DE").collect();
+/// assert_eq!(germany.len(), 1);
+/// assert_eq!(germany[0].rate, RateKind::Standard);
+/// assert_eq!(germany[0].change, ChangeKind::Increased);
+/// assert_eq!(germany[0].to_string(), "DE standard rate: 19% -> 20% (effective 2026-11-01)");
+/// ```
+#[typeshare]
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct RateChangelog {
+    /// The changes, sorted by jurisdiction and rate
+    pub changes: Vec<RateChange>,
+}
+
+impl RateChangelog {
+    /// Compares the rates of an older and a newer dataset.
+    ///
+    /// Jurisdictions only in one of the datasets have all their rates introduced or removed.
+    pub fn between(old: &TaxDatabase, new: &TaxDatabase) -> Self {
+        let old = jurisdiction_rates(old);
+        let new = jurisdiction_rates(new);
+
+        let mut jurisdictions: Vec<&String> = old.keys().chain(new.keys()).collect();
+        jurisdictions.sort();
+        jurisdictions.dedup();
+
+        let mut changes = Vec::new();
+        for jurisdiction in jurisdictions {
+            let old_rates = old.get(jurisdiction);
+            let new_rates = new.get(jurisdiction);
+            for (index, rate) in RATE_KINDS.into_iter().enumerate() {
+                let old_rate = old_rates.and_then(|rates| rates[index]);
+                let new_rate = new_rates.and_then(|rates| rates[index]);
+                let change = match (old_rate, new_rate) {
+                    (None, Some(_)) => ChangeKind::Introduced,
+                    (Some(_), None) => ChangeKind::Removed,
+                    (Some(old), Some(new)) if new > old => ChangeKind::Increased,
+                    (Some(old), Some(new)) if new < old => ChangeKind::Decreased,
+                    _ => continue,
+                };
+                changes.push(RateChange {
+                    jurisdiction: jurisdiction.clone(),
+                    rate,
+                    change,
+                    old: old_rate,
+                    new: new_rate,
+                    effective: None,
+                });
+            }
+        }
+        Self { changes }
+    }
+
+    /// Sets the date all changes take effect.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InputValidationError::InvalidDate` if the date isn't an ISO 8601 date (YYYY-MM-DD).
+    pub fn effective_from(mut self, date: &str) -> Result<Self, InputValidationError> {
+        if !is_iso_date(date) {
+            return Err(InputValidationError::InvalidDate(date.to_string()));
+        }
+        for change in &mut self.changes {
+            change.effective = Some(date.to_string());
+        }
+        Ok(self)
+    }
+
+    /// The changes of a country and its states.
+    pub fn for_country<'a>(&'a self, country: &'a str) -> impl Iterator<Item = &'a RateChange> {
+        self.changes.iter().filter(move |change| {
+            change.jurisdiction == country
+                || change
+                    .jurisdiction
+                    .strip_prefix(country)
+                    .is_some_and(|rest| rest.starts_with('-'))
+        })
+    }
+
+    /// Returns true if no rate changed.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+impl fmt::Display for RateChangelog {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for change in &self.changes {
+            writeln!(f, "{}", change)?;
+        }
+        Ok(())
+    }
+}
+
+const RATE_KINDS: [RateKind; 5] = [
+    RateKind::Standard,
+    RateKind::Reduced,
+    RateKind::ReducedAlt,
+    RateKind::SuperReduced,
+    RateKind::Parking,
+];
+
+type Rates = [Option<Percentage>; 5];
+
+fn country_rates(country: &Country) -> Rates {
+    [
+        Some(country.standard_rate),
+        country.reduced_rate,
+        country.reduced_rate_alt,
+        country.super_reduced_rate,
+        country.parking_rate,
+    ]
+}
+
+fn state_rates(state: &State) -> Rates {
+    [Some(state.standard_rate), None, None, None, None]
+}
+
+/// The rates of all countries and states of a dataset, by jurisdiction code.
+fn jurisdiction_rates(db: &TaxDatabase) -> HashMap<String, Rates> {
+    let mut rates = HashMap::new();
+    for (code, country) in &db.countries {
+        rates.insert(code.clone(), country_rates(country));
+        for (state_code, state) in country.states.iter().flatten() {
+            rates.insert(state_code.clone(), state_rates(state));
+        }
+    }
+    rates
+}
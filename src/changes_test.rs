@@ -0,0 +1,75 @@
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+
+    use crate::changes::{ChangeKind, RateChangelog, RateKind};
+    use crate::{InputValidationError, Percentage, TaxDatabase};
+
+    /// The fixture with a raised French reduced rate, a lowered BC PST and without Quebec.
+    fn update() -> TaxDatabase {
+        let mut countries: serde_json::Value =
+            serde_json::from_str(include_str!("../fixtures/vat_rates.json")).unwrap();
+        countries["FR"]["reduced_rate"] = serde_json::json!(0.11);
+        countries["FR"]["parking_rate"] = serde_json::json!(0.13);
+        countries["CA"]["states"]["CA-BC"]["standard_rate"] = serde_json::json!(0.06);
+        countries["CA"]["states"]
+            .as_object_mut()
+            .unwrap()
+            .remove("CA-QC");
+        TaxDatabase::from_json(
+            &countries.to_string(),
+            include_str!("../fixtures/trade_agreements.json"),
+        )
+        .expect("Updated data should load")
+    }
+
+    #[test]
+    fn test_rate_changes() {
+        let changelog = RateChangelog::between(&TaxDatabase::fixture(), &update());
+        let summary: Vec<(&str, RateKind, ChangeKind)> = changelog
+            .changes
+            .iter()
+            .map(|change| (change.jurisdiction.as_str(), change.rate, change.change))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("CA-BC", RateKind::Standard, ChangeKind::Decreased),
+                ("CA-QC", RateKind::Standard, ChangeKind::Removed),
+                ("FR", RateKind::Reduced, ChangeKind::Increased),
+                ("FR", RateKind::Parking, ChangeKind::Introduced),
+            ]
+        );
+        assert_eq!(
+            changelog.changes[2].new,
+            Some(Percentage::from_fraction(dec!(0.11)))
+        );
+        assert_eq!(changelog.for_country("CA").count(), 2);
+        assert_eq!(changelog.for_country("C").count(), 0);
+        assert_eq!(
+            changelog.to_string(),
+            "CA-BC standard rate: 7% -> 6%\n\
+             CA-QC standard rate: 9.975% -> none\n\
+             FR reduced rate: 10% -> 11%\n\
+             FR parking rate: none -> 13%\n"
+        );
+    }
+
+    #[test]
+    fn test_effective_date() {
+        let db = TaxDatabase::fixture();
+        assert!(RateChangelog::between(&db, &db).is_empty());
+
+        let changelog = RateChangelog::between(&db, &update());
+        assert!(matches!(
+            changelog.clone().effective_from("next month"),
+            Err(InputValidationError::InvalidDate(_))
+        ));
+
+        let changelog = changelog.effective_from("2026-11-01").unwrap();
+        let json = serde_json::to_value(&changelog).unwrap();
+        assert_eq!(json["changes"][0]["effective"], "2026-11-01");
+        assert_eq!(json["changes"][0]["change"], "decreased");
+        assert_eq!(json["changes"][0]["old"], 0.07);
+    }
+}
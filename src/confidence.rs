@@ -0,0 +1,69 @@
+//! Confidence of calculation results.
+//!
+//! A result is only as precise as the data it was resolved from. A US destination
+//! whose cities levy their own sales tax, but was resolved without a local rate, gets
+//! the state's rate only; a region without an entry gets the country's rate. The
+//! confidence tells UIs whether to show a result as the final tax, or as an estimate.
+
+use serde::Serialize;
+use typeshare::typeshare;
+
+use crate::Warning;
+
+/// How precisely a result reflects the tax due, from most to least precise.
+#[typeshare]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Confidence {
+    /// Resolved from data at the requested granularity
+    #[default]
+    Exact,
+    /// Local taxes may apply, but only the state or province rate was applied
+    EstimatedStateLevel,
+    /// The region has no entry; the country's rate was applied
+    EstimatedFallbackRate,
+    /// The tax due isn't known; no tax was collected, but the buyer owes use tax, or
+    /// the country isn't in the data
+    Unknown,
+}
+
+impl Confidence {
+    /// The confidence of a result with the given warnings: the least precise
+    /// confidence any of the warnings implies.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use world_tax::confidence::Confidence;
+    /// # use world_tax::Warning;
+    /// assert_eq!(Confidence::from_warnings(&[]), Confidence::Exact);
+    ///
+    /// let warnings = [Warning::LocalTaxesPossible {
+    ///     region: "US-AK".to_string(),
+    /// }];
+    /// assert_eq!(Confidence::from_warnings(&warnings), Confidence::EstimatedStateLevel);
+    /// ```
+    pub fn from_warnings(warnings: &[Warning]) -> Self {
+        warnings
+            .iter()
+            .map(|warning| match warning {
+                Warning::LocalTaxesPossible { .. } => Confidence::EstimatedStateLevel,
+                Warning::RegionDefaulted { .. } => Confidence::EstimatedFallbackRate,
+                Warning::UseTaxNotice { .. } | Warning::CountryDefaulted { .. } => {
+                    Confidence::Unknown
+                }
+                Warning::HomeRuleJurisdiction { .. }
+                | Warning::CurrencyMismatch { .. }
+                | Warning::NotAgreementMember { .. }
+                | Warning::StaleRates { .. }
+                | Warning::OverrideConflict { .. } => Confidence::Exact,
+            })
+            .max()
+            .unwrap_or_default()
+    }
+
+    /// Returns true if the result is the final tax.
+    pub fn is_exact(&self) -> bool {
+        *self == Confidence::Exact
+    }
+}
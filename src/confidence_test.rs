@@ -0,0 +1,73 @@
+#[cfg(test)]
+mod tests {
+    use crate::calculator::{TaxCalculator, TaxRequest};
+    use crate::confidence::Confidence;
+    use crate::profile::{CalculationProfile, NoNexusPolicy};
+    use crate::types::AnnualTurnover;
+    use crate::{Region, TaxDatabase, TaxScenario, ThresholdPolicy, TransactionType};
+
+    fn quote_confidence(db: &TaxDatabase, source: &str, destination: &str) -> Confidence {
+        let mut scenario = TaxScenario::new(
+            Region::parse(source).unwrap(),
+            Region::parse(destination).unwrap(),
+            TransactionType::B2C,
+        );
+        scenario.threshold_policy = ThresholdPolicy::AssumeAbove;
+        db.quote(TaxRequest::new(scenario, 100.0))
+            .expect("Quote should succeed")
+            .confidence
+    }
+
+    #[test]
+    fn test_confidence_by_data_granularity() {
+        let db = TaxDatabase::new().expect("Embedded data should load");
+
+        assert_eq!(quote_confidence(&db, "DE", "FR"), Confidence::Exact);
+        // Alaskan cities levy their own sales tax, and no local rates are loaded
+        assert_eq!(
+            quote_confidence(&db, "US-WA", "US-AK"),
+            Confidence::EstimatedStateLevel
+        );
+
+        let fixture = TaxDatabase::fixture();
+        // Alberta has no entry in the fixture; only the federal GST applies
+        assert_eq!(
+            quote_confidence(&fixture, "CA-ON", "CA-AB"),
+            Confidence::EstimatedFallbackRate
+        );
+        assert_eq!(
+            quote_confidence(&fixture, "CA-ON", "CA-BC"),
+            Confidence::Exact
+        );
+    }
+
+    #[test]
+    fn test_confidence_of_use_tax_notice() {
+        let mut db = TaxDatabase::new().expect("Embedded data should load");
+        db.register_profile(
+            "notice",
+            CalculationProfile {
+                no_nexus: NoNexusPolicy::UseTaxNotice,
+                ..Default::default()
+            },
+        );
+        let scenario = TaxScenario::new(
+            Region::parse("US-CA").unwrap(),
+            Region::parse("US-WA").unwrap(),
+            TransactionType::B2C,
+        )
+        // No earlier sales this year
+        .with_turnover(AnnualTurnover::default())
+        .with_profile("notice");
+
+        let explanation = scenario
+            .explain(100.0, &db)
+            .expect("Explanation should succeed");
+        assert_eq!(explanation.confidence, Confidence::Unknown);
+        assert!(!explanation.confidence.is_exact());
+        assert_eq!(
+            serde_json::to_value(explanation.confidence).unwrap(),
+            "unknown"
+        );
+    }
+}
@@ -0,0 +1,210 @@
+//! Conformance testing of `TaxCalculator` implementations.
+//!
+//! A conformance vector is a scenario with the outcome the built-in engine produces
+//! for it on the embedded dataset. Alternative backends (e.g. a SQLite store, or a
+//! remote service) can run the reference vectors to prove they match the built-in
+//! engine, and load further vectors of their own from JSON.
+//!
+//! Vector files have the form:
+//!
+//! ```json
+//! {
+//!     "vectors": [
+//!         {
+//!             "name": "eu_b2c_below_threshold",
+//!             "source": "DE",
+//!             "destination": "FR",
+//!             "transaction_type": "b2c",
+//!             "amount": 100.0,
+//!             "turnover": { "current_year": 0 },
+//!             "expected": { "calculation_type": "origin", "tax": 19.0 }
+//!         }
+//!     ]
+//! }
+//! ```
+//!
+//! Regions are ISO 3166-1 codes, or ISO 3166-2 codes for states and provinces. The
+//! optional fields `digital`, `vat_rate`, `threshold_policy` and `turnover` set the
+//! scenario's `is_digital_product_or_service`, `vat_rate`, `threshold_policy` and
+//! `turnover`.
+
+use serde::{Deserialize, Serialize};
+
+use crate::calculator::{TaxCalculator, TaxRequest};
+use crate::errors::DatasetError;
+use crate::types::AnnualTurnover;
+use crate::vat_id::VatId;
+use crate::{
+    InputValidationError, Region, TaxCalculationType, TaxScenario, ThresholdPolicy,
+    TransactionType, VatRate,
+};
+
+/// The reference vectors, as expected of the embedded dataset.
+pub const REFERENCE_VECTORS: &str = include_str!("../fixtures/conformance.json");
+
+/// Tax amounts within this distance of the expected amount match (half a cent).
+pub const TAX_TOLERANCE: f64 = 0.005;
+
+/// The outcome expected for a vector.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Expected {
+    /// The calculation type that applies
+    pub calculation_type: TaxCalculationType,
+    /// The tax amount
+    pub tax: f64,
+}
+
+/// A scenario with its expected outcome.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ConformanceVector {
+    /// Unique name of the vector
+    pub name: String,
+    /// Region of the seller (e.g. "DE", "US-CA")
+    pub source: String,
+    /// Region of the buyer
+    pub destination: String,
+    /// Type of transaction
+    pub transaction_type: TransactionType,
+    /// The net transaction amount
+    pub amount: f64,
+    /// Whether the product or service is digital
+    #[serde(default)]
+    pub digital: bool,
+    /// Specific VAT rate to apply
+    #[serde(default)]
+    pub vat_rate: Option<VatRate>,
+    /// How thresholds are applied
+    #[serde(default)]
+    pub threshold_policy: ThresholdPolicy,
+    /// The buyer's VAT number, checked by its format
+    #[serde(default)]
+    pub buyer_vat_id: Option<VatId>,
+    /// The seller's turnover towards annual thresholds
+    #[serde(default)]
+    pub turnover: Option<AnnualTurnover>,
+    /// The expected outcome
+    pub expected: Expected,
+}
+
+impl ConformanceVector {
+    /// Builds the tax request of the vector.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a region code is invalid.
+    pub fn request(&self) -> Result<TaxRequest, InputValidationError> {
+        let mut scenario = TaxScenario::new(
+            Region::parse(&self.source)?,
+            Region::parse(&self.destination)?,
+            self.transaction_type.clone(),
+        );
+        scenario.is_digital_product_or_service = self.digital;
+        scenario.vat_rate = self.vat_rate;
+        scenario.threshold_policy = self.threshold_policy;
+        scenario.buyer_vat_id = self.buyer_vat_id.as_ref().map(VatId::format_check);
+        scenario.turnover = self.turnover;
+        Ok(TaxRequest::new(scenario, self.amount))
+    }
+}
+
+#[derive(Deserialize)]
+struct VectorFile {
+    vectors: Vec<ConformanceVector>,
+}
+
+/// Loads conformance vectors from JSON.
+///
+/// # Errors
+///
+/// Returns `DatasetError::InvalidJson` if the JSON doesn't describe vectors.
+pub fn load_vectors(json: &str) -> Result<Vec<ConformanceVector>, DatasetError> {
+    serde_json::from_str::<VectorFile>(json)
+        .map(|file| file.vectors)
+        .map_err(|e| DatasetError::InvalidJson("conformance".to_string(), e.to_string()))
+}
+
+/// The reference vectors shipped with the crate.
+pub fn reference_vectors() -> Vec<ConformanceVector> {
+    load_vectors(REFERENCE_VECTORS).expect("Reference vectors are valid")
+}
+
+/// A vector whose outcome didn't match.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Mismatch {
+    /// Name of the vector
+    pub name: String,
+    /// The expected outcome
+    pub expected: Expected,
+    /// The outcome of the calculator, if it produced one
+    pub actual: Option<Expected>,
+    /// The error of the calculator, if it failed
+    pub error: Option<String>,
+}
+
+/// The result of running vectors against a calculator.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct ConformanceReport {
+    /// Number of vectors that matched
+    pub passed: usize,
+    /// Vectors that didn't match
+    pub mismatches: Vec<Mismatch>,
+}
+
+impl ConformanceReport {
+    /// Returns true if all vectors matched.
+    pub fn is_conformant(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+}
+
+/// Runs vectors against a calculator.
+///
+/// A vector matches if the quote has the expected calculation type, and a tax amount
+/// within `TAX_TOLERANCE` of the expected amount.
+///
+/// # Examples
+///
+/// ```
+/// # use world_tax::conformance::{reference_vectors, run};
+/// # use world_tax::TaxDatabase;
+/// let db = TaxDatabase::new().unwrap();
+///
+/// let report = run(&db, &reference_vectors());
+/// assert!(report.is_conformant());
+/// ```
+pub fn run<C: TaxCalculator + ?Sized>(
+    calculator: &C,
+    vectors: &[ConformanceVector],
+) -> ConformanceReport {
+    let mut report = ConformanceReport::default();
+    for vector in vectors {
+        let quote = vector
+            .request()
+            .map_err(|e| e.to_string())
+            .and_then(|request| calculator.quote(request).map_err(|e| e.to_string()));
+        let mut mismatch = Mismatch {
+            name: vector.name.clone(),
+            expected: vector.expected.clone(),
+            actual: None,
+            error: None,
+        };
+        match quote {
+            Ok(quote) => {
+                let actual = Expected {
+                    calculation_type: quote.calculation_type,
+                    tax: quote.tax,
+                };
+                if actual.calculation_type == vector.expected.calculation_type
+                    && (actual.tax - vector.expected.tax).abs() < TAX_TOLERANCE
+                {
+                    report.passed += 1;
+                    continue;
+                }
+                mismatch.actual = Some(actual);
+            }
+            Err(error) => mismatch.error = Some(error),
+        }
+        report.mismatches.push(mismatch);
+    }
+    report
+}
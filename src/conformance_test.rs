@@ -0,0 +1,72 @@
+#[cfg(test)]
+mod tests {
+    use crate::calculator::{TaxCalculator, TaxQuote, TaxRequest};
+    use crate::conformance::{load_vectors, reference_vectors, run};
+    use crate::{DatasetError, ProcessingError, TaxCalculationType, TaxDatabase};
+
+    /// A backend that quotes the built-in engine, but rounds tax down to whole units.
+    struct Truncating(TaxDatabase);
+
+    impl TaxCalculator for Truncating {
+        fn quote(&self, request: TaxRequest) -> Result<TaxQuote, ProcessingError> {
+            let mut quote = self.0.quote(request)?;
+            quote.tax = quote.tax.floor();
+            Ok(quote)
+        }
+    }
+
+    #[test]
+    fn test_built_in_engine_is_conformant() {
+        let db = TaxDatabase::new().expect("Embedded data should load");
+        let vectors = reference_vectors();
+        let report = run(&db, &vectors);
+        assert!(report.is_conformant(), "{:?}", report.mismatches);
+        assert_eq!(report.passed, vectors.len());
+    }
+
+    #[test]
+    fn test_mismatches_are_reported() {
+        let db = TaxDatabase::new().expect("Embedded data should load");
+        let report = run(&Truncating(db), &reference_vectors());
+        assert!(!report.is_conformant());
+        let names: Vec<&str> = report
+            .mismatches
+            .iter()
+            .map(|mismatch| mismatch.name.as_str())
+            .collect();
+        assert_eq!(
+            names,
+            vec![
+                "us_interstate_b2c_assume_above_threshold",
+                "ca_interprovincial_b2c_above_threshold"
+            ]
+        );
+        assert_eq!(report.mismatches[1].actual.as_ref().unwrap().tax, 7736.0);
+    }
+
+    #[test]
+    fn test_custom_vectors() {
+        let vectors = load_vectors(
+            r#"{ "vectors": [
+                { "name": "invalid_region", "source": "DE", "destination": "XX", "transaction_type": "b2c", "amount": 100.0,
+                  "expected": { "calculation_type": "origin", "tax": 19.0 } },
+                { "name": "digital", "source": "FR", "destination": "DE", "transaction_type": "b2c", "amount": 10.0, "digital": true,
+                  "expected": { "calculation_type": "destination", "tax": 1.9 } }
+            ] }"#,
+        )
+        .expect("Vectors should load");
+        let report = run(&TaxDatabase::fixture(), &vectors);
+        assert_eq!(report.passed, 1);
+        assert_eq!(report.mismatches[0].name, "invalid_region");
+        assert!(report.mismatches[0].error.is_some());
+        assert_eq!(
+            vectors[1].expected.calculation_type,
+            TaxCalculationType::Destination
+        );
+
+        assert!(matches!(
+            load_vectors(r#"{ "vectors": [{ "name": "incomplete" }] }"#),
+            Err(DatasetError::InvalidJson(document, _)) if document == "conformance"
+        ));
+    }
+}
@@ -0,0 +1,164 @@
+//! Coverage of the loaded dataset.
+//!
+//! `TaxDatabase::coverage` lists every ISO 3166-1 country with what the loaded data
+//! knows about it: rates, reduced rate categories, states, trade agreements and
+//! thresholds. The report serializes to JSON, so it can be shown as a coverage matrix
+//! or compared between releases to track gaps.
+
+use serde::Serialize;
+use typeshare::typeshare;
+
+use crate::types::TaxRuleConfig;
+use crate::TaxDatabase;
+
+/// What the dataset covers of a single country.
+#[typeshare]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CountryCoverage {
+    /// ISO 3166-1 alpha-2 code of the country
+    pub code: String,
+    /// Whether the country has rate data
+    pub rates: bool,
+    /// Number of reduced rate categories (reduced, reduced alt, super-reduced, parking)
+    pub reduced_rates: u32,
+    /// Number of states or provinces with rate data
+    pub states: u32,
+    /// Trade agreements the country, or one of its states, is a member of
+    pub agreements: Vec<String>,
+    /// Whether one of the agreements has thresholds for sales within it
+    pub thresholds: bool,
+}
+
+/// Number of countries with each kind of data.
+#[typeshare]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct CoverageSummary {
+    /// Number of ISO 3166-1 countries
+    pub countries: u32,
+    /// Countries with rate data
+    pub rates: u32,
+    /// Countries with reduced rate categories
+    pub reduced_rates: u32,
+    /// Countries with states or provinces
+    pub states: u32,
+    /// Countries in a trade agreement
+    pub agreements: u32,
+    /// Countries with thresholds
+    pub thresholds: u32,
+}
+
+/// Coverage of the loaded dataset, for all ISO 3166-1 countries.
+///
+/// # Examples
+///
+/// ```
+/// # use world_tax::TaxDatabase;
+/// let db = TaxDatabase::fixture();
+/// let coverage = db.coverage();
+///
+/// let germany = coverage.country("DE").unwrap();
+/// assert!(germany.rates);
+/// assert_eq!(germany.agreements, vec!["EU".to_string()]);
+///
+/// assert_eq!(coverage.summary.rates, 3);
+/// assert!(coverage.gaps().contains(&"US"));
+/// ```
+#[typeshare]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Coverage {
+    /// All countries, sorted by code
+    pub countries: Vec<CountryCoverage>,
+    /// Totals over all countries
+    pub summary: CoverageSummary,
+}
+
+impl Coverage {
+    /// The coverage of a country.
+    pub fn country(&self, code: &str) -> Option<&CountryCoverage> {
+        self.countries
+            .binary_search_by(|country| country.code.as_str().cmp(code))
+            .ok()
+            .map(|index| &self.countries[index])
+    }
+
+    /// Codes of the countries without rate data.
+    pub fn gaps(&self) -> Vec<&str> {
+        self.countries
+            .iter()
+            .filter(|country| !country.rates)
+            .map(|country| country.code.as_str())
+            .collect()
+    }
+}
+
+fn has_threshold(rule: &Option<TaxRuleConfig>) -> bool {
+    rule.as_ref()
+        .is_some_and(|rule| rule.threshold.is_some() || rule.threshold_digital_products.is_some())
+}
+
+impl TaxDatabase {
+    /// Reports which countries the loaded data covers.
+    pub fn coverage(&self) -> Coverage {
+        let mut codes: Vec<&str> = rust_iso3166::ALL_ALPHA2.to_vec();
+        codes.sort_unstable();
+
+        let mut agreement_ids: Vec<&String> = self.trade_agreements.keys().collect();
+        agreement_ids.sort();
+
+        let countries: Vec<CountryCoverage> = codes
+            .into_iter()
+            .map(|code| {
+                let country = self.countries.get(code);
+                let subdivision = format!("{}-", code);
+                let agreements: Vec<&String> = agreement_ids
+                    .iter()
+                    .copied()
+                    .filter(|id| {
+                        self.trade_agreements[*id]
+                            .members
+                            .iter()
+                            .any(|member| member == code || member.starts_with(&subdivision))
+                    })
+                    .collect();
+                let thresholds = agreements.iter().any(|id| {
+                    let rules = &self.trade_agreements[*id].tax_rules;
+                    has_threshold(&rules.internal_b2b) || has_threshold(&rules.internal_b2c)
+                });
+
+                CountryCoverage {
+                    code: code.to_string(),
+                    rates: country.is_some(),
+                    reduced_rates: country.map_or(0, |country| {
+                        [
+                            country.reduced_rate,
+                            country.reduced_rate_alt,
+                            country.super_reduced_rate,
+                            country.parking_rate,
+                        ]
+                        .iter()
+                        .filter(|rate| rate.is_some())
+                        .count() as u32
+                    }),
+                    states: country
+                        .and_then(|country| country.states.as_ref())
+                        .map_or(0, |states| states.len() as u32),
+                    agreements: agreements.into_iter().cloned().collect(),
+                    thresholds,
+                }
+            })
+            .collect();
+
+        let count = |covered: fn(&CountryCoverage) -> bool| {
+            countries.iter().filter(|country| covered(country)).count() as u32
+        };
+        let summary = CoverageSummary {
+            countries: countries.len() as u32,
+            rates: count(|country| country.rates),
+            reduced_rates: count(|country| country.reduced_rates > 0),
+            states: count(|country| country.states > 0),
+            agreements: count(|country| !country.agreements.is_empty()),
+            thresholds: count(|country| country.thresholds),
+        };
+        Coverage { countries, summary }
+    }
+}
@@ -0,0 +1,33 @@
+#[cfg(test)]
+mod tests {
+    use crate::TaxDatabase;
+
+    #[test]
+    fn test_coverage_of_embedded_data() {
+        let db = TaxDatabase::new().expect("Embedded data should load");
+        let coverage = db.coverage();
+        assert_eq!(coverage.summary.countries, coverage.countries.len() as u32);
+        assert_eq!(
+            coverage.summary.rates as usize,
+            coverage.countries.len() - coverage.gaps().len()
+        );
+
+        // Members by state or province
+        let us = coverage.country("US").expect("US is an ISO country");
+        assert!(us.rates);
+        assert_eq!(us.agreements, vec!["US".to_string()]);
+        assert!(us.states > 0);
+
+        let germany = coverage.country("DE").expect("DE is an ISO country");
+        assert!(germany.reduced_rates > 0);
+        assert!(germany.thresholds);
+        assert_eq!(germany.states, 0);
+
+        let thailand = coverage.country("TH").expect("TH is an ISO country");
+        assert!(thailand.agreements.is_empty());
+        assert!(!thailand.thresholds);
+
+        let json = serde_json::to_value(&coverage).unwrap();
+        assert_eq!(json["countries"][0]["code"], "AD");
+    }
+}
@@ -0,0 +1,321 @@
+//! ISO 4217 currency codes.
+//!
+//! `CurrencyCode` is a validated currency code with its minor units (the number of
+//! decimal places amounts are stated in). Currencies of the dataset are checked by
+//! `TaxDatabase::validate`, and a scenario's currency is compared against the
+//! destination's currency (see `Warning::CurrencyMismatch`). Amounts compared against
+//! thresholds in another currency are converted with `ExchangeRates`.
+
+use std::fmt;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::errors::InputValidationError;
+use crate::TaxDatabase;
+
+/// Active ISO 4217 currency codes and their minor units.
+///
+/// ANG is kept until the Netherlands Antillean guilder is fully replaced by XCG.
+pub const CURRENCIES: [(&str, u8); 156] = [
+    ("AED", 2),
+    ("AFN", 2),
+    ("ALL", 2),
+    ("AMD", 2),
+    ("ANG", 2),
+    ("AOA", 2),
+    ("ARS", 2),
+    ("AUD", 2),
+    ("AWG", 2),
+    ("AZN", 2),
+    ("BAM", 2),
+    ("BBD", 2),
+    ("BDT", 2),
+    ("BHD", 3),
+    ("BIF", 0),
+    ("BMD", 2),
+    ("BND", 2),
+    ("BOB", 2),
+    ("BRL", 2),
+    ("BSD", 2),
+    ("BTN", 2),
+    ("BWP", 2),
+    ("BYN", 2),
+    ("BZD", 2),
+    ("CAD", 2),
+    ("CDF", 2),
+    ("CHF", 2),
+    ("CLP", 0),
+    ("CNY", 2),
+    ("COP", 2),
+    ("CRC", 2),
+    ("CUP", 2),
+    ("CVE", 2),
+    ("CZK", 2),
+    ("DJF", 0),
+    ("DKK", 2),
+    ("DOP", 2),
+    ("DZD", 2),
+    ("EGP", 2),
+    ("ERN", 2),
+    ("ETB", 2),
+    ("EUR", 2),
+    ("FJD", 2),
+    ("FKP", 2),
+    ("GBP", 2),
+    ("GEL", 2),
+    ("GHS", 2),
+    ("GIP", 2),
+    ("GMD", 2),
+    ("GNF", 0),
+    ("GTQ", 2),
+    ("GYD", 2),
+    ("HKD", 2),
+    ("HNL", 2),
+    ("HTG", 2),
+    ("HUF", 2),
+    ("IDR", 2),
+    ("ILS", 2),
+    ("INR", 2),
+    ("IQD", 3),
+    ("IRR", 2),
+    ("ISK", 0),
+    ("JMD", 2),
+    ("JOD", 3),
+    ("JPY", 0),
+    ("KES", 2),
+    ("KGS", 2),
+    ("KHR", 2),
+    ("KMF", 0),
+    ("KPW", 2),
+    ("KRW", 0),
+    ("KWD", 3),
+    ("KYD", 2),
+    ("KZT", 2),
+    ("LAK", 2),
+    ("LBP", 2),
+    ("LKR", 2),
+    ("LRD", 2),
+    ("LSL", 2),
+    ("LYD", 3),
+    ("MAD", 2),
+    ("MDL", 2),
+    ("MGA", 2),
+    ("MKD", 2),
+    ("MMK", 2),
+    ("MNT", 2),
+    ("MOP", 2),
+    ("MRU", 2),
+    ("MUR", 2),
+    ("MVR", 2),
+    ("MWK", 2),
+    ("MXN", 2),
+    ("MYR", 2),
+    ("MZN", 2),
+    ("NAD", 2),
+    ("NGN", 2),
+    ("NIO", 2),
+    ("NOK", 2),
+    ("NPR", 2),
+    ("NZD", 2),
+    ("OMR", 3),
+    ("PAB", 2),
+    ("PEN", 2),
+    ("PGK", 2),
+    ("PHP", 2),
+    ("PKR", 2),
+    ("PLN", 2),
+    ("PYG", 0),
+    ("QAR", 2),
+    ("RON", 2),
+    ("RSD", 2),
+    ("RUB", 2),
+    ("RWF", 0),
+    ("SAR", 2),
+    ("SBD", 2),
+    ("SCR", 2),
+    ("SDG", 2),
+    ("SEK", 2),
+    ("SGD", 2),
+    ("SHP", 2),
+    ("SLE", 2),
+    ("SOS", 2),
+    ("SRD", 2),
+    ("SSP", 2),
+    ("STN", 2),
+    ("SVC", 2),
+    ("SYP", 2),
+    ("SZL", 2),
+    ("THB", 2),
+    ("TJS", 2),
+    ("TMT", 2),
+    ("TND", 3),
+    ("TOP", 2),
+    ("TRY", 2),
+    ("TTD", 2),
+    ("TWD", 2),
+    ("TZS", 2),
+    ("UAH", 2),
+    ("UGX", 0),
+    ("USD", 2),
+    ("UYU", 2),
+    ("UZS", 2),
+    ("VED", 2),
+    ("VES", 2),
+    ("VND", 0),
+    ("VUV", 0),
+    ("WST", 2),
+    ("XAF", 0),
+    ("XCD", 2),
+    ("XCG", 2),
+    ("XOF", 0),
+    ("XPF", 0),
+    ("YER", 2),
+    ("ZAR", 2),
+    ("ZMW", 2),
+    ("ZWG", 2),
+];
+
+/// A validated ISO 4217 currency code.
+///
+/// # Examples
+///
+/// ```
+/// # use world_tax::currency::CurrencyCode;
+/// let yen = CurrencyCode::parse("JPY").unwrap();
+/// assert_eq!(yen.minor_units(), 0);
+/// assert!(CurrencyCode::parse("XYZ").is_err());
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CurrencyCode {
+    code: &'static str,
+    minor_units: u8,
+}
+
+/// One of the active currencies.
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for CurrencyCode {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let &(code, minor_units) = u.choose(&CURRENCIES)?;
+        Ok(Self { code, minor_units })
+    }
+}
+
+impl CurrencyCode {
+    /// Parses an ISO 4217 currency code (e.g. "EUR").
+    ///
+    /// # Errors
+    ///
+    /// Returns `InputValidationError::InvalidCurrencyCode` if the code isn't an active ISO 4217 code.
+    pub fn parse(code: &str) -> Result<Self, InputValidationError> {
+        CURRENCIES
+            .iter()
+            .find(|(known, _)| *known == code)
+            .map(|&(code, minor_units)| Self { code, minor_units })
+            .ok_or_else(|| InputValidationError::InvalidCurrencyCode(code.to_string()))
+    }
+
+    /// The three-letter code (e.g. "EUR").
+    pub fn code(&self) -> &'static str {
+        self.code
+    }
+
+    /// Number of decimal places amounts are stated in (e.g. 2 for EUR, 0 for JPY).
+    pub fn minor_units(&self) -> u8 {
+        self.minor_units
+    }
+}
+
+impl fmt::Display for CurrencyCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.code)
+    }
+}
+
+impl Serialize for CurrencyCode {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.code)
+    }
+}
+
+impl<'de> Deserialize<'de> for CurrencyCode {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let code = String::deserialize(deserializer)?;
+        Self::parse(&code).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Converts amounts between currencies, for comparison against thresholds.
+///
+/// Implemented for closures, so rates can come from a table or a service:
+///
+/// ```
+/// # use world_tax::currency::{CurrencyCode, ExchangeRates};
+/// let rates = |from: CurrencyCode, to: CurrencyCode| match (from.code(), to.code()) {
+///     ("USD", "EUR") => Some(0.9),
+///     _ => None,
+/// };
+/// let usd = CurrencyCode::parse("USD").unwrap();
+/// let eur = CurrencyCode::parse("EUR").unwrap();
+/// assert_eq!(rates.rate(usd, eur), Some(0.9));
+/// ```
+pub trait ExchangeRates: Send + Sync {
+    /// The rate an amount in `from` is multiplied by to state it in `to`, if known.
+    fn rate(&self, from: CurrencyCode, to: CurrencyCode) -> Option<f64>;
+}
+
+impl<F> ExchangeRates for F
+where
+    F: Fn(CurrencyCode, CurrencyCode) -> Option<f64> + Send + Sync,
+{
+    fn rate(&self, from: CurrencyCode, to: CurrencyCode) -> Option<f64> {
+        self(from, to)
+    }
+}
+
+impl TaxDatabase {
+    /// Sets the exchange rates amounts are converted with, when a scenario's currency
+    /// isn't the currency of a threshold.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use world_tax::currency::CurrencyCode;
+    /// # use world_tax::types::AnnualTurnover;
+    /// # use world_tax::{Region, TaxCalculationType, TaxDatabase, TaxScenario, TransactionType};
+    /// let mut db = TaxDatabase::new().unwrap();
+    /// db.set_exchange_rates(|from: CurrencyCode, to: CurrencyCode| {
+    ///     (from.code() == "USD" && to.code() == "EUR").then_some(0.9)
+    /// });
+    ///
+    /// let mut scenario = TaxScenario::new(
+    ///     Region::parse("DE").unwrap(),
+    ///     Region::parse("FR").unwrap(),
+    ///     TransactionType::B2C,
+    /// );
+    /// scenario.currency = Some(CurrencyCode::parse("USD").unwrap());
+    /// scenario.turnover = Some(AnnualTurnover::default());
+    /// // $10,500 are €9,450, below the EU threshold of €10,000
+    /// assert_eq!(
+    ///     scenario.determine_calculation_type(&db, 10_500.0).unwrap(),
+    ///     TaxCalculationType::Origin
+    /// );
+    /// ```
+    pub fn set_exchange_rates(&mut self, rates: impl ExchangeRates + 'static) {
+        self.exchange_rates = Some(Box::new(rates));
+    }
+
+    /// The rate an amount in `from` is multiplied by to state it in `to`; 1 for the
+    /// same currency.
+    pub fn exchange_rate(&self, from: CurrencyCode, to: CurrencyCode) -> Option<f64> {
+        if from == to {
+            return Some(1.0);
+        }
+        self.exchange_rates.as_ref()?.rate(from, to)
+    }
+
+    /// The currency of a country, if the country is in the data and its currency is valid.
+    pub fn currency(&self, country: &str) -> Option<CurrencyCode> {
+        let country = self.country(country).ok()?;
+        CurrencyCode::parse(&country.currency).ok()
+    }
+}
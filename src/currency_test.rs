@@ -0,0 +1,137 @@
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::calculator::{TaxCalculator, TaxRequest};
+    use crate::currency::{CurrencyCode, CURRENCIES};
+    use crate::types::AnnualTurnover;
+    use crate::validation::Severity;
+    use crate::{
+        DatabaseError, InputValidationError, ProcessingError, Region, TaxCalculationType,
+        TaxDatabase, TaxScenario, ThresholdPolicy, TransactionType, Warning,
+    };
+
+    #[test]
+    fn test_parse_currency_code() {
+        let euro = CurrencyCode::parse("EUR").expect("Valid currency");
+        assert_eq!(euro.code(), "EUR");
+        assert_eq!(euro.minor_units(), 2);
+        assert_eq!(CurrencyCode::parse("KWD").unwrap().minor_units(), 3);
+        assert!(matches!(
+            CurrencyCode::parse("eur"),
+            Err(InputValidationError::InvalidCurrencyCode(_))
+        ));
+
+        let parsed: CurrencyCode = serde_json::from_value(json!("JPY")).expect("Valid currency");
+        assert_eq!(serde_json::to_value(parsed).unwrap(), json!("JPY"));
+        assert!(serde_json::from_value::<CurrencyCode>(json!("XXY")).is_err());
+    }
+
+    #[test]
+    fn test_dataset_currencies_are_valid() {
+        let db = TaxDatabase::new().expect("Embedded data should load");
+        assert!(db.validate().is_empty());
+        assert!(CURRENCIES.windows(2).all(|pair| pair[0].0 < pair[1].0));
+    }
+
+    #[test]
+    fn test_invalid_dataset_currency_is_flagged() {
+        let countries = json!({
+            "DE": { "type": "vat", "currency": "EURO", "standard_rate": 0.19 }
+        })
+        .to_string();
+        let db = TaxDatabase::from_json(&countries, "{}").expect("Data should load");
+
+        let issues = db.validate();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].severity, Severity::Error);
+        assert_eq!(issues[0].pointer, "/DE/currency");
+        assert!(db.currency("DE").is_none());
+    }
+
+    #[test]
+    fn test_scenario_currency_mismatch() {
+        let db = TaxDatabase::fixture();
+        let germany = Region::parse("DE").expect("Valid region");
+        let scenario = TaxScenario::new(germany.clone(), germany, TransactionType::B2C);
+
+        let usd = CurrencyCode::parse("USD").expect("Valid currency");
+        let quote = db
+            .quote(TaxRequest::new(scenario.clone().with_currency(usd), 100.0))
+            .expect("Quote should succeed");
+        assert_eq!(
+            quote.warnings,
+            vec![Warning::CurrencyMismatch {
+                currency: "USD".to_string(),
+                destination_currency: "EUR".to_string(),
+            }]
+        );
+
+        let eur = CurrencyCode::parse("EUR").expect("Valid currency");
+        let quote = db
+            .quote(TaxRequest::new(scenario.with_currency(eur), 100.0))
+            .expect("Quote should succeed");
+        assert!(quote.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_thresholds_in_other_currencies() {
+        let mut db = TaxDatabase::fixture();
+        let usd = CurrencyCode::parse("USD").expect("Valid currency");
+        let eur = CurrencyCode::parse("EUR").expect("Valid currency");
+        let scenario = TaxScenario::new(
+            Region::parse("DE").expect("Valid region"),
+            Region::parse("FR").expect("Valid region"),
+            TransactionType::B2C,
+        )
+        // No earlier sales this year
+        .with_turnover(AnnualTurnover::default());
+
+        // Without a currency, amounts are compared as given
+        assert_eq!(
+            scenario.determine_calculation_type(&db, 10_500.0).unwrap(),
+            TaxCalculationType::Destination
+        );
+        // A USD amount can't be compared against the EUR threshold without a rate
+        let in_usd = scenario.clone().with_currency(usd);
+        assert!(matches!(
+            in_usd.determine_calculation_type(&db, 10_500.0),
+            Err(ProcessingError::DatabaseError(
+                DatabaseError::ExchangeRateNotFound { .. }
+            ))
+        ));
+        // Unless the comparison doesn't matter
+        let mut registered = in_usd.clone();
+        registered.threshold_policy = ThresholdPolicy::AssumeAbove;
+        assert_eq!(
+            registered
+                .determine_calculation_type(&db, 10_500.0)
+                .unwrap(),
+            TaxCalculationType::Destination
+        );
+
+        db.set_exchange_rates(|from: CurrencyCode, to: CurrencyCode| {
+            (from.code() == "USD" && to.code() == "EUR").then_some(0.9)
+        });
+        assert_eq!(
+            in_usd.determine_calculation_type(&db, 10_500.0).unwrap(),
+            TaxCalculationType::Origin
+        );
+        assert_eq!(
+            in_usd.determine_calculation_type(&db, 11_200.0).unwrap(),
+            TaxCalculationType::Destination
+        );
+        let explanation = in_usd.explain(10_500.0, &db).unwrap();
+        assert!(explanation
+            .steps
+            .iter()
+            .any(|step| step.check == "exchange_rate" && step.outcome.contains("9450 EUR")));
+        assert_eq!(
+            scenario
+                .with_currency(eur)
+                .determine_calculation_type(&db, 10_500.0)
+                .unwrap(),
+            TaxCalculationType::Destination
+        );
+    }
+}
@@ -0,0 +1,343 @@
+//! Customs duty and landed cost.
+//!
+//! Duty rates depend on the customs territory (e.g. "EU", or a country code) and the
+//! product's HS chapter. This crate doesn't ship tariff data; callers load a duty
+//! table per territory. A landed cost quote combines duty, import VAT and the fee a
+//! carrier charges for advancing them (disbursement fee), and assigns the charges to
+//! the seller or the buyer by the incoterm.
+//!
+//! Where the destination has postponed accounting (UK PVA, the Dutch Article 23
+//! licence, French autoliquidation), a VAT-registered importer declares import VAT in
+//! its VAT return instead of paying it at the border; the quote then leaves it out of
+//! the cash charges, and reports it as `postponed_import_vat` for the return.
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+
+use crate::errors::DatabaseError;
+use crate::import::ImportScheme;
+use crate::types::{Incoterm, PostponedAccounting};
+use crate::{ProcessingError, TaxDatabase, TaxRate, TaxScenario, TransactionType};
+
+/// Duty rates of a customs territory by HS chapter.
+#[derive(Debug, Clone, Default)]
+pub struct DutyTable {
+    rates: HashMap<u8, f64>,
+    default_rate: f64,
+    duty_free_limit: Option<f64>,
+}
+
+impl DutyTable {
+    /// Parses a duty table from CSV lines of `chapter,rate`; a chapter of `*` sets the
+    /// rate of chapters without a line of their own.
+    ///
+    /// Empty lines and lines starting with `#` are skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use world_tax::customs::DutyTable;
+    /// let table = DutyTable::parse("61,0.12\n*,0.04").unwrap();
+    /// assert_eq!(table.rate(61), 0.12);
+    /// assert_eq!(table.rate(85), 0.04);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::InvalidDutyRecord` with the line number if a line can't be parsed.
+    pub fn parse(csv: &str) -> Result<Self, DatabaseError> {
+        let mut table = Self::default();
+        for (index, line) in csv.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let invalid = || DatabaseError::InvalidDutyRecord(index + 1);
+            let Some((chapter, rate)) = line.split_once(',') else {
+                return Err(invalid());
+            };
+            let rate: f64 = rate.trim().parse().map_err(|_| invalid())?;
+            if !(0.0..1.0).contains(&rate) {
+                return Err(invalid());
+            }
+            match chapter.trim() {
+                "*" => table.default_rate = rate,
+                chapter => {
+                    let chapter: u8 = chapter.parse().map_err(|_| invalid())?;
+                    if !(1..=97).contains(&chapter) {
+                        return Err(invalid());
+                    }
+                    table.rates.insert(chapter, rate);
+                }
+            }
+        }
+        Ok(table)
+    }
+
+    /// Sets the consignment value up to which no duty is due (e.g. 150 in the EU).
+    pub fn with_duty_free_limit(mut self, limit: f64) -> Self {
+        self.duty_free_limit = Some(limit);
+        self
+    }
+
+    /// The duty rate of an HS chapter.
+    pub fn rate(&self, chapter: u8) -> f64 {
+        self.rates
+            .get(&chapter)
+            .copied()
+            .unwrap_or(self.default_rate)
+    }
+
+    /// The consignment value up to which no duty is due, if any.
+    pub fn duty_free_limit(&self) -> Option<f64> {
+        self.duty_free_limit
+    }
+}
+
+/// The fee a carrier charges for advancing duty and import VAT.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct DisbursementFee {
+    /// Share of the advanced charges (e.g. 0.02 for 2%)
+    pub rate: f64,
+    /// Minimum fee
+    pub minimum: f64,
+}
+
+impl DisbursementFee {
+    /// The fee for advancing the given charges; no fee if nothing is advanced.
+    pub fn amount(&self, advanced: f64) -> f64 {
+        if advanced <= 0.0 {
+            return 0.0;
+        }
+        round((advanced * self.rate).max(self.minimum))
+    }
+}
+
+impl TaxDatabase {
+    /// Loads the duty table of a customs territory (e.g. "EU", "CH"), replacing any loaded before.
+    pub fn load_duty_rates(&mut self, territory: &str, table: DutyTable) {
+        self.duty_rates.insert(territory.to_string(), table);
+    }
+
+    /// The loaded duty table of a customs territory.
+    pub fn duty_rates(&self, territory: &str) -> Option<&DutyTable> {
+        self.duty_rates.get(territory)
+    }
+
+    /// Sets the disbursement fee carriers charge in a customs territory.
+    pub fn set_disbursement_fee(&mut self, territory: &str, fee: DisbursementFee) {
+        self.disbursement_fees.insert(territory.to_string(), fee);
+    }
+
+    /// The customs territory of a country: its customs union (e.g. "EU"), or the
+    /// country itself.
+    pub fn customs_territory(&self, country: &str) -> String {
+        self.trade_agreements
+            .iter()
+            .find(|(_, agreement)| {
+                agreement.is_international() && agreement.members.iter().any(|m| m == country)
+            })
+            .map(|(id, _)| id.clone())
+            .unwrap_or_else(|| country.to_string())
+    }
+}
+
+/// An item of an imported consignment.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct CustomsItem {
+    /// Customs value of the item
+    pub value: f64,
+    /// HS chapter of the item (the first two digits of its HS code)
+    pub hs_chapter: u8,
+}
+
+impl CustomsItem {
+    /// Creates a new customs item.
+    pub fn new(value: f64, hs_chapter: u8) -> Self {
+        Self { value, hs_chapter }
+    }
+}
+
+/// The cost of importing a consignment, and who pays it.
+#[derive(Debug, Clone, Serialize)]
+pub struct LandedCost {
+    /// The incoterm the charges are assigned by
+    pub incoterm: Incoterm,
+    /// Customs value of the goods
+    pub goods: f64,
+    /// Customs duty
+    pub duty: f64,
+    /// How import VAT is handled, for imports into the EU
+    pub scheme: Option<ImportScheme>,
+    /// The applied import tax rates
+    pub rates: Vec<TaxRate>,
+    /// Import VAT (or sales tax) on the goods and duty
+    pub import_vat: f64,
+    /// The scheme the importer accounts for import VAT under in its VAT return, if
+    /// any
+    pub postponed_accounting: Option<PostponedAccounting>,
+    /// Import VAT declared (and deducted) in the importer's VAT return instead of paid
+    /// in cash; not part of `seller_pays` or `buyer_pays_on_delivery`
+    pub postponed_import_vat: f64,
+    /// The carrier's fee for advancing duty and import VAT
+    pub disbursement_fee: f64,
+    /// Import charges the seller pays (or collects at checkout, under IOSS)
+    pub seller_pays: f64,
+    /// Import charges the buyer pays on delivery
+    pub buyer_pays_on_delivery: f64,
+    /// Goods plus all import charges
+    pub total: f64,
+}
+
+/// Quotes the landed cost of a cross-border consignment of physical goods.
+///
+/// Duty uses the duty table of the destination's customs territory (no duty if none
+/// is loaded, or the consignment is below the table's duty-free limit). Import VAT
+/// is charged on the goods plus duty. With `Incoterm::Ddp` the seller pays all import
+/// charges; otherwise the buyer pays them on delivery, except IOSS VAT, which the
+/// seller collects at checkout. Returns `None` if the scenario isn't an import.
+///
+/// If the destination has postponed accounting and the importer is a business (the
+/// buyer of a B2B sale, or the seller under `Incoterm::Ddp`), import VAT goes in the
+/// importer's VAT return instead: it's assumed the importer is registered there and,
+/// where the scheme requires it, authorised. Consignments under IOSS or the EU special
+/// arrangements are paid as before.
+///
+/// # Examples
+///
+/// ```
+/// # use world_tax::customs::{landed_cost, CustomsItem, DutyTable};
+/// # use world_tax::types::Incoterm;
+/// # use world_tax::{Region, TaxDatabase, TaxScenario, TransactionType};
+/// let mut db = TaxDatabase::fixture();
+/// db.load_duty_rates("EU", DutyTable::parse("61,0.12").unwrap().with_duty_free_limit(150.0));
+/// let scenario = TaxScenario::new(
+///     Region::new("CA".to_string(), None).unwrap(),
+///     Region::new("DE".to_string(), None).unwrap(),
+///     TransactionType::B2C,
+/// );
+///
+/// let cost = landed_cost(&scenario, &[CustomsItem::new(200.0, 61)], Incoterm::Dap, &db)
+///     .unwrap()
+///     .unwrap();
+/// assert_eq!(cost.duty, 24.0);
+/// assert_eq!(cost.import_vat, 42.56); // 19% of 224
+/// assert_eq!(cost.buyer_pays_on_delivery, 66.56);
+/// ```
+///
+/// # Errors
+///
+/// Returns an error if the destination's rates can't be resolved.
+pub fn landed_cost(
+    scenario: &TaxScenario,
+    items: &[CustomsItem],
+    incoterm: Incoterm,
+    db: &TaxDatabase,
+) -> Result<Option<LandedCost>, ProcessingError> {
+    let destination = &scenario.destination_region;
+    let territory = db.customs_territory(&destination.country);
+    if scenario.is_digital_product_or_service
+        || territory == db.customs_territory(&scenario.source_region.country)
+    {
+        return Ok(None);
+    }
+
+    let goods = round(items.iter().map(|item| item.value).sum());
+    let duty = match db.duty_rates(&territory) {
+        Some(table) if table.duty_free_limit().is_none_or(|limit| goods > limit) => round(
+            items
+                .iter()
+                .map(|item| item.value * table.rate(item.hs_chapter))
+                .sum(),
+        ),
+        _ => 0.0,
+    };
+
+    // The import scheme depends on the value of the goods; VAT is charged on the
+    // goods plus duty
+    let (scheme, rates, import_vat) = match scenario.import_vat(goods, db)? {
+        Some(import) => {
+            let import_vat = scenario.sum_rates(goods + duty, &import.rates, db)?;
+            (Some(import.scheme), import.rates, import_vat)
+        }
+        None => {
+            let rates = db.get_rate(
+                &destination.country,
+                destination.region.as_deref(),
+                scenario.vat_rate.as_ref(),
+            )?;
+            let import_vat = scenario.sum_rates(goods + duty, &rates, db)?;
+            (None, rates, import_vat)
+        }
+    };
+
+    let ioss = scheme == Some(ImportScheme::Ioss);
+    let postponed_accounting = postponed_accounting(scenario, scheme, incoterm, db);
+    let postponed_import_vat = if postponed_accounting.is_some() {
+        import_vat
+    } else {
+        0.0
+    };
+    let advanced = if ioss {
+        duty
+    } else {
+        duty + import_vat - postponed_import_vat
+    };
+    let disbursement_fee = db
+        .disbursement_fees
+        .get(&territory)
+        .map_or(0.0, |fee| fee.amount(advanced));
+
+    let charges = round(duty + import_vat + disbursement_fee);
+    let payable = round(charges - postponed_import_vat);
+    let (seller_pays, buyer_pays_on_delivery) = if incoterm.seller_pays_import_charges() {
+        (payable, 0.0)
+    } else if ioss {
+        (import_vat, round(charges - import_vat))
+    } else {
+        (0.0, payable)
+    };
+
+    Ok(Some(LandedCost {
+        incoterm,
+        goods,
+        duty,
+        scheme,
+        rates,
+        import_vat,
+        postponed_accounting,
+        postponed_import_vat,
+        disbursement_fee,
+        seller_pays,
+        buyer_pays_on_delivery,
+        total: round(goods + charges),
+    }))
+}
+
+/// The destination's postponed accounting scheme, if the importer can use it.
+fn postponed_accounting(
+    scenario: &TaxScenario,
+    scheme: Option<ImportScheme>,
+    incoterm: Incoterm,
+    db: &TaxDatabase,
+) -> Option<PostponedAccounting> {
+    let business_importer =
+        incoterm.seller_pays_import_charges() || scenario.transaction_type == TransactionType::B2B;
+    if !business_importer
+        || matches!(
+            scheme,
+            Some(ImportScheme::Ioss | ImportScheme::SpecialArrangements)
+        )
+    {
+        return None;
+    }
+    db.get_country(&scenario.destination_region.country)
+        .ok()?
+        .postponed_accounting()
+        .cloned()
+}
+
+fn round(amount: f64) -> f64 {
+    (amount * 100.0).round() / 100.0
+}
@@ -0,0 +1,205 @@
+#[cfg(test)]
+mod tests {
+    use crate::customs::{landed_cost, CustomsItem, DisbursementFee, DutyTable};
+    use crate::import::ImportScheme;
+    use crate::profile::{CalculationProfile, SchemeFlags};
+    use crate::types::Incoterm;
+    use crate::{DatabaseError, Region, TaxDatabase, TaxScenario, TransactionType};
+
+    fn setup() -> TaxDatabase {
+        let mut db = TaxDatabase::fixture();
+        db.load_duty_rates(
+            "EU",
+            DutyTable::parse("# Apparel\n61,0.12\n*,0.04")
+                .expect("Valid duty table")
+                .with_duty_free_limit(150.0),
+        );
+        db.set_disbursement_fee(
+            "EU",
+            DisbursementFee {
+                rate: 0.02,
+                minimum: 10.0,
+            },
+        );
+        db
+    }
+
+    fn import_to_germany() -> TaxScenario {
+        TaxScenario::new(
+            Region::parse("CA-BC").expect("Valid region"),
+            Region::parse("DE").expect("Valid region"),
+            TransactionType::B2C,
+        )
+    }
+
+    #[test]
+    fn test_duty_table() {
+        let table = DutyTable::parse("61,0.12\n*,0.04").expect("Valid duty table");
+        assert_eq!(table.rate(61), 0.12);
+        assert_eq!(table.rate(85), 0.04);
+        assert!(matches!(
+            DutyTable::parse("61,0.12\n98,0.1"),
+            Err(DatabaseError::InvalidDutyRecord(2))
+        ));
+        assert!(matches!(
+            DutyTable::parse("61"),
+            Err(DatabaseError::InvalidDutyRecord(1))
+        ));
+    }
+
+    #[test]
+    fn test_landed_cost_dap_and_ddp() {
+        let db = setup();
+        let items = [CustomsItem::new(150.0, 61), CustomsItem::new(50.0, 85)];
+
+        let cost = landed_cost(&import_to_germany(), &items, Incoterm::Dap, &db)
+            .expect("Landed cost should be calculated")
+            .expect("Scenario is an import");
+        assert_eq!(cost.scheme, Some(ImportScheme::StandardImport));
+        assert_eq!(cost.duty, 20.0); // 12% of 150, 4% of 50
+        assert_eq!(cost.import_vat, 41.8); // 19% of 220
+        assert_eq!(cost.disbursement_fee, 10.0); // Minimum fee
+        assert_eq!(cost.seller_pays, 0.0);
+        assert_eq!(cost.buyer_pays_on_delivery, 71.8);
+        assert_eq!(cost.total, 271.8);
+
+        let cost = landed_cost(&import_to_germany(), &items, Incoterm::Ddp, &db)
+            .expect("Landed cost should be calculated")
+            .expect("Scenario is an import");
+        assert_eq!(cost.seller_pays, 71.8);
+        assert_eq!(cost.buyer_pays_on_delivery, 0.0);
+    }
+
+    #[test]
+    fn test_landed_cost_low_value() {
+        let mut db = setup();
+        let items = [CustomsItem::new(100.0, 61)];
+
+        let cost = landed_cost(&import_to_germany(), &items, Incoterm::Dap, &db)
+            .expect("Landed cost should be calculated")
+            .expect("Scenario is an import");
+        assert_eq!(cost.scheme, Some(ImportScheme::SpecialArrangements));
+        assert_eq!(cost.duty, 0.0);
+        assert_eq!(cost.buyer_pays_on_delivery, 29.0); // VAT plus minimum fee
+
+        db.register_profile(
+            "ioss",
+            CalculationProfile {
+                schemes: SchemeFlags {
+                    ioss: true,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
+        let scenario = import_to_germany().with_profile("ioss");
+        let cost = landed_cost(&scenario, &items, Incoterm::Dap, &db)
+            .expect("Landed cost should be calculated")
+            .expect("Scenario is an import");
+        assert_eq!(cost.scheme, Some(ImportScheme::Ioss));
+        assert_eq!(cost.disbursement_fee, 0.0);
+        assert_eq!(cost.seller_pays, 19.0);
+        assert_eq!(cost.buyer_pays_on_delivery, 0.0);
+    }
+
+    #[test]
+    fn test_landed_cost_postponed_accounting() {
+        let mut db = TaxDatabase::new().expect("Database should load");
+        db.set_disbursement_fee(
+            "GB",
+            DisbursementFee {
+                rate: 0.02,
+                minimum: 10.0,
+            },
+        );
+        let import = |destination: &str, transaction_type| {
+            TaxScenario::new(
+                Region::parse("US-CA").expect("Valid region"),
+                Region::parse(destination).expect("Valid region"),
+                transaction_type,
+            )
+        };
+        let items = [CustomsItem::new(1000.0, 84)];
+
+        // A VAT-registered buyer declares the import VAT in its return, and pays nothing
+        // at the border
+        let cost = landed_cost(
+            &import("GB", TransactionType::B2B),
+            &items,
+            Incoterm::Dap,
+            &db,
+        )
+        .expect("Landed cost should be calculated")
+        .expect("Scenario is an import");
+        let scheme = cost
+            .postponed_accounting
+            .expect("UK has postponed accounting");
+        assert_eq!(scheme.name, "Postponed VAT accounting");
+        assert!(!scheme.authorisation_required);
+        assert_eq!(cost.import_vat, 200.0);
+        assert_eq!(cost.postponed_import_vat, 200.0);
+        assert_eq!(cost.disbursement_fee, 0.0);
+        assert_eq!(cost.buyer_pays_on_delivery, 0.0);
+        assert_eq!(cost.total, 1200.0);
+
+        // Consumers pay import VAT on delivery
+        let cost = landed_cost(
+            &import("GB", TransactionType::B2C),
+            &items,
+            Incoterm::Dap,
+            &db,
+        )
+        .expect("Landed cost should be calculated")
+        .expect("Scenario is an import");
+        assert!(cost.postponed_accounting.is_none());
+        assert_eq!(cost.postponed_import_vat, 0.0);
+        assert_eq!(cost.buyer_pays_on_delivery, 210.0); // VAT plus minimum fee
+
+        // Under DDP the seller imports, and accounts for the VAT itself
+        let cost = landed_cost(
+            &import("NL", TransactionType::B2C),
+            &items,
+            Incoterm::Ddp,
+            &db,
+        )
+        .expect("Landed cost should be calculated")
+        .expect("Scenario is an import");
+        assert!(
+            cost.postponed_accounting
+                .expect("Netherlands has postponed accounting")
+                .authorisation_required
+        );
+        assert_eq!(cost.postponed_import_vat, 210.0);
+        assert_eq!(cost.seller_pays, 0.0);
+
+        // Germany has no postponed accounting
+        let cost = landed_cost(
+            &import("DE", TransactionType::B2B),
+            &items,
+            Incoterm::Dap,
+            &db,
+        )
+        .expect("Landed cost should be calculated")
+        .expect("Scenario is an import");
+        assert!(cost.postponed_accounting.is_none());
+        assert_eq!(cost.buyer_pays_on_delivery, 190.0);
+    }
+
+    #[test]
+    fn test_landed_cost_not_an_import() {
+        let db = setup();
+        let scenario = TaxScenario::new(
+            Region::parse("FR").expect("Valid region"),
+            Region::parse("DE").expect("Valid region"),
+            TransactionType::B2C,
+        );
+        let cost = landed_cost(
+            &scenario,
+            &[CustomsItem::new(100.0, 61)],
+            Incoterm::Ddp,
+            &db,
+        )
+        .expect("Landed cost should be calculated");
+        assert!(cost.is_none());
+    }
+}
@@ -0,0 +1,148 @@
+//! The tax data as of a date.
+//!
+//! Rates, agreement memberships and agreement rules (with their thresholds) change
+//! over time. The data records their history: `rate_history` on countries,
+//! `membership_periods` and versioned `tax_rules` on agreements. `TaxDatabase::as_of`
+//! returns a `DatedView` that resolves all of it to one date, and can be passed
+//! wherever a provider is accepted, so backfills and audits recalculate past sales
+//! with the rest of the API unchanged.
+
+use std::borrow::Cow;
+use std::sync::Arc;
+
+use crate::calculator::{quote, TaxCalculator, TaxQuote, TaxRequest};
+use crate::certificate::is_iso_date;
+use crate::currency::CurrencyCode;
+use crate::errors::{DatabaseError, InputValidationError, ProcessingError};
+use crate::overrides::OverrideRule;
+use crate::profile::CalculationProfile;
+use crate::rate_provider::RateProvider;
+use crate::sst::BoundaryTable;
+use crate::types::{Country, TaxRate, TradeAgreement, VatRate};
+use crate::{TaxDatabase, TaxScenario};
+
+/// The data of a `TaxDatabase` as it was on a date.
+///
+/// # Examples
+///
+/// ```
+/// # use world_tax::{Region, TaxDatabase, TaxScenario, TransactionType};
+/// let db = TaxDatabase::new().unwrap();
+/// let scenario = TaxScenario::new(
+///     Region::parse("DE").unwrap(),
+///     Region::parse("DE").unwrap(),
+///     TransactionType::B2C,
+/// );
+///
+/// // Germany lowered its standard rate to 16% in the second half of 2020
+/// let past = db.as_of("2020-10-01").unwrap();
+/// assert_eq!(scenario.calculate_tax(100.0, &past).unwrap(), 16.0);
+/// assert_eq!(scenario.calculate_tax(100.0, &db).unwrap(), 19.0);
+/// ```
+#[derive(Clone, Copy)]
+pub struct DatedView<'a> {
+    db: &'a TaxDatabase,
+    date: &'a str,
+}
+
+impl TaxDatabase {
+    /// A view of the data as it was on a date (ISO 8601).
+    ///
+    /// # Errors
+    ///
+    /// Returns `InputValidationError::InvalidDate` if the date isn't an ISO 8601 date (YYYY-MM-DD).
+    pub fn as_of<'a>(&'a self, date: &'a str) -> Result<DatedView<'a>, InputValidationError> {
+        if !is_iso_date(date) {
+            return Err(InputValidationError::InvalidDate(date.to_string()));
+        }
+        Ok(DatedView { db: self, date })
+    }
+}
+
+impl<'a> DatedView<'a> {
+    /// The date the data is resolved to.
+    pub fn date(&self) -> &'a str {
+        self.date
+    }
+
+    /// The database the view resolves.
+    pub fn database(&self) -> &'a TaxDatabase {
+        self.db
+    }
+}
+
+impl RateProvider for DatedView<'_> {
+    fn get_country(&self, code: &str) -> Result<Cow<'_, Country>, DatabaseError> {
+        Ok(self.db.country(code)?.as_of(self.date))
+    }
+
+    fn get_rate(
+        &self,
+        country: &str,
+        region: Option<&str>,
+        vat_rate: Option<&VatRate>,
+    ) -> Result<Vec<TaxRate>, DatabaseError> {
+        let mut rates = Vec::new();
+        self.get_rate_into(country, region, vat_rate, &mut rates)?;
+        Ok(rates)
+    }
+
+    fn get_rule(&self, rule_id: &str) -> Result<TradeAgreement, DatabaseError> {
+        Ok(self.db.get_rule(rule_id)?.as_of(self.date))
+    }
+
+    fn get_federal_rule(&self, country: &str) -> Option<TradeAgreement> {
+        self.db
+            .get_federal_rule(country)
+            .map(|agreement| agreement.as_of(self.date))
+    }
+
+    fn get_international_rule(&self, source: &str, dest: &str) -> Option<TradeAgreement> {
+        self.db
+            .trade_agreements
+            .values()
+            .filter(|agreement| agreement.is_international())
+            .map(|agreement| agreement.as_of(self.date))
+            .find(|agreement| {
+                agreement.is_implemented_by(source) && agreement.is_implemented_by(dest)
+            })
+    }
+
+    fn get_rate_into(
+        &self,
+        country: &str,
+        region: Option<&str>,
+        vat_rate: Option<&VatRate>,
+        rates: &mut Vec<TaxRate>,
+    ) -> Result<(), DatabaseError> {
+        let country_data = self.db.country(country)?.as_of(self.date);
+        self.db
+            .push_country_rates(country, &country_data, region, vat_rate, rates)
+    }
+
+    fn get_profile(&self, name: &str) -> Result<&CalculationProfile, DatabaseError> {
+        self.db.get_profile(name)
+    }
+
+    fn local_table(&self, state: &str) -> Option<Arc<BoundaryTable>> {
+        self.db.local_table(state)
+    }
+
+    fn exchange_rate(&self, from: CurrencyCode, to: CurrencyCode) -> Option<f64> {
+        self.db.exchange_rate(from, to)
+    }
+
+    fn no_agreement_reason(&self, source: &str, dest: &str) -> Option<String> {
+        RateProvider::no_agreement_reason(self.db, source, dest)
+    }
+
+    fn matching_overrides(&self, scenario: &TaxScenario) -> Vec<&OverrideRule> {
+        self.db.matching_overrides(scenario)
+    }
+}
+
+impl TaxCalculator for DatedView<'_> {
+    fn quote(&self, request: TaxRequest) -> Result<TaxQuote, ProcessingError> {
+        quote(self, self.db, request)
+    }
+}
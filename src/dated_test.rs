@@ -0,0 +1,120 @@
+#[cfg(test)]
+mod tests {
+    use crate::calculator::{TaxCalculator, TaxRequest};
+    use crate::types::AnnualTurnover;
+    use crate::vat_id::VatId;
+    use crate::{
+        InputValidationError, RateProvider, Region, TaxCalculationType, TaxDatabase, TaxScenario,
+        TransactionType, VatRate,
+    };
+
+    fn setup() -> TaxDatabase {
+        TaxDatabase::from_files("vat_rates.json", "trade_agreements.json")
+            .expect("Tax database should load")
+    }
+
+    fn scenario(source: &str, destination: &str, transaction_type: TransactionType) -> TaxScenario {
+        TaxScenario::new(
+            Region::parse(source).expect("Valid region"),
+            Region::parse(destination).expect("Valid region"),
+            transaction_type,
+        )
+    }
+
+    #[test]
+    fn test_rates_as_of() {
+        let db = setup();
+        let domestic = scenario("DE", "DE", TransactionType::B2C);
+        let reduced = |provider: &dyn RateProvider| {
+            provider
+                .get_rate("DE", None, Some(&VatRate::Reduced))
+                .unwrap()[0]
+                .rate
+                .to_string()
+        };
+
+        let lowered = db.as_of("2020-07-01").unwrap();
+        assert_eq!(domestic.calculate_tax(100.0, &lowered).unwrap(), 16.0);
+        assert_eq!(reduced(&lowered), "5%");
+        let last_day = db.as_of("2020-12-31").unwrap();
+        assert_eq!(domestic.calculate_tax(100.0, &last_day).unwrap(), 16.0);
+
+        for date in ["2020-06-30", "2021-01-01"] {
+            let view = db.as_of(date).unwrap();
+            assert_eq!(domestic.calculate_tax(100.0, &view).unwrap(), 19.0);
+            assert_eq!(reduced(&view), "7%");
+        }
+    }
+
+    #[test]
+    fn test_memberships_as_of() {
+        let db = setup();
+        let calculation_type = |provider: &dyn RateProvider, source: &str| {
+            scenario(source, "DE", TransactionType::B2B)
+                .with_buyer_vat_id(VatId::parse("DE136695976").unwrap())
+                .determine_calculation_type(provider, 100.0)
+                .unwrap()
+        };
+
+        // The UK applied EU VAT rules until the end of the transition period
+        let before_brexit = db.as_of("2020-12-31").unwrap();
+        assert_eq!(
+            calculation_type(&before_brexit, "GB"),
+            TaxCalculationType::ReverseCharge
+        );
+        let after_brexit = db.as_of("2021-01-01").unwrap();
+        assert_eq!(
+            calculation_type(&after_brexit, "GB"),
+            TaxCalculationType::ZeroRated
+        );
+        assert_eq!(calculation_type(&db, "GB"), TaxCalculationType::ZeroRated);
+
+        // Croatia joined in 2013
+        let before_accession = db.as_of("2013-06-30").unwrap();
+        assert_eq!(
+            calculation_type(&before_accession, "HR"),
+            TaxCalculationType::ZeroRated
+        );
+        assert_eq!(
+            calculation_type(&db, "HR"),
+            TaxCalculationType::ReverseCharge
+        );
+    }
+
+    #[test]
+    fn test_rules_as_of() {
+        let db = setup();
+        let distance_sale =
+            scenario("DE", "FR", TransactionType::B2C).with_turnover(AnnualTurnover {
+                current_year: 20_000.0,
+                previous_year: 0.0,
+                ..Default::default()
+            });
+
+        let quote = db
+            .as_of("2021-06-30")
+            .unwrap()
+            .quote(TaxRequest::new(distance_sale.clone(), 100.0))
+            .unwrap();
+        assert_eq!(quote.calculation_type, TaxCalculationType::Origin);
+        assert_eq!(quote.tax, 19.0);
+
+        let quote = db
+            .as_of("2021-07-01")
+            .unwrap()
+            .quote(TaxRequest::new(distance_sale, 100.0))
+            .unwrap();
+        assert_eq!(quote.calculation_type, TaxCalculationType::Destination);
+        assert_eq!(quote.tax, 20.0);
+    }
+
+    #[test]
+    fn test_invalid_date() {
+        let db = setup();
+        assert!(matches!(
+            db.as_of("2020-13-01"),
+            Err(InputValidationError::InvalidDate(_))
+        ));
+        assert_eq!(db.as_of("2020-07-01").unwrap().date(), "2020-07-01");
+    }
+}
@@ -0,0 +1,219 @@
+//! Explanations as decision graphs.
+//!
+//! An `Explanation` lists the decisions taken while resolving a scenario in order.
+//! For tools that visualize them (e.g. for tax analysts reviewing why a sale was
+//! reverse charged), `Explanation::graph` turns the explanation into a graph: the
+//! scenario, each decision, and the outcome, with the rates, warnings and legal
+//! references of the outcome attached to it.
+//!
+//! The graph serializes to JSON as nodes and edges, and `DecisionGraph::to_dot` writes
+//! it in Graphviz DOT format.
+
+use serde::Serialize;
+
+use crate::explain::Explanation;
+
+/// What a node of a decision graph stands for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NodeKind {
+    /// The scenario, where the decisions start
+    Scenario,
+    /// A decision step
+    Decision,
+    /// The resulting calculation type and tax
+    Outcome,
+    /// An applied tax rate
+    Rate,
+    /// A warning about the result
+    Warning,
+    /// The legal basis of an applied rule or rate
+    LegalReference,
+}
+
+/// A node of a decision graph.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GraphNode {
+    /// Identifier of the node, unique in the graph (e.g. "n2")
+    pub id: String,
+    /// What the node stands for
+    pub kind: NodeKind,
+    /// Short label (e.g. the check of a decision, "agreement")
+    pub label: String,
+    /// Details of the node (e.g. the outcome of a decision)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub detail: Option<String>,
+}
+
+/// A directed edge of a decision graph.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct GraphEdge {
+    /// Identifier of the node the edge starts at
+    pub from: String,
+    /// Identifier of the node the edge ends at
+    pub to: String,
+    /// How the nodes relate (e.g. "applies")
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+}
+
+/// The decision path of an explanation, as nodes and edges.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct DecisionGraph {
+    /// The nodes, the scenario first, then the decisions in order and the outcome
+    pub nodes: Vec<GraphNode>,
+    /// The edges
+    pub edges: Vec<GraphEdge>,
+}
+
+impl DecisionGraph {
+    fn add_node(&mut self, kind: NodeKind, label: String, detail: Option<String>) -> String {
+        let id = format!("n{}", self.nodes.len());
+        self.nodes.push(GraphNode {
+            id: id.clone(),
+            kind,
+            label,
+            detail,
+        });
+        id
+    }
+
+    fn add_edge(&mut self, from: &str, to: &str, label: Option<&str>) {
+        self.edges.push(GraphEdge {
+            from: from.to_string(),
+            to: to.to_string(),
+            label: label.map(str::to_string),
+        });
+    }
+
+    /// The graph in Graphviz DOT format, with a shape per kind of node.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use world_tax::{Region, TaxDatabase, TaxScenario, TransactionType};
+    /// let db = TaxDatabase::fixture();
+    /// let scenario = TaxScenario::new(
+    ///     Region::parse("DE").unwrap(),
+    ///     Region::parse("FR").unwrap(),
+    ///     TransactionType::B2C,
+    /// );
+    /// let dot = scenario.explain(100.0, &db).unwrap().graph().to_dot();
+    /// assert!(dot.starts_with("digraph explanation {"));
+    /// assert!(dot.contains("n0 -> n1;"));
+    /// ```
+    pub fn to_dot(&self) -> String {
+        let mut lines = vec![
+            "digraph explanation {".to_string(),
+            "    rankdir=TB;".to_string(),
+        ];
+        for node in &self.nodes {
+            let shape = match node.kind {
+                NodeKind::Scenario => "oval",
+                NodeKind::Decision => "box",
+                NodeKind::Outcome => "doubleoctagon",
+                NodeKind::Rate => "note",
+                NodeKind::Warning => "hexagon",
+                NodeKind::LegalReference => "folder",
+            };
+            let label = match &node.detail {
+                Some(detail) => format!("{}\\n{}", escape(&node.label), escape(detail)),
+                None => escape(&node.label),
+            };
+            lines.push(format!(
+                "    {} [shape={shape}, label=\"{label}\"];",
+                node.id
+            ));
+        }
+        for edge in &self.edges {
+            lines.push(match &edge.label {
+                Some(label) => format!(
+                    "    {} -> {} [label=\"{}\"];",
+                    edge.from,
+                    edge.to,
+                    escape(label)
+                ),
+                None => format!("    {} -> {};", edge.from, edge.to),
+            });
+        }
+        lines.push("}".to_string());
+        lines.join("\n") + "\n"
+    }
+
+    /// Serializes the graph as JSON.
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("Decision graph serializes to JSON")
+    }
+}
+
+/// Escapes text for a quoted DOT string.
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+impl Explanation {
+    /// The decision path as a graph: the scenario, the decisions in order, and the
+    /// outcome with its rates, warnings and legal references.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use world_tax::decision_graph::NodeKind;
+    /// # use world_tax::{Region, TaxDatabase, TaxScenario, TransactionType};
+    /// let db = TaxDatabase::fixture();
+    /// let scenario = TaxScenario::new(
+    ///     Region::parse("DE").unwrap(),
+    ///     Region::parse("FR").unwrap(),
+    ///     TransactionType::B2C,
+    /// );
+    /// let graph = scenario.explain(100.0, &db).unwrap().graph();
+    /// assert_eq!(graph.nodes[1].kind, NodeKind::Decision);
+    /// assert_eq!(graph.nodes[1].label, "agreement");
+    ///
+    /// let json = serde_json::to_value(&graph).unwrap();
+    /// assert_eq!(json["edges"][0]["from"], "n0");
+    /// ```
+    pub fn graph(&self) -> DecisionGraph {
+        let mut graph = DecisionGraph::default();
+        let mut previous = graph.add_node(NodeKind::Scenario, "scenario".to_string(), None);
+        for step in &self.steps {
+            let node = graph.add_node(
+                NodeKind::Decision,
+                step.check.clone(),
+                Some(step.outcome.clone()),
+            );
+            graph.add_edge(&previous, &node, None);
+            previous = node;
+        }
+
+        let outcome = graph.add_node(
+            NodeKind::Outcome,
+            format!("{:?}", self.calculation_type),
+            Some(format!("tax {} ({:?})", self.tax, self.confidence)),
+        );
+        graph.add_edge(&previous, &outcome, None);
+        for rate in &self.rates {
+            let node = graph.add_node(
+                NodeKind::Rate,
+                format!("{:?} {}", rate.tax_type, rate.rate),
+                Some(format!("{:?}", rate.source)),
+            );
+            graph.add_edge(&outcome, &node, Some("applies"));
+        }
+        for warning in &self.warnings {
+            let node = graph.add_node(NodeKind::Warning, format!("{warning:?}"), None);
+            graph.add_edge(&outcome, &node, Some("warns"));
+        }
+        for reference in &self.legal_references {
+            let node = graph.add_node(
+                NodeKind::LegalReference,
+                reference.subject.clone(),
+                Some(reference.citation.clone()),
+            );
+            graph.add_edge(&outcome, &node, Some("cites"));
+        }
+        graph
+    }
+}
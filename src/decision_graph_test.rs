@@ -0,0 +1,106 @@
+#[cfg(test)]
+mod tests {
+    use crate::decision_graph::{DecisionGraph, GraphEdge, GraphNode, NodeKind};
+    use crate::vat_id::VatId;
+    use crate::{Region, TaxDatabase, TaxScenario, TransactionType};
+
+    fn reverse_charged() -> TaxScenario {
+        TaxScenario::new(
+            Region::parse("DE").expect("Valid region"),
+            Region::parse("FR").expect("Valid region"),
+            TransactionType::B2B,
+        )
+        .with_buyer_vat_id(VatId::parse("FR40303265045").expect("Valid VAT number"))
+    }
+
+    #[test]
+    fn test_graph_follows_the_explanation() {
+        let db = TaxDatabase::new().unwrap();
+        let explanation = reverse_charged().explain(100.0, &db).unwrap();
+        let graph = explanation.graph();
+
+        // The scenario, each decision, the outcome, one rate and the legal references
+        let steps = explanation.steps.len();
+        let references = explanation.legal_references.len();
+        assert!(references > 0);
+        assert_eq!(graph.nodes.len(), 1 + steps + 1 + 1 + references);
+        assert_eq!(graph.edges.len(), graph.nodes.len() - 1);
+        assert_eq!(graph.nodes[0].kind, NodeKind::Scenario);
+        for (node, step) in graph.nodes[1..=steps].iter().zip(&explanation.steps) {
+            assert_eq!(node.kind, NodeKind::Decision);
+            assert_eq!(node.label, step.check);
+            assert_eq!(node.detail.as_deref(), Some(step.outcome.as_str()));
+        }
+
+        let outcome = &graph.nodes[steps + 1];
+        assert_eq!(outcome.kind, NodeKind::Outcome);
+        assert_eq!(outcome.label, "ReverseCharge");
+        let rate = &graph.nodes[steps + 2];
+        assert_eq!(rate.kind, NodeKind::Rate);
+        assert_eq!(
+            graph.edges[steps + 1],
+            GraphEdge {
+                from: outcome.id.clone(),
+                to: rate.id.clone(),
+                label: Some("applies".to_string()),
+            }
+        );
+        assert!(graph.nodes[steps + 3..]
+            .iter()
+            .all(|node| node.kind == NodeKind::LegalReference));
+
+        // Identifiers are unique, and edges connect existing nodes
+        for edge in &graph.edges {
+            assert!(graph.nodes.iter().any(|node| node.id == edge.from));
+            assert!(graph.nodes.iter().any(|node| node.id == edge.to));
+        }
+    }
+
+    #[test]
+    fn test_json_and_dot() {
+        let graph = DecisionGraph {
+            nodes: vec![
+                GraphNode {
+                    id: "n0".to_string(),
+                    kind: NodeKind::Scenario,
+                    label: "scenario".to_string(),
+                    detail: None,
+                },
+                GraphNode {
+                    id: "n1".to_string(),
+                    kind: NodeKind::Decision,
+                    label: "agreement".to_string(),
+                    detail: Some("\"EU\" rule\\internal_b2b".to_string()),
+                },
+            ],
+            edges: vec![GraphEdge {
+                from: "n0".to_string(),
+                to: "n1".to_string(),
+                label: None,
+            }],
+        };
+
+        assert_eq!(
+            serde_json::to_value(&graph).unwrap(),
+            serde_json::json!({
+                "nodes": [
+                    { "id": "n0", "kind": "scenario", "label": "scenario" },
+                    { "id": "n1", "kind": "decision", "label": "agreement",
+                      "detail": "\"EU\" rule\\internal_b2b" }
+                ],
+                "edges": [{ "from": "n0", "to": "n1" }]
+            })
+        );
+        assert_eq!(
+            graph.to_dot(),
+            concat!(
+                "digraph explanation {\n",
+                "    rankdir=TB;\n",
+                "    n0 [shape=oval, label=\"scenario\"];\n",
+                "    n1 [shape=box, label=\"agreement\\n\\\"EU\\\" rule\\\\internal_b2b\"];\n",
+                "    n0 -> n1;\n",
+                "}\n",
+            )
+        );
+    }
+}
@@ -0,0 +1,226 @@
+//! US drop shipments.
+//!
+//! In a drop shipment, a retailer sells to a customer and has its supplier ship the
+//! goods to the customer directly. The supplier's sale to the retailer is exempt as
+//! a sale for resale only if the ship-to state accepts the retailer's resale
+//! certificate. States differ: many accept a certificate of any state, some only
+//! in-state or multistate (SST, MTC) certificates, and California only accepts
+//! retailers holding a California seller's permit. If the certificate isn't
+//! accepted, the supplier must collect the ship-to state's tax.
+//!
+//! The outcome assumes the supplier has nexus in the ship-to state; without nexus,
+//! the supplier has no obligation to collect there.
+
+use serde::Serialize;
+
+use crate::certificate::{is_iso_date, today, CertificateKind, ExemptionCertificate};
+use crate::sst;
+use crate::types::normalize_state_code;
+use crate::{
+    InputValidationError, ProcessingError, Region, TaxDatabase, TaxRate, TaxScenario,
+    ThresholdPolicy, TransactionType,
+};
+
+/// Which resale certificates a state accepts for drop shipments.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CertificateAcceptance {
+    /// Certificates of any state
+    AnyState,
+    /// In-state certificates, and multistate certificates (SST, MTC) issued for the country
+    InStateOrMultistate,
+    /// Only certificates of retailers registered in the state
+    InStateOnly,
+}
+
+/// States whose drop shipment rules differ from their default; SST members accept
+/// in-state and multistate certificates, other states certificates of any state.
+pub const DROP_SHIP_RULES: [(&str, CertificateAcceptance); 1] = [
+    // Regulation 1706: out-of-state retailers without a seller's permit make the
+    // drop shipper the retailer, liable on the retail selling price
+    ("US-CA", CertificateAcceptance::InStateOnly),
+];
+
+/// The drop shipment rules of a state, by its ISO 3166-2 code or its bare code.
+///
+/// # Examples
+///
+/// ```
+/// # use world_tax::dropship::{acceptance, CertificateAcceptance};
+/// assert_eq!(acceptance("US-CA"), CertificateAcceptance::InStateOnly);
+/// assert_eq!(acceptance("US-WA"), CertificateAcceptance::InStateOrMultistate);
+/// assert_eq!(acceptance("US-TX"), CertificateAcceptance::AnyState);
+/// assert_eq!(acceptance("ca"), acceptance("US-CA"));
+/// ```
+pub fn acceptance(state: &str) -> CertificateAcceptance {
+    let state = normalize_state_code("US", state);
+    DROP_SHIP_RULES
+        .iter()
+        .find(|(code, _)| *code == state)
+        .map(|(_, acceptance)| *acceptance)
+        .unwrap_or(if sst::member(&state).is_some() {
+            CertificateAcceptance::InStateOrMultistate
+        } else {
+            CertificateAcceptance::AnyState
+        })
+}
+
+/// How the supplier's sale is treated in the ship-to state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DropShipTreatment {
+    /// The retailer's certificate is accepted; exempt sale for resale
+    Exempt,
+    /// The certificate isn't accepted (or missing); the supplier collects the
+    /// ship-to state's tax
+    SupplierMustCollect,
+    /// The ship-to state has no sales tax
+    NotTaxable,
+}
+
+/// The outcome of a drop shipment for the supplier.
+#[derive(Debug, Clone, Serialize)]
+pub struct DropShipOutcome {
+    /// How the sale is treated
+    pub treatment: DropShipTreatment,
+    /// The ship-to state's drop shipment rules
+    pub acceptance: CertificateAcceptance,
+    /// The rates the supplier collects
+    pub rates: Vec<TaxRate>,
+    /// The tax the supplier collects
+    pub tax: f64,
+}
+
+/// A drop shipment from a supplier to a retailer's customer.
+///
+/// # Examples
+///
+/// ```
+/// # use world_tax::certificate::{CertificateKind, ExemptionCertificate};
+/// # use world_tax::dropship::{DropShipment, DropShipTreatment};
+/// # use world_tax::{Region, TaxDatabase};
+/// let db = TaxDatabase::new().unwrap();
+/// let shipment = DropShipment::new(
+///     Region::parse("US-NV").unwrap(),
+///     Region::parse("US-CA").unwrap(),
+/// )
+/// .with_certificate(ExemptionCertificate::new("US-OR", "OR-77", CertificateKind::Resale));
+///
+/// // California doesn't accept the Oregon retailer's certificate
+/// let outcome = shipment.outcome(100.0, &db).unwrap();
+/// assert_eq!(outcome.treatment, DropShipTreatment::SupplierMustCollect);
+/// assert_eq!(outcome.tax, 8.25);
+/// ```
+#[derive(Debug, Clone)]
+pub struct DropShipment {
+    /// Where the supplier ships from
+    pub supplier: Region,
+    /// Where the goods are delivered (the retailer's customer)
+    pub ship_to: Region,
+    /// The retailer's resale certificate, if given to the supplier
+    pub certificate: Option<ExemptionCertificate>,
+    /// Date of the shipment (ISO 8601); the certificate's expiry is checked against it,
+    /// and against today without one
+    pub date: Option<String>,
+}
+
+impl DropShipment {
+    /// Creates a drop shipment without a resale certificate.
+    pub fn new(supplier: Region, ship_to: Region) -> Self {
+        Self {
+            supplier,
+            ship_to,
+            certificate: None,
+            date: None,
+        }
+    }
+
+    /// Sets the retailer's resale certificate.
+    pub fn with_certificate(mut self, certificate: ExemptionCertificate) -> Self {
+        self.certificate = Some(certificate);
+        self
+    }
+
+    /// Sets the date of the shipment.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InputValidationError::InvalidDate` if the date isn't an ISO 8601 date (YYYY-MM-DD).
+    pub fn with_date(mut self, date: &str) -> Result<Self, InputValidationError> {
+        if !is_iso_date(date) {
+            return Err(InputValidationError::InvalidDate(date.to_string()));
+        }
+        self.date = Some(date.to_string());
+        Ok(self)
+    }
+
+    /// Returns true if the ship-to state accepts the retailer's certificate on the date
+    /// of the shipment (today, without one).
+    pub fn is_certificate_accepted(&self) -> bool {
+        let Some(certificate) = &self.certificate else {
+            return false;
+        };
+        let Some(state) = &self.ship_to.region else {
+            return false;
+        };
+        if certificate.kind != CertificateKind::Resale
+            || certificate.is_expired_on(&self.date.clone().unwrap_or_else(today))
+        {
+            return false;
+        }
+        match acceptance(state) {
+            CertificateAcceptance::AnyState => certificate.jurisdiction.starts_with("US"),
+            CertificateAcceptance::InStateOrMultistate => certificate.covers(&self.ship_to),
+            CertificateAcceptance::InStateOnly => &certificate.jurisdiction == state,
+        }
+    }
+
+    /// Determines the supplier's treatment of the sale, and the tax to collect on the
+    /// amount (the retail selling price, in states that tax drop shippers on it).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the ship-to state's rates can't be resolved.
+    pub fn outcome(
+        &self,
+        amount: f64,
+        db: &TaxDatabase,
+    ) -> Result<DropShipOutcome, ProcessingError> {
+        let state = self.ship_to.region.as_deref().unwrap_or_default();
+        let acceptance = acceptance(state);
+        let no_outcome = |treatment| DropShipOutcome {
+            treatment,
+            acceptance,
+            rates: Vec::new(),
+            tax: 0.0,
+        };
+
+        let has_sales_tax = db
+            .get_country(&self.ship_to.country)?
+            .state(state)
+            .is_some_and(|state| !state.standard_rate().is_zero());
+        if !has_sales_tax {
+            return Ok(no_outcome(DropShipTreatment::NotTaxable));
+        }
+        if self.is_certificate_accepted() {
+            return Ok(no_outcome(DropShipTreatment::Exempt));
+        }
+
+        // The supplier is treated as the retailer of the customer
+        let mut scenario = TaxScenario::new(
+            self.supplier.clone(),
+            self.ship_to.clone(),
+            TransactionType::B2C,
+        );
+        scenario.threshold_policy = ThresholdPolicy::AssumeAbove;
+        scenario.date = self.date.clone();
+        let rates = scenario.get_rates(amount, db)?;
+        let tax = scenario.calculate_tax(amount, db)?;
+        Ok(DropShipOutcome {
+            treatment: DropShipTreatment::SupplierMustCollect,
+            acceptance,
+            rates,
+            tax,
+        })
+    }
+}
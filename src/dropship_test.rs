@@ -0,0 +1,86 @@
+#[cfg(test)]
+mod tests {
+    use crate::certificate::{CertificateKind, ExemptionCertificate};
+    use crate::dropship::{CertificateAcceptance, DropShipTreatment, DropShipment};
+    use crate::{Region, TaxDatabase};
+
+    fn setup() -> TaxDatabase {
+        TaxDatabase::from_files("vat_rates.json", "trade_agreements.json")
+            .expect("Tax database should load")
+    }
+
+    fn shipment(ship_to: &str, jurisdiction: &str) -> DropShipment {
+        DropShipment::new(
+            Region::parse("US-NV").expect("Valid region"),
+            Region::parse(ship_to).expect("Valid region"),
+        )
+        .with_certificate(ExemptionCertificate::new(
+            jurisdiction,
+            "RS-1",
+            CertificateKind::Resale,
+        ))
+    }
+
+    #[test]
+    fn test_any_state_certificate() {
+        let db = setup();
+        let outcome = shipment("US-TX", "US-OR").outcome(100.0, &db).unwrap();
+        assert_eq!(outcome.acceptance, CertificateAcceptance::AnyState);
+        assert_eq!(outcome.treatment, DropShipTreatment::Exempt);
+        assert_eq!(outcome.tax, 0.0);
+    }
+
+    #[test]
+    fn test_sst_member_requires_in_state_or_multistate_certificate() {
+        let db = setup();
+        let outcome = shipment("US-WA", "US-OR").outcome(100.0, &db).unwrap();
+        assert_eq!(outcome.treatment, DropShipTreatment::SupplierMustCollect);
+        assert_eq!(outcome.tax, 6.5);
+
+        let outcome = shipment("US-WA", "US").outcome(100.0, &db).unwrap();
+        assert_eq!(outcome.treatment, DropShipTreatment::Exempt);
+    }
+
+    #[test]
+    fn test_california_requires_in_state_certificate() {
+        let db = setup();
+        let outcome = shipment("US-CA", "US").outcome(100.0, &db).unwrap();
+        assert_eq!(outcome.acceptance, CertificateAcceptance::InStateOnly);
+        assert_eq!(outcome.treatment, DropShipTreatment::SupplierMustCollect);
+
+        let outcome = shipment("US-CA", "US-CA").outcome(100.0, &db).unwrap();
+        assert_eq!(outcome.treatment, DropShipTreatment::Exempt);
+    }
+
+    #[test]
+    fn test_missing_or_expired_certificate() {
+        let db = setup();
+        let without = DropShipment::new(
+            Region::parse("US-NV").unwrap(),
+            Region::parse("US-TX").unwrap(),
+        );
+        let outcome = without.outcome(100.0, &db).unwrap();
+        assert_eq!(outcome.treatment, DropShipTreatment::SupplierMustCollect);
+        assert_eq!(outcome.tax, 6.25);
+
+        let expired = without.with_certificate(
+            ExemptionCertificate::new("US-TX", "RS-1", CertificateKind::Resale)
+                .with_expiry("2020-12-31")
+                .unwrap(),
+        );
+        assert!(!expired.is_certificate_accepted());
+
+        // The certificate was valid when the goods were shipped
+        let shipped = expired.with_date("2020-06-01").unwrap();
+        assert!(shipped.is_certificate_accepted());
+        let outcome = shipped.outcome(100.0, &db).unwrap();
+        assert_eq!(outcome.treatment, DropShipTreatment::Exempt);
+    }
+
+    #[test]
+    fn test_state_without_sales_tax() {
+        let db = setup();
+        let outcome = shipment("US-OR", "US-CA").outcome(100.0, &db).unwrap();
+        assert_eq!(outcome.treatment, DropShipTreatment::NotTaxable);
+    }
+}
@@ -0,0 +1,123 @@
+//! E-invoicing and real-time reporting mandates.
+//!
+//! A growing number of countries require invoices to be issued through a
+//! government clearance channel, like Italy's SdI or Mexico's CFDI. Mandates apply
+//! to sellers established in the country, often phased in by transaction type and
+//! turnover. This module lists the mandates, so billing systems know when an
+//! invoice must go through a clearance channel; quotes carry the mandates of the
+//! seller's country.
+
+use serde::Serialize;
+use typeshare::typeshare;
+
+use crate::{TaxScenario, TransactionType};
+
+/// The transactions a mandate covers.
+#[typeshare]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MandateScope {
+    /// Sales to businesses
+    B2B,
+    /// Sales to consumers
+    B2C,
+    /// All sales
+    All,
+}
+
+impl MandateScope {
+    /// Returns true if the scope covers the transaction type.
+    pub fn covers(&self, transaction_type: &TransactionType) -> bool {
+        match self {
+            MandateScope::B2B => *transaction_type == TransactionType::B2B,
+            MandateScope::B2C => *transaction_type == TransactionType::B2C,
+            MandateScope::All => true,
+        }
+    }
+}
+
+/// An e-invoicing mandate of a country.
+#[typeshare]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct EInvoicingMandate {
+    /// ISO 3166-1 code of the country
+    pub country: &'static str,
+    /// Name of the clearance channel
+    pub system: &'static str,
+    /// The transactions covered
+    pub scope: MandateScope,
+    /// Date the mandate applies from (ISO 8601)
+    pub effective_from: &'static str,
+    /// Annual turnover (in the country's currency) above which the mandate applies, if limited
+    pub min_annual_turnover: Option<u64>,
+}
+
+const fn mandate(
+    country: &'static str,
+    system: &'static str,
+    scope: MandateScope,
+    effective_from: &'static str,
+    min_annual_turnover: Option<u64>,
+) -> EInvoicingMandate {
+    EInvoicingMandate {
+        country,
+        system,
+        scope,
+        effective_from,
+        min_annual_turnover,
+    }
+}
+
+/// Known e-invoicing mandates.
+pub const MANDATES: [EInvoicingMandate; 7] = [
+    mandate("IT", "SdI", MandateScope::All, "2019-01-01", None),
+    mandate(
+        "PL",
+        "KSeF",
+        MandateScope::B2B,
+        "2026-02-01",
+        Some(200_000_000),
+    ),
+    mandate("PL", "KSeF", MandateScope::B2B, "2026-04-01", None),
+    mandate("RO", "e-Factura", MandateScope::B2B, "2024-01-01", None),
+    mandate("RO", "e-Factura", MandateScope::B2C, "2025-01-01", None),
+    mandate("MX", "CFDI", MandateScope::All, "2014-01-01", None),
+    mandate(
+        "IN",
+        "e-invoice (IRP)",
+        MandateScope::B2B,
+        "2023-08-01",
+        Some(50_000_000),
+    ),
+];
+
+/// Lists the mandates of a country that cover the transaction type.
+///
+/// # Examples
+///
+/// ```
+/// # use world_tax::einvoicing::mandates;
+/// # use world_tax::TransactionType;
+/// let italy: Vec<_> = mandates("IT", &TransactionType::B2C).collect();
+/// assert_eq!(italy[0].system, "SdI");
+/// ```
+pub fn mandates<'a>(
+    country: &'a str,
+    transaction_type: &'a TransactionType,
+) -> impl Iterator<Item = &'static EInvoicingMandate> + 'a {
+    MANDATES
+        .iter()
+        .filter(move |mandate| mandate.country == country && mandate.scope.covers(transaction_type))
+}
+
+impl TaxScenario {
+    /// Lists the e-invoicing mandates of the seller's country that cover the scenario.
+    ///
+    /// Check `effective_from` against the invoice date, and `min_annual_turnover`
+    /// against the seller's turnover.
+    pub fn e_invoicing_mandates(&self) -> Vec<EInvoicingMandate> {
+        mandates(&self.source_region.country, &self.transaction_type)
+            .copied()
+            .collect()
+    }
+}
@@ -0,0 +1,35 @@
+#[cfg(test)]
+mod tests {
+    use crate::calculator::{TaxCalculator, TaxRequest};
+    use crate::einvoicing::{mandates, MandateScope};
+    use crate::{Region, TaxDatabase, TaxScenario, TransactionType};
+
+    #[test]
+    fn test_mandates_by_transaction_type() {
+        let b2b: Vec<_> = mandates("PL", &TransactionType::B2B).collect();
+        assert_eq!(b2b.len(), 2);
+        assert_eq!(b2b[0].min_annual_turnover, Some(200_000_000));
+        assert!(mandates("PL", &TransactionType::B2C).next().is_none());
+
+        let romania: Vec<_> = mandates("RO", &TransactionType::B2C).collect();
+        assert_eq!(romania[0].scope, MandateScope::B2C);
+        assert_eq!(romania[0].effective_from, "2025-01-01");
+    }
+
+    #[test]
+    fn test_quote_carries_mandates() {
+        let db = TaxDatabase::new().expect("Tax database should load");
+        let italy = Region::parse("IT").expect("Valid region");
+        let scenario = TaxScenario::new(italy.clone(), italy, TransactionType::B2C);
+
+        let quote = db
+            .quote(TaxRequest::new(scenario, 100.0))
+            .expect("Quote should succeed");
+        assert_eq!(quote.e_invoicing.len(), 1);
+        assert_eq!(quote.e_invoicing[0].system, "SdI");
+
+        let germany = Region::parse("DE").expect("Valid region");
+        let scenario = TaxScenario::new(germany.clone(), germany, TransactionType::B2B);
+        assert!(scenario.e_invoicing_mandates().is_empty());
+    }
+}
@@ -0,0 +1,258 @@
+//! Versioned serialization of tax requests.
+//!
+//! Requests queued for asynchronous processing (e.g. on Kafka or SQS) may be replayed
+//! by a later release of the crate than the one that wrote them. `TaxRequest::to_message`
+//! wraps a request in an envelope with its format version, and `TaxRequest::from_message`
+//! reads every version it knows:
+//!
+//! ```json
+//! {
+//!     "version": 1,
+//!     "request": {
+//!         "source": "DE",
+//!         "destination": "FR",
+//!         "transaction_type": "b2c",
+//!         "amount": 100.0
+//!     }
+//! }
+//! ```
+//!
+//! Fields added to a version are optional, so messages written before the field
+//! existed still load, and fields a release doesn't know are ignored, so messages of
+//! a newer release load with the fields the older one understands. Changes that
+//! can't be made that way get a new version.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::calculator::TaxRequest;
+use crate::certificate::ExemptionCertificate;
+use crate::currency::CurrencyCode;
+use crate::errors::DatasetError;
+use crate::types::{AnnualTurnover, Incoterm, SupplyKind};
+use crate::vat_id::VatIdCheck;
+use crate::{
+    InputValidationError, Region, TaxScenario, ThresholdPolicy, TradeAgreementOverride,
+    TransactionType, VatRate,
+};
+
+/// The version `TaxRequest::to_message` writes.
+pub const MESSAGE_VERSION: u32 = 1;
+
+/// Version 1 of the serialized tax request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaxRequestV1 {
+    /// Region of the seller, as an ISO 3166-1 or ISO 3166-2 code (e.g. "DE", "US-CA")
+    pub source: String,
+    /// Postal code of the seller
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_postal_code: Option<String>,
+    /// Local jurisdiction of the seller
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_jurisdiction: Option<String>,
+    /// Region of the buyer
+    pub destination: String,
+    /// Postal code of the buyer
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub destination_postal_code: Option<String>,
+    /// Local jurisdiction of the buyer
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub destination_jurisdiction: Option<String>,
+    /// Type of transaction
+    pub transaction_type: TransactionType,
+    /// The net transaction amount
+    pub amount: f64,
+    /// Optional override for trade agreement application
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub trade_agreement_override: Option<TradeAgreementOverride>,
+    /// Whether the product or service is digital
+    #[serde(default)]
+    pub digital: bool,
+    /// The buyer's resale or exemption certificate
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exemption_certificate: Option<ExemptionCertificate>,
+    /// The check of the buyer's VAT number
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub buyer_vat_id: Option<VatIdCheck>,
+    /// How thresholds are applied
+    #[serde(default)]
+    pub threshold_policy: ThresholdPolicy,
+    /// Specific VAT rate to apply
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vat_rate: Option<VatRate>,
+    /// Name of a calculation profile
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+    /// Delivery terms of cross-border physical goods
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub incoterm: Option<Incoterm>,
+    /// Currency the amounts are stated in
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub currency: Option<CurrencyCode>,
+    /// The seller's turnover in the threshold's jurisdiction
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub turnover: Option<AnnualTurnover>,
+    /// Date of the supply (ISO 8601)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub date: Option<String>,
+    /// What is supplied
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub supply_kind: Option<SupplyKind>,
+    /// Attributes of the sale for override rules
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub attributes: HashMap<String, String>,
+}
+
+fn region_code(region: &Region) -> String {
+    region
+        .region
+        .clone()
+        .unwrap_or_else(|| region.country.clone())
+}
+
+fn region(
+    code: &str,
+    postal_code: &Option<String>,
+    jurisdiction: &Option<String>,
+) -> Result<Region, InputValidationError> {
+    Ok(Region {
+        postal_code: postal_code.clone(),
+        jurisdiction: jurisdiction.clone(),
+        ..Region::parse(code)?
+    })
+}
+
+impl From<&TaxRequest> for TaxRequestV1 {
+    fn from(request: &TaxRequest) -> Self {
+        let scenario = &request.scenario;
+        Self {
+            source: region_code(&scenario.source_region),
+            source_postal_code: scenario.source_region.postal_code.clone(),
+            source_jurisdiction: scenario.source_region.jurisdiction.clone(),
+            destination: region_code(&scenario.destination_region),
+            destination_postal_code: scenario.destination_region.postal_code.clone(),
+            destination_jurisdiction: scenario.destination_region.jurisdiction.clone(),
+            transaction_type: scenario.transaction_type.clone(),
+            amount: request.amount,
+            trade_agreement_override: scenario.trade_agreement_override.clone(),
+            digital: scenario.is_digital_product_or_service,
+            exemption_certificate: scenario.exemption_certificate.clone(),
+            buyer_vat_id: scenario.buyer_vat_id.clone(),
+            threshold_policy: scenario.threshold_policy,
+            vat_rate: scenario.vat_rate,
+            profile: scenario.profile.clone(),
+            incoterm: scenario.incoterm,
+            currency: scenario.currency,
+            turnover: scenario.turnover,
+            date: scenario.date.clone(),
+            supply_kind: scenario.supply_kind,
+            attributes: scenario.attributes.clone(),
+        }
+    }
+}
+
+impl TaxRequestV1 {
+    /// Builds the tax request.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a region code is invalid.
+    pub fn into_request(self) -> Result<TaxRequest, InputValidationError> {
+        let mut scenario = TaxScenario::new(
+            region(
+                &self.source,
+                &self.source_postal_code,
+                &self.source_jurisdiction,
+            )?,
+            region(
+                &self.destination,
+                &self.destination_postal_code,
+                &self.destination_jurisdiction,
+            )?,
+            self.transaction_type,
+        );
+        scenario.trade_agreement_override = self.trade_agreement_override;
+        scenario.is_digital_product_or_service = self.digital;
+        scenario.exemption_certificate = self.exemption_certificate;
+        scenario.buyer_vat_id = self.buyer_vat_id;
+        scenario.threshold_policy = self.threshold_policy;
+        scenario.vat_rate = self.vat_rate;
+        scenario.profile = self.profile;
+        scenario.incoterm = self.incoterm;
+        scenario.currency = self.currency;
+        scenario.turnover = self.turnover;
+        scenario.supply_kind = self.supply_kind;
+        scenario.attributes = self.attributes;
+        if let Some(date) = &self.date {
+            scenario = scenario.with_date(date)?;
+        }
+        Ok(TaxRequest::new(scenario, self.amount))
+    }
+}
+
+#[derive(Serialize)]
+struct Envelope<'a, T> {
+    version: u32,
+    request: &'a T,
+}
+
+#[derive(Deserialize)]
+struct RawEnvelope {
+    version: u32,
+    request: serde_json::Value,
+}
+
+fn invalid(error: impl ToString) -> DatasetError {
+    DatasetError::InvalidJson("request".to_string(), error.to_string())
+}
+
+impl TaxRequest {
+    /// Serializes the request in the current message version.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use world_tax::calculator::TaxRequest;
+    /// # use world_tax::{Region, TaxScenario, TransactionType};
+    /// let scenario = TaxScenario::new(
+    ///     Region::parse("DE").unwrap(),
+    ///     Region::parse("FR").unwrap(),
+    ///     TransactionType::B2C,
+    /// );
+    /// let message = TaxRequest::new(scenario, 100.0).to_message();
+    ///
+    /// let request = TaxRequest::from_message(&message).unwrap();
+    /// assert_eq!(request.scenario.destination_region.country, "FR");
+    /// assert_eq!(request.amount, 100.0);
+    /// ```
+    pub fn to_message(&self) -> String {
+        serde_json::to_string(&Envelope {
+            version: MESSAGE_VERSION,
+            request: &TaxRequestV1::from(self),
+        })
+        .expect("Requests serialize to JSON")
+    }
+
+    /// Deserializes a request of any known message version.
+    ///
+    /// # Errors
+    ///
+    /// - `DatasetError::UnsupportedSchemaVersion` if the message version is newer than
+    ///   this release knows
+    /// - `DatasetError::InvalidJson` if the message doesn't describe a request, or has
+    ///   an invalid region code
+    pub fn from_message(json: &str) -> Result<Self, DatasetError> {
+        let envelope: RawEnvelope = serde_json::from_str(json).map_err(invalid)?;
+        match envelope.version {
+            1 => serde_json::from_value::<TaxRequestV1>(envelope.request)
+                .map_err(invalid)?
+                .into_request()
+                .map_err(invalid),
+            version => Err(DatasetError::UnsupportedSchemaVersion(
+                "request".to_string(),
+                version,
+            )),
+        }
+    }
+}
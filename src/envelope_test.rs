@@ -0,0 +1,109 @@
+#[cfg(test)]
+mod tests {
+    use crate::calculator::TaxRequest;
+    use crate::certificate::{CertificateKind, ExemptionCertificate};
+    use crate::currency::CurrencyCode;
+    use crate::envelope::MESSAGE_VERSION;
+    use crate::types::Incoterm;
+    use crate::{
+        DatasetError, Region, TaxScenario, ThresholdPolicy, TradeAgreementOverride,
+        TransactionType, VatRate,
+    };
+
+    #[test]
+    fn test_message_round_trip() {
+        let mut scenario = TaxScenario::new(
+            Region::parse("US-CA").unwrap().with_postal_code("94103"),
+            Region::parse("US-WA").unwrap(),
+            TransactionType::B2B,
+        );
+        scenario.trade_agreement_override = Some(TradeAgreementOverride::NoAgreement);
+        scenario.is_digital_product_or_service = true;
+        scenario.exemption_certificate = Some(ExemptionCertificate::new(
+            "US-WA",
+            "RS-1",
+            CertificateKind::Resale,
+        ));
+        scenario.threshold_policy = ThresholdPolicy::AssumeAbove;
+        scenario.vat_rate = Some(VatRate::Reduced);
+        scenario.profile = Some("strict".to_string());
+        scenario.incoterm = Some(Incoterm::Ddp);
+        scenario.currency = Some(CurrencyCode::parse("USD").unwrap());
+
+        let message = TaxRequest::new(scenario, 250.0).to_message();
+        let json: serde_json::Value = serde_json::from_str(&message).unwrap();
+        assert_eq!(json["version"], MESSAGE_VERSION);
+
+        let request = TaxRequest::from_message(&message).unwrap();
+        let scenario = request.scenario;
+        assert_eq!(request.amount, 250.0);
+        assert_eq!(scenario.source_region.region.as_deref(), Some("US-CA"));
+        assert_eq!(scenario.source_region.postal_code.as_deref(), Some("94103"));
+        assert_eq!(scenario.destination_region.region.as_deref(), Some("US-WA"));
+        assert_eq!(scenario.transaction_type, TransactionType::B2B);
+        assert!(matches!(
+            scenario.trade_agreement_override,
+            Some(TradeAgreementOverride::NoAgreement)
+        ));
+        assert!(scenario.is_digital_product_or_service);
+        assert_eq!(scenario.exemption_certificate.unwrap().number, "RS-1");
+        assert_eq!(scenario.threshold_policy, ThresholdPolicy::AssumeAbove);
+        assert_eq!(scenario.vat_rate, Some(VatRate::Reduced));
+        assert_eq!(scenario.profile.as_deref(), Some("strict"));
+        assert_eq!(scenario.incoterm, Some(Incoterm::Ddp));
+        assert_eq!(scenario.currency.unwrap().code(), "USD");
+    }
+
+    #[test]
+    fn test_old_and_newer_messages() {
+        // Written before the optional fields existed
+        let old = r#"{
+            "version": 1,
+            "request": { "source": "DE", "destination": "FR", "transaction_type": "b2c", "amount": 100.0 }
+        }"#;
+        let request = TaxRequest::from_message(old).unwrap();
+        assert_eq!(request.scenario.source_region.country, "DE");
+        assert!(!request.scenario.is_digital_product_or_service);
+        assert_eq!(
+            request.scenario.threshold_policy,
+            ThresholdPolicy::default()
+        );
+        assert!(request.scenario.vat_rate.is_none());
+
+        // Written by a release with fields this one doesn't know
+        let newer = r#"{
+            "version": 1,
+            "request": {
+                "source": "DE", "destination": "FR", "transaction_type": "b2b", "amount": 100.0,
+                "digital": true, "buyer_tax_id": "FR123"
+            },
+            "trace_id": "abc"
+        }"#;
+        let request = TaxRequest::from_message(newer).unwrap();
+        assert!(request.scenario.is_digital_product_or_service);
+    }
+
+    #[test]
+    fn test_message_errors() {
+        let unknown = r#"{ "version": 9, "request": {} }"#;
+        assert!(matches!(
+            TaxRequest::from_message(unknown),
+            Err(DatasetError::UnsupportedSchemaVersion(_, 9))
+        ));
+
+        let missing = r#"{ "version": 1, "request": { "source": "DE" } }"#;
+        assert!(matches!(
+            TaxRequest::from_message(missing),
+            Err(DatasetError::InvalidJson(..))
+        ));
+
+        let invalid_region = r#"{
+            "version": 1,
+            "request": { "source": "XX", "destination": "FR", "transaction_type": "b2c", "amount": 1.0 }
+        }"#;
+        assert!(matches!(
+            TaxRequest::from_message(invalid_region),
+            Err(DatasetError::InvalidJson(..))
+        ));
+    }
+}
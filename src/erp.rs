@@ -0,0 +1,237 @@
+//! Tax codes of accounting systems.
+//!
+//! ERPs post tax with their own codes: tax codes in SAP (MWSKZ), tax items or groups
+//! in NetSuite, tax rates in Xero. A `TaxCodeMapping` translates the outcome of a
+//! calculation into these codes, so postings can be generated directly from quotes.
+//! It's loaded from a mapping file of the form:
+//!
+//! ```json
+//! {
+//!     "system": "sap",
+//!     "codes": [
+//!         { "jurisdiction": "DE", "scheme": "origin", "rate": "reduced", "code": "A2" },
+//!         { "jurisdiction": "DE", "scheme": "origin", "code": "A1" },
+//!         { "jurisdiction": "US-CA", "tax": { "type": "local_sales_tax" }, "code": "CL" },
+//!         { "scheme": "reverse_charge", "code": "E0" }
+//!     ]
+//! }
+//! ```
+//!
+//! An outcome is the jurisdiction whose rates apply (the seller's for origin taxation,
+//! otherwise the buyer's), the calculation type (`scheme`), the rate band and the type
+//! of tax. Every field but `code` is optional and matches any outcome if left out; a
+//! country matches its states too. The first matching entry wins, so list specific
+//! entries before general ones.
+
+use serde::{Deserialize, Serialize};
+
+use crate::calculation::rounded_rate_taxes;
+use crate::calculator::TaxQuote;
+use crate::errors::{DatabaseError, DatasetError};
+use crate::ledger::LedgerAccounts;
+use crate::percentage::Percentage;
+use crate::{Region, TaxCalculationType, TaxScenario, TaxType, VatRate};
+
+/// A tax code, and the outcomes it's used for.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TaxCodeEntry {
+    /// Country or state code (e.g. "DE", "US-CA")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jurisdiction: Option<String>,
+    /// The calculation type
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scheme: Option<TaxCalculationType>,
+    /// The rate band
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rate: Option<VatRate>,
+    /// The type of tax
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tax: Option<TaxType>,
+    /// The code in the accounting system
+    pub code: String,
+    /// Liability account the tax of the code is posted to; `accounts.tax` if not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub account: Option<String>,
+}
+
+impl TaxCodeEntry {
+    fn matches(
+        &self,
+        region: &Region,
+        scheme: &TaxCalculationType,
+        rate: Option<VatRate>,
+        tax: Option<TaxType>,
+    ) -> bool {
+        let jurisdiction = self
+            .jurisdiction
+            .as_deref()
+            .is_none_or(|code| region.country == code || region.region.as_deref() == Some(code));
+        jurisdiction
+            && self.scheme.as_ref().is_none_or(|s| s == scheme)
+            && self.rate.is_none_or(|r| Some(r) == rate)
+            && self.tax.is_none_or(|t| Some(t) == tax)
+    }
+}
+
+/// The tax codes of an accounting system.
+///
+/// # Examples
+///
+/// ```
+/// # use world_tax::calculator::{TaxCalculator, TaxRequest};
+/// # use world_tax::erp::TaxCodeMapping;
+/// # use world_tax::vat_id::VatId;
+/// # use world_tax::{Region, TaxDatabase, TaxScenario, TransactionType};
+/// let mapping = TaxCodeMapping::from_json(
+///     r#"{ "system": "xero", "codes": [
+///         { "jurisdiction": "DE", "scheme": "origin", "code": "OUTPUT_DE" },
+///         { "scheme": "reverse_charge", "code": "ECOUTPUTSERVICES" }
+///     ] }"#,
+/// )
+/// .unwrap();
+///
+/// let db = TaxDatabase::fixture();
+/// let scenario = TaxScenario::new(
+///     Region::parse("DE").unwrap(),
+///     Region::parse("FR").unwrap(),
+///     TransactionType::B2B,
+/// )
+/// .with_buyer_vat_id(VatId::parse("FR40303265045").unwrap());
+/// let quote = db.quote(TaxRequest::new(scenario.clone(), 100.0)).unwrap();
+/// let postings = mapping.postings(&scenario, &quote).unwrap();
+/// assert_eq!(postings[0].code, "ECOUTPUTSERVICES");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TaxCodeMapping {
+    /// Name of the accounting system (e.g. "sap", "netsuite", "xero")
+    #[serde(default)]
+    pub system: String,
+    /// The tax codes, most specific first
+    pub codes: Vec<TaxCodeEntry>,
+    /// General ledger accounts of journal lines (see `ledger`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub accounts: Option<LedgerAccounts>,
+}
+
+/// The tax of a quote posted under a tax code.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TaxPosting {
+    /// The code in the accounting system
+    pub code: String,
+    /// Liability account of the code, if mapped
+    pub account: Option<String>,
+    /// Country or state code of the jurisdiction whose rates applied
+    pub jurisdiction: String,
+    /// The type of tax; `None` if no tax was charged
+    pub tax_type: Option<TaxType>,
+    /// The tax rate
+    pub rate: Percentage,
+    /// The amount the rate was applied to
+    pub base: f64,
+    /// The tax amount, rounded to 2 decimal places
+    pub amount: f64,
+}
+
+impl TaxCodeMapping {
+    /// Loads a mapping file.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatasetError::InvalidJson` if the JSON doesn't describe a mapping.
+    pub fn from_json(json: &str) -> Result<Self, DatasetError> {
+        serde_json::from_str(json)
+            .map_err(|e| DatasetError::InvalidJson("tax code mapping".to_string(), e.to_string()))
+    }
+
+    /// The code of an outcome, if one is mapped.
+    pub fn code(
+        &self,
+        region: &Region,
+        scheme: &TaxCalculationType,
+        rate: Option<VatRate>,
+        tax: Option<TaxType>,
+    ) -> Option<&str> {
+        self.entry(region, scheme, rate, tax)
+            .map(|entry| entry.code.as_str())
+    }
+
+    fn entry(
+        &self,
+        region: &Region,
+        scheme: &TaxCalculationType,
+        rate: Option<VatRate>,
+        tax: Option<TaxType>,
+    ) -> Option<&TaxCodeEntry> {
+        self.codes
+            .iter()
+            .find(|entry| entry.matches(region, scheme, rate, tax))
+    }
+
+    /// Postings of a quote: one per applied rate, or a single posting of the net
+    /// amount if no tax was charged (e.g. reverse charge or zero-rated sales).
+    ///
+    /// The rate band is that of VAT rates, or else the scenario's `vat_rate`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::TaxCodeNotMapped` if no code is mapped for an outcome.
+    pub fn postings(
+        &self,
+        scenario: &TaxScenario,
+        quote: &TaxQuote,
+    ) -> Result<Vec<TaxPosting>, DatabaseError> {
+        let region = match quote.calculation_type {
+            TaxCalculationType::Origin => &scenario.source_region,
+            _ => &scenario.destination_region,
+        };
+        let jurisdiction = region.region.as_ref().unwrap_or(&region.country).clone();
+        let entry = |rate: Option<VatRate>, tax: Option<TaxType>| {
+            self.entry(region, &quote.calculation_type, rate, tax)
+                .ok_or_else(|| {
+                    DatabaseError::TaxCodeNotMapped(format!(
+                        "{} {:?}{}{}",
+                        jurisdiction,
+                        quote.calculation_type,
+                        rate.map(|rate| format!(" {rate}")).unwrap_or_default(),
+                        tax.map(|tax| format!(" {tax:?}")).unwrap_or_default()
+                    ))
+                })
+        };
+
+        if quote.rates.is_empty() {
+            let entry = entry(scenario.vat_rate, None)?;
+            return Ok(vec![TaxPosting {
+                code: entry.code.clone(),
+                account: entry.account.clone(),
+                jurisdiction,
+                tax_type: None,
+                rate: Percentage::ZERO,
+                base: quote.amount,
+                amount: 0.0,
+            }]);
+        }
+
+        let mut postings = Vec::with_capacity(quote.rates.len());
+        for (rate, (base, amount)) in quote
+            .rates
+            .iter()
+            .zip(rounded_rate_taxes(quote.amount, &quote.rates))
+        {
+            let band = match rate.tax_type {
+                TaxType::VAT(band) => Some(band),
+                _ => scenario.vat_rate,
+            };
+            let entry = entry(band, Some(rate.tax_type))?;
+            postings.push(TaxPosting {
+                code: entry.code.clone(),
+                account: entry.account.clone(),
+                jurisdiction: jurisdiction.clone(),
+                tax_type: Some(rate.tax_type),
+                rate: rate.rate,
+                base,
+                amount,
+            });
+        }
+        Ok(postings)
+    }
+}
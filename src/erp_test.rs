@@ -0,0 +1,101 @@
+#[cfg(test)]
+mod tests {
+    use crate::calculator::{TaxCalculator, TaxRequest};
+    use crate::erp::TaxCodeMapping;
+    use crate::vat_id::VatId;
+    use crate::{
+        DatabaseError, DatasetError, Region, TaxDatabase, TaxScenario, TaxType, TransactionType,
+        VatRate,
+    };
+
+    const SAP: &str = r#"{
+        "system": "sap",
+        "codes": [
+            { "jurisdiction": "DE", "scheme": "origin", "rate": "reduced", "code": "A2" },
+            { "jurisdiction": "DE", "scheme": "origin", "code": "A1" },
+            { "jurisdiction": "CA-QC", "tax": { "type": "q_s_t" }, "code": "QS" },
+            { "jurisdiction": "CA", "tax": { "type": "g_s_t" }, "code": "GS" },
+            { "scheme": "reverse_charge", "code": "E0" }
+        ]
+    }"#;
+
+    fn postings(
+        source: &str,
+        destination: &str,
+        transaction_type: TransactionType,
+        vat_rate: Option<VatRate>,
+        buyer_vat_id: Option<&str>,
+    ) -> Result<Vec<(String, f64)>, DatabaseError> {
+        let db = TaxDatabase::fixture();
+        let mapping = TaxCodeMapping::from_json(SAP).unwrap();
+        let mut scenario = TaxScenario::new(
+            Region::parse(source).unwrap(),
+            Region::parse(destination).unwrap(),
+            transaction_type,
+        );
+        scenario.vat_rate = vat_rate;
+        scenario.buyer_vat_id = buyer_vat_id.map(|id| VatId::parse(id).unwrap().format_check());
+        let quote = db.quote(TaxRequest::new(scenario.clone(), 100.0)).unwrap();
+        Ok(mapping
+            .postings(&scenario, &quote)?
+            .into_iter()
+            .map(|posting| (posting.code, posting.amount))
+            .collect())
+    }
+
+    #[test]
+    fn test_postings() {
+        let domestic = postings("DE", "DE", TransactionType::B2C, None, None).unwrap();
+        assert_eq!(domestic, vec![("A1".to_string(), 19.0)]);
+        let reduced = postings(
+            "DE",
+            "DE",
+            TransactionType::B2C,
+            Some(VatRate::Reduced),
+            None,
+        )
+        .unwrap();
+        assert_eq!(reduced, vec![("A2".to_string(), 7.0)]);
+
+        // Without tax, the net amount is posted under the scheme's code
+        let reverse_charge = postings(
+            "DE",
+            "FR",
+            TransactionType::B2B,
+            None,
+            Some("FR40303265045"),
+        )
+        .unwrap();
+        assert_eq!(reverse_charge, vec![("E0".to_string(), 0.0)]);
+
+        // One posting per rate; the state entry comes first
+        let quebec = postings("CA-QC", "CA-QC", TransactionType::B2B, None, None).unwrap();
+        assert_eq!(
+            quebec,
+            vec![("GS".to_string(), 5.0), ("QS".to_string(), 10.47)]
+        );
+    }
+
+    #[test]
+    fn test_unmapped_outcome() {
+        assert!(matches!(
+            postings("CA-ON", "CA-ON", TransactionType::B2C, None, None),
+            Err(DatabaseError::TaxCodeNotMapped(outcome)) if outcome.starts_with("CA-ON ")
+        ));
+
+        let mapping = TaxCodeMapping::from_json(SAP).unwrap();
+        assert_eq!(
+            mapping.code(
+                &Region::parse("CA-BC").unwrap(),
+                &crate::TaxCalculationType::Destination,
+                None,
+                Some(TaxType::GST)
+            ),
+            Some("GS")
+        );
+        assert!(matches!(
+            TaxCodeMapping::from_json(r#"{ "codes": [{ "scheme": "origin" }] }"#),
+            Err(DatasetError::InvalidJson(_, _))
+        ));
+    }
+}
@@ -12,30 +12,89 @@
 //!   trade agreements or tax rates.
 //! - `ProcessingError`: Errors that occur during the processing of tax calculations,
 //!   such as invalid amounts or errors propagated from other error types.
+//!
+//! Loading a dataset in strict mode reports a `DatasetError` instead.
 
 use serde::Serialize;
 use thiserror::Error;
 
+use crate::schema::SchemaViolation;
+use crate::validation::ValidationIssue;
+
 #[derive(Debug, Error, Serialize)]
 pub enum InputValidationError {
-    #[error("Invalid country code: {0}")]
-    InvalidCountryCode(String),
-    #[error("Invalid region code: {0}")]
-    InvalidRegionCode(String),
+    #[error("Invalid country code: {code}{}", did_you_mean(.suggestions))]
+    InvalidCountryCode {
+        code: String,
+        suggestions: Vec<String>,
+    },
+    #[error("Invalid region code: {code}{}", did_you_mean(.suggestions))]
+    InvalidRegionCode {
+        code: String,
+        suggestions: Vec<String>,
+    },
     #[error("Unexpected region code: {0} - Country has no regions.")]
     UnexpectedRegionCode(String),
+    #[error("Invalid currency code: {0}")]
+    InvalidCurrencyCode(String),
+    #[error("Invalid VAT ID {id}: {reason}")]
+    InvalidVatId { id: String, reason: String },
+    #[error("Invalid date: {0} - Expected YYYY-MM-DD.")]
+    InvalidDate(String),
+    #[error("Certificate not valid for the buyer: {0}")]
+    CertificateMismatch(String),
 }
 
-#[derive(Debug, Error, Serialize)]
+#[derive(Debug, Clone, Error, Serialize)]
 pub enum DatabaseError {
     #[error("Trade agreement not found: {0}")]
     TradeAgreementNotFound(String),
-    #[error("Country not found: {0}")]
-    CountryNotFound(String),
-    #[error("Region not found: {0}")]
-    RegionNotFound(String),
+    #[error("Country not found: {code}{}", did_you_mean(.suggestions))]
+    CountryNotFound {
+        code: String,
+        suggestions: Vec<String>,
+    },
+    #[error("Region not found: {code}{}", did_you_mean(.suggestions))]
+    RegionNotFound {
+        code: String,
+        suggestions: Vec<String>,
+    },
     #[error("VAT rate not found: {0}")]
     VatRateNotFound(String),
+    #[error("Calculation profile not found: {0}")]
+    ProfileNotFound(String),
+    #[error("Exchange rate not found: {from} to {to}")]
+    ExchangeRateNotFound { from: String, to: String },
+    #[error("Amount in {amount} compared against a threshold in {threshold}")]
+    CurrencyMismatch { amount: String, threshold: String },
+    #[error("Tax rule not found: {0}")]
+    TaxRuleNotFound(String),
+    #[error("Not a Streamlined Sales Tax member state: {0}")]
+    NotSstMember(String),
+    #[error("Invalid boundary record on line {0}")]
+    InvalidBoundaryRecord(usize),
+    #[error("Local rates of {state} unavailable: {reason}")]
+    LocalRatesUnavailable { state: String, reason: String },
+    #[error("Invalid duty record on line {0}")]
+    InvalidDutyRecord(usize),
+    #[error("Invalid HS code: {0}")]
+    InvalidHsCode(String),
+    #[error("Rate provider unavailable: {0}")]
+    ProviderUnavailable(String),
+    #[error("No tax code mapped for {0}")]
+    TaxCodeNotMapped(String),
+    #[error("No ledger accounts mapped in {0:?}")]
+    AccountNotMapped(String),
+    #[error("VAT ID lookup failed: {0}")]
+    VatIdLookupFailed(String),
+    #[error("Region group not found: {0}")]
+    RegionGroupNotFound(String),
+    #[error("Invalid region group {0}")]
+    InvalidRegionGroup(String),
+    #[error("Invalid override rule {0}")]
+    InvalidOverrideRule(String),
+    #[error("Invalid update {0}")]
+    InvalidUpdate(String),
 }
 
 #[derive(Debug, Error, Serialize)]
@@ -46,6 +105,8 @@ pub enum ProcessingError {
     DatabaseError(DatabaseError),
     #[error("Invalid amount")]
     InvalidAmount,
+    #[error("Invalid invoice line: {0}")]
+    InvalidLine(usize),
 }
 
 impl From<InputValidationError> for ProcessingError {
@@ -59,3 +120,71 @@ impl From<DatabaseError> for ProcessingError {
         ProcessingError::DatabaseError(err)
     }
 }
+
+#[derive(Debug, Error, Serialize)]
+pub enum DatasetError {
+    #[error("Invalid JSON in {0}: {1}")]
+    InvalidJson(String, String),
+    #[error("Unsupported schema version of {0}: {1}")]
+    UnsupportedSchemaVersion(String, u32),
+    #[error("Dataset does not match the schema: {}", format_violations(.0))]
+    SchemaViolations(Vec<SchemaViolation>),
+    #[error("Dataset has errors: {}", format_violations(.0))]
+    InvalidData(Vec<ValidationIssue>),
+}
+
+fn format_violations<T: ToString>(violations: &[T]) -> String {
+    violations
+        .iter()
+        .map(T::to_string)
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+fn did_you_mean(suggestions: &[String]) -> String {
+    if suggestions.is_empty() {
+        String::new()
+    } else {
+        format!(" - Did you mean {}?", suggestions.join(", "))
+    }
+}
+
+/// Most suggestions listed in an error.
+const MAX_SUGGESTIONS: usize = 3;
+
+/// The codes closest to a code that wasn't found, by Levenshtein distance; at most
+/// `MAX_SUGGESTIONS`, and only codes within one edit per three characters (at least one).
+pub(crate) fn suggestions<'a>(
+    code: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> Vec<String> {
+    let code = code.trim().to_ascii_uppercase();
+    let max_distance = (code.chars().count() / 3).max(1);
+    let mut matches: Vec<(usize, &str)> = candidates
+        .into_iter()
+        .map(|candidate| (levenshtein(&code, candidate), candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .collect();
+    matches.sort_unstable();
+    matches.dedup();
+    matches
+        .into_iter()
+        .take(MAX_SUGGESTIONS)
+        .map(|(_, candidate)| candidate.to_string())
+        .collect()
+}
+
+fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, a) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, b) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(a != *b);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
@@ -0,0 +1,353 @@
+//! Explanation of tax calculation decisions.
+//!
+//! This module records the decisions taken while resolving a `TaxScenario` (which
+//! agreement was selected, which threshold branch was taken, ...) and compares two
+//! scenarios, to answer questions like "why was customer A charged VAT and customer
+//! B not".
+
+use std::fmt;
+
+use serde::Serialize;
+
+use crate::confidence::Confidence;
+use crate::rate_provider::RateProvider;
+use crate::{ProcessingError, TaxCalculationType, TaxDatabase, TaxRate, TaxScenario, Warning};
+
+/// A single decision taken while resolving a scenario.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct DecisionStep {
+    /// What was checked (e.g. "agreement", "internal_b2c")
+    pub check: String,
+    /// The outcome of the check
+    pub outcome: String,
+}
+
+/// The legal basis of a rule or rate applied to a scenario.
+///
+/// Displays as "subject: citation", for notes on invoices:
+///
+/// ```
+/// # use world_tax::explain::LegalReference;
+/// let reference = LegalReference {
+///     subject: "European Union internal_b2b".to_string(),
+///     citation: "Article 196, Council Directive 2006/112/EC".to_string(),
+/// };
+/// assert_eq!(
+///     reference.to_string(),
+///     "European Union internal_b2b: Article 196, Council Directive 2006/112/EC"
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LegalReference {
+    /// What the reference is the basis of (e.g. "European Union internal_b2b", "DE rate")
+    pub subject: String,
+    /// The citation (e.g. "Article 196, Council Directive 2006/112/EC")
+    pub citation: String,
+}
+
+impl fmt::Display for LegalReference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.subject, self.citation)
+    }
+}
+
+/// Collects decision steps and legal references; disabled traces skip formatting
+/// entirely.
+pub(crate) struct Trace {
+    steps: Option<Vec<DecisionStep>>,
+    references: Vec<LegalReference>,
+}
+
+impl Trace {
+    pub(crate) fn enabled() -> Self {
+        Self {
+            steps: Some(Vec::new()),
+            references: Vec::new(),
+        }
+    }
+
+    pub(crate) fn disabled() -> Self {
+        Self {
+            steps: None,
+            references: Vec::new(),
+        }
+    }
+
+    /// Records the legal basis of an applied rule or rate, if it has one.
+    pub(crate) fn cite(&mut self, citation: Option<&str>, subject: impl FnOnce() -> String) {
+        if let (Some(_), Some(citation)) = (&self.steps, citation) {
+            let reference = LegalReference {
+                subject: subject(),
+                citation: citation.to_string(),
+            };
+            if !self.references.contains(&reference) {
+                self.references.push(reference);
+            }
+        }
+    }
+
+    pub(crate) fn record(&mut self, check: &str, outcome: impl FnOnce() -> String) {
+        if let Some(steps) = &mut self.steps {
+            steps.push(DecisionStep {
+                check: check.to_string(),
+                outcome: outcome(),
+            });
+        }
+    }
+
+    /// The decision steps and legal references.
+    pub(crate) fn into_parts(self) -> (Vec<DecisionStep>, Vec<LegalReference>) {
+        (self.steps.unwrap_or_default(), self.references)
+    }
+}
+
+/// How a scenario was resolved, step by step.
+#[derive(Debug, Serialize)]
+pub struct Explanation {
+    /// The decisions taken, in order
+    pub steps: Vec<DecisionStep>,
+    /// The resulting calculation type
+    pub calculation_type: TaxCalculationType,
+    /// The applied tax rates
+    pub rates: Vec<TaxRate>,
+    /// The calculated tax amount
+    pub tax: f64,
+    /// Issues with the result that didn't stop the calculation
+    pub warnings: Vec<Warning>,
+    /// How precisely the tax reflects the tax due, derived from the warnings
+    pub confidence: Confidence,
+    /// Legal basis of the applied rules and rates, where the data records one
+    pub legal_references: Vec<LegalReference>,
+}
+
+/// A scenario field that differs between two scenarios.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FieldChange {
+    /// Name of the field
+    pub field: String,
+    /// Value in the first scenario
+    pub left: String,
+    /// Value in the second scenario
+    pub right: String,
+}
+
+/// Why two scenarios produce a different tax.
+#[derive(Debug, Serialize)]
+pub struct DifferenceExplanation {
+    /// Fields that differ between the scenarios
+    pub changes: Vec<FieldChange>,
+    /// Explanation of the first scenario
+    pub left: Explanation,
+    /// Explanation of the second scenario
+    pub right: Explanation,
+    /// The first decision that differs, as `(left, right)`
+    pub first_divergence: Option<(Option<DecisionStep>, Option<DecisionStep>)>,
+}
+
+impl DifferenceExplanation {
+    /// Returns true if both scenarios result in the same tax.
+    pub fn is_same_tax(&self) -> bool {
+        self.left.tax == self.right.tax
+    }
+}
+
+impl TaxScenario {
+    /// Explains how the scenario is resolved for the given amount.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use world_tax::types::{Region, TransactionType, TaxScenario};
+    /// # use world_tax::provider::TaxDatabase;
+    /// # let db = TaxDatabase::new().unwrap();
+    /// let scenario = TaxScenario::new(
+    ///     Region::new("DE".to_string(), None).unwrap(),
+    ///     Region::new("FR".to_string(), None).unwrap(),
+    ///     TransactionType::B2C,
+    /// );
+    /// let explanation = scenario.explain(100.0, &db).unwrap();
+    /// assert_eq!(explanation.steps[0].check, "agreement");
+    /// ```
+    pub fn explain(&self, amount: f64, db: &TaxDatabase) -> Result<Explanation, ProcessingError> {
+        let mut trace = Trace::enabled();
+        let rates = self.trace_rates(amount, db, &mut trace)?;
+        let calculation_type = self.determine_calculation_type(db, amount)?;
+        let tax = self.sum_rates(amount, &rates, db)?;
+        let warnings = self.warnings(&calculation_type, &rates, db);
+        let confidence = Confidence::from_warnings(&warnings);
+
+        let (steps, legal_references) = trace.into_parts();
+        Ok(Explanation {
+            steps,
+            calculation_type,
+            rates,
+            tax,
+            warnings,
+            confidence,
+            legal_references,
+        })
+    }
+
+    /// The legal basis of the rules and rates applied to the scenario, where the data
+    /// records one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use world_tax::{Region, TaxDatabase, TaxScenario, TransactionType};
+    /// let db = TaxDatabase::new().unwrap();
+    /// let scenario = TaxScenario::new(
+    ///     Region::parse("DE").unwrap(),
+    ///     Region::parse("FR").unwrap(),
+    ///     TransactionType::B2B,
+    /// );
+    /// let references = scenario.legal_references(100.0, &db).unwrap();
+    /// assert_eq!(references[0].citation, "Article 196, Council Directive 2006/112/EC");
+    /// ```
+    pub fn legal_references<P: RateProvider + ?Sized>(
+        &self,
+        amount: f64,
+        db: &P,
+    ) -> Result<Vec<LegalReference>, ProcessingError> {
+        let mut trace = Trace::enabled();
+        self.trace_rates(amount, db, &mut trace)?;
+        Ok(trace.into_parts().1)
+    }
+
+    /// Lists the fields that differ between two scenarios.
+    pub fn diff(&self, other: &TaxScenario) -> Vec<FieldChange> {
+        let mut changes = Vec::new();
+        let mut compare = |field: &str, left: String, right: String| {
+            if left != right {
+                changes.push(FieldChange {
+                    field: field.to_string(),
+                    left,
+                    right,
+                });
+            }
+        };
+
+        compare(
+            "source_region",
+            self.source_region.to_string(),
+            other.source_region.to_string(),
+        );
+        compare(
+            "destination_region",
+            self.destination_region.to_string(),
+            other.destination_region.to_string(),
+        );
+        compare(
+            "transaction_type",
+            format!("{:?}", self.transaction_type),
+            format!("{:?}", other.transaction_type),
+        );
+        compare(
+            "trade_agreement_override",
+            format!("{:?}", self.trade_agreement_override),
+            format!("{:?}", other.trade_agreement_override),
+        );
+        compare(
+            "is_digital_product_or_service",
+            self.is_digital_product_or_service.to_string(),
+            other.is_digital_product_or_service.to_string(),
+        );
+        compare(
+            "exemption_certificate",
+            format!("{:?}", self.exemption_certificate),
+            format!("{:?}", other.exemption_certificate),
+        );
+        compare(
+            "buyer_vat_id",
+            format!("{:?}", self.buyer_vat_id),
+            format!("{:?}", other.buyer_vat_id),
+        );
+        compare(
+            "threshold_policy",
+            format!("{:?}", self.threshold_policy),
+            format!("{:?}", other.threshold_policy),
+        );
+        compare(
+            "vat_rate",
+            format!("{:?}", self.vat_rate),
+            format!("{:?}", other.vat_rate),
+        );
+        compare(
+            "profile",
+            format!("{:?}", self.profile),
+            format!("{:?}", other.profile),
+        );
+        compare(
+            "incoterm",
+            format!("{:?}", self.incoterm),
+            format!("{:?}", other.incoterm),
+        );
+        compare(
+            "date",
+            format!("{:?}", self.date),
+            format!("{:?}", other.date),
+        );
+        compare(
+            "supply_kind",
+            format!("{:?}", self.supply_kind),
+            format!("{:?}", other.supply_kind),
+        );
+        let attributes = |scenario: &TaxScenario| {
+            let mut attributes: Vec<String> = scenario
+                .attributes
+                .iter()
+                .map(|(key, value)| format!("{key}={value}"))
+                .collect();
+            attributes.sort_unstable();
+            attributes.join(", ")
+        };
+        compare("attributes", attributes(self), attributes(other));
+
+        changes
+    }
+
+    /// Explains why this scenario and `other` produce a different tax for the same amount.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use world_tax::types::{Region, TransactionType, TaxScenario};
+    /// # use world_tax::provider::TaxDatabase;
+    /// # use world_tax::vat_id::VatId;
+    /// # let db = TaxDatabase::new().unwrap();
+    /// let consumer = TaxScenario::new(
+    ///     Region::new("DE".to_string(), None).unwrap(),
+    ///     Region::new("FR".to_string(), None).unwrap(),
+    ///     TransactionType::B2C,
+    /// );
+    /// let mut business = consumer
+    ///     .clone()
+    ///     .with_buyer_vat_id(VatId::parse("FR40303265045").unwrap());
+    /// business.transaction_type = TransactionType::B2B;
+    ///
+    /// let difference = consumer.explain_difference(&business, 100.0, &db).unwrap();
+    /// assert_eq!(difference.changes[0].field, "transaction_type");
+    /// assert!(!difference.is_same_tax());
+    /// ```
+    pub fn explain_difference(
+        &self,
+        other: &TaxScenario,
+        amount: f64,
+        db: &TaxDatabase,
+    ) -> Result<DifferenceExplanation, ProcessingError> {
+        let left = self.explain(amount, db)?;
+        let right = other.explain(amount, db)?;
+
+        let steps = left.steps.len().max(right.steps.len());
+        let first_divergence = (0..steps)
+            .map(|i| (left.steps.get(i).cloned(), right.steps.get(i).cloned()))
+            .find(|(l, r)| l != r);
+
+        Ok(DifferenceExplanation {
+            changes: self.diff(other),
+            left,
+            right,
+            first_divergence,
+        })
+    }
+}
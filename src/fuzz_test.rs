@@ -0,0 +1,57 @@
+#[cfg(all(test, feature = "arbitrary"))]
+mod tests {
+    use std::collections::HashMap;
+
+    use arbitrary::{Arbitrary, Unstructured};
+
+    use crate::calculator::{TaxCalculator, TaxRequest};
+    use crate::types::Country;
+    use crate::{Region, TaxDatabase, TaxScenario, TradeAgreement};
+
+    /// Deterministic pseudo-random bytes (xorshift), as a fuzzer would feed them.
+    fn bytes(seed: u64, len: usize) -> Vec<u8> {
+        let mut state = seed.wrapping_mul(0x9E37_79B9_7F4A_7C15) | 1;
+        (0..len)
+            .map(|_| {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                state as u8
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_arbitrary_scenarios() {
+        let db = TaxDatabase::new().unwrap();
+        for seed in 0..2000 {
+            let data = bytes(seed, 512);
+            let mut u = Unstructured::new(&data);
+            let scenario = TaxScenario::arbitrary(&mut u).unwrap();
+            let amount = f64::from(u32::arbitrary(&mut u).unwrap()) / 100.0;
+            // Malformed input may fail, but mustn't panic
+            let _ = db.quote(TaxRequest::new(scenario.clone(), amount));
+            let _ = scenario.explain(amount, &db);
+        }
+    }
+
+    #[test]
+    fn test_arbitrary_datasets() {
+        for seed in 0..200 {
+            let data = bytes(seed, 4096);
+            let mut u = Unstructured::new(&data);
+            let countries = HashMap::<String, Country>::arbitrary(&mut u).unwrap();
+            let agreements = HashMap::<String, TradeAgreement>::arbitrary(&mut u).unwrap();
+            let region = Region::arbitrary(&mut u).unwrap();
+
+            let countries = serde_json::to_string(&countries).unwrap();
+            let agreements = serde_json::to_string(&agreements).unwrap();
+            if let Ok(db) = TaxDatabase::from_json(&countries, &agreements) {
+                let _ = db.validate();
+                let scenario =
+                    TaxScenario::new(region.clone(), region, crate::TransactionType::B2C);
+                let _ = db.quote(TaxRequest::new(scenario, 100.0));
+            }
+        }
+    }
+}
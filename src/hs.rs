@@ -0,0 +1,196 @@
+//! HS code classification helper.
+//!
+//! Customs duty depends on the Harmonized System (HS) code of a product, but most
+//! merchants only have free-text product types. This module maps product types to
+//! HS chapters (and headings, where a keyword is specific enough) by keyword. The
+//! built-in table covers common e-commerce categories; extend it with your own
+//! keywords, which take precedence over the built-in ones.
+//!
+//! The classification is an estimate for landed cost quotes, not a binding tariff
+//! classification.
+
+use serde::Serialize;
+
+use crate::customs::CustomsItem;
+use crate::errors::DatabaseError;
+
+/// Keywords, HS chapter and heading of the built-in table.
+const BUILTIN: &[(&str, u8, Option<u16>)] = &[
+    ("coffee", 9, Some(901)),
+    ("tea", 9, Some(902)),
+    ("spice", 9, None),
+    ("chocolate", 18, Some(1806)),
+    ("wine", 22, Some(2204)),
+    ("beer", 22, Some(2203)),
+    ("perfume", 33, Some(3303)),
+    ("cosmetic", 33, Some(3304)),
+    ("makeup", 33, Some(3304)),
+    ("shampoo", 33, Some(3305)),
+    ("soap", 34, Some(3401)),
+    ("candle", 34, Some(3406)),
+    ("handbag", 42, Some(4202)),
+    ("backpack", 42, Some(4202)),
+    ("wallet", 42, Some(4202)),
+    ("book", 49, Some(4901)),
+    ("poster", 49, Some(4911)),
+    ("t-shirt", 61, Some(6109)),
+    ("tshirt", 61, Some(6109)),
+    ("sweater", 61, Some(6110)),
+    ("hoodie", 61, Some(6110)),
+    ("sock", 61, Some(6115)),
+    ("jacket", 62, None),
+    ("coat", 62, None),
+    ("jeans", 62, None),
+    ("dress", 62, None),
+    ("shirt", 62, None),
+    ("shoe", 64, None),
+    ("sneaker", 64, None),
+    ("boot", 64, None),
+    ("hat", 65, None),
+    ("cap", 65, None),
+    ("mug", 69, Some(6912)),
+    ("jewelry", 71, Some(7113)),
+    ("jewellery", 71, Some(7113)),
+    ("necklace", 71, Some(7113)),
+    ("laptop", 84, Some(8471)),
+    ("computer", 84, Some(8471)),
+    ("smartphone", 85, Some(8517)),
+    ("phone", 85, Some(8517)),
+    ("headphone", 85, Some(8518)),
+    ("speaker", 85, Some(8518)),
+    ("bicycle", 87, Some(8712)),
+    ("sunglasses", 90, Some(9004)),
+    ("watch", 91, None),
+    ("furniture", 94, None),
+    ("chair", 94, Some(9401)),
+    ("lamp", 94, Some(9405)),
+    ("toy", 95, Some(9503)),
+    ("puzzle", 95, Some(9503)),
+    ("painting", 97, Some(9701)),
+];
+
+/// A keyword of a classification table.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct HsEntry {
+    /// Lowercase keyword; may span several words (e.g. "yoga mat")
+    pub keyword: String,
+    /// HS chapter (the first two digits of the HS code)
+    pub chapter: u8,
+    /// HS heading (the first four digits of the HS code), if known
+    pub heading: Option<u16>,
+}
+
+/// Keywords mapped to HS chapters and headings.
+///
+/// # Examples
+///
+/// ```
+/// # use world_tax::hs::HsTable;
+/// let mut table = HsTable::builtin();
+/// assert_eq!(table.classify("Organic Cotton T-Shirt").unwrap().heading, Some(6109));
+///
+/// table.add("yoga mat", 39, None).unwrap();
+/// assert_eq!(table.classify("Cork Yoga Mats").unwrap().chapter, 39);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct HsTable {
+    entries: Vec<HsEntry>,
+}
+
+impl HsTable {
+    /// Creates an empty table.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a table with keywords for common e-commerce product categories.
+    pub fn builtin() -> Self {
+        let entries = BUILTIN
+            .iter()
+            .map(|&(keyword, chapter, heading)| HsEntry {
+                keyword: keyword.to_string(),
+                chapter,
+                heading,
+            })
+            .collect();
+        Self { entries }
+    }
+
+    /// Adds a keyword; keywords added later take precedence over earlier ones.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::InvalidHsCode` if the chapter isn't between 1 and 97, or
+    /// the heading doesn't belong to the chapter.
+    pub fn add(
+        &mut self,
+        keyword: &str,
+        chapter: u8,
+        heading: Option<u16>,
+    ) -> Result<&mut Self, DatabaseError> {
+        let valid = (1..=97).contains(&chapter)
+            && heading.is_none_or(|heading| heading / 100 == u16::from(chapter));
+        if !valid {
+            let code = match heading {
+                Some(heading) => format!("{:04}", heading),
+                None => format!("{:02}", chapter),
+            };
+            return Err(DatabaseError::InvalidHsCode(code));
+        }
+        self.entries.push(HsEntry {
+            keyword: keyword.trim().to_lowercase(),
+            chapter,
+            heading,
+        });
+        Ok(self)
+    }
+
+    /// The keywords of the table.
+    pub fn entries(&self) -> &[HsEntry] {
+        &self.entries
+    }
+
+    /// Classifies a free-text product type.
+    ///
+    /// Keywords match whole words, ignoring case and a plural "s"; the longest matching
+    /// keyword wins, then the one added last.
+    pub fn classify(&self, product_type: &str) -> Option<&HsEntry> {
+        let product = words(product_type);
+        self.entries
+            .iter()
+            .rev()
+            .filter(|entry| contains(&product, &words(&entry.keyword)))
+            .fold(None, |best: Option<&HsEntry>, entry| match best {
+                Some(best) if best.keyword.len() >= entry.keyword.len() => Some(best),
+                _ => Some(entry),
+            })
+    }
+
+    /// Classifies a product type and creates a customs item with its HS chapter.
+    pub fn customs_item(&self, value: f64, product_type: &str) -> Option<CustomsItem> {
+        self.classify(product_type)
+            .map(|entry| CustomsItem::new(value, entry.chapter))
+    }
+}
+
+/// Splits text into lowercase words; hyphens are kept (e.g. "t-shirt").
+fn words(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric() && c != '-')
+        .filter(|word| !word.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Returns true if `keyword` occurs as consecutive words in `words`.
+fn contains(words: &[String], keyword: &[String]) -> bool {
+    if keyword.is_empty() {
+        return false;
+    }
+    words.windows(keyword.len()).any(|window| {
+        window
+            .iter()
+            .zip(keyword)
+            .all(|(word, key)| word == key || word.strip_suffix('s') == Some(key.as_str()))
+    })
+}
@@ -0,0 +1,64 @@
+#[cfg(test)]
+mod tests {
+    use crate::hs::HsTable;
+    use crate::DatabaseError;
+
+    #[test]
+    fn test_builtin_classification() {
+        let table = HsTable::builtin();
+        assert_eq!(
+            table.classify("Organic Cotton T-Shirt").map(|e| e.heading),
+            Some(Some(6109))
+        );
+        assert_eq!(table.classify("Running shoes").map(|e| e.chapter), Some(64));
+        assert_eq!(
+            table.classify("Wireless Headphones").map(|e| e.heading),
+            Some(Some(8518))
+        );
+        // Whole words only; "smartphone" wins over "phone"
+        assert_eq!(
+            table
+                .classify("Smartphone case")
+                .map(|e| e.keyword.as_str()),
+            Some("smartphone")
+        );
+        assert!(table.classify("Phonebook").is_none());
+        assert!(table.classify("Gift card").is_none());
+    }
+
+    #[test]
+    fn test_user_keywords() {
+        let mut table = HsTable::builtin();
+        table
+            .add("Yoga Mat", 39, Some(3918))
+            .expect("Valid HS code")
+            .add("book", 48, None)
+            .expect("Valid HS code");
+
+        assert_eq!(
+            table.classify("cork yoga mats").map(|e| e.chapter),
+            Some(39)
+        );
+        // Later keywords take precedence
+        assert_eq!(table.classify("Sketch book").map(|e| e.chapter), Some(48));
+
+        let item = table
+            .customs_item(20.0, "Paperback book")
+            .expect("Classified");
+        assert_eq!(item.hs_chapter, 48);
+    }
+
+    #[test]
+    fn test_invalid_hs_codes() {
+        let mut table = HsTable::new();
+        assert!(matches!(
+            table.add("gadget", 98, None),
+            Err(DatabaseError::InvalidHsCode(code)) if code == "98"
+        ));
+        assert!(matches!(
+            table.add("gadget", 85, Some(8471)),
+            Err(DatabaseError::InvalidHsCode(code)) if code == "8471"
+        ));
+        assert!(table.entries().is_empty());
+    }
+}
@@ -0,0 +1,211 @@
+//! EU import VAT on low-value consignments.
+//!
+//! Goods sold from outside the EU to EU consumers are taxed on import. For
+//! consignments with an intrinsic value of up to €150, import VAT is handled in
+//! one of two ways:
+//!
+//! - Import One-Stop-Shop (IOSS): the seller charges destination VAT at checkout and
+//!   the import itself is exempt.
+//! - Special arrangements: without IOSS, the postal operator or courier collects the
+//!   import VAT from the consignee on delivery, at the standard rate.
+//!
+//! Above €150, goods go through the standard import procedure, and import VAT is due
+//! when the goods are cleared by customs (usually collected on delivery, too).
+//!
+//! Outside of the EU's schemes, countries tax sales from sellers abroad by their
+//! `import_rules`: many require sellers of digital services to consumers, and some
+//! sellers of low-value goods, to register and charge the country's tax. Business
+//! buyers of services usually account for the tax by reverse charge, but not everywhere.
+//! Switzerland and Norway only require it from sellers whose turnover in the country
+//! exceeds a threshold; below it, low-value goods are taxed on import.
+
+use serde::Serialize;
+
+use crate::calculation::policy_note;
+use crate::explain::Trace;
+use crate::profile::CalculationProfile;
+use crate::rate_provider::RateProvider;
+use crate::types::VatRate;
+use crate::{
+    DatabaseError, ProcessingError, TaxCalculationType, TaxDatabase, TaxRate, TaxScenario,
+    TransactionType,
+};
+
+/// Intrinsic value (in euros) up to which a consignment counts as low-value.
+pub const LOW_VALUE_CONSIGNMENT_LIMIT: f64 = 150.0;
+
+/// How import VAT on a consignment is handled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ImportScheme {
+    /// The seller charges VAT at checkout under the Import One-Stop-Shop
+    Ioss,
+    /// The carrier collects import VAT at the standard rate from the consignee
+    SpecialArrangements,
+    /// Import VAT is due on customs clearance (consignments above €150)
+    StandardImport,
+}
+
+/// The import VAT of a consignment, and when it's paid.
+#[derive(Debug, Clone, Serialize)]
+pub struct ImportVat {
+    /// How import VAT is handled
+    pub scheme: ImportScheme,
+    /// The applied tax rates
+    pub rates: Vec<TaxRate>,
+    /// The import VAT amount
+    pub vat: f64,
+    /// VAT the seller collects at checkout
+    pub collected_at_checkout: f64,
+    /// VAT the consignee pays on delivery; show it as "taxes due on delivery"
+    pub due_on_delivery: f64,
+}
+
+impl TaxScenario {
+    /// Returns true if the scenario imports goods into the EU from outside.
+    pub fn is_eu_import<P: RateProvider + ?Sized>(&self, db: &P) -> bool {
+        let Ok(eu) = db.get_rule("EU") else {
+            return false;
+        };
+        !self.is_digital_product_or_service
+            && eu.members.contains(&self.destination_region.country)
+            && !eu.members.contains(&self.source_region.country)
+    }
+
+    /// Returns true if the seller charges VAT at checkout under IOSS.
+    pub(crate) fn is_ioss_sale<P: RateProvider + ?Sized>(
+        &self,
+        consignment_value: f64,
+        profile: &CalculationProfile,
+        db: &P,
+    ) -> bool {
+        profile.schemes.ioss
+            && self.transaction_type == TransactionType::B2C
+            && consignment_value <= LOW_VALUE_CONSIGNMENT_LIMIT
+            && self.is_eu_import(db)
+    }
+
+    /// The destination's import rule for a cross-border sale without trade agreement,
+    /// with a description of the sale it covers.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::ExchangeRateNotFound` if the seller's turnover has to be
+    /// converted to the currency of the low-value goods threshold, without exchange rate.
+    pub(crate) fn import_rule<P: RateProvider + ?Sized>(
+        &self,
+        amount: f64,
+        db: &P,
+        trace: &mut Trace,
+    ) -> Result<Option<(&'static str, TaxCalculationType)>, DatabaseError> {
+        let Some(rules) = db
+            .get_country(&self.destination_region.country)
+            .ok()
+            .and_then(|country| country.import_rules.clone())
+        else {
+            return Ok(None);
+        };
+        if let Some(threshold) = rules.low_value_goods_threshold.filter(|_| {
+            self.transaction_type == TransactionType::B2C && !self.is_digital_product_or_service
+        }) {
+            let policy =
+                self.unknown_turnover_policy(Some(threshold), self.threshold_policy, trace);
+            let (turnover, currency) =
+                self.threshold_amount(Some(threshold), amount, policy, db, trace)?;
+            if threshold.is_below(turnover, currency, policy)? {
+                trace.record("import_threshold", || {
+                    format!(
+                        "{} below the low-value goods threshold {:?}{}: taxed on import",
+                        turnover,
+                        threshold,
+                        policy_note(policy)
+                    )
+                });
+                return Ok(None);
+            }
+        }
+        Ok(match self.transaction_type {
+            TransactionType::B2C if self.is_digital_product_or_service => rules
+                .b2c_digital
+                .clone()
+                .map(|calc_type| ("B2C digital", calc_type)),
+            TransactionType::B2C => rules
+                .b2c_low_value_goods
+                .clone()
+                .filter(|_| {
+                    rules
+                        .low_value_goods_limit
+                        .is_none_or(|limit| amount <= limit)
+                })
+                .map(|calc_type| ("B2C low-value goods", calc_type)),
+            TransactionType::B2B if self.is_digital_product_or_service => rules
+                .b2b_services
+                .clone()
+                .map(|calc_type| ("B2B services", calc_type)),
+            TransactionType::B2B => None,
+        })
+    }
+
+    /// Calculates the import VAT of a consignment into the EU.
+    ///
+    /// The consignment value is its intrinsic value in euros (excluding transport and
+    /// insurance). Returns `None` if the scenario isn't an EU import.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use world_tax::import::ImportScheme;
+    /// # use world_tax::{Region, TaxDatabase, TaxScenario, TransactionType};
+    /// let db = TaxDatabase::fixture();
+    /// let scenario = TaxScenario::new(
+    ///     Region::new("CA".to_string(), None).unwrap(),
+    ///     Region::new("DE".to_string(), None).unwrap(),
+    ///     TransactionType::B2C,
+    /// );
+    ///
+    /// let import = scenario.import_vat(100.0, &db).unwrap().unwrap();
+    /// assert_eq!(import.scheme, ImportScheme::SpecialArrangements);
+    /// assert_eq!(import.due_on_delivery, 19.0);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the destination's rates can't be resolved.
+    pub fn import_vat(
+        &self,
+        consignment_value: f64,
+        db: &TaxDatabase,
+    ) -> Result<Option<ImportVat>, ProcessingError> {
+        if !self.is_eu_import(db) {
+            return Ok(None);
+        }
+        let profile = self.resolve_profile(db)?;
+
+        let scheme = if self.is_ioss_sale(consignment_value, &profile, db) {
+            ImportScheme::Ioss
+        } else if consignment_value <= LOW_VALUE_CONSIGNMENT_LIMIT {
+            ImportScheme::SpecialArrangements
+        } else {
+            ImportScheme::StandardImport
+        };
+        // Special arrangements only allow the standard rate
+        let vat_rate = match scheme {
+            ImportScheme::SpecialArrangements => Some(&VatRate::Standard),
+            _ => self.vat_rate.as_ref(),
+        };
+
+        let rates = db.get_rate(&self.destination_region.country, None, vat_rate)?;
+        let vat = self.sum_rates(consignment_value, &rates, db)?;
+        let (collected_at_checkout, due_on_delivery) = match scheme {
+            ImportScheme::Ioss => (vat, 0.0),
+            _ => (0.0, vat),
+        };
+        Ok(Some(ImportVat {
+            scheme,
+            rates,
+            vat,
+            collected_at_checkout,
+            due_on_delivery,
+        }))
+    }
+}
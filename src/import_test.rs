@@ -0,0 +1,93 @@
+#[cfg(test)]
+mod tests {
+    use crate::calculator::{TaxCalculator, TaxRequest};
+    use crate::import::ImportScheme;
+    use crate::profile::{CalculationProfile, SchemeFlags};
+    use crate::types::VatRate;
+    use crate::{Region, TaxCalculationType, TaxDatabase, TaxScenario, TransactionType};
+
+    fn import_to_germany() -> TaxScenario {
+        TaxScenario::new(
+            Region::parse("CA-BC").expect("Valid region"),
+            Region::parse("DE").expect("Valid region"),
+            TransactionType::B2C,
+        )
+    }
+
+    #[test]
+    fn test_special_arrangements() {
+        let db = TaxDatabase::fixture();
+        let mut scenario = import_to_germany();
+        scenario.vat_rate = Some(VatRate::Reduced);
+
+        let import = scenario
+            .import_vat(100.0, &db)
+            .expect("Import VAT should be calculated")
+            .expect("Scenario is an EU import");
+        assert_eq!(import.scheme, ImportScheme::SpecialArrangements);
+        assert_eq!(import.vat, 19.0); // Standard rate only
+        assert_eq!(import.collected_at_checkout, 0.0);
+        assert_eq!(import.due_on_delivery, 19.0);
+
+        let quote = db
+            .quote(TaxRequest::new(scenario, 100.0))
+            .expect("Quote should succeed");
+        assert_eq!(quote.calculation_type, TaxCalculationType::ZeroRated);
+    }
+
+    #[test]
+    fn test_ioss() {
+        let mut db = TaxDatabase::fixture();
+        db.register_profile(
+            "ioss",
+            CalculationProfile {
+                schemes: SchemeFlags {
+                    ioss: true,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+        );
+        let scenario = import_to_germany().with_profile("ioss");
+
+        let import = scenario
+            .import_vat(100.0, &db)
+            .expect("Import VAT should be calculated")
+            .expect("Scenario is an EU import");
+        assert_eq!(import.scheme, ImportScheme::Ioss);
+        assert_eq!(import.collected_at_checkout, 19.0);
+        assert_eq!(import.due_on_delivery, 0.0);
+
+        let quote = db
+            .quote(TaxRequest::new(scenario.clone(), 100.0))
+            .expect("Quote should succeed");
+        assert_eq!(quote.calculation_type, TaxCalculationType::Destination);
+        assert_eq!(quote.tax, 19.0);
+
+        // Above the low-value limit, IOSS doesn't apply
+        let import = scenario
+            .import_vat(200.0, &db)
+            .expect("Import VAT should be calculated")
+            .expect("Scenario is an EU import");
+        assert_eq!(import.scheme, ImportScheme::StandardImport);
+        assert_eq!(import.due_on_delivery, 38.0);
+    }
+
+    #[test]
+    fn test_not_an_import() {
+        let db = TaxDatabase::fixture();
+        let scenario = TaxScenario::new(
+            Region::parse("FR").expect("Valid region"),
+            Region::parse("DE").expect("Valid region"),
+            TransactionType::B2C,
+        );
+        assert!(scenario
+            .import_vat(100.0, &db)
+            .expect("Import VAT should be calculated")
+            .is_none());
+
+        let mut digital = import_to_germany();
+        digital.is_digital_product_or_service = true;
+        assert!(!digital.is_eu_import(&db));
+    }
+}
@@ -0,0 +1,663 @@
+//! Invoice calculation with line items.
+//!
+//! An `Invoice` applies a scenario to several line items at once. Rates are resolved
+//! a single time for the invoice (using its net total for threshold checks), and tax
+//! is rounded per line, so the result lines up with what ERPs and invoicing systems
+//! book. Lines may be priced net or gross (tax-inclusive); gross lines are normalized
+//! to their net amount before totaling. Flat per-transaction fees of the destination
+//! (e.g. the Colorado Retail Delivery Fee) are added on top, and rate brackets of the
+//! state (e.g. New York's exemption of clothing under $110) apply per line.
+//!
+//! Supplies with portions at different VAT rates (e.g. a hotel stay with breakfast in
+//! Germany, at 7% and 19%) are a single line with components; each portion is taxed
+//! at its own rate, and listed with its tax on the line. Lines of a category with its
+//! own VAT rate in the taxing country (e.g. food at Japan's reduced 8%) are taxed at
+//! that rate, unless the scenario sets one.
+
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use typeshare::typeshare;
+
+use crate::calculation::{item_net_of_gross_decimal, sum_item_rates_decimal};
+use crate::explain::LegalReference;
+use crate::profile::Rounding;
+use crate::rate_provider::{self, RateProvider};
+use crate::types::{RateBracket, SupplyKind};
+use crate::{
+    ProcessingError, RateSource, Region, TaxCalculationType, TaxRate, TaxScenario, VatRate,
+};
+
+/// How tax is rounded on a line with multiple units.
+#[typeshare]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LineRounding {
+    /// Calculate tax on the extended amount (unit price times quantity), then round
+    #[default]
+    PerLine,
+    /// Round the tax of a single unit, then multiply by the quantity
+    PerUnit,
+}
+
+/// Which line absorbs the rounding difference of an invoice, so the sum of the line
+/// taxes equals the tax of the net total.
+#[typeshare]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "content", rename_all = "snake_case")]
+pub enum RoundingAdjustment {
+    /// Keep the rounded line taxes; the difference is only reported
+    #[default]
+    None,
+    /// The line with the largest tax (the first, if several)
+    LargestLine,
+    /// The last line
+    LastLine,
+    /// The line at an index, in the order of the invoice lines
+    Line(usize),
+}
+
+/// A portion of a line with its VAT rate.
+type Portion = (Option<VatRate>, LineItem);
+
+/// The resolved rates of a VAT rate.
+type Band = (Option<VatRate>, Vec<TaxRate>);
+
+/// A line on an invoice.
+#[typeshare]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LineItem {
+    /// Price per unit; net, unless `tax_inclusive` is set
+    pub unit_price: Decimal,
+    /// Number of units; may be fractional (e.g. kilograms)
+    pub quantity: Decimal,
+    /// Whether the unit price includes tax
+    #[serde(default)]
+    pub tax_inclusive: bool,
+    /// Category of the item (e.g. "clothing"), matched against the state's rate brackets
+    /// and the country's category rates
+    #[serde(default)]
+    pub category: Option<String>,
+    /// Portions of the unit price taxed at their own VAT rate; the rest of the unit
+    /// price is taxed at the line's rate
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub components: Vec<LineComponent>,
+}
+
+/// A portion of a line taxed at its own VAT rate (e.g. the breakfast of a hotel stay).
+#[typeshare]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LineComponent {
+    /// Price per unit of the portion; net, unless the line is `tax_inclusive`
+    pub unit_price: Decimal,
+    /// VAT rate of the portion
+    pub vat_rate: VatRate,
+}
+
+impl LineItem {
+    /// Creates a new line item with a net (tax-exclusive) unit price.
+    pub fn new(unit_price: Decimal, quantity: Decimal) -> Self {
+        Self {
+            unit_price,
+            quantity,
+            tax_inclusive: false,
+            category: None,
+            components: Vec::new(),
+        }
+    }
+
+    /// Creates a new line item with a gross (tax-inclusive) unit price.
+    pub fn gross(unit_price: Decimal, quantity: Decimal) -> Self {
+        Self {
+            unit_price,
+            quantity,
+            tax_inclusive: true,
+            category: None,
+            components: Vec::new(),
+        }
+    }
+
+    /// Sets the category of the item.
+    pub fn with_category(mut self, category: impl Into<String>) -> Self {
+        self.category = Some(category.into());
+        self
+    }
+
+    /// Taxes a portion of the unit price at its own VAT rate.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use world_tax::invoice::{Invoice, LineItem};
+    /// # use world_tax::{Region, TaxDatabase, TaxScenario, TransactionType, VatRate};
+    /// # use rust_decimal_macros::dec;
+    /// let db = TaxDatabase::fixture();
+    /// let scenario = TaxScenario::new(
+    ///     Region::parse("DE").unwrap(),
+    ///     Region::parse("DE").unwrap(),
+    ///     TransactionType::B2C,
+    /// );
+    ///
+    /// // A night at 115, of which 100 for the room at 7%, and 15 for breakfast at 19%
+    /// let mut invoice = Invoice::new(scenario);
+    /// invoice.add_line(LineItem::new(dec!(115), dec!(1)).with_component(VatRate::Reduced, dec!(100)));
+    ///
+    /// let totals = invoice.calculate(&db).unwrap();
+    /// assert_eq!(totals.tax, dec!(9.85));
+    /// assert_eq!(totals.lines[0].components.len(), 2);
+    /// ```
+    pub fn with_component(mut self, vat_rate: VatRate, unit_price: Decimal) -> Self {
+        self.components.push(LineComponent {
+            unit_price,
+            vat_rate,
+        });
+        self
+    }
+
+    /// Splits the line into its portions, with their VAT rate; the rest of the unit
+    /// price comes first, at `vat_rate`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `ProcessingError::InvalidAmount` if the components exceed the unit price.
+    fn portions(&self, vat_rate: Option<VatRate>) -> Result<Vec<Portion>, ProcessingError> {
+        if self.components.is_empty() {
+            return Ok(vec![(vat_rate, self.clone())]);
+        }
+        let portion = |unit_price: Decimal| LineItem {
+            unit_price,
+            quantity: self.quantity,
+            tax_inclusive: self.tax_inclusive,
+            category: self.category.clone(),
+            components: Vec::new(),
+        };
+
+        let rest: Decimal = self.unit_price
+            - self
+                .components
+                .iter()
+                .map(|component| component.unit_price)
+                .sum::<Decimal>();
+        if rest < Decimal::ZERO {
+            return Err(ProcessingError::InvalidAmount);
+        }
+        let mut portions = Vec::new();
+        if !rest.is_zero() {
+            portions.push((vat_rate, portion(rest)));
+        }
+        for component in &self.components {
+            portions.push((Some(component.vat_rate), portion(component.unit_price)));
+        }
+        Ok(portions)
+    }
+
+    /// Amount of the line (unit price times quantity); gross if `tax_inclusive` is set.
+    pub fn amount(&self) -> Decimal {
+        self.unit_price * self.quantity
+    }
+
+    /// Net amount of the line at the given rates, without rounding.
+    fn net_amount(&self, rates: &[TaxRate]) -> Decimal {
+        if self.tax_inclusive {
+            item_net_of_gross_decimal(self.unit_price, self.quantity, rates)
+        } else {
+            self.amount()
+        }
+    }
+}
+
+/// The calculated tax of a single line.
+#[derive(Debug, Clone, Serialize)]
+pub struct LineTax {
+    /// Net amount of the line
+    pub net: Decimal,
+    /// Rounded tax of the line
+    pub tax: Decimal,
+    /// Gross amount of the line
+    pub gross: Decimal,
+    /// Tax per portion, if the line has components; the rest of the unit price first
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub components: Vec<ComponentTax>,
+}
+
+/// The calculated tax of a portion of a line.
+#[derive(Debug, Clone, Serialize)]
+pub struct ComponentTax {
+    /// VAT rate of the portion; none for the scenario's default rate
+    pub vat_rate: Option<VatRate>,
+    /// The applied tax rates
+    pub rates: Vec<TaxRate>,
+    /// Net amount of the portion
+    pub net: Decimal,
+    /// Rounded tax of the portion
+    pub tax: Decimal,
+    /// Gross amount of the portion
+    pub gross: Decimal,
+}
+
+/// A flat fee charged on an invoice.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FeeCharge {
+    /// Name of the fee
+    pub name: String,
+    /// Amount of the fee
+    pub amount: Decimal,
+}
+
+/// The calculated totals of an invoice.
+#[derive(Debug, Clone, Serialize)]
+pub struct InvoiceTotals {
+    /// The applied tax rates; those of line components are listed on the lines
+    pub rates: Vec<TaxRate>,
+    /// Tax per line, in the order of the invoice lines
+    pub lines: Vec<LineTax>,
+    /// Sum of the net amounts
+    pub net: Decimal,
+    /// Sum of the line taxes
+    pub tax: Decimal,
+    /// Sum of the rounded line taxes minus the tax of all lines, rounded once; absorbed
+    /// by `adjusted_line` if the invoice has a `rounding_adjustment`
+    pub rounding_difference: Decimal,
+    /// Index of the line whose tax was adjusted by the rounding difference
+    pub adjusted_line: Option<usize>,
+    /// Whether the invoice must show the seller's registration number for the buyer
+    /// to deduct the tax (Japan's qualified invoices)
+    pub qualified_invoice: bool,
+    /// Sum of the gross amounts
+    pub gross: Decimal,
+    /// Flat fees of the destination
+    pub fees: Vec<FeeCharge>,
+    /// Gross amount plus fees
+    pub total: Decimal,
+    /// Legal basis of the applied rules and rates, for notes on the invoice (e.g.
+    /// the article a reverse charge is based on)
+    pub legal_references: Vec<LegalReference>,
+}
+
+/// An invoice with line items for a single scenario.
+///
+/// Line taxes are rounded according to the scenario's calculation profile
+/// (2 decimal places, half away from zero, by default).
+///
+/// # Examples
+///
+/// ```
+/// # use world_tax::invoice::{Invoice, LineItem, LineRounding};
+/// # use world_tax::{Region, TaxDatabase, TaxScenario, TransactionType};
+/// # use rust_decimal_macros::dec;
+/// let db = TaxDatabase::fixture();
+/// let scenario = TaxScenario::new(
+///     Region::new("DE".to_string(), None).unwrap(),
+///     Region::new("DE".to_string(), None).unwrap(),
+///     TransactionType::B2C,
+/// );
+///
+/// let mut invoice = Invoice::new(scenario);
+/// invoice.add_line(LineItem::new(dec!(0.99), dec!(3)));
+///
+/// let totals = invoice.calculate(&db).unwrap();
+/// assert_eq!(totals.tax, dec!(0.56)); // 19% of 2.97
+///
+/// invoice.line_rounding = LineRounding::PerUnit;
+/// let totals = invoice.calculate(&db).unwrap();
+/// assert_eq!(totals.tax, dec!(0.57)); // 19% of 0.99, rounded to 0.19, times 3
+/// ```
+#[derive(Debug, Clone)]
+pub struct Invoice {
+    /// The scenario all lines are calculated with
+    pub scenario: TaxScenario,
+    /// The invoice lines
+    pub lines: Vec<LineItem>,
+    /// How tax is rounded on lines with multiple units
+    pub line_rounding: LineRounding,
+    /// What is supplied, to match flat fees; physical or digital goods, by the scenario
+    pub supply_kind: SupplyKind,
+    /// Which line absorbs the rounding difference
+    pub rounding_adjustment: RoundingAdjustment,
+}
+
+impl Invoice {
+    /// Creates an empty invoice for the scenario.
+    pub fn new(scenario: TaxScenario) -> Self {
+        let supply_kind = scenario.supply_kind();
+        Self {
+            scenario,
+            lines: Vec::new(),
+            line_rounding: LineRounding::default(),
+            supply_kind,
+            rounding_adjustment: RoundingAdjustment::default(),
+        }
+    }
+
+    /// Adds a line to the invoice.
+    pub fn add_line(&mut self, line: LineItem) -> &mut Self {
+        self.lines.push(line);
+        self
+    }
+
+    /// Calculates the tax of all lines with the rates of a provider (e.g. a `TaxDatabase`,
+    /// or a `DatedView` of it).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the rates can't be resolved, and
+    /// `ProcessingError::InvalidLine` if the line of the rounding adjustment doesn't
+    /// exist.
+    pub fn calculate<P: RateProvider + ?Sized>(
+        &self,
+        db: &P,
+    ) -> Result<InvoiceTotals, ProcessingError> {
+        let rounding = self.scenario.resolve_profile(db)?.rounding;
+        let amount = self.lines.iter().map(LineItem::amount).sum();
+        let region = self.taxing_region(amount, db)?;
+        let lines = self
+            .lines
+            .iter()
+            .map(|line| line.portions(self.line_vat_rate(line, region, db)))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Gross lines are normalized with the resolved rates, but the rates depend on
+        // the net total (thresholds); resolve once more with the normalized total.
+        let mut bands = self.resolve_rates(amount, &lines, db)?;
+        if self.lines.iter().any(|line| line.tax_inclusive) {
+            bands = self.resolve_rates(net_total(&lines, &bands), &lines, db)?;
+        }
+
+        let brackets = self.brackets(net_total(&lines, &bands), db)?;
+        let mut exact = Decimal::ZERO;
+        let mut lines: Vec<LineTax> = self
+            .lines
+            .iter()
+            .zip(&lines)
+            .map(|(line, portions)| {
+                let components: Vec<ComponentTax> = portions
+                    .iter()
+                    .map(|(vat_rate, portion)| {
+                        let rates = line_rates(portion, band(&bands, *vat_rate), &brackets);
+                        exact += exact_tax(portion, &rates, portion.quantity);
+                        let tax = self.line_tax(portion, &rates, &rounding);
+                        ComponentTax {
+                            vat_rate: *vat_rate,
+                            rates,
+                            net: tax.net,
+                            tax: tax.tax,
+                            gross: tax.gross,
+                        }
+                    })
+                    .collect();
+                LineTax {
+                    net: components.iter().map(|component| component.net).sum(),
+                    tax: components.iter().map(|component| component.tax).sum(),
+                    gross: components.iter().map(|component| component.gross).sum(),
+                    components: if line.components.is_empty() {
+                        Vec::new()
+                    } else {
+                        components
+                    },
+                }
+            })
+            .collect();
+        let rates = bands.swap_remove(0).1;
+
+        let rounding_difference =
+            lines.iter().map(|line| line.tax).sum::<Decimal>() - rounding.apply(exact);
+        let adjusted_line = self.adjust_rounding(&mut lines, rounding_difference)?;
+
+        let net: Decimal = lines.iter().map(|line| line.net).sum();
+        let tax: Decimal = lines.iter().map(|line| line.tax).sum();
+        let fees = self.fees(net, &rates, db)?;
+        let gross = net + tax;
+        let total = gross + fees.iter().map(|fee| fee.amount).sum::<Decimal>();
+        let net_amount = net.to_f64().ok_or(ProcessingError::InvalidAmount)?;
+        let legal_references = self.scenario.legal_references(net_amount, db)?;
+        let calculation_type = self.scenario.determine_calculation_type(db, net_amount)?;
+        let qualified_invoice = self
+            .scenario
+            .qualified_invoice(&calculation_type, !tax.is_zero());
+        Ok(InvoiceTotals {
+            rates,
+            lines,
+            net,
+            tax,
+            rounding_difference,
+            adjusted_line,
+            qualified_invoice,
+            gross,
+            fees,
+            total,
+            legal_references,
+        })
+    }
+
+    /// Moves the rounding difference into the line of the rounding adjustment (its
+    /// largest component, if it has components), and returns the line's index.
+    fn adjust_rounding(
+        &self,
+        lines: &mut [LineTax],
+        difference: Decimal,
+    ) -> Result<Option<usize>, ProcessingError> {
+        if difference.is_zero() || lines.is_empty() {
+            return Ok(None);
+        }
+        let index = match self.rounding_adjustment {
+            RoundingAdjustment::None => return Ok(None),
+            RoundingAdjustment::LargestLine => largest(lines.iter().map(|line| line.tax)),
+            RoundingAdjustment::LastLine => lines.len() - 1,
+            RoundingAdjustment::Line(index) if index < lines.len() => index,
+            RoundingAdjustment::Line(index) => return Err(ProcessingError::InvalidLine(index)),
+        };
+
+        let tax_inclusive = self.lines[index].tax_inclusive;
+        let adjust = |net: &mut Decimal, tax: &mut Decimal, gross: &mut Decimal| {
+            *tax -= difference;
+            if tax_inclusive {
+                *net += difference;
+            } else {
+                *gross -= difference;
+            }
+        };
+        let line = &mut lines[index];
+        adjust(&mut line.net, &mut line.tax, &mut line.gross);
+        if !line.components.is_empty() {
+            let largest = largest(line.components.iter().map(|component| component.tax));
+            let component = &mut line.components[largest];
+            adjust(&mut component.net, &mut component.tax, &mut component.gross);
+        }
+        Ok(Some(index))
+    }
+
+    /// Flat fees of the destination state that apply to the invoice; only charged
+    /// if tax is collected there.
+    fn fees<P: RateProvider + ?Sized>(
+        &self,
+        net: Decimal,
+        rates: &[TaxRate],
+        db: &P,
+    ) -> Result<Vec<FeeCharge>, ProcessingError> {
+        let destination = &self.scenario.destination_region;
+        let Some(region) = &destination.region else {
+            return Ok(Vec::new());
+        };
+        if rates.is_empty() {
+            return Ok(Vec::new());
+        }
+        let country = db.get_country(&destination.country)?;
+        let Some(state) = rate_provider::state(&country, &destination.country, region) else {
+            return Ok(Vec::new());
+        };
+
+        let mut fees = Vec::new();
+        for fee in &state.fees {
+            let min_amount = fee
+                .min_amount
+                .and_then(Decimal::from_f64)
+                .unwrap_or_default();
+            if !fee.applies_to.covers(self.supply_kind) || net < min_amount {
+                continue;
+            }
+            fees.push(FeeCharge {
+                name: fee.name.clone(),
+                amount: Decimal::from_f64(fee.amount).ok_or(ProcessingError::InvalidAmount)?,
+            });
+        }
+        Ok(fees)
+    }
+
+    /// Rate brackets of the state whose rates apply to the invoice.
+    fn brackets<P: RateProvider + ?Sized>(
+        &self,
+        net: Decimal,
+        db: &P,
+    ) -> Result<Vec<RateBracket>, ProcessingError> {
+        let region = self.taxing_region(net, db)?;
+        let Some(code) = region.region.as_deref() else {
+            return Ok(Vec::new());
+        };
+        let country = db.get_country(&region.country)?;
+        Ok(rate_provider::state(&country, &region.country, code)
+            .map_or_else(Vec::new, |state| state.brackets.clone()))
+    }
+
+    /// The region whose rates apply: the seller's for origin-based taxation, the
+    /// buyer's otherwise.
+    fn taxing_region<P: RateProvider + ?Sized>(
+        &self,
+        net: Decimal,
+        db: &P,
+    ) -> Result<&Region, ProcessingError> {
+        let amount = net.to_f64().ok_or(ProcessingError::InvalidAmount)?;
+        Ok(
+            match self.scenario.determine_calculation_type(db, amount)? {
+                TaxCalculationType::Origin => &self.scenario.source_region,
+                _ => &self.scenario.destination_region,
+            },
+        )
+    }
+
+    /// The VAT rate of a line: the scenario's, or else that of the line's category in
+    /// the taxing country.
+    fn line_vat_rate<P: RateProvider + ?Sized>(
+        &self,
+        line: &LineItem,
+        region: &Region,
+        db: &P,
+    ) -> Option<VatRate> {
+        self.scenario.vat_rate.or_else(|| {
+            let category = line.category.as_deref()?;
+            db.get_country(&region.country)
+                .ok()?
+                .category_rate(category)
+        })
+    }
+
+    /// Resolves the rates of the scenario's VAT rate, followed by those of the other
+    /// VAT rates of line components.
+    fn resolve_rates<P: RateProvider + ?Sized>(
+        &self,
+        net: Decimal,
+        lines: &[Vec<Portion>],
+        db: &P,
+    ) -> Result<Vec<Band>, ProcessingError> {
+        let amount = net.to_f64().ok_or(ProcessingError::InvalidAmount)?;
+        let mut bands = vec![(self.scenario.vat_rate, self.scenario.get_rates(amount, db)?)];
+        for (vat_rate, _) in lines.iter().flatten() {
+            if bands.iter().all(|(resolved, _)| resolved != vat_rate) {
+                let mut scenario = self.scenario.clone();
+                scenario.vat_rate = *vat_rate;
+                bands.push((*vat_rate, scenario.get_rates(amount, db)?));
+            }
+        }
+        Ok(bands)
+    }
+
+    /// Calculates the rounded tax of a line; for gross lines, the net amount is
+    /// the gross amount minus the rounded tax, so the gross amount is kept as is.
+    fn line_tax(&self, line: &LineItem, rates: &[TaxRate], rounding: &Rounding) -> LineTax {
+        let tax = match self.line_rounding {
+            LineRounding::PerLine => rounding.apply(exact_tax(line, rates, line.quantity)),
+            LineRounding::PerUnit => {
+                rounding.apply(rounding.apply(exact_tax(line, rates, Decimal::ONE)) * line.quantity)
+            }
+        };
+
+        if line.tax_inclusive {
+            LineTax {
+                net: line.amount() - tax,
+                tax,
+                gross: line.amount(),
+                components: Vec::new(),
+            }
+        } else {
+            LineTax {
+                net: line.amount(),
+                tax,
+                gross: line.amount() + tax,
+                components: Vec::new(),
+            }
+        }
+    }
+}
+
+/// The unrounded tax of `quantity` units of a line.
+fn exact_tax(line: &LineItem, rates: &[TaxRate], quantity: Decimal) -> Decimal {
+    if line.tax_inclusive {
+        line.unit_price * quantity - item_net_of_gross_decimal(line.unit_price, quantity, rates)
+    } else {
+        sum_item_rates_decimal(line.unit_price, quantity, rates)
+    }
+}
+
+/// Index of the largest amount; the first of equal amounts.
+fn largest(amounts: impl Iterator<Item = Decimal>) -> usize {
+    amounts
+        .enumerate()
+        .fold((0, None), |(index, max), (i, amount)| match max {
+            Some(max) if amount <= max => (index, Some(max)),
+            _ => (i, Some(amount)),
+        })
+        .0
+}
+
+/// The resolved rates of a VAT rate.
+fn band(bands: &[Band], vat_rate: Option<VatRate>) -> &[TaxRate] {
+    bands
+        .iter()
+        .find(|(resolved, _)| *resolved == vat_rate)
+        .map_or(&[], |(_, rates)| rates.as_slice())
+}
+
+/// Sum of the net amounts of the line portions, without rounding.
+fn net_total(lines: &[Vec<Portion>], bands: &[Band]) -> Decimal {
+    lines
+        .iter()
+        .flatten()
+        .map(|(vat_rate, portion)| portion.net_amount(band(bands, *vat_rate)))
+        .sum()
+}
+
+/// The rates of a line: state rates are replaced by the rate of the first bracket the
+/// line's net unit price and category fall into.
+fn line_rates(line: &LineItem, rates: &[TaxRate], brackets: &[RateBracket]) -> Vec<TaxRate> {
+    let unit_price = if line.tax_inclusive {
+        line.net_amount(rates)
+            .checked_div(line.quantity)
+            .unwrap_or(line.unit_price)
+    } else {
+        line.unit_price
+    };
+    let unit_price = unit_price.to_f64().unwrap_or_default();
+    let bracket = brackets
+        .iter()
+        .find(|bracket| bracket.contains(unit_price, line.category.as_deref()));
+
+    rates
+        .iter()
+        .map(|rate| match bracket {
+            Some(bracket) if rate.source == RateSource::StateSpecific => TaxRate {
+                rate: bracket.rate,
+                ..*rate
+            },
+            _ => *rate,
+        })
+        .collect()
+}
@@ -0,0 +1,383 @@
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+
+    use crate::invoice::{FeeCharge, Invoice, LineItem, LineRounding, RoundingAdjustment};
+    use crate::profile::{CalculationProfile, Rounding, RoundingStrategy};
+    use crate::types::{AnnualTurnover, RateBracket, TaxCap};
+    use crate::vat_id::VatId;
+    use crate::{
+        Percentage, ProcessingError, Region, TaxDatabase, TaxScenario, ThresholdPolicy,
+        TransactionType, VatRate,
+    };
+
+    fn invoice() -> Invoice {
+        let scenario = TaxScenario::new(
+            Region::new("DE".to_string(), None).expect("Valid German region"),
+            Region::new("DE".to_string(), None).expect("Valid German region"),
+            TransactionType::B2C,
+        );
+        Invoice::new(scenario)
+    }
+
+    #[test]
+    fn test_invoice_per_line_rounding() {
+        let db = TaxDatabase::fixture();
+        let mut invoice = invoice();
+        invoice
+            .add_line(LineItem::new(dec!(0.99), dec!(3)))
+            .add_line(LineItem::new(dec!(12.50), dec!(1)));
+
+        let totals = invoice.calculate(&db).expect("Calculation should succeed");
+        assert_eq!(totals.lines[0].tax, dec!(0.56)); // 0.5643
+        assert_eq!(totals.lines[1].tax, dec!(2.38)); // 2.375
+        assert_eq!(totals.net, dec!(15.47));
+        assert_eq!(totals.tax, dec!(2.94));
+        assert_eq!(totals.gross, dec!(18.41));
+    }
+
+    #[test]
+    fn test_invoice_per_unit_rounding() {
+        let db = TaxDatabase::fixture();
+        let mut invoice = invoice();
+        invoice.line_rounding = LineRounding::PerUnit;
+        invoice.add_line(LineItem::new(dec!(0.99), dec!(3)));
+
+        let totals = invoice.calculate(&db).expect("Calculation should succeed");
+        assert_eq!(totals.lines[0].tax, dec!(0.57)); // 0.1881 -> 0.19, times 3
+    }
+
+    #[test]
+    fn test_invoice_profile_rounding() {
+        let mut db = TaxDatabase::fixture();
+        db.register_profile(
+            "bankers",
+            CalculationProfile {
+                rounding: Rounding {
+                    decimal_places: 2,
+                    strategy: RoundingStrategy::HalfEven,
+                },
+                ..Default::default()
+            },
+        );
+        let mut invoice = invoice();
+        invoice.scenario = invoice.scenario.with_profile("bankers");
+        invoice.add_line(LineItem::new(dec!(1.50), dec!(1)));
+
+        let totals = invoice.calculate(&db).expect("Calculation should succeed");
+        assert_eq!(totals.tax, dec!(0.28)); // 0.285, half to even
+    }
+
+    #[test]
+    fn test_invoice_mixed_gross_and_net_lines() {
+        let db = TaxDatabase::fixture();
+        let mut invoice = invoice();
+        invoice
+            .add_line(LineItem::gross(dec!(11.90), dec!(1)))
+            .add_line(LineItem::new(dec!(5.00), dec!(1)));
+
+        let totals = invoice.calculate(&db).expect("Calculation should succeed");
+        assert_eq!(totals.lines[0].net, dec!(10.00));
+        assert_eq!(totals.lines[0].tax, dec!(1.90));
+        assert_eq!(totals.lines[0].gross, dec!(11.90));
+        assert_eq!(totals.lines[1].gross, dec!(5.95));
+        assert_eq!(totals.net, dec!(15.00));
+        assert_eq!(totals.gross, dec!(17.85));
+    }
+
+    #[test]
+    fn test_invoice_gross_line_threshold_uses_net() {
+        let db = TaxDatabase::fixture();
+        let scenario = TaxScenario::new(
+            Region::new("DE".to_string(), None).expect("Valid German region"),
+            Region::new("FR".to_string(), None).expect("Valid French region"),
+            TransactionType::B2C,
+        )
+        // No earlier sales this year
+        .with_turnover(AnnualTurnover::default());
+        let mut invoice = Invoice::new(scenario);
+        // 10,500 gross is above the EU threshold, but 8,823.53 net at 19% is not
+        invoice.add_line(LineItem::gross(dec!(10500), dec!(1)));
+
+        let totals = invoice.calculate(&db).expect("Calculation should succeed");
+        assert_eq!(totals.rates[0].rate, Percentage::from_fraction(dec!(0.19)));
+        assert_eq!(totals.lines[0].tax, dec!(1676.47));
+    }
+
+    #[test]
+    fn test_invoice_gross_line_with_capped_rate() {
+        let mut db = TaxDatabase::new().expect("Embedded data should load");
+        let states = db.countries.get_mut("US").unwrap().states.as_mut().unwrap();
+        states.get_mut("US-WA").unwrap().cap = Some(TaxCap {
+            max_taxable_amount: Some(100.0),
+            per_item: true,
+            ..Default::default()
+        });
+        let mut scenario = TaxScenario::new(
+            Region::parse("US-CA").unwrap(),
+            Region::parse("US-WA").unwrap(),
+            TransactionType::B2C,
+        );
+        scenario.threshold_policy = ThresholdPolicy::AssumeAbove;
+        let mut invoice = Invoice::new(scenario);
+        // 6.5% of at most 100 per unit: 206.50 gross is 200 net
+        invoice.add_line(LineItem::gross(dec!(206.50), dec!(2)));
+
+        let totals = invoice.calculate(&db).expect("Calculation should succeed");
+        assert_eq!(totals.lines[0].tax, dec!(13.00));
+        assert_eq!(totals.net, dec!(400.00));
+        assert_eq!(totals.rounding_difference, dec!(0));
+
+        invoice.line_rounding = LineRounding::PerUnit;
+        let totals = invoice.calculate(&db).expect("Calculation should succeed");
+        assert_eq!(totals.lines[0].tax, dec!(13.00));
+    }
+
+    #[test]
+    fn test_invoice_line_components() {
+        let db = TaxDatabase::fixture();
+        let mut hotel = invoice();
+        // Two nights at 115: the room at 7%, breakfast at 19%
+        hotel.add_line(
+            LineItem::new(dec!(115), dec!(2)).with_component(VatRate::Reduced, dec!(100)),
+        );
+
+        let totals = hotel.calculate(&db).expect("Calculation should succeed");
+        let line = &totals.lines[0];
+        assert_eq!(line.tax, dec!(19.70));
+        assert_eq!(line.components.len(), 2);
+        assert_eq!(line.components[0].vat_rate, None);
+        assert_eq!(line.components[0].net, dec!(30));
+        assert_eq!(line.components[0].tax, dec!(5.70));
+        assert_eq!(line.components[1].vat_rate, Some(VatRate::Reduced));
+        assert_eq!(
+            line.components[1].rates[0].rate,
+            Percentage::from_fraction(dec!(0.07))
+        );
+        assert_eq!(line.components[1].tax, dec!(14.00));
+        assert_eq!(totals.rates[0].rate, Percentage::from_fraction(dec!(0.19)));
+
+        // Gross-priced, each portion is normalized at its own rate
+        let mut gross = invoice();
+        gross.add_line(
+            LineItem::gross(dec!(124.85), dec!(1)).with_component(VatRate::Reduced, dec!(107)),
+        );
+        let totals = gross.calculate(&db).expect("Calculation should succeed");
+        assert_eq!(totals.net, dec!(115.00));
+        assert_eq!(totals.tax, dec!(9.85));
+        assert_eq!(totals.gross, dec!(124.85));
+
+        // Lines without components don't list them
+        let mut plain = invoice();
+        plain.add_line(LineItem::new(dec!(10), dec!(1)));
+        let totals = plain.calculate(&db).expect("Calculation should succeed");
+        assert!(totals.lines[0].components.is_empty());
+
+        let mut invalid = invoice();
+        invalid
+            .add_line(LineItem::new(dec!(10), dec!(1)).with_component(VatRate::Reduced, dec!(12)));
+        assert!(matches!(
+            invalid.calculate(&db),
+            Err(ProcessingError::InvalidAmount)
+        ));
+    }
+
+    fn us_invoice(state: &str) -> Invoice {
+        let mut scenario = TaxScenario::new(
+            Region::parse("US-TX").expect("Valid Texas region"),
+            Region::parse(state).expect("Valid US region"),
+            TransactionType::B2C,
+        );
+        scenario.threshold_policy = ThresholdPolicy::AssumeAbove;
+        Invoice::new(scenario)
+    }
+
+    #[test]
+    fn test_invoice_flat_fees() {
+        let db = TaxDatabase::from_files("vat_rates.json", "trade_agreements.json")
+            .expect("Tax database should load");
+
+        let mut invoice = us_invoice("US-CO");
+        invoice.add_line(LineItem::new(dec!(20.00), dec!(1)));
+        let totals = invoice.calculate(&db).expect("Calculation should succeed");
+        assert_eq!(totals.tax, dec!(0.58)); // 2.9%
+        assert_eq!(
+            totals.fees,
+            vec![FeeCharge {
+                name: "Retail Delivery Fee".to_string(),
+                amount: dec!(0.28),
+            }]
+        );
+        assert_eq!(totals.gross, dec!(20.58));
+        assert_eq!(totals.total, dec!(20.86));
+
+        // Not levied on digital goods
+        invoice.scenario.is_digital_product_or_service = true;
+        let mut digital = Invoice::new(invoice.scenario.clone());
+        digital.add_line(LineItem::new(dec!(20.00), dec!(1)));
+        let totals = digital.calculate(&db).expect("Calculation should succeed");
+        assert!(totals.fees.is_empty());
+        assert_eq!(totals.total, totals.gross);
+
+        // Minnesota's fee only applies from 100
+        let mut invoice = us_invoice("US-MN");
+        invoice.add_line(LineItem::new(dec!(99.99), dec!(1)));
+        assert!(invoice.calculate(&db).unwrap().fees.is_empty());
+        invoice.add_line(LineItem::new(dec!(0.01), dec!(1)));
+        assert_eq!(invoice.calculate(&db).unwrap().fees[0].amount, dec!(0.5));
+    }
+
+    #[test]
+    fn test_invoice_rate_brackets() {
+        let mut db = TaxDatabase::from_files("vat_rates.json", "trade_agreements.json")
+            .expect("Tax database should load");
+        let states = db.countries.get_mut("US").unwrap().states.as_mut().unwrap();
+        states.get_mut("US-NY").unwrap().brackets = vec![RateBracket {
+            min_price: None,
+            max_price: Some(110.0),
+            rate: Percentage::ZERO,
+            category: Some("clothing".to_string()),
+        }];
+
+        let mut invoice = us_invoice("US-NY");
+        invoice
+            .add_line(LineItem::new(dec!(50.00), dec!(2)).with_category("clothing"))
+            .add_line(LineItem::new(dec!(110.00), dec!(1)).with_category("Clothing"))
+            .add_line(LineItem::new(dec!(50.00), dec!(1)));
+        let totals = invoice.calculate(&db).expect("Calculation should succeed");
+        assert_eq!(totals.lines[0].tax, dec!(0.00));
+        assert_eq!(totals.lines[1].tax, dec!(4.40));
+        assert_eq!(totals.lines[2].tax, dec!(2.00));
+        assert_eq!(totals.tax, dec!(6.40));
+        assert_eq!(totals.rates[0].rate, Percentage::from_fraction(dec!(0.04)));
+    }
+
+    #[test]
+    fn test_invoice_legal_references() {
+        let db = TaxDatabase::new().expect("Embedded data should load");
+        let scenario = TaxScenario::new(
+            Region::parse("DE").expect("Valid region"),
+            Region::parse("FR").expect("Valid region"),
+            TransactionType::B2B,
+        )
+        .with_buyer_vat_id(VatId::parse("FR40303265045").expect("Valid VAT ID"));
+        let mut cross_border = Invoice::new(scenario);
+        cross_border.add_line(LineItem::new(dec!(100), dec!(1)));
+
+        let totals = cross_border
+            .calculate(&db)
+            .expect("Calculation should succeed");
+        let notes: Vec<String> = totals
+            .legal_references
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(
+            notes,
+            ["European Union internal_b2b: Article 196, Council Directive 2006/112/EC"]
+        );
+
+        // Domestic sales cite the rate only
+        let mut domestic = invoice();
+        domestic.add_line(LineItem::new(dec!(10), dec!(1)));
+        let totals = domestic.calculate(&db).expect("Calculation should succeed");
+        assert_eq!(totals.legal_references.len(), 1);
+        assert_eq!(totals.legal_references[0].subject, "DE rate");
+    }
+
+    #[test]
+    fn test_invoice_rounding_difference() {
+        let db = TaxDatabase::fixture();
+        let mut invoice = invoice();
+        invoice
+            .add_line(LineItem::new(dec!(0.99), dec!(1)))
+            .add_line(LineItem::new(dec!(0.99), dec!(1)))
+            .add_line(LineItem::new(dec!(12.50), dec!(1)));
+
+        // 0.19 + 0.19 + 2.38, but 2.75 on the total (2.7512)
+        let totals = invoice.calculate(&db).expect("Calculation should succeed");
+        assert_eq!(totals.tax, dec!(2.76));
+        assert_eq!(totals.rounding_difference, dec!(0.01));
+        assert_eq!(totals.adjusted_line, None);
+
+        invoice.rounding_adjustment = RoundingAdjustment::LargestLine;
+        let totals = invoice.calculate(&db).expect("Calculation should succeed");
+        assert_eq!(totals.rounding_difference, dec!(0.01));
+        assert_eq!(totals.adjusted_line, Some(2));
+        assert_eq!(totals.lines[2].tax, dec!(2.37));
+        assert_eq!(totals.tax, dec!(2.75));
+        assert_eq!(totals.gross, dec!(17.23));
+
+        invoice.rounding_adjustment = RoundingAdjustment::Line(0);
+        let totals = invoice.calculate(&db).expect("Calculation should succeed");
+        assert_eq!(totals.lines[0].tax, dec!(0.18));
+        assert_eq!(totals.lines[0].gross, dec!(1.17));
+        assert_eq!(totals.tax, dec!(2.75));
+
+        invoice.rounding_adjustment = RoundingAdjustment::Line(3);
+        assert!(matches!(
+            invoice.calculate(&db),
+            Err(ProcessingError::InvalidLine(3))
+        ));
+
+        // Nothing to absorb
+        let mut exact = self::invoice();
+        exact.rounding_adjustment = RoundingAdjustment::LastLine;
+        exact.add_line(LineItem::new(dec!(10.00), dec!(2)));
+        let totals = exact.calculate(&db).expect("Calculation should succeed");
+        assert_eq!(totals.rounding_difference, dec!(0));
+        assert_eq!(totals.adjusted_line, None);
+    }
+
+    #[test]
+    fn test_invoice_category_rates() {
+        let db = TaxDatabase::new().expect("Embedded data should load");
+        let japan = Region::parse("JP").expect("Valid region");
+        let mut invoice =
+            Invoice::new(TaxScenario::new(japan.clone(), japan, TransactionType::B2B));
+        invoice
+            .add_line(LineItem::new(dec!(1000), dec!(1)).with_category("Food"))
+            .add_line(LineItem::new(dec!(1000), dec!(1)).with_category("alcohol"))
+            .add_line(LineItem::new(dec!(500), dec!(2)))
+            .add_line(LineItem::new(dec!(100), dec!(1)).with_category("non_alcoholic_beverages"))
+            // Beverages include alcohol, which is taxed at the standard rate
+            .add_line(LineItem::new(dec!(100), dec!(1)).with_category("beverages"));
+
+        let totals = invoice.calculate(&db).expect("Calculation should succeed");
+        assert_eq!(totals.lines[0].tax, dec!(80));
+        assert_eq!(totals.lines[1].tax, dec!(100));
+        assert_eq!(totals.lines[2].tax, dec!(100));
+        assert_eq!(totals.lines[3].tax, dec!(8));
+        assert_eq!(totals.lines[4].tax, dec!(10));
+        assert_eq!(totals.tax, dec!(298));
+        assert!(totals.qualified_invoice);
+
+        // The scenario's rate applies to all lines
+        invoice.scenario.vat_rate = Some(VatRate::Standard);
+        let totals = invoice.calculate(&db).expect("Calculation should succeed");
+        assert_eq!(totals.lines[0].tax, dec!(100));
+
+        // Countries without category rates
+        let mut germany = self::invoice();
+        germany.add_line(LineItem::new(dec!(10), dec!(1)).with_category("food"));
+        let totals = germany.calculate(&db).expect("Calculation should succeed");
+        assert_eq!(totals.tax, dec!(1.90));
+        assert!(!totals.qualified_invoice);
+    }
+
+    #[test]
+    fn test_invoice_with_dated_view() {
+        let db = TaxDatabase::new().expect("Embedded data should load");
+        let mut invoice = invoice();
+        invoice.add_line(LineItem::new(dec!(100.00), dec!(1)));
+
+        // Germany charged 16% in the second half of 2020
+        let past = db.as_of("2020-10-01").expect("Valid date");
+        let totals = invoice
+            .calculate(&past)
+            .expect("Calculation should succeed");
+        assert_eq!(totals.tax, dec!(16.00));
+        let totals = invoice.calculate(&db).expect("Calculation should succeed");
+        assert_eq!(totals.tax, dec!(19.00));
+    }
+}
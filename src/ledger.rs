@@ -0,0 +1,152 @@
+//! Journal lines of quoted sales.
+//!
+//! Posts a quote as a balanced journal entry: the gross amount to the receivable
+//! account, the net amount to revenue, the tax of each posting (see `erp`) to the
+//! liability account of its tax code, and the difference between the quoted tax and
+//! the tax of the postings, rounded per rate, to a rounding account. The accounts are
+//! set in the mapping file:
+//!
+//! ```json
+//! {
+//!     "system": "sap",
+//!     "accounts": { "receivable": "140000", "revenue": "800000", "tax": "176000", "rounding": "481000" },
+//!     "codes": [
+//!         { "jurisdiction": "DE", "scheme": "origin", "code": "A1", "account": "176600" },
+//!         { "scheme": "reverse_charge", "code": "E0" }
+//!     ]
+//! }
+//! ```
+
+use serde::{Deserialize, Serialize};
+
+use crate::calculator::TaxQuote;
+use crate::erp::TaxCodeMapping;
+use crate::errors::DatabaseError;
+use crate::TaxScenario;
+
+/// General ledger accounts of journal lines.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LedgerAccounts {
+    /// Account of the gross amount owed by the buyer (e.g. accounts receivable)
+    pub receivable: String,
+    /// Account of the net amount
+    pub revenue: String,
+    /// Liability account of tax whose code has no account
+    pub tax: String,
+    /// Account of rounding differences
+    pub rounding: String,
+}
+
+/// A line of a journal entry. Amounts are rounded to 2 decimal places; one of
+/// `debit` and `credit` is zero.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct JournalLine {
+    /// The general ledger account
+    pub account: String,
+    /// Debit amount
+    pub debit: f64,
+    /// Credit amount
+    pub credit: f64,
+    /// The tax code of the line, if any
+    pub tax_code: Option<String>,
+    /// Country or state code of the jurisdiction the tax is owed to, for tax lines
+    pub jurisdiction: Option<String>,
+}
+
+impl JournalLine {
+    fn new(account: &str, amount: f64) -> Self {
+        let amount = round(amount);
+        Self {
+            account: account.to_string(),
+            debit: if amount < 0.0 { -amount } else { 0.0 },
+            credit: amount.max(0.0),
+            tax_code: None,
+            jurisdiction: None,
+        }
+    }
+}
+
+fn round(amount: f64) -> f64 {
+    (amount * 100.0).round() / 100.0
+}
+
+impl TaxCodeMapping {
+    /// Journal lines of a quote: the receivable debit, and credits of the revenue,
+    /// the tax per posting and the rounding difference (if any).
+    ///
+    /// The revenue line carries the tax code if the quote has a single posting, so
+    /// untaxed sales (e.g. reverse charge) are reported under their code. Postings
+    /// without tax have no line of their own.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use world_tax::calculator::{TaxCalculator, TaxRequest};
+    /// # use world_tax::erp::TaxCodeMapping;
+    /// # use world_tax::{Region, TaxDatabase, TaxScenario, TransactionType};
+    /// let mapping = TaxCodeMapping::from_json(
+    ///     r#"{
+    ///         "accounts": { "receivable": "1200", "revenue": "4000", "tax": "2200", "rounding": "4900" },
+    ///         "codes": [{ "jurisdiction": "DE", "code": "A1" }]
+    ///     }"#,
+    /// )
+    /// .unwrap();
+    ///
+    /// let db = TaxDatabase::fixture();
+    /// let germany = Region::parse("DE").unwrap();
+    /// let scenario = TaxScenario::new(germany.clone(), germany, TransactionType::B2C);
+    /// let quote = db.quote(TaxRequest::new(scenario.clone(), 100.0)).unwrap();
+    ///
+    /// let lines = mapping.journal(&scenario, &quote).unwrap();
+    /// assert_eq!(lines[0].debit, 119.0);
+    /// assert_eq!((lines[1].account.as_str(), lines[1].credit), ("4000", 100.0));
+    /// assert_eq!((lines[2].account.as_str(), lines[2].credit), ("2200", 19.0));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::AccountNotMapped` if the mapping has no `accounts`, and
+    /// `DatabaseError::TaxCodeNotMapped` if no code is mapped for an outcome.
+    pub fn journal(
+        &self,
+        scenario: &TaxScenario,
+        quote: &TaxQuote,
+    ) -> Result<Vec<JournalLine>, DatabaseError> {
+        let accounts = self
+            .accounts
+            .as_ref()
+            .ok_or_else(|| DatabaseError::AccountNotMapped(self.system.clone()))?;
+        let postings = self.postings(scenario, quote)?;
+
+        let receivable = round(quote.amount) + round(quote.tax);
+        let mut lines = vec![
+            JournalLine::new(&accounts.receivable, -receivable),
+            JournalLine {
+                tax_code: match postings.as_slice() {
+                    [posting] => Some(posting.code.clone()),
+                    _ => None,
+                },
+                ..JournalLine::new(&accounts.revenue, quote.amount)
+            },
+        ];
+
+        let mut posted = 0.0;
+        for posting in postings.into_iter().filter(|posting| posting.amount != 0.0) {
+            posted += posting.amount;
+            lines.push(JournalLine {
+                tax_code: Some(posting.code),
+                jurisdiction: Some(posting.jurisdiction),
+                ..JournalLine::new(
+                    posting.account.as_deref().unwrap_or(&accounts.tax),
+                    posting.amount,
+                )
+            });
+        }
+
+        let difference = round(round(quote.tax) - posted);
+        if difference != 0.0 {
+            lines.push(JournalLine::new(&accounts.rounding, difference));
+        }
+        Ok(lines)
+    }
+}
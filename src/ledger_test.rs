@@ -0,0 +1,109 @@
+#[cfg(test)]
+mod tests {
+    use crate::calculator::{TaxCalculator, TaxRequest};
+    use crate::erp::TaxCodeMapping;
+    use crate::vat_id::VatId;
+    use crate::{DatabaseError, Region, TaxDatabase, TaxScenario, TransactionType};
+
+    const MAPPING: &str = r#"{
+        "system": "netsuite",
+        "accounts": { "receivable": "1100", "revenue": "4000", "tax": "2200", "rounding": "4900" },
+        "codes": [
+            { "jurisdiction": "CA-QC", "tax": { "type": "q_s_t" }, "code": "QST", "account": "2230" },
+            { "jurisdiction": "CA", "code": "GST" },
+            { "scheme": "reverse_charge", "code": "RC" }
+        ]
+    }"#;
+
+    fn journal(
+        source: &str,
+        destination: &str,
+        transaction_type: TransactionType,
+        amount: f64,
+        buyer_vat_id: Option<&str>,
+    ) -> Vec<(String, f64, f64, Option<String>)> {
+        let db = TaxDatabase::fixture();
+        let mapping = TaxCodeMapping::from_json(MAPPING).unwrap();
+        let mut scenario = TaxScenario::new(
+            Region::parse(source).unwrap(),
+            Region::parse(destination).unwrap(),
+            transaction_type,
+        );
+        scenario.buyer_vat_id = buyer_vat_id.map(|id| VatId::parse(id).unwrap().format_check());
+        let quote = db.quote(TaxRequest::new(scenario.clone(), amount)).unwrap();
+        let lines = mapping.journal(&scenario, &quote).unwrap();
+
+        let debits: f64 = lines.iter().map(|line| line.debit).sum();
+        let credits: f64 = lines.iter().map(|line| line.credit).sum();
+        assert!((debits - credits).abs() < 1e-9, "{lines:?}");
+        lines
+            .into_iter()
+            .map(|line| (line.account, line.debit, line.credit, line.tax_code))
+            .collect()
+    }
+
+    fn line(
+        account: &str,
+        debit: f64,
+        credit: f64,
+        code: Option<&str>,
+    ) -> (String, f64, f64, Option<String>) {
+        (account.to_string(), debit, credit, code.map(str::to_string))
+    }
+
+    #[test]
+    fn test_journal_per_jurisdiction() {
+        assert_eq!(
+            journal("CA-QC", "CA-QC", TransactionType::B2B, 100.0, None),
+            vec![
+                line("1100", 115.47, 0.0, None),
+                line("4000", 0.0, 100.0, None),
+                line("2200", 0.0, 5.0, Some("GST")),
+                line("2230", 0.0, 10.47, Some("QST")),
+            ]
+        );
+
+        // Reverse charge: no tax line, the revenue carries the code
+        assert_eq!(
+            journal(
+                "DE",
+                "FR",
+                TransactionType::B2B,
+                100.0,
+                Some("FR40303265045")
+            ),
+            vec![
+                line("1100", 100.0, 0.0, None),
+                line("4000", 0.0, 100.0, Some("RC")),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rounding_difference() {
+        // GST 0.05 and QST 0.11 rounded per rate, 0.17 on the total
+        assert_eq!(
+            journal("CA-QC", "CA-QC", TransactionType::B2B, 1.07, None),
+            vec![
+                line("1100", 1.24, 0.0, None),
+                line("4000", 0.0, 1.07, None),
+                line("2200", 0.0, 0.05, Some("GST")),
+                line("2230", 0.0, 0.11, Some("QST")),
+                line("4900", 0.0, 0.01, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_accounts_not_mapped() {
+        let db = TaxDatabase::fixture();
+        let mapping = TaxCodeMapping::from_json(r#"{ "system": "xero", "codes": [] }"#).unwrap();
+        let germany = Region::parse("DE").unwrap();
+        let scenario = TaxScenario::new(germany.clone(), germany, TransactionType::B2C);
+        let quote = db.quote(TaxRequest::new(scenario.clone(), 100.0)).unwrap();
+        assert!(matches!(
+            mapping.journal(&scenario, &quote),
+            Err(DatabaseError::AccountNotMapped(system)) if system == "xero"
+        ));
+    }
+}
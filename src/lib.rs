@@ -1,13 +1,125 @@
+pub mod alias;
+mod alias_test;
+#[cfg(feature = "async")]
+pub mod async_provider;
+mod async_provider_test;
+pub mod audit;
+mod audit_test;
+pub mod brazil;
+mod brazil_test;
 pub mod calculation;
 mod calculation_test;
+pub mod calculator;
+pub mod cart;
+mod cart_test;
+pub mod certificate;
+mod certificate_test;
+pub mod changes;
+mod changes_test;
+pub mod confidence;
+mod confidence_test;
+pub mod conformance;
+mod conformance_test;
+pub mod coverage;
+mod coverage_test;
+pub mod currency;
+mod currency_test;
+pub mod customs;
+mod customs_test;
+pub mod dated;
+mod dated_test;
+pub mod decision_graph;
+mod decision_graph_test;
+pub mod dropship;
+mod dropship_test;
+pub mod einvoicing;
+mod einvoicing_test;
+pub mod envelope;
+mod envelope_test;
+pub mod erp;
+mod erp_test;
 pub mod errors;
+pub mod explain;
+mod fuzz_test;
+pub mod hs;
+mod hs_test;
+pub mod import;
+mod import_test;
+pub mod invoice;
+mod invoice_test;
+pub mod ledger;
+mod ledger_test;
+pub mod local;
+pub mod local_store;
+mod local_store_test;
+mod local_test;
+pub mod marketplace;
+mod marketplace_test;
+pub mod migration;
+mod migration_test;
+pub mod missing_country;
+mod missing_country_test;
+pub mod nexus;
+mod nexus_test;
+pub mod overlay;
+mod overlay_test;
+pub mod overrides;
+mod overrides_test;
+pub mod percentage;
+mod percentage_test;
+pub mod profile;
 pub mod provider;
+mod provider_test;
+pub mod quick;
+pub mod rate_provider;
+mod rate_provider_test;
+pub mod region_group;
+mod region_group_test;
+pub mod requirements;
+mod requirements_test;
+#[cfg(feature = "async")]
+pub mod resilience;
+mod resilience_test;
+pub mod returns;
+mod returns_test;
+pub mod schema;
+mod schema_test;
+pub mod self_assessment;
+mod self_assessment_test;
+pub mod shipment;
+mod shipment_test;
+pub mod simulation;
+mod simulation_test;
+pub mod sst;
+mod sst_test;
+#[cfg(feature = "static-table")]
+pub mod table;
+mod table_test;
+pub mod threshold;
+mod threshold_test;
+pub mod transfer;
+mod transfer_test;
 pub mod types;
+pub mod updates;
+mod updates_test;
+pub mod validation;
+mod validation_test;
+pub mod vat_id;
+mod vat_id_test;
+pub mod view;
+mod view_test;
+pub mod warning;
 
+pub use calculator::{TaxCalculator, TaxQuote, TaxRequest};
+pub use percentage::Percentage;
+pub use profile::CalculationProfile;
 pub use provider::TaxDatabase;
+pub use quick::{quick_rate, quick_tax};
+pub use rate_provider::RateProvider;
 pub use types::{
-    Region, TaxCalculationType, TaxRate, TaxScenario, TaxType, TradeAgreement,
-    TradeAgreementOverride, TransactionType, VatRate,
+    BuyerType, RateSource, Region, TaxCalculationType, TaxRate, TaxScenario, TaxType,
+    ThresholdPolicy, TradeAgreement, TradeAgreementOverride, TransactionType, VatRate,
 };
+pub use warning::Warning;
 
-pub use errors::{DatabaseError, InputValidationError, ProcessingError};
+pub use errors::{DatabaseError, DatasetError, InputValidationError, ProcessingError};
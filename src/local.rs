@@ -0,0 +1,168 @@
+//! Local sales tax rates.
+//!
+//! Many US states allow counties, cities and districts to levy sales tax on top of
+//! the state rate; some (like Alaska) have no state sales tax, but local taxes. The
+//! dataset marks states with local taxes, and callers can supply a local-rate layer
+//! per state as a boundary table of ZIP code ranges.
+//!
+//! In home-rule states (e.g. Colorado, Alabama, Louisiana), some cities administer
+//! their own sales tax; records of the local-rate layer can be flagged as home-rule,
+//! which is reported as a warning since separate registration may be required.
+//!
+//! Local rates are only applied when the scenario's region carries a postal code or
+//! the name of its local jurisdiction, using the destination's rate, or the seller's
+//! rate for intrastate sales of SST states with origin sourcing.
+
+use std::sync::Arc;
+
+use crate::percentage::Percentage;
+use crate::rate_provider::RateProvider;
+use crate::sst::{self, BoundaryRecord, BoundaryTable, Sourcing};
+use crate::types::normalize_state_code;
+use crate::{
+    DatabaseError, RateSource, Region, TaxDatabase, TaxRate, TaxScenario, TaxType, VatRate,
+};
+
+impl TaxDatabase {
+    /// Loads the local rates of a state (e.g. "US-AK"), replacing any loaded before.
+    ///
+    /// The code is normalized like a region's ("us-ak" is "US-AK"); it has to include
+    /// the country, as local rates aren't limited to one country.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use world_tax::sst::BoundaryTable;
+    /// # use world_tax::{Region, TaxDatabase, TaxScenario, ThresholdPolicy, TransactionType};
+    /// let mut db = TaxDatabase::new().unwrap();
+    /// db.load_local_rates("US-AK", BoundaryTable::parse("99501,99524,0.0,Anchorage\n99801,99811,0.05,Juneau").unwrap());
+    ///
+    /// let mut scenario = TaxScenario::new(
+    ///     Region::parse("US-AK").unwrap().with_postal_code("99801"),
+    ///     Region::parse("US-AK").unwrap().with_postal_code("99801"),
+    ///     TransactionType::B2C,
+    /// );
+    /// scenario.threshold_policy = ThresholdPolicy::AssumeAbove;
+    /// assert_eq!(scenario.calculate_tax(100.0, &db).unwrap(), 5.0);
+    /// ```
+    pub fn load_local_rates(&mut self, state: &str, table: BoundaryTable) {
+        self.local_rates.insert(local_key(state), Arc::new(table));
+    }
+
+    /// The loaded local rates of a state.
+    pub fn local_rates(&self, state: &str) -> Option<&BoundaryTable> {
+        self.local_rates
+            .get(state)
+            .or_else(|| self.local_rates.get(&local_key(state)))
+            .map(Arc::as_ref)
+    }
+
+    /// Retrieves the tax rates of a region, with its local rate if local rates of its
+    /// state are loaded and the region has a postal code or jurisdiction.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use world_tax::sst::BoundaryTable;
+    /// # use world_tax::{Region, TaxDatabase};
+    /// let mut db = TaxDatabase::new().unwrap();
+    /// db.load_local_rates("US-CO", BoundaryTable::parse("80201,80299,0.0481,Denver").unwrap());
+    ///
+    /// let denver = Region::parse("US-CO").unwrap().with_jurisdiction("Denver");
+    /// let rates = db.get_rate_at(&denver, None).unwrap();
+    /// assert_eq!(rates.len(), 2); // State and local rate
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of `get_rate`.
+    pub fn get_rate_at(
+        &self,
+        region: &Region,
+        vat_rate: Option<&VatRate>,
+    ) -> Result<Vec<TaxRate>, DatabaseError> {
+        let mut rates = self.get_rate(&region.country, region.region.as_deref(), vat_rate)?;
+        let local = region
+            .region
+            .as_deref()
+            .and_then(|state| self.local_table(state))
+            .and_then(|table| {
+                region
+                    .local_record(&table)
+                    .map(|record| Percentage::from_f64(record.rate))
+            });
+        if let Some(rate) = local {
+            rates.push(TaxRate::new(
+                rate,
+                TaxType::LocalSalesTax,
+                RateSource::Local,
+            ));
+        }
+        Ok(rates)
+    }
+
+    /// Returns true if local jurisdictions of the state levy their own sales tax.
+    pub fn has_local_taxes(&self, country: &str, state: &str) -> bool {
+        self.state(country, state)
+            .is_some_and(|state| state.local_taxes)
+    }
+}
+
+impl TaxScenario {
+    /// Resolves the local rate for a sale into `state`, if local rates are loaded
+    /// and the sourced location has a postal code or jurisdiction.
+    pub(crate) fn local_rate<P: RateProvider + ?Sized>(
+        &self,
+        state: &str,
+        db: &P,
+    ) -> Option<TaxRate> {
+        let record = self.local_record(state, db)?;
+        Some(TaxRate::new(
+            Percentage::from_f64(record.rate),
+            TaxType::LocalSalesTax,
+            RateSource::Local,
+        ))
+    }
+
+    /// Finds the local-rate record of the sourced location of a sale into `state`.
+    pub(crate) fn local_record<P: RateProvider + ?Sized>(
+        &self,
+        state: &str,
+        db: &P,
+    ) -> Option<BoundaryRecord> {
+        let table = db.local_table(state)?;
+
+        let intrastate = self.source_region.region.as_deref() == Some(state);
+        let sourcing = sst::member(state)
+            .map(|member| member.intrastate_sourcing)
+            .unwrap_or(Sourcing::Destination);
+        let location = if intrastate && sourcing == Sourcing::Origin {
+            &self.source_region
+        } else {
+            &self.destination_region
+        };
+
+        location.local_record(&table).cloned()
+    }
+}
+
+impl Region {
+    /// Finds the local-rate record of the region: by postal code, or else by
+    /// jurisdiction.
+    pub(crate) fn local_record<'a>(&self, table: &'a BoundaryTable) -> Option<&'a BoundaryRecord> {
+        match (&self.postal_code, &self.jurisdiction) {
+            (Some(postal_code), _) => table.lookup(postal_code),
+            (None, Some(jurisdiction)) => table.lookup_jurisdiction(jurisdiction),
+            (None, None) => None,
+        }
+    }
+}
+
+/// The key of a state's local rates: its normalized ISO 3166-2 code, if the code
+/// includes the country.
+pub(crate) fn local_key(state: &str) -> String {
+    match state.split_once('-') {
+        Some((country, _)) => normalize_state_code(country, state),
+        None => state.to_string(),
+    }
+}
@@ -0,0 +1,240 @@
+//! Lazily loaded local rates.
+//!
+//! ZIP-level local rates of all US states take a lot of memory. Instead of loading
+//! every state's table up front with `TaxDatabase::load_local_rates`, a
+//! `LocalRateStore` loads a state's table from a backing store when a scenario first
+//! needs it. Loaded tables are kept within a memory budget; when it's exceeded, the
+//! least recently used tables are evicted, and loaded again on their next use.
+//!
+//! `DirectoryStore` reads tables from CSV files; implement `LocalRateStore` to load
+//! them from another store (e.g. an SQLite database).
+//!
+//! Before a batch with known destinations (e.g. a marketplace's daily settlement),
+//! `TaxDatabase::prefetch` loads their tables up front, so the batch doesn't wait on
+//! the store, and reports destinations whose data is missing or can't be loaded.
+
+use std::collections::HashMap;
+use std::io;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, PoisonError};
+
+use log::warn;
+
+use crate::errors::DatabaseError;
+use crate::local::local_key;
+use crate::sst::BoundaryTable;
+use crate::{Region, TaxDatabase};
+
+/// Loads the local rates of a state from a backing store.
+pub trait LocalRateStore: Send + Sync {
+    /// Loads the local rates of a state (e.g. "US-AK"); none if the store has no
+    /// table for the state.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the table can't be read or parsed.
+    fn load(&self, state: &str) -> Result<Option<BoundaryTable>, DatabaseError>;
+}
+
+/// Reads local rates from a directory with a CSV file per state (e.g. `US-AK.csv`),
+/// in the format of `BoundaryTable::parse`.
+#[derive(Debug, Clone)]
+pub struct DirectoryStore {
+    dir: PathBuf,
+}
+
+impl DirectoryStore {
+    /// Creates a store reading from `dir`.
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+}
+
+impl LocalRateStore for DirectoryStore {
+    fn load(&self, state: &str) -> Result<Option<BoundaryTable>, DatabaseError> {
+        let path = self.dir.join(format!("{}.csv", state));
+        match std::fs::read_to_string(&path) {
+            Ok(csv) => BoundaryTable::parse(&csv).map(Some),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(error) => Err(DatabaseError::LocalRatesUnavailable {
+                state: state.to_string(),
+                reason: error.to_string(),
+            }),
+        }
+    }
+}
+
+#[derive(Default)]
+struct Entries {
+    /// Loaded tables (none if the store has no table for the state), with their
+    /// size and when they were last used
+    tables: HashMap<String, (Option<Arc<BoundaryTable>>, usize, u64)>,
+    resident: usize,
+    clock: u64,
+}
+
+/// Local rates loaded from a store, within a memory budget.
+pub(crate) struct LocalRateCache {
+    store: Box<dyn LocalRateStore>,
+    memory_budget: usize,
+    entries: Mutex<Entries>,
+}
+
+impl LocalRateCache {
+    /// The local rates of a state, loaded from the store if they aren't resident;
+    /// none if they can't be loaded.
+    fn get(&self, state: &str) -> Option<Arc<BoundaryTable>> {
+        self.load(state).unwrap_or_else(|error| {
+            warn!("Local rates of {} not loaded: {}", state, error);
+            None
+        })
+    }
+
+    /// The local rates of a state, loaded from the store if they aren't resident.
+    /// Tables larger than the budget are used, but not kept.
+    fn load(&self, state: &str) -> Result<Option<Arc<BoundaryTable>>, DatabaseError> {
+        let mut entries = self.entries.lock().unwrap_or_else(PoisonError::into_inner);
+        entries.clock += 1;
+        let clock = entries.clock;
+        if let Some((table, _, last_used)) = entries.tables.get_mut(state) {
+            *last_used = clock;
+            return Ok(table.clone());
+        }
+
+        let table = self.store.load(state)?.map(Arc::new);
+        let size = table.as_ref().map_or(0, |table| table.memory_size());
+        if size > self.memory_budget {
+            return Ok(table);
+        }
+        while entries.resident + size > self.memory_budget {
+            let Some(oldest) = entries
+                .tables
+                .iter()
+                .min_by_key(|(_, (_, _, last_used))| *last_used)
+                .map(|(state, _)| state.clone())
+            else {
+                break;
+            };
+            if let Some((_, evicted, _)) = entries.tables.remove(&oldest) {
+                entries.resident -= evicted;
+            }
+        }
+        entries.resident += size;
+        entries
+            .tables
+            .insert(state.to_string(), (table.clone(), size, clock));
+        Ok(table)
+    }
+}
+
+impl TaxDatabase {
+    /// Loads local rates lazily from `store`, keeping at most `memory_budget` bytes
+    /// of tables resident. Tables loaded with `load_local_rates` take precedence,
+    /// and don't count against the budget.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use world_tax::local_store::DirectoryStore;
+    /// # use world_tax::TaxDatabase;
+    /// let mut db = TaxDatabase::new().unwrap();
+    /// db.set_local_rate_store(DirectoryStore::new("/var/lib/tax/local"), 64 * 1024 * 1024);
+    /// ```
+    pub fn set_local_rate_store(
+        &mut self,
+        store: impl LocalRateStore + 'static,
+        memory_budget: usize,
+    ) {
+        self.local_rate_store = Some(LocalRateCache {
+            store: Box::new(store),
+            memory_budget,
+            entries: Mutex::new(Entries::default()),
+        });
+    }
+
+    /// Prepares for a batch of sales to the given destinations: checks that their
+    /// countries have data (or a missing country policy), and loads the local rates
+    /// of US destinations from the local rate store. Tables beyond the memory budget
+    /// evict those loaded before them, so the budget should fit the batch.
+    ///
+    /// Returns the problems found, one per missing country and per table that can't
+    /// be loaded; an empty list if all destinations are ready.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use world_tax::local_store::DirectoryStore;
+    /// # use world_tax::{DatabaseError, Region, TaxDatabase};
+    /// let mut db = TaxDatabase::new().unwrap();
+    /// db.set_local_rate_store(DirectoryStore::new("/var/lib/tax/local"), 64 * 1024 * 1024);
+    ///
+    /// let destinations = [Region::parse("US-AK").unwrap(), Region::parse("FJ").unwrap()];
+    /// let problems = db.prefetch(&destinations);
+    /// assert!(matches!(
+    ///     problems[..],
+    ///     [DatabaseError::CountryNotFound { ref code, .. }] if code == "FJ"
+    /// ));
+    /// ```
+    pub fn prefetch(&self, destinations: &[Region]) -> Vec<DatabaseError> {
+        let mut problems = Vec::new();
+        let mut missing: Vec<&str> = Vec::new();
+        let mut states: Vec<String> = Vec::new();
+        for destination in destinations {
+            if missing.contains(&destination.country.as_str()) {
+                continue;
+            }
+            if let Err(error) = self.country(&destination.country) {
+                missing.push(&destination.country);
+                problems.push(error);
+                continue;
+            }
+            if let Some(state) = destination
+                .region
+                .as_deref()
+                .filter(|_| destination.country == "US")
+            {
+                let key = local_key(state);
+                if !states.contains(&key) {
+                    states.push(key);
+                }
+            }
+        }
+
+        let Some(cache) = &self.local_rate_store else {
+            return problems;
+        };
+        for state in states {
+            if self.local_rates.contains_key(&state) {
+                continue;
+            }
+            if let Err(error) = cache.load(&state) {
+                problems.push(error);
+            }
+        }
+        problems
+    }
+
+    /// Bytes of local rates currently resident from the local rate store.
+    pub fn resident_local_rates(&self) -> usize {
+        self.local_rate_store.as_ref().map_or(0, |cache| {
+            cache
+                .entries
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .resident
+        })
+    }
+
+    /// The local rates of a state: loaded with `load_local_rates`, or else from the
+    /// local rate store.
+    pub fn local_table(&self, state: &str) -> Option<Arc<BoundaryTable>> {
+        if let Some(table) = self.local_rates.get(state) {
+            return Some(table.clone());
+        }
+        let key = local_key(state);
+        if let Some(table) = self.local_rates.get(&key) {
+            return Some(table.clone());
+        }
+        self.local_rate_store.as_ref()?.get(&key)
+    }
+}
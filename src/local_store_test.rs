@@ -0,0 +1,159 @@
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use crate::local_store::{DirectoryStore, LocalRateStore};
+    use crate::sst::BoundaryTable;
+    use crate::{
+        DatabaseError, RateProvider, Region, TaxDatabase, TaxScenario, ThresholdPolicy,
+        TransactionType,
+    };
+
+    /// Tables by state, counting how often they're loaded.
+    struct CountingStore {
+        tables: HashMap<&'static str, &'static str>,
+        loads: Arc<AtomicUsize>,
+    }
+
+    impl LocalRateStore for CountingStore {
+        fn load(&self, state: &str) -> Result<Option<BoundaryTable>, DatabaseError> {
+            self.loads.fetch_add(1, Ordering::Relaxed);
+            self.tables
+                .get(state)
+                .map(|csv| BoundaryTable::parse(csv))
+                .transpose()
+        }
+    }
+
+    fn counting_store() -> (CountingStore, Arc<AtomicUsize>) {
+        let loads = Arc::new(AtomicUsize::new(0));
+        let store = CountingStore {
+            tables: HashMap::from([
+                ("US-AK", "99801,99811,0.05,Juneau"),
+                ("US-CO", "80201,80299,0.04,Denver"),
+                ("US-LA", "71201,71213,0.06,Monroe"),
+            ]),
+            loads: loads.clone(),
+        };
+        (store, loads)
+    }
+
+    #[test]
+    fn test_lazy_loading_and_eviction() {
+        let table_size = BoundaryTable::parse("99801,99811,0.05,Juneau")
+            .unwrap()
+            .memory_size();
+        let (store, loads) = counting_store();
+        let mut db = TaxDatabase::new().expect("Embedded data should load");
+        db.set_local_rate_store(store, 2 * table_size);
+        assert_eq!(db.resident_local_rates(), 0);
+
+        assert!(db.local_table("US-AK").is_some());
+        assert!(db.local_table("US-CO").is_some());
+        assert_eq!(db.resident_local_rates(), 2 * table_size);
+        // Resident tables aren't loaded again
+        assert!(db.local_table("US-AK").is_some());
+        assert_eq!(loads.load(Ordering::Relaxed), 2);
+
+        // Loading a third table evicts the least recently used one (Colorado)
+        assert!(db.local_table("US-LA").is_some());
+        assert_eq!(db.resident_local_rates(), 2 * table_size);
+        assert!(db.local_table("US-AK").is_some());
+        assert_eq!(loads.load(Ordering::Relaxed), 3);
+        assert!(db.local_table("US-CO").is_some());
+        assert_eq!(loads.load(Ordering::Relaxed), 4);
+
+        // States without a table are remembered too
+        assert!(db.local_table("US-TX").is_none());
+        assert!(db.local_table("US-TX").is_none());
+        assert_eq!(loads.load(Ordering::Relaxed), 5);
+
+        // Tables larger than the budget are used, but not kept
+        let (store, loads) = counting_store();
+        db.set_local_rate_store(store, table_size - 1);
+        assert!(db.local_table("US-AK").is_some());
+        assert!(db.local_table("US-AK").is_some());
+        assert_eq!(loads.load(Ordering::Relaxed), 2);
+        assert_eq!(db.resident_local_rates(), 0);
+    }
+
+    #[test]
+    fn test_calculation_with_lazy_local_rates() {
+        let (store, _) = counting_store();
+        let mut db = TaxDatabase::new().expect("Embedded data should load");
+        db.set_local_rate_store(store, 1024 * 1024);
+
+        let mut scenario = TaxScenario::new(
+            Region::parse("US-WA").unwrap(),
+            Region::parse("US-AK").unwrap().with_postal_code("99801"),
+            TransactionType::B2C,
+        );
+        scenario.threshold_policy = ThresholdPolicy::AssumeAbove;
+        assert_eq!(scenario.calculate_tax(100.0, &db).unwrap(), 5.0);
+
+        // Loaded tables take precedence over the store
+        db.load_local_rates(
+            "US-AK",
+            BoundaryTable::parse("99801,99811,0.03,Juneau").unwrap(),
+        );
+        assert_eq!(scenario.calculate_tax(100.0, &db).unwrap(), 3.0);
+        assert!(RateProvider::local_table(&db, "US-AK").is_some());
+    }
+
+    #[test]
+    fn test_directory_store() {
+        let dir = std::env::temp_dir().join(format!("world-tax-local-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("US-AK.csv"), "# Juneau\n99801,99811,0.05,Juneau\n").unwrap();
+        std::fs::write(dir.join("US-CO.csv"), "80201,80299,4%,Denver\n").unwrap();
+
+        let store = DirectoryStore::new(&dir);
+        let table = store.load("US-AK").unwrap().expect("Table should be found");
+        assert_eq!(table.lookup("99801").unwrap().rate, 0.05);
+        assert!(store.load("US-TX").unwrap().is_none());
+        assert!(matches!(
+            store.load("US-CO"),
+            Err(DatabaseError::InvalidBoundaryRecord(1))
+        ));
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn test_prefetch() {
+        let table_size = BoundaryTable::parse("99801,99811,0.05,Juneau")
+            .unwrap()
+            .memory_size();
+        let (store, loads) = counting_store();
+        let mut db = TaxDatabase::new().expect("Embedded data should load");
+        db.set_local_rate_store(store, 3 * table_size);
+
+        let destinations = ["US-AK", "US-CO", "US-AK", "US-TX", "DE", "FJ", "FJ"]
+            .map(|code| Region::parse(code).expect("Valid region"));
+        let problems = db.prefetch(&destinations);
+        assert_eq!(problems.len(), 1);
+        assert!(matches!(
+            &problems[0],
+            DatabaseError::CountryNotFound { code, .. } if code == "FJ"
+        ));
+        // Alaska, Colorado, and Texas (without a table), once each
+        assert_eq!(loads.load(Ordering::Relaxed), 3);
+        assert_eq!(db.resident_local_rates(), 2 * table_size);
+
+        // The batch is served from the cache
+        assert!(db.local_table("US-AK").is_some());
+        assert!(db.local_table("US-CO").is_some());
+        assert!(db.local_table("US-TX").is_none());
+        assert_eq!(loads.load(Ordering::Relaxed), 3);
+
+        // Tables that can't be loaded are reported
+        db.set_local_rate_store(DirectoryStore::new("/dev/null"), table_size);
+        let problems = db.prefetch(&[Region::parse("US-AK").unwrap()]);
+        assert!(matches!(
+            &problems[..],
+            [DatabaseError::LocalRatesUnavailable { state, .. }] if state == "US-AK"
+        ));
+    }
+}
@@ -0,0 +1,158 @@
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+
+    use crate::calculator::{TaxCalculator, TaxRequest};
+    use crate::sst::BoundaryTable;
+    use crate::{
+        Percentage, RateSource, Region, TaxDatabase, TaxScenario, TaxType, ThresholdPolicy,
+        TransactionType, Warning,
+    };
+
+    fn setup() -> TaxDatabase {
+        TaxDatabase::from_files("vat_rates.json", "trade_agreements.json")
+            .expect("Tax database should load")
+    }
+
+    fn alaska(postal_code: Option<&str>) -> TaxScenario {
+        let mut region = Region::parse("US-AK").expect("Valid Alaska region");
+        if let Some(postal_code) = postal_code {
+            region = region.with_postal_code(postal_code);
+        }
+        let mut scenario = TaxScenario::new(
+            Region::parse("US-WA").expect("Valid Washington region"),
+            region,
+            TransactionType::B2C,
+        );
+        scenario.threshold_policy = ThresholdPolicy::AssumeAbove;
+        scenario
+    }
+
+    #[test]
+    fn test_alaska_without_local_rates_warns() {
+        let db = setup();
+        assert!(db.has_local_taxes("US", "US-AK"));
+
+        let quote = db
+            .quote(TaxRequest::new(alaska(Some("99801")), 100.0))
+            .expect("Quote should succeed");
+        assert_eq!(quote.tax, 0.0);
+        assert_eq!(
+            quote.warnings,
+            vec![Warning::LocalTaxesPossible {
+                region: "US-AK".to_string()
+            }]
+        );
+    }
+
+    #[test]
+    fn test_alaska_local_rate_layer() {
+        let mut db = setup();
+        db.load_local_rates(
+            "US-AK",
+            BoundaryTable::parse("99801,99811,0.05,Juneau\n99901,99950,0.07,Ketchikan").unwrap(),
+        );
+
+        let quote = db
+            .quote(TaxRequest::new(alaska(Some("99801")), 100.0))
+            .expect("Quote should succeed");
+        assert_eq!(quote.rates.len(), 1);
+        assert_eq!(quote.rates[0].tax_type, TaxType::LocalSalesTax);
+        assert_eq!(quote.rates[0].source, RateSource::Local);
+        assert_eq!(quote.tax, 5.0);
+        assert!(quote.warnings.is_empty());
+
+        // Without a postal code, the local rate can't be resolved
+        let quote = db
+            .quote(TaxRequest::new(alaska(None), 100.0))
+            .expect("Quote should succeed");
+        assert_eq!(quote.tax, 0.0);
+        assert_eq!(quote.warnings.len(), 1);
+    }
+
+    #[test]
+    fn test_local_rates_outside_sst() {
+        let mut db = setup();
+        db.load_local_rates(
+            "US-CA",
+            BoundaryTable::parse("90001,90089,0.0225,Los Angeles").unwrap(),
+        );
+        assert!(db.sst_boundaries("US-CA").is_none());
+
+        let mut scenario = alaska(None);
+        scenario.destination_region = Region::parse("US-CA").unwrap().with_postal_code("90012");
+        let rates = scenario
+            .get_rates(100.0, &db)
+            .expect("Rates should be found");
+        assert_eq!(rates.len(), 2);
+        assert_eq!(rates[1].rate, Percentage::from_fraction(dec!(0.0225)));
+    }
+
+    #[test]
+    fn test_home_rule_jurisdiction() {
+        let mut db = setup();
+        let colorado = "80201,80239,0.0481,Denver,true\n80901,80951,0.0307,Colorado Springs,true\n81301,81303,0.04,,false";
+        db.load_local_rates("US-CO", BoundaryTable::parse(colorado).unwrap());
+
+        let mut scenario = alaska(None);
+        scenario.destination_region = Region::parse("US-CO").unwrap().with_postal_code("80202");
+        let quote = db
+            .quote(TaxRequest::new(scenario.clone(), 100.0))
+            .expect("Quote should succeed");
+        assert_eq!(quote.rates[1].rate, Percentage::from_fraction(dec!(0.0481)));
+        assert_eq!(
+            quote.warnings,
+            vec![Warning::HomeRuleJurisdiction {
+                region: "US-CO".to_string(),
+                jurisdiction: Some("Denver".to_string()),
+            }]
+        );
+
+        scenario.destination_region = Region::parse("US-CO").unwrap().with_postal_code("81301");
+        let quote = db
+            .quote(TaxRequest::new(scenario, 100.0))
+            .expect("Quote should succeed");
+        assert_eq!(quote.rates.len(), 2);
+        assert!(quote.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_local_rate_by_jurisdiction() {
+        let mut db = setup();
+        db.load_local_rates(
+            "US-AK",
+            BoundaryTable::parse("99801,99811,0.05,Juneau\n99901,99950,0.07,Ketchikan").unwrap(),
+        );
+
+        let mut scenario = alaska(None);
+        scenario.destination_region = Region::parse("US-AK")
+            .unwrap()
+            .with_jurisdiction("ketchikan");
+        let quote = db
+            .quote(TaxRequest::new(scenario.clone(), 100.0))
+            .expect("Quote should succeed");
+        assert_eq!(quote.tax, 7.0);
+        assert!(quote.warnings.is_empty());
+
+        // The postal code takes precedence
+        scenario.destination_region = scenario.destination_region.with_postal_code("99801");
+        assert_eq!(scenario.calculate_tax(100.0, &db).unwrap(), 5.0);
+
+        // Combined state and local rates of a region
+        db.load_local_rates(
+            "US-CA",
+            BoundaryTable::parse("90001,90089,0.0225,Los Angeles").unwrap(),
+        );
+        let los_angeles = Region::parse("US-CA")
+            .unwrap()
+            .with_jurisdiction("Los Angeles");
+        let rates = db.get_rate_at(&los_angeles, None).unwrap();
+        assert_eq!(rates.len(), 2);
+        assert_eq!(rates[0].tax_type, TaxType::StateSalesTax);
+        assert_eq!(rates[1].rate, Percentage::from_fraction(dec!(0.0225)));
+        let unknown = Region::parse("US-CA")
+            .unwrap()
+            .with_jurisdiction("Springfield");
+        assert_eq!(db.get_rate_at(&unknown, None).unwrap().len(), 1);
+    }
+}
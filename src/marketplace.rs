@@ -0,0 +1,166 @@
+//! Marketplace and platform fee handling.
+//!
+//! A marketplace commission charged to a seller is a B2B service of its own, with
+//! its place of supply where the seller is established. This module builds the
+//! commission scenario, quotes it together with the underlying sale, and settles
+//! the amounts between buyer, platform and seller.
+
+use serde::Serialize;
+
+use crate::calculator::{TaxCalculator, TaxQuote, TaxRequest};
+use crate::vat_id::VatIdCheck;
+use crate::{ProcessingError, Region, TaxScenario, TransactionType};
+
+/// A commission a platform charges a seller for a sale.
+#[derive(Debug, Clone)]
+pub struct Commission {
+    /// Where the platform is established
+    pub platform: Region,
+    /// The net commission amount
+    pub amount: f64,
+    /// The check of the seller's VAT number; intra-EU commissions are only reverse
+    /// charged with one
+    pub seller_vat_id: Option<VatIdCheck>,
+}
+
+impl Commission {
+    /// Creates a new commission.
+    pub fn new(platform: Region, amount: f64) -> Self {
+        Self {
+            platform,
+            amount,
+            seller_vat_id: None,
+        }
+    }
+
+    /// Sets the seller's VAT number, checked by its format or verified online.
+    pub fn with_seller_vat_id(mut self, check: impl Into<VatIdCheck>) -> Self {
+        self.seller_vat_id = Some(check.into());
+        self
+    }
+}
+
+impl TaxScenario {
+    /// Creates the scenario for a platform fee charged to a seller.
+    ///
+    /// The fee is a B2B electronically supplied service from the platform to the
+    /// seller: domestic fees carry the platform's local tax, intra-EU fees are reverse
+    /// charged (given the seller's VAT number), and fees to sellers outside an
+    /// agreement are zero-rated exports.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use world_tax::vat_id::VatId;
+    /// # use world_tax::{Region, TaxDatabase, TaxScenario};
+    /// let db = TaxDatabase::fixture();
+    /// let scenario = TaxScenario::platform_fee(
+    ///     Region::new("FR".to_string(), None).unwrap(),
+    ///     Region::new("DE".to_string(), None).unwrap(),
+    /// )
+    /// .with_buyer_vat_id(VatId::parse("DE136695976").unwrap());
+    /// assert_eq!(scenario.calculate_tax(15.0, &db).unwrap(), 0.0); // Reverse charge
+    /// ```
+    pub fn platform_fee(platform: Region, seller: Region) -> Self {
+        let mut scenario = TaxScenario::new(platform, seller, TransactionType::B2B);
+        scenario.is_digital_product_or_service = true;
+        scenario
+    }
+}
+
+/// The quotes of a sale and the platform's commission on it.
+#[derive(Debug, Serialize)]
+pub struct MarketplaceQuote {
+    /// Tax on the sale to the buyer
+    pub sale: TaxQuote,
+    /// Tax on the commission charged to the seller
+    pub commission: TaxQuote,
+}
+
+/// Quotes a sale and the platform's commission in one pass.
+///
+/// The seller is the sale's source region. The commission scenario uses the same
+/// calculation profile as the sale, so both follow the same platform policy.
+///
+/// # Errors
+///
+/// Returns an error if either quote fails.
+pub fn quote_with_commission<C: TaxCalculator + ?Sized>(
+    calculator: &C,
+    sale: TaxRequest,
+    commission: &Commission,
+) -> Result<MarketplaceQuote, ProcessingError> {
+    let mut fee_scenario = TaxScenario::platform_fee(
+        commission.platform.clone(),
+        sale.scenario.source_region.clone(),
+    );
+    fee_scenario.profile = sale.scenario.profile.clone();
+    fee_scenario.buyer_vat_id = commission.seller_vat_id.clone();
+
+    let sale = calculator.quote(sale)?;
+    let commission = calculator.quote(TaxRequest::new(fee_scenario, commission.amount))?;
+    Ok(MarketplaceQuote { sale, commission })
+}
+
+/// The amounts of a marketplace sale, settled between buyer, platform and seller.
+#[derive(Debug, Serialize)]
+pub struct Settlement {
+    /// Tax the buyer pays on the sale
+    pub buyer_tax: f64,
+    /// Total the buyer pays (sale amount plus tax)
+    pub buyer_total: f64,
+    /// Tax the platform charges on its commission
+    pub commission_tax: f64,
+    /// Total the platform withholds (commission plus tax)
+    pub commission_total: f64,
+    /// What the platform pays out to the seller (buyer total minus commission total)
+    pub seller_payout: f64,
+    /// The underlying quotes
+    pub quotes: MarketplaceQuote,
+}
+
+impl From<MarketplaceQuote> for Settlement {
+    fn from(quotes: MarketplaceQuote) -> Self {
+        let seller_payout = ((quotes.sale.total - quotes.commission.total) * 100.0).round() / 100.0;
+        Self {
+            buyer_tax: quotes.sale.tax,
+            buyer_total: quotes.sale.total,
+            commission_tax: quotes.commission.tax,
+            commission_total: quotes.commission.total,
+            seller_payout,
+            quotes,
+        }
+    }
+}
+
+/// Settles a sale and the platform's commission in one call.
+///
+/// The seller remains liable for the tax on the sale (it's part of the payout), and
+/// self-assesses reverse charged commission tax.
+///
+/// # Examples
+///
+/// ```
+/// # use world_tax::calculator::TaxRequest;
+/// # use world_tax::marketplace::{settle, Commission};
+/// # use world_tax::{Region, TaxDatabase, TaxScenario, TransactionType};
+/// let db = TaxDatabase::fixture();
+/// let germany = Region::new("DE".to_string(), None).unwrap();
+/// let sale = TaxScenario::new(germany.clone(), germany.clone(), TransactionType::B2C);
+///
+/// let settlement = settle(&db, TaxRequest::new(sale, 100.0), &Commission::new(germany, 10.0)).unwrap();
+/// assert_eq!(settlement.buyer_total, 119.0);
+/// assert_eq!(settlement.commission_total, 11.9);
+/// assert_eq!(settlement.seller_payout, 107.1);
+/// ```
+///
+/// # Errors
+///
+/// Returns an error if either quote fails.
+pub fn settle<C: TaxCalculator + ?Sized>(
+    calculator: &C,
+    sale: TaxRequest,
+    commission: &Commission,
+) -> Result<Settlement, ProcessingError> {
+    quote_with_commission(calculator, sale, commission).map(Settlement::from)
+}
@@ -0,0 +1,61 @@
+#[cfg(test)]
+mod tests {
+    use crate::calculator::TaxRequest;
+    use crate::marketplace::{quote_with_commission, settle, Commission};
+    use crate::vat_id::VatId;
+    use crate::{Region, TaxCalculationType, TaxDatabase, TaxScenario, TransactionType};
+
+    fn region(code: &str) -> Region {
+        Region::parse(code).expect("Valid region")
+    }
+
+    #[test]
+    fn test_platform_fee_domestic() {
+        let db = TaxDatabase::fixture();
+        let scenario = TaxScenario::platform_fee(region("DE"), region("DE"));
+        let tax = scenario
+            .calculate_tax(10.0, &db)
+            .expect("Tax calculation should succeed");
+        assert_eq!(tax, 1.9); // German VAT on the fee
+    }
+
+    #[test]
+    fn test_quote_with_commission_cross_border() {
+        let db = TaxDatabase::fixture();
+        let sale = TaxScenario::new(region("DE"), region("DE"), TransactionType::B2C);
+
+        let quote = quote_with_commission(
+            &db,
+            TaxRequest::new(sale, 100.0),
+            &Commission::new(region("FR"), 15.0)
+                .with_seller_vat_id(VatId::parse("DE136695976").unwrap()),
+        )
+        .expect("Quote should succeed");
+        assert_eq!(quote.sale.tax, 19.0);
+        assert_eq!(
+            quote.commission.calculation_type,
+            TaxCalculationType::ReverseCharge
+        );
+        assert_eq!(quote.commission.tax, 0.0);
+    }
+
+    #[test]
+    fn test_settle_cross_border_commission() {
+        let db = TaxDatabase::fixture();
+        let mut sale = TaxScenario::new(region("FR"), region("DE"), TransactionType::B2C);
+        sale.is_digital_product_or_service = true;
+
+        let settlement = settle(
+            &db,
+            TaxRequest::new(sale, 50.0),
+            &Commission::new(region("DE"), 7.5)
+                .with_seller_vat_id(VatId::parse("FR40303265045").unwrap()),
+        )
+        .expect("Settlement should succeed");
+        assert_eq!(settlement.buyer_tax, 9.5); // German VAT, digital destination rule
+        assert_eq!(settlement.buyer_total, 59.5);
+        assert_eq!(settlement.commission_tax, 0.0); // Reverse charged to the French seller
+        assert_eq!(settlement.commission_total, 7.5);
+        assert_eq!(settlement.seller_payout, 52.0);
+    }
+}
@@ -0,0 +1,216 @@
+//! Dataset schema versions and migrations.
+//!
+//! Dataset files carry a top-level `schema_version`; files without one are treated
+//! as version 1. On load, older files are upgraded step by step to
+//! `CURRENT_SCHEMA_VERSION`, so custom files pinned to an older release keep working
+//! after the dataset format changes.
+//!
+//! Version history:
+//!
+//! - 1: Rates may be `false` to mean "no rate"; thresholds are plain numbers. A `true`
+//!   rate has no meaning and is rejected.
+//! - 2: Missing rates are `null`; thresholds are `{ "amount": .., "basis": .. }`
+
+use serde_json::{json, Map, Value};
+
+use crate::errors::DatasetError;
+use crate::schema::SchemaViolation;
+
+/// The dataset schema version this release reads natively.
+pub const CURRENT_SCHEMA_VERSION: u32 = 2;
+
+/// Rate fields of a country that were allowed to be booleans in version 1.
+const OPTIONAL_RATES: [&str; 4] = [
+    "reduced_rate",
+    "reduced_rate_alt",
+    "super_reduced_rate",
+    "parking_rate",
+];
+
+/// Threshold fields of a tax rule that were plain numbers in version 1.
+const THRESHOLDS: [&str; 2] = ["threshold", "threshold_digital_products"];
+
+/// The kind of change made while migrating a dataset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MigrationChange {
+    /// A `false` rate was replaced by `null`
+    BooleanRate,
+    /// A plain threshold number was tagged with a basis
+    UntaggedThreshold,
+    /// A state key was normalized to its ISO 3166-2 form (e.g. "BC" to "CA-BC")
+    StateKey,
+}
+
+/// A change made while migrating a dataset.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MigrationNote {
+    /// The kind of change
+    pub change: MigrationChange,
+    /// The dataset the change was made in (e.g. "vat_rates")
+    pub document: String,
+    /// JSON Pointer to the changed value
+    pub pointer: String,
+    /// What was changed
+    pub message: String,
+}
+
+/// A dataset upgraded to the current schema version.
+#[derive(Debug)]
+pub struct Migrated {
+    /// The upgraded data, without the `schema_version` key
+    pub value: Value,
+    /// The schema version the data was stored in
+    pub from_version: u32,
+    /// The changes made while upgrading
+    pub notes: Vec<MigrationNote>,
+}
+
+/// Reads the schema version of a dataset and removes the `schema_version` key.
+fn take_version(document: &str, value: &mut Value) -> Result<u32, DatasetError> {
+    let Some(object) = value.as_object_mut() else {
+        // Not a dataset; left for schema validation or deserialization to report
+        return Ok(CURRENT_SCHEMA_VERSION);
+    };
+    let version = match object.remove("schema_version") {
+        None => 1,
+        Some(version) => version
+            .as_u64()
+            .and_then(|version| u32::try_from(version).ok())
+            .ok_or_else(|| {
+                DatasetError::InvalidJson(
+                    document.to_string(),
+                    format!("invalid schema_version {}", version),
+                )
+            })?,
+    };
+    if version == 0 || version > CURRENT_SCHEMA_VERSION {
+        return Err(DatasetError::UnsupportedSchemaVersion(
+            document.to_string(),
+            version,
+        ));
+    }
+    Ok(version)
+}
+
+/// Upgrades a tax rates dataset (`vat_rates.json`) to the current schema version.
+///
+/// # Examples
+///
+/// ```
+/// # use world_tax::migration::migrate_vat_rates;
+/// let v1 = serde_json::json!({ "DE": { "type": "vat", "currency": "EUR", "standard_rate": 0.19, "reduced_rate": false } });
+///
+/// let migrated = migrate_vat_rates(v1).unwrap();
+/// assert_eq!(migrated.from_version, 1);
+/// assert!(migrated.value["DE"]["reduced_rate"].is_null());
+/// ```
+///
+/// # Errors
+///
+/// Returns `DatasetError::UnsupportedSchemaVersion` if the data is newer than this release,
+/// and `DatasetError::SchemaViolations` if a rate is `true`.
+pub fn migrate_vat_rates(mut value: Value) -> Result<Migrated, DatasetError> {
+    let from_version = take_version("vat_rates", &mut value)?;
+    let mut notes = Vec::new();
+
+    if from_version < 2 {
+        let mut violations = Vec::new();
+        for (code, country) in objects_mut(&mut value) {
+            for field in OPTIONAL_RATES {
+                let pointer = format!("/{}/{}", code, field);
+                match country.get_mut(field) {
+                    Some(Value::Bool(true)) => violations.push(SchemaViolation {
+                        document: "vat_rates".to_string(),
+                        pointer,
+                        message: "rate true has no meaning; use a number or null".to_string(),
+                    }),
+                    Some(rate @ Value::Bool(false)) => {
+                        notes.push(MigrationNote {
+                            change: MigrationChange::BooleanRate,
+                            document: "vat_rates".to_string(),
+                            pointer,
+                            message: "boolean rate false replaced by null".to_string(),
+                        });
+                        *rate = Value::Null;
+                    }
+                    _ => {}
+                }
+            }
+        }
+        if !violations.is_empty() {
+            return Err(DatasetError::SchemaViolations(violations));
+        }
+    }
+
+    Ok(Migrated {
+        value,
+        from_version,
+        notes,
+    })
+}
+
+/// Upgrades a trade agreements dataset (`trade_agreements.json`) to the current schema version.
+///
+/// # Examples
+///
+/// ```
+/// # use world_tax::migration::migrate_trade_agreements;
+/// let v1 = serde_json::json!({ "EU": { "tax_rules": { "internal_b2c": { "type": "threshold_based", "threshold": 10000 } } } });
+///
+/// let migrated = migrate_trade_agreements(v1).unwrap();
+/// assert_eq!(migrated.value["EU"]["tax_rules"]["internal_b2c"]["threshold"]["amount"], 10000);
+/// ```
+///
+/// # Errors
+///
+/// Returns `DatasetError::UnsupportedSchemaVersion` if the data is newer than this release.
+pub fn migrate_trade_agreements(mut value: Value) -> Result<Migrated, DatasetError> {
+    let from_version = take_version("trade_agreements", &mut value)?;
+    let mut notes = Vec::new();
+
+    if from_version < 2 {
+        for (id, agreement) in objects_mut(&mut value) {
+            let Some(Value::Object(rules)) = agreement.get_mut("tax_rules") else {
+                continue;
+            };
+            for (rule_name, rule) in rules.iter_mut() {
+                let Value::Object(rule) = rule else { continue };
+                for field in THRESHOLDS {
+                    if let Some(threshold) = rule.get_mut(field).filter(|t| t.is_number()) {
+                        notes.push(MigrationNote {
+                            change: MigrationChange::UntaggedThreshold,
+                            document: "trade_agreements".to_string(),
+                            pointer: format!("/{}/tax_rules/{}/{}", id, rule_name, field),
+                            message: format!(
+                                "threshold {} tagged with basis transaction",
+                                threshold
+                            ),
+                        });
+                        *threshold = json!({ "amount": threshold, "basis": "transaction" });
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(Migrated {
+        value,
+        from_version,
+        notes,
+    })
+}
+
+/// Iterates over the object-valued entries of a top-level map.
+fn objects_mut(value: &mut Value) -> impl Iterator<Item = (&String, &mut Map<String, Value>)> {
+    value
+        .as_object_mut()
+        .into_iter()
+        .flat_map(|map| map.iter_mut())
+        .filter_map(|(key, entry)| entry.as_object_mut().map(|entry| (key, entry)))
+}
+
+/// Escapes a key for use as a JSON Pointer reference token (RFC 6901): `~` as `~0`,
+/// `/` as `~1`.
+pub(crate) fn pointer_token(key: &str) -> String {
+    key.replace('~', "~0").replace('/', "~1")
+}
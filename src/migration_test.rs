@@ -0,0 +1,106 @@
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+    use serde_json::json;
+
+    use crate::migration::{migrate_trade_agreements, migrate_vat_rates, CURRENT_SCHEMA_VERSION};
+    use crate::types::{Threshold, ThresholdBasis};
+    use crate::{DatasetError, Region, TaxDatabase, TaxScenario, TransactionType, VatRate};
+
+    fn v1_rates() -> String {
+        json!({
+            "DE": {
+                "type": "vat",
+                "currency": "EUR",
+                "standard_rate": 0.19,
+                "reduced_rate": 0.07,
+                "super_reduced_rate": false
+            }
+        })
+        .to_string()
+    }
+
+    fn v1_agreements() -> String {
+        json!({
+            "EU": {
+                "name": "European Union",
+                "type": "customs_union",
+                "members": ["DE"],
+                "default_applicable": true,
+                "applies_to": { "physical_goods": true, "digital_goods": true, "services": true },
+                "tax_rules": {
+                    "internal_b2c": {
+                        "type": "threshold_based",
+                        "below_threshold": "origin",
+                        "above_threshold": "destination",
+                        "threshold": 10000
+                    },
+                    "external_export": { "type": "zero_rated" }
+                }
+            }
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn test_migrate_v1_rates() {
+        let migrated = migrate_vat_rates(serde_json::from_str(&v1_rates()).unwrap()).unwrap();
+        assert_eq!(migrated.from_version, 1);
+        assert!(migrated.value["DE"]["super_reduced_rate"].is_null());
+        assert_eq!(migrated.notes.len(), 1);
+        assert_eq!(migrated.notes[0].pointer, "/DE/super_reduced_rate");
+    }
+
+    #[test]
+    fn test_current_version_is_unchanged() {
+        let data =
+            json!({ "schema_version": CURRENT_SCHEMA_VERSION, "DE": { "reduced_rate": null } });
+        let migrated = migrate_vat_rates(data).unwrap();
+        assert_eq!(migrated.from_version, CURRENT_SCHEMA_VERSION);
+        assert!(migrated.notes.is_empty());
+        assert!(migrated.value.get("schema_version").is_none());
+    }
+
+    #[test]
+    fn test_unsupported_version() {
+        let result = migrate_trade_agreements(json!({ "schema_version": 99 }));
+        assert!(matches!(
+            result,
+            Err(DatasetError::UnsupportedSchemaVersion(_, 99))
+        ));
+    }
+
+    #[test]
+    fn test_load_v1_dataset() {
+        let db =
+            TaxDatabase::from_json(&v1_rates(), &v1_agreements()).expect("v1 data should load");
+        assert_eq!(db.migration_notes().len(), 2);
+        assert_eq!(
+            db.get_country("DE").unwrap().rate(&VatRate::SuperReduced),
+            None
+        );
+
+        let rule = db.get_rule("EU").unwrap().tax_rules.internal_b2c.unwrap();
+        assert_eq!(
+            rule.threshold,
+            Some(Threshold {
+                amount: dec!(10000),
+                basis: ThresholdBasis::Transaction,
+                currency: None,
+            })
+        );
+
+        // Strict mode validates the migrated data
+        assert!(TaxDatabase::from_json_strict(&v1_rates(), &v1_agreements()).is_ok());
+
+        let germany = Region::new("DE".to_string(), None).unwrap();
+        let scenario = TaxScenario::new(germany.clone(), germany, TransactionType::B2C);
+        assert_eq!(scenario.calculate_tax(100.0, &db).unwrap(), 19.0);
+    }
+
+    #[test]
+    fn test_embedded_data_is_current() {
+        assert!(TaxDatabase::new().unwrap().migration_notes().is_empty());
+        assert!(TaxDatabase::fixture().migration_notes().is_empty());
+    }
+}
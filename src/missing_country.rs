@@ -0,0 +1,112 @@
+//! Countries missing from the dataset.
+//!
+//! The dataset doesn't cover every country (e.g. Guinea, Fiji, Somalia), and by
+//! default a calculation involving one fails with `DatabaseError::CountryNotFound`.
+//! Integrators who prefer a result to an error set a `MissingCountryPolicy` on the
+//! database: countries without data are then taxed at zero or at a rate of their
+//! choosing, and quotes flag the result with `Warning::CountryDefaulted`.
+//!
+//! The policy applies to ISO 3166-1 codes only, so typos are still reported, and
+//! `TaxDatabase::get_country` keeps returning the dataset's countries alone.
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+use typeshare::typeshare;
+
+use crate::percentage::Percentage;
+use crate::types::{Country, TaxSystemType};
+use crate::TaxDatabase;
+
+/// What to do with a country that isn't in the dataset.
+#[typeshare]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "content")]
+#[serde(rename_all = "snake_case")]
+pub enum MissingCountryPolicy {
+    /// Fail with `DatabaseError::CountryNotFound`
+    #[default]
+    Error,
+    /// Charge no tax, as in a country without a sales tax
+    ZeroTax,
+    /// Charge VAT at the given rate
+    DefaultRate(Percentage),
+}
+
+impl MissingCountryPolicy {
+    /// The data countries that aren't in the dataset are taxed with.
+    pub(crate) fn country(&self) -> Option<Country> {
+        let (tax_type, standard_rate) = match *self {
+            MissingCountryPolicy::Error => return None,
+            MissingCountryPolicy::ZeroTax => (TaxSystemType::None, Percentage::ZERO),
+            MissingCountryPolicy::DefaultRate(rate) => (TaxSystemType::Vat, rate),
+        };
+        Some(Country {
+            tax_type,
+            currency: String::new(),
+            standard_rate,
+            reduced_rate: None,
+            reduced_rate_alt: None,
+            super_reduced_rate: None,
+            parking_rate: None,
+            vat_name: None,
+            vat_abbr: None,
+            states: None,
+            import_rules: None,
+            legal_reference: None,
+            rate_history: Vec::new(),
+            additional_taxes: Vec::new(),
+            interstate_rates: None,
+            category_rates: HashMap::new(),
+            extensions: BTreeMap::new(),
+        })
+    }
+}
+
+impl TaxDatabase {
+    /// Sets what to do with countries that aren't in the dataset.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use world_tax::missing_country::MissingCountryPolicy;
+    /// # use world_tax::{Percentage, Region, TaxDatabase, TaxScenario, TransactionType};
+    /// # use rust_decimal_macros::dec;
+    /// let mut db = TaxDatabase::fixture();
+    /// let sale = TaxScenario::new(
+    ///     Region::parse("FJ").unwrap(),
+    ///     Region::parse("FJ").unwrap(),
+    ///     TransactionType::B2C,
+    /// );
+    /// assert!(sale.calculate_tax(100.0, &db).is_err());
+    ///
+    /// db.set_missing_country_policy(MissingCountryPolicy::ZeroTax);
+    /// assert_eq!(sale.calculate_tax(100.0, &db).unwrap(), 0.0);
+    ///
+    /// db.set_missing_country_policy(MissingCountryPolicy::DefaultRate(
+    ///     Percentage::from_percent(dec!(15)),
+    /// ));
+    /// assert_eq!(sale.calculate_tax(100.0, &db).unwrap(), 15.0);
+    /// ```
+    pub fn set_missing_country_policy(&mut self, policy: MissingCountryPolicy) {
+        self.missing_country_policy = policy;
+        self.missing_country = policy.country();
+    }
+
+    /// What is done with countries that aren't in the dataset.
+    pub fn missing_country_policy(&self) -> MissingCountryPolicy {
+        self.missing_country_policy
+    }
+
+    /// The data a country that isn't in the dataset is taxed with, if the policy
+    /// doesn't reject it.
+    pub(crate) fn missing_country(&self, code: &str) -> Option<&Country> {
+        rust_iso3166::from_alpha2(code)?;
+        self.missing_country.as_ref()
+    }
+
+    /// Returns true if the country isn't in the dataset, and is taxed by the policy.
+    pub(crate) fn is_defaulted_country(&self, code: &str) -> bool {
+        !self.countries.contains_key(code) && self.missing_country(code).is_some()
+    }
+}
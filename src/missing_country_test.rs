@@ -0,0 +1,87 @@
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+
+    use crate::calculator::{TaxCalculator, TaxRequest};
+    use crate::confidence::Confidence;
+    use crate::missing_country::MissingCountryPolicy;
+    use crate::{
+        DatabaseError, Percentage, ProcessingError, Region, TaxCalculationType, TaxDatabase,
+        TaxScenario, TransactionType, Warning,
+    };
+
+    fn sale(source: &str, destination: &str) -> TaxScenario {
+        TaxScenario::new(
+            Region::parse(source).expect("Valid region"),
+            Region::parse(destination).expect("Valid region"),
+            TransactionType::B2C,
+        )
+    }
+
+    #[test]
+    fn test_missing_country_fails_by_default() {
+        let db = TaxDatabase::new().unwrap();
+        assert_eq!(db.missing_country_policy(), MissingCountryPolicy::Error);
+        for country in ["GN", "FJ", "SO"] {
+            assert!(matches!(
+                sale(country, country).calculate_tax(100.0, &db),
+                Err(ProcessingError::DatabaseError(
+                    DatabaseError::CountryNotFound { .. }
+                ))
+            ));
+        }
+    }
+
+    #[test]
+    fn test_zero_tax() {
+        let mut db = TaxDatabase::new().unwrap();
+        db.set_missing_country_policy(MissingCountryPolicy::ZeroTax);
+
+        let quote = db.quote(TaxRequest::new(sale("FJ", "FJ"), 100.0)).unwrap();
+        assert_eq!(quote.tax, 0.0);
+        assert_eq!(
+            quote.warnings,
+            vec![Warning::CountryDefaulted {
+                country: "FJ".to_string()
+            }]
+        );
+        assert_eq!(quote.confidence, Confidence::Unknown);
+
+        // Exports to the country are zero rated as before, without a warning
+        let export = db.quote(TaxRequest::new(sale("DE", "FJ"), 100.0)).unwrap();
+        assert_eq!(export.calculation_type, TaxCalculationType::ZeroRated);
+        assert!(export.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_default_rate() {
+        let mut db = TaxDatabase::new().unwrap();
+        db.set_missing_country_policy(MissingCountryPolicy::DefaultRate(Percentage::from_percent(
+            dec!(15),
+        )));
+        assert_eq!(sale("SO", "SO").calculate_tax(100.0, &db).unwrap(), 15.0);
+        assert_eq!(
+            db.get_rate("GN", None, None).unwrap()[0].rate.to_string(),
+            "15%"
+        );
+
+        // Countries in the dataset, and codes that aren't countries, are unaffected
+        assert_eq!(sale("DE", "DE").calculate_tax(100.0, &db).unwrap(), 19.0);
+        assert!(matches!(
+            db.get_rate("XX", None, None),
+            Err(DatabaseError::CountryNotFound { .. })
+        ));
+        assert!(db.get_country("SO").is_err());
+    }
+
+    #[test]
+    fn test_policy_serialization() {
+        let policy = MissingCountryPolicy::DefaultRate(Percentage::from_percent(dec!(15)));
+        let json = serde_json::to_string(&policy).unwrap();
+        assert_eq!(json, r#"{"type":"default_rate","content":0.15}"#);
+        assert_eq!(
+            serde_json::from_str::<MissingCountryPolicy>(&json).unwrap(),
+            policy
+        );
+    }
+}
@@ -0,0 +1,87 @@
+//! US economic nexus by state.
+//!
+//! Since South Dakota v. Wayfair (2018), states require sellers without a physical
+//! presence to collect their sales tax once the seller's sales into the state reach a
+//! threshold over a calendar year. Most states set $100,000, some more ($500,000 in
+//! California, New York and Texas), and some also count transactions: 200 sales
+//! establish nexus on their own in many states, while Connecticut and New York
+//! require both tests to be met.
+//!
+//! The tests are recorded as `economic_nexus` on the states, and replace the single
+//! threshold of the federal agreement. The seller's sales into the state, and their
+//! number, come from the scenario's `turnover` (see `threshold::ThresholdTracker`);
+//! without it, the transaction alone is compared.
+
+use crate::currency::CurrencyCode;
+use crate::errors::DatabaseError;
+use crate::explain::Trace;
+use crate::rate_provider::{self, RateProvider};
+use crate::types::{EconomicNexus, Threshold, ThresholdBasis};
+use crate::{TaxScenario, ThresholdPolicy};
+
+impl TaxScenario {
+    /// The economic nexus test of the destination state, if the data has one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use world_tax::{Region, TaxDatabase, TaxScenario, TransactionType};
+    /// # use rust_decimal_macros::dec;
+    /// let db = TaxDatabase::new().unwrap();
+    /// let scenario = TaxScenario::new(
+    ///     Region::parse("US-WA").unwrap(),
+    ///     Region::parse("US-TX").unwrap(),
+    ///     TransactionType::B2C,
+    /// );
+    /// assert_eq!(scenario.economic_nexus(&db).unwrap().sales, dec!(500_000));
+    /// ```
+    pub fn economic_nexus<P: RateProvider + ?Sized>(&self, db: &P) -> Option<EconomicNexus> {
+        let destination = &self.destination_region;
+        let state = destination.region.as_deref()?;
+        let country = db.get_country(&destination.country).ok()?;
+        rate_provider::state(&country, &destination.country, state)?.economic_nexus
+    }
+
+    /// Whether the sale establishes or falls under economic nexus in the destination
+    /// state; `None` if the state has no test in the data.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::ExchangeRateNotFound` if the scenario's amounts have to be
+    /// converted to US dollars, without exchange rate.
+    pub(crate) fn has_economic_nexus<P: RateProvider + ?Sized>(
+        &self,
+        amount: f64,
+        db: &P,
+        trace: &mut Trace,
+    ) -> Result<Option<bool>, DatabaseError> {
+        let Some(nexus) = self.economic_nexus(db) else {
+            return Ok(None);
+        };
+        let threshold = Threshold {
+            amount: nexus.sales,
+            basis: ThresholdBasis::AnnualTurnover,
+            currency: CurrencyCode::parse("USD").ok(),
+        };
+        let policy = self.unknown_turnover_policy(Some(threshold), self.threshold_policy, trace);
+        let (sales, _) = self.threshold_amount(Some(threshold), amount, policy, db, trace)?;
+        let transactions = self
+            .turnover
+            .map_or(1, |turnover| turnover.transactions_with_transaction());
+        let has_nexus = match policy {
+            ThresholdPolicy::Respect => nexus.is_met(sales, transactions),
+            ThresholdPolicy::AssumeAbove => true,
+            ThresholdPolicy::AssumeBelow => false,
+        };
+        trace.record("economic_nexus", || {
+            format!(
+                "{:?}, sales {}, transactions {}: {}",
+                nexus,
+                sales,
+                transactions,
+                if has_nexus { "nexus" } else { "no nexus" }
+            )
+        });
+        Ok(Some(has_nexus))
+    }
+}
@@ -0,0 +1,126 @@
+#[cfg(test)]
+mod tests {
+    use crate::threshold::ThresholdTracker;
+    use crate::types::AnnualTurnover;
+    use crate::{
+        Region, TaxCalculationType, TaxDatabase, TaxScenario, ThresholdPolicy, TransactionType,
+    };
+
+    fn setup() -> TaxDatabase {
+        TaxDatabase::from_files("vat_rates.json", "trade_agreements.json")
+            .expect("Tax database should load")
+    }
+
+    fn sale(destination: &str, sales: f64, transactions: u32) -> TaxScenario {
+        TaxScenario::new(
+            Region::parse("US-OR").expect("Valid region"),
+            Region::parse(destination).expect("Valid region"),
+            TransactionType::B2C,
+        )
+        .with_turnover(AnnualTurnover {
+            current_year: sales,
+            current_year_transactions: transactions,
+            ..Default::default()
+        })
+    }
+
+    #[test]
+    fn test_sales_threshold_per_state() {
+        let db = setup();
+        let calculation_type =
+            |scenario: TaxScenario| scenario.determine_calculation_type(&db, 100.0).unwrap();
+
+        // $100,000 in Florida, $500,000 in California
+        assert_eq!(
+            calculation_type(sale("US-FL", 200_000.0, 10)),
+            TaxCalculationType::Destination
+        );
+        assert_eq!(
+            calculation_type(sale("US-CA", 200_000.0, 10)),
+            TaxCalculationType::ZeroRated
+        );
+        assert_eq!(
+            calculation_type(sale("US-CA", 499_950.0, 10)),
+            TaxCalculationType::Destination
+        );
+
+        // Without turnover, a sale isn't taken for the year's sales
+        let mut single = sale("US-FL", 0.0, 0);
+        single.turnover = None;
+        assert_eq!(
+            calculation_type(single.clone()),
+            TaxCalculationType::Destination
+        );
+        single.threshold_policy = ThresholdPolicy::AssumeBelow;
+        assert_eq!(calculation_type(single), TaxCalculationType::ZeroRated);
+
+        // Colorado's notice and report regime still applies below its threshold
+        assert_eq!(
+            calculation_type(sale("US-CO", 1_000.0, 1)),
+            TaxCalculationType::NoticeAndReport
+        );
+    }
+
+    #[test]
+    fn test_transaction_threshold() {
+        let db = setup();
+        let calculation_type =
+            |scenario: TaxScenario| scenario.determine_calculation_type(&db, 100.0).unwrap();
+
+        // Georgia: $100,000 or 200 transactions; the sale is the 200th
+        assert_eq!(
+            calculation_type(sale("US-GA", 5_000.0, 199)),
+            TaxCalculationType::Destination
+        );
+        assert_eq!(
+            calculation_type(sale("US-GA", 5_000.0, 198)),
+            TaxCalculationType::ZeroRated
+        );
+        // Florida doesn't count transactions
+        assert_eq!(
+            calculation_type(sale("US-FL", 5_000.0, 1_000)),
+            TaxCalculationType::ZeroRated
+        );
+
+        // New York: $500,000 and 100 transactions
+        assert_eq!(
+            calculation_type(sale("US-NY", 600_000.0, 50)),
+            TaxCalculationType::ZeroRated
+        );
+        assert_eq!(
+            calculation_type(sale("US-NY", 600_000.0, 99)),
+            TaxCalculationType::Destination
+        );
+
+        let explanation = sale("US-NY", 600_000.0, 99).explain(100.0, &db).unwrap();
+        assert!(explanation
+            .steps
+            .iter()
+            .any(|step| step.check == "economic_nexus" && step.outcome.ends_with(": nexus")));
+    }
+
+    #[test]
+    fn test_tracker_counts_transactions() {
+        let db = setup();
+        let mut tracker = ThresholdTracker::new();
+        let scenario = sale("US-GA", 0.0, 0);
+        for _ in 0..199 {
+            tracker.record(&scenario, 10.0, "2025-03-01", &db).unwrap();
+        }
+        assert_eq!(tracker.transactions_in("US-GA", 2025), 199);
+
+        // Last year's 199 sales and this one make 200 in neither year
+        let tracked = tracker.track(scenario.clone(), "2026-01-05", &db).unwrap();
+        assert_eq!(
+            tracked.determine_calculation_type(&db, 10.0).unwrap(),
+            TaxCalculationType::ZeroRated
+        );
+
+        // The 200th sale of the year establishes nexus
+        let tracked = tracker.track(scenario, "2025-12-01", &db).unwrap();
+        assert_eq!(
+            tracked.determine_calculation_type(&db, 10.0).unwrap(),
+            TaxCalculationType::Destination
+        );
+    }
+}
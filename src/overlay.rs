@@ -0,0 +1,192 @@
+//! Partial rate data layered over a loaded database.
+//!
+//! Replacing the dataset with `TaxDatabase::from_json` means maintaining a full copy of
+//! it, just to correct one rate. An overlay holds only what differs: the fields of a
+//! country to change, a country the dataset is missing, an agreement's members.
+//! `TaxDatabase::merge_json` applies it to the loaded data like a JSON merge patch
+//! (RFC 7396):
+//!
+//! - Objects are merged field by field; fields the overlay doesn't mention are kept
+//! - Other values, including arrays (e.g. agreement members), replace the loaded ones
+//! - `null` removes a field, or a country or agreement
+//!
+//! Overlays are read like datasets: they are migrated from older schema versions, and
+//! state keys are normalized before merging ("BC" patches "CA-BC"). Region groups and
+//! override rules keep the regions they were resolved to when defined.
+
+use serde::de::DeserializeOwned;
+use serde::de::Error as _;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+use crate::errors::DatasetError;
+use crate::migration::{self, Migrated, MigrationNote};
+use crate::types::normalize_state_code;
+use crate::TaxDatabase;
+
+impl TaxDatabase {
+    /// Merges partial rate data over the loaded countries and trade agreements; pass
+    /// `"{}"` for a dataset without changes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use world_tax::{
+    /// #     Region, TaxCalculationType, TaxDatabase, TaxScenario, TransactionType, VatRate,
+    /// # };
+    /// let mut db = TaxDatabase::fixture();
+    /// db.merge_json(
+    ///     r#"{
+    ///         "FR": { "reduced_rate": 0.11 },
+    ///         "AT": { "type": "vat", "currency": "EUR", "standard_rate": 0.2 }
+    ///     }"#,
+    ///     r#"{ "EU": { "members": ["AT", "DE", "FR"] } }"#,
+    /// )
+    /// .unwrap();
+    ///
+    /// let france = db.get_country("FR").unwrap();
+    /// assert_eq!(france.rate(&VatRate::Reduced).unwrap().to_f64(), 0.11);
+    /// assert_eq!(france.standard_rate().to_f64(), 0.2);
+    ///
+    /// // Digital sales to consumers in the new member are taxed at its rate
+    /// let mut sale = TaxScenario::new(
+    ///     Region::parse("DE").unwrap(),
+    ///     Region::parse("AT").unwrap(),
+    ///     TransactionType::B2C,
+    /// );
+    /// sale.is_digital_product_or_service = true;
+    /// assert_eq!(
+    ///     sale.determine_calculation_type(&db, 100.0).unwrap(),
+    ///     TaxCalculationType::Destination
+    /// );
+    /// assert_eq!(sale.calculate_tax(100.0, &db).unwrap(), 20.0);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatasetError::InvalidJson` if either overlay isn't a JSON object, or a
+    /// merged country or agreement isn't valid (e.g. a new country without a
+    /// `standard_rate`), and `DatasetError::UnsupportedSchemaVersion` if either can't
+    /// be migrated. Nothing is merged then.
+    pub fn merge_json(
+        &mut self,
+        countries_json: &str,
+        trade_agreements_json: &str,
+    ) -> Result<(), DatasetError> {
+        let (mut countries, mut notes) =
+            read_overlay("vat_rates", countries_json, migration::migrate_vat_rates)?;
+        let (trade_agreements, agreement_notes) = read_overlay(
+            "trade_agreements",
+            trade_agreements_json,
+            migration::migrate_trade_agreements,
+        )?;
+        let mut state_keys = Vec::new();
+        for (code, country) in &mut countries {
+            state_keys.extend(normalize_state_keys(code, country));
+        }
+
+        let countries = merge_entries("vat_rates", &self.countries, countries)?;
+        let trade_agreements =
+            merge_entries("trade_agreements", &self.trade_agreements, trade_agreements)?;
+
+        self.countries = countries;
+        self.trade_agreements = trade_agreements;
+        for (key, written) in state_keys {
+            match written {
+                Some(written) => self.state_keys.insert(key, written),
+                None => self.state_keys.remove(&key),
+            };
+        }
+        notes.extend(agreement_notes);
+        self.migration_notes.extend(notes);
+        Ok(())
+    }
+}
+
+/// Parses and migrates an overlay, returning its entries and the migration notes.
+fn read_overlay(
+    document: &str,
+    json: &str,
+    migrate: fn(Value) -> Result<Migrated, DatasetError>,
+) -> Result<(Map<String, Value>, Vec<MigrationNote>), DatasetError> {
+    let invalid = |reason: String| DatasetError::InvalidJson(document.to_string(), reason);
+    let value = serde_json::from_str(json).map_err(|err| invalid(err.to_string()))?;
+    let migrated = migrate(value)?;
+    match migrated.value {
+        Value::Object(entries) => Ok((entries, migrated.notes)),
+        _ => Err(invalid("the overlay must be an object".to_string())),
+    }
+}
+
+/// Renames the state keys of a country's overlay to their ISO 3166-2 form; returns
+/// the keys by country and normalized key, with the key as written if it was renamed.
+fn normalize_state_keys(
+    code: &str,
+    country: &mut Value,
+) -> Vec<((String, String), Option<String>)> {
+    let Some(Value::Object(states)) = country.get_mut("states") else {
+        return Vec::new();
+    };
+    let mut keys = Vec::with_capacity(states.len());
+    *states = std::mem::take(states)
+        .into_iter()
+        .map(|(key, state)| {
+            let canonical = normalize_state_code(code, &key);
+            let written = (canonical != key).then_some(key);
+            keys.push(((code.to_string(), canonical.clone()), written));
+            (canonical, state)
+        })
+        .collect();
+    keys
+}
+
+/// Merges overlay entries over loaded ones, into a new map.
+fn merge_entries<T: Serialize + DeserializeOwned + Clone>(
+    document: &str,
+    loaded: &HashMap<String, T>,
+    overlay: Map<String, Value>,
+) -> Result<HashMap<String, T>, DatasetError> {
+    let mut merged = loaded.clone();
+    for (key, patch) in overlay {
+        if patch.is_null() {
+            merged.remove(&key);
+            continue;
+        }
+        let invalid = |err: serde_json::Error| {
+            DatasetError::InvalidJson(document.to_string(), format!("{key}: {err}"))
+        };
+        let mut value = match merged.get(&key) {
+            Some(entry) => serde_json::to_value(entry).map_err(invalid)?,
+            None => Value::Null,
+        };
+        merge_patch(&mut value, patch);
+        let entry = match value {
+            Value::Object(_) => serde_json::from_value(value).map_err(invalid)?,
+            _ => return Err(invalid(serde_json::Error::custom("expected an object"))),
+        };
+        merged.insert(key, entry);
+    }
+    Ok(merged)
+}
+
+/// Applies a JSON merge patch (RFC 7396) to a value.
+fn merge_patch(value: &mut Value, patch: Value) {
+    let Value::Object(fields) = patch else {
+        *value = patch;
+        return;
+    };
+    if !value.is_object() {
+        *value = Value::Object(Map::new());
+    }
+    let Value::Object(target) = value else {
+        return;
+    };
+    for (field, patch) in fields {
+        if patch.is_null() {
+            target.remove(&field);
+        } else {
+            merge_patch(target.entry(field).or_insert(Value::Null), patch);
+        }
+    }
+}
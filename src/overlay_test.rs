@@ -0,0 +1,122 @@
+#[cfg(test)]
+mod tests {
+    use crate::{
+        DatasetError, Region, TaxCalculationType, TaxDatabase, TaxScenario, TransactionType,
+        VatRate,
+    };
+
+    fn sale(source: &str, destination: &str, transaction_type: TransactionType) -> TaxScenario {
+        TaxScenario::new(
+            Region::parse(source).expect("Valid region"),
+            Region::parse(destination).expect("Valid region"),
+            transaction_type,
+        )
+    }
+
+    #[test]
+    fn test_merge_over_embedded_data() {
+        let mut db = TaxDatabase::new().unwrap();
+        let countries = db.countries.len();
+        db.merge_json(
+            r#"{
+                "FR": { "reduced_rate": 0.11, "x-tax-code": "FR-R" },
+                "FJ": { "type": "vat", "currency": "FJD", "standard_rate": 0.15 },
+                "CA": { "states": { "BC": { "standard_rate": 0.08 } } }
+            }"#,
+            "{}",
+        )
+        .unwrap();
+        assert_eq!(db.countries.len(), countries + 1);
+
+        // Only the given fields change
+        let france = db.get_country("FR").unwrap();
+        assert_eq!(france.rate(&VatRate::Reduced).unwrap().to_f64(), 0.11);
+        assert_eq!(france.standard_rate().to_f64(), 0.2);
+        assert_eq!(france.rate(&VatRate::SuperReduced).unwrap().to_f64(), 0.021);
+        assert_eq!(france.extensions()["x-tax-code"], "FR-R");
+        assert_eq!(
+            sale("FJ", "FJ", TransactionType::B2C)
+                .calculate_tax(100.0, &db)
+                .unwrap(),
+            15.0
+        );
+
+        // The state key is normalized, and the state's other fields are kept
+        let bc = db.get_country("CA").unwrap().state("CA-BC").unwrap();
+        assert_eq!(bc.standard_rate().to_f64(), 0.08);
+        assert_eq!(
+            db.get_country("CA").unwrap().state("BC").unwrap().code(),
+            "CA-BC"
+        );
+    }
+
+    #[test]
+    fn test_merge_agreements_and_removals() {
+        let mut db = TaxDatabase::fixture();
+        db.merge_json(
+            r#"{ "AT": { "type": "vat", "currency": "EUR", "standard_rate": 0.2 } }"#,
+            r#"{ "EU": { "members": ["AT", "DE", "FR"] } }"#,
+        )
+        .unwrap();
+        let mut digital = sale("DE", "AT", TransactionType::B2C);
+        digital.is_digital_product_or_service = true;
+        assert_eq!(
+            digital.determine_calculation_type(&db, 100.0).unwrap(),
+            TaxCalculationType::Destination
+        );
+        assert_eq!(digital.calculate_tax(100.0, &db).unwrap(), 20.0);
+
+        // Null removes a field, a country or an agreement
+        db.merge_json(
+            r#"{ "FR": { "reduced_rate": null }, "AT": null }"#,
+            r#"{ "CA": null }"#,
+        )
+        .unwrap();
+        assert!(db
+            .get_country("FR")
+            .unwrap()
+            .rate(&VatRate::Reduced)
+            .is_none());
+        assert!(db.get_country("AT").is_err());
+        assert!(!db.trade_agreements.contains_key("CA"));
+    }
+
+    #[test]
+    fn test_invalid_overlays_change_nothing() {
+        let mut db = TaxDatabase::fixture();
+        // A new country needs a full record; the valid entries aren't merged either
+        let result = db.merge_json(
+            r#"{ "FR": { "reduced_rate": 0.11 }, "AT": { "standard_rate": 0.2 } }"#,
+            "{}",
+        );
+        assert!(matches!(
+            result,
+            Err(DatasetError::InvalidJson(document, message))
+                if document == "vat_rates" && message.starts_with("AT: ")
+        ));
+        assert_eq!(
+            db.get_country("FR")
+                .unwrap()
+                .rate(&VatRate::Reduced)
+                .unwrap()
+                .to_f64(),
+            0.1
+        );
+
+        assert!(db.merge_json("[]", "{}").is_err());
+        assert!(db.merge_json(r#"{ "FR": 0.11 }"#, "{}").is_err());
+        assert!(matches!(
+            db.merge_json("{}", r#"{ "schema_version": 99 }"#),
+            Err(DatasetError::UnsupportedSchemaVersion(..))
+        ));
+
+        // Rates of older schema versions are migrated
+        db.merge_json(r#"{ "FR": { "reduced_rate": false } }"#, "{}")
+            .unwrap();
+        assert!(db
+            .get_country("FR")
+            .unwrap()
+            .rate(&VatRate::Reduced)
+            .is_none());
+    }
+}
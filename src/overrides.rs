@@ -0,0 +1,269 @@
+//! Business rule overrides.
+//!
+//! Some sales are taxed by the seller's policy rather than by the dataset alone:
+//! books to Sweden at the reduced rate, wholesale customers exempt under an
+//! arrangement on file. Override rules declare these policies on the database, and the
+//! engine applies them before the standard calculation, so they are recorded in
+//! explanations and quotes like any other decision.
+//!
+//! A rule matches a scenario by its conditions: the buyer's region (a region code, a
+//! trade agreement or a region group, see `region_group`), the transaction type, and
+//! attributes of the scenario (`TaxScenario::with_attribute`, e.g. `category=books`).
+//! Of the matching rules, the one with the highest priority applies. Matching rules of
+//! the same priority with different actions conflict: the one defined first applies,
+//! and quotes warn with `Warning::OverrideConflict`.
+//!
+//! Rules apply to calculations with a `TaxDatabase` (and its `DatedView`); other rate
+//! providers have none, unless they implement `RateProvider::matching_overrides`.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use typeshare::typeshare;
+
+use crate::errors::DatabaseError;
+use crate::explain::Trace;
+use crate::rate_provider::RateProvider;
+use crate::region_group::RegionGroup;
+use crate::{TaxDatabase, TaxScenario, TransactionType, VatRate};
+
+/// The sales an override rule applies to; a condition that isn't set matches any sale.
+#[typeshare]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OverrideCondition {
+    /// The buyer's region: a region code ("SE", "US-CA"), a trade agreement ("EU") or
+    /// a region group
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub destination: Option<String>,
+    /// The transaction type
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transaction_type: Option<TransactionType>,
+    /// Attributes the scenario must have, with these values (e.g. `category=books`)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub attributes: HashMap<String, String>,
+}
+
+/// What an override rule does.
+#[typeshare]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "content")]
+#[serde(rename_all = "snake_case")]
+pub enum OverrideAction {
+    /// Charge the rate of the category (e.g. `Reduced`) where tax is charged, instead
+    /// of the scenario's rate
+    Rate(VatRate),
+    /// Charge no tax, for the given reason
+    Exempt(String),
+}
+
+/// A prioritized business rule, applied before the standard calculation.
+///
+/// # Examples
+///
+/// ```
+/// # use world_tax::overrides::{OverrideAction, OverrideCondition, OverrideRule};
+/// # use world_tax::{Region, TaxDatabase, TaxScenario, TransactionType, VatRate};
+/// let mut db = TaxDatabase::new().unwrap();
+/// db.add_override_rule(OverrideRule {
+///     name: "books-se".to_string(),
+///     priority: 10,
+///     when: OverrideCondition {
+///         destination: Some("SE".to_string()),
+///         attributes: [("category".to_string(), "books".to_string())].into(),
+///         ..Default::default()
+///     },
+///     then: OverrideAction::Rate(VatRate::ReducedAlt),
+/// })
+/// .unwrap();
+///
+/// let sale = TaxScenario::new(
+///     Region::parse("SE").unwrap(),
+///     Region::parse("SE").unwrap(),
+///     TransactionType::B2C,
+/// );
+/// assert_eq!(sale.calculate_tax(100.0, &db).unwrap(), 25.0);
+/// let books = sale.with_attribute("category", "books");
+/// assert_eq!(books.calculate_tax(100.0, &db).unwrap(), 6.0);
+/// ```
+#[typeshare]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OverrideRule {
+    /// Name of the rule, recorded where it's applied
+    pub name: String,
+    /// Precedence over other matching rules; the highest applies
+    #[serde(default)]
+    pub priority: i32,
+    /// The sales the rule applies to
+    #[serde(default)]
+    pub when: OverrideCondition,
+    /// What the rule does
+    pub then: OverrideAction,
+}
+
+/// An override rule with its destination resolved to regions.
+#[derive(Debug, Clone)]
+pub(crate) struct CompiledRule {
+    rule: OverrideRule,
+    destination: Option<RegionGroup>,
+}
+
+impl CompiledRule {
+    fn matches(&self, scenario: &TaxScenario) -> bool {
+        let condition = &self.rule.when;
+        self.destination
+            .as_ref()
+            .is_none_or(|group| group.contains(&scenario.destination_region))
+            && condition
+                .transaction_type
+                .as_ref()
+                .is_none_or(|transaction_type| *transaction_type == scenario.transaction_type)
+            && condition
+                .attributes
+                .iter()
+                .all(|(key, value)| scenario.attributes.get(key) == Some(value))
+    }
+}
+
+impl TaxDatabase {
+    /// Adds an override rule. Its destination is resolved when the rule is added, like
+    /// the members of a region group.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::InvalidOverrideRule` if the name is empty or taken, or
+    /// the destination is neither a region code, an agreement nor a region group.
+    pub fn add_override_rule(&mut self, rule: OverrideRule) -> Result<(), DatabaseError> {
+        let invalid =
+            |reason: &str| DatabaseError::InvalidOverrideRule(format!("{}: {reason}", rule.name));
+        if rule.name.trim().is_empty() {
+            return Err(invalid("the name must not be empty"));
+        }
+        if self
+            .override_rules
+            .iter()
+            .any(|added| added.rule.name == rule.name)
+        {
+            return Err(invalid("a rule of the name was added before"));
+        }
+        let destination = match &rule.when.destination {
+            Some(destination) => Some(self.resolve_regions(destination).ok_or_else(|| {
+                invalid("the destination is neither a region, an agreement nor a group")
+            })?),
+            None => None,
+        };
+        self.override_rules.push(CompiledRule { rule, destination });
+        Ok(())
+    }
+
+    /// Loads override rules from JSON configuration: an array of rules, in the order
+    /// they are defined in.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use world_tax::{Region, TaxCalculationType, TaxDatabase, TaxScenario, TransactionType};
+    /// let mut db = TaxDatabase::fixture();
+    /// db.load_override_rules(r#"[{
+    ///     "name": "wholesale",
+    ///     "when": { "attributes": { "customer_group": "wholesale" } },
+    ///     "then": { "type": "exempt", "content": "Wholesale agreement 2026-14" }
+    /// }]"#).unwrap();
+    ///
+    /// let sale = TaxScenario::new(
+    ///     Region::parse("DE").unwrap(),
+    ///     Region::parse("DE").unwrap(),
+    ///     TransactionType::B2B,
+    /// )
+    /// .with_attribute("customer_group", "wholesale");
+    /// assert_eq!(sale.determine_calculation_type(&db, 100.0).unwrap(), TaxCalculationType::Exempt);
+    /// assert_eq!(sale.calculate_tax(100.0, &db).unwrap(), 0.0);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::InvalidOverrideRule` if the JSON isn't an array of rules,
+    /// or a rule is invalid (see `add_override_rule`); the rules before it are added.
+    pub fn load_override_rules(&mut self, json: &str) -> Result<(), DatabaseError> {
+        let rules: Vec<OverrideRule> = serde_json::from_str(json).map_err(|error| {
+            DatabaseError::InvalidOverrideRule(format!("configuration: {error}"))
+        })?;
+        rules
+            .into_iter()
+            .try_for_each(|rule| self.add_override_rule(rule))
+    }
+
+    /// The override rules, in the order they were added.
+    pub fn override_rules(&self) -> impl Iterator<Item = &OverrideRule> {
+        self.override_rules.iter().map(|compiled| &compiled.rule)
+    }
+
+    /// The override rules matching a scenario, by precedence: by priority, highest
+    /// first, then in the order they were added.
+    pub fn matching_overrides(&self, scenario: &TaxScenario) -> Vec<&OverrideRule> {
+        let mut rules: Vec<&OverrideRule> = self
+            .override_rules
+            .iter()
+            .filter(|compiled| compiled.matches(scenario))
+            .map(|compiled| &compiled.rule)
+            .collect();
+        rules.sort_by_key(|rule| std::cmp::Reverse(rule.priority));
+        rules
+    }
+}
+
+impl TaxScenario {
+    /// Sets an attribute of the sale, for override rules to match (e.g. `category` to
+    /// `books`).
+    pub fn with_attribute(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.attributes.insert(key.into(), value.into());
+        self
+    }
+
+    /// The override rule that applies to the scenario, if any.
+    pub fn override_rule<'a, P: RateProvider + ?Sized>(
+        &self,
+        db: &'a P,
+    ) -> Option<&'a OverrideRule> {
+        db.matching_overrides(self).into_iter().next()
+    }
+
+    /// The rules that conflict with the applied override rule: matching rules of the
+    /// same priority with another action.
+    pub fn override_conflicts<'a, P: RateProvider + ?Sized>(
+        &self,
+        db: &'a P,
+    ) -> Vec<&'a OverrideRule> {
+        let mut rules = db.matching_overrides(self).into_iter();
+        let Some(applied) = rules.next() else {
+            return Vec::new();
+        };
+        rules
+            .take_while(|rule| rule.priority == applied.priority)
+            .filter(|rule| rule.then != applied.then)
+            .collect()
+    }
+
+    /// Records the applied override rule and its conflicts, and returns its action.
+    pub(crate) fn trace_override<'a, P: RateProvider + ?Sized>(
+        &self,
+        db: &'a P,
+        trace: &mut Trace,
+    ) -> Option<&'a OverrideAction> {
+        let rule = self.override_rule(db)?;
+        trace.record("override", || {
+            let action = match &rule.then {
+                OverrideAction::Rate(vat_rate) => format!("{vat_rate:?} rate"),
+                OverrideAction::Exempt(reason) => format!("Exempt: {reason}"),
+            };
+            format!("{} (priority {}): {action}", rule.name, rule.priority)
+        });
+        let conflicts = self.override_conflicts(db);
+        if !conflicts.is_empty() {
+            trace.record("override_conflict", || {
+                let names: Vec<&str> = conflicts.iter().map(|rule| rule.name.as_str()).collect();
+                format!("{} applied over {}", rule.name, names.join(", "))
+            });
+        }
+        Some(&rule.then)
+    }
+}
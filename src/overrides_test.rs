@@ -0,0 +1,201 @@
+#[cfg(test)]
+mod tests {
+    use crate::calculator::{TaxCalculator, TaxRequest};
+    use crate::confidence::Confidence;
+    use crate::overrides::{OverrideAction, OverrideCondition, OverrideRule};
+    use crate::{
+        DatabaseError, RateSource, Region, TaxCalculationType, TaxDatabase, TaxScenario,
+        TransactionType, VatRate, Warning,
+    };
+
+    fn sale(source: &str, destination: &str, transaction_type: TransactionType) -> TaxScenario {
+        TaxScenario::new(
+            Region::parse(source).expect("Valid region"),
+            Region::parse(destination).expect("Valid region"),
+            transaction_type,
+        )
+    }
+
+    fn rule(
+        name: &str,
+        priority: i32,
+        when: OverrideCondition,
+        then: OverrideAction,
+    ) -> OverrideRule {
+        OverrideRule {
+            name: name.to_string(),
+            priority,
+            when,
+            then,
+        }
+    }
+
+    fn attribute(key: &str, value: &str) -> OverrideCondition {
+        OverrideCondition {
+            attributes: [(key.to_string(), value.to_string())].into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_rate_override() {
+        let mut db = TaxDatabase::new().unwrap();
+        db.add_override_rule(rule(
+            "books-se",
+            0,
+            OverrideCondition {
+                destination: Some("SE".to_string()),
+                ..attribute("category", "books")
+            },
+            OverrideAction::Rate(VatRate::ReducedAlt),
+        ))
+        .unwrap();
+
+        let books = sale("SE", "SE", TransactionType::B2C).with_attribute("category", "books");
+        assert_eq!(books.calculate_tax(100.0, &db).unwrap(), 6.0);
+
+        // The rule applies over the scenario's rate, and not elsewhere
+        let mut standard = books.clone();
+        standard.vat_rate = Some(VatRate::Standard);
+        assert_eq!(standard.calculate_tax(100.0, &db).unwrap(), 6.0);
+        let denmark = sale("DK", "DK", TransactionType::B2C).with_attribute("category", "books");
+        assert_eq!(denmark.calculate_tax(100.0, &db).unwrap(), 25.0);
+        let clothes = sale("SE", "SE", TransactionType::B2C).with_attribute("category", "clothes");
+        assert_eq!(clothes.calculate_tax(100.0, &db).unwrap(), 25.0);
+
+        let explanation = books.explain(100.0, &db).unwrap();
+        let step = explanation
+            .steps
+            .iter()
+            .find(|step| step.check == "override")
+            .unwrap();
+        assert_eq!(step.outcome, "books-se (priority 0): ReducedAlt rate");
+    }
+
+    #[test]
+    fn test_exempt_override() {
+        let mut db = TaxDatabase::new().unwrap();
+        db.add_override_rule(rule(
+            "wholesale",
+            0,
+            OverrideCondition {
+                transaction_type: Some(TransactionType::B2B),
+                ..attribute("customer_group", "wholesale")
+            },
+            OverrideAction::Exempt("Wholesale agreement 2026-14".to_string()),
+        ))
+        .unwrap();
+
+        let wholesale =
+            sale("DE", "DE", TransactionType::B2B).with_attribute("customer_group", "wholesale");
+        let quote = db.quote(TaxRequest::new(wholesale, 100.0)).unwrap();
+        assert_eq!(quote.calculation_type, TaxCalculationType::Exempt);
+        assert_eq!(quote.tax, 0.0);
+        assert_eq!(quote.rates.len(), 1);
+        assert_eq!(quote.rates[0].source, RateSource::Override);
+
+        // Sales tax systems charge nothing either, without a VAT rate
+        let us = sale("US-CA", "US-CA", TransactionType::B2B)
+            .with_attribute("customer_group", "wholesale");
+        let rates = us.get_rates(100.0, &db).unwrap();
+        assert!(rates.is_empty());
+        assert_eq!(us.calculate_tax(100.0, &db).unwrap(), 0.0);
+
+        // Consumers aren't covered by the rule
+        let consumer =
+            sale("DE", "DE", TransactionType::B2C).with_attribute("customer_group", "wholesale");
+        assert_eq!(consumer.calculate_tax(100.0, &db).unwrap(), 19.0);
+
+        let explanation = sale("DE", "DE", TransactionType::B2B)
+            .with_attribute("customer_group", "wholesale")
+            .explain(100.0, &db)
+            .unwrap();
+        assert_eq!(explanation.steps[0].check, "override");
+        assert_eq!(
+            explanation.steps[0].outcome,
+            "wholesale (priority 0): Exempt: Wholesale agreement 2026-14"
+        );
+    }
+
+    #[test]
+    fn test_priority_and_conflicts() {
+        let mut db = TaxDatabase::new().unwrap();
+        db.define_region_group("Nordics", &["DK", "FI", "IS", "NO", "SE"])
+            .unwrap();
+        db.load_override_rules(
+            r#"[
+                { "name": "books-nordics", "priority": 1,
+                  "when": { "destination": "Nordics", "attributes": { "category": "books" } },
+                  "then": { "type": "rate", "content": "reduced" } },
+                { "name": "books-eu", "priority": 1,
+                  "when": { "destination": "EU", "attributes": { "category": "books" } },
+                  "then": { "type": "rate", "content": "reduced_alt" } },
+                { "name": "charity", "priority": 5,
+                  "when": { "attributes": { "customer_group": "charity" } },
+                  "then": { "type": "exempt", "content": "Donation" } }
+            ]"#,
+        )
+        .unwrap();
+        assert_eq!(db.override_rules().count(), 3);
+
+        // Norway isn't in the EU: no conflict
+        let norway = sale("NO", "NO", TransactionType::B2C).with_attribute("category", "books");
+        let quote = db.quote(TaxRequest::new(norway, 100.0)).unwrap();
+        assert_eq!(quote.tax, 15.0);
+        assert!(quote.warnings.is_empty());
+
+        // Sweden is in both: the rule added first applies, with a warning
+        let sweden = sale("SE", "SE", TransactionType::B2C).with_attribute("category", "books");
+        assert_eq!(sweden.override_rule(&db).unwrap().name, "books-nordics");
+        let quote = db.quote(TaxRequest::new(sweden.clone(), 100.0)).unwrap();
+        assert_eq!(quote.tax, 12.0);
+        assert_eq!(
+            quote.warnings,
+            vec![Warning::OverrideConflict {
+                rule: "books-nordics".to_string(),
+                conflicting: vec!["books-eu".to_string()],
+            }]
+        );
+        assert_eq!(quote.confidence, Confidence::Exact);
+        let explanation = sweden.explain(100.0, &db).unwrap();
+        assert!(explanation
+            .steps
+            .iter()
+            .any(|step| step.check == "override_conflict"
+                && step.outcome == "books-nordics applied over books-eu"));
+
+        // A higher priority wins without a conflict
+        let charity = sweden.with_attribute("customer_group", "charity");
+        assert_eq!(charity.override_rule(&db).unwrap().name, "charity");
+        assert!(charity.override_conflicts(&db).is_empty());
+        assert_eq!(charity.calculate_tax(100.0, &db).unwrap(), 0.0);
+    }
+
+    #[test]
+    fn test_invalid_rules() {
+        let mut db = TaxDatabase::new().unwrap();
+        let exempt = OverrideAction::Exempt("Reason".to_string());
+        assert!(matches!(
+            db.add_override_rule(rule(" ", 0, Default::default(), exempt.clone())),
+            Err(DatabaseError::InvalidOverrideRule(_))
+        ));
+        let unknown = OverrideCondition {
+            destination: Some("Nowhere".to_string()),
+            ..Default::default()
+        };
+        assert!(matches!(
+            db.add_override_rule(rule("unknown", 0, unknown, exempt.clone())),
+            Err(DatabaseError::InvalidOverrideRule(message)) if message.starts_with("unknown: ")
+        ));
+        db.add_override_rule(rule("once", 0, Default::default(), exempt.clone()))
+            .unwrap();
+        assert!(db
+            .add_override_rule(rule("once", 0, Default::default(), exempt))
+            .is_err());
+        assert!(matches!(
+            db.load_override_rules(r#"{ "name": "not an array" }"#),
+            Err(DatabaseError::InvalidOverrideRule(message)) if message.starts_with("configuration: ")
+        ));
+        assert_eq!(db.override_rules().count(), 1);
+    }
+}
@@ -0,0 +1,157 @@
+//! Tax rates as percentages.
+//!
+//! `Percentage` stores a rate as a decimal fraction (0.19 for 19%), and is created
+//! with an explicit unit, `from_fraction` or `from_percent`, so 0.19 can't be
+//! mistaken for 19.0. It displays as a percentage ("19%"), and (de)serializes as
+//! the fraction, so the dataset and JSON results keep their format.
+//!
+//! Code that switches on rate values compares integer basis points (1900 for 19%)
+//! instead: `basis_points` and `from_basis_points`. Where rates are read from JSON,
+//! like override configuration, `{ "basis_points": 1900 }` is accepted for the fraction.
+
+use std::fmt;
+
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+/// Decimal places kept when converting a floating-point fraction.
+const PRECISION: u32 = 10;
+
+/// A tax rate, stored as a decimal fraction.
+///
+/// # Examples
+///
+/// ```
+/// # use world_tax::Percentage;
+/// # use rust_decimal_macros::dec;
+/// let rate = Percentage::from_percent(dec!(19));
+/// assert_eq!(rate, Percentage::from_fraction(dec!(0.19)));
+/// assert_eq!(rate.fraction(), dec!(0.19));
+/// assert_eq!(rate.to_string(), "19%");
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Percentage(Decimal);
+
+/// Rates from -100% to 200%, with up to 4 decimal places.
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Percentage {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        Ok(Self(Decimal::new(u.int_in_range(-10_000..=20_000)?, 4)))
+    }
+}
+
+impl Percentage {
+    /// A rate of 0%.
+    pub const ZERO: Percentage = Percentage(Decimal::ZERO);
+
+    /// Creates a rate from a decimal fraction (0.19 for 19%).
+    pub fn from_fraction(fraction: Decimal) -> Self {
+        Self(fraction.normalize())
+    }
+
+    /// Creates a rate from a number of percent (19 for 19%).
+    pub fn from_percent(percent: Decimal) -> Self {
+        Self::from_fraction(percent / Decimal::ONE_HUNDRED)
+    }
+
+    /// Creates a rate from basis points (1900 for 19%).
+    pub fn from_basis_points(basis_points: i64) -> Self {
+        Self::from_fraction(Decimal::new(basis_points, 4))
+    }
+
+    /// Creates a rate from a floating-point fraction, rounded to 10 decimal places so
+    /// floating-point noise (0.14975000000000002) doesn't carry over.
+    pub(crate) fn from_f64(fraction: f64) -> Self {
+        let fraction = Decimal::from_f64(fraction).unwrap_or_default();
+        Self::from_fraction(fraction.round_dp(PRECISION))
+    }
+
+    /// Creates a rate from a mantissa and scale (19 and 2 for 19%), for static tables.
+    #[cfg(feature = "static-table")]
+    pub(crate) const fn from_parts(mantissa: u32, scale: u32) -> Self {
+        Self(Decimal::from_parts(mantissa, 0, 0, false, scale))
+    }
+
+    /// The rate as a decimal fraction (0.19 for 19%).
+    pub fn fraction(&self) -> Decimal {
+        self.0
+    }
+
+    /// The rate as a number of percent (19 for 19%).
+    pub fn percent(&self) -> Decimal {
+        (self.0 * Decimal::ONE_HUNDRED).normalize()
+    }
+
+    /// The rate in basis points (1900 for 19%); none if it isn't a whole number of them
+    /// (Quebec's 9.975%).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use world_tax::{Percentage, TaxDatabase, VatRate};
+    /// let db = TaxDatabase::fixture();
+    /// let france = db.get_country("FR").unwrap();
+    /// match france.rate(&VatRate::ReducedAlt).and_then(|rate| rate.basis_points()) {
+    ///     Some(550) => {}
+    ///     other => panic!("unexpected rate {other:?}"),
+    /// }
+    /// assert_eq!(Percentage::from_basis_points(997).basis_points(), Some(997));
+    /// assert_eq!(Percentage::from_basis_points(9975).to_string(), "99.75%");
+    /// ```
+    pub fn basis_points(&self) -> Option<i64> {
+        let basis_points = self.0 * Decimal::from(10_000);
+        if basis_points.fract().is_zero() {
+            basis_points.to_i64()
+        } else {
+            None
+        }
+    }
+
+    /// The rate as a floating-point fraction, for calculations on `f64` amounts.
+    pub fn to_f64(&self) -> f64 {
+        self.0.to_f64().unwrap_or_default()
+    }
+
+    /// Returns true if the rate is 0%.
+    pub fn is_zero(&self) -> bool {
+        self.0.is_zero()
+    }
+}
+
+impl fmt::Display for Percentage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}%", self.percent())
+    }
+}
+
+impl Serialize for Percentage {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_f64(self.to_f64())
+    }
+}
+
+/// A rate in JSON: the fraction, or basis points.
+#[derive(Deserialize)]
+#[serde(untagged, expecting = "a fraction, or an object with basis_points")]
+enum Serialized {
+    Fraction(f64),
+    BasisPoints(BasisPoints),
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct BasisPoints {
+    basis_points: i64,
+}
+
+impl<'de> Deserialize<'de> for Percentage {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(match Serialized::deserialize(deserializer)? {
+            Serialized::Fraction(fraction) => Self::from_f64(fraction),
+            Serialized::BasisPoints(BasisPoints { basis_points }) => {
+                Self::from_basis_points(basis_points)
+            }
+        })
+    }
+}
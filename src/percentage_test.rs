@@ -0,0 +1,82 @@
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+
+    use crate::missing_country::MissingCountryPolicy;
+    use crate::{quick_rate, Percentage, TaxDatabase, TaxRate, VatRate};
+
+    #[test]
+    fn test_percent_and_fraction() {
+        let qst = Percentage::from_percent(dec!(9.975));
+        assert_eq!(qst.fraction(), dec!(0.09975));
+        assert_eq!(qst.percent(), dec!(9.975));
+        assert_eq!(qst.to_string(), "9.975%");
+        assert_eq!(Percentage::from_fraction(dec!(0.190)).to_string(), "19%");
+        assert!(Percentage::ZERO.is_zero());
+    }
+
+    #[test]
+    fn test_serializes_as_fraction() {
+        let db = TaxDatabase::fixture();
+        let rates = db.get_rate("FR", None, None).expect("Rate in fixture");
+        let json = serde_json::to_value(rates[0]).expect("Rate should serialize");
+        assert_eq!(json["rate"], 0.2);
+
+        let rate: TaxRate = serde_json::from_value(json).expect("Rate should deserialize");
+        assert_eq!(rate.rate, Percentage::from_percent(dec!(20)));
+    }
+
+    #[test]
+    fn test_combined_rate_without_float_noise() {
+        let db = TaxDatabase::fixture();
+        let rate = quick_rate(&db, "CA", Some("CA-QC"), None).expect("Rate in fixture");
+        // GST 5% + QST 9.975% compounded, with the floating-point noise rounded off
+        assert_eq!(rate, Percentage::from_fraction(dec!(0.1547375)));
+    }
+
+    #[test]
+    fn test_basis_points() {
+        let rate = Percentage::from_basis_points(1900);
+        assert_eq!(rate, Percentage::from_percent(dec!(19)));
+        assert_eq!(rate.basis_points(), Some(1900));
+        assert_eq!(
+            Percentage::from_percent(dec!(5.5)).basis_points(),
+            Some(550)
+        );
+        assert_eq!(Percentage::from_percent(dec!(9.975)).basis_points(), None);
+        assert_eq!(Percentage::ZERO.basis_points(), Some(0));
+
+        // Read from JSON in either form, written as the fraction
+        let rate: Percentage = serde_json::from_str(r#"{ "basis_points": 1100 }"#).unwrap();
+        assert_eq!(rate, Percentage::from_percent(dec!(11)));
+        assert_eq!(serde_json::to_string(&rate).unwrap(), "0.11");
+        assert!(serde_json::from_str::<Percentage>(r#"{ "bps": 1100 }"#).is_err());
+        assert!(serde_json::from_str::<Percentage>(r#""11%""#).is_err());
+    }
+
+    #[test]
+    fn test_basis_points_in_overrides() {
+        let mut db = TaxDatabase::fixture();
+        db.merge_json(
+            r#"{ "FR": { "reduced_rate": { "basis_points": 1100 } } }"#,
+            "{}",
+        )
+        .unwrap();
+        let france = db.get_country("FR").unwrap();
+        assert_eq!(
+            france
+                .rate(&VatRate::Reduced)
+                .and_then(|rate| rate.basis_points()),
+            Some(1100)
+        );
+
+        let policy: MissingCountryPolicy = serde_json::from_str(
+            r#"{ "type": "default_rate", "content": { "basis_points": 1500 } }"#,
+        )
+        .unwrap();
+        assert_eq!(
+            policy,
+            MissingCountryPolicy::DefaultRate(Percentage::from_basis_points(1500))
+        );
+    }
+}
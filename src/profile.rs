@@ -0,0 +1,164 @@
+//! Named calculation profiles.
+//!
+//! A profile bundles the options that usually stay the same across an entire
+//! platform (rounding, strictness, fallback behaviour and scheme registrations).
+//! Profiles are registered on the `TaxDatabase` by name and referenced from a
+//! `TaxScenario`, so the policy is defined once instead of at every call site.
+
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use typeshare::typeshare;
+
+/// Strategy used when rounding a calculated tax amount.
+#[typeshare]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RoundingStrategy {
+    /// Round half away from zero (2.345 -> 2.35, -2.345 -> -2.35)
+    #[default]
+    HalfAwayFromZero,
+    /// Round half to even, also known as banker's rounding (2.345 -> 2.34)
+    HalfEven,
+    /// Always round away from zero (2.341 -> 2.35)
+    Up,
+    /// Always round towards zero (2.349 -> 2.34)
+    Down,
+}
+
+impl From<RoundingStrategy> for rust_decimal::RoundingStrategy {
+    fn from(strategy: RoundingStrategy) -> Self {
+        match strategy {
+            RoundingStrategy::HalfAwayFromZero => {
+                rust_decimal::RoundingStrategy::MidpointAwayFromZero
+            }
+            RoundingStrategy::HalfEven => rust_decimal::RoundingStrategy::MidpointNearestEven,
+            RoundingStrategy::Up => rust_decimal::RoundingStrategy::AwayFromZero,
+            RoundingStrategy::Down => rust_decimal::RoundingStrategy::ToZero,
+        }
+    }
+}
+
+/// Rounding applied to the final tax amount.
+#[typeshare]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Rounding {
+    /// Number of decimal places to keep
+    pub decimal_places: u32,
+    /// How to round the last decimal place
+    pub strategy: RoundingStrategy,
+}
+
+impl Default for Rounding {
+    fn default() -> Self {
+        Self {
+            decimal_places: 2,
+            strategy: RoundingStrategy::HalfAwayFromZero,
+        }
+    }
+}
+
+impl Rounding {
+    /// Rounds a decimal amount according to this configuration.
+    pub fn apply(&self, amount: Decimal) -> Decimal {
+        amount.round_dp_with_strategy(self.decimal_places, self.strategy.into())
+    }
+}
+
+/// How strictly the input is matched against the database.
+#[typeshare]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Strictness {
+    /// Ignore regions that are not found in the database
+    #[default]
+    Lenient,
+    /// Fail with `DatabaseError::RegionNotFound` when a region is not found
+    Strict,
+}
+
+/// Calculation type to use when a trade agreement has no rule for the transaction type.
+#[typeshare]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FallbackPolicy {
+    /// Use the destination tax rate
+    #[default]
+    Destination,
+    /// Use the origin tax rate
+    Origin,
+    /// Fail with `DatabaseError::TaxRuleNotFound`
+    Error,
+}
+
+/// What to do with a US sale into a state where the seller has no nexus.
+#[typeshare]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NoNexusPolicy {
+    /// Collect no tax
+    #[default]
+    CollectNothing,
+    /// Collect no tax, but warn with `Warning::UseTaxNotice`, so the buyer can be told
+    /// that use tax is owed in their state
+    UseTaxNotice,
+}
+
+/// When B2B sales within the EU are reverse charged.
+#[typeshare]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReverseChargePolicy {
+    /// Only with a valid VAT number of the buyer (`TaxScenario::buyer_vat_id`) from
+    /// another member state; B2B sales without one are taxed like B2C sales
+    #[default]
+    RequireVatId,
+    /// Trust `TransactionType::B2B`; a buyer's VAT number that is given must still be
+    /// valid and from another member state
+    TransactionType,
+}
+
+/// Tax schemes the seller is registered for.
+#[typeshare]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SchemeFlags {
+    /// Registered for the EU One-Stop-Shop and opted into destination taxation,
+    /// even below the distance selling threshold
+    pub oss: bool,
+    /// Registered for the EU Import One-Stop-Shop; B2C imports of up to €150 are
+    /// charged destination VAT at checkout
+    pub ioss: bool,
+}
+
+/// A reusable set of calculation options.
+///
+/// # Examples
+///
+/// ```
+/// # use world_tax::provider::TaxDatabase;
+/// # use world_tax::profile::{CalculationProfile, Strictness};
+/// let mut db = TaxDatabase::new().unwrap();
+/// db.register_profile(
+///     "strict",
+///     CalculationProfile {
+///         strictness: Strictness::Strict,
+///         ..Default::default()
+///     },
+/// );
+/// assert!(db.get_profile("strict").is_ok());
+/// ```
+#[typeshare]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CalculationProfile {
+    /// Rounding applied to the final tax amount
+    pub rounding: Rounding,
+    /// How strictly regions are matched against the database
+    pub strictness: Strictness,
+    /// What to do when an agreement has no rule for the transaction type
+    pub fallback: FallbackPolicy,
+    /// Tax schemes the seller is registered for
+    pub schemes: SchemeFlags,
+    /// What to do with US sales into states where the seller has no nexus
+    pub no_nexus: NoNexusPolicy,
+    /// When B2B sales within the EU are reverse charged
+    pub reverse_charge: ReverseChargePolicy,
+}
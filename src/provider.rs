@@ -6,12 +6,29 @@
 //! from JSON sources.
 
 use log::debug;
+use serde::de::Error as _;
 use std::collections::HashMap;
+use std::sync::Arc;
 
-use super::types::{Country, TaxSystemType, TaxType, VatRate};
+use super::types::{
+    normalize_state_code, Country, RateSource, State, SupplyKind, TaxSystemType, TaxType, VatRate,
+};
 use crate::{
-    errors::DatabaseError,
+    currency::ExchangeRates,
+    customs::{DisbursementFee, DutyTable},
+    errors::{suggestions, DatabaseError, DatasetError},
+    local_store::LocalRateCache,
+    migration::{self, pointer_token, Migrated, MigrationChange, MigrationNote},
+    missing_country::MissingCountryPolicy,
+    overrides::CompiledRule,
+    percentage::Percentage,
+    profile::CalculationProfile,
+    rate_provider,
+    region_group::RegionGroup,
+    schema,
+    sst::BoundaryTable,
     types::{TaxRate, TradeAgreement},
+    view::CountryView,
 };
 
 /// Database containing tax rates and trade agreements for different jurisdictions.
@@ -20,9 +37,35 @@ use crate::{
 /// and international trade agreements.
 pub struct TaxDatabase {
     /// Map of country codes to their tax information
-    countries: HashMap<String, Country>,
+    pub(crate) countries: HashMap<String, Country>,
     /// Map of trade agreement identifiers to their details
     pub trade_agreements: HashMap<String, TradeAgreement>,
+    /// Map of profile names to their calculation options
+    profiles: HashMap<String, CalculationProfile>,
+    /// Changes made while upgrading the loaded data to the current schema version,
+    /// and normalized state keys
+    pub(crate) migration_notes: Vec<MigrationNote>,
+    /// State keys as written in the data, by country and normalized key, where they
+    /// were normalized
+    pub(crate) state_keys: HashMap<(String, String), String>,
+    /// Local rate tables, by state code
+    pub(crate) local_rates: HashMap<String, Arc<BoundaryTable>>,
+    /// Store local rates are loaded from lazily, within a memory budget
+    pub(crate) local_rate_store: Option<LocalRateCache>,
+    /// Duty tables, by customs territory
+    pub(crate) duty_rates: HashMap<String, DutyTable>,
+    /// Carrier disbursement fees, by customs territory
+    pub(crate) disbursement_fees: HashMap<String, DisbursementFee>,
+    /// Exchange rates amounts are compared against thresholds with
+    pub(crate) exchange_rates: Option<Box<dyn ExchangeRates>>,
+    /// What to do with countries that aren't in the dataset
+    pub(crate) missing_country_policy: MissingCountryPolicy,
+    /// The data countries that aren't in the dataset are taxed with, by the policy
+    pub(crate) missing_country: Option<Country>,
+    /// Custom region groups, by name
+    pub(crate) region_groups: HashMap<String, RegionGroup>,
+    /// Override rules, in the order they were added
+    pub(crate) override_rules: Vec<CompiledRule>,
 }
 
 impl TaxDatabase {
@@ -46,8 +89,44 @@ impl TaxDatabase {
         Self::from_json(countries, trade_agreements)
     }
 
+    /// Creates a small, stable TaxDatabase intended for unit tests.
+    ///
+    /// Unlike `TaxDatabase::new`, the fixture data never changes when rates are
+    /// updated, so tests built on it keep passing across releases.
+    ///
+    /// Countries:
+    ///
+    /// - `DE`: VAT 19%, reduced 7%
+    /// - `FR`: VAT 20%, reduced 10%, reduced (alt) 5.5%, super-reduced 2.1%
+    /// - `CA`: GST 5%, with `CA-BC` (PST 7%), `CA-ON` (HST 8%) and `CA-QC` (QST 9.975%)
+    ///
+    /// Trade agreements:
+    ///
+    /// - `EU`: Customs union of `DE` and `FR`; B2B reverse charge, B2C origin below
+    ///   10,000 and destination above, digital products always destination
+    /// - `CA`: Federal state; B2B destination, B2C exempt below 30,000 and destination above
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use world_tax::provider::TaxDatabase;
+    ///
+    /// let db = TaxDatabase::fixture();
+    /// assert_eq!(db.get_country("DE").unwrap().standard_rate().to_f64(), 0.19);
+    /// ```
+    pub fn fixture() -> Self {
+        let countries = include_str!("../fixtures/vat_rates.json");
+        let trade_agreements = include_str!("../fixtures/trade_agreements.json");
+
+        Self::from_json(countries, trade_agreements).expect("Fixture data is valid")
+    }
+
     /// Creates a new TaxDatabase instance from JSON strings.
     ///
+    /// Data stored in an older schema version is migrated to the current one, and
+    /// state keys are normalized to their ISO 3166-2 form ("BC" to "CA-BC"); see
+    /// `migration_notes` for the changes made.
+    ///
     /// # Arguments
     ///
     /// * `countries_json` - JSON string containing country tax rates
@@ -55,18 +134,65 @@ impl TaxDatabase {
     ///
     /// # Errors
     ///
-    /// Returns an error if either JSON string cannot be parsed.
+    /// Returns an error if either JSON string cannot be parsed or migrated.
     pub fn from_json(
         countries_json: &str,
         trade_agreements_json: &str,
     ) -> Result<Self, serde_json::Error> {
-        let countries: HashMap<String, Country> = serde_json::from_str(countries_json)?;
-        let trade_agreements: HashMap<String, TradeAgreement> =
-            serde_json::from_str(trade_agreements_json)?;
-        Ok(Self {
-            countries,
-            trade_agreements,
-        })
+        let countries = migration::migrate_vat_rates(serde_json::from_str(countries_json)?)
+            .map_err(serde_json::Error::custom)?;
+        let trade_agreements =
+            migration::migrate_trade_agreements(serde_json::from_str(trade_agreements_json)?)
+                .map_err(serde_json::Error::custom)?;
+        Self::from_migrated(countries, trade_agreements)
+    }
+
+    /// Creates a new TaxDatabase instance from JSON strings, validating both against
+    /// the published schemas first.
+    ///
+    /// Unlike `from_json`, unknown fields are rejected, and every violation is
+    /// reported with a JSON Pointer to the offending value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use world_tax::{DatasetError, TaxDatabase};
+    /// let countries = r#"{ "DE": { "type": "vat", "currency": "EUR", "standard_rat": 0.19 } }"#;
+    ///
+    /// match TaxDatabase::from_json_strict(countries, "{}") {
+    ///     Err(DatasetError::SchemaViolations(violations)) => {
+    ///         assert_eq!(violations[0].pointer, "/DE");
+    ///         assert_eq!(violations[1].pointer, "/DE/standard_rat");
+    ///     }
+    ///     _ => unreachable!(),
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatasetError::InvalidJson` if either string isn't valid JSON,
+    /// `DatasetError::UnsupportedSchemaVersion` if either can't be migrated, and
+    /// `DatasetError::SchemaViolations` if either doesn't match its schema.
+    pub fn from_json_strict(
+        countries_json: &str,
+        trade_agreements_json: &str,
+    ) -> Result<Self, DatasetError> {
+        let parse = |document: &str, json: &str| {
+            serde_json::from_str::<serde_json::Value>(json)
+                .map_err(|err| DatasetError::InvalidJson(document.to_string(), err.to_string()))
+        };
+        let countries = migration::migrate_vat_rates(parse("vat_rates", countries_json)?)?;
+        let trade_agreements =
+            migration::migrate_trade_agreements(parse("trade_agreements", trade_agreements_json)?)?;
+
+        let mut violations = schema::validate_vat_rates(&countries.value);
+        violations.extend(schema::validate_trade_agreements(&trade_agreements.value));
+        if !violations.is_empty() {
+            return Err(DatasetError::SchemaViolations(violations));
+        }
+
+        Self::from_migrated(countries, trade_agreements)
+            .map_err(|err| DatasetError::InvalidJson("dataset".to_string(), err.to_string()))
     }
 
     /// Creates a new TaxDatabase instance from JSON files.
@@ -78,7 +204,7 @@ impl TaxDatabase {
     ///
     /// # Errors
     ///
-    /// Returns an error if either file cannot be read or parsed.
+    /// Returns an error if either file cannot be read, parsed or migrated.
     pub fn from_files(
         rates_path: &str,
         agreements_path: &str,
@@ -86,15 +212,71 @@ impl TaxDatabase {
         let rates_data = std::fs::read_to_string(rates_path)?;
         let agreements_data = std::fs::read_to_string(agreements_path)?;
 
-        let countries = serde_json::from_str(&rates_data)?;
-        let trade_agreements = serde_json::from_str(&agreements_data)?;
+        Ok(Self::from_json(&rates_data, &agreements_data)?)
+    }
+
+    fn from_migrated(
+        countries: Migrated,
+        trade_agreements: Migrated,
+    ) -> Result<Self, serde_json::Error> {
+        let mut migration_notes = countries.notes;
+        migration_notes.extend(trade_agreements.notes);
+
+        let mut countries: HashMap<String, Country> = serde_json::from_value(countries.value)?;
+        let mut state_keys = HashMap::new();
+        for (code, country) in &mut countries {
+            let Some(states) = country.states.take() else {
+                continue;
+            };
+            let mut normalized = HashMap::with_capacity(states.len());
+            for (key, state) in states {
+                let canonical = normalize_state_code(code, &key);
+                if canonical != key {
+                    migration_notes.push(MigrationNote {
+                        change: MigrationChange::StateKey,
+                        document: "vat_rates".to_string(),
+                        pointer: format!("/{}/states/{}", code, pointer_token(&key)),
+                        message: format!("state key {} normalized to {}", key, canonical),
+                    });
+                    state_keys.insert((code.clone(), canonical.clone()), key);
+                }
+                if normalized.insert(canonical.clone(), state).is_some() {
+                    return Err(serde_json::Error::custom(format!(
+                        "duplicate state {} in {}",
+                        canonical, code
+                    )));
+                }
+            }
+            country.states = Some(normalized);
+        }
 
         Ok(Self {
             countries,
-            trade_agreements,
+            trade_agreements: serde_json::from_value(trade_agreements.value)?,
+            profiles: HashMap::new(),
+            migration_notes,
+            state_keys,
+            local_rates: HashMap::new(),
+            local_rate_store: None,
+            duty_rates: HashMap::new(),
+            disbursement_fees: HashMap::new(),
+            exchange_rates: None,
+            missing_country_policy: MissingCountryPolicy::Error,
+            missing_country: None,
+            region_groups: HashMap::new(),
+            override_rules: Vec::new(),
         })
     }
 
+    /// Changes made while upgrading the loaded data to the current schema version,
+    /// and state keys that were normalized.
+    ///
+    /// Empty if the data was already stored in the current version, with ISO 3166-2
+    /// state keys.
+    pub fn migration_notes(&self) -> &[MigrationNote] {
+        &self.migration_notes
+    }
+
     /// Retrieves the federal-level trade agreement for a country.
     ///
     /// # Arguments
@@ -126,11 +308,12 @@ impl TaxDatabase {
     ///
     /// # Returns
     ///
-    /// Returns the trade agreement if one exists between the two countries.
+    /// Returns the trade agreement if one exists between the two countries, and both
+    /// implemented its tax rules.
     pub fn get_international_rule(&self, source: &str, dest: &str) -> Option<TradeAgreement> {
         for agreement in self.trade_agreements.values() {
-            if agreement.members.contains(&source.to_string())
-                && agreement.members.contains(&dest.to_string())
+            if agreement.is_implemented_by(source)
+                && agreement.is_implemented_by(dest)
                 && agreement.is_international()
             {
                 return Some(agreement.clone());
@@ -139,6 +322,51 @@ impl TaxDatabase {
         None
     }
 
+    /// Finds an international trade agreement one country is a member of, and the other
+    /// is listed as a non-member of; returns the agreement's identifier and the non-member.
+    pub(crate) fn non_member_agreement<'a>(
+        &self,
+        source: &'a str,
+        dest: &'a str,
+    ) -> Option<(&str, &'a str)> {
+        self.trade_agreements.iter().find_map(|(id, agreement)| {
+            let is_member = |country: &str| agreement.members.iter().any(|m| m == country);
+            let is_non_member = |country: &str| agreement.non_members.iter().any(|m| m == country);
+            if !agreement.is_international() {
+                None
+            } else if is_member(source) && is_non_member(dest) {
+                Some((id.as_str(), dest))
+            } else if is_non_member(source) && is_member(dest) {
+                Some((id.as_str(), source))
+            } else {
+                None
+            }
+        })
+    }
+
+    /// Finds an international trade agreement both countries are members of, but one
+    /// of them hasn't implemented the tax rules of; returns the agreement and that member.
+    pub(crate) fn non_implemented_rule(
+        &self,
+        source: &str,
+        dest: &str,
+    ) -> Option<(&TradeAgreement, String)> {
+        self.trade_agreements.values().find_map(|agreement| {
+            let members = [source, dest];
+            if !agreement.is_international()
+                || !members
+                    .iter()
+                    .all(|country| agreement.members.iter().any(|member| member == country))
+            {
+                return None;
+            }
+            members
+                .into_iter()
+                .find(|country| !agreement.is_implemented_by(country))
+                .map(|country| (agreement, country.to_string()))
+        })
+    }
+
     /// Retrieves tax information for a specific country.
     ///
     /// # Arguments
@@ -148,15 +376,42 @@ impl TaxDatabase {
     /// # Errors
     ///
     /// Returns `DatabaseError::CountryNotFound` if the country code is not found.
-    pub fn get_country(&self, code: &str) -> Result<&Country, DatabaseError> {
-        let country = self.countries.get(code);
-        if let Some(country) = country {
-            Ok(country)
-        } else {
-            Err(DatabaseError::CountryNotFound(code.to_string()))
+    pub fn get_country(&self, code: &str) -> Result<CountryView<'_>, DatabaseError> {
+        self.countries
+            .get_key_value(code)
+            .map(|(code, country)| CountryView::new(code, country))
+            .ok_or_else(|| self.country_not_found(code))
+    }
+
+    /// The error of a country that isn't in the dataset, with the closest codes that are.
+    pub(crate) fn country_not_found(&self, code: &str) -> DatabaseError {
+        DatabaseError::CountryNotFound {
+            code: code.to_string(),
+            suggestions: suggestions(code, self.countries.keys().map(String::as_str)),
         }
     }
 
+    /// Retrieves the stored data of a country, or the data of the missing country
+    /// policy.
+    pub(crate) fn country(&self, code: &str) -> Result<&Country, DatabaseError> {
+        self.countries
+            .get(code)
+            .or_else(|| self.missing_country(code))
+            .ok_or_else(|| self.country_not_found(code))
+    }
+
+    /// Retrieves the stored data of a state; codes not found as given are normalized
+    /// (see `normalize_state_code`), so "bc" finds "CA-BC".
+    pub(crate) fn state(&self, country: &str, code: &str) -> Option<&State> {
+        rate_provider::state(self.countries.get(country)?, country, code)
+    }
+
+    /// Returns true if the state has a notice-and-report regime for sellers without nexus.
+    pub fn has_notice_and_report(&self, country: &str, state: &str) -> bool {
+        self.state(country, state)
+            .is_some_and(|state| state.notice_and_report)
+    }
+
     /// Retrieves a specific trade agreement by ID.
     ///
     /// # Arguments
@@ -175,6 +430,27 @@ impl TaxDatabase {
         }
     }
 
+    /// Registers a named calculation profile, replacing any existing profile with the same name.
+    ///
+    /// # Arguments
+    ///
+    /// * `name` - The name scenarios use to reference the profile
+    /// * `profile` - The calculation options
+    pub fn register_profile(&mut self, name: impl Into<String>, profile: CalculationProfile) {
+        self.profiles.insert(name.into(), profile);
+    }
+
+    /// Retrieves a registered calculation profile by name.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::ProfileNotFound` if no profile was registered under that name.
+    pub fn get_profile(&self, name: &str) -> Result<&CalculationProfile, DatabaseError> {
+        self.profiles
+            .get(name)
+            .ok_or_else(|| DatabaseError::ProfileNotFound(name.to_string()))
+    }
+
     /// Retrieves applicable tax rates for a jurisdiction.
     ///
     /// # Arguments
@@ -213,105 +489,159 @@ impl TaxDatabase {
         region: Option<&str>,
         vat_rate: Option<&VatRate>,
     ) -> Result<Vec<TaxRate>, DatabaseError> {
-        let country_data = self.get_country(country)?;
         let mut rates = Vec::new();
+        self.push_rates(country, region, vat_rate, &mut rates)?;
+        Ok(rates)
+    }
+
+    /// Appends the tax rates of a jurisdiction to `rates`; see `get_rate`.
+    pub(crate) fn push_rates(
+        &self,
+        country: &str,
+        region: Option<&str>,
+        vat_rate: Option<&VatRate>,
+        rates: &mut Vec<TaxRate>,
+    ) -> Result<(), DatabaseError> {
+        let country_data = self.country(country)?;
+        self.push_country_rates(country, country_data, region, vat_rate, rates)
+    }
+
+    /// Appends the tax rates of a jurisdiction of the given country data to `rates`.
+    pub(crate) fn push_country_rates(
+        &self,
+        country: &str,
+        country_data: &Country,
+        region: Option<&str>,
+        vat_rate: Option<&VatRate>,
+        rates: &mut Vec<TaxRate>,
+    ) -> Result<(), DatabaseError> {
+        let start = rates.len();
 
         // Special case for US which doesn't have a specific tax system type
         if country == "US" {
-            if let Some(region_code) = region {
-                if let Some(states) = &country_data.states {
-                    if let Some(state) = states.get(region_code) {
-                        // Only add the rate if it's non-zero
-                        if state.standard_rate > 0.0 {
-                            rates.push(TaxRate {
-                                rate: state.standard_rate,
-                                tax_type: TaxType::StateSalesTax,
-                                compound: false,
-                            });
-                        }
-                    }
+            if let Some(state) =
+                region.and_then(|code| rate_provider::state(country_data, country, code))
+            {
+                // Only add the rate if it's non-zero
+                if !state.standard_rate.is_zero() {
+                    rates.push(
+                        TaxRate::new(
+                            state.standard_rate,
+                            TaxType::StateSalesTax,
+                            RateSource::StateSpecific,
+                        )
+                        .with_cap(state.cap),
+                    );
                 }
             }
-            return Ok(rates);
+            return Ok(());
         }
 
         match country_data.tax_type {
             TaxSystemType::Gst => {
                 if let Some(region_code) = region {
-                    if let Some(states) = &country_data.states {
-                        if let Some(state) = states.get(region_code) {
-                            match state.tax_type {
-                                TaxSystemType::Hst => {
-                                    rates.clear(); // Ensure no other rates exist
-                                    rates.push(TaxRate {
-                                        rate: state.standard_rate,
-                                        tax_type: TaxType::HST,
-                                        compound: false,
-                                    });
-                                }
-                                TaxSystemType::Qst => {
-                                    rates.push(TaxRate {
-                                        rate: country_data.standard_rate,
-                                        tax_type: TaxType::GST,
-                                        compound: false,
-                                    });
-                                    rates.push(TaxRate {
-                                        rate: state.standard_rate,
-                                        tax_type: TaxType::QST,
-                                        compound: true,
-                                    });
-                                }
-                                TaxSystemType::Pst => {
-                                    rates.push(TaxRate {
-                                        rate: country_data.standard_rate,
-                                        tax_type: TaxType::GST,
-                                        compound: false,
-                                    });
-                                    rates.push(TaxRate {
-                                        rate: state.standard_rate,
-                                        tax_type: TaxType::PST,
-                                        compound: true,
-                                    });
-                                }
-                                _ => {
-                                    debug!("Adding default GST rate");
-                                    rates.push(TaxRate {
-                                        rate: country_data.standard_rate,
-                                        tax_type: TaxType::GST,
-                                        compound: false,
-                                    });
-                                }
+                    if let Some(state) = rate_provider::state(country_data, country, region_code) {
+                        match state.tax_type {
+                            TaxSystemType::Hst => {
+                                // HST replaces GST, single rate
+                                rates.push(
+                                    TaxRate::new(
+                                        state.standard_rate,
+                                        TaxType::HST,
+                                        RateSource::StateSpecific,
+                                    )
+                                    .with_cap(state.cap),
+                                );
+                            }
+                            TaxSystemType::Qst => {
+                                rates.push(TaxRate::new(
+                                    country_data.standard_rate,
+                                    TaxType::GST,
+                                    RateSource::CountryDefault,
+                                ));
+                                rates.push(
+                                    TaxRate::new(
+                                        state.standard_rate,
+                                        TaxType::QST,
+                                        RateSource::StateSpecific,
+                                    )
+                                    .compounding()
+                                    .with_cap(state.cap),
+                                );
+                            }
+                            TaxSystemType::Pst => {
+                                rates.push(TaxRate::new(
+                                    country_data.standard_rate,
+                                    TaxType::GST,
+                                    RateSource::CountryDefault,
+                                ));
+                                rates.push(
+                                    TaxRate::new(
+                                        state.standard_rate,
+                                        TaxType::PST,
+                                        RateSource::StateSpecific,
+                                    )
+                                    .compounding()
+                                    .with_cap(state.cap),
+                                );
+                            }
+                            _ => {
+                                debug!("Adding default GST rate");
+                                rates.push(TaxRate::new(
+                                    country_data.standard_rate,
+                                    TaxType::GST,
+                                    RateSource::CountryDefault,
+                                ));
                             }
                         }
+                    } else {
+                        debug!("Region {} not found, defaulting to GST", region_code);
+                        rates.push(TaxRate::new(
+                            country_data.standard_rate,
+                            TaxType::GST,
+                            RateSource::Fallback,
+                        ));
                     }
                 } else {
-                    rates.push(TaxRate {
-                        rate: country_data.standard_rate,
-                        tax_type: TaxType::GST,
-                        compound: false,
-                    });
+                    rates.push(TaxRate::new(
+                        country_data.standard_rate,
+                        TaxType::GST,
+                        RateSource::CountryDefault,
+                    ));
                 }
             }
-            TaxSystemType::Vat => self.handle_vat_rates(country_data, vat_rate, &mut rates)?,
+            TaxSystemType::Vat => self.handle_vat_rates(country_data, vat_rate, rates)?,
+            TaxSystemType::Icms => {
+                // The state's ICMS on goods, with the country's taxes on goods
+                let (rate, source) =
+                    match region.map(|code| rate_provider::state(country_data, country, code)) {
+                        Some(Some(state)) => (state.standard_rate, RateSource::StateSpecific),
+                        Some(None) => (country_data.standard_rate, RateSource::Fallback),
+                        None => (country_data.standard_rate, RateSource::CountryDefault),
+                    };
+                rates.push(TaxRate::new(rate, TaxType::ICMS, source));
+                rates.extend(
+                    country_data
+                        .additional_taxes
+                        .iter()
+                        .filter(|tax| tax.covers(SupplyKind::PhysicalGoods))
+                        .map(|tax| TaxRate::new(tax.rate, tax.tax, RateSource::CountryDefault)),
+                );
+            }
             TaxSystemType::Pst | TaxSystemType::Hst | TaxSystemType::Qst => {
-                self.handle_gst_rates(country_data, region, &mut rates)?
+                self.handle_gst_rates(country, country_data, region, rates)?
             }
             TaxSystemType::None => {
                 debug!("No tax system type");
             }
         }
 
-        if rates.is_empty() {
-            if matches!(country_data.tax_type, TaxSystemType::Vat) {
-                Err(DatabaseError::VatRateNotFound(
-                    vat_rate.unwrap_or(&VatRate::Standard).to_string(),
-                ))
-            } else {
-                Ok(rates)
-            }
-        } else {
-            Ok(rates)
+        if rates.len() == start && matches!(country_data.tax_type, TaxSystemType::Vat) {
+            return Err(DatabaseError::VatRateNotFound(
+                vat_rate.unwrap_or(&VatRate::Standard).to_string(),
+            ));
         }
+        Ok(())
     }
 
     fn handle_vat_rates(
@@ -326,87 +656,101 @@ impl TaxDatabase {
             VatRate::Reduced => country.reduced_rate,
             VatRate::ReducedAlt => country.reduced_rate_alt,
             VatRate::SuperReduced => country.super_reduced_rate,
-            VatRate::Zero | VatRate::Exempt | VatRate::ReverseCharge => Some(0.0),
+            VatRate::Zero | VatRate::Exempt | VatRate::ReverseCharge => Some(Percentage::ZERO),
         };
 
         if let Some(rate_value) = rate {
-            rates.push(TaxRate {
-                rate: rate_value,
-                tax_type: TaxType::VAT(rate_type.clone()),
-                compound: false,
-            });
+            rates.push(TaxRate::new(
+                rate_value,
+                TaxType::VAT(*rate_type),
+                RateSource::CountryDefault,
+            ));
         }
         Ok(())
     }
 
     fn handle_gst_rates(
         &self,
+        code: &str,
         country: &Country,
         region: Option<&str>,
         rates: &mut Vec<TaxRate>,
     ) -> Result<(), DatabaseError> {
         if let Some(region_code) = region {
-            if let Some(states) = &country.states {
-                if let Some(state) = states.get(region_code) {
-                    debug!("############ Found state: {}", region_code);
-                    match state.tax_type {
-                        TaxSystemType::Hst => {
-                            // HST replaces GST, single rate
-                            rates.push(TaxRate {
-                                rate: state.standard_rate,
-                                tax_type: TaxType::HST,
-                                compound: false,
-                            });
-                        }
-                        TaxSystemType::Qst => {
-                            // Add GST first
-                            rates.push(TaxRate {
-                                rate: country.standard_rate,
-                                tax_type: TaxType::GST,
-                                compound: false,
-                            });
-                            // Then QST
-                            rates.push(TaxRate {
-                                rate: state.standard_rate,
-                                tax_type: TaxType::QST,
-                                compound: true,
-                            });
-                        }
-                        TaxSystemType::Pst => {
-                            // Only add rates if not zero-rated
-                            // Add GST first
-                            rates.push(TaxRate {
-                                rate: country.standard_rate,
-                                tax_type: TaxType::GST,
-                                compound: false,
-                            });
-                            // Then PST
-                            rates.push(TaxRate {
-                                rate: state.standard_rate,
-                                tax_type: TaxType::PST,
-                                compound: true,
-                            });
-                        }
-                        _ => {
-                            // Just GST for other cases
-                            rates.push(TaxRate {
-                                rate: country.standard_rate,
-                                tax_type: TaxType::GST,
-                                compound: false,
-                            });
-                        }
+            if let Some(state) = rate_provider::state(country, code, region_code) {
+                debug!("############ Found state: {}", region_code);
+                match state.tax_type {
+                    TaxSystemType::Hst => {
+                        // HST replaces GST, single rate
+                        rates.push(
+                            TaxRate::new(
+                                state.standard_rate,
+                                TaxType::HST,
+                                RateSource::StateSpecific,
+                            )
+                            .with_cap(state.cap),
+                        );
+                    }
+                    TaxSystemType::Qst => {
+                        // Add GST first
+                        rates.push(TaxRate::new(
+                            country.standard_rate,
+                            TaxType::GST,
+                            RateSource::CountryDefault,
+                        ));
+                        // Then QST
+                        rates.push(
+                            TaxRate::new(
+                                state.standard_rate,
+                                TaxType::QST,
+                                RateSource::StateSpecific,
+                            )
+                            .compounding()
+                            .with_cap(state.cap),
+                        );
+                    }
+                    TaxSystemType::Pst => {
+                        // Only add rates if not zero-rated
+                        // Add GST first
+                        rates.push(TaxRate::new(
+                            country.standard_rate,
+                            TaxType::GST,
+                            RateSource::CountryDefault,
+                        ));
+                        // Then PST
+                        rates.push(
+                            TaxRate::new(
+                                state.standard_rate,
+                                TaxType::PST,
+                                RateSource::StateSpecific,
+                            )
+                            .compounding()
+                            .with_cap(state.cap),
+                        );
+                    }
+                    _ => {
+                        // Just GST for other cases
+                        rates.push(TaxRate::new(
+                            country.standard_rate,
+                            TaxType::GST,
+                            RateSource::CountryDefault,
+                        ));
                     }
-                    return Ok(());
                 }
+                return Ok(());
             }
         }
 
         // Default to just GST if no region or region not found
-        rates.push(TaxRate {
-            rate: country.standard_rate,
-            tax_type: TaxType::GST,
-            compound: false,
-        });
+        rates.push(TaxRate::new(
+            country.standard_rate,
+            TaxType::GST,
+            if region.is_some() {
+                RateSource::Fallback
+            } else {
+                RateSource::CountryDefault
+            },
+        ));
         Ok(())
     }
 }
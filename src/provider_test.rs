@@ -0,0 +1,228 @@
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+
+    use crate::vat_id::VatId;
+    use crate::{
+        quick_rate, quick_tax, InputValidationError, Percentage, ProcessingError, RateSource,
+        Region, TaxDatabase, TaxScenario, TaxType, TransactionType, VatRate,
+    };
+
+    #[test]
+    fn test_fixture_countries() {
+        let db = TaxDatabase::fixture();
+
+        let rates = db
+            .get_rate("FR", None, Some(&VatRate::SuperReduced))
+            .expect("Rates should be found");
+        assert_eq!(rates[0].rate, Percentage::from_fraction(dec!(0.021)));
+
+        let rates = db
+            .get_rate("CA", Some("CA-QC"), None)
+            .expect("Rates should be found");
+        assert_eq!(rates.len(), 2);
+        assert_eq!(rates[1].tax_type, TaxType::QST);
+
+        assert!(db.get_country("US").is_err());
+    }
+
+    #[test]
+    fn test_fixture_agreements() {
+        let db = TaxDatabase::fixture();
+        assert!(db.get_international_rule("DE", "FR").is_some());
+        assert!(db.get_federal_rule("CA").is_some());
+
+        let scenario = TaxScenario::new(
+            Region::new("DE".to_string(), None).expect("Valid German region"),
+            Region::new("FR".to_string(), None).expect("Valid French region"),
+            TransactionType::B2B,
+        )
+        .with_buyer_vat_id(VatId::parse("FR40303265045").unwrap());
+        let tax = scenario
+            .calculate_tax(100.0, &db)
+            .expect("Tax calculation should succeed");
+        assert_eq!(tax, 0.0); // EU reverse charge
+    }
+
+    #[test]
+    fn test_quick_rate() {
+        let db = TaxDatabase::fixture();
+        let rate = quick_rate(&db, "FR", None, Some(VatRate::Reduced)).expect("Rate should exist");
+        assert_eq!(rate, Percentage::from_fraction(dec!(0.1)));
+
+        let rate = quick_rate(&db, "CA", Some("CA-BC"), None).expect("Rate should exist");
+        assert_eq!(rate, Percentage::from_fraction(dec!(0.1235))); // GST 5% + PST 7% compounded
+
+        // The rate is exact, like the tax of `calculate_tax`
+        let rate = quick_rate(&db, "CA", Some("CA-QC"), None).expect("Rate should exist");
+        assert_eq!(rate.fraction(), dec!(0.1547375));
+        let quebec = TaxScenario::new(
+            Region::parse("CA-QC").unwrap(),
+            Region::parse("CA-QC").unwrap(),
+            TransactionType::B2C,
+        );
+        assert_eq!(
+            quebec.calculate_tax(1000.0, &db).unwrap(),
+            154.74 // 1000 * 15.47375%, rounded
+        );
+
+        // Codes are normalized like those of regions
+        let rate = quick_rate(&db, "de", None, None).expect("Rate should exist");
+        assert_eq!(rate, Percentage::from_fraction(dec!(0.19)));
+        let rate = quick_rate(&db, "DEU", None, None).expect("Rate should exist");
+        assert_eq!(rate, Percentage::from_fraction(dec!(0.19)));
+        let rate = quick_rate(&db, "can", Some("bc"), None).expect("Rate should exist");
+        assert_eq!(rate, Percentage::from_fraction(dec!(0.1235)));
+
+        let result = quick_rate(&db, "XX", None, None);
+        assert!(matches!(
+            result,
+            Err(ProcessingError::InputValidationError(
+                InputValidationError::InvalidCountryCode { .. }
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_quick_tax() {
+        let db = TaxDatabase::fixture();
+        let tax = quick_tax(&db, "DE", "DE", 100.0, TransactionType::B2C).expect("Tax");
+        assert_eq!(tax, 19.0);
+
+        let tax = quick_tax(&db, "CA-ON", "CA-ON", 100000.0, TransactionType::B2C).expect("Tax");
+        assert_eq!(tax, 8000.0);
+
+        let tax = quick_tax(&db, "de", "DEU", 100.0, TransactionType::B2C).expect("Tax");
+        assert_eq!(tax, 19.0);
+    }
+
+    #[test]
+    fn test_rate_sources() {
+        let db = TaxDatabase::fixture();
+
+        let rates = db.get_rate("CA", Some("CA-BC"), None).unwrap();
+        assert_eq!(rates[0].source, RateSource::CountryDefault); // GST
+        assert_eq!(rates[1].source, RateSource::StateSpecific); // PST
+
+        let rates = db.get_rate("CA", Some("CA-AB"), None).unwrap();
+        assert_eq!(rates.len(), 1);
+        assert_eq!(rates[0].tax_type, TaxType::GST);
+        assert_eq!(rates[0].source, RateSource::Fallback);
+
+        let rates = db.get_rate("DE", None, None).unwrap();
+        assert_eq!(rates[0].source, RateSource::CountryDefault);
+
+        let scenario = TaxScenario::new(
+            Region::new("FR".to_string(), None).unwrap(),
+            Region::new("DE".to_string(), None).unwrap(),
+            TransactionType::B2B,
+        )
+        .with_buyer_vat_id(VatId::parse("DE136695976").unwrap());
+        let rates = scenario.get_rates(100.0, &db).unwrap();
+        assert_eq!(rates[0].source, RateSource::AgreementImplied); // Reverse charge
+    }
+
+    #[test]
+    fn test_state_key_normalization() {
+        let countries = r#"{
+            "CA": {
+                "type": "gst",
+                "currency": "CAD",
+                "standard_rate": 0.05,
+                "states": {
+                    "BC": { "standard_rate": 0.07, "type": "pst" },
+                    "ca-on": { "standard_rate": 0.08, "type": "hst" },
+                    "CA-QC": { "standard_rate": 0.09975, "type": "qst" }
+                }
+            }
+        }"#;
+        let db = TaxDatabase::from_json(countries, "{}").expect("Data should load");
+        let pointers: Vec<&str> = db
+            .migration_notes()
+            .iter()
+            .map(|note| note.pointer.as_str())
+            .collect();
+        assert_eq!(pointers.len(), 2);
+        assert!(pointers.contains(&"/CA/states/BC"));
+        assert!(pointers.contains(&"/CA/states/ca-on"));
+
+        // Any convention finds the state, instead of falling back to GST only
+        for code in ["CA-BC", "BC", "bc", "ca-bc"] {
+            let rates = db.get_rate("CA", Some(code), None).unwrap();
+            assert_eq!(rates.len(), 2, "{}", code);
+            assert_eq!(rates[1].tax_type, TaxType::PST);
+        }
+        assert_eq!(
+            db.get_country("CA").unwrap().state("on").unwrap().code(),
+            "CA-ON"
+        );
+
+        let region = Region::new("CA".to_string(), Some("bc".to_string())).unwrap();
+        assert_eq!(region.region.as_deref(), Some("CA-BC"));
+
+        let duplicate = r#"{
+            "CA": {
+                "type": "gst",
+                "currency": "CAD",
+                "standard_rate": 0.05,
+                "states": {
+                    "BC": { "standard_rate": 0.07, "type": "pst" },
+                    "CA-BC": { "standard_rate": 0.07, "type": "pst" }
+                }
+            }
+        }"#;
+        assert!(TaxDatabase::from_json(duplicate, "{}").is_err());
+    }
+
+    #[test]
+    fn test_region_code_conventions() {
+        let db = TaxDatabase::new().expect("Database should load");
+        let regions = [
+            Region::new("CA".to_string(), Some("CA-BC".to_string())),
+            Region::new("CA".to_string(), Some("BC".to_string())),
+            Region::new("ca".to_string(), Some("bc".to_string())),
+            Region::parse("CA-BC"),
+            Region::parse("ca-bc"),
+        ];
+        for region in regions {
+            let region = region.expect("Valid region");
+            assert_eq!(
+                (region.country.as_str(), region.region.as_deref()),
+                ("CA", Some("CA-BC"))
+            );
+            assert_eq!(db.get_rate_at(&region, None).unwrap().len(), 2);
+        }
+
+        // A subdivision of another country isn't a region of the country
+        assert!(matches!(
+            Region::new("CA".to_string(), Some("US-WA".to_string())),
+            Err(InputValidationError::InvalidRegionCode { .. })
+        ));
+    }
+
+    #[test]
+    fn test_error_suggestions() {
+        match Region::parse("CA-BZ") {
+            Err(InputValidationError::InvalidRegionCode { code, suggestions }) => {
+                assert_eq!(code, "CA-BZ");
+                assert!(suggestions.contains(&"CA-BC".to_string()));
+                assert!(suggestions.len() <= 3);
+            }
+            other => panic!("Expected an invalid region code, got {:?}", other),
+        }
+
+        let error = Region::parse("DX").unwrap_err();
+        assert!(error
+            .to_string()
+            .starts_with("Invalid country code: DX - Did you mean "));
+
+        let db = TaxDatabase::fixture();
+        let error = db.get_country("FX").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Country not found: FX - Did you mean FR?"
+        );
+        let error = db.get_country("ZZZZ").unwrap_err();
+        assert_eq!(error.to_string(), "Country not found: ZZZZ");
+    }
+}
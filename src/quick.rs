@@ -0,0 +1,63 @@
+//! Stateless shortcuts for simple use-cases.
+//!
+//! These functions cover the common case of "what's the rate here" and "how much
+//! tax is this" without building a `TaxScenario` first. Regions are given as a
+//! single code, either a country ("DE") or a subdivision ("US-CA"); codes are
+//! normalized like those of `Region::new`, so "de" and "DEU" are Germany.
+
+use crate::calculation::combined_rate_decimal;
+use crate::percentage::Percentage;
+use crate::{ProcessingError, Region, TaxDatabase, TaxScenario, TransactionType, VatRate};
+
+/// Returns the combined tax rate of a jurisdiction.
+///
+/// Compound rates (e.g. Quebec's QST) are applied on top of the preceding rates.
+///
+/// # Examples
+///
+/// ```
+/// # use world_tax::{quick_rate, TaxDatabase, VatRate};
+/// let db = TaxDatabase::fixture();
+/// let rate = quick_rate(&db, "DE", None, Some(VatRate::Standard)).unwrap();
+/// assert_eq!(rate.to_string(), "19%");
+/// ```
+///
+/// # Errors
+///
+/// Returns an error if the codes are invalid, or if the rate isn't in the database.
+pub fn quick_rate(
+    db: &TaxDatabase,
+    country: &str,
+    region: Option<&str>,
+    vat_rate: Option<VatRate>,
+) -> Result<Percentage, ProcessingError> {
+    let region = Region::new(country.to_string(), region.map(str::to_string))?;
+    let rates = db.get_rate(&region.country, region.region.as_deref(), vat_rate.as_ref())?;
+    Ok(Percentage::from_fraction(combined_rate_decimal(&rates)))
+}
+
+/// Calculates the tax of a transaction between two regions with default settings.
+///
+/// # Examples
+///
+/// ```
+/// # use world_tax::{quick_tax, TaxDatabase, TransactionType};
+/// let db = TaxDatabase::fixture();
+/// let tax = quick_tax(&db, "DE", "FR", 100.0, TransactionType::B2C).unwrap();
+/// // French VAT: without the seller's turnover, a sale isn't taken for the year's sales
+/// assert_eq!(tax, 20.0);
+/// ```
+///
+/// # Errors
+///
+/// Returns an error if the codes are invalid, or if the calculation fails.
+pub fn quick_tax(
+    db: &TaxDatabase,
+    from: &str,
+    to: &str,
+    amount: f64,
+    transaction_type: TransactionType,
+) -> Result<f64, ProcessingError> {
+    let scenario = TaxScenario::new(Region::parse(from)?, Region::parse(to)?, transaction_type);
+    scenario.calculate_tax(amount, db)
+}
@@ -0,0 +1,248 @@
+//! Pluggable rate lookups.
+//!
+//! `TaxScenario::calculate_tax`, `get_rates` and `determine_calculation_type` look
+//! up countries, rates and trade agreements through the `RateProvider` trait, which
+//! `TaxDatabase` implements. Implement it to back calculations with your own store
+//! (e.g. a SQL database, or a remote service) instead of the embedded dataset.
+//!
+//! Data beyond countries, rates and agreements (calculation profiles, local rate
+//! tables) is looked up through provided methods that default to none; scenarios
+//! referencing a profile fail with `DatabaseError::ProfileNotFound` unless the
+//! provider overrides `get_profile`.
+
+use std::borrow::Cow;
+use std::sync::Arc;
+
+use crate::currency::CurrencyCode;
+use crate::errors::DatabaseError;
+use crate::overrides::OverrideRule;
+use crate::profile::CalculationProfile;
+use crate::sst::BoundaryTable;
+use crate::types::{Country, State, TaxRate, TradeAgreement, VatRate};
+use crate::{TaxDatabase, TaxScenario};
+
+/// Looks up the data tax calculations need.
+///
+/// # Examples
+///
+/// ```
+/// use std::borrow::Cow;
+/// use rust_decimal_macros::dec;
+/// use world_tax::types::{Country, TaxRate, TradeAgreement, VatRate};
+/// use world_tax::{
+///     DatabaseError, Percentage, RateProvider, Region, TaxDatabase, TaxScenario, TransactionType,
+/// };
+///
+/// /// Serves rates from the embedded dataset, with a different German standard rate.
+/// struct Store {
+///     db: TaxDatabase,
+/// }
+///
+/// impl RateProvider for Store {
+///     fn get_country(&self, code: &str) -> Result<Cow<'_, Country>, DatabaseError> {
+///         RateProvider::get_country(&self.db, code)
+///     }
+///
+///     fn get_rate(
+///         &self,
+///         country: &str,
+///         region: Option<&str>,
+///         vat_rate: Option<&VatRate>,
+///     ) -> Result<Vec<TaxRate>, DatabaseError> {
+///         let mut rates = self.db.get_rate(country, region, vat_rate)?;
+///         if country == "DE" && vat_rate.is_none() {
+///             rates[0].rate = Percentage::from_percent(dec!(20));
+///         }
+///         Ok(rates)
+///     }
+///
+///     fn get_rule(&self, rule_id: &str) -> Result<TradeAgreement, DatabaseError> {
+///         self.db.get_rule(rule_id)
+///     }
+///
+///     fn get_federal_rule(&self, country: &str) -> Option<TradeAgreement> {
+///         self.db.get_federal_rule(country)
+///     }
+///
+///     fn get_international_rule(&self, source: &str, dest: &str) -> Option<TradeAgreement> {
+///         self.db.get_international_rule(source, dest)
+///     }
+/// }
+///
+/// let store = Store { db: TaxDatabase::fixture() };
+/// let scenario = TaxScenario::new(
+///     Region::parse("DE").unwrap(),
+///     Region::parse("DE").unwrap(),
+///     TransactionType::B2C,
+/// );
+/// assert_eq!(scenario.calculate_tax(100.0, &store).unwrap(), 20.0);
+/// ```
+pub trait RateProvider {
+    /// Retrieves the tax information of a country.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::CountryNotFound` if the country isn't known.
+    fn get_country(&self, code: &str) -> Result<Cow<'_, Country>, DatabaseError>;
+
+    /// Retrieves the tax rates of a jurisdiction (see `TaxDatabase::get_rate`).
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::CountryNotFound` if the country isn't known, and
+    /// `DatabaseError::VatRateNotFound` if the requested VAT rate doesn't exist.
+    fn get_rate(
+        &self,
+        country: &str,
+        region: Option<&str>,
+        vat_rate: Option<&VatRate>,
+    ) -> Result<Vec<TaxRate>, DatabaseError>;
+
+    /// Retrieves a trade agreement by identifier (e.g. "EU").
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::TradeAgreementNotFound` if the agreement isn't known.
+    fn get_rule(&self, rule_id: &str) -> Result<TradeAgreement, DatabaseError>;
+
+    /// Finds the federal agreement between the states of a country (e.g. "US").
+    fn get_federal_rule(&self, country: &str) -> Option<TradeAgreement>;
+
+    /// Finds the international agreement between two countries, if both implemented
+    /// its tax rules.
+    fn get_international_rule(&self, source: &str, dest: &str) -> Option<TradeAgreement>;
+
+    /// Appends the tax rates of a jurisdiction to `rates`; override it to avoid the
+    /// allocation of `get_rate`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of `get_rate`.
+    fn get_rate_into(
+        &self,
+        country: &str,
+        region: Option<&str>,
+        vat_rate: Option<&VatRate>,
+        rates: &mut Vec<TaxRate>,
+    ) -> Result<(), DatabaseError> {
+        rates.extend(self.get_rate(country, region, vat_rate)?);
+        Ok(())
+    }
+
+    /// Retrieves a calculation profile by name; no profiles by default.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::ProfileNotFound` if no profile has that name.
+    fn get_profile(&self, name: &str) -> Result<&CalculationProfile, DatabaseError> {
+        Err(DatabaseError::ProfileNotFound(name.to_string()))
+    }
+
+    /// The local rates of a state; none by default.
+    fn local_table(&self, _state: &str) -> Option<Arc<BoundaryTable>> {
+        None
+    }
+
+    /// The rate an amount in `from` is multiplied by to state it in `to`, to compare
+    /// it against thresholds; none by default.
+    fn exchange_rate(&self, _from: CurrencyCode, _to: CurrencyCode) -> Option<f64> {
+        None
+    }
+
+    /// Why no international agreement applies between two countries, where that may
+    /// be unexpected (e.g. a member hasn't implemented the agreement's tax rules);
+    /// recorded in explanations. None by default.
+    fn no_agreement_reason(&self, _source: &str, _dest: &str) -> Option<String> {
+        None
+    }
+
+    /// The override rules matching a scenario, by precedence (see `overrides`); none
+    /// by default.
+    fn matching_overrides(&self, _scenario: &TaxScenario) -> Vec<&OverrideRule> {
+        Vec::new()
+    }
+}
+
+/// Looks up a state of a country; see `TaxDatabase::state`.
+pub(crate) fn state<'a>(country: &'a Country, country_code: &str, code: &str) -> Option<&'a State> {
+    let states = country.states.as_ref()?;
+    states
+        .get(code)
+        .or_else(|| states.get(&crate::types::normalize_state_code(country_code, code)))
+}
+
+/// Returns true if a flag of the state is set; false if it isn't known.
+pub(crate) fn state_flag<P: RateProvider + ?Sized>(
+    db: &P,
+    country: &str,
+    code: &str,
+    flag: impl Fn(&State) -> bool,
+) -> bool {
+    db.get_country(country)
+        .ok()
+        .is_some_and(|data| state(&data, country, code).is_some_and(flag))
+}
+
+impl RateProvider for TaxDatabase {
+    fn get_country(&self, code: &str) -> Result<Cow<'_, Country>, DatabaseError> {
+        self.country(code).map(Cow::Borrowed)
+    }
+
+    fn get_rate(
+        &self,
+        country: &str,
+        region: Option<&str>,
+        vat_rate: Option<&VatRate>,
+    ) -> Result<Vec<TaxRate>, DatabaseError> {
+        TaxDatabase::get_rate(self, country, region, vat_rate)
+    }
+
+    fn get_rule(&self, rule_id: &str) -> Result<TradeAgreement, DatabaseError> {
+        TaxDatabase::get_rule(self, rule_id)
+    }
+
+    fn get_federal_rule(&self, country: &str) -> Option<TradeAgreement> {
+        TaxDatabase::get_federal_rule(self, country)
+    }
+
+    fn get_international_rule(&self, source: &str, dest: &str) -> Option<TradeAgreement> {
+        TaxDatabase::get_international_rule(self, source, dest)
+    }
+
+    fn get_rate_into(
+        &self,
+        country: &str,
+        region: Option<&str>,
+        vat_rate: Option<&VatRate>,
+        rates: &mut Vec<TaxRate>,
+    ) -> Result<(), DatabaseError> {
+        self.push_rates(country, region, vat_rate, rates)
+    }
+
+    fn get_profile(&self, name: &str) -> Result<&CalculationProfile, DatabaseError> {
+        TaxDatabase::get_profile(self, name)
+    }
+
+    fn local_table(&self, state: &str) -> Option<Arc<BoundaryTable>> {
+        TaxDatabase::local_table(self, state)
+    }
+
+    fn exchange_rate(&self, from: CurrencyCode, to: CurrencyCode) -> Option<f64> {
+        TaxDatabase::exchange_rate(self, from, to)
+    }
+
+    fn no_agreement_reason(&self, source: &str, dest: &str) -> Option<String> {
+        if let Some((agreement, member)) = self.non_implemented_rule(source, dest) {
+            return Some(format!(
+                "{} hasn't implemented the tax rules of {}",
+                member, agreement.name
+            ));
+        }
+        self.non_member_agreement(source, dest)
+            .map(|(agreement, country)| format!("{} isn't a member of {}", country, agreement))
+    }
+
+    fn matching_overrides(&self, scenario: &TaxScenario) -> Vec<&OverrideRule> {
+        TaxDatabase::matching_overrides(self, scenario)
+    }
+}
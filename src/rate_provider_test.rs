@@ -0,0 +1,107 @@
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+    use std::collections::HashMap;
+
+    use crate::rate_provider::RateProvider;
+    use crate::types::{Country, TradeAgreement};
+    use crate::{
+        DatabaseError, ProcessingError, RateSource, Region, TaxDatabase, TaxRate, TaxScenario,
+        TaxType, TransactionType, VatRate,
+    };
+
+    /// Standard rates of a fixed set of countries, without trade agreements.
+    struct StandardRates(HashMap<String, Country>);
+
+    impl RateProvider for StandardRates {
+        fn get_country(&self, code: &str) -> Result<Cow<'_, Country>, DatabaseError> {
+            self.0
+                .get(code)
+                .map(Cow::Borrowed)
+                .ok_or_else(|| DatabaseError::CountryNotFound {
+                    code: code.to_string(),
+                    suggestions: Vec::new(),
+                })
+        }
+
+        fn get_rate(
+            &self,
+            country: &str,
+            _region: Option<&str>,
+            _vat_rate: Option<&VatRate>,
+        ) -> Result<Vec<TaxRate>, DatabaseError> {
+            let country = self.get_country(country)?;
+            Ok(vec![TaxRate::new(
+                country.standard_rate,
+                TaxType::VAT(VatRate::Standard),
+                RateSource::CountryDefault,
+            )])
+        }
+
+        fn get_rule(&self, rule_id: &str) -> Result<TradeAgreement, DatabaseError> {
+            Err(DatabaseError::TradeAgreementNotFound(rule_id.to_string()))
+        }
+
+        fn get_federal_rule(&self, _country: &str) -> Option<TradeAgreement> {
+            None
+        }
+
+        fn get_international_rule(&self, _source: &str, _dest: &str) -> Option<TradeAgreement> {
+            None
+        }
+    }
+
+    fn scenario(source: &str, destination: &str) -> TaxScenario {
+        TaxScenario::new(
+            Region::parse(source).unwrap(),
+            Region::parse(destination).unwrap(),
+            TransactionType::B2C,
+        )
+    }
+
+    #[test]
+    fn test_custom_provider() {
+        let provider = StandardRates(TaxDatabase::fixture().countries);
+
+        let domestic = scenario("DE", "DE");
+        assert_eq!(domestic.calculate_tax(100.0, &provider).unwrap(), 19.0);
+        let rates = domestic.get_rates(100.0, &provider).unwrap();
+        assert_eq!(rates.len(), 1);
+        assert_eq!(rates[0].tax_type, TaxType::VAT(VatRate::Standard));
+
+        assert!(matches!(
+            scenario("DE", "US").calculate_tax(100.0, &provider),
+            Err(ProcessingError::DatabaseError(
+                DatabaseError::CountryNotFound { .. }
+            ))
+        ));
+
+        // The provider has no calculation profiles
+        assert!(matches!(
+            domestic
+                .with_profile("strict")
+                .calculate_tax(100.0, &provider),
+            Err(ProcessingError::DatabaseError(
+                DatabaseError::ProfileNotFound(_)
+            ))
+        ));
+    }
+
+    #[test]
+    fn test_database_as_provider() {
+        let db = TaxDatabase::new().expect("Embedded data should load");
+        let provider: &dyn RateProvider = &db;
+
+        for (source, destination) in [("DE", "FR"), ("US-CA", "US-CA"), ("CA-ON", "CA-QC")] {
+            let scenario = scenario(source, destination);
+            assert_eq!(
+                scenario.calculate_tax(100.0, provider).unwrap(),
+                scenario.calculate_tax(100.0, &db).unwrap()
+            );
+        }
+        assert_eq!(
+            provider.no_agreement_reason("NO", "DE").as_deref(),
+            Some("NO isn't a member of EU")
+        );
+    }
+}
@@ -0,0 +1,230 @@
+//! Custom groups of regions.
+//!
+//! Business rules often apply to a set of countries that isn't a trade agreement:
+//! "DACH", the Nordics, the countries a warehouse ships to. Region groups are defined
+//! once on the database, in code or from a configuration file, and rules reference
+//! them by name; the database resolves their membership, so applications don't
+//! enumerate countries.
+//!
+//! Members of a group are country codes ("DE"), subdivision codes ("US-CA"), trade
+//! agreement identifiers ("EU", for the agreement's members) and the names of other
+//! groups. Groups are resolved to region codes when they are defined: redefining a
+//! group doesn't change the groups that included it.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::errors::DatabaseError;
+use crate::{Region, TaxDatabase, TaxScenario};
+
+/// A named group of regions, resolved to country and subdivision codes.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RegionGroup {
+    members: BTreeSet<String>,
+}
+
+impl RegionGroup {
+    /// The country and subdivision codes of the group, ordered by code.
+    pub fn members(&self) -> impl Iterator<Item = &str> {
+        self.members.iter().map(String::as_str)
+    }
+
+    /// Returns true if the region is in the group: its country is a member, or its
+    /// subdivision is.
+    pub fn contains(&self, region: &Region) -> bool {
+        self.members.contains(&region.country)
+            || region
+                .region
+                .as_ref()
+                .is_some_and(|code| self.members.contains(code))
+    }
+}
+
+impl TaxDatabase {
+    /// Defines a region group, replacing any defined before under the name.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use world_tax::{Region, TaxDatabase};
+    /// let mut db = TaxDatabase::fixture();
+    /// db.define_region_group("DACH", &["DE", "AT", "CH"]).unwrap();
+    /// db.define_region_group("warehouse-zone", &["EU", "DACH", "CA-BC"]).unwrap();
+    ///
+    /// let zone = db.region_group("warehouse-zone").unwrap();
+    /// assert!(zone.contains(&Region::parse("FR").unwrap())); // EU member
+    /// assert!(zone.contains(&Region::parse("CH").unwrap()));
+    /// assert!(!zone.contains(&Region::parse("CA-ON").unwrap()));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::InvalidRegionGroup` if the name is empty or a country
+    /// code or agreement identifier, or a member is neither a region code, an agreement
+    /// nor a defined group.
+    pub fn define_region_group(
+        &mut self,
+        name: impl Into<String>,
+        members: &[&str],
+    ) -> Result<(), DatabaseError> {
+        let name = name.into();
+        let members = members.iter().map(|member| member.to_string()).collect();
+        let definitions = BTreeMap::from([(name.clone(), members)]);
+        let group = self.resolve_group(&name, &definitions, &mut Vec::new())?;
+        self.region_groups.insert(name, group);
+        Ok(())
+    }
+
+    /// Loads region groups from JSON configuration: an object of group names and their
+    /// members. Groups may include each other, in any order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use world_tax::{Region, TaxDatabase};
+    /// let mut db = TaxDatabase::new().unwrap();
+    /// db.load_region_groups(r#"{
+    ///     "DACH": ["DE", "AT", "CH"],
+    ///     "Nordics": ["DK", "FI", "IS", "NO", "SE"],
+    ///     "Central Europe": ["DACH", "CZ", "PL"]
+    /// }"#).unwrap();
+    ///
+    /// let austria = Region::parse("AT").unwrap();
+    /// assert_eq!(db.region_groups_of(&austria), vec!["Central Europe", "DACH"]);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::InvalidRegionGroup` if the JSON isn't an object of
+    /// member lists, a group is invalid (see `define_region_group`), or groups include
+    /// each other in a cycle. No group is loaded then.
+    pub fn load_region_groups(&mut self, json: &str) -> Result<(), DatabaseError> {
+        let definitions: BTreeMap<String, Vec<String>> =
+            serde_json::from_str(json).map_err(|error| {
+                DatabaseError::InvalidRegionGroup(format!("configuration: {error}"))
+            })?;
+        let groups = definitions
+            .keys()
+            .map(|name| {
+                Ok((
+                    name.clone(),
+                    self.resolve_group(name, &definitions, &mut Vec::new())?,
+                ))
+            })
+            .collect::<Result<Vec<_>, DatabaseError>>()?;
+        self.region_groups.extend(groups);
+        Ok(())
+    }
+
+    /// A defined region group.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::RegionGroupNotFound` if no group is defined under the
+    /// name.
+    pub fn region_group(&self, name: &str) -> Result<&RegionGroup, DatabaseError> {
+        self.region_groups
+            .get(name)
+            .ok_or_else(|| DatabaseError::RegionGroupNotFound(name.to_string()))
+    }
+
+    /// The names of the groups a region is in, ordered by name.
+    pub fn region_groups_of(&self, region: &Region) -> Vec<&str> {
+        let mut names: Vec<&str> = self
+            .region_groups
+            .iter()
+            .filter(|(_, group)| group.contains(region))
+            .map(|(name, _)| name.as_str())
+            .collect();
+        names.sort_unstable();
+        names
+    }
+
+    /// Resolves a group to region codes; `definitions` are groups being defined, which
+    /// take precedence over defined ones, and `path` the groups being resolved.
+    fn resolve_group(
+        &self,
+        name: &str,
+        definitions: &BTreeMap<String, Vec<String>>,
+        path: &mut Vec<String>,
+    ) -> Result<RegionGroup, DatabaseError> {
+        let invalid =
+            |reason: String| DatabaseError::InvalidRegionGroup(format!("{name}: {reason}"));
+        if name.trim().is_empty()
+            || rust_iso3166::from_alpha2(name).is_some()
+            || self.trade_agreements.contains_key(name)
+        {
+            return Err(invalid(
+                "the name must not be empty, a country code or an agreement".to_string(),
+            ));
+        }
+        if path.iter().any(|group| group == name) {
+            return Err(invalid(format!("includes itself via {}", path.join(", "))));
+        }
+
+        let Some(members) = definitions.get(name) else {
+            return self.region_group(name).cloned();
+        };
+        path.push(name.to_string());
+        let mut group = RegionGroup::default();
+        for member in members {
+            if definitions.contains_key(member) || self.region_groups.contains_key(member) {
+                let included = self.resolve_group(member, definitions, path)?;
+                group.members.extend(included.members);
+            } else if let Some(agreement) = self.trade_agreements.get(member) {
+                group.members.extend(agreement.members.iter().cloned());
+            } else {
+                let region = Region::parse(member).map_err(|_| {
+                    invalid(format!(
+                        "{member} is neither a region, an agreement nor a group"
+                    ))
+                })?;
+                group
+                    .members
+                    .insert(region.region.unwrap_or(region.country));
+            }
+        }
+        path.pop();
+        Ok(group)
+    }
+
+    /// Resolves a reference to regions outside of group definitions: a defined group,
+    /// a trade agreement or a region code.
+    pub(crate) fn resolve_regions(&self, reference: &str) -> Option<RegionGroup> {
+        if let Some(group) = self.region_groups.get(reference) {
+            return Some(group.clone());
+        }
+        let members = match self.trade_agreements.get(reference) {
+            Some(agreement) => agreement.members.iter().cloned().collect(),
+            None => {
+                let region = Region::parse(reference).ok()?;
+                BTreeSet::from([region.region.unwrap_or(region.country)])
+            }
+        };
+        Some(RegionGroup { members })
+    }
+}
+
+impl TaxScenario {
+    /// Returns true if the buyer is in a region group.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use world_tax::{Region, TaxDatabase, TaxScenario, TransactionType};
+    /// let mut db = TaxDatabase::fixture();
+    /// db.define_region_group("DACH", &["DE", "AT", "CH"]).unwrap();
+    /// let scenario = TaxScenario::new(
+    ///     Region::parse("FR").unwrap(),
+    ///     Region::parse("DE").unwrap(),
+    ///     TransactionType::B2C,
+    /// );
+    /// assert!(scenario.destination_in("DACH", &db).unwrap());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::RegionGroupNotFound` if the group isn't defined.
+    pub fn destination_in(&self, group: &str, db: &TaxDatabase) -> Result<bool, DatabaseError> {
+        Ok(db.region_group(group)?.contains(&self.destination_region))
+    }
+}
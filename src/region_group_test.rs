@@ -0,0 +1,91 @@
+#[cfg(test)]
+mod tests {
+    use crate::{DatabaseError, Region, TaxDatabase, TaxScenario, TransactionType};
+
+    fn region(code: &str) -> Region {
+        Region::parse(code).expect("Valid region")
+    }
+
+    #[test]
+    fn test_define_region_group() {
+        let mut db = TaxDatabase::fixture();
+        db.define_region_group("DACH", &["de", "AT", "CH"])
+            .expect("Group should be defined");
+        db.define_region_group("west-coast", &["US-CA", "US-OR", "US-WA", "CA-BC"])
+            .expect("Group should be defined");
+        db.define_region_group("zone-1", &["EU", "DACH", "west-coast"])
+            .expect("Group should be defined");
+
+        let zone = db.region_group("zone-1").expect("Group is defined");
+        assert_eq!(
+            zone.members().collect::<Vec<_>>(),
+            vec!["AT", "CA-BC", "CH", "DE", "FR", "US-CA", "US-OR", "US-WA"]
+        );
+        assert!(zone.contains(&region("FR")));
+        assert!(zone.contains(&region("DE").with_postal_code("10115")));
+        assert!(zone.contains(&region("US-WA")));
+        assert!(!zone.contains(&region("US")));
+        assert!(!zone.contains(&region("US-NY")));
+
+        assert_eq!(db.region_groups_of(&region("CH")), vec!["DACH", "zone-1"]);
+        assert!(db.region_groups_of(&region("JP")).is_empty());
+
+        // Groups are resolved when defined
+        db.define_region_group("DACH", &["DE", "AT", "CH", "LI"])
+            .expect("Group should be redefined");
+        assert!(!db.region_group("zone-1").unwrap().contains(&region("LI")));
+    }
+
+    #[test]
+    fn test_load_region_groups() {
+        let mut db = TaxDatabase::fixture();
+        db.load_region_groups(
+            r#"{
+                "all": ["Nordics", "DACH"],
+                "DACH": ["DE", "AT", "CH"],
+                "Nordics": ["DK", "FI", "IS", "NO", "SE"]
+            }"#,
+        )
+        .expect("Groups should load");
+        assert!(db.region_group("all").unwrap().contains(&region("SE")));
+
+        let scenario = TaxScenario::new(region("FR"), region("AT"), TransactionType::B2C);
+        assert!(scenario.destination_in("all", &db).unwrap());
+        assert!(!scenario.destination_in("Nordics", &db).unwrap());
+        assert!(matches!(
+            scenario.destination_in("Benelux", &db),
+            Err(DatabaseError::RegionGroupNotFound(_))
+        ));
+    }
+
+    #[test]
+    fn test_invalid_region_groups() {
+        let mut db = TaxDatabase::fixture();
+        for (name, members) in [
+            ("DE", vec!["AT"]),
+            ("EU", vec!["AT"]),
+            ("", vec!["AT"]),
+            ("zone", vec!["XX"]),
+            ("zone", vec!["Benelux"]),
+            ("zone", vec!["zone"]),
+        ] {
+            assert!(
+                matches!(
+                    db.define_region_group(name, &members),
+                    Err(DatabaseError::InvalidRegionGroup(_))
+                ),
+                "{name}: {members:?}"
+            );
+        }
+
+        let cycle = r#"{ "a": ["DE", "b"], "b": ["FR", "a"], "c": ["CH"] }"#;
+        match db.load_region_groups(cycle) {
+            Err(DatabaseError::InvalidRegionGroup(reason)) => {
+                assert_eq!(reason, "a: includes itself via a, b")
+            }
+            other => panic!("Expected a cycle, got {other:?}"),
+        }
+        assert!(db.region_group("c").is_err());
+        assert!(db.load_region_groups(r#"{ "a": "DE" }"#).is_err());
+    }
+}
@@ -0,0 +1,129 @@
+//! Invoice requirements per jurisdiction.
+//!
+//! What a compliant invoice must contain differs by country: EU member states
+//! require sequential numbering and the VAT amount in their own currency, and most
+//! allow simplified invoices (fewer details, no buyer) up to a limit; other
+//! countries require the buyer's tax ID only above a certain amount. The metadata
+//! here is curated for the countries listed in `SIMPLIFIED_INVOICES`, and the rules
+//! common to all EU member states.
+//!
+//! In Japan, buyers can only deduct the consumption tax of qualified invoices, which
+//! show the seller's registration number ("T" and 13 digits). Quotes and invoice
+//! totals flag when the tax charged calls for one (`qualified_invoice`).
+
+use serde::Serialize;
+use typeshare::typeshare;
+
+use crate::{TaxCalculationType, TaxDatabase, TaxScenario};
+
+/// Curated invoice rules: country, simplified invoice limit and buyer tax ID
+/// threshold, in the country's currency.
+const SIMPLIFIED_INVOICES: &[(&str, Option<f64>, Option<f64>)] = &[
+    ("AT", Some(400.0), None),
+    ("CZ", Some(10000.0), None),
+    ("DE", Some(250.0), None),
+    ("DK", Some(3000.0), None),
+    ("ES", Some(400.0), None),
+    ("FI", Some(400.0), None),
+    ("FR", Some(150.0), None),
+    ("IT", Some(400.0), None),
+    ("NL", Some(100.0), None),
+    ("PL", Some(450.0), None),
+    ("PT", Some(1000.0), None),
+    ("SE", Some(4000.0), None),
+    ("GB", Some(250.0), None),
+    ("CH", Some(400.0), None),
+    ("SG", Some(1000.0), None),
+    ("JP", None, None),
+    ("AU", None, Some(1000.0)),
+    ("NZ", None, Some(1000.0)),
+];
+
+/// Countries outside the EU that require sequential invoice numbering.
+const SEQUENTIAL_NUMBERING: [&str; 2] = ["GB", "CH"];
+
+/// Countries whose invoices must show the seller's tax registration number for the
+/// buyer to deduct the tax (qualified invoices).
+const QUALIFIED_INVOICES: [&str; 1] = ["JP"];
+
+/// What a compliant invoice must contain in a country.
+#[typeshare]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct InvoiceRequirements {
+    /// ISO 3166-1 code of the country
+    pub country: String,
+    /// Invoices must carry a unique, sequential number
+    pub sequential_numbering: bool,
+    /// Gross amount from which the buyer's tax ID must be shown, if any
+    pub buyer_tax_id_threshold: Option<f64>,
+    /// Reverse-charged and intra-EU supplies must show the buyer's VAT ID
+    pub buyer_vat_id_for_reverse_charge: bool,
+    /// Gross amount up to which a simplified invoice is sufficient, if allowed
+    pub simplified_invoice_limit: Option<f64>,
+    /// Currency the tax amount must be stated in, if it must be the local currency
+    pub tax_currency: Option<String>,
+    /// Invoices must show the seller's tax registration number (e.g. Japan's
+    /// qualified invoice number)
+    pub seller_registration_number: bool,
+}
+
+impl TaxDatabase {
+    /// Returns what a compliant invoice must contain in a country.
+    ///
+    /// Returns `None` if the country isn't in the database, or there's no invoice
+    /// metadata for it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use world_tax::TaxDatabase;
+    /// let db = TaxDatabase::fixture();
+    /// let requirements = db.invoice_requirements("DE").unwrap();
+    /// assert!(requirements.sequential_numbering);
+    /// assert_eq!(requirements.simplified_invoice_limit, Some(250.0));
+    /// assert_eq!(requirements.tax_currency.as_deref(), Some("EUR"));
+    /// ```
+    pub fn invoice_requirements(&self, country: &str) -> Option<InvoiceRequirements> {
+        let currency = &self.country(country).ok()?.currency;
+        let eu_member = self
+            .get_rule("EU")
+            .is_ok_and(|eu| eu.members.iter().any(|member| member == country));
+        let curated = SIMPLIFIED_INVOICES
+            .iter()
+            .find(|(code, _, _)| *code == country);
+        if !eu_member && curated.is_none() {
+            return None;
+        }
+
+        let (simplified_invoice_limit, buyer_tax_id_threshold) = curated
+            .map(|&(_, simplified, buyer)| (simplified, buyer))
+            .unwrap_or_default();
+        Some(InvoiceRequirements {
+            country: country.to_string(),
+            sequential_numbering: eu_member || SEQUENTIAL_NUMBERING.contains(&country),
+            buyer_tax_id_threshold,
+            buyer_vat_id_for_reverse_charge: eu_member,
+            simplified_invoice_limit,
+            // EU member states require the VAT amount in their currency (Article 230
+            // of the VAT Directive)
+            tax_currency: eu_member.then(|| currency.clone()),
+            seller_registration_number: QUALIFIED_INVOICES.contains(&country),
+        })
+    }
+}
+
+impl TaxScenario {
+    /// Returns true if tax is charged for a country with qualified invoices, so the
+    /// invoice must show the seller's registration number.
+    pub(crate) fn qualified_invoice(
+        &self,
+        calculation_type: &TaxCalculationType,
+        tax_charged: bool,
+    ) -> bool {
+        let country = match calculation_type {
+            TaxCalculationType::Origin => &self.source_region.country,
+            _ => &self.destination_region.country,
+        };
+        tax_charged && QUALIFIED_INVOICES.contains(&country.as_str())
+    }
+}
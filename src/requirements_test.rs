@@ -0,0 +1,67 @@
+#[cfg(test)]
+mod tests {
+    use crate::calculator::{TaxCalculator, TaxRequest};
+    use crate::{Region, TaxDatabase, TaxScenario, TransactionType};
+
+    #[test]
+    fn test_eu_invoice_requirements() {
+        let db = TaxDatabase::fixture();
+        let france = db
+            .invoice_requirements("FR")
+            .expect("France has requirements");
+        assert!(france.sequential_numbering);
+        assert!(france.buyer_vat_id_for_reverse_charge);
+        assert_eq!(france.simplified_invoice_limit, Some(150.0));
+        assert_eq!(france.tax_currency.as_deref(), Some("EUR"));
+    }
+
+    #[test]
+    fn test_non_eu_invoice_requirements() {
+        let db = TaxDatabase::new().expect("Tax database should load");
+        let australia = db
+            .invoice_requirements("AU")
+            .expect("Australia has requirements");
+        assert!(!australia.sequential_numbering);
+        assert!(!australia.buyer_vat_id_for_reverse_charge);
+        assert_eq!(australia.buyer_tax_id_threshold, Some(1000.0));
+        assert!(australia.tax_currency.is_none());
+
+        // EU members without curated limits still follow the EU rules
+        let belgium = db
+            .invoice_requirements("BE")
+            .expect("Belgium has requirements");
+        assert!(belgium.sequential_numbering);
+        assert!(belgium.simplified_invoice_limit.is_none());
+
+        assert!(db.invoice_requirements("TH").is_none());
+        assert!(db.invoice_requirements("XX").is_none());
+    }
+
+    #[test]
+    fn test_qualified_invoice() {
+        let db = TaxDatabase::new().expect("Tax database should load");
+        let japan = db
+            .invoice_requirements("JP")
+            .expect("Japan has requirements");
+        assert!(japan.seller_registration_number);
+        assert!(!japan.sequential_numbering);
+        assert!(
+            !db.invoice_requirements("DE")
+                .unwrap()
+                .seller_registration_number
+        );
+
+        let quote = |source: &str, destination: &str| {
+            let scenario = TaxScenario::new(
+                Region::parse(source).unwrap(),
+                Region::parse(destination).unwrap(),
+                TransactionType::B2C,
+            );
+            db.quote(TaxRequest::new(scenario, 1000.0)).unwrap()
+        };
+        let domestic = quote("JP", "JP");
+        assert_eq!(domestic.tax, 100.0);
+        assert!(domestic.qualified_invoice);
+        assert!(!quote("DE", "DE").qualified_invoice);
+    }
+}
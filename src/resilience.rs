@@ -0,0 +1,711 @@
+//! Retries, timeouts and a circuit breaker for remote rate providers.
+//!
+//! `ResilientProvider` wraps an `AsyncRateProvider` that calls a remote rates service.
+//! Each lookup times out after `RetryPolicy::timeout`, and lookups that time out or
+//! fail with `DatabaseError::ProviderUnavailable` are retried with exponential
+//! backoff. After `CircuitBreaker::failure_threshold` consecutive failures, the
+//! circuit opens and lookups go straight to the embedded dataset until
+//! `CircuitBreaker::reset_after` has passed. Lookups that fall back are answered from
+//! the embedded dataset, and quotes warn with `Warning::StaleRates`, so checkout
+//! never waits on a service that is down.
+//!
+//! `ResilientProvider::quote_within` bounds a single quote by a budget, for checkout
+//! latency targets: attempts are cut short at the deadline, lookups after it are
+//! answered from the embedded dataset, and the quote is returned as an estimate,
+//! flagged with `Warning::StaleRates` ("deadline exceeded"), instead of an error.
+//! Attempts cut short by the deadline don't count as failures of the provider.
+//!
+//! Once `reset_after` has passed, the circuit is half-open: the next lookup is a
+//! single trial attempt, while other lookups keep falling back. If it succeeds, the
+//! circuit closes; if it fails, the circuit opens again.
+//!
+//! Timeouts and backoff sleep on a `Timer`. `ThreadTimer` works with any runtime, on
+//! a single timer thread; implement `Timer` with the runtime's sleep (e.g.
+//! `tokio::time::sleep`) to use the runtime's timer instead.
+
+use std::collections::BTreeMap;
+use std::future::{poll_fn, Future};
+use std::pin::{pin, Pin};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Condvar, Mutex, MutexGuard, OnceLock};
+use std::task::{Context, Poll, Waker};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crate::async_provider::{AsyncRateProvider, Prefetched};
+use crate::calculation::add_amounts;
+use crate::calculator::{TaxQuote, TaxRequest};
+use crate::confidence::Confidence;
+use crate::currency::CurrencyCode;
+use crate::errors::DatabaseError;
+use crate::profile::CalculationProfile;
+use crate::types::{Country, TaxRate, TradeAgreement, VatRate};
+use crate::{ProcessingError, TaxDatabase, Warning};
+
+/// Sleeps asynchronously.
+pub trait Timer {
+    /// Completes after the duration.
+    fn sleep(&self, duration: Duration) -> impl Future<Output = ()> + Send;
+}
+
+/// Sleeps on a single timer thread shared by all sleeps, which wakes each task when
+/// its duration has passed; works with any runtime. Sleeps that are dropped before
+/// they complete, like the timeout of a lookup that answered in time, are cancelled.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ThreadTimer;
+
+impl Timer for ThreadTimer {
+    fn sleep(&self, duration: Duration) -> impl Future<Output = ()> + Send {
+        Sleep {
+            deadline: Instant::now() + duration,
+            key: None,
+        }
+    }
+}
+
+/// The pending sleeps of `ThreadTimer`, by deadline, with the waker of their task.
+#[derive(Default)]
+struct Sleeps {
+    pending: BTreeMap<(Instant, u64), Option<Waker>>,
+    next_id: u64,
+}
+
+/// The sleeps, and the condition the timer thread waits on for an earlier deadline.
+fn sleeps() -> &'static (Mutex<Sleeps>, Condvar) {
+    static SLEEPS: OnceLock<(Mutex<Sleeps>, Condvar)> = OnceLock::new();
+    SLEEPS.get_or_init(|| {
+        thread::spawn(wake_sleeps);
+        (Mutex::new(Sleeps::default()), Condvar::new())
+    })
+}
+
+/// Wakes the tasks of sleeps that are due; runs on the timer thread.
+fn wake_sleeps() {
+    let (sleeps, due) = sleeps();
+    let mut guard = lock(sleeps);
+    loop {
+        let now = Instant::now();
+        guard = match guard.pending.first_key_value().map(|(key, _)| *key) {
+            None => due.wait(guard).unwrap_or_else(|err| err.into_inner()),
+            Some(key) if key.0 <= now => {
+                if let Some(Some(waker)) = guard.pending.remove(&key) {
+                    waker.wake();
+                }
+                guard
+            }
+            Some((deadline, _)) => {
+                due.wait_timeout(guard, deadline - now)
+                    .unwrap_or_else(|err| err.into_inner())
+                    .0
+            }
+        };
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(|err| err.into_inner())
+}
+
+/// A sleep of `ThreadTimer`; scheduled when first polled, complete once the timer
+/// thread removed it.
+struct Sleep {
+    deadline: Instant,
+    key: Option<(Instant, u64)>,
+}
+
+impl Future for Sleep {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, context: &mut Context<'_>) -> Poll<()> {
+        let (sleeps, due) = sleeps();
+        let mut guard = lock(sleeps);
+        match self.key {
+            Some(key) => match guard.pending.get_mut(&key) {
+                Some(waker) => {
+                    *waker = Some(context.waker().clone());
+                    Poll::Pending
+                }
+                None => Poll::Ready(()),
+            },
+            None if self.deadline <= Instant::now() => Poll::Ready(()),
+            None => {
+                let key = (self.deadline, guard.next_id);
+                guard.next_id += 1;
+                guard.pending.insert(key, Some(context.waker().clone()));
+                if guard.pending.first_key_value().map(|(first, _)| *first) == Some(key) {
+                    due.notify_one();
+                }
+                self.key = Some(key);
+                Poll::Pending
+            }
+        }
+    }
+}
+
+impl Drop for Sleep {
+    fn drop(&mut self) {
+        if let Some(key) = self.key {
+            lock(&sleeps().0).pending.remove(&key);
+        }
+    }
+}
+
+/// The number of sleeps `ThreadTimer` has scheduled that end after the instant.
+#[cfg(test)]
+pub(crate) fn sleeps_after(instant: Instant) -> usize {
+    lock(&sleeps().0)
+        .pending
+        .keys()
+        .filter(|(deadline, _)| *deadline > instant)
+        .count()
+}
+
+/// How lookups of the remote provider are retried.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    /// Attempts per lookup, including the first
+    pub max_attempts: u32,
+    /// Backoff before the second attempt; doubled for each further attempt
+    pub initial_backoff: Duration,
+    /// Upper bound of the backoff
+    pub max_backoff: Duration,
+    /// How long an attempt may take before it counts as failed
+    pub timeout: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(50),
+            max_backoff: Duration::from_secs(1),
+            timeout: Duration::from_secs(2),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// The backoff after a failed attempt (1 for the first).
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+}
+
+/// When the circuit to the remote provider opens, and for how long; after that, a
+/// single trial lookup decides whether it closes.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CircuitBreaker {
+    /// Consecutive failed attempts that open the circuit
+    pub failure_threshold: u32,
+    /// How long the circuit stays open before the remote provider is tried again
+    pub reset_after: Duration,
+}
+
+impl Default for CircuitBreaker {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 5,
+            reset_after: Duration::from_secs(30),
+        }
+    }
+}
+
+/// The state of the circuit.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Circuit {
+    /// Lookups go to the remote provider
+    Closed { consecutive_failures: u32 },
+    /// Lookups fall back until the instant; the first lookup after it is a trial
+    Open { until: Instant },
+    /// A trial lookup is in flight; other lookups fall back
+    HalfOpen,
+}
+
+impl Default for Circuit {
+    fn default() -> Self {
+        Circuit::Closed {
+            consecutive_failures: 0,
+        }
+    }
+}
+
+/// Opens the circuit again if a trial lookup ends without an outcome (cut short by a
+/// deadline, or dropped), so the next lookup is a trial.
+struct Trial<'a>(&'a Mutex<Circuit>);
+
+impl Drop for Trial<'_> {
+    fn drop(&mut self) {
+        let mut circuit = lock(self.0);
+        if *circuit == Circuit::HalfOpen {
+            *circuit = Circuit::Open {
+                until: Instant::now(),
+            };
+        }
+    }
+}
+
+/// An `AsyncRateProvider` that retries lookups of a remote provider, and falls back
+/// to the embedded dataset when it is unavailable.
+///
+/// # Examples
+///
+/// ```
+/// # use std::time::Duration;
+/// # use world_tax::calculator::TaxRequest;
+/// # use world_tax::resilience::{ResilientProvider, RetryPolicy};
+/// # use world_tax::{Region, TaxDatabase, TaxScenario, TransactionType};
+/// # async fn checkout(remote: TaxDatabase) {
+/// let provider = ResilientProvider::new(remote, TaxDatabase::new().unwrap()).with_retry(
+///     RetryPolicy {
+///         timeout: Duration::from_millis(300),
+///         ..Default::default()
+///     },
+/// );
+///
+/// let scenario = TaxScenario::new(
+///     Region::parse("DE").unwrap(),
+///     Region::parse("FR").unwrap(),
+///     TransactionType::B2C,
+/// );
+/// let quote = provider.quote(TaxRequest::new(scenario, 100.0)).await.unwrap();
+/// # }
+/// ```
+pub struct ResilientProvider<P, T = ThreadTimer> {
+    remote: P,
+    fallback: TaxDatabase,
+    retry: RetryPolicy,
+    breaker: CircuitBreaker,
+    timer: T,
+    circuit: Mutex<Circuit>,
+}
+
+impl<P: AsyncRateProvider + Sync> ResilientProvider<P> {
+    /// Wraps a remote provider, falling back to the given dataset (usually
+    /// `TaxDatabase::new`); with the default retry policy and circuit breaker.
+    pub fn new(remote: P, fallback: TaxDatabase) -> Self {
+        Self {
+            remote,
+            fallback,
+            retry: RetryPolicy::default(),
+            breaker: CircuitBreaker::default(),
+            timer: ThreadTimer,
+            circuit: Mutex::new(Circuit::default()),
+        }
+    }
+}
+
+impl<P: AsyncRateProvider + Sync, T: Timer + Sync> ResilientProvider<P, T> {
+    /// Sets how lookups are retried.
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Sets when the circuit opens.
+    pub fn with_circuit_breaker(mut self, breaker: CircuitBreaker) -> Self {
+        self.breaker = breaker;
+        self
+    }
+
+    /// Sleeps on another timer.
+    pub fn with_timer<U: Timer + Sync>(self, timer: U) -> ResilientProvider<P, U> {
+        ResilientProvider {
+            remote: self.remote,
+            fallback: self.fallback,
+            retry: self.retry,
+            breaker: self.breaker,
+            timer,
+            circuit: self.circuit,
+        }
+    }
+
+    /// Returns true if the circuit is open, and lookups are answered from the
+    /// embedded dataset; also while a trial lookup of the half-open circuit is in
+    /// flight.
+    pub fn is_open(&self) -> bool {
+        match *lock(&self.circuit) {
+            Circuit::Closed { .. } => false,
+            Circuit::Open { until } => Instant::now() < until,
+            Circuit::HalfOpen => true,
+        }
+    }
+
+    /// Returns true if the circuit is half-open: `reset_after` has passed since it
+    /// opened, and the next lookup is (or is already) a trial.
+    pub fn is_half_open(&self) -> bool {
+        match *lock(&self.circuit) {
+            Circuit::Closed { .. } => false,
+            Circuit::Open { until } => until <= Instant::now(),
+            Circuit::HalfOpen => true,
+        }
+    }
+
+    /// Calculates a tax quote; see `TaxCalculator::quote`. If any lookup fell back
+    /// to the embedded dataset, the quote warns with `Warning::StaleRates`.
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of `TaxCalculator::quote`, and of the provider's lookups.
+    pub async fn quote(&self, request: TaxRequest) -> Result<TaxQuote, ProcessingError> {
+        self.quote_by(request, None).await
+    }
+
+    /// Calculates a tax quote within a time budget; see `quote`. Lookups that would
+    /// exceed the budget are answered from the embedded dataset, and the quote warns
+    /// with `Warning::StaleRates` ("deadline exceeded").
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::time::Duration;
+    /// # use world_tax::calculator::TaxRequest;
+    /// # use world_tax::resilience::ResilientProvider;
+    /// # use world_tax::{Region, TaxDatabase, TaxScenario, TransactionType};
+    /// # async fn checkout(provider: ResilientProvider<TaxDatabase>, scenario: TaxScenario) {
+    /// let quote = provider
+    ///     .quote_within(TaxRequest::new(scenario, 100.0), Duration::from_millis(150))
+    ///     .await
+    ///     .unwrap();
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns the errors of `quote`; exceeding the budget isn't one.
+    pub async fn quote_within(
+        &self,
+        request: TaxRequest,
+        budget: Duration,
+    ) -> Result<TaxQuote, ProcessingError> {
+        self.quote_by(request, Some(Instant::now() + budget)).await
+    }
+
+    async fn quote_by(
+        &self,
+        request: TaxRequest,
+        deadline: Option<Instant>,
+    ) -> Result<TaxQuote, ProcessingError> {
+        let lookups = Lookups {
+            provider: self,
+            budget: Budget::until(deadline),
+        };
+        let scenario = &request.scenario;
+        let prefetched = Prefetched::fetch(scenario, &lookups).await?;
+        let calculation_type = scenario.determine_calculation_type(&prefetched, request.amount)?;
+        let rates = scenario.get_rates(request.amount, &prefetched)?;
+        let tax = scenario.sum_rates(request.amount, &rates, &prefetched)?;
+        let mut warnings = scenario.warnings(&calculation_type, &rates, &self.fallback);
+        if lookups.budget.fell_back.load(Ordering::Relaxed) {
+            warnings.push(Warning::StaleRates {
+                reason: if lookups.budget.exceeded.load(Ordering::Relaxed) {
+                    "deadline exceeded".to_string()
+                } else if self.is_open() {
+                    "circuit open".to_string()
+                } else {
+                    "lookup failed".to_string()
+                },
+            });
+        }
+        let confidence = Confidence::from_warnings(&warnings);
+        let qualified_invoice = scenario.qualified_invoice(&calculation_type, tax != 0.0);
+        let self_assessment =
+            scenario.self_assessment(&calculation_type, tax, request.amount, &prefetched);
+
+        Ok(TaxQuote {
+            calculation_type,
+            rates,
+            amount: request.amount,
+            tax,
+            total: add_amounts(request.amount, tax)?,
+            warnings,
+            confidence,
+            e_invoicing: scenario.e_invoicing_mandates(),
+            qualified_invoice,
+            self_assessment,
+        })
+    }
+
+    /// Looks up with the remote provider, retrying failed attempts, or with the
+    /// embedded dataset if the circuit is open, all attempts failed, or the budget
+    /// ran out.
+    async fn call<R, F, Fut>(
+        &self,
+        lookup: F,
+        failed: impl Fn(&R) -> bool + Send,
+        fallback: impl FnOnce(&TaxDatabase) -> R + Send,
+        budget: &Budget,
+    ) -> R
+    where
+        F: Fn() -> Fut + Send,
+        Fut: Future<Output = R> + Send,
+    {
+        if let Some(trial) = self.admit() {
+            let _trial = trial.then(|| Trial(&self.circuit));
+            // A trial is a single attempt
+            let max_attempts = if trial { 1 } else { self.retry.max_attempts };
+            for attempt in 1..=max_attempts {
+                let Some(remaining) = budget.remaining() else {
+                    budget.exceeded.store(true, Ordering::Relaxed);
+                    break;
+                };
+                let limit = self.retry.timeout.min(remaining);
+                let result = timeout(&self.timer, limit, lookup()).await;
+                match result {
+                    Some(result) if !failed(&result) => {
+                        self.record(true);
+                        return result;
+                    }
+                    // Cut short by the deadline, not the provider's failure
+                    None if limit < self.retry.timeout => {
+                        budget.exceeded.store(true, Ordering::Relaxed);
+                        break;
+                    }
+                    _ => self.record(false),
+                }
+                if attempt == max_attempts || self.is_open() {
+                    break;
+                }
+                let backoff = self.retry.backoff(attempt);
+                if budget
+                    .remaining()
+                    .is_none_or(|remaining| remaining <= backoff)
+                {
+                    budget.exceeded.store(true, Ordering::Relaxed);
+                    break;
+                }
+                self.timer.sleep(backoff).await;
+            }
+        }
+        budget.fell_back.store(true, Ordering::Relaxed);
+        fallback(&self.fallback)
+    }
+
+    /// Whether a lookup may call the remote provider: `Some(true)` if it is the trial
+    /// of the half-open circuit, `None` if it has to fall back.
+    fn admit(&self) -> Option<bool> {
+        let mut circuit = lock(&self.circuit);
+        match *circuit {
+            Circuit::Closed { .. } => Some(false),
+            Circuit::Open { until } if Instant::now() < until => None,
+            Circuit::Open { .. } => {
+                *circuit = Circuit::HalfOpen;
+                Some(true)
+            }
+            Circuit::HalfOpen => None,
+        }
+    }
+
+    /// Records the outcome of an attempt: a success closes the circuit; it opens after
+    /// too many consecutive failures, or a failed trial.
+    fn record(&self, succeeded: bool) {
+        let mut circuit = lock(&self.circuit);
+        let open = Circuit::Open {
+            until: Instant::now() + self.breaker.reset_after,
+        };
+        *circuit = match *circuit {
+            _ if succeeded => Circuit::default(),
+            Circuit::Closed {
+                consecutive_failures,
+            } if consecutive_failures + 1 >= self.breaker.failure_threshold => open,
+            Circuit::Closed {
+                consecutive_failures,
+            } => Circuit::Closed {
+                consecutive_failures: consecutive_failures + 1,
+            },
+            Circuit::HalfOpen => open,
+            // An attempt that started before the circuit opened
+            Circuit::Open { until } => Circuit::Open { until },
+        };
+    }
+}
+
+/// Returns true if a lookup failed because the remote provider is unavailable.
+fn unavailable<R>(result: &Result<R, DatabaseError>) -> bool {
+    matches!(result, Err(DatabaseError::ProviderUnavailable(_)))
+}
+
+/// Runs a future, or gives up on it after the duration.
+async fn timeout<R>(
+    timer: &impl Timer,
+    duration: Duration,
+    future: impl Future<Output = R>,
+) -> Option<R> {
+    let mut future = pin!(future);
+    let mut sleep = pin!(timer.sleep(duration));
+    poll_fn(|context| {
+        if let Poll::Ready(output) = future.as_mut().poll(context) {
+            return Poll::Ready(Some(output));
+        }
+        if sleep.as_mut().poll(context).is_ready() {
+            return Poll::Ready(None);
+        }
+        Poll::Pending
+    })
+    .await
+}
+
+/// The deadline of a single request, noting whether any lookup fell back, and
+/// whether the deadline was why.
+struct Budget {
+    deadline: Option<Instant>,
+    fell_back: AtomicBool,
+    exceeded: AtomicBool,
+}
+
+impl Budget {
+    fn until(deadline: Option<Instant>) -> Self {
+        Self {
+            deadline,
+            fell_back: AtomicBool::new(false),
+            exceeded: AtomicBool::new(false),
+        }
+    }
+
+    /// The time left before the deadline, unlimited without one; `None` once it has
+    /// passed.
+    fn remaining(&self) -> Option<Duration> {
+        match self.deadline {
+            Some(deadline) => deadline
+                .checked_duration_since(Instant::now())
+                .filter(|remaining| !remaining.is_zero()),
+            None => Some(Duration::MAX),
+        }
+    }
+}
+
+/// The lookups of a single request.
+struct Lookups<'a, P, T> {
+    provider: &'a ResilientProvider<P, T>,
+    budget: Budget,
+}
+
+impl<P: AsyncRateProvider + Sync, T: Timer + Sync> AsyncRateProvider for Lookups<'_, P, T> {
+    async fn get_country(&self, code: &str) -> Result<Country, DatabaseError> {
+        let remote = &self.provider.remote;
+        self.provider
+            .call(
+                || remote.get_country(code),
+                unavailable,
+                |db| db.country(code).cloned(),
+                &self.budget,
+            )
+            .await
+    }
+
+    async fn get_rate(
+        &self,
+        country: &str,
+        region: Option<&str>,
+        vat_rate: Option<&VatRate>,
+    ) -> Result<Vec<TaxRate>, DatabaseError> {
+        let remote = &self.provider.remote;
+        self.provider
+            .call(
+                || remote.get_rate(country, region, vat_rate),
+                unavailable,
+                |db| db.get_rate(country, region, vat_rate),
+                &self.budget,
+            )
+            .await
+    }
+
+    async fn get_rule(&self, rule_id: &str) -> Result<TradeAgreement, DatabaseError> {
+        let remote = &self.provider.remote;
+        self.provider
+            .call(
+                || remote.get_rule(rule_id),
+                unavailable,
+                |db| db.get_rule(rule_id),
+                &self.budget,
+            )
+            .await
+    }
+
+    async fn get_federal_rule(&self, country: &str) -> Option<TradeAgreement> {
+        let remote = &self.provider.remote;
+        self.provider
+            .call(
+                || remote.get_federal_rule(country),
+                |_| false,
+                |db| db.get_federal_rule(country),
+                &self.budget,
+            )
+            .await
+    }
+
+    async fn get_international_rule(&self, source: &str, dest: &str) -> Option<TradeAgreement> {
+        let remote = &self.provider.remote;
+        self.provider
+            .call(
+                || remote.get_international_rule(source, dest),
+                |_| false,
+                |db| db.get_international_rule(source, dest),
+                &self.budget,
+            )
+            .await
+    }
+
+    async fn get_profile(&self, name: &str) -> Result<CalculationProfile, DatabaseError> {
+        // Profiles are configuration of the application, not remote data
+        self.provider.fallback.get_profile(name).copied()
+    }
+
+    async fn exchange_rate(&self, from: CurrencyCode, to: CurrencyCode) -> Option<f64> {
+        let remote = &self.provider.remote;
+        self.provider
+            .call(
+                || remote.exchange_rate(from, to),
+                |_| false,
+                |db| db.exchange_rate(from, to),
+                &self.budget,
+            )
+            .await
+    }
+}
+
+impl<P: AsyncRateProvider + Sync, T: Timer + Sync> AsyncRateProvider for ResilientProvider<P, T> {
+    async fn get_country(&self, code: &str) -> Result<Country, DatabaseError> {
+        self.lookups().get_country(code).await
+    }
+
+    async fn get_rate(
+        &self,
+        country: &str,
+        region: Option<&str>,
+        vat_rate: Option<&VatRate>,
+    ) -> Result<Vec<TaxRate>, DatabaseError> {
+        self.lookups().get_rate(country, region, vat_rate).await
+    }
+
+    async fn get_rule(&self, rule_id: &str) -> Result<TradeAgreement, DatabaseError> {
+        self.lookups().get_rule(rule_id).await
+    }
+
+    async fn get_federal_rule(&self, country: &str) -> Option<TradeAgreement> {
+        self.lookups().get_federal_rule(country).await
+    }
+
+    async fn get_international_rule(&self, source: &str, dest: &str) -> Option<TradeAgreement> {
+        self.lookups().get_international_rule(source, dest).await
+    }
+
+    async fn get_profile(&self, name: &str) -> Result<CalculationProfile, DatabaseError> {
+        self.lookups().get_profile(name).await
+    }
+
+    async fn exchange_rate(&self, from: CurrencyCode, to: CurrencyCode) -> Option<f64> {
+        self.lookups().exchange_rate(from, to).await
+    }
+}
+
+impl<P, T> ResilientProvider<P, T> {
+    fn lookups(&self) -> Lookups<'_, P, T> {
+        Lookups {
+            provider: self,
+            budget: Budget::until(None),
+        }
+    }
+}
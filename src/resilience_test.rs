@@ -0,0 +1,318 @@
+#[cfg(all(test, feature = "async"))]
+mod tests {
+    use std::future::{pending, Future};
+    use std::pin::pin;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::task::{Context, Poll, Wake, Waker};
+    use std::thread::{self, Thread};
+    use std::time::{Duration, Instant};
+
+    use crate::async_provider::AsyncRateProvider;
+    use crate::calculator::TaxRequest;
+    use crate::resilience::{
+        sleeps_after, CircuitBreaker, ResilientProvider, RetryPolicy, ThreadTimer, Timer,
+    };
+    use crate::types::{Country, TradeAgreement};
+    use crate::{
+        DatabaseError, Region, TaxDatabase, TaxRate, TaxScenario, TransactionType, VatRate, Warning,
+    };
+
+    struct ThreadWaker(Thread);
+
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+        let mut context = Context::from_waker(&waker);
+        let mut future = pin!(future);
+        loop {
+            match future.as_mut().poll(&mut context) {
+                Poll::Ready(output) => return output,
+                Poll::Pending => thread::park(),
+            }
+        }
+    }
+
+    #[derive(Clone, Copy, PartialEq)]
+    enum Health {
+        Up,
+        Down,
+        Hanging,
+    }
+
+    /// A rates service that is up, down, or never answers; serves 20% German VAT
+    /// when up, to tell its rates from the embedded dataset's.
+    struct Remote {
+        db: TaxDatabase,
+        health: Arc<Mutex<Health>>,
+        attempts: Arc<AtomicUsize>,
+    }
+
+    impl Remote {
+        fn new(health: Health, attempts: Arc<AtomicUsize>) -> Self {
+            Self::with_health(Arc::new(Mutex::new(health)), attempts)
+        }
+
+        /// A remote whose health can change.
+        fn with_health(health: Arc<Mutex<Health>>, attempts: Arc<AtomicUsize>) -> Self {
+            let countries = include_str!("../fixtures/vat_rates.json").replacen(
+                "\"standard_rate\": 0.19",
+                "\"standard_rate\": 0.2",
+                1,
+            );
+            let trade_agreements = include_str!("../fixtures/trade_agreements.json");
+            Self {
+                db: TaxDatabase::from_json(&countries, trade_agreements).unwrap(),
+                health,
+                attempts,
+            }
+        }
+
+        async fn lookup(&self) -> Result<(), DatabaseError> {
+            self.attempts.fetch_add(1, Ordering::Relaxed);
+            let health = *self.health.lock().unwrap();
+            match health {
+                Health::Up => Ok(()),
+                Health::Down => Err(DatabaseError::ProviderUnavailable("503".to_string())),
+                Health::Hanging => pending().await,
+            }
+        }
+    }
+
+    impl AsyncRateProvider for Remote {
+        async fn get_country(&self, code: &str) -> Result<Country, DatabaseError> {
+            self.lookup().await?;
+            self.db.country(code).cloned()
+        }
+
+        async fn get_rate(
+            &self,
+            country: &str,
+            region: Option<&str>,
+            vat_rate: Option<&VatRate>,
+        ) -> Result<Vec<TaxRate>, DatabaseError> {
+            self.lookup().await?;
+            self.db.get_rate(country, region, vat_rate)
+        }
+
+        async fn get_rule(&self, rule_id: &str) -> Result<TradeAgreement, DatabaseError> {
+            self.lookup().await?;
+            self.db.get_rule(rule_id)
+        }
+
+        async fn get_federal_rule(&self, country: &str) -> Option<TradeAgreement> {
+            self.lookup().await.ok()?;
+            self.db.get_federal_rule(country)
+        }
+
+        async fn get_international_rule(&self, source: &str, dest: &str) -> Option<TradeAgreement> {
+            self.lookup().await.ok()?;
+            self.db.get_international_rule(source, dest)
+        }
+    }
+
+    fn provider(health: Health) -> ResilientProvider<Remote> {
+        provider_counting(health, Arc::default())
+    }
+
+    fn provider_counting(health: Health, attempts: Arc<AtomicUsize>) -> ResilientProvider<Remote> {
+        ResilientProvider::new(Remote::new(health, attempts), TaxDatabase::fixture())
+            .with_retry(RetryPolicy {
+                max_attempts: 2,
+                initial_backoff: Duration::from_millis(1),
+                max_backoff: Duration::from_millis(2),
+                timeout: Duration::from_millis(20),
+            })
+            .with_circuit_breaker(CircuitBreaker {
+                failure_threshold: 4,
+                reset_after: Duration::from_secs(60),
+            })
+    }
+
+    fn request() -> TaxRequest {
+        let scenario = TaxScenario::new(
+            Region::parse("DE").unwrap(),
+            Region::parse("DE").unwrap(),
+            TransactionType::B2C,
+        );
+        TaxRequest::new(scenario, 100.0)
+    }
+
+    #[test]
+    fn test_backoff() {
+        let retry = RetryPolicy {
+            max_attempts: 5,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_millis(300),
+            timeout: Duration::from_secs(1),
+        };
+        assert_eq!(retry.backoff(1), Duration::from_millis(100));
+        assert_eq!(retry.backoff(2), Duration::from_millis(200));
+        assert_eq!(retry.backoff(3), Duration::from_millis(300));
+        assert_eq!(retry.backoff(40), Duration::from_millis(300));
+    }
+
+    #[test]
+    fn test_remote_up() {
+        let provider = provider(Health::Up);
+        let quote = block_on(provider.quote(request())).unwrap();
+        assert_eq!(quote.tax, 20.0);
+        assert!(quote.warnings.is_empty());
+        assert!(!provider.is_open());
+    }
+
+    #[test]
+    fn test_remote_down_falls_back_and_opens_circuit() {
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let provider = provider_counting(Health::Down, Arc::clone(&attempts));
+        let quote = block_on(provider.quote(request())).unwrap();
+        // The fixture's German VAT
+        assert_eq!(quote.tax, 19.0);
+        assert!(quote
+            .warnings
+            .iter()
+            .any(|warning| matches!(warning, Warning::StaleRates { .. })));
+        assert!(provider.is_open());
+
+        // With the circuit open, the remote isn't called
+        let before = attempts.load(Ordering::Relaxed);
+        let quote = block_on(provider.quote(request())).unwrap();
+        assert_eq!(quote.tax, 19.0);
+        assert_eq!(attempts.load(Ordering::Relaxed), before);
+        assert!(quote.warnings.contains(&Warning::StaleRates {
+            reason: "circuit open".to_string()
+        }));
+    }
+
+    #[test]
+    fn test_hanging_remote_times_out() {
+        let provider = provider(Health::Hanging);
+        let tax = block_on(provider.quote(request())).unwrap().tax;
+        assert_eq!(tax, 19.0);
+    }
+
+    #[test]
+    fn test_deadline() {
+        let provider = ResilientProvider::new(
+            Remote::new(Health::Hanging, Arc::default()),
+            TaxDatabase::fixture(),
+        )
+        .with_retry(RetryPolicy {
+            timeout: Duration::from_secs(5),
+            ..Default::default()
+        })
+        .with_circuit_breaker(CircuitBreaker {
+            failure_threshold: 1,
+            reset_after: Duration::from_secs(60),
+        });
+
+        let started = Instant::now();
+        let quote = block_on(provider.quote_within(request(), Duration::from_millis(30))).unwrap();
+        assert!(started.elapsed() < Duration::from_secs(5));
+        // An estimate from the fixture's German VAT
+        assert_eq!(quote.tax, 19.0);
+        assert_eq!(
+            quote.warnings,
+            vec![Warning::StaleRates {
+                reason: "deadline exceeded".to_string()
+            }]
+        );
+        // Attempts cut short by the deadline aren't failures of the provider
+        assert!(!provider.is_open());
+
+        // Within the budget, the remote's rates
+        let provider = provider_counting(Health::Up, Arc::default());
+        let quote = block_on(provider.quote_within(request(), Duration::from_secs(5))).unwrap();
+        assert_eq!(quote.tax, 20.0);
+        assert!(quote.warnings.is_empty());
+    }
+
+    #[test]
+    fn test_half_open_trial() {
+        let health = Arc::new(Mutex::new(Health::Down));
+        let attempts = Arc::new(AtomicUsize::new(0));
+        let provider = ResilientProvider::new(
+            Remote::with_health(Arc::clone(&health), Arc::clone(&attempts)),
+            TaxDatabase::fixture(),
+        )
+        .with_retry(RetryPolicy {
+            max_attempts: 2,
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(2),
+            timeout: Duration::from_millis(20),
+        })
+        .with_circuit_breaker(CircuitBreaker {
+            failure_threshold: 4,
+            reset_after: Duration::from_millis(30),
+        });
+        assert_eq!(block_on(provider.quote(request())).unwrap().tax, 19.0);
+        assert!(provider.is_open());
+        assert!(!provider.is_half_open());
+
+        // A failed trial is a single attempt, and opens the circuit again
+        thread::sleep(Duration::from_millis(40));
+        assert!(provider.is_half_open());
+        assert!(!provider.is_open());
+        let before = attempts.load(Ordering::Relaxed);
+        assert_eq!(block_on(provider.quote(request())).unwrap().tax, 19.0);
+        assert_eq!(attempts.load(Ordering::Relaxed), before + 1);
+        assert!(provider.is_open());
+        assert!(!provider.is_half_open());
+
+        // A successful trial closes it
+        *health.lock().unwrap() = Health::Up;
+        thread::sleep(Duration::from_millis(40));
+        let quote = block_on(provider.quote(request())).unwrap();
+        assert_eq!(quote.tax, 20.0);
+        assert!(quote.warnings.is_empty());
+        assert!(!provider.is_open());
+        assert!(!provider.is_half_open());
+    }
+
+    #[test]
+    fn test_thread_timer() {
+        let started = Instant::now();
+        block_on(ThreadTimer.sleep(Duration::from_millis(20)));
+        assert!(started.elapsed() >= Duration::from_millis(20));
+
+        // Sleeps dropped before they complete, like the timeouts of lookups that
+        // answered in time, are cancelled
+        let waker = Waker::from(Arc::new(ThreadWaker(thread::current())));
+        let mut context = Context::from_waker(&waker);
+        for _ in 0..100 {
+            let mut sleep = pin!(ThreadTimer.sleep(Duration::from_secs(600)));
+            assert!(sleep.as_mut().poll(&mut context).is_pending());
+        }
+        assert_eq!(sleeps_after(started + Duration::from_secs(300)), 0);
+    }
+
+    /// Completes immediately, and counts the sleeps.
+    #[derive(Default)]
+    struct NoWait {
+        sleeps: AtomicUsize,
+    }
+
+    impl Timer for Arc<NoWait> {
+        async fn sleep(&self, _duration: Duration) {
+            self.sleeps.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    #[test]
+    fn test_custom_timer_and_trait_use() {
+        let timer = Arc::new(NoWait::default());
+        let provider = provider(Health::Down).with_timer(Arc::clone(&timer));
+        let scenario = request().scenario;
+        // Without a quote, lookups fall back silently
+        assert_eq!(
+            block_on(scenario.calculate_tax_async(100.0, &provider)).unwrap(),
+            19.0
+        );
+        assert!(timer.sleeps.load(Ordering::Relaxed) > 0);
+    }
+}
@@ -0,0 +1,217 @@
+//! Returned goods.
+//!
+//! When a customer returns goods (an RMA flow), the original sale is reversed with
+//! a credit note. If the goods cross a border on their way back, the seller imports
+//! them; goods re-imported unaltered within three years of their export qualify for
+//! returned goods relief, and no duty or import VAT is due.
+
+use serde::Serialize;
+
+use crate::calculator::{TaxQuote, TaxRequest};
+use crate::certificate::is_iso_date;
+use crate::customs::{landed_cost, CustomsItem, LandedCost};
+use crate::self_assessment::SelfAssessment;
+use crate::types::Incoterm;
+use crate::{InputValidationError, ProcessingError, TaxDatabase, TaxScenario, TransactionType};
+
+/// Years after export within which re-imported goods qualify for relief.
+pub const RELIEF_TIME_LIMIT_YEARS: u32 = 3;
+
+/// The conditions of returned goods relief for a re-import.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ReturnedGoodsRelief {
+    /// Date the goods were exported (ISO 8601)
+    pub exported: String,
+    /// Date the goods are re-imported (ISO 8601)
+    pub reimported: String,
+    /// Whether the goods are returned in the state they were exported in
+    pub unaltered: bool,
+}
+
+impl ReturnedGoodsRelief {
+    /// Creates the conditions of a re-import.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InputValidationError::InvalidDate` if a date isn't an ISO 8601 date (YYYY-MM-DD).
+    pub fn new(
+        exported: &str,
+        reimported: &str,
+        unaltered: bool,
+    ) -> Result<Self, InputValidationError> {
+        if let Some(date) = [exported, reimported]
+            .into_iter()
+            .find(|date| !is_iso_date(date))
+        {
+            return Err(InputValidationError::InvalidDate(date.to_string()));
+        }
+        Ok(Self {
+            exported: exported.to_string(),
+            reimported: reimported.to_string(),
+            unaltered,
+        })
+    }
+
+    /// The last day of relief: the same day `RELIEF_TIME_LIMIT_YEARS` after the export.
+    /// Dates compare as text, so goods exported on 29 February have until 28 February.
+    pub fn last_day(&self) -> String {
+        let year = self
+            .exported
+            .get(..4)
+            .and_then(|year| year.parse::<u32>().ok())
+            .unwrap_or_default();
+        format!(
+            "{:04}{}",
+            year + RELIEF_TIME_LIMIT_YEARS,
+            self.exported.get(4..).unwrap_or_default()
+        )
+    }
+
+    /// Returns true if the re-import qualifies for relief.
+    pub fn applies(&self) -> bool {
+        self.unaltered && self.reimported <= self.last_day()
+    }
+}
+
+/// Goods returned by the customer of an earlier sale.
+#[derive(Debug, Clone)]
+pub struct GoodsReturn {
+    /// The original sale
+    pub original: TaxRequest,
+    /// The quote of the original sale, with the tax that was charged
+    pub quote: TaxQuote,
+    /// The returned items, for import charges
+    pub items: Vec<CustomsItem>,
+    /// Relief claimed for the re-import, if any
+    pub relief: Option<ReturnedGoodsRelief>,
+}
+
+/// The tax consequences of a return.
+#[derive(Debug, Serialize)]
+pub struct ReturnOutcome {
+    /// The reversal of the original sale, with negative amounts
+    pub credit_note: TaxQuote,
+    /// Whether returned goods relief was applied to the re-import
+    pub relief_applied: bool,
+    /// Import charges the seller pays on the re-import; `None` if the goods don't
+    /// cross a customs border
+    pub import: Option<LandedCost>,
+}
+
+impl TaxQuote {
+    /// Reverses the quote, for a credit note: amounts are negated, rates are kept.
+    pub fn reversal(&self) -> TaxQuote {
+        TaxQuote {
+            calculation_type: self.calculation_type.clone(),
+            rates: self.rates.clone(),
+            amount: -self.amount,
+            tax: -self.tax,
+            total: -self.total,
+            warnings: self.warnings.clone(),
+            confidence: self.confidence,
+            e_invoicing: self.e_invoicing.clone(),
+            qualified_invoice: self.qualified_invoice,
+            self_assessment: self
+                .self_assessment
+                .clone()
+                .map(|assessment| SelfAssessment {
+                    estimated_tax: assessment.estimated_tax.map(|tax| -tax),
+                    ..assessment
+                }),
+        }
+    }
+}
+
+impl GoodsReturn {
+    /// Creates a return of the original sale, quoted as `quote` at the time.
+    pub fn new(original: TaxRequest, quote: TaxQuote, items: Vec<CustomsItem>) -> Self {
+        Self {
+            original,
+            quote,
+            items,
+            relief: None,
+        }
+    }
+
+    /// Claims returned goods relief for the re-import.
+    pub fn with_relief(mut self, relief: ReturnedGoodsRelief) -> Self {
+        self.relief = Some(relief);
+        self
+    }
+
+    /// Calculates the credit note of the original sale and the import charges of the
+    /// goods shipped back to the seller. The credit note reverses the tax that was
+    /// charged, even if the rates changed since.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use world_tax::calculator::{TaxCalculator, TaxRequest};
+    /// # use world_tax::customs::CustomsItem;
+    /// # use world_tax::returns::{GoodsReturn, ReturnedGoodsRelief};
+    /// # use world_tax::{Region, TaxDatabase, TaxScenario, TransactionType};
+    /// let db = TaxDatabase::fixture();
+    /// let sale = TaxScenario::new(
+    ///     Region::new("DE".to_string(), None).unwrap(),
+    ///     Region::new("CA".to_string(), None).unwrap(),
+    ///     TransactionType::B2C,
+    /// );
+    ///
+    /// let request = TaxRequest::new(sale, 100.0);
+    /// let quote = db.quote(request.clone()).unwrap();
+    ///
+    /// let outcome = GoodsReturn::new(request, quote, vec![CustomsItem::new(100.0, 61)])
+    ///     .with_relief(ReturnedGoodsRelief::new("2026-03-02", "2026-04-01", true).unwrap())
+    ///     .process(&db)
+    ///     .unwrap();
+    /// assert_eq!(outcome.credit_note.amount, -100.0);
+    /// assert_eq!(outcome.import.unwrap().seller_pays, 0.0);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the original sale or the import charges can't be calculated.
+    pub fn process(&self, db: &TaxDatabase) -> Result<ReturnOutcome, ProcessingError> {
+        let credit_note = self.quote.reversal();
+
+        // The seller imports the goods, and pays the charges
+        let sale = &self.original.scenario;
+        let mut scenario = TaxScenario::new(
+            sale.destination_region.clone(),
+            sale.source_region.clone(),
+            TransactionType::B2B,
+        )
+        .with_incoterm(Incoterm::Ddp);
+        scenario.vat_rate = sale.vat_rate;
+        scenario.profile = sale.profile.clone();
+
+        let relief_applied = self
+            .relief
+            .as_ref()
+            .is_some_and(ReturnedGoodsRelief::applies);
+        let import = landed_cost(&scenario, &self.items, Incoterm::Ddp, db)?.map(|cost| {
+            if relief_applied {
+                LandedCost {
+                    duty: 0.0,
+                    rates: Vec::new(),
+                    import_vat: 0.0,
+                    postponed_accounting: None,
+                    postponed_import_vat: 0.0,
+                    disbursement_fee: 0.0,
+                    seller_pays: 0.0,
+                    buyer_pays_on_delivery: 0.0,
+                    total: cost.goods,
+                    ..cost
+                }
+            } else {
+                cost
+            }
+        });
+
+        Ok(ReturnOutcome {
+            credit_note,
+            relief_applied: relief_applied && import.is_some(),
+            import,
+        })
+    }
+}
@@ -0,0 +1,131 @@
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+
+    use crate::calculator::{TaxCalculator, TaxRequest};
+    use crate::customs::{CustomsItem, DutyTable};
+    use crate::returns::{GoodsReturn, ReturnedGoodsRelief};
+    use crate::{
+        InputValidationError, Percentage, Region, TaxDatabase, TaxScenario, TransactionType,
+        VatRate,
+    };
+
+    fn setup() -> TaxDatabase {
+        let mut db = TaxDatabase::fixture();
+        db.load_duty_rates("EU", DutyTable::parse("61,0.12").expect("Valid duty table"));
+        db
+    }
+
+    fn export_return(db: &TaxDatabase) -> GoodsReturn {
+        let sale = TaxScenario::new(
+            Region::parse("DE").expect("Valid region"),
+            Region::parse("CA-ON").expect("Valid region"),
+            TransactionType::B2C,
+        );
+        let request = TaxRequest::new(sale, 100.0);
+        let quote = db.quote(request.clone()).expect("Sale should be quoted");
+        GoodsReturn::new(request, quote, vec![CustomsItem::new(100.0, 61)])
+    }
+
+    #[test]
+    fn test_return_with_relief() {
+        let db = setup();
+        let outcome = export_return(&db)
+            .with_relief(ReturnedGoodsRelief::new("2026-01-15", "2026-03-16", true).unwrap())
+            .process(&db)
+            .expect("Return should be processed");
+
+        assert!(outcome.relief_applied);
+        assert_eq!(outcome.credit_note.amount, -100.0);
+        assert_eq!(outcome.credit_note.tax, 0.0); // Export was zero-rated
+        let import = outcome.import.expect("Goods are re-imported");
+        assert_eq!(import.duty, 0.0);
+        assert_eq!(import.import_vat, 0.0);
+        assert_eq!(import.seller_pays, 0.0);
+    }
+
+    #[test]
+    fn test_return_without_relief() {
+        let db = setup();
+        let expired = ReturnedGoodsRelief::new("2023-01-15", "2026-01-16", true).unwrap();
+        assert!(!expired.applies());
+
+        let outcome = export_return(&db)
+            .with_relief(expired)
+            .process(&db)
+            .expect("Return should be processed");
+        assert!(!outcome.relief_applied);
+        let import = outcome.import.expect("Goods are re-imported");
+        assert_eq!(import.duty, 12.0);
+        assert_eq!(import.import_vat, 21.28); // 19% of 112
+        assert_eq!(import.seller_pays, 33.28);
+    }
+
+    #[test]
+    fn test_domestic_return_credit_note() {
+        let db = setup();
+        let sale = TaxScenario::new(
+            Region::parse("DE").expect("Valid region"),
+            Region::parse("DE").expect("Valid region"),
+            TransactionType::B2C,
+        );
+        let request = TaxRequest::new(sale, 100.0);
+        let quote = db.quote(request.clone()).expect("Sale should be quoted");
+        let outcome = GoodsReturn::new(request, quote, Vec::new())
+            .process(&db)
+            .expect("Return should be processed");
+
+        assert_eq!(outcome.credit_note.tax, -19.0);
+        assert_eq!(outcome.credit_note.total, -119.0);
+        assert_eq!(
+            outcome.credit_note.rates[0].rate,
+            Percentage::from_fraction(dec!(0.19))
+        );
+        assert!(outcome.import.is_none());
+        assert!(!outcome.relief_applied);
+    }
+
+    #[test]
+    fn test_credit_note_after_rate_change() {
+        let mut db = setup();
+        let sale = TaxScenario::new(
+            Region::parse("DE").expect("Valid region"),
+            Region::parse("DE").expect("Valid region"),
+            TransactionType::B2C,
+        );
+        let request = TaxRequest::new(sale, 100.0);
+        let quote = db.quote(request.clone()).expect("Sale should be quoted");
+
+        // The credit note reverses the 19% charged, not the rate of the return
+        db.set_country_rate("DE", VatRate::Standard, Percentage::from_percent(dec!(16)))
+            .unwrap();
+        let outcome = GoodsReturn::new(request, quote, Vec::new())
+            .process(&db)
+            .expect("Return should be processed");
+        assert_eq!(outcome.credit_note.tax, -19.0);
+        assert_eq!(outcome.credit_note.total, -119.0);
+    }
+
+    #[test]
+    fn test_relief_time_limit() {
+        let relief = |exported, reimported| {
+            ReturnedGoodsRelief::new(exported, reimported, true)
+                .unwrap()
+                .applies()
+        };
+        // Three calendar years, whatever the leap days in between
+        assert!(relief("2024-03-01", "2027-03-01"));
+        assert!(!relief("2024-03-01", "2027-03-02"));
+        assert!(relief("2023-01-15", "2026-01-15"));
+        // Goods exported on a leap day have until 28 February
+        assert!(relief("2024-02-29", "2027-02-28"));
+        assert!(!relief("2024-02-29", "2027-03-01"));
+
+        let altered = ReturnedGoodsRelief::new("2026-01-15", "2026-02-15", false).unwrap();
+        assert!(!altered.applies());
+        assert!(matches!(
+            ReturnedGoodsRelief::new("15.01.2026", "2026-02-15", true),
+            Err(InputValidationError::InvalidDate(_))
+        ));
+    }
+}
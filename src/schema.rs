@@ -0,0 +1,287 @@
+//! JSON Schema for the tax datasets.
+//!
+//! The schemas for `vat_rates.json` and `trade_agreements.json` are embedded in the
+//! crate, so custom datasets can be checked against them before use. The schemas
+//! describe the current dataset version; migrate older data first (see
+//! `migration`). Validation
+//! supports the subset of JSON Schema the published schemas use: `type`, `enum`,
+//! `$ref` (to local `$defs`), `anyOf`, `properties`, `required`,
+//! `additionalProperties`, `patternProperties` (prefix patterns like `^x-`),
+//! `propertyNames`, `items`, `minimum`/`maximum` and `minLength`/`maxLength`.
+
+use std::fmt;
+
+use serde::Serialize;
+use serde_json::Value;
+
+/// JSON Schema of the tax rates dataset (`vat_rates.json`).
+pub const VAT_RATES_SCHEMA: &str = include_str!("../schema/vat_rates.schema.json");
+
+/// JSON Schema of the trade agreements dataset (`trade_agreements.json`).
+pub const TRADE_AGREEMENTS_SCHEMA: &str = include_str!("../schema/trade_agreements.schema.json");
+
+/// A single schema violation in a dataset.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SchemaViolation {
+    /// The dataset the violation was found in (e.g. "vat_rates")
+    pub document: String,
+    /// JSON Pointer to the offending value (e.g. "/DE/standard_rate")
+    pub pointer: String,
+    /// What's wrong with the value
+    pub message: String,
+}
+
+impl fmt::Display for SchemaViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let pointer = if self.pointer.is_empty() {
+            "/"
+        } else {
+            &self.pointer
+        };
+        write!(f, "{}{}: {}", self.document, pointer, self.message)
+    }
+}
+
+/// Validates a tax rates dataset against the embedded schema.
+///
+/// # Examples
+///
+/// ```
+/// # use world_tax::schema::validate_vat_rates;
+/// let data = serde_json::json!({ "DE": { "type": "vat", "currency": "EUR", "standard_rate": 1.9 } });
+///
+/// let violations = validate_vat_rates(&data);
+/// assert_eq!(violations[0].pointer, "/DE/standard_rate");
+/// ```
+pub fn validate_vat_rates(instance: &Value) -> Vec<SchemaViolation> {
+    validate("vat_rates", VAT_RATES_SCHEMA, instance)
+}
+
+/// Validates a trade agreements dataset against the embedded schema.
+pub fn validate_trade_agreements(instance: &Value) -> Vec<SchemaViolation> {
+    validate("trade_agreements", TRADE_AGREEMENTS_SCHEMA, instance)
+}
+
+fn validate(document: &str, schema: &str, instance: &Value) -> Vec<SchemaViolation> {
+    let root: Value = serde_json::from_str(schema).expect("Embedded schema is valid JSON");
+    let mut validator = Validator {
+        root: &root,
+        document,
+        violations: Vec::new(),
+    };
+    validator.check(&root, instance, "");
+    validator.violations
+}
+
+struct Validator<'a> {
+    root: &'a Value,
+    document: &'a str,
+    violations: Vec<SchemaViolation>,
+}
+
+impl<'a> Validator<'a> {
+    fn report(&mut self, pointer: &str, message: String) {
+        self.violations.push(SchemaViolation {
+            document: self.document.to_string(),
+            pointer: pointer.to_string(),
+            message,
+        });
+    }
+
+    fn resolve(&self, reference: &str) -> &'a Value {
+        let path = reference
+            .strip_prefix('#')
+            .expect("Embedded schema only uses local references");
+        self.root
+            .pointer(path)
+            .expect("Embedded schema references exist")
+    }
+
+    fn check(&mut self, schema: &'a Value, instance: &Value, pointer: &str) {
+        if let Some(reference) = schema.get("$ref").and_then(Value::as_str) {
+            let target = self.resolve(reference);
+            self.check(target, instance, pointer);
+        }
+
+        if let Some(options) = schema.get("anyOf").and_then(Value::as_array) {
+            let matches = options.iter().any(|option| {
+                let mut nested = Validator {
+                    root: self.root,
+                    document: self.document,
+                    violations: Vec::new(),
+                };
+                nested.check(option, instance, pointer);
+                nested.violations.is_empty()
+            });
+            if !matches {
+                self.report(
+                    pointer,
+                    format!("{} matches none of the allowed schemas", instance),
+                );
+            }
+        }
+
+        if let Some(expected) = schema.get("type") {
+            let types: Vec<&str> = match expected {
+                Value::String(name) => vec![name.as_str()],
+                Value::Array(names) => names.iter().filter_map(Value::as_str).collect(),
+                _ => Vec::new(),
+            };
+            if !types.iter().any(|name| has_type(instance, name)) {
+                self.report(
+                    pointer,
+                    format!(
+                        "expected {}, found {}",
+                        types.join(" or "),
+                        type_name(instance)
+                    ),
+                );
+                return;
+            }
+        }
+
+        if let Some(allowed) = schema.get("enum").and_then(Value::as_array) {
+            if !allowed.contains(instance) {
+                let allowed: Vec<String> = allowed.iter().map(Value::to_string).collect();
+                self.report(
+                    pointer,
+                    format!("{} is not one of {}", instance, allowed.join(", ")),
+                );
+            }
+        }
+
+        match instance {
+            Value::Number(number) => self.check_number(schema, number.as_f64(), pointer),
+            Value::String(string) => self.check_length(schema, string, pointer),
+            Value::Array(items) => {
+                if let Some(item_schema) = schema.get("items") {
+                    for (index, item) in items.iter().enumerate() {
+                        self.check(item_schema, item, &format!("{}/{}", pointer, index));
+                    }
+                }
+            }
+            Value::Object(object) => self.check_object(schema, object, pointer),
+            _ => {}
+        }
+    }
+
+    fn check_number(&mut self, schema: &Value, number: Option<f64>, pointer: &str) {
+        let Some(number) = number else { return };
+        if let Some(minimum) = schema.get("minimum").and_then(Value::as_f64) {
+            if number < minimum {
+                self.report(
+                    pointer,
+                    format!("{} is less than the minimum of {}", number, minimum),
+                );
+            }
+        }
+        if let Some(maximum) = schema.get("maximum").and_then(Value::as_f64) {
+            if number > maximum {
+                self.report(
+                    pointer,
+                    format!("{} is greater than the maximum of {}", number, maximum),
+                );
+            }
+        }
+    }
+
+    fn check_length(&mut self, schema: &Value, string: &str, pointer: &str) {
+        let length = string.chars().count() as u64;
+        if let Some(min) = schema.get("minLength").and_then(Value::as_u64) {
+            if length < min {
+                self.report(pointer, format!("\"{}\" is shorter than {}", string, min));
+            }
+        }
+        if let Some(max) = schema.get("maxLength").and_then(Value::as_u64) {
+            if length > max {
+                self.report(pointer, format!("\"{}\" is longer than {}", string, max));
+            }
+        }
+    }
+
+    fn check_object(
+        &mut self,
+        schema: &'a Value,
+        object: &serde_json::Map<String, Value>,
+        pointer: &str,
+    ) {
+        if let Some(required) = schema.get("required").and_then(Value::as_array) {
+            for name in required.iter().filter_map(Value::as_str) {
+                if !object.contains_key(name) {
+                    self.report(pointer, format!("missing required property \"{}\"", name));
+                }
+            }
+        }
+
+        let properties = schema.get("properties").and_then(Value::as_object);
+        let patterns = schema.get("patternProperties").and_then(Value::as_object);
+        let additional = schema.get("additionalProperties");
+        let property_names = schema.get("propertyNames");
+
+        for (name, value) in object {
+            let child = format!("{}/{}", pointer, escape(name));
+
+            if let Some(names_schema) = property_names {
+                self.check(names_schema, &Value::String(name.clone()), pointer);
+            }
+
+            let pattern_schema = patterns.and_then(|patterns| {
+                patterns
+                    .iter()
+                    .find(|(pattern, _)| matches_prefix(pattern, name))
+                    .map(|(_, schema)| schema)
+            });
+            match properties
+                .and_then(|properties| properties.get(name))
+                .or(pattern_schema)
+            {
+                Some(property_schema) => self.check(property_schema, value, &child),
+                None => match additional {
+                    Some(Value::Bool(false)) => {
+                        self.report(&child, format!("unknown property \"{}\"", name))
+                    }
+                    Some(additional_schema @ Value::Object(_)) => {
+                        self.check(additional_schema, value, &child)
+                    }
+                    _ => {}
+                },
+            }
+        }
+    }
+}
+
+/// Matches a `patternProperties` pattern; only prefix patterns ("^x-") are supported.
+fn matches_prefix(pattern: &str, name: &str) -> bool {
+    pattern
+        .strip_prefix('^')
+        .is_some_and(|prefix| name.starts_with(prefix))
+}
+
+fn has_type(instance: &Value, name: &str) -> bool {
+    match name {
+        "object" => instance.is_object(),
+        "array" => instance.is_array(),
+        "string" => instance.is_string(),
+        "number" => instance.is_number(),
+        "integer" => instance.is_i64() || instance.is_u64(),
+        "boolean" => instance.is_boolean(),
+        "null" => instance.is_null(),
+        _ => false,
+    }
+}
+
+fn type_name(instance: &Value) -> &'static str {
+    match instance {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+/// Escapes a property name for use in a JSON Pointer (RFC 6901).
+fn escape(name: &str) -> String {
+    name.replace('~', "~0").replace('/', "~1")
+}
@@ -0,0 +1,151 @@
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::schema::{validate_trade_agreements, validate_vat_rates};
+    use crate::{DatasetError, TaxDatabase};
+
+    #[test]
+    fn test_embedded_datasets_match_schema() {
+        let documents = [
+            (include_str!("../vat_rates.json"), true),
+            (include_str!("../trade_agreements.json"), false),
+            (include_str!("../fixtures/vat_rates.json"), true),
+            (include_str!("../fixtures/trade_agreements.json"), false),
+        ];
+        for (json, is_rates) in documents {
+            let value = serde_json::from_str(json).expect("Dataset should be valid JSON");
+            let violations = if is_rates {
+                validate_vat_rates(&value)
+            } else {
+                validate_trade_agreements(&value)
+            };
+            assert!(violations.is_empty(), "{:?}", violations);
+        }
+    }
+
+    #[test]
+    fn test_schema_violations_are_pointered() {
+        let data = json!({
+            "DE": {
+                "type": "sales",
+                "currency": "EUR",
+                "standard_rate": 0.19,
+                "states": { "DE-BY": { "standard_rate": "high", "type": "vat" } }
+            },
+            "FRA": { "type": "vat", "currency": "EUR", "standard_rate": 0.2 }
+        });
+
+        let pointers: Vec<String> = validate_vat_rates(&data)
+            .into_iter()
+            .map(|violation| violation.pointer)
+            .collect();
+        assert_eq!(
+            pointers,
+            vec!["/DE/states/DE-BY/standard_rate", "/DE/type", ""]
+        );
+    }
+
+    #[test]
+    fn test_trade_agreement_violations() {
+        let data = json!({
+            "EU": {
+                "name": "European Union",
+                "type": "customs_union",
+                "members": ["DE", 7],
+                "default_applicable": true,
+                "applies_to": { "physical_goods": true, "digital_goods": true, "services": true },
+                "tax_rules": {
+                    "internal_b2c": { "type": "threshold_based", "threshold": -1 },
+                    "external_export": { "type": "zero_rated" }
+                }
+            }
+        });
+
+        let violations = validate_trade_agreements(&data);
+        let pointers: Vec<&str> = violations.iter().map(|v| v.pointer.as_str()).collect();
+        assert_eq!(
+            pointers,
+            vec!["/EU/members/1", "/EU/tax_rules/internal_b2c"]
+        );
+    }
+
+    #[test]
+    fn test_strict_loader() {
+        let countries = include_str!("../fixtures/vat_rates.json");
+        let agreements = include_str!("../fixtures/trade_agreements.json");
+        let db = TaxDatabase::from_json_strict(countries, agreements).expect("Fixtures are valid");
+        assert_eq!(db.get_country("FR").unwrap().standard_rate().to_f64(), 0.2);
+
+        let result = TaxDatabase::from_json_strict(countries, "[]");
+        match result {
+            Err(DatasetError::SchemaViolations(violations)) => {
+                assert_eq!(violations.len(), 1);
+                assert_eq!(violations[0].document, "trade_agreements");
+                assert_eq!(
+                    violations[0].to_string(),
+                    "trade_agreements/: expected object, found array"
+                );
+            }
+            _ => panic!("Expected schema violations"),
+        }
+
+        assert!(matches!(
+            TaxDatabase::from_json_strict("{", agreements),
+            Err(DatasetError::InvalidJson(..))
+        ));
+    }
+
+    #[test]
+    fn test_extension_fields() {
+        let countries = json!({
+            "US": {
+                "type": "none",
+                "currency": "USD",
+                "standard_rate": 0.0,
+                "x-netsuite-tax-code": "US-EXEMPT",
+                "states": {
+                    "US-CA": {
+                        "standard_rate": 0.0725,
+                        "type": "vat",
+                        "x-gl-account": 2310,
+                        "gl-account": 2310
+                    }
+                }
+            }
+        });
+        let agreements = json!({
+            "XU": {
+                "name": "Example Union",
+                "type": "customs_union",
+                "members": ["US"],
+                "default_applicable": true,
+                "applies_to": { "physical_goods": true, "digital_goods": true, "services": true },
+                "tax_rules": { "external_export": { "type": "zero_rated" } },
+                "x-sap-tax-codes": { "b2b": "V0", "b2c": "V1" }
+            }
+        });
+
+        // Only fields without the prefix are unknown
+        let pointers: Vec<String> = validate_vat_rates(&countries)
+            .into_iter()
+            .map(|violation| violation.pointer)
+            .collect();
+        assert_eq!(pointers, vec!["/US/states/US-CA/gl-account"]);
+        assert!(validate_trade_agreements(&agreements).is_empty());
+
+        let db = TaxDatabase::from_json(&countries.to_string(), &agreements.to_string())
+            .expect("Extensions should load");
+        let us = db.get_country("US").unwrap();
+        assert_eq!(us.extensions()["x-netsuite-tax-code"], "US-EXEMPT");
+        let california = us.state("US-CA").unwrap();
+        assert_eq!(california.extensions().len(), 1);
+        assert_eq!(california.extensions()["x-gl-account"], 2310);
+        let union = db.get_rule("XU").unwrap();
+        assert_eq!(union.extensions()["x-sap-tax-codes"]["b2c"], "V1");
+
+        // Extensions are kept when the data is serialized again
+        let serialized = serde_json::to_value(&union).unwrap();
+        assert_eq!(serialized["x-sap-tax-codes"]["b2b"], "V0");
+    }
+}
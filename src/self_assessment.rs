@@ -0,0 +1,169 @@
+//! Tax the buyer self-assesses on reverse-charged sales.
+//!
+//! On a reverse-charged sale (e.g. B2B within the EU, or services imported by a
+//! business) the seller charges no tax, and the buyer accounts for it in their own
+//! return instead, at their country's rate, usually deducting it in the same return.
+//! Quotes flag these sales (`TaxQuote.self_assessment`), with an estimate of the tax
+//! at the rate the buyer's country would charge, for the buying side to show.
+//!
+//! The estimate applies the destination's rate as if the seller charged it; it's an
+//! estimate because the buyer may be entitled to another rate (e.g. a reduced one)
+//! the scenario doesn't state.
+//!
+//! For the buyer's own books, `TaxScenario::acquisition` calculates the VAT of an
+//! intra-community acquisition (a reverse-charged purchase from another member of a
+//! customs union like the EU) from the same scenario as the sale: due at the buyer's
+//! rates, and deducted as input VAT in the same return.
+
+use serde::Serialize;
+use typeshare::typeshare;
+
+use crate::calculation::combined_rate;
+use crate::explain::Trace;
+use crate::percentage::Percentage;
+use crate::rate_provider::RateProvider;
+use crate::{ProcessingError, TaxCalculationType, TaxRate, TaxScenario};
+
+/// Tax the buyer accounts for on a reverse-charged sale.
+#[typeshare]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SelfAssessment {
+    /// ISO 3166-1 code of the country the buyer accounts for the tax in
+    pub country: String,
+    /// The destination's rate, if it has one
+    pub estimated_rate: Option<Percentage>,
+    /// Tax at the estimated rate, rounded like the quote's tax
+    pub estimated_tax: Option<f64>,
+}
+
+/// The VAT of an intra-community acquisition, as the buyer books it.
+#[typeshare]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Acquisition {
+    /// ISO 3166-1 code of the country the acquisition is taxed in (the buyer's)
+    pub country: String,
+    /// The rates of the buyer's country
+    pub rates: Vec<TaxRate>,
+    /// The net amount of the purchase
+    pub amount: f64,
+    /// VAT due on the acquisition
+    pub tax: f64,
+    /// Input VAT deducted in the same return; the full tax, so the acquisition is
+    /// neutral for a buyer with the full right of deduction
+    pub deductible: f64,
+}
+
+impl TaxScenario {
+    /// What the buyer self-assesses on a sale, or `None` if the seller charges the tax
+    /// (or none is due).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use world_tax::{Region, TaxCalculationType, TaxDatabase, TaxScenario, TransactionType};
+    /// let db = TaxDatabase::fixture();
+    /// let scenario = TaxScenario::new(
+    ///     Region::parse("DE").unwrap(),
+    ///     Region::parse("FR").unwrap(),
+    ///     TransactionType::B2B,
+    /// );
+    ///
+    /// let assessment = scenario
+    ///     .self_assessment(&TaxCalculationType::ReverseCharge, 0.0, 100.0, &db)
+    ///     .unwrap();
+    /// assert_eq!(assessment.country, "FR");
+    /// assert_eq!(assessment.estimated_rate.unwrap().to_string(), "20%");
+    /// assert_eq!(assessment.estimated_tax, Some(20.0));
+    /// ```
+    pub fn self_assessment<P: RateProvider + ?Sized>(
+        &self,
+        calculation_type: &TaxCalculationType,
+        tax: f64,
+        amount: f64,
+        db: &P,
+    ) -> Option<SelfAssessment> {
+        // Non-VAT countries keep charging their tax on reverse-charged sales
+        if *calculation_type != TaxCalculationType::ReverseCharge || tax != 0.0 {
+            return None;
+        }
+
+        let mut rates = Vec::new();
+        let known = self
+            .get_regional_rates(
+                TaxCalculationType::Destination,
+                db,
+                &mut rates,
+                &mut Trace::disabled(),
+            )
+            .is_ok()
+            && !rates.is_empty();
+        Some(SelfAssessment {
+            country: self.destination_region.country.clone(),
+            estimated_rate: known.then(|| Percentage::from_f64(combined_rate(&rates))),
+            estimated_tax: known
+                .then(|| self.sum_rates(amount, &rates, db).ok())
+                .flatten(),
+        })
+    }
+
+    /// The VAT the buyer accounts for on the purchase, or `None` if it isn't an
+    /// intra-community acquisition: a sale reverse charged between members of a
+    /// customs union. The scenario is the sale's, with the buyer as the destination
+    /// (and, within the EU, the buyer's VAT number).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the calculation type or the buyer's rates can't be
+    /// determined.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use world_tax::vat_id::VatId;
+    /// # use world_tax::{Region, TaxDatabase, TaxScenario, TransactionType};
+    /// let db = TaxDatabase::fixture();
+    /// let purchase = TaxScenario::new(
+    ///     Region::parse("DE").unwrap(),
+    ///     Region::parse("FR").unwrap(),
+    ///     TransactionType::B2B,
+    /// )
+    /// .with_buyer_vat_id(VatId::parse("FR40303265045").unwrap());
+    ///
+    /// let acquisition = purchase.acquisition(1000.0, &db).unwrap().unwrap();
+    /// assert_eq!(acquisition.country, "FR");
+    /// assert_eq!(acquisition.tax, 200.0);
+    /// assert_eq!(acquisition.deductible, 200.0);
+    /// ```
+    pub fn acquisition<P: RateProvider + ?Sized>(
+        &self,
+        amount: f64,
+        db: &P,
+    ) -> Result<Option<Acquisition>, ProcessingError> {
+        let (source, destination) = (
+            &self.source_region.country,
+            &self.destination_region.country,
+        );
+        if source == destination
+            || db.get_international_rule(source, destination).is_none()
+            || self.determine_calculation_type(db, amount)? != TaxCalculationType::ReverseCharge
+        {
+            return Ok(None);
+        }
+
+        let mut rates = Vec::new();
+        self.get_regional_rates(
+            TaxCalculationType::Destination,
+            db,
+            &mut rates,
+            &mut Trace::disabled(),
+        )?;
+        let tax = self.sum_rates(amount, &rates, db)?;
+        Ok(Some(Acquisition {
+            country: destination.clone(),
+            rates,
+            amount,
+            tax,
+            deductible: tax,
+        }))
+    }
+}
@@ -0,0 +1,119 @@
+#[cfg(test)]
+mod tests {
+    use crate::calculator::{TaxCalculator, TaxRequest};
+    use crate::vat_id::VatId;
+    use crate::{Region, TaxDatabase, TaxScenario, TransactionType, VatRate};
+
+    fn setup() -> TaxDatabase {
+        TaxDatabase::from_files("vat_rates.json", "trade_agreements.json")
+            .expect("Tax database should load")
+    }
+
+    fn scenario(source: &str, destination: &str, transaction_type: TransactionType) -> TaxScenario {
+        TaxScenario::new(
+            Region::parse(source).expect("Valid region"),
+            Region::parse(destination).expect("Valid region"),
+            transaction_type,
+        )
+    }
+
+    fn french() -> VatId {
+        VatId::parse("FR40303265045").expect("Valid VAT ID")
+    }
+
+    #[test]
+    fn test_reverse_charge_self_assessment() {
+        let db = setup();
+        let quote = db
+            .quote(TaxRequest::new(
+                scenario("DE", "FR", TransactionType::B2B).with_buyer_vat_id(french()),
+                250.0,
+            ))
+            .unwrap();
+        assert_eq!(quote.tax, 0.0);
+        let assessment = quote.self_assessment.expect("Buyer self-assesses");
+        assert_eq!(assessment.country, "FR");
+        assert_eq!(assessment.estimated_rate.unwrap().to_string(), "20%");
+        assert_eq!(assessment.estimated_tax, Some(50.0));
+
+        // At the rate the scenario states
+        let mut reduced = scenario("DE", "FR", TransactionType::B2B).with_buyer_vat_id(french());
+        reduced.vat_rate = Some(VatRate::Reduced);
+        let quote = db.quote(TaxRequest::new(reduced, 100.0)).unwrap();
+        assert_eq!(quote.self_assessment.unwrap().estimated_tax, Some(10.0));
+
+        // Services imported by a business
+        let mut import = scenario("US", "DE", TransactionType::B2B);
+        import.is_digital_product_or_service = true;
+        let quote = db.quote(TaxRequest::new(import, 100.0)).unwrap();
+        assert_eq!(quote.self_assessment.unwrap().estimated_tax, Some(19.0));
+
+        // Credit notes reverse the estimate
+        let quote = db
+            .quote(TaxRequest::new(
+                scenario("DE", "FR", TransactionType::B2B).with_buyer_vat_id(french()),
+                100.0,
+            ))
+            .unwrap();
+        let reversal = quote.reversal();
+        assert_eq!(reversal.self_assessment.unwrap().estimated_tax, Some(-20.0));
+    }
+
+    #[test]
+    fn test_no_self_assessment() {
+        let db = setup();
+        for (source, destination, transaction_type) in [
+            ("DE", "FR", TransactionType::B2C),
+            ("DE", "DE", TransactionType::B2B),
+            ("DE", "US-CA", TransactionType::B2B),
+        ] {
+            let quote = db
+                .quote(TaxRequest::new(
+                    scenario(source, destination, transaction_type),
+                    100.0,
+                ))
+                .unwrap();
+            assert!(quote.self_assessment.is_none(), "{source} to {destination}");
+        }
+    }
+
+    #[test]
+    fn test_acquisition() {
+        let db = setup();
+        let purchase = scenario("DE", "FR", TransactionType::B2B).with_buyer_vat_id(french());
+        let acquisition = purchase.acquisition(250.0, &db).unwrap().unwrap();
+        assert_eq!(acquisition.country, "FR");
+        assert_eq!(acquisition.rates.len(), 1);
+        assert_eq!(acquisition.rates[0].rate.to_string(), "20%");
+        assert_eq!(acquisition.amount, 250.0);
+        assert_eq!(acquisition.tax, 50.0);
+        assert_eq!(acquisition.deductible, 50.0);
+
+        let mut reduced = purchase.clone();
+        reduced.vat_rate = Some(VatRate::Reduced);
+        assert_eq!(reduced.acquisition(100.0, &db).unwrap().unwrap().tax, 10.0);
+
+        let gcc = scenario("AE", "SA", TransactionType::B2B);
+        let acquisition = gcc.acquisition(100.0, &db).unwrap().unwrap();
+        assert_eq!(
+            (acquisition.country.as_str(), acquisition.tax),
+            ("SA", 15.0)
+        );
+    }
+
+    #[test]
+    fn test_no_acquisition() {
+        let db = setup();
+        let mut import = scenario("US", "DE", TransactionType::B2B);
+        import.is_digital_product_or_service = true;
+        for purchase in [
+            // The seller charges its VAT without the buyer's VAT number
+            scenario("DE", "FR", TransactionType::B2B),
+            scenario("DE", "DE", TransactionType::B2B).with_buyer_vat_id(french()),
+            // Imported services are reverse charged, but not acquisitions
+            import,
+        ] {
+            assert!(purchase.acquisition(100.0, &db).unwrap().is_none());
+        }
+    }
+}
@@ -0,0 +1,180 @@
+//! Multi-leg shipments.
+//!
+//! Goods don't always travel straight from the seller to the buyer: they may pass
+//! through an intermediate country, for example a bonded warehouse in the
+//! Netherlands before delivery to Germany. Import VAT is due where the goods are
+//! released into free circulation, which isn't necessarily the first customs
+//! border they cross. A `Shipment` splits the route into legs and determines the
+//! outcome of each: an export, an import, a movement under customs supervision, or
+//! a movement of goods already in free circulation (e.g. within the EU).
+
+use serde::Serialize;
+
+use crate::{ProcessingError, Region, TaxDatabase, TaxRate, TaxScenario};
+
+/// An intermediate stop of a shipment.
+#[derive(Debug, Clone)]
+pub struct Waypoint {
+    /// Where the goods stop
+    pub region: Region,
+    /// Whether the goods are stored under customs supervision (bonded warehouse,
+    /// customs warehousing), so import is suspended
+    pub bonded: bool,
+}
+
+impl Waypoint {
+    /// Creates a stop where goods are released into free circulation.
+    pub fn new(region: Region) -> Self {
+        Self {
+            region,
+            bonded: false,
+        }
+    }
+
+    /// Creates a stop at a bonded warehouse.
+    pub fn bonded(region: Region) -> Self {
+        Self {
+            region,
+            bonded: true,
+        }
+    }
+}
+
+/// What happens to the goods on a leg.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LegOutcome {
+    /// The goods leave a customs territory into customs supervision elsewhere; the
+    /// export is zero-rated and import is suspended
+    ExportSuspended,
+    /// The goods are released into free circulation at the end of the leg; import
+    /// VAT is due there
+    Import,
+    /// Goods in free circulation move between countries of a customs union (e.g. an
+    /// intra-EU movement)
+    IntraUnionMovement,
+    /// The goods move within a country
+    Domestic,
+    /// The goods move under customs supervision, e.g. between bonded warehouses
+    Suspended,
+}
+
+/// A leg of a shipment.
+#[derive(Debug, Clone, Serialize)]
+pub struct Leg {
+    /// Where the leg starts
+    pub from: String,
+    /// Where the leg ends
+    pub to: String,
+    /// What happens to the goods on the leg
+    pub outcome: LegOutcome,
+    /// Import VAT rates, for import legs
+    pub rates: Vec<TaxRate>,
+    /// Import VAT, for import legs
+    pub import_vat: f64,
+}
+
+/// A scenario with intermediate stops between the seller and the buyer.
+///
+/// # Examples
+///
+/// ```
+/// # use world_tax::shipment::{LegOutcome, Shipment, Waypoint};
+/// # use world_tax::{Region, TaxDatabase, TaxScenario, TransactionType};
+/// let db = TaxDatabase::fixture();
+/// let scenario = TaxScenario::new(
+///     Region::new("CA".to_string(), None).unwrap(),
+///     Region::new("DE".to_string(), None).unwrap(),
+///     TransactionType::B2B,
+/// );
+/// // Stored in a bonded warehouse in France before delivery to Germany
+/// let shipment = Shipment::new(scenario).via(Waypoint::bonded(Region::new("FR".to_string(), None).unwrap()));
+///
+/// let legs = shipment.legs(1000.0, &db).unwrap();
+/// assert_eq!(legs[0].outcome, LegOutcome::ExportSuspended);
+/// assert_eq!(legs[1].outcome, LegOutcome::Import); // Import VAT in Germany
+/// assert_eq!(legs[1].import_vat, 190.0);
+/// ```
+#[derive(Debug, Clone)]
+pub struct Shipment {
+    /// The scenario; its source and destination are the first and last stop
+    pub scenario: TaxScenario,
+    /// The intermediate stops, in order
+    pub waypoints: Vec<Waypoint>,
+}
+
+impl Shipment {
+    /// Creates a shipment without intermediate stops.
+    pub fn new(scenario: TaxScenario) -> Self {
+        Self {
+            scenario,
+            waypoints: Vec::new(),
+        }
+    }
+
+    /// Adds an intermediate stop.
+    pub fn via(mut self, waypoint: Waypoint) -> Self {
+        self.waypoints.push(waypoint);
+        self
+    }
+
+    /// Determines the outcome of each leg, for goods of the given value.
+    ///
+    /// The goods start in free circulation at the seller, and end up released into
+    /// free circulation at the buyer.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the rates of an importing country can't be resolved.
+    pub fn legs(&self, amount: f64, db: &TaxDatabase) -> Result<Vec<Leg>, ProcessingError> {
+        let mut stops = vec![(&self.scenario.source_region, false)];
+        stops.extend(self.waypoints.iter().map(|w| (&w.region, w.bonded)));
+        stops.push((&self.scenario.destination_region, false));
+
+        // The customs territory the goods are in free circulation in, if any
+        let mut circulating = Some(db.customs_territory(&self.scenario.source_region.country));
+        let mut legs = Vec::new();
+        for pair in stops.windows(2) {
+            let ((from, _), (to, bonded)) = (pair[0], pair[1]);
+            let territory = db.customs_territory(&to.country);
+            let in_free_circulation = circulating.as_ref() == Some(&territory);
+
+            // Goods already in free circulation in the territory stay there, even if
+            // stored in a bonded warehouse
+            let outcome = if bonded && !in_free_circulation {
+                if circulating.is_some() {
+                    LegOutcome::ExportSuspended
+                } else {
+                    LegOutcome::Suspended
+                }
+            } else if !in_free_circulation {
+                LegOutcome::Import
+            } else if from.country == to.country {
+                LegOutcome::Domestic
+            } else {
+                LegOutcome::IntraUnionMovement
+            };
+
+            let rates = match outcome {
+                LegOutcome::Import => {
+                    db.get_rate(&to.country, None, self.scenario.vat_rate.as_ref())?
+                }
+                _ => Vec::new(),
+            };
+            let import_vat = self.scenario.sum_rates(amount, &rates, db)?;
+
+            circulating = match outcome {
+                LegOutcome::ExportSuspended | LegOutcome::Suspended => None,
+                _ => Some(territory),
+            };
+            legs.push(Leg {
+                from: from.to_string(),
+                to: to.to_string(),
+                outcome,
+                rates,
+                import_vat,
+            });
+        }
+        Ok(legs)
+    }
+}
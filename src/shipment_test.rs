@@ -0,0 +1,70 @@
+#[cfg(test)]
+mod tests {
+    use crate::shipment::{LegOutcome, Shipment, Waypoint};
+    use crate::{Region, TaxDatabase, TaxScenario, TransactionType};
+
+    fn region(code: &str) -> Region {
+        Region::parse(code).expect("Valid region")
+    }
+
+    fn outcomes(shipment: &Shipment, db: &TaxDatabase) -> Vec<LegOutcome> {
+        shipment
+            .legs(100.0, db)
+            .expect("Legs should be determined")
+            .iter()
+            .map(|leg| leg.outcome)
+            .collect()
+    }
+
+    #[test]
+    fn test_import_then_intra_union_movement() {
+        let db = TaxDatabase::fixture();
+        let scenario = TaxScenario::new(region("CA"), region("DE"), TransactionType::B2B);
+        // Released into free circulation in France, then moved on to Germany
+        let shipment = Shipment::new(scenario).via(Waypoint::new(region("FR")));
+
+        let legs = shipment
+            .legs(100.0, &db)
+            .expect("Legs should be determined");
+        assert_eq!(legs[0].outcome, LegOutcome::Import);
+        assert_eq!(legs[0].to, "FR");
+        assert_eq!(legs[0].import_vat, 20.0); // French import VAT
+        assert_eq!(legs[1].outcome, LegOutcome::IntraUnionMovement);
+        assert_eq!(legs[1].import_vat, 0.0);
+    }
+
+    #[test]
+    fn test_bonded_warehouse_chain() {
+        let db = TaxDatabase::fixture();
+        let scenario = TaxScenario::new(region("CA"), region("DE"), TransactionType::B2B);
+        let shipment = Shipment::new(scenario)
+            .via(Waypoint::bonded(region("FR")))
+            .via(Waypoint::bonded(region("DE")));
+
+        assert_eq!(
+            outcomes(&shipment, &db),
+            vec![
+                LegOutcome::ExportSuspended,
+                LegOutcome::Suspended,
+                LegOutcome::Import
+            ]
+        );
+    }
+
+    #[test]
+    fn test_goods_in_free_circulation() {
+        let db = TaxDatabase::fixture();
+        let scenario = TaxScenario::new(region("FR"), region("DE"), TransactionType::B2B);
+        // A bonded warehouse doesn't suspend goods already in free circulation
+        let shipment = Shipment::new(scenario.clone()).via(Waypoint::bonded(region("DE")));
+        assert_eq!(
+            outcomes(&shipment, &db),
+            vec![LegOutcome::IntraUnionMovement, LegOutcome::Domestic]
+        );
+
+        assert_eq!(
+            outcomes(&Shipment::new(scenario), &db),
+            vec![LegOutcome::IntraUnionMovement]
+        );
+    }
+}
@@ -0,0 +1,322 @@
+//! Synthetic datasets for load testing.
+//!
+//! The embedded dataset has a few hundred jurisdictions; integrations that load ZIP-level
+//! local rates or custom agreements work with many more. `Simulation` generates a
+//! dataset of that scale in the format of `vat_rates.json` and `trade_agreements.json`,
+//! and random requests against it:
+//!
+//! - Countries taxing by subdivision (like the US and Canada) have a rate for each of
+//!   their ISO 3166-2 subdivisions, a federal agreement between them, and a boundary
+//!   table of ZIP code ranges with local rates per subdivision
+//! - The other countries have a VAT with a standard and, mostly, a reduced rate, and
+//!   are grouped into customs unions with EU-like rules
+//!
+//! Rates are random within the range of real ones, and all codes are real ISO codes,
+//! so generated requests pass validation. The same seed gives the same dataset and
+//! requests.
+
+use serde_json::{json, Map, Value};
+
+use crate::calculator::TaxRequest;
+use crate::sst::BoundaryTable;
+use crate::{Region, TaxDatabase, TaxScenario, TransactionType};
+
+/// Size and seed of a simulation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SimulationConfig {
+    /// Seed of the generator
+    pub seed: u64,
+    /// Countries in the dataset, at most the 249 of ISO 3166-1
+    pub countries: usize,
+    /// Share of the countries with subdivisions that tax by subdivision
+    pub federal_share: f64,
+    /// ZIP code ranges with a local rate, per subdivision
+    pub local_ranges: usize,
+    /// Customs unions between the countries that don't tax by subdivision
+    pub agreements: usize,
+    /// Members per customs union
+    pub agreement_members: usize,
+}
+
+impl Default for SimulationConfig {
+    fn default() -> Self {
+        Self {
+            seed: 1,
+            countries: 249,
+            federal_share: 0.25,
+            local_ranges: 100,
+            agreements: 50,
+            agreement_members: 8,
+        }
+    }
+}
+
+/// A generated dataset.
+#[derive(Debug, Clone)]
+pub struct SyntheticDataset {
+    /// Countries, in the format of `vat_rates.json`
+    pub vat_rates: String,
+    /// Agreements, in the format of `trade_agreements.json`
+    pub trade_agreements: String,
+    /// Boundary tables (CSV) of local rates, by subdivision code
+    pub local_rates: Vec<(String, String)>,
+    /// Country codes with the codes of their taxing subdivisions, if any
+    regions: Vec<(String, Vec<String>)>,
+}
+
+impl SyntheticDataset {
+    /// Loads the dataset, with its local rates.
+    pub fn database(&self) -> TaxDatabase {
+        let mut db = TaxDatabase::from_json(&self.vat_rates, &self.trade_agreements)
+            .expect("Generated data is valid");
+        for (state, csv) in &self.local_rates {
+            let table = BoundaryTable::parse(csv).expect("Generated boundary table is valid");
+            db.load_local_rates(state, table);
+        }
+        db
+    }
+
+    /// Number of subdivisions with their own rate.
+    pub fn subdivisions(&self) -> usize {
+        self.regions.iter().map(|(_, states)| states.len()).sum()
+    }
+}
+
+/// Generates datasets and requests.
+///
+/// # Examples
+///
+/// ```
+/// # use world_tax::calculator::TaxCalculator;
+/// # use world_tax::simulation::{Simulation, SimulationConfig};
+/// let mut simulation = Simulation::new(SimulationConfig {
+///     countries: 40,
+///     local_ranges: 10,
+///     agreements: 5,
+///     ..SimulationConfig::default()
+/// });
+/// let dataset = simulation.dataset();
+/// let db = dataset.database();
+///
+/// for request in simulation.requests(&dataset, 100) {
+///     db.quote(request).unwrap();
+/// }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Simulation {
+    config: SimulationConfig,
+    rng: Rng,
+}
+
+impl Simulation {
+    /// Creates a simulation.
+    pub fn new(config: SimulationConfig) -> Self {
+        Self {
+            config,
+            rng: Rng(config.seed),
+        }
+    }
+
+    /// Generates a dataset.
+    pub fn dataset(&mut self) -> SyntheticDataset {
+        let mut countries = Map::new();
+        let mut agreements = Map::new();
+        let mut local_rates = Vec::new();
+        let mut regions = Vec::new();
+
+        for country in rust_iso3166::ALL.iter().take(self.config.countries) {
+            let code = country.alpha2.to_string();
+            let subdivisions = country.subdivisions().unwrap_or_default();
+            if subdivisions.is_empty() || !self.rng.chance(self.config.federal_share) {
+                countries.insert(code.clone(), self.vat_country());
+                regions.push((code, Vec::new()));
+                continue;
+            }
+
+            // A federal tax and state taxes (Canada), or state taxes only (US)
+            let federal = self.rng.chance(0.5);
+            let mut states = Map::new();
+            for subdivision in subdivisions {
+                states.insert(
+                    subdivision.code.to_string(),
+                    json!({
+                        "type": if federal { "pst" } else { "vat" },
+                        "standard_rate": self.rng.rate(0, 1000),
+                    }),
+                );
+                local_rates.push((subdivision.code.to_string(), self.boundary_table()));
+            }
+            let members: Vec<String> = states.keys().cloned().collect();
+            countries.insert(
+                code.clone(),
+                json!({
+                    "type": if federal { "gst" } else { "none" },
+                    "currency": "USD",
+                    "standard_rate": if federal { self.rng.rate(300, 1000) } else { 0.0 },
+                    "states": states,
+                }),
+            );
+            agreements.insert(
+                code.clone(),
+                json!({
+                    "name": format!("{} (simulated)", country.name),
+                    "type": "federal_state",
+                    "members": members,
+                    "default_applicable": true,
+                    "applies_to": { "physical_goods": true, "digital_goods": true, "services": true },
+                    "tax_rules": {
+                        "internal_b2b": { "type": "destination" },
+                        "internal_b2c": { "type": "destination" },
+                        "external_export": { "type": "zero_rated" }
+                    }
+                }),
+            );
+            regions.push((code, members));
+        }
+
+        let vat_countries: Vec<&String> = regions
+            .iter()
+            .filter(|(_, states)| states.is_empty())
+            .map(|(code, _)| code)
+            .collect();
+        if !vat_countries.is_empty() {
+            for index in 1..=self.config.agreements {
+                let mut members = Vec::new();
+                while members.len() < self.config.agreement_members.min(vat_countries.len()) {
+                    let member = vat_countries[self.rng.below(vat_countries.len())];
+                    if !members.contains(&member) {
+                        members.push(member);
+                    }
+                }
+                agreements.insert(format!("SIM{index}"), self.customs_union(index, members));
+            }
+        }
+
+        countries.insert("schema_version".to_string(), json!(2));
+        agreements.insert("schema_version".to_string(), json!(2));
+        SyntheticDataset {
+            vat_rates: Value::Object(countries).to_string(),
+            trade_agreements: Value::Object(agreements).to_string(),
+            local_rates,
+            regions,
+        }
+    }
+
+    /// Generates requests between random regions of a dataset: 5-digit postal codes
+    /// for subdivisions, B2B and B2C, 30% digital, amounts up to 10,000.
+    pub fn requests(&mut self, dataset: &SyntheticDataset, count: usize) -> Vec<TaxRequest> {
+        (0..count)
+            .map(|_| {
+                let mut scenario = TaxScenario::new(
+                    self.region(dataset),
+                    self.region(dataset),
+                    if self.rng.chance(0.5) {
+                        TransactionType::B2B
+                    } else {
+                        TransactionType::B2C
+                    },
+                );
+                scenario.is_digital_product_or_service = self.rng.chance(0.3);
+                let amount = (self.rng.below(1_000_000) + 1) as f64 / 100.0;
+                TaxRequest::new(scenario, amount)
+            })
+            .collect()
+    }
+
+    fn region(&mut self, dataset: &SyntheticDataset) -> Region {
+        let (country, states) = &dataset.regions[self.rng.below(dataset.regions.len())];
+        if states.is_empty() {
+            return Region::new(country.clone(), None).expect("Generated region is valid");
+        }
+        let state = states[self.rng.below(states.len())].clone();
+        Region::new(country.clone(), Some(state))
+            .expect("Generated region is valid")
+            .with_postal_code(format!("{:05}", self.rng.below(100_000)))
+    }
+
+    fn vat_country(&mut self) -> Value {
+        let mut country = json!({
+            "type": "vat",
+            "currency": "USD",
+            "standard_rate": self.rng.rate(500, 2700),
+        });
+        if self.rng.chance(0.8) {
+            country["reduced_rate"] = json!(self.rng.rate(0, 1000));
+        }
+        country
+    }
+
+    fn customs_union(&mut self, index: usize, members: Vec<&String>) -> Value {
+        let threshold = (self.rng.below(20) + 1) * 5_000;
+        json!({
+            "name": format!("Simulated Union {index}"),
+            "type": "customs_union",
+            "members": members,
+            "default_applicable": true,
+            "applies_to": { "physical_goods": true, "digital_goods": true, "services": true },
+            "tax_rules": {
+                "internal_b2b": { "type": "reverse_charge" },
+                "internal_b2c": {
+                    "type": "threshold_based",
+                    "below_threshold": "origin",
+                    "above_threshold": "destination",
+                    "threshold": { "amount": threshold, "basis": "annual_turnover", "currency": "USD" },
+                    "below_threshold_digital_products": "destination",
+                    "above_threshold_digital_products": "destination",
+                    "threshold_digital_products": { "amount": 0, "basis": "transaction", "currency": "USD" }
+                },
+                "external_export": { "type": "zero_rated" }
+            }
+        })
+    }
+
+    /// A boundary table splitting the ZIP codes into `local_ranges` ranges.
+    fn boundary_table(&mut self) -> String {
+        let ranges = self.config.local_ranges.max(1);
+        let width = 100_000 / ranges;
+        (0..ranges)
+            .map(|index| {
+                let low = index * width;
+                let high = if index + 1 == ranges {
+                    99_999
+                } else {
+                    low + width - 1
+                };
+                format!(
+                    "{low},{high},{},Locality {}\n",
+                    self.rng.rate(0, 500),
+                    index + 1
+                )
+            })
+            .collect()
+    }
+}
+
+/// SplitMix64: fast, and deterministic for a seed; not for anything but test data.
+#[derive(Debug, Clone)]
+struct Rng(u64);
+
+impl Rng {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// A number below `n` (at least 1).
+    fn below(&mut self, n: usize) -> usize {
+        (self.next() % n as u64) as usize
+    }
+
+    fn chance(&mut self, probability: f64) -> bool {
+        ((self.next() >> 11) as f64 / (1u64 << 53) as f64) < probability
+    }
+
+    /// A rate between two rates in basis points, in steps of 0.25%.
+    fn rate(&mut self, low: usize, high: usize) -> f64 {
+        let steps = (high - low) / 25;
+        (low + self.below(steps + 1) * 25) as f64 / 10_000.0
+    }
+}
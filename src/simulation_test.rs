@@ -0,0 +1,54 @@
+#[cfg(test)]
+mod tests {
+    use crate::calculator::TaxCalculator;
+    use crate::simulation::{Simulation, SimulationConfig};
+    use crate::TaxDatabase;
+
+    fn config() -> SimulationConfig {
+        SimulationConfig {
+            countries: 60,
+            federal_share: 0.5,
+            local_ranges: 20,
+            agreements: 10,
+            ..SimulationConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_dataset() {
+        let dataset = Simulation::new(config()).dataset();
+        let db = dataset.database();
+        assert!(dataset.subdivisions() > 100);
+        assert_eq!(dataset.local_rates.len(), dataset.subdivisions());
+
+        let (state, _) = &dataset.local_rates[0];
+        let table = db.local_rates(state).expect("Local rates are loaded");
+        assert_eq!(table.records().len(), 20);
+        assert_eq!(table.records()[19].zip_high, 99_999);
+        assert!(db.get_rule("SIM10").is_ok());
+        assert!(
+            TaxDatabase::from_json_strict(&dataset.vat_rates, &dataset.trade_agreements).is_ok()
+        );
+
+        // Same seed, same dataset
+        let again = Simulation::new(config()).dataset();
+        assert_eq!(dataset.vat_rates, again.vat_rates);
+        assert_eq!(dataset.trade_agreements, again.trade_agreements);
+    }
+
+    #[test]
+    fn test_requests() {
+        let mut simulation = Simulation::new(config());
+        let dataset = simulation.dataset();
+        let db = dataset.database();
+        let requests = simulation.requests(&dataset, 1000);
+        assert_eq!(requests.len(), 1000);
+        for request in requests {
+            let scenario = request.scenario.clone();
+            let quote = db
+                .quote(request)
+                .unwrap_or_else(|error| panic!("{scenario:?}: {error}"));
+            assert!(quote.tax >= 0.0);
+        }
+    }
+}
@@ -0,0 +1,241 @@
+//! US Streamlined Sales Tax (SST).
+//!
+//! Member states of the Streamlined Sales and Use Tax Agreement use uniform
+//! definitions and sourcing rules, and publish boundary files that assign local
+//! rates to address ranges. This module holds the membership metadata, and a
+//! simplified boundary table (ZIP code ranges with their combined local rate)
+//! that can be loaded per state for sellers registered under SST.
+//!
+//! Boundary tables are loaded into the local-rate layer (see `local`), which applies
+//! the state's sourcing rules.
+
+use serde::Serialize;
+use typeshare::typeshare;
+
+use crate::errors::DatabaseError;
+use crate::types::normalize_state_code;
+use crate::TaxDatabase;
+
+/// Membership status of a state in the SST agreement.
+#[typeshare]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SstMembership {
+    /// Full member, in compliance with all requirements
+    Full,
+    /// Associate member, in compliance except for some requirements (e.g. sourcing)
+    Associate,
+}
+
+/// Which location's local rate applies to a sale.
+#[typeshare]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Sourcing {
+    /// The rate where the goods are delivered
+    Destination,
+    /// The rate where the seller is located
+    Origin,
+}
+
+/// An SST member state.
+#[typeshare]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct SstState {
+    /// ISO 3166-2 code of the state (e.g. "US-WA")
+    pub code: &'static str,
+    /// Membership status
+    pub membership: SstMembership,
+    /// Sourcing of sales within the state; interstate sales are always destination sourced
+    pub intrastate_sourcing: Sourcing,
+}
+
+const fn full(code: &'static str, intrastate_sourcing: Sourcing) -> SstState {
+    SstState {
+        code,
+        membership: SstMembership::Full,
+        intrastate_sourcing,
+    }
+}
+
+/// SST member states, as listed by the Streamlined Sales Tax Governing Board.
+pub const MEMBERS: [SstState; 24] = [
+    full("US-AR", Sourcing::Destination),
+    full("US-GA", Sourcing::Destination),
+    full("US-IN", Sourcing::Destination),
+    full("US-IA", Sourcing::Destination),
+    full("US-KS", Sourcing::Destination),
+    full("US-KY", Sourcing::Destination),
+    full("US-MI", Sourcing::Destination),
+    full("US-MN", Sourcing::Destination),
+    full("US-NE", Sourcing::Destination),
+    full("US-NV", Sourcing::Destination),
+    full("US-NJ", Sourcing::Destination),
+    full("US-NC", Sourcing::Destination),
+    full("US-ND", Sourcing::Destination),
+    full("US-OH", Sourcing::Origin),
+    full("US-OK", Sourcing::Destination),
+    full("US-RI", Sourcing::Destination),
+    full("US-SD", Sourcing::Destination),
+    full("US-UT", Sourcing::Origin),
+    full("US-VT", Sourcing::Destination),
+    full("US-WA", Sourcing::Destination),
+    full("US-WV", Sourcing::Destination),
+    full("US-WI", Sourcing::Destination),
+    full("US-WY", Sourcing::Destination),
+    SstState {
+        code: "US-TN",
+        membership: SstMembership::Associate,
+        intrastate_sourcing: Sourcing::Origin,
+    },
+];
+
+/// Looks up the SST membership of a state, by its ISO 3166-2 code or its bare code.
+///
+/// # Examples
+///
+/// ```
+/// # use world_tax::sst::{member, SstMembership};
+/// assert_eq!(member("US-WA").unwrap().membership, SstMembership::Full);
+/// assert_eq!(member("wa").unwrap().code, "US-WA");
+/// assert!(member("US-CA").is_none());
+/// ```
+pub fn member(code: &str) -> Option<&'static SstState> {
+    let find = |code: &str| MEMBERS.iter().find(|state| state.code == code);
+    find(code).or_else(|| find(&normalize_state_code("US", code)))
+}
+
+/// A range of ZIP codes sharing a local rate.
+#[derive(Debug, Clone, PartialEq)]
+pub struct BoundaryRecord {
+    /// First ZIP code of the range (inclusive)
+    pub zip_low: u32,
+    /// Last ZIP code of the range (inclusive)
+    pub zip_high: u32,
+    /// Combined local rate (county, city, district) on top of the state rate
+    pub rate: f64,
+    /// Name of the local jurisdiction, if given
+    pub jurisdiction: Option<String>,
+    /// Whether the jurisdiction administers its own sales tax (home-rule city), so
+    /// separate registration and remittance may be required
+    pub home_rule: bool,
+}
+
+/// Local rates of a state by ZIP code range, in a simplified SST boundary format.
+///
+/// Also used for the local-rate layer of states outside SST.
+#[derive(Debug, Clone, Default)]
+pub struct BoundaryTable {
+    records: Vec<BoundaryRecord>,
+}
+
+impl BoundaryTable {
+    /// Parses a boundary table from CSV lines of
+    /// `zip_low,zip_high,rate[,jurisdiction[,home_rule]]`, where `home_rule` is
+    /// `true` or `false`.
+    ///
+    /// Empty lines and lines starting with `#` are skipped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use world_tax::sst::BoundaryTable;
+    /// let table = BoundaryTable::parse("98101,98199,0.0385,Seattle").unwrap();
+    /// assert_eq!(table.lookup("98109").unwrap().rate, 0.0385);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::InvalidBoundaryRecord` with the line number if a line can't be parsed.
+    pub fn parse(csv: &str) -> Result<Self, DatabaseError> {
+        let mut records = Vec::new();
+        for (index, line) in csv.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let invalid = || DatabaseError::InvalidBoundaryRecord(index + 1);
+            let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+            if fields.len() < 3 || fields.len() > 5 {
+                return Err(invalid());
+            }
+            let record = BoundaryRecord {
+                zip_low: fields[0].parse().map_err(|_| invalid())?,
+                zip_high: fields[1].parse().map_err(|_| invalid())?,
+                rate: fields[2].parse().map_err(|_| invalid())?,
+                jurisdiction: fields
+                    .get(3)
+                    .filter(|name| !name.is_empty())
+                    .map(|name| name.to_string()),
+                home_rule: match fields.get(4) {
+                    Some(flag) => flag.parse().map_err(|_| invalid())?,
+                    None => false,
+                },
+            };
+            if record.zip_low > record.zip_high || !(0.0..1.0).contains(&record.rate) {
+                return Err(invalid());
+            }
+            records.push(record);
+        }
+        Ok(Self { records })
+    }
+
+    /// Finds the record for a postal code; ZIP+4 codes ("98109-1234") use the first five digits.
+    pub fn lookup(&self, postal_code: &str) -> Option<&BoundaryRecord> {
+        let zip: u32 = postal_code.get(..5)?.parse().ok()?;
+        self.records
+            .iter()
+            .find(|record| (record.zip_low..=record.zip_high).contains(&zip))
+    }
+
+    /// Finds the record of a local jurisdiction by name, ignoring case.
+    pub fn lookup_jurisdiction(&self, jurisdiction: &str) -> Option<&BoundaryRecord> {
+        self.records.iter().find(|record| {
+            record
+                .jurisdiction
+                .as_deref()
+                .is_some_and(|name| name.eq_ignore_ascii_case(jurisdiction.trim()))
+        })
+    }
+
+    /// The records of the table.
+    pub fn records(&self) -> &[BoundaryRecord] {
+        &self.records
+    }
+
+    /// Approximate number of bytes the table takes in memory.
+    pub fn memory_size(&self) -> usize {
+        std::mem::size_of::<Self>()
+            + self.records.capacity() * std::mem::size_of::<BoundaryRecord>()
+            + self
+                .records
+                .iter()
+                .filter_map(|record| record.jurisdiction.as_ref())
+                .map(String::capacity)
+                .sum::<usize>()
+    }
+}
+
+impl TaxDatabase {
+    /// Loads the boundary table of an SST member state, replacing any loaded before.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::NotSstMember` if the state isn't an SST member.
+    pub fn load_sst_boundaries(
+        &mut self,
+        state: &str,
+        table: BoundaryTable,
+    ) -> Result<(), DatabaseError> {
+        let Some(member) = member(state) else {
+            return Err(DatabaseError::NotSstMember(state.to_string()));
+        };
+        self.load_local_rates(member.code, table);
+        Ok(())
+    }
+
+    /// The loaded boundary table of an SST member state.
+    pub fn sst_boundaries(&self, state: &str) -> Option<&BoundaryTable> {
+        member(state).and_then(|member| self.local_rates(member.code))
+    }
+}
@@ -0,0 +1,119 @@
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+
+    use crate::sst::{member, BoundaryTable, Sourcing};
+    use crate::{
+        DatabaseError, Percentage, RateSource, Region, TaxDatabase, TaxScenario, TaxType,
+        ThresholdPolicy, TransactionType,
+    };
+
+    fn setup() -> TaxDatabase {
+        let mut db = TaxDatabase::from_files("vat_rates.json", "trade_agreements.json")
+            .expect("Tax database should load");
+        let washington = "# zip_low,zip_high,rate,jurisdiction\n98101,98199,0.0385,Seattle\n99201,99224,0.024,Spokane";
+        db.load_sst_boundaries("US-WA", BoundaryTable::parse(washington).unwrap())
+            .expect("Washington is an SST member");
+        let utah = "84101,84199,0.0275,Salt Lake City\n84601,84606,0.0175,Provo";
+        db.load_sst_boundaries("US-UT", BoundaryTable::parse(utah).unwrap())
+            .expect("Utah is an SST member");
+        db
+    }
+
+    fn scenario(source: Region, destination: Region) -> TaxScenario {
+        let mut scenario = TaxScenario::new(source, destination, TransactionType::B2C);
+        scenario.threshold_policy = ThresholdPolicy::AssumeAbove;
+        scenario
+    }
+
+    #[test]
+    fn test_destination_local_rate() {
+        let db = setup();
+        let oregon = Region::parse("US-OR").unwrap();
+        let seattle = Region::parse("US-WA")
+            .unwrap()
+            .with_postal_code("98109-1234");
+
+        let rates = scenario(oregon.clone(), seattle)
+            .get_rates(100.0, &db)
+            .unwrap();
+        assert_eq!(rates.len(), 2);
+        assert_eq!(rates[0].tax_type, TaxType::StateSalesTax);
+        assert_eq!(rates[1].tax_type, TaxType::LocalSalesTax);
+        assert_eq!(rates[1].source, RateSource::Local);
+        assert_eq!(rates[1].rate, Percentage::from_fraction(dec!(0.0385)));
+
+        // Without a postal code, only the state rate applies
+        let washington = Region::parse("US-WA").unwrap();
+        let rates = scenario(oregon.clone(), washington)
+            .get_rates(100.0, &db)
+            .unwrap();
+        assert_eq!(rates.len(), 1);
+
+        // Postal codes outside the table don't add a local rate
+        let elsewhere = Region::parse("US-WA").unwrap().with_postal_code("98001");
+        let rates = scenario(oregon, elsewhere).get_rates(100.0, &db).unwrap();
+        assert_eq!(rates.len(), 1);
+    }
+
+    #[test]
+    fn test_intrastate_origin_sourcing() {
+        let db = setup();
+        assert_eq!(
+            member("US-UT").unwrap().intrastate_sourcing,
+            Sourcing::Origin
+        );
+        let salt_lake = Region::parse("US-UT").unwrap().with_postal_code("84101");
+        let provo = Region::parse("US-UT").unwrap().with_postal_code("84604");
+
+        let rates = scenario(salt_lake.clone(), provo.clone())
+            .get_rates(100.0, &db)
+            .unwrap();
+        assert_eq!(rates[1].rate, Percentage::from_fraction(dec!(0.0275))); // Seller's location
+
+        // Interstate sales are destination sourced
+        let oregon = Region::parse("US-OR").unwrap();
+        let rates = scenario(oregon, provo).get_rates(100.0, &db).unwrap();
+        assert_eq!(rates[1].rate, Percentage::from_fraction(dec!(0.0175)));
+    }
+
+    #[test]
+    fn test_state_code_conventions() {
+        let mut db = TaxDatabase::fixture();
+        let table = || BoundaryTable::parse("84101,84199,0.0275,Salt Lake City").unwrap();
+
+        // SST members by bare code, stored under the ISO 3166-2 code
+        assert_eq!(member("ut").expect("Utah is an SST member").code, "US-UT");
+        db.load_sst_boundaries("UT", table())
+            .expect("Utah is an SST member");
+        assert!(db.sst_boundaries("US-UT").is_some());
+        assert!(db.local_rates("us-ut").is_some());
+
+        // Other local rates need the country, in any case
+        db.load_local_rates("us-ak", table());
+        assert!(db.local_rates("US-AK").is_some());
+        assert!(db.local_table("US-AK").is_some());
+    }
+
+    #[test]
+    fn test_boundary_errors() {
+        let mut db = TaxDatabase::fixture();
+        assert!(matches!(
+            db.load_sst_boundaries("US-CA", BoundaryTable::default()),
+            Err(DatabaseError::NotSstMember(_))
+        ));
+
+        assert!(matches!(
+            BoundaryTable::parse("98101,98199,0.0385\n98199,98101,0.01"),
+            Err(DatabaseError::InvalidBoundaryRecord(2))
+        ));
+        assert!(matches!(
+            BoundaryTable::parse("98101,98199"),
+            Err(DatabaseError::InvalidBoundaryRecord(1))
+        ));
+        assert!(matches!(
+            BoundaryTable::parse("98101,98199,0.0385,Seattle,yes"),
+            Err(DatabaseError::InvalidBoundaryRecord(1))
+        ));
+    }
+}
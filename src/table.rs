@@ -0,0 +1,103 @@
+//! Static country table (`static-table` feature).
+//!
+//! The rates of the embedded `vat_rates.json` are compiled into the binary as a
+//! static slice sorted by country code, so looking up a rate needs neither JSON
+//! parsing at startup nor hashing of `String` keys, for latency-sensitive edge
+//! deployments. The table holds the rates and flags of countries and states; flat
+//! fees, trade agreements and tax calculation need a `TaxDatabase`.
+
+use crate::percentage::Percentage;
+use crate::types::{normalize_state_code, TaxSystemType};
+use crate::VatRate;
+
+/// The rates of a country.
+#[derive(Debug, PartialEq)]
+pub struct CountryRecord {
+    /// ISO 3166-1 alpha-2 code of the country
+    pub code: &'static str,
+    /// Type of tax system used in the country
+    pub tax_type: TaxSystemType,
+    /// Currency code of the country
+    pub currency: &'static str,
+    /// Standard tax rate
+    pub standard_rate: Percentage,
+    /// Reduced tax rate, if the country has one
+    pub reduced_rate: Option<Percentage>,
+    /// Alternative reduced tax rate, if the country has one
+    pub reduced_rate_alt: Option<Percentage>,
+    /// Super-reduced tax rate, if the country has one
+    pub super_reduced_rate: Option<Percentage>,
+    /// Parking rate, if the country has one
+    pub parking_rate: Option<Percentage>,
+    /// Full name of the VAT system
+    pub vat_name: Option<&'static str>,
+    /// Abbreviation of the VAT system name
+    pub vat_abbr: Option<&'static str>,
+    /// States or provinces, sorted by code
+    pub states: &'static [StateRecord],
+}
+
+/// The rate of a state or province.
+#[derive(Debug, PartialEq)]
+pub struct StateRecord {
+    /// ISO 3166-2 code of the state (e.g. "US-CA")
+    pub code: &'static str,
+    /// Type of tax system used in the state
+    pub tax_type: TaxSystemType,
+    /// Standard tax rate of the state
+    pub standard_rate: Percentage,
+    /// Whether local jurisdictions levy their own sales tax
+    pub local_taxes: bool,
+    /// Whether sellers without nexus must notify buyers of their use tax
+    pub notice_and_report: bool,
+}
+
+/// All countries of the embedded dataset, sorted by code.
+pub static COUNTRIES: &[CountryRecord] = include!(concat!(env!("OUT_DIR"), "/countries.rs"));
+
+/// Looks up a country of the static table.
+///
+/// # Examples
+///
+/// ```
+/// # use world_tax::table;
+/// # use world_tax::VatRate;
+/// let germany = table::country("DE").unwrap();
+/// assert_eq!(germany.rate(VatRate::Standard).unwrap().to_string(), "19%");
+///
+/// let california = table::country("US").unwrap().state("US-CA").unwrap();
+/// assert_eq!(california.standard_rate.to_string(), "8.25%");
+/// ```
+pub fn country(code: &str) -> Option<&'static CountryRecord> {
+    COUNTRIES
+        .binary_search_by(|country| country.code.cmp(code))
+        .ok()
+        .map(|index| &COUNTRIES[index])
+}
+
+impl CountryRecord {
+    /// Looks up a state or province of the country; bare or lower case codes ("bc")
+    /// are normalized.
+    pub fn state(&self, code: &str) -> Option<&'static StateRecord> {
+        let states = self.states;
+        let find = |code: &str| {
+            states
+                .binary_search_by(|state| state.code.cmp(code))
+                .ok()
+                .map(|index| &states[index])
+        };
+        find(code).or_else(|| find(&normalize_state_code(self.code, code)))
+    }
+
+    /// The rate of a VAT rate category, if the country has one; `Zero` is always 0%.
+    pub fn rate(&self, vat_rate: VatRate) -> Option<Percentage> {
+        match vat_rate {
+            VatRate::Standard => Some(self.standard_rate),
+            VatRate::Reduced => self.reduced_rate,
+            VatRate::ReducedAlt => self.reduced_rate_alt,
+            VatRate::SuperReduced => self.super_reduced_rate,
+            VatRate::Zero => Some(Percentage::ZERO),
+            VatRate::Exempt | VatRate::ReverseCharge => None,
+        }
+    }
+}
@@ -0,0 +1,50 @@
+#[cfg(all(test, feature = "static-table"))]
+mod tests {
+    use crate::table::{self, COUNTRIES};
+    use crate::{TaxDatabase, VatRate};
+
+    #[test]
+    fn test_table_matches_embedded_data() {
+        let db = TaxDatabase::new().expect("Embedded data should load");
+        assert_eq!(COUNTRIES.len(), db.countries.len());
+        assert!(COUNTRIES.windows(2).all(|pair| pair[0].code < pair[1].code));
+
+        for record in COUNTRIES {
+            let view = db.get_country(record.code).expect("Country is in the data");
+            assert_eq!(record.tax_type, *view.tax_system(), "{}", record.code);
+            assert_eq!(record.currency, view.currency());
+            assert_eq!(record.vat_name, view.vat_name());
+            for vat_rate in [
+                VatRate::Standard,
+                VatRate::Reduced,
+                VatRate::ReducedAlt,
+                VatRate::SuperReduced,
+            ] {
+                assert_eq!(
+                    record.rate(vat_rate),
+                    view.rate(&vat_rate),
+                    "{}",
+                    record.code
+                );
+            }
+            assert_eq!(record.parking_rate, view.parking_rate());
+
+            assert_eq!(record.states.len(), view.states().count());
+            for state in view.states() {
+                let state_record = record.state(state.code()).expect("State is in the table");
+                assert_eq!(state_record.standard_rate, state.standard_rate());
+                assert_eq!(state_record.local_taxes, state.has_local_taxes());
+            }
+        }
+    }
+
+    #[test]
+    fn test_unknown_codes() {
+        assert!(table::country("XX").is_none());
+        assert!(table::country("CA").unwrap().state("US-CA").is_none());
+        assert_eq!(
+            table::country("CA").unwrap().state("bc").unwrap().code,
+            "CA-BC"
+        );
+    }
+}
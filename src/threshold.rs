@@ -0,0 +1,170 @@
+//! Cumulative turnover for annual thresholds.
+//!
+//! Thresholds like the EU distance selling threshold (€10,000) or US economic nexus
+//! ($100,000) are based on the seller's turnover over a calendar year, not on a single
+//! transaction. `ThresholdTracker` accumulates sales per jurisdiction and year, and
+//! sets the turnover of a scenario (`TaxScenario::turnover`) before it is calculated.
+//!
+//! The jurisdiction of a sale is where its threshold applies: the trade agreement for
+//! cross-border sales between members (the EU threshold covers sales into all other
+//! member states), or else the destination state or country.
+
+use std::collections::{BTreeMap, HashMap};
+
+use serde::{Deserialize, Serialize};
+
+use crate::certificate::is_iso_date;
+use crate::errors::InputValidationError;
+use crate::rate_provider::RateProvider;
+use crate::types::AnnualTurnover;
+use crate::TaxScenario;
+
+/// Accumulates a seller's sales, and their number, per jurisdiction and calendar year.
+///
+/// Amounts are recorded as given; record them in the currency the scenarios state
+/// their amounts in.
+///
+/// # Examples
+///
+/// ```
+/// # use world_tax::threshold::ThresholdTracker;
+/// # use world_tax::{Region, TaxCalculationType, TaxDatabase, TaxScenario, TransactionType};
+/// let db = TaxDatabase::fixture();
+/// let scenario = TaxScenario::new(
+///     Region::parse("DE").unwrap(),
+///     Region::parse("FR").unwrap(),
+///     TransactionType::B2C,
+/// );
+///
+/// let mut tracker = ThresholdTracker::new();
+/// tracker.record(&scenario, 9_500.0, "2026-03-01", &db).unwrap();
+///
+/// // A sale into another member state takes the seller over the EU threshold
+/// let tracked = tracker.track(scenario, "2026-04-01", &db).unwrap();
+/// assert_eq!(
+///     tracked.determine_calculation_type(&db, 1_000.0).unwrap(),
+///     TaxCalculationType::Destination
+/// );
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ThresholdTracker {
+    sales: HashMap<String, BTreeMap<i32, f64>>,
+    #[serde(default)]
+    transactions: HashMap<String, BTreeMap<i32, u32>>,
+}
+
+impl ThresholdTracker {
+    /// Creates a tracker without sales.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a sale on a date (ISO 8601).
+    ///
+    /// # Errors
+    ///
+    /// Returns `InputValidationError::InvalidDate` if the date isn't an ISO 8601 date (YYYY-MM-DD).
+    pub fn record<P: RateProvider + ?Sized>(
+        &mut self,
+        scenario: &TaxScenario,
+        amount: f64,
+        date: &str,
+        db: &P,
+    ) -> Result<(), InputValidationError> {
+        let year = year_of(date)?;
+        let jurisdiction = jurisdiction(scenario, db);
+        *self
+            .transactions
+            .entry(jurisdiction.clone())
+            .or_default()
+            .entry(year)
+            .or_default() += 1;
+        *self
+            .sales
+            .entry(jurisdiction)
+            .or_default()
+            .entry(year)
+            .or_default() += amount;
+        Ok(())
+    }
+
+    /// The turnover recorded in a jurisdiction in a year.
+    pub fn turnover_in(&self, jurisdiction: &str, year: i32) -> f64 {
+        self.sales
+            .get(jurisdiction)
+            .and_then(|years| years.get(&year))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// The number of sales recorded in a jurisdiction in a year.
+    pub fn transactions_in(&self, jurisdiction: &str, year: i32) -> u32 {
+        self.transactions
+            .get(jurisdiction)
+            .and_then(|years| years.get(&year))
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// The turnover in the jurisdiction of a scenario, for a sale on a date.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InputValidationError::InvalidDate` if the date isn't an ISO 8601 date (YYYY-MM-DD).
+    pub fn turnover<P: RateProvider + ?Sized>(
+        &self,
+        scenario: &TaxScenario,
+        date: &str,
+        db: &P,
+    ) -> Result<AnnualTurnover, InputValidationError> {
+        let year = year_of(date)?;
+        let jurisdiction = jurisdiction(scenario, db);
+        Ok(AnnualTurnover {
+            current_year: self.turnover_in(&jurisdiction, year),
+            previous_year: self.turnover_in(&jurisdiction, year - 1),
+            current_year_transactions: self.transactions_in(&jurisdiction, year),
+            previous_year_transactions: self.transactions_in(&jurisdiction, year - 1),
+        })
+    }
+
+    /// Sets the scenario's turnover for a sale on a date.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InputValidationError::InvalidDate` if the date isn't an ISO 8601 date (YYYY-MM-DD).
+    pub fn track<P: RateProvider + ?Sized>(
+        &self,
+        scenario: TaxScenario,
+        date: &str,
+        db: &P,
+    ) -> Result<TaxScenario, InputValidationError> {
+        let turnover = self.turnover(&scenario, date, db)?;
+        Ok(scenario.with_turnover(turnover))
+    }
+}
+
+/// The jurisdiction whose threshold a sale counts towards: the agreement of a
+/// cross-border sale between members, or else the destination state or country.
+pub fn jurisdiction<P: RateProvider + ?Sized>(scenario: &TaxScenario, db: &P) -> String {
+    let destination = &scenario.destination_region;
+    if !scenario.is_same_country() {
+        if let Some(agreement) =
+            db.get_international_rule(&scenario.source_region.country, &destination.country)
+        {
+            return agreement.name;
+        }
+    }
+    destination
+        .region
+        .clone()
+        .unwrap_or_else(|| destination.country.clone())
+}
+
+fn year_of(date: &str) -> Result<i32, InputValidationError> {
+    if !is_iso_date(date) {
+        return Err(InputValidationError::InvalidDate(date.to_string()));
+    }
+    date[..4]
+        .parse()
+        .map_err(|_| InputValidationError::InvalidDate(date.to_string()))
+}
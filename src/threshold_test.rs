@@ -0,0 +1,132 @@
+#[cfg(test)]
+mod tests {
+    use crate::threshold::{jurisdiction, ThresholdTracker};
+    use crate::types::AnnualTurnover;
+    use crate::{
+        InputValidationError, Region, TaxCalculationType, TaxDatabase, TaxScenario, TransactionType,
+    };
+
+    fn setup() -> TaxDatabase {
+        TaxDatabase::from_files("vat_rates.json", "trade_agreements.json")
+            .expect("Tax database should load")
+    }
+
+    fn scenario(source: &str, destination: &str) -> TaxScenario {
+        TaxScenario::new(
+            Region::parse(source).expect("Valid region"),
+            Region::parse(destination).expect("Valid region"),
+            TransactionType::B2C,
+        )
+    }
+
+    #[test]
+    fn test_jurisdiction() {
+        let db = setup();
+        assert_eq!(jurisdiction(&scenario("DE", "FR"), &db), "European Union");
+        assert_eq!(jurisdiction(&scenario("DE", "DE"), &db), "DE");
+        assert_eq!(jurisdiction(&scenario("US-TX", "US-CA"), &db), "US-CA");
+        assert_eq!(jurisdiction(&scenario("DE", "US-CA"), &db), "US-CA");
+    }
+
+    #[test]
+    fn test_eu_sales_accumulate_across_member_states() {
+        let db = setup();
+        let mut tracker = ThresholdTracker::new();
+        tracker
+            .record(&scenario("DE", "FR"), 6_000.0, "2026-02-01", &db)
+            .unwrap();
+        tracker
+            .record(&scenario("DE", "IT"), 3_000.0, "2026-03-01", &db)
+            .unwrap();
+        // Domestic sales don't count towards the distance selling threshold
+        tracker
+            .record(&scenario("DE", "DE"), 50_000.0, "2026-03-01", &db)
+            .unwrap();
+
+        let sale = scenario("DE", "AT");
+        let below = tracker.track(sale.clone(), "2026-04-01", &db).unwrap();
+        assert_eq!(
+            below.determine_calculation_type(&db, 500.0).unwrap(),
+            TaxCalculationType::Origin
+        );
+        assert_eq!(
+            below.determine_calculation_type(&db, 1_000.0).unwrap(),
+            TaxCalculationType::Destination
+        );
+
+        // Without the turnover, a sale isn't taken for the year's sales
+        assert_eq!(
+            sale.determine_calculation_type(&db, 1_000.0).unwrap(),
+            TaxCalculationType::Destination
+        );
+    }
+
+    #[test]
+    fn test_previous_year_above_threshold() {
+        let db = setup();
+        let mut tracker = ThresholdTracker::new();
+        tracker
+            .record(&scenario("DE", "FR"), 12_000.0, "2025-11-30", &db)
+            .unwrap();
+
+        let tracked = tracker
+            .track(scenario("DE", "FR"), "2026-01-02", &db)
+            .unwrap();
+        assert_eq!(
+            tracked.turnover,
+            Some(AnnualTurnover {
+                current_year: 0.0,
+                previous_year: 12_000.0,
+                current_year_transactions: 0,
+                previous_year_transactions: 1,
+            })
+        );
+        assert_eq!(
+            tracked.determine_calculation_type(&db, 10.0).unwrap(),
+            TaxCalculationType::Destination
+        );
+
+        // Two years later, the sales no longer count
+        let tracked = tracker
+            .track(scenario("DE", "FR"), "2027-01-02", &db)
+            .unwrap();
+        assert_eq!(
+            tracked.determine_calculation_type(&db, 10.0).unwrap(),
+            TaxCalculationType::Origin
+        );
+    }
+
+    #[test]
+    fn test_us_nexus_per_state() {
+        let db = setup();
+        let mut tracker = ThresholdTracker::new();
+        tracker
+            .record(&scenario("US-TX", "US-FL"), 99_950.0, "2026-05-01", &db)
+            .unwrap();
+
+        let florida = tracker
+            .track(scenario("US-TX", "US-FL"), "2026-06-01", &db)
+            .unwrap();
+        assert_eq!(
+            florida.determine_calculation_type(&db, 100.0).unwrap(),
+            TaxCalculationType::Destination
+        );
+        let washington = tracker
+            .track(scenario("US-TX", "US-WA"), "2026-06-01", &db)
+            .unwrap();
+        assert_ne!(
+            washington.determine_calculation_type(&db, 100.0).unwrap(),
+            TaxCalculationType::Destination
+        );
+    }
+
+    #[test]
+    fn test_invalid_date() {
+        let db = setup();
+        let mut tracker = ThresholdTracker::new();
+        assert!(matches!(
+            tracker.record(&scenario("DE", "FR"), 1.0, "01/02/2026", &db),
+            Err(InputValidationError::InvalidDate(_))
+        ));
+    }
+}
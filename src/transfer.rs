@@ -0,0 +1,179 @@
+//! Intra-company stock transfers.
+//!
+//! Moving own goods between a company's warehouses isn't a sale, but within the EU
+//! it's treated as one: the transfer is a deemed intra-Community supply, exempt in
+//! the origin state and reported there, and a deemed intra-Community acquisition in
+//! the destination state, where the company must register and self-assess VAT.
+//! Under the call-off stock simplification (goods held for a known customer), no
+//! registration in the destination is needed.
+//!
+//! Transfers outside a customs union are an export and an import instead; transfers
+//! within a country have no VAT consequences.
+
+use serde::Serialize;
+
+use crate::types::VatRate;
+use crate::{ProcessingError, Region, TaxDatabase, TaxRate, TaxScenario, TransactionType};
+
+/// How a stock transfer is treated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransferTreatment {
+    /// Within a country; no VAT consequences
+    Domestic,
+    /// Between countries of a customs union (e.g. the EU); deemed supply and acquisition
+    DeemedIntraUnionSupply,
+    /// Between customs territories; an export and an import
+    ExportAndImport,
+}
+
+/// Something that has to be reported for a stock transfer.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", content = "content")]
+pub enum TransferReport {
+    /// The deemed supply in the recapitulative statement (EC Sales List) of the origin
+    RecapitulativeStatement {
+        /// The origin country
+        country: String,
+    },
+    /// The deemed acquisition in the VAT return of the destination
+    AcquisitionInVatReturn {
+        /// The destination country
+        country: String,
+    },
+    /// The transfer in the call-off stock register, in the origin and destination
+    CallOffStockRegister,
+    /// The import in the destination, with import VAT
+    ImportDeclaration {
+        /// The destination country
+        country: String,
+    },
+}
+
+/// A transfer of own goods between warehouses.
+#[derive(Debug, Clone)]
+pub struct StockTransfer {
+    /// Where the goods leave from
+    pub from: Region,
+    /// Where the goods are moved to
+    pub to: Region,
+    /// Value of the goods (purchase or cost price)
+    pub value: f64,
+    /// Whether the goods are call-off stock for a known customer
+    pub call_off_stock: bool,
+    /// The VAT rate of the goods; standard if not set
+    pub vat_rate: Option<VatRate>,
+}
+
+/// The VAT treatment of a stock transfer.
+#[derive(Debug, Clone, Serialize)]
+pub struct TransferOutcome {
+    /// How the transfer is treated
+    pub treatment: TransferTreatment,
+    /// The country the company must register for VAT in, if any
+    pub registration_required_in: Option<String>,
+    /// The rates of the self-assessed acquisition VAT or import VAT in the destination
+    pub rates: Vec<TaxRate>,
+    /// Self-assessed acquisition VAT, or import VAT, in the destination; usually deductible
+    pub vat: f64,
+    /// What has to be reported
+    pub reports: Vec<TransferReport>,
+}
+
+impl StockTransfer {
+    /// Creates a transfer of goods of the given value.
+    pub fn new(from: Region, to: Region, value: f64) -> Self {
+        Self {
+            from,
+            to,
+            value,
+            call_off_stock: false,
+            vat_rate: None,
+        }
+    }
+
+    /// Determines the treatment of the transfer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use world_tax::transfer::{StockTransfer, TransferTreatment};
+    /// # use world_tax::{Region, TaxDatabase};
+    /// let db = TaxDatabase::fixture();
+    /// let transfer = StockTransfer::new(
+    ///     Region::new("FR".to_string(), None).unwrap(),
+    ///     Region::new("DE".to_string(), None).unwrap(),
+    ///     10000.0,
+    /// );
+    ///
+    /// let outcome = transfer.outcome(&db).unwrap();
+    /// assert_eq!(outcome.treatment, TransferTreatment::DeemedIntraUnionSupply);
+    /// assert_eq!(outcome.registration_required_in.as_deref(), Some("DE"));
+    /// assert_eq!(outcome.vat, 1900.0);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the destination's rates can't be resolved.
+    pub fn outcome(&self, db: &TaxDatabase) -> Result<TransferOutcome, ProcessingError> {
+        let (from, to) = (&self.from.country, &self.to.country);
+        let treatment = if from == to {
+            TransferTreatment::Domestic
+        } else if db.get_international_rule(from, to).is_some() {
+            TransferTreatment::DeemedIntraUnionSupply
+        } else {
+            TransferTreatment::ExportAndImport
+        };
+
+        let (registration_required_in, reports) = match treatment {
+            TransferTreatment::Domestic => (None, Vec::new()),
+            TransferTreatment::DeemedIntraUnionSupply if self.call_off_stock => (
+                None,
+                vec![
+                    TransferReport::CallOffStockRegister,
+                    TransferReport::RecapitulativeStatement {
+                        country: from.clone(),
+                    },
+                ],
+            ),
+            TransferTreatment::DeemedIntraUnionSupply => (
+                Some(to.clone()),
+                vec![
+                    TransferReport::RecapitulativeStatement {
+                        country: from.clone(),
+                    },
+                    TransferReport::AcquisitionInVatReturn {
+                        country: to.clone(),
+                    },
+                ],
+            ),
+            TransferTreatment::ExportAndImport => (
+                None,
+                vec![TransferReport::ImportDeclaration {
+                    country: to.clone(),
+                }],
+            ),
+        };
+
+        // Call-off stock is acquired by the customer, not the company
+        let vat_due =
+            registration_required_in.is_some() || treatment == TransferTreatment::ExportAndImport;
+        let rates = if vat_due {
+            db.get_rate(to, self.to.region.as_deref(), self.vat_rate.as_ref())?
+        } else {
+            Vec::new()
+        };
+        let mut scenario =
+            TaxScenario::new(self.from.clone(), self.to.clone(), TransactionType::B2B);
+        scenario.vat_rate = self.vat_rate;
+        let vat = scenario.sum_rates(self.value, &rates, db)?;
+
+        Ok(TransferOutcome {
+            treatment,
+            registration_required_in,
+            rates,
+            vat,
+            reports,
+        })
+    }
+}
@@ -0,0 +1,65 @@
+#[cfg(test)]
+mod tests {
+    use crate::transfer::{StockTransfer, TransferReport, TransferTreatment};
+    use crate::types::VatRate;
+    use crate::{Region, TaxDatabase};
+
+    fn region(code: &str) -> Region {
+        Region::parse(code).expect("Valid region")
+    }
+
+    #[test]
+    fn test_intra_union_transfer() {
+        let db = TaxDatabase::fixture();
+        let mut transfer = StockTransfer::new(region("DE"), region("FR"), 1000.0);
+        transfer.vat_rate = Some(VatRate::Reduced);
+
+        let outcome = transfer.outcome(&db).expect("Transfer should be treated");
+        assert_eq!(outcome.treatment, TransferTreatment::DeemedIntraUnionSupply);
+        assert_eq!(outcome.registration_required_in.as_deref(), Some("FR"));
+        assert_eq!(outcome.vat, 100.0); // French reduced rate, self-assessed
+        assert_eq!(
+            outcome.reports,
+            vec![
+                TransferReport::RecapitulativeStatement {
+                    country: "DE".to_string()
+                },
+                TransferReport::AcquisitionInVatReturn {
+                    country: "FR".to_string()
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_call_off_stock() {
+        let db = TaxDatabase::fixture();
+        let mut transfer = StockTransfer::new(region("DE"), region("FR"), 1000.0);
+        transfer.call_off_stock = true;
+
+        let outcome = transfer.outcome(&db).expect("Transfer should be treated");
+        assert_eq!(outcome.treatment, TransferTreatment::DeemedIntraUnionSupply);
+        assert!(outcome.registration_required_in.is_none());
+        assert_eq!(outcome.vat, 0.0);
+        assert!(outcome
+            .reports
+            .contains(&TransferReport::CallOffStockRegister));
+    }
+
+    #[test]
+    fn test_domestic_and_cross_border_transfers() {
+        let db = TaxDatabase::fixture();
+        let outcome = StockTransfer::new(region("DE"), region("DE"), 1000.0)
+            .outcome(&db)
+            .expect("Transfer should be treated");
+        assert_eq!(outcome.treatment, TransferTreatment::Domestic);
+        assert!(outcome.reports.is_empty());
+
+        let outcome = StockTransfer::new(region("CA-ON"), region("DE"), 1000.0)
+            .outcome(&db)
+            .expect("Transfer should be treated");
+        assert_eq!(outcome.treatment, TransferTreatment::ExportAndImport);
+        assert!(outcome.registration_required_in.is_none());
+        assert_eq!(outcome.vat, 190.0); // German import VAT
+    }
+}
@@ -5,16 +5,26 @@
 //! the fundamental data structures and enums needed to represent tax scenarios,
 //! trade agreements, and calculation rules.
 
-use crate::errors::InputValidationError;
+use crate::alias::{alpha2, subdivision_alias};
+use crate::certificate::{CertificateKind, ExemptionCertificate};
+use crate::currency::CurrencyCode;
+use crate::errors::{suggestions, DatabaseError, InputValidationError};
+use crate::percentage::Percentage;
+use crate::vat_id::VatIdCheck;
 use log::debug;
+use rust_decimal::Decimal;
 use serde::{Deserialize, Deserializer, Serialize};
-use std::collections::HashMap;
+use serde_json::Value;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
 use strum_macros::Display;
 use typeshare::typeshare;
 
 /// Represents different types of tax systems used globally.
 #[typeshare]
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(rename_all = "lowercase")]
 pub enum TaxSystemType {
     /// Value Added Tax - Common in EU and many other countries
@@ -27,6 +37,8 @@ pub enum TaxSystemType {
     Hst,
     /// Quebec Sales Tax
     Qst,
+    /// Brazilian state tax on goods (ICMS), with federal and municipal taxes alongside
+    Icms,
     /// No tax system applicable
     None,
 }
@@ -34,6 +46,7 @@ pub enum TaxSystemType {
 /// Defines the type of transaction between parties.
 #[typeshare]
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(rename_all = "lowercase")]
 pub enum TransactionType {
     /// Business to Business transaction
@@ -42,9 +55,74 @@ pub enum TransactionType {
     B2C,
 }
 
+/// Who the buyer is; decides the transaction type and which certificate the buyer
+/// may present (see `TaxScenario::for_buyer`).
+#[typeshare]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(rename_all = "snake_case")]
+pub enum BuyerType {
+    /// A consumer
+    Individual,
+    /// A business buying for its own use
+    Business,
+    /// A non-profit organization; exempt with an exemption certificate in the US
+    NonProfit,
+    /// A government body; exempt with an exemption certificate in the US
+    Government,
+    /// A business buying for resale; exempt with a resale certificate in the US
+    Reseller,
+}
+
+impl BuyerType {
+    /// The transaction type of a sale to the buyer.
+    pub fn transaction_type(&self) -> TransactionType {
+        match self {
+            BuyerType::Individual => TransactionType::B2C,
+            _ => TransactionType::B2B,
+        }
+    }
+
+    /// The kind of certificate the buyer may present, if any.
+    pub fn certificate_kind(&self) -> Option<CertificateKind> {
+        match self {
+            BuyerType::Individual | BuyerType::Business => None,
+            BuyerType::NonProfit | BuyerType::Government => Some(CertificateKind::Exemption),
+            BuyerType::Reseller => Some(CertificateKind::Resale),
+        }
+    }
+}
+
+/// Delivery terms of a cross-border shipment (Incoterms 2020), as far as they
+/// decide who pays the import charges.
+#[typeshare]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(rename_all = "UPPERCASE")]
+pub enum Incoterm {
+    /// Ex Works; the buyer collects the goods at the seller's premises
+    Exw,
+    /// Free Carrier; the seller hands the goods to the buyer's carrier
+    Fca,
+    /// Delivered At Place (formerly DDU); the buyer pays import charges
+    #[default]
+    #[serde(alias = "DDU")]
+    Dap,
+    /// Delivered Duty Paid; the seller pays import charges
+    Ddp,
+}
+
+impl Incoterm {
+    /// Returns true if the seller pays duty and import taxes.
+    pub fn seller_pays_import_charges(&self) -> bool {
+        *self == Incoterm::Ddp
+    }
+}
+
 /// Specifies how tax should be calculated for a given transaction.
 #[typeshare]
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(rename_all = "snake_case")]
 pub enum TaxCalculationType {
     /// Use origin tax rate; below threshold
@@ -61,11 +139,15 @@ pub enum TaxCalculationType {
     None,
     /// Calculation depends on threshold
     ThresholdBased,
+    /// No tax collected, but the destination requires the seller to notify the buyer
+    /// of the use tax owed and to report the sale (US notice-and-report states)
+    NoticeAndReport,
 }
 
 /// Represents different types of taxes that can be applied.
 #[typeshare]
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(tag = "type", content = "content")]
 #[serde(rename_all = "snake_case")]
 pub enum TaxType {
@@ -81,11 +163,23 @@ pub enum TaxType {
     QST,
     /// US State Sales Tax
     StateSalesTax,
+    /// Local (county, city, district) sales tax
+    LocalSalesTax,
+    /// Brazilian state tax on goods (Imposto sobre Circulação de Mercadorias e Serviços)
+    ICMS,
+    /// Brazilian municipal tax on services (Imposto Sobre Serviços)
+    ISS,
+    /// Brazilian federal social contribution (Programa de Integração Social)
+    PIS,
+    /// Brazilian federal social contribution (Contribuição para o Financiamento da
+    /// Seguridade Social)
+    COFINS,
 }
 
 /// Different rates that can be applied for Value Added Tax.
 #[typeshare]
-#[derive(Debug, Clone, Display, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Copy, Display, Serialize, Deserialize, PartialEq)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(rename_all = "snake_case")]
 pub enum VatRate {
     /// Standard VAT rate
@@ -107,6 +201,7 @@ pub enum VatRate {
 /// Defines the type of trade agreement between regions.
 #[typeshare]
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(rename_all = "snake_case")]
 pub enum TradeAgreementType {
     /// Agreement between multiple countries in a customs union (e.g., EU)
@@ -118,6 +213,7 @@ pub enum TradeAgreementType {
 /// Override options for trade agreement application.
 #[typeshare]
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 #[serde(tag = "type", content = "content")]
 pub enum TradeAgreementOverride {
     /// Explicitly use a specific agreement (e.g., "EU", "USMCA")
@@ -129,6 +225,7 @@ pub enum TradeAgreementOverride {
 /// Specifies which types of goods/services an agreement applies to.
 #[typeshare]
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct AppliesTo {
     /// Whether the agreement applies to physical goods
     pub physical_goods: bool,
@@ -141,6 +238,7 @@ pub struct AppliesTo {
 /// Represents a trade agreement between regions or states.
 #[typeshare]
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct TradeAgreement {
     /// Name of the trade agreement
     pub name: String,
@@ -148,15 +246,72 @@ pub struct TradeAgreement {
     pub r#type: TradeAgreementType,
     /// List of member regions/states
     pub members: Vec<String>,
+    /// Members that haven't implemented the agreement's tax rules (e.g. GCC members
+    /// without VAT); transactions involving them are exports and imports
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub non_implementing_members: Vec<String>,
+    /// Countries that aren't members, though commonly taken for members (e.g. the EFTA
+    /// states for the EU); sales between them and members are flagged with
+    /// `Warning::NotAgreementMember`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub non_members: Vec<String>,
     /// Whether agreement applies by default
     pub default_applicable: bool,
     /// Types of goods/services covered
     pub applies_to: AppliesTo,
     /// Tax rules under this agreement
     pub tax_rules: TaxRules,
+    /// When members joined or left, for calculations as of a date; former members are
+    /// only listed here
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub membership_periods: HashMap<String, MembershipPeriod>,
+    /// Extension fields (`x-*`) with custom metadata, kept as loaded
+    #[cfg_attr(feature = "arbitrary", arbitrary(default))]
+    #[serde(
+        flatten,
+        default,
+        deserialize_with = "extension_fields",
+        skip_serializing_if = "BTreeMap::is_empty"
+    )]
+    pub(crate) extensions: BTreeMap<String, Value>,
+}
+
+/// Keeps the extension fields (`x-*`) of the fields a dataset entry doesn't know.
+fn extension_fields<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<BTreeMap<String, Value>, D::Error> {
+    let mut fields = BTreeMap::<String, Value>::deserialize(deserializer)?;
+    fields.retain(|name, _| name.starts_with("x-"));
+    Ok(fields)
+}
+
+/// The dates a country was a member of a trade agreement (ISO 8601, inclusive).
+#[typeshare]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct MembershipPeriod {
+    /// First day of the membership; without one, since the start
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub joined: Option<String>,
+    /// Last day of the membership; without one, still a member
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub left: Option<String>,
+}
+
+impl MembershipPeriod {
+    /// Returns true if the country was a member on the date (ISO 8601).
+    pub fn contains(&self, date: &str) -> bool {
+        self.joined.as_deref().is_none_or(|joined| joined <= date)
+            && self.left.as_deref().is_none_or(|left| date <= left)
+    }
 }
 
 impl TradeAgreement {
+    /// The agreement's extension fields (`x-*`), like internal tax codes.
+    pub fn extensions(&self) -> &BTreeMap<String, Value> {
+        &self.extensions
+    }
+
     /// Returns true if this is a federal-level agreement
     pub fn is_federal(&self) -> bool {
         self.r#type == TradeAgreementType::FederalState
@@ -166,11 +321,233 @@ impl TradeAgreement {
     pub fn is_international(&self) -> bool {
         self.r#type == TradeAgreementType::CustomsUnion
     }
+
+    /// The agreement as it was on a date (ISO 8601): its members then, and the rules in
+    /// effect.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use world_tax::TaxDatabase;
+    /// let db = TaxDatabase::new().unwrap();
+    /// let eu = db.get_rule("EU").unwrap();
+    /// assert!(!eu.members.contains(&"GB".to_string()));
+    /// assert!(eu.as_of("2020-12-31").members.contains(&"GB".to_string()));
+    /// assert!(!eu.as_of("2013-06-30").members.contains(&"HR".to_string()));
+    /// ```
+    pub fn as_of(&self, date: &str) -> TradeAgreement {
+        let mut agreement = self.clone();
+        agreement.tax_rules = self.tax_rules.as_of(date).clone();
+        agreement.members.retain(|member| {
+            self.membership_periods
+                .get(member)
+                .is_none_or(|period| period.contains(date))
+        });
+        let mut former: Vec<&String> = self
+            .membership_periods
+            .iter()
+            .filter(|(member, period)| !self.members.contains(member) && period.contains(date))
+            .map(|(member, _)| member)
+            .collect();
+        former.sort();
+        agreement.members.extend(former.into_iter().cloned());
+        agreement
+    }
+
+    /// Returns true if the region is a member that implemented the agreement's tax rules.
+    pub fn is_implemented_by(&self, region: &str) -> bool {
+        self.members.iter().any(|member| member == region)
+            && !self
+                .non_implementing_members
+                .iter()
+                .any(|member| member == region)
+    }
+}
+
+/// What a threshold amount is compared against.
+#[typeshare]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(rename_all = "snake_case")]
+pub enum ThresholdBasis {
+    /// The amount of the single transaction being calculated
+    #[default]
+    Transaction,
+    /// The seller's cumulative turnover in the jurisdiction over a calendar year
+    AnnualTurnover,
+}
+
+/// The seller's turnover in the jurisdiction of a threshold, compared against
+/// `ThresholdBasis::AnnualTurnover` thresholds instead of the transaction amount.
+///
+/// Tracked by `threshold::ThresholdTracker`, or taken from the seller's books.
+#[typeshare]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct AnnualTurnover {
+    /// Turnover of the current calendar year, before the transaction
+    pub current_year: f64,
+    /// Turnover of the previous calendar year
+    #[serde(default)]
+    pub previous_year: f64,
+    /// Number of sales in the current calendar year, before the transaction
+    #[serde(default)]
+    pub current_year_transactions: u32,
+    /// Number of sales in the previous calendar year
+    #[serde(default)]
+    pub previous_year_transactions: u32,
+}
+
+impl AnnualTurnover {
+    /// The amount compared against an annual threshold for a transaction: the previous
+    /// year's turnover, or the current year's including the transaction, whichever is
+    /// higher. A threshold exceeded in the previous year applies for the whole year.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use world_tax::types::AnnualTurnover;
+    /// let turnover = AnnualTurnover { current_year: 9_500.0, previous_year: 4_000.0, ..Default::default() };
+    /// assert_eq!(turnover.with_transaction(1_000.0), 10_500.0);
+    ///
+    /// let turnover = AnnualTurnover { current_year: 0.0, previous_year: 12_000.0, ..Default::default() };
+    /// assert_eq!(turnover.with_transaction(100.0), 12_000.0);
+    /// ```
+    pub fn with_transaction(&self, amount: f64) -> f64 {
+        self.previous_year.max(self.current_year + amount)
+    }
+
+    /// The number of sales compared against a transaction threshold, like
+    /// `with_transaction`: the previous year's, or the current year's including the
+    /// transaction, whichever is higher.
+    pub fn transactions_with_transaction(&self) -> u32 {
+        self.previous_year_transactions
+            .max(self.current_year_transactions.saturating_add(1))
+    }
+}
+
+/// How thresholds of tax rules are applied to a scenario.
+#[typeshare]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(rename_all = "snake_case")]
+pub enum ThresholdPolicy {
+    /// Compare the amount against the threshold
+    #[default]
+    Respect,
+    /// Treat the amount as above the threshold (e.g. the seller registered at the destination)
+    AssumeAbove,
+    /// Treat the amount as below the threshold (e.g. the seller opted out of
+    /// destination registration, and taxes at origin)
+    AssumeBelow,
+}
+
+impl ThresholdPolicy {
+    /// Returns true if the amount counts as below the threshold.
+    pub fn is_below(&self, amount: Decimal, threshold: Decimal) -> bool {
+        match self {
+            ThresholdPolicy::Respect => amount < threshold,
+            ThresholdPolicy::AssumeAbove => false,
+            ThresholdPolicy::AssumeBelow => true,
+        }
+    }
+}
+
+/// A monetary threshold of a tax rule.
+///
+/// Amounts of scenarios stated in another currency are converted with the provider's
+/// exchange rates before they're compared (see `TaxDatabase::set_exchange_rates`).
+#[typeshare]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct Threshold {
+    /// The threshold amount
+    #[serde(with = "amount")]
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = amount::arbitrary))]
+    pub amount: Decimal,
+    /// What the amount is compared against
+    #[serde(default)]
+    pub basis: ThresholdBasis,
+    /// Currency of the amount (e.g. "EUR"); without one, amounts are compared as given
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub currency: Option<CurrencyCode>,
+}
+
+impl Threshold {
+    /// Returns true if an amount counts as below the threshold under a policy.
+    ///
+    /// The amount must be in the threshold's currency, unless the threshold is 0;
+    /// without a currency, it's compared as given.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rust_decimal_macros::dec;
+    /// # use world_tax::currency::CurrencyCode;
+    /// # use world_tax::types::{Threshold, ThresholdBasis, ThresholdPolicy};
+    /// let eur = CurrencyCode::parse("EUR").ok();
+    /// let threshold = Threshold { amount: dec!(10_000), basis: ThresholdBasis::AnnualTurnover, currency: eur };
+    ///
+    /// assert!(threshold.is_below(dec!(9_999.99), eur, ThresholdPolicy::Respect).unwrap());
+    /// assert!(!threshold.is_below(dec!(10_000), None, ThresholdPolicy::Respect).unwrap());
+    /// let usd = CurrencyCode::parse("USD").ok();
+    /// assert!(threshold.is_below(dec!(100), usd, ThresholdPolicy::Respect).is_err());
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::CurrencyMismatch` if the policy compares an amount in
+    /// another currency.
+    pub fn is_below(
+        &self,
+        amount: Decimal,
+        currency: Option<CurrencyCode>,
+        policy: ThresholdPolicy,
+    ) -> Result<bool, DatabaseError> {
+        if let (ThresholdPolicy::Respect, Some(from), Some(to)) = (policy, currency, self.currency)
+        {
+            if from != to && !self.amount.is_zero() {
+                return Err(DatabaseError::CurrencyMismatch {
+                    amount: from.to_string(),
+                    threshold: to.to_string(),
+                });
+            }
+        }
+        Ok(policy.is_below(amount, self.amount))
+    }
+}
+
+/// (De)serializes a threshold amount as a JSON number, as the dataset states it.
+mod amount {
+    use rust_decimal::prelude::ToPrimitive;
+    use rust_decimal::Decimal;
+    use serde::{Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(amount: &Decimal, serializer: S) -> Result<S::Ok, S::Error> {
+        match amount.to_u64() {
+            Some(whole) if amount.fract().is_zero() => serializer.serialize_u64(whole),
+            _ => serializer.serialize_f64(amount.to_f64().unwrap_or_default()),
+        }
+    }
+
+    /// Reads the exact amount of a string (e.g. "10000.10"); a number is read as a float,
+    /// to the shortest decimal that stands for it. Trailing zeros aren't kept (10000.10
+    /// is 10000.1).
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Decimal, D::Error> {
+        rust_decimal::serde::float::deserialize(deserializer).map(|amount| amount.normalize())
+    }
+
+    /// Amounts up to 10 million, with cents.
+    #[cfg(feature = "arbitrary")]
+    pub fn arbitrary(u: &mut arbitrary::Unstructured) -> arbitrary::Result<Decimal> {
+        Ok(Decimal::new(u.int_in_range(0..=1_000_000_000)?, 2))
+    }
 }
 
 /// Configuration for tax calculation rules based on various thresholds and conditions.
 #[typeshare]
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct TaxRuleConfig {
     /// Default tax calculation type
     pub r#type: TaxCalculationType,
@@ -178,70 +555,130 @@ pub struct TaxRuleConfig {
     pub below_threshold: Option<TaxCalculationType>,
     /// Tax calculation type for amounts above threshold
     pub above_threshold: Option<TaxCalculationType>,
-    /// Monetary threshold for standard goods
-    pub threshold: Option<u32>,
+    /// Monetary threshold for standard goods, compared against the transaction amount
+    /// or the seller's annual turnover (`Threshold::basis`)
+    pub threshold: Option<Threshold>,
     /// Tax calculation type for digital products below threshold
     pub below_threshold_digital_products: Option<TaxCalculationType>,
     /// Tax calculation type for digital products above threshold
     pub above_threshold_digital_products: Option<TaxCalculationType>,
-    /// Monetary threshold for digital products
-    pub threshold_digital_products: Option<u32>,
+    /// Monetary threshold for digital products, like `threshold`; a transaction
+    /// threshold of 0 taxes every sale by `above_threshold_digital_products`
+    pub threshold_digital_products: Option<Threshold>,
     /// Whether a resale certificate is required for special treatment
     pub requires_resale_certificate: Option<bool>,
+    /// Legal basis of the rule (e.g. "Article 196, Council Directive 2006/112/EC")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub legal_reference: Option<String>,
 }
 
 impl TaxRuleConfig {
     /// Determines the tax calculation type based on the amount and threshold
     ///
     /// # Arguments
-    /// * `amount` - The transaction amount
-    /// * `ignore_threshold` - Whether to ignore threshold-based calculations
-    pub fn by_threshold(&self, amount: u32, ignore_threshold: bool) -> &TaxCalculationType {
-        let has_threshold = self.below_threshold.is_some()
-            && self.above_threshold.is_some()
-            && self.threshold.is_some();
-        if has_threshold {
-            let rule_threshold: u32 = self.threshold.unwrap();
-            if amount < rule_threshold && !ignore_threshold {
-                return self.below_threshold.as_ref().unwrap();
-            } else {
-                return self.above_threshold.as_ref().unwrap();
+    /// * `amount` - The amount compared against the threshold (see `Threshold::basis`)
+    /// * `currency` - Currency of the amount; without one, it's compared as given
+    /// * `policy` - How the threshold is applied
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::CurrencyMismatch` if the amount isn't in the threshold's
+    /// currency.
+    pub fn by_threshold(
+        &self,
+        amount: Decimal,
+        currency: Option<CurrencyCode>,
+        policy: ThresholdPolicy,
+    ) -> Result<&TaxCalculationType, DatabaseError> {
+        match (&self.below_threshold, &self.above_threshold, self.threshold) {
+            (Some(below), Some(above), Some(threshold)) => {
+                if threshold.is_below(amount, currency, policy)? {
+                    Ok(below)
+                } else {
+                    Ok(above)
+                }
             }
+            _ => Ok(&self.r#type),
+        }
+    }
+
+    /// The threshold that applies to standard goods, or to digital products.
+    pub fn threshold_for(&self, is_digital_product_or_service: bool) -> Option<Threshold> {
+        if is_digital_product_or_service {
+            self.threshold_digital_products
+        } else {
+            self.threshold
         }
-        &self.r#type
     }
 
-    /// Determines the tax calculation type for digital products based on amount and threshold
+    /// Determines the tax calculation type for digital products based on the amount and
+    /// `threshold_digital_products`, like `by_threshold`.
+    ///
+    /// Whether the amount is the single transaction or the seller's cumulative turnover
+    /// depends on the threshold's basis: the EU's €10,000 threshold is annual, and
+    /// covers digital products and goods sold to other member states together.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rust_decimal_macros::dec;
+    /// # use world_tax::currency::CurrencyCode;
+    /// # use world_tax::provider::TaxDatabase;
+    /// # use world_tax::types::{TaxCalculationType, ThresholdPolicy};
+    /// let db = TaxDatabase::new().unwrap();
+    /// let eu = db.get_rule("EU").unwrap();
+    /// let rule = eu.tax_rules.internal_b2c.as_ref().unwrap();
+    /// let eur = CurrencyCode::parse("EUR").ok();
+    ///
+    /// let below = rule.by_digital_product_threshold(dec!(9_999.99), eur, ThresholdPolicy::Respect);
+    /// assert_eq!(below.unwrap(), &TaxCalculationType::Origin);
+    /// let above = rule.by_digital_product_threshold(dec!(10_000), eur, ThresholdPolicy::Respect);
+    /// assert_eq!(above.unwrap(), &TaxCalculationType::Destination);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::CurrencyMismatch` if the amount isn't in the threshold's
+    /// currency.
     pub fn by_digital_product_threshold(
         &self,
-        amount: u32,
-        ignore_threshold: bool,
-    ) -> &TaxCalculationType {
-        let has_threshold = self.below_threshold_digital_products.is_some()
-            && self.above_threshold_digital_products.is_some()
-            && self.threshold_digital_products.is_some();
-        if has_threshold {
-            let rule_threshold: u32 = self.threshold_digital_products.unwrap();
-            if amount < rule_threshold && !ignore_threshold {
-                return self.below_threshold_digital_products.as_ref().unwrap();
-            } else {
-                return self.above_threshold_digital_products.as_ref().unwrap();
+        amount: Decimal,
+        currency: Option<CurrencyCode>,
+        policy: ThresholdPolicy,
+    ) -> Result<&TaxCalculationType, DatabaseError> {
+        match (
+            &self.below_threshold_digital_products,
+            &self.above_threshold_digital_products,
+            self.threshold_digital_products,
+        ) {
+            (Some(below), Some(above), Some(threshold)) => {
+                if threshold.is_below(amount, currency, policy)? {
+                    Ok(below)
+                } else {
+                    Ok(above)
+                }
             }
+            _ => Ok(&self.r#type),
         }
-        &self.r#type
     }
 
     /// Determines the appropriate tax calculation type based on product type and amount
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::CurrencyMismatch` if the amount isn't in the threshold's
+    /// currency.
     pub fn by_threshold_or_digital_product_threshold(
         &self,
-        amount: u32,
+        amount: Decimal,
+        currency: Option<CurrencyCode>,
         is_digital_product_or_service: bool,
-        ignore_threshold: bool,
-    ) -> &TaxCalculationType {
+        policy: ThresholdPolicy,
+    ) -> Result<&TaxCalculationType, DatabaseError> {
         if is_digital_product_or_service {
-            return self.by_digital_product_threshold(amount, ignore_threshold);
+            return self.by_digital_product_threshold(amount, currency, policy);
         }
-        self.by_threshold(amount, ignore_threshold)
+        self.by_threshold(amount, currency, policy)
     }
 
     /// Determines if the transaction qualifies for reseller treatment
@@ -256,6 +693,7 @@ impl TaxRuleConfig {
 /// Collection of tax rules for different transaction scenarios
 #[typeshare]
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct TaxRules {
     /// Rules for internal B2B transactions
     pub internal_b2b: Option<TaxRuleConfig>,
@@ -263,11 +701,55 @@ pub struct TaxRules {
     pub internal_b2c: Option<TaxRuleConfig>,
     /// Rules for external exports
     pub external_export: TaxRuleConfig,
+    /// Rule for transactions without an internal rule; without one, the calculation
+    /// profile's fallback policy applies
+    #[serde(default)]
+    pub fallback: Option<TaxRuleConfig>,
+    /// First day the rules apply (ISO 8601); without one, they always applied
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub valid_from: Option<String>,
+    /// Rules that applied before these, each from its own `valid_from`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub previous: Vec<TaxRules>,
+}
+
+impl TaxRules {
+    /// The rules that apply on a date (ISO 8601): the latest rules that took effect
+    /// on or before the date, or the earliest rules for dates before all of them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use world_tax::TaxDatabase;
+    /// # use rust_decimal_macros::dec;
+    /// let db = TaxDatabase::new().unwrap();
+    /// let rules = db.get_rule("EU").unwrap().tax_rules;
+    ///
+    /// // The EU-wide distance selling threshold replaced national thresholds on 2021-07-01
+    /// let threshold = |date| rules.as_of(date).internal_b2c.as_ref().unwrap().threshold;
+    /// assert_eq!(threshold("2021-07-01").unwrap().amount, dec!(10_000));
+    /// assert_eq!(threshold("2021-06-30").unwrap().amount, dec!(35_000));
+    /// ```
+    pub fn as_of(&self, date: &str) -> &TaxRules {
+        let in_effect = |rules: &&TaxRules| {
+            rules
+                .valid_from
+                .as_deref()
+                .is_none_or(|valid_from| valid_from <= date)
+        };
+        let versions = || std::iter::once(self).chain(&self.previous);
+        versions()
+            .filter(in_effect)
+            .max_by_key(|rules| rules.valid_from.as_deref())
+            .or_else(|| versions().min_by_key(|rules| rules.valid_from.as_deref()))
+            .unwrap_or(self)
+    }
 }
 
 /// Product-specific tax rules configuration
 #[typeshare]
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct ProductRules {
     /// Default tax rule to apply
     pub default: String,
@@ -275,40 +757,216 @@ pub struct ProductRules {
     pub specific_products: HashMap<String, String>,
 }
 
+/// Kind of supply, as distinguished by agreements and fees.
+#[typeshare]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(rename_all = "snake_case")]
+pub enum SupplyKind {
+    /// Physical goods
+    PhysicalGoods,
+    /// Digital goods
+    DigitalGoods,
+    /// Services
+    Services,
+}
+
+impl AppliesTo {
+    /// Returns true if the kind of supply is covered
+    pub fn covers(&self, kind: SupplyKind) -> bool {
+        match kind {
+            SupplyKind::PhysicalGoods => self.physical_goods,
+            SupplyKind::DigitalGoods => self.digital_goods,
+            SupplyKind::Services => self.services,
+        }
+    }
+}
+
+/// A flat per-transaction fee levied alongside sales tax (e.g. Colorado Retail Delivery Fee)
+#[typeshare]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct FlatFee {
+    /// Name of the fee
+    pub name: String,
+    /// Amount per transaction, in the currency of the country
+    pub amount: f64,
+    /// Kinds of supply the fee is levied on
+    pub applies_to: AppliesTo,
+    /// Minimum net transaction amount for the fee to apply
+    #[serde(default)]
+    pub min_amount: Option<f64>,
+}
+
+/// A limit of the tax levied at a rate (e.g. Florida levies its discretionary sales
+/// surtax on the first $5,000 of an item's price)
+#[typeshare]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct TaxCap {
+    /// Only this much of the amount is taxed at the rate
+    #[serde(default)]
+    pub max_taxable_amount: Option<f64>,
+    /// The tax at the rate is at most this much
+    #[serde(default)]
+    pub max_tax: Option<f64>,
+    /// Whether the cap applies to each item instead of the transaction; a scenario's
+    /// amount is a single item, carts apply the cap to each unit
+    #[serde(default)]
+    pub per_item: bool,
+}
+
+/// A rate that replaces a state's rate for items priced within a bracket (e.g. New
+/// York exempts clothing items under $110 from its state rate)
+#[typeshare]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct RateBracket {
+    /// Lowest unit price of the bracket (inclusive), if limited
+    #[serde(default)]
+    pub min_price: Option<f64>,
+    /// Unit price the bracket ends below (exclusive), if limited
+    #[serde(default)]
+    pub max_price: Option<f64>,
+    /// Rate of the state within the bracket
+    pub rate: Percentage,
+    /// Category of the items the bracket applies to (e.g. "clothing"); all items if not set
+    #[serde(default)]
+    pub category: Option<String>,
+}
+
+impl RateBracket {
+    /// Returns true if an item of the unit price and category is in the bracket.
+    pub fn contains(&self, unit_price: f64, category: Option<&str>) -> bool {
+        self.min_price.is_none_or(|min| unit_price >= min)
+            && self.max_price.is_none_or(|max| unit_price < max)
+            && self
+                .category
+                .as_deref()
+                .is_none_or(|bracket| category.is_some_and(|c| c.eq_ignore_ascii_case(bracket)))
+    }
+}
+
 /// Represents tax information for a state/province
 #[typeshare]
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct State {
     /// Standard tax rate for the state
-    pub standard_rate: f64,
+    pub standard_rate: Percentage,
     /// Type of tax system used in the state
     #[serde(rename = "type")]
     pub tax_type: TaxSystemType,
+    /// Flat fees levied per transaction
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub fees: Vec<FlatFee>,
+    /// Whether local jurisdictions levy their own sales tax (e.g. Alaska boroughs)
+    #[serde(default)]
+    pub local_taxes: bool,
+    /// Whether sellers without nexus must notify buyers of their use tax and report
+    /// sales to the state (e.g. Colorado)
+    #[serde(default)]
+    pub notice_and_report: bool,
+    /// Limit of the tax levied at the state's rate
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cap: Option<TaxCap>,
+    /// Rates that replace the state's rate for items priced within a bracket; the
+    /// first matching bracket applies
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub brackets: Vec<RateBracket>,
+    /// Legal basis of the state's rate (e.g. a statute citation)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub legal_reference: Option<String>,
+    /// Sales and transactions from which sellers without physical presence must
+    /// collect the state's tax; replaces the threshold of the federal agreement
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub economic_nexus: Option<EconomicNexus>,
+    /// Extension fields (`x-*`) with custom metadata, kept as loaded
+    #[cfg_attr(feature = "arbitrary", arbitrary(default))]
+    #[serde(
+        flatten,
+        default,
+        deserialize_with = "extension_fields",
+        skip_serializing_if = "BTreeMap::is_empty"
+    )]
+    pub(crate) extensions: BTreeMap<String, Value>,
+}
+
+/// A state's economic nexus test: a seller has nexus once its sales into the state
+/// over a calendar year reach `sales`, or (`requires_both`: and) their number reaches
+/// `transactions`.
+///
+/// # Examples
+///
+/// ```
+/// # use rust_decimal_macros::dec;
+/// # use world_tax::types::EconomicNexus;
+/// // New York: $500,000 and 100 transactions
+/// let nexus: EconomicNexus = serde_json::from_str(
+///     r#"{ "sales": 500000, "transactions": 100, "requires_both": true }"#,
+/// ).unwrap();
+/// assert!(!nexus.is_met(dec!(600_000), 50));
+/// assert!(nexus.is_met(dec!(600_000), 100));
+/// ```
+#[typeshare]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct EconomicNexus {
+    /// Sales (USD) from which the seller has nexus
+    #[serde(with = "amount")]
+    #[cfg_attr(feature = "arbitrary", arbitrary(with = amount::arbitrary))]
+    pub sales: Decimal,
+    /// Number of transactions from which the seller has nexus, if the state counts them
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub transactions: Option<u32>,
+    /// Whether both the sales and the transaction test must be met
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub requires_both: bool,
 }
 
-/// Custom deserializer for handling rate values that might be boolean or numeric
-fn deserialize_rate<'de, D>(deserializer: D) -> Result<Option<f64>, D::Error>
-where
-    D: Deserializer<'de>,
-{
-    #[derive(Deserialize)]
-    #[serde(untagged)]
-    enum RateValue {
-        Number(f64),
-        Boolean(bool),
+impl EconomicNexus {
+    /// Returns true if sales and transactions over a year establish nexus.
+    pub fn is_met(&self, sales: Decimal, transactions: u32) -> bool {
+        let sales_met = sales >= self.sales;
+        let Some(threshold) = self.transactions else {
+            return sales_met;
+        };
+        let transactions_met = transactions >= threshold;
+        if self.requires_both {
+            sales_met && transactions_met
+        } else {
+            sales_met || transactions_met
+        }
     }
+}
 
-    match Option::<RateValue>::deserialize(deserializer)? {
-        Some(RateValue::Number(rate)) => Ok(Some(rate)),
-        Some(RateValue::Boolean(false)) => Ok(None),
-        Some(RateValue::Boolean(true)) => Ok(None),
-        None => Ok(None),
+impl State {
+    /// Creates a state taxed at a rate, without fees, local taxes or other rules.
+    pub fn new(tax_type: TaxSystemType, standard_rate: Percentage) -> Self {
+        Self {
+            standard_rate,
+            tax_type,
+            fees: Vec::new(),
+            local_taxes: false,
+            notice_and_report: false,
+            cap: None,
+            brackets: Vec::new(),
+            legal_reference: None,
+            economic_nexus: None,
+            extensions: BTreeMap::new(),
+        }
+    }
+
+    /// The state's extension fields (`x-*`), like internal tax codes.
+    pub fn extensions(&self) -> &BTreeMap<String, Value> {
+        &self.extensions
     }
 }
 
 /// Represents tax information for a country
 #[typeshare]
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct Country {
     /// Type of tax system used in the country
     #[serde(rename = "type")]
@@ -316,25 +974,285 @@ pub struct Country {
     /// Currency code for the country
     pub currency: String,
     /// Standard tax rate
-    pub standard_rate: f64,
+    pub standard_rate: Percentage,
     /// Reduced tax rate if applicable
-    #[serde(default, deserialize_with = "deserialize_rate")]
-    pub reduced_rate: Option<f64>,
+    #[serde(default)]
+    pub reduced_rate: Option<Percentage>,
     /// Alternative reduced tax rate if applicable
-    #[serde(default, deserialize_with = "deserialize_rate")]
-    pub reduced_rate_alt: Option<f64>,
+    #[serde(default)]
+    pub reduced_rate_alt: Option<Percentage>,
     /// Super-reduced tax rate if applicable
-    #[serde(default, deserialize_with = "deserialize_rate")]
-    pub super_reduced_rate: Option<f64>,
+    #[serde(default)]
+    pub super_reduced_rate: Option<Percentage>,
     /// Parking rate if applicable
-    #[serde(default, deserialize_with = "deserialize_rate")]
-    pub parking_rate: Option<f64>,
+    #[serde(default)]
+    pub parking_rate: Option<Percentage>,
     /// Full name of the VAT system
     pub vat_name: Option<String>,
     /// Abbreviation of the VAT system name
     pub vat_abbr: Option<String>,
     /// Tax information for states/provinces if applicable
     pub states: Option<HashMap<String, State>>,
+    /// How sales from sellers abroad are taxed, where no trade agreement applies
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub import_rules: Option<ImportRules>,
+    /// Legal basis of the country's rates (e.g. "§ 12 UStG")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub legal_reference: Option<String>,
+    /// Rates that applied for a period instead of the current ones, for calculations
+    /// as of a date
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub rate_history: Vec<RatePeriod>,
+    /// Country-level taxes levied alongside the standard rate (e.g. PIS and COFINS in
+    /// Brazil)
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub additional_taxes: Vec<AdditionalTax>,
+    /// Rates of sales between states, where they differ from the states' own rates
+    /// (Brazilian interstate ICMS)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interstate_rates: Option<InterstateRates>,
+    /// VAT rates of item categories (e.g. `"food": "reduced"`), for invoice lines of
+    /// the category
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub category_rates: HashMap<String, VatRate>,
+    /// Extension fields (`x-*`) with custom metadata, kept as loaded
+    #[cfg_attr(feature = "arbitrary", arbitrary(default))]
+    #[serde(
+        flatten,
+        default,
+        deserialize_with = "extension_fields",
+        skip_serializing_if = "BTreeMap::is_empty"
+    )]
+    pub(crate) extensions: BTreeMap<String, Value>,
+}
+
+impl Country {
+    /// The country's extension fields (`x-*`), like internal tax codes.
+    pub fn extensions(&self) -> &BTreeMap<String, Value> {
+        &self.extensions
+    }
+
+    /// The VAT rate of an item category, if the country has one; categories are
+    /// matched case-insensitively.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use world_tax::{RateProvider, TaxDatabase, VatRate};
+    /// let db = TaxDatabase::new().unwrap();
+    /// let japan = RateProvider::get_country(&db, "JP").unwrap();
+    /// assert_eq!(japan.category_rate("Food"), Some(VatRate::Reduced));
+    /// assert_eq!(japan.category_rate("alcohol"), None);
+    /// ```
+    pub fn category_rate(&self, category: &str) -> Option<VatRate> {
+        self.category_rates
+            .iter()
+            .find(|(name, _)| name.eq_ignore_ascii_case(category))
+            .map(|(_, vat_rate)| *vat_rate)
+    }
+
+    /// The country's data with the rates that applied on a date (ISO 8601).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use world_tax::{RateProvider, TaxDatabase};
+    /// # use rust_decimal_macros::dec;
+    /// let db = TaxDatabase::new().unwrap();
+    /// let germany = RateProvider::get_country(&db, "DE").unwrap();
+    /// // Temporarily lowered in the second half of 2020
+    /// assert_eq!(germany.as_of("2020-07-01").standard_rate.fraction(), dec!(0.16));
+    /// assert_eq!(germany.as_of("2021-01-01").standard_rate.fraction(), dec!(0.19));
+    /// ```
+    pub fn as_of(&self, date: &str) -> Cow<'_, Country> {
+        let Some(period) = self.rate_history.iter().find(|period| {
+            period.valid_from.as_str() <= date && date <= period.valid_until.as_str()
+        }) else {
+            return Cow::Borrowed(self);
+        };
+        let mut country = self.clone();
+        country.standard_rate = period.standard_rate;
+        country.reduced_rate = period.reduced_rate.or(self.reduced_rate);
+        country.reduced_rate_alt = period.reduced_rate_alt.or(self.reduced_rate_alt);
+        country.super_reduced_rate = period.super_reduced_rate.or(self.super_reduced_rate);
+        country.parking_rate = period.parking_rate.or(self.parking_rate);
+        Cow::Owned(country)
+    }
+}
+
+/// A country-level tax levied alongside the standard rate.
+#[typeshare]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct AdditionalTax {
+    /// The type of tax
+    pub tax: TaxType,
+    /// The tax rate
+    pub rate: Percentage,
+    /// What the tax is levied on; all supplies if not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub applies_to: Option<AppliesTo>,
+    /// Whether municipalities set the rate; a local-rate record of the seller's
+    /// municipality replaces `rate`, which is the rate otherwise assumed
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub local: bool,
+}
+
+impl AdditionalTax {
+    /// Returns true if the tax is levied on the kind of supply.
+    pub fn covers(&self, kind: SupplyKind) -> bool {
+        self.applies_to
+            .as_ref()
+            .is_none_or(|applies_to| applies_to.covers(kind))
+    }
+}
+
+/// The rates of sales between the states of a country: `reduced_rate` from the states
+/// of `reduced_from` to the other states, `rate` otherwise.
+///
+/// # Examples
+///
+/// ```
+/// # use world_tax::types::InterstateRates;
+/// # use rust_decimal_macros::dec;
+/// // Brazil: 7% from the South and Southeast (except Espírito Santo) to other states
+/// let rates: InterstateRates = serde_json::from_str(
+///     r#"{ "rate": 0.12, "reduced_rate": 0.07, "reduced_from": ["BR-SP", "BR-RS"] }"#,
+/// ).unwrap();
+/// assert_eq!(rates.between("BR-SP", "BR-BA").fraction(), dec!(0.07));
+/// assert_eq!(rates.between("BR-SP", "BR-RS").fraction(), dec!(0.12));
+/// assert_eq!(rates.between("BR-BA", "BR-SP").fraction(), dec!(0.12));
+/// ```
+#[typeshare]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct InterstateRates {
+    /// Rate of sales between states
+    pub rate: Percentage,
+    /// Rate of sales from the states of `reduced_from` to the other states
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reduced_rate: Option<Percentage>,
+    /// States (ISO 3166-2) whose sales to the other states are taxed at `reduced_rate`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub reduced_from: Vec<String>,
+}
+
+impl InterstateRates {
+    /// The rate of a sale from one state to another (ISO 3166-2 codes).
+    pub fn between(&self, source: &str, destination: &str) -> Percentage {
+        let reduced = |state: &str| self.reduced_from.iter().any(|code| code == state);
+        match self.reduced_rate {
+            Some(rate) if reduced(source) && !reduced(destination) => rate,
+            _ => self.rate,
+        }
+    }
+}
+
+/// The rates of a country over a past period (ISO 8601 dates, inclusive); rates it
+/// doesn't state are the current ones.
+#[typeshare]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct RatePeriod {
+    /// First day the rates applied
+    pub valid_from: String,
+    /// Last day the rates applied
+    pub valid_until: String,
+    /// Standard tax rate
+    pub standard_rate: Percentage,
+    /// Reduced tax rate
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reduced_rate: Option<Percentage>,
+    /// Alternative reduced tax rate
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reduced_rate_alt: Option<Percentage>,
+    /// Super-reduced tax rate
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub super_reduced_rate: Option<Percentage>,
+    /// Parking rate
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parking_rate: Option<Percentage>,
+}
+
+/// How a country taxes sales from sellers abroad, where no trade agreement applies;
+/// sales without a rule are zero rated (B2B sales are left to the buyer).
+///
+/// # Examples
+///
+/// ```
+/// # use world_tax::types::ImportRules;
+/// // Australia: GST on digital services and goods up to AUD 1000
+/// let rules: ImportRules = serde_json::from_str(r#"{
+///     "b2c_digital": "destination",
+///     "b2c_low_value_goods": "destination",
+///     "low_value_goods_limit": 1000.0
+/// }"#).unwrap();
+///
+/// // Norway (VOEC): VAT on goods up to NOK 3000, once the seller's sales exceed NOK 50,000
+/// let rules: ImportRules = serde_json::from_str(r#"{
+///     "b2c_low_value_goods": "destination",
+///     "low_value_goods_limit": 3000.0,
+///     "low_value_goods_threshold": { "amount": 50000, "basis": "annual_turnover", "currency": "NOK" }
+/// }"#).unwrap();
+/// ```
+#[typeshare]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct ImportRules {
+    /// B2C sales of digital products and services (e.g. `Destination`: the seller
+    /// registers and charges the country's tax)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub b2c_digital: Option<TaxCalculationType>,
+    /// B2C sales of goods valued up to `low_value_goods_limit` (all goods, without one)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub b2c_low_value_goods: Option<TaxCalculationType>,
+    /// Value of a consignment, in the country's currency, up to which goods count as
+    /// low-value
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub low_value_goods_limit: Option<f64>,
+    /// Seller's turnover from which `b2c_low_value_goods` applies, like the Swiss
+    /// mail-order rule (LVG) or Norway's VOEC scheme; below it, the goods are taxed on
+    /// import
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub low_value_goods_threshold: Option<Threshold>,
+    /// B2B sales of digital products and services: `ReverseCharge` where the buyer
+    /// accounts for the tax, `Destination` where the country has no reverse charge
+    /// for imported services, and the seller registers and charges its tax
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub b2b_services: Option<TaxCalculationType>,
+    /// Postponed accounting of import VAT, where VAT-registered importers declare it in
+    /// their VAT return instead of paying it at the border
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub postponed_accounting: Option<PostponedAccounting>,
+}
+
+/// A scheme for accounting for import VAT in the VAT return (e.g. the UK's postponed
+/// VAT accounting), where it's deducted in the same return, so no VAT is paid in cash.
+///
+/// # Examples
+///
+/// ```
+/// # use world_tax::types::PostponedAccounting;
+/// let scheme: PostponedAccounting = serde_json::from_str(r#"{
+///     "name": "Article 23 licence",
+///     "legal_reference": "Art. 23 Wet OB 1968",
+///     "authorisation_required": true
+/// }"#).unwrap();
+/// assert!(scheme.authorisation_required);
+/// ```
+#[typeshare]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct PostponedAccounting {
+    /// Name of the scheme (e.g. "Postponed VAT accounting")
+    pub name: String,
+    /// Legal basis of the scheme
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub legal_reference: Option<String>,
+    /// Whether importers need an authorisation to use it (e.g. the Dutch Article 23
+    /// licence); otherwise any VAT-registered importer can
+    #[serde(default)]
+    pub authorisation_required: bool,
 }
 
 /// Represents a geographical region for tax purposes
@@ -350,35 +1268,218 @@ pub struct Country {
 /// let california = Region::new("US".to_string(), Some("US-CA".to_string())).unwrap();
 /// ```
 #[typeshare]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Region {
     /// ISO 3166-1 alpha-2 country code
     pub country: String,
     /// Optional ISO 3166-2 region code
     pub region: Option<String>,
+    /// Optional postal code, used to resolve local rates
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub postal_code: Option<String>,
+    /// Optional local jurisdiction (e.g. "Juneau"), used to resolve local rates without
+    /// a postal code
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jurisdiction: Option<String>,
+}
+
+/// Mostly valid codes: an ISO 3166-1 country and, if any, one of its subdivisions,
+/// but also arbitrary strings, like a region that skipped validation.
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Region {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        let country = *u.choose(rust_iso3166::ALL)?;
+        let subdivisions = country.subdivisions().unwrap_or_default();
+        Ok(Self {
+            country: if u.ratio(1, 10)? {
+                u.arbitrary()?
+            } else {
+                country.alpha2.to_string()
+            },
+            region: match u.int_in_range(0..=9)? {
+                0 => u.arbitrary()?,
+                1..=5 if !subdivisions.is_empty() => Some(u.choose(subdivisions)?.code.to_string()),
+                _ => None,
+            },
+            postal_code: u.arbitrary()?,
+            jurisdiction: u.arbitrary()?,
+        })
+    }
+}
+
+/// The serialized forms of a region: a code ("US-CA"), or its fields.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawRegion {
+    Code(String),
+    Fields {
+        country: String,
+        #[serde(default)]
+        region: Option<String>,
+        #[serde(default)]
+        postal_code: Option<String>,
+        #[serde(default)]
+        jurisdiction: Option<String>,
+    },
+}
+
+/// Deserializes a region from its code or its fields, validating the codes like
+/// `Region::parse` and `Region::new`.
+impl<'de> Deserialize<'de> for Region {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let region = match RawRegion::deserialize(deserializer)? {
+            RawRegion::Code(code) => Region::parse(&code),
+            RawRegion::Fields {
+                country,
+                region,
+                postal_code,
+                jurisdiction,
+            } => Region::new(country, region).map(|region| Region {
+                postal_code,
+                jurisdiction,
+                ..region
+            }),
+        };
+        region.map_err(serde::de::Error::custom)
+    }
+}
+
+/// Normalizes a state or province code to its ISO 3166-2 form: upper case, with the
+/// country prefix added to bare codes, and renamed codes replaced (see
+/// `alias::SUBDIVISION_ALIASES`).
+///
+/// # Examples
+///
+/// ```
+/// # use world_tax::types::normalize_state_code;
+/// assert_eq!(normalize_state_code("CA", "BC"), "CA-BC");
+/// assert_eq!(normalize_state_code("CA", "ca-bc"), "CA-BC");
+/// assert_eq!(normalize_state_code("CA", "PQ"), "CA-QC");
+/// ```
+pub fn normalize_state_code(country: &str, code: &str) -> String {
+    let code = code.trim().to_ascii_uppercase();
+    let code = if code.contains('-') {
+        code
+    } else {
+        format!("{}-{}", country.to_ascii_uppercase(), code)
+    };
+    subdivision_alias(&code).map_or(code, str::to_string)
 }
 
 impl Region {
-    /// Creates a new Region with validation
+    /// Creates a new Region with validation; the codes are normalized (see
+    /// `normalize_state_code`), so "bc" and "CA-BC" are the same region of "CA", and
+    /// alpha-3 ("CAN") and numeric ("124") country codes replaced by alpha-2 codes
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use world_tax::types::Region;
+    /// let bare = Region::new("ca".to_string(), Some("bc".to_string())).unwrap();
+    /// let full = Region::new("CA".to_string(), Some("CA-BC".to_string())).unwrap();
+    /// assert_eq!(bare.country, full.country);
+    /// assert_eq!(bare.region, full.region);
+    ///
+    /// let alpha3 = Region::new("CAN".to_string(), Some("CAN-BC".to_string())).unwrap();
+    /// assert_eq!(alpha3.region, full.region);
+    ///
+    /// // The region has to be a subdivision of the country
+    /// assert!(Region::new("CA".to_string(), Some("US-WA".to_string())).is_err());
+    /// ```
     pub fn new(country: String, region: Option<String>) -> Result<Self, InputValidationError> {
+        let country = country.trim().to_ascii_uppercase();
+        let country = alpha2(&country).map_or(country, str::to_string);
+        let region = region.map(|code| {
+            // A subdivision code prefixed with the alpha-3 or numeric country code
+            let code = match code.trim().split_once('-') {
+                Some((prefix, subdivision)) if alpha2(prefix) == Some(country.as_str()) => {
+                    format!("{country}-{subdivision}")
+                }
+                _ => code,
+            };
+            normalize_state_code(&country, &code)
+        });
         Self::validate(&country, &region)?;
-        Ok(Self { country, region })
+        Ok(Self {
+            country,
+            region,
+            postal_code: None,
+            jurisdiction: None,
+        })
+    }
+
+    /// Sets the postal code of the region
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use world_tax::types::Region;
+    /// let seattle = Region::parse("US-WA").unwrap().with_postal_code("98101");
+    /// assert_eq!(seattle.to_string(), "US-WA 98101");
+    /// ```
+    pub fn with_postal_code(mut self, postal_code: impl Into<String>) -> Self {
+        self.postal_code = Some(postal_code.into());
+        self
+    }
+
+    /// Sets the local jurisdiction of the region, for local rates where the postal code
+    /// isn't known
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use world_tax::types::Region;
+    /// let juneau = Region::parse("US-AK").unwrap().with_jurisdiction("Juneau");
+    /// assert_eq!(juneau.to_string(), "US-AK Juneau");
+    /// ```
+    pub fn with_jurisdiction(mut self, jurisdiction: impl Into<String>) -> Self {
+        self.jurisdiction = Some(jurisdiction.into());
+        self
+    }
+
+    /// Creates a new Region from a single code, either a country ("DE") or a
+    /// subdivision ("US-CA")
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use world_tax::types::Region;
+    /// let california = Region::parse("US-CA").unwrap();
+    /// assert_eq!(california.country, "US");
+    /// assert_eq!(california.region.as_deref(), Some("US-CA"));
+    /// ```
+    pub fn parse(code: &str) -> Result<Self, InputValidationError> {
+        match code.split_once('-') {
+            Some((country, _)) => Self::new(country.to_string(), Some(code.to_string())),
+            None => Self::new(code.to_string(), None),
+        }
     }
 
     /// Validates country and region codes against ISO standards
     fn validate(country: &str, region: &Option<String>) -> Result<(), InputValidationError> {
-        let country_info = rust_iso3166::from_alpha2(country)
-            .ok_or_else(|| InputValidationError::InvalidCountryCode(country.to_string()))?;
+        let country_info = rust_iso3166::from_alpha2(country).ok_or_else(|| {
+            InputValidationError::InvalidCountryCode {
+                code: country.to_string(),
+                suggestions: suggestions(country, rust_iso3166::ALL_ALPHA2.iter().copied()),
+            }
+        })?;
 
         debug!("Found country: {}", country_info.name);
 
         if let Some(region_code) = region {
-            let _ = country_info
+            let subdivisions = country_info
                 .subdivisions()
                 .ok_or_else(|| InputValidationError::UnexpectedRegionCode(region_code.clone()))?;
 
             let region_info = rust_iso3166::iso3166_2::from_code(region_code)
-                .ok_or_else(|| InputValidationError::InvalidRegionCode(region_code.clone()))?;
+                .filter(|_| region_code.split_once('-').map(|(prefix, _)| prefix) == Some(country))
+                .ok_or_else(|| InputValidationError::InvalidRegionCode {
+                    code: region_code.clone(),
+                    suggestions: suggestions(
+                        region_code,
+                        subdivisions.iter().map(|subdivision| subdivision.code),
+                    ),
+                })?;
 
             debug!("Found region: {}", region_info.name);
         }
@@ -387,9 +1488,39 @@ impl Region {
     }
 }
 
+impl fmt::Display for Region {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.region {
+            Some(region) => write!(f, "{}", region)?,
+            None => write!(f, "{}", self.country)?,
+        }
+        if let Some(postal_code) = &self.postal_code {
+            write!(f, " {}", postal_code)?;
+        }
+        if let Some(jurisdiction) = &self.jurisdiction {
+            write!(f, " {}", jurisdiction)?;
+        }
+        Ok(())
+    }
+}
+
 /// Represents a complete tax calculation scenario
+///
+/// Scenarios serialize to JSON with their regions as fields; only the regions and the
+/// transaction type are required to deserialize one:
+///
+/// ```
+/// # use world_tax::types::TaxScenario;
+/// let scenario: TaxScenario = serde_json::from_str(r#"{
+///     "source_region": "DE",
+///     "destination_region": { "country": "US", "region": "US-WA", "postal_code": "98101" },
+///     "transaction_type": "b2c"
+/// }"#).unwrap();
+/// assert_eq!(scenario.destination_region.to_string(), "US-WA 98101");
+/// ```
 #[typeshare]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct TaxScenario {
     /// Region where the seller is located
     pub source_region: Region,
@@ -400,25 +1531,230 @@ pub struct TaxScenario {
     /// How the tax should be calculated
     // pub calculation_type: TaxCalculationType,
     /// Optional override for trade agreement application
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub trade_agreement_override: Option<TradeAgreementOverride>,
     /// Whether the product/service is digital
+    #[serde(default)]
     pub is_digital_product_or_service: bool,
-    /// Whether the buyer has a resale certificate (relevant for B2B in US)
-    pub has_resale_certificate: bool,
-    /// Whether to ignore thresholds in calculations
-    pub ignore_threshold: bool,
+    /// The buyer's resale or exemption certificate (relevant for B2B in US); only
+    /// honored in its jurisdiction and until it expires
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exemption_certificate: Option<ExemptionCertificate>,
+    /// The check of the buyer's VAT number; sales within the EU are only reverse
+    /// charged with a valid number from another member state
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "arbitrary", arbitrary(default))]
+    pub buyer_vat_id: Option<VatIdCheck>,
+    /// How thresholds of tax rules are applied
+    #[serde(default)]
+    pub threshold_policy: ThresholdPolicy,
     /// Specific VAT rate to apply if applicable
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub vat_rate: Option<VatRate>,
+    /// Name of a calculation profile registered on the database
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub profile: Option<String>,
+    /// Delivery terms of cross-border physical goods; with `Incoterm::Ddp` the seller's
+    /// quote includes the destination's import taxes
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub incoterm: Option<Incoterm>,
+    /// Currency the amounts are stated in; a currency other than the destination's is
+    /// flagged with `Warning::CurrencyMismatch`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub currency: Option<CurrencyCode>,
+    /// The seller's turnover in the threshold's jurisdiction; without it, annual
+    /// turnover thresholds are compared against the transaction amount
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub turnover: Option<AnnualTurnover>,
+    /// Date of the supply (ISO 8601); agreement rules are resolved as of that date,
+    /// the current rules apply without one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub date: Option<String>,
+    /// What is supplied, where taxes differ for goods and services (e.g. Brazilian ICMS
+    /// and ISS); physical or digital goods by `is_digital_product_or_service` if not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub supply_kind: Option<SupplyKind>,
+    /// Attributes of the sale for override rules to match (e.g. `category=books`), see
+    /// `overrides`
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub attributes: HashMap<String, String>,
+}
+
+/// Where a tax rate was taken from.
+#[typeshare]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(rename_all = "snake_case")]
+pub enum RateSource {
+    /// The country's rate (e.g. VAT, or the federal GST component)
+    #[default]
+    CountryDefault,
+    /// The rate of a state or province entry (e.g. HST, PST, US state sales tax)
+    StateSpecific,
+    /// A zero rate implied by the trade agreement outcome (reverse charge, zero-rated, exempt)
+    AgreementImplied,
+    /// The country's rate, used because the requested region has no entry
+    Fallback,
+    /// A local (county, city, district) rate, e.g. from an SST boundary table
+    Local,
+    /// A zero rate of a sale exempted by an override rule
+    Override,
+}
+
+/// A tax-inclusive amount, split into its net amount and the tax it contains.
+#[typeshare]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct GrossSplit {
+    /// The tax-inclusive amount
+    pub gross: f64,
+    /// The net amount (gross amount minus tax)
+    pub net: f64,
+    /// The tax contained in the gross amount
+    pub tax: f64,
+}
+
+/// The tax of a single applied rate.
+#[typeshare]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct AppliedRate {
+    /// The applied rate
+    pub rate: TaxRate,
+    /// The amount the rate was applied to, including the taxes it compounds on
+    pub base: f64,
+    /// The tax of the rate, rounded like the total
+    pub tax: f64,
+}
+
+/// The result of a tax calculation, broken down by rate.
+#[typeshare]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TaxCalculationResult {
+    /// The calculation type that was applied
+    pub calculation_type: TaxCalculationType,
+    /// The applied rates, in the order they were applied
+    pub rates: Vec<AppliedRate>,
+    /// The net amount
+    pub amount: f64,
+    /// The total tax, as returned by `calculate_tax`; the rounded taxes of the rates
+    /// may add up to a cent more or less
+    pub tax: f64,
+    /// The gross amount (net amount plus tax)
+    pub total: f64,
+}
+
+/// How the tax of a rate is computed.
+#[typeshare]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+#[serde(tag = "type", content = "content")]
+#[serde(rename_all = "snake_case")]
+pub enum RateBasis {
+    /// A percentage (`TaxRate::rate`) of the amount
+    #[default]
+    Percentage,
+    /// A fixed amount per transaction
+    Flat(f64),
+    /// A fixed amount per unit (e.g. an excise per bottle)
+    PerUnit(f64),
+}
+
+impl RateBasis {
+    fn is_percentage(&self) -> bool {
+        *self == RateBasis::Percentage
+    }
 }
 
 /// Represents a specific tax rate and its characteristics.
+///
+/// Rates serialized before `basis` and `compounds_on` existed deserialize as
+/// percentage rates that compound on all preceding taxes, if at all.
+///
+/// # Examples
+///
+/// ```
+/// # use world_tax::types::{RateBasis, RateSource, TaxRate, TaxType};
+/// # use world_tax::Percentage;
+/// # use rust_decimal_macros::dec;
+/// let gst = TaxRate::new(Percentage::from_fraction(dec!(0.05)), TaxType::GST, RateSource::CountryDefault);
+/// let qst = TaxRate::new(Percentage::from_fraction(dec!(0.09975)), TaxType::QST, RateSource::StateSpecific)
+///     .compounding_on(TaxType::GST);
+/// assert!(qst.compound);
+///
+/// let levy = TaxRate::per_unit(0.5, TaxType::LocalSalesTax, RateSource::Local);
+/// assert_eq!(levy.basis, RateBasis::PerUnit(0.5));
+/// ```
 #[typeshare]
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
 pub struct TaxRate {
-    /// The numerical tax rate as a decimal (e.g., 0.20 for 20%)
-    pub rate: f64,
+    /// The tax rate (e.g. 20%); 0% for flat and per-unit amounts
+    pub rate: Percentage,
     /// The type of tax (VAT, GST, etc.)
     pub tax_type: TaxType,
-    /// Whether this tax compounds on top of other taxes
+    /// Whether this tax compounds on top of the preceding taxes (all of them, or those
+    /// of `compounds_on`)
     pub compound: bool,
+    /// Where the rate was taken from
+    #[serde(default)]
+    pub source: RateSource,
+    /// Limit of the tax levied at the rate
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cap: Option<TaxCap>,
+    /// How the tax is computed
+    #[serde(default, skip_serializing_if = "RateBasis::is_percentage")]
+    pub basis: RateBasis,
+    /// The preceding tax a compound rate compounds on; all preceding taxes if not set
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compounds_on: Option<TaxType>,
+}
+
+impl TaxRate {
+    /// Creates a percentage rate that doesn't compound and isn't capped.
+    pub fn new(rate: Percentage, tax_type: TaxType, source: RateSource) -> Self {
+        Self {
+            rate,
+            tax_type,
+            compound: false,
+            source,
+            cap: None,
+            basis: RateBasis::Percentage,
+            compounds_on: None,
+        }
+    }
+
+    /// Creates a fixed amount per transaction.
+    pub fn flat(amount: f64, tax_type: TaxType, source: RateSource) -> Self {
+        Self {
+            basis: RateBasis::Flat(amount),
+            ..Self::new(Percentage::ZERO, tax_type, source)
+        }
+    }
+
+    /// Creates a fixed amount per unit.
+    pub fn per_unit(amount: f64, tax_type: TaxType, source: RateSource) -> Self {
+        Self {
+            basis: RateBasis::PerUnit(amount),
+            ..Self::new(Percentage::ZERO, tax_type, source)
+        }
+    }
+
+    /// Compounds the rate on all preceding taxes.
+    pub fn compounding(mut self) -> Self {
+        self.compound = true;
+        self.compounds_on = None;
+        self
+    }
+
+    /// Compounds the rate on the preceding taxes of a type only.
+    pub fn compounding_on(mut self, tax_type: TaxType) -> Self {
+        self.compound = true;
+        self.compounds_on = Some(tax_type);
+        self
+    }
+
+    /// Limits the tax levied at the rate.
+    pub fn with_cap(mut self, cap: Option<TaxCap>) -> Self {
+        self.cap = cap;
+        self
+    }
 }
@@ -0,0 +1,246 @@
+//! Updates of loaded rates and agreements.
+//!
+//! Rates change mid-year, and countries join or leave agreements. Long-running services
+//! apply such changes to the loaded database with these methods, instead of restarting
+//! or parsing the full dataset again. Each change is checked like loaded data would be
+//! (see `validation`): rates are between 0 and 100%, state codes are ISO 3166-2 codes
+//! of their country, and agreement members are countries of the data or their
+//! subdivisions. A rejected change leaves the database as it was.
+//!
+//! For many changes at once, `TaxDatabase::merge_json` applies partial data (see
+//! `overlay`). Region groups and override rules keep the regions they were resolved to
+//! when defined.
+
+use rust_decimal::Decimal;
+
+use crate::certificate::is_iso_date;
+use crate::errors::DatabaseError;
+use crate::types::{normalize_state_code, MembershipPeriod, State, TradeAgreement};
+use crate::{Percentage, Region, TaxDatabase, VatRate};
+
+impl TaxDatabase {
+    /// Sets a rate of a country, returning the rate it replaced.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rust_decimal_macros::dec;
+    /// # use world_tax::{Percentage, Region, TaxDatabase, TaxScenario, TransactionType, VatRate};
+    /// let mut db = TaxDatabase::fixture();
+    /// let previous = db
+    ///     .set_country_rate("DE", VatRate::Standard, Percentage::from_percent(dec!(16)))
+    ///     .unwrap();
+    /// assert_eq!(previous.unwrap().to_string(), "19%");
+    ///
+    /// let sale = TaxScenario::new(
+    ///     Region::parse("DE").unwrap(),
+    ///     Region::parse("DE").unwrap(),
+    ///     TransactionType::B2C,
+    /// );
+    /// assert_eq!(sale.calculate_tax(100.0, &db).unwrap(), 16.0);
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::CountryNotFound` if the country isn't in the dataset, and
+    /// `DatabaseError::InvalidUpdate` if the rate isn't between 0 and 100%, or the
+    /// category has no rate of its own (`Zero`, `Exempt` and `ReverseCharge`).
+    pub fn set_country_rate(
+        &mut self,
+        country: &str,
+        vat_rate: VatRate,
+        rate: Percentage,
+    ) -> Result<Option<Percentage>, DatabaseError> {
+        check_rate(country, rate)?;
+        let not_found = self.country_not_found(country);
+        let data = self.countries.get_mut(country).ok_or(not_found)?;
+        let field = match vat_rate {
+            VatRate::Standard => return Ok(Some(std::mem::replace(&mut data.standard_rate, rate))),
+            VatRate::Reduced => &mut data.reduced_rate,
+            VatRate::ReducedAlt => &mut data.reduced_rate_alt,
+            VatRate::SuperReduced => &mut data.super_reduced_rate,
+            VatRate::Zero | VatRate::Exempt | VatRate::ReverseCharge => {
+                return Err(DatabaseError::InvalidUpdate(format!(
+                    "{country}: {vat_rate:?} has no rate of its own"
+                )))
+            }
+        };
+        Ok(field.replace(rate))
+    }
+
+    /// Adds a state to a country, or replaces it, returning the state it replaced. The
+    /// code is normalized (see `normalize_state_code`), so "BC" adds "CA-BC".
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use rust_decimal_macros::dec;
+    /// # use world_tax::types::{State, TaxSystemType};
+    /// # use world_tax::{Percentage, TaxDatabase};
+    /// let mut db = TaxDatabase::fixture();
+    /// let mut alberta = State::new(TaxSystemType::None, Percentage::from_percent(dec!(0)));
+    /// alberta.legal_reference = Some("No provincial sales tax".to_string());
+    /// assert!(db.upsert_state("CA", "AB", alberta).unwrap().is_none());
+    ///
+    /// let state = db.get_country("CA").unwrap().state("CA-AB").unwrap();
+    /// assert_eq!(state.legal_reference(), Some("No provincial sales tax"));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::CountryNotFound` if the country isn't in the dataset, and
+    /// `DatabaseError::InvalidUpdate` if the code isn't an ISO 3166-2 code of the
+    /// country, or the state's rate isn't between 0 and 100%.
+    pub fn upsert_state(
+        &mut self,
+        country: &str,
+        code: &str,
+        state: State,
+    ) -> Result<Option<State>, DatabaseError> {
+        let code = normalize_state_code(country, code);
+        if !code.starts_with(&format!("{country}-"))
+            || rust_iso3166::iso3166_2::from_code(&code).is_none()
+        {
+            return Err(DatabaseError::InvalidUpdate(format!(
+                "{code} isn't an ISO 3166-2 code of {country}"
+            )));
+        }
+        check_rate(&code, state.standard_rate)?;
+        let not_found = self.country_not_found(country);
+        let data = self.countries.get_mut(country).ok_or(not_found)?;
+        Ok(data
+            .states
+            .get_or_insert_with(Default::default)
+            .insert(code, state))
+    }
+
+    /// Adds a trade agreement, or replaces the one with the identifier, returning the
+    /// agreement it replaced.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::InvalidUpdate` if the identifier is empty, or a member is
+    /// neither a country of the data nor a subdivision of one.
+    pub fn add_trade_agreement(
+        &mut self,
+        id: &str,
+        agreement: TradeAgreement,
+    ) -> Result<Option<TradeAgreement>, DatabaseError> {
+        if id.trim().is_empty() {
+            return Err(DatabaseError::InvalidUpdate(
+                "the agreement identifier must not be empty".to_string(),
+            ));
+        }
+        if let Some(member) = agreement
+            .members
+            .iter()
+            .find(|member| !self.is_known_member(member))
+        {
+            return Err(unknown_member(id, member));
+        }
+        Ok(self.trade_agreements.insert(id.to_string(), agreement))
+    }
+
+    /// Adds a member to a trade agreement; returns false if it was a member already. A
+    /// period of an earlier membership is removed, so the member is one at any date.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::TradeAgreementNotFound` if the agreement isn't in the
+    /// dataset, and `DatabaseError::InvalidUpdate` if the member is neither a country
+    /// of the data nor a subdivision of one.
+    pub fn add_member(&mut self, agreement: &str, member: &str) -> Result<bool, DatabaseError> {
+        let member = member_code(member)
+            .filter(|member| self.is_known_member(member))
+            .ok_or_else(|| unknown_member(agreement, member))?;
+        let data = self.agreement_mut(agreement)?;
+        if data.members.contains(&member) {
+            return Ok(false);
+        }
+        data.membership_periods.remove(&member);
+        data.members.push(member);
+        Ok(true)
+    }
+
+    /// Removes a member from a trade agreement; returns false if it wasn't a member.
+    ///
+    /// With the date the member `left` (ISO 8601), its membership period is kept, so
+    /// calculations as of an earlier date still treat it as a member (see
+    /// `TradeAgreement::as_of`). Without one, the membership is removed entirely.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use world_tax::TaxDatabase;
+    /// let mut db = TaxDatabase::fixture();
+    /// assert!(db.remove_member("EU", "FR", Some("2026-06-30")).unwrap());
+    ///
+    /// let eu = db.get_rule("EU").unwrap();
+    /// assert_eq!(eu.members, vec!["DE"]);
+    /// assert!(eu.as_of("2026-06-30").members.contains(&"FR".to_string()));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatabaseError::TradeAgreementNotFound` if the agreement isn't in the
+    /// dataset, and `DatabaseError::InvalidUpdate` if the date isn't an ISO 8601 date.
+    pub fn remove_member(
+        &mut self,
+        agreement: &str,
+        member: &str,
+        left: Option<&str>,
+    ) -> Result<bool, DatabaseError> {
+        if let Some(date) = left.filter(|date| !is_iso_date(date)) {
+            return Err(DatabaseError::InvalidUpdate(format!(
+                "{agreement}: {date} isn't an ISO 8601 date"
+            )));
+        }
+        let member = member_code(member).unwrap_or_else(|| member.to_string());
+        let data = self.agreement_mut(agreement)?;
+        let Some(index) = data.members.iter().position(|code| *code == member) else {
+            return Ok(false);
+        };
+        data.members.remove(index);
+        data.non_implementing_members.retain(|code| *code != member);
+        match left {
+            Some(date) => {
+                data.membership_periods
+                    .entry(member)
+                    .or_insert_with(MembershipPeriod::default)
+                    .left = Some(date.to_string());
+            }
+            None => {
+                data.membership_periods.remove(&member);
+            }
+        }
+        Ok(true)
+    }
+
+    fn agreement_mut(&mut self, id: &str) -> Result<&mut TradeAgreement, DatabaseError> {
+        self.trade_agreements
+            .get_mut(id)
+            .ok_or_else(|| DatabaseError::TradeAgreementNotFound(id.to_string()))
+    }
+}
+
+/// The code a region is listed under in agreements: "DE", "US-CA".
+fn member_code(member: &str) -> Option<String> {
+    let region = Region::parse(member).ok()?;
+    Some(region.region.unwrap_or(region.country))
+}
+
+fn unknown_member(agreement: &str, member: &str) -> DatabaseError {
+    DatabaseError::InvalidUpdate(format!(
+        "{agreement}: member {member} is neither a country of the data nor a subdivision of one"
+    ))
+}
+
+fn check_rate(code: &str, rate: Percentage) -> Result<(), DatabaseError> {
+    if (Decimal::ZERO..=Decimal::ONE).contains(&rate.fraction()) {
+        Ok(())
+    } else {
+        Err(DatabaseError::InvalidUpdate(format!(
+            "{code}: rate {rate} isn't between 0 and 100%"
+        )))
+    }
+}
@@ -0,0 +1,122 @@
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+
+    use crate::types::{State, TaxSystemType};
+    use crate::{
+        DatabaseError, Percentage, Region, TaxDatabase, TaxScenario, TransactionType, VatRate,
+    };
+
+    fn sale(source: &str, destination: &str) -> TaxScenario {
+        TaxScenario::new(
+            Region::parse(source).expect("Valid region"),
+            Region::parse(destination).expect("Valid region"),
+            TransactionType::B2C,
+        )
+    }
+
+    fn percent(percent: rust_decimal::Decimal) -> Percentage {
+        Percentage::from_percent(percent)
+    }
+
+    #[test]
+    fn test_set_country_rate() {
+        let mut db = TaxDatabase::new().unwrap();
+        let previous = db
+            .set_country_rate("FR", VatRate::Reduced, percent(dec!(11)))
+            .unwrap();
+        assert_eq!(previous, Some(percent(dec!(10))));
+        let mut reduced = sale("FR", "FR");
+        reduced.vat_rate = Some(VatRate::Reduced);
+        assert_eq!(reduced.calculate_tax(100.0, &db).unwrap(), 11.0);
+
+        // A country without the category gains it
+        assert_eq!(
+            db.set_country_rate("AD", VatRate::Reduced, percent(dec!(1)))
+                .unwrap(),
+            None
+        );
+
+        assert!(matches!(
+            db.set_country_rate("FR", VatRate::Standard, percent(dec!(120))),
+            Err(DatabaseError::InvalidUpdate(_))
+        ));
+        assert!(matches!(
+            db.set_country_rate("FR", VatRate::Exempt, percent(dec!(0))),
+            Err(DatabaseError::InvalidUpdate(_))
+        ));
+        assert!(matches!(
+            db.set_country_rate("XX", VatRate::Standard, percent(dec!(10))),
+            Err(DatabaseError::CountryNotFound { .. })
+        ));
+        assert_eq!(sale("FR", "FR").calculate_tax(100.0, &db).unwrap(), 20.0);
+    }
+
+    #[test]
+    fn test_upsert_state() {
+        let mut db = TaxDatabase::fixture();
+        let previous = db
+            .upsert_state("CA", "bc", State::new(TaxSystemType::Pst, percent(dec!(8))))
+            .unwrap();
+        assert_eq!(previous.unwrap().standard_rate, percent(dec!(7)));
+        assert_eq!(
+            db.get_country("CA")
+                .unwrap()
+                .state("CA-BC")
+                .unwrap()
+                .standard_rate(),
+            percent(dec!(8))
+        );
+
+        for code in ["US-WA", "XX"] {
+            assert!(matches!(
+                db.upsert_state("CA", code, State::new(TaxSystemType::Pst, percent(dec!(5)))),
+                Err(DatabaseError::InvalidUpdate(_))
+            ));
+        }
+        assert!(db
+            .upsert_state(
+                "CA",
+                "MB",
+                State::new(TaxSystemType::Pst, percent(dec!(200)))
+            )
+            .is_err());
+        assert!(db.get_country("CA").unwrap().state("CA-MB").is_none());
+    }
+
+    #[test]
+    fn test_agreement_members() {
+        let mut db = TaxDatabase::new().unwrap();
+        assert!(!db.add_member("EU", "DE").unwrap());
+        assert!(matches!(
+            db.add_member("EU", "XX"),
+            Err(DatabaseError::InvalidUpdate(_))
+        ));
+        assert!(matches!(
+            db.add_member("XU", "DE"),
+            Err(DatabaseError::TradeAgreementNotFound(_))
+        ));
+
+        // Leaving on a date keeps the membership for earlier calculations
+        assert!(db.remove_member("EU", "HR", Some("2026-06-30")).unwrap());
+        let eu = db.get_rule("EU").unwrap();
+        assert!(!eu.members.contains(&"HR".to_string()));
+        assert!(eu.as_of("2026-06-30").members.contains(&"HR".to_string()));
+        assert!(!eu.as_of("2026-07-01").members.contains(&"HR".to_string()));
+        assert!(!db.remove_member("EU", "HR", None).unwrap());
+        assert!(db.remove_member("EU", "AT", Some("30.06.2026")).is_err());
+
+        // Rejoining
+        assert!(db.add_member("EU", "hr").unwrap());
+        let eu = db.get_rule("EU").unwrap();
+        assert!(eu.as_of("2026-07-01").members.contains(&"HR".to_string()));
+
+        let mut agreement = db.get_rule("EU").unwrap();
+        agreement.name = "Test union".to_string();
+        agreement.members = vec!["DE".to_string(), "XX".to_string()];
+        assert!(db.add_trade_agreement("TU", agreement.clone()).is_err());
+        agreement.members.pop();
+        assert!(db.add_trade_agreement("TU", agreement).unwrap().is_none());
+        assert_eq!(db.get_rule("TU").unwrap().name, "Test union");
+    }
+}
@@ -0,0 +1,378 @@
+//! Checks of the loaded tax data.
+//!
+//! `TaxDatabase::validate` reports problems in the loaded data that don't prevent
+//! loading, such as legacy constructs that were migrated on load, so maintainers of
+//! custom datasets can clean them up, and values that load but produce wrong
+//! results, such as currencies that aren't ISO 4217 codes.
+//!
+//! Services loading rate files supplied by customers reject bad ones before using
+//! them with `TaxDatabase::validated`, which fails on any error: rates outside 0 to
+//! 100%, state keys that aren't ISO 3166-2 codes of their country, agreement members
+//! that aren't countries of the data (or their subdivisions), and threshold-based
+//! rules without a threshold.
+
+use std::fmt;
+
+use rust_decimal::Decimal;
+use serde::Serialize;
+
+use crate::certificate::is_iso_date;
+use crate::currency::CurrencyCode;
+use crate::errors::{DatasetError, InputValidationError};
+use crate::migration::{pointer_token, MigrationChange};
+use crate::percentage::Percentage;
+use crate::types::{TaxCalculationType, TaxRuleConfig};
+use crate::TaxDatabase;
+
+/// How serious a validation issue is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    /// The data works, but should be updated
+    Warning,
+    /// The data produces wrong results
+    Error,
+}
+
+/// A problem found in the loaded data.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ValidationIssue {
+    /// How serious the issue is
+    pub severity: Severity,
+    /// The dataset the issue was found in (e.g. "vat_rates")
+    pub document: String,
+    /// JSON Pointer to the offending value
+    pub pointer: String,
+    /// What's wrong with the value
+    pub message: String,
+}
+
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}: {}", self.document, self.pointer, self.message)
+    }
+}
+
+impl TaxDatabase {
+    /// Checks the loaded data and lists the problems found.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use world_tax::TaxDatabase;
+    /// let countries = r#"{ "DE": { "type": "vat", "currency": "EUR", "standard_rate": 0.19, "reduced_rate": false } }"#;
+    /// let db = TaxDatabase::from_json(countries, "{}").unwrap();
+    ///
+    /// let issues = db.validate();
+    /// assert_eq!(issues[0].pointer, "/DE/reduced_rate");
+    /// ```
+    pub fn validate(&self) -> Vec<ValidationIssue> {
+        let mut issues: Vec<ValidationIssue> = self
+            .migration_notes()
+            .iter()
+            .filter(|note| note.change == MigrationChange::BooleanRate)
+            .map(|note| ValidationIssue {
+                severity: Severity::Warning,
+                document: note.document.clone(),
+                pointer: note.pointer.clone(),
+                message: "legacy boolean rate; use null for \"no rate\"".to_string(),
+            })
+            .collect();
+
+        let mut codes: Vec<&String> = self.countries.keys().collect();
+        codes.sort();
+        for code in codes {
+            let country = &self.countries[code];
+            let currency = &country.currency;
+            if let Err(error) = CurrencyCode::parse(currency) {
+                issues.push(ValidationIssue {
+                    severity: Severity::Error,
+                    document: "vat_rates".to_string(),
+                    pointer: format!("/{}/currency", code),
+                    message: error.to_string(),
+                });
+            }
+            let rates = [
+                ("standard_rate", Some(country.standard_rate)),
+                ("reduced_rate", country.reduced_rate),
+                ("reduced_rate_alt", country.reduced_rate_alt),
+                ("super_reduced_rate", country.super_reduced_rate),
+                ("parking_rate", country.parking_rate),
+            ];
+            for (field, rate) in rates {
+                check_rate(&mut issues, format!("/{}/{}", code, field), rate);
+            }
+            for (index, tax) in country.additional_taxes.iter().enumerate() {
+                check_rate(
+                    &mut issues,
+                    format!("/{}/additional_taxes/{}/rate", code, index),
+                    Some(tax.rate),
+                );
+            }
+
+            let mut states: Vec<_> = country.states.iter().flatten().collect();
+            states.sort_by_key(|(key, _)| *key);
+            for (key, state) in states {
+                // The key as written in the file, before it was normalized
+                let written = self
+                    .state_keys
+                    .get(&(code.clone(), key.clone()))
+                    .unwrap_or(key);
+                let pointer = format!("/{}/states/{}", code, pointer_token(written));
+                let is_subdivision = key.strip_prefix(code.as_str()).is_some_and(|rest| {
+                    rest.starts_with('-') && rust_iso3166::iso3166_2::from_code(key).is_some()
+                });
+                if !is_subdivision {
+                    issues.push(ValidationIssue {
+                        severity: Severity::Error,
+                        document: "vat_rates".to_string(),
+                        pointer: pointer.clone(),
+                        message: format!("{} isn't an ISO 3166-2 code of {}", key, code),
+                    });
+                }
+                check_rate(
+                    &mut issues,
+                    format!("{}/standard_rate", pointer),
+                    Some(state.standard_rate),
+                );
+                for (index, bracket) in state.brackets.iter().enumerate() {
+                    check_rate(
+                        &mut issues,
+                        format!("{}/brackets/{}/rate", pointer, index),
+                        Some(bracket.rate),
+                    );
+                }
+            }
+
+            for (index, period) in country.rate_history.iter().enumerate() {
+                let rates = [
+                    ("standard_rate", Some(period.standard_rate)),
+                    ("reduced_rate", period.reduced_rate),
+                    ("reduced_rate_alt", period.reduced_rate_alt),
+                    ("super_reduced_rate", period.super_reduced_rate),
+                    ("parking_rate", period.parking_rate),
+                ];
+                for (field, rate) in rates {
+                    check_rate(
+                        &mut issues,
+                        format!("/{}/rate_history/{}/{}", code, index, field),
+                        rate,
+                    );
+                }
+                for (field, date) in [
+                    ("valid_from", &period.valid_from),
+                    ("valid_until", &period.valid_until),
+                ] {
+                    check_date(
+                        &mut issues,
+                        "vat_rates",
+                        format!("/{}/rate_history/{}/{}", code, index, field),
+                        date,
+                    );
+                }
+            }
+        }
+
+        let mut ids: Vec<&String> = self.trade_agreements.keys().collect();
+        ids.sort();
+        for id in ids {
+            let agreement = &self.trade_agreements[id];
+            for (index, member) in agreement.members.iter().enumerate() {
+                if !self.is_known_member(member) {
+                    issues.push(ValidationIssue {
+                        severity: Severity::Error,
+                        document: "trade_agreements".to_string(),
+                        pointer: format!("/{}/members/{}", id, index),
+                        message: format!(
+                            "member {} is neither a country of the data nor a subdivision of one",
+                            member
+                        ),
+                    });
+                }
+            }
+
+            let rules = &agreement.tax_rules;
+            let versions = std::iter::once((String::new(), rules)).chain(
+                rules
+                    .previous
+                    .iter()
+                    .enumerate()
+                    .map(|(index, rules)| (format!("/previous/{}", index), rules)),
+            );
+            for (path, version) in versions {
+                let configs = [
+                    ("internal_b2b", version.internal_b2b.as_ref()),
+                    ("internal_b2c", version.internal_b2c.as_ref()),
+                    ("external_export", Some(&version.external_export)),
+                    ("fallback", version.fallback.as_ref()),
+                ];
+                for (rule, config) in configs {
+                    if let Some(config) = config {
+                        check_thresholds(
+                            &mut issues,
+                            format!("/{}/tax_rules{}/{}", id, path, rule),
+                            config,
+                        );
+                    }
+                }
+                if let Some(valid_from) = &version.valid_from {
+                    check_date(
+                        &mut issues,
+                        "trade_agreements",
+                        format!("/{}/tax_rules{}/valid_from", id, path),
+                        valid_from,
+                    );
+                }
+            }
+            let periods = &self.trade_agreements[id].membership_periods;
+            let mut members: Vec<&String> = periods.keys().collect();
+            members.sort();
+            for member in members {
+                let period = &periods[member];
+                for (field, date) in [("joined", &period.joined), ("left", &period.left)] {
+                    if let Some(date) = date {
+                        check_date(
+                            &mut issues,
+                            "trade_agreements",
+                            format!("/{}/membership_periods/{}/{}", id, member, field),
+                            date,
+                        );
+                    }
+                }
+            }
+            if rules.fallback.is_some() {
+                continue;
+            }
+            let missing = [
+                ("internal_b2b", rules.internal_b2b.is_none()),
+                ("internal_b2c", rules.internal_b2c.is_none()),
+            ];
+            for (rule, _) in missing.iter().filter(|(_, missing)| *missing) {
+                issues.push(ValidationIssue {
+                    severity: Severity::Warning,
+                    document: "trade_agreements".to_string(),
+                    pointer: format!("/{}/tax_rules/{}", id, rule),
+                    message: "rule missing without a fallback rule; the calculation profile's fallback policy applies".to_string(),
+                });
+            }
+        }
+        issues
+    }
+
+    /// Returns the database if its data has no errors (see `validate`), for loading
+    /// data supplied by others; warnings are accepted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use world_tax::{DatasetError, TaxDatabase};
+    /// let countries = r#"{ "DE": { "type": "vat", "currency": "EUR", "standard_rate": 19 } }"#;
+    ///
+    /// match TaxDatabase::from_json(countries, "{}").unwrap().validated() {
+    ///     Err(DatasetError::InvalidData(issues)) => {
+    ///         assert_eq!(issues[0].pointer, "/DE/standard_rate");
+    ///     }
+    ///     _ => unreachable!(),
+    /// }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns `DatasetError::InvalidData` with the errors found.
+    pub fn validated(self) -> Result<Self, DatasetError> {
+        let errors: Vec<ValidationIssue> = self
+            .validate()
+            .into_iter()
+            .filter(|issue| issue.severity == Severity::Error)
+            .collect();
+        if errors.is_empty() {
+            Ok(self)
+        } else {
+            Err(DatasetError::InvalidData(errors))
+        }
+    }
+
+    /// Returns true if an agreement member exists: a country of the data, or a
+    /// subdivision of one (taxed at the country's rate without a state entry, like
+    /// Alberta).
+    pub(crate) fn is_known_member(&self, member: &str) -> bool {
+        match member.split_once('-') {
+            Some((country, _)) => {
+                self.countries.contains_key(country)
+                    && rust_iso3166::iso3166_2::from_code(member).is_some()
+            }
+            None => self.countries.contains_key(member),
+        }
+    }
+}
+
+/// Adds an error to `issues` if the rate isn't between 0 and 100%.
+fn check_rate(issues: &mut Vec<ValidationIssue>, pointer: String, rate: Option<Percentage>) {
+    let valid = Decimal::ZERO..=Decimal::ONE;
+    if let Some(rate) = rate.filter(|rate| !valid.contains(&rate.fraction())) {
+        issues.push(ValidationIssue {
+            severity: Severity::Error,
+            document: "vat_rates".to_string(),
+            pointer,
+            message: format!("rate {} isn't between 0 and 100%", rate),
+        });
+    }
+}
+
+/// Adds errors to `issues` for thresholds a rule's threshold-based outcomes need, but
+/// which are missing: without them the rule resolves to its `type`.
+fn check_thresholds(issues: &mut Vec<ValidationIssue>, pointer: String, config: &TaxRuleConfig) {
+    let sets = [
+        (
+            ["threshold", "below_threshold", "above_threshold"],
+            [
+                config.threshold.is_some(),
+                config.below_threshold.is_some(),
+                config.above_threshold.is_some(),
+            ],
+        ),
+        (
+            [
+                "threshold_digital_products",
+                "below_threshold_digital_products",
+                "above_threshold_digital_products",
+            ],
+            [
+                config.threshold_digital_products.is_some(),
+                config.below_threshold_digital_products.is_some(),
+                config.above_threshold_digital_products.is_some(),
+            ],
+        ),
+    ];
+    for (index, (fields, present)) in sets.into_iter().enumerate() {
+        // The rule type only requires the standard set; a partial set of either is
+        // incomplete
+        let required = index == 0 && config.r#type == TaxCalculationType::ThresholdBased;
+        if !required && !present.contains(&true) {
+            continue;
+        }
+        for (field, _) in fields.iter().zip(present).filter(|(_, present)| !present) {
+            issues.push(ValidationIssue {
+                severity: Severity::Error,
+                document: "trade_agreements".to_string(),
+                pointer: format!("{}/{}", pointer, field),
+                message: format!(
+                    "missing; threshold-based outcomes need {}",
+                    fields.join(", ")
+                ),
+            });
+        }
+    }
+}
+
+/// Adds an error to `issues` if the date isn't an ISO 8601 date.
+fn check_date(issues: &mut Vec<ValidationIssue>, document: &str, pointer: String, date: &str) {
+    if !is_iso_date(date) {
+        issues.push(ValidationIssue {
+            severity: Severity::Error,
+            document: document.to_string(),
+            pointer,
+            message: InputValidationError::InvalidDate(date.to_string()).to_string(),
+        });
+    }
+}
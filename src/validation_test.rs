@@ -0,0 +1,254 @@
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use crate::validation::Severity;
+    use crate::{DatasetError, TaxDatabase};
+
+    #[test]
+    fn test_legacy_boolean_rates_are_flagged() {
+        let countries = json!({
+            "AT": { "type": "vat", "currency": "EUR", "standard_rate": 0.2, "parking_rate": false },
+            "DE": { "type": "vat", "currency": "EUR", "standard_rate": 0.19, "reduced_rate": 0.07 }
+        })
+        .to_string();
+        let db = TaxDatabase::from_json(&countries, "{}").expect("Legacy data should load");
+
+        let issues = db.validate();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].severity, Severity::Warning);
+        assert_eq!(issues[0].document, "vat_rates");
+        assert_eq!(issues[0].pointer, "/AT/parking_rate");
+        assert_eq!(db.get_country("AT").unwrap().parking_rate(), None);
+    }
+
+    #[test]
+    fn test_true_rate_is_rejected() {
+        let countries = json!({
+            "DE": { "type": "vat", "currency": "EUR", "standard_rate": 0.19, "reduced_rate": true }
+        })
+        .to_string();
+
+        assert!(TaxDatabase::from_json(&countries, "{}").is_err());
+        match TaxDatabase::from_json_strict(&countries, "{}") {
+            Err(DatasetError::SchemaViolations(violations)) => {
+                assert_eq!(violations[0].pointer, "/DE/reduced_rate");
+            }
+            _ => panic!("Expected a schema violation"),
+        }
+    }
+
+    #[test]
+    fn test_booleans_are_invalid_in_current_version() {
+        let countries = json!({
+            "schema_version": 2,
+            "DE": { "type": "vat", "currency": "EUR", "standard_rate": 0.19, "reduced_rate": false }
+        })
+        .to_string();
+        assert!(TaxDatabase::from_json(&countries, "{}").is_err());
+
+        let countries = json!({
+            "schema_version": 2,
+            "DE": { "type": "vat", "currency": "EUR", "standard_rate": 0.19, "reduced_rate": null }
+        })
+        .to_string();
+        let db = TaxDatabase::from_json(&countries, "{}").expect("Null rates should load");
+        assert!(db.validate().is_empty());
+    }
+
+    #[test]
+    fn test_missing_rule_without_fallback() {
+        let countries = json!({
+            "DE": { "type": "vat", "currency": "EUR", "standard_rate": 0.19 },
+            "FR": { "type": "vat", "currency": "EUR", "standard_rate": 0.2 }
+        })
+        .to_string();
+        let agreements = json!({
+            "XU": {
+                "name": "Example Union",
+                "type": "customs_union",
+                "members": ["DE", "FR"],
+                "default_applicable": true,
+                "applies_to": { "physical_goods": true, "digital_goods": true, "services": true },
+                "tax_rules": {
+                    "internal_b2b": { "type": "reverse_charge" },
+                    "external_export": { "type": "zero_rated" }
+                }
+            }
+        });
+        let db = TaxDatabase::from_json(&countries, &agreements.to_string())
+            .expect("Agreements should load");
+        let issues = db.validate();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].severity, Severity::Warning);
+        assert_eq!(issues[0].pointer, "/XU/tax_rules/internal_b2c");
+
+        let mut agreements = agreements;
+        agreements["XU"]["tax_rules"]["fallback"] = json!({ "type": "origin" });
+        let db = TaxDatabase::from_json(&countries, &agreements.to_string())
+            .expect("Agreements should load");
+        assert!(db.validate().is_empty());
+    }
+
+    #[test]
+    fn test_invalid_rule_dates_are_flagged() {
+        let agreements = json!({
+            "XU": {
+                "name": "Example Union",
+                "type": "customs_union",
+                "members": ["DE", "FR"],
+                "default_applicable": true,
+                "applies_to": { "physical_goods": true, "digital_goods": true, "services": true },
+                "tax_rules": {
+                    "fallback": { "type": "origin" },
+                    "external_export": { "type": "zero_rated" },
+                    "valid_from": "2021-07-01",
+                    "previous": [{
+                        "fallback": { "type": "destination" },
+                        "external_export": { "type": "zero_rated" },
+                        "valid_from": "1 July 2015"
+                    }]
+                }
+            }
+        });
+        let countries = json!({
+            "DE": { "type": "vat", "currency": "EUR", "standard_rate": 0.19 },
+            "FR": { "type": "vat", "currency": "EUR", "standard_rate": 0.2 }
+        });
+        let db = TaxDatabase::from_json(&countries.to_string(), &agreements.to_string())
+            .expect("Agreements should load");
+        let issues = db.validate();
+        assert_eq!(issues.len(), 1);
+        assert_eq!(issues[0].severity, Severity::Error);
+        assert_eq!(issues[0].pointer, "/XU/tax_rules/previous/0/valid_from");
+    }
+
+    #[test]
+    fn test_invalid_history_dates_are_flagged() {
+        let countries = json!({
+            "DE": {
+                "type": "vat", "currency": "EUR", "standard_rate": 0.19,
+                "rate_history": [
+                    { "valid_from": "2020-07-01", "valid_until": "2020-12-31", "standard_rate": 0.16 },
+                    { "valid_from": "2007", "valid_until": "2006-12-31", "standard_rate": 0.16 }
+                ]
+            }
+        })
+        .to_string();
+        let agreements = json!({
+            "XU": {
+                "name": "Example Union",
+                "type": "customs_union",
+                "members": ["DE"],
+                "membership_periods": { "DE": { "joined": "01/01/1958" } },
+                "default_applicable": true,
+                "applies_to": { "physical_goods": true, "digital_goods": true, "services": true },
+                "tax_rules": {
+                    "fallback": { "type": "origin" },
+                    "external_export": { "type": "zero_rated" }
+                }
+            }
+        });
+        let db =
+            TaxDatabase::from_json(&countries, &agreements.to_string()).expect("Data should load");
+        let issues = db.validate();
+        let pointers: Vec<&str> = issues.iter().map(|issue| issue.pointer.as_str()).collect();
+        assert_eq!(
+            pointers,
+            [
+                "/DE/rate_history/1/valid_from",
+                "/XU/membership_periods/DE/joined"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_customer_data_errors() {
+        let countries = json!({
+            "DE": {
+                "type": "vat", "currency": "EUR", "standard_rate": 19, "reduced_rate": 0.07,
+                "rate_history": [
+                    { "valid_from": "2020-07-01", "valid_until": "2020-12-31", "standard_rate": -0.16 }
+                ]
+            },
+            "US": {
+                "type": "none", "currency": "USD", "standard_rate": 0,
+                "states": {
+                    "US-CA": { "standard_rate": 0.0725, "type": "vat" },
+                    "US-XX": { "standard_rate": 7.25, "type": "vat" },
+                    "yy": { "standard_rate": 0.05, "type": "vat" },
+                    "a/b~c": { "standard_rate": 0.05, "type": "vat" }
+                }
+            }
+        })
+        .to_string();
+        let agreements = json!({
+            "XU": {
+                "name": "Example Union",
+                "type": "customs_union",
+                "members": ["DE", "FX", "US-MT", "US-QQ"],
+                "default_applicable": true,
+                "applies_to": { "physical_goods": true, "digital_goods": true, "services": true },
+                "tax_rules": {
+                    "internal_b2b": { "type": "reverse_charge" },
+                    "internal_b2c": {
+                        "type": "threshold_based",
+                        "below_threshold": "origin",
+                        "above_threshold": "destination",
+                        "below_threshold_digital_products": "origin"
+                    },
+                    "external_export": { "type": "zero_rated" }
+                }
+            }
+        });
+        let db =
+            TaxDatabase::from_json(&countries, &agreements.to_string()).expect("Data should load");
+
+        let issues = db.validate();
+        assert!(issues.iter().all(|issue| issue.severity == Severity::Error));
+        let pointers: Vec<&str> = issues.iter().map(|issue| issue.pointer.as_str()).collect();
+        assert_eq!(
+            pointers,
+            [
+                "/DE/standard_rate",
+                "/DE/rate_history/0/standard_rate",
+                // Keys as written in the file, escaped
+                "/US/states/a~1b~0c",
+                "/US/states/US-XX",
+                "/US/states/US-XX/standard_rate",
+                "/US/states/yy",
+                "/XU/members/1",
+                "/XU/members/3",
+                "/XU/tax_rules/internal_b2c/threshold",
+                "/XU/tax_rules/internal_b2c/threshold_digital_products",
+                "/XU/tax_rules/internal_b2c/above_threshold_digital_products",
+            ]
+        );
+
+        match db.validated() {
+            Err(DatasetError::InvalidData(errors)) => assert_eq!(errors, issues),
+            _ => panic!("Expected invalid data"),
+        }
+        assert!(TaxDatabase::new().unwrap().validated().is_ok());
+
+        // Also the keys of overlays
+        let mut db = TaxDatabase::fixture();
+        db.merge_json(
+            r#"{ "CA": { "states": { "zz": { "standard_rate": 0.05, "type": "vat" } } } }"#,
+            "{}",
+        )
+        .expect("Overlay should merge");
+        let pointers: Vec<String> = db
+            .validate()
+            .into_iter()
+            .map(|issue| issue.pointer)
+            .collect();
+        assert_eq!(pointers, ["/CA/states/zz"]);
+    }
+
+    #[test]
+    fn test_embedded_data_is_clean() {
+        assert!(TaxDatabase::new().unwrap().validate().is_empty());
+        assert!(TaxDatabase::fixture().validate().is_empty());
+    }
+}
@@ -0,0 +1,832 @@
+//! VAT identification numbers.
+//!
+//! `VatId::parse` checks the format of a VAT number for its country prefix, and its
+//! check digits where the country defines them, for the EU member states (with "EL"
+//! for Greece, and "XI" for Northern Ireland), the UK, Switzerland and Norway. A
+//! syntactically valid number may still be unassigned or revoked; with the
+//! `online-validation` feature, `VatIdVerifier` asks VIES (the EU's VAT Information
+//! Exchange System) or HMRC whether it's registered.
+//!
+//! Either way, the result is a `VatIdCheck`: what was checked, how and when, to keep
+//! as evidence that the buyer is a business when a sale is reverse charged.
+
+use std::fmt;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use typeshare::typeshare;
+
+use crate::errors::InputValidationError;
+use crate::profile::{CalculationProfile, ReverseChargePolicy};
+use crate::{TaxScenario, TradeAgreement};
+
+/// Checks the number after the prefix.
+type Check = fn(&str) -> Validity;
+
+/// Country prefixes, with the ISO 3166-1 code of their country, and the check of the
+/// number after the prefix.
+const FORMATS: [(&str, &str, Check); 31] = [
+    ("AT", "AT", austria),
+    ("BE", "BE", belgium),
+    ("BG", "BG", bulgaria),
+    ("CHE", "CH", switzerland),
+    ("CY", "CY", cyprus),
+    ("CZ", "CZ", czechia),
+    ("DE", "DE", germany),
+    ("DK", "DK", denmark),
+    ("EE", "EE", estonia),
+    ("EL", "GR", greece),
+    ("ES", "ES", spain),
+    ("FI", "FI", finland),
+    ("FR", "FR", france),
+    ("GB", "GB", united_kingdom),
+    ("HR", "HR", croatia),
+    ("HU", "HU", hungary),
+    ("IE", "IE", ireland),
+    ("IT", "IT", italy),
+    ("LT", "LT", lithuania),
+    ("LU", "LU", luxembourg),
+    ("LV", "LV", latvia),
+    ("MT", "MT", malta),
+    ("NL", "NL", netherlands),
+    ("NO", "NO", norway),
+    ("PL", "PL", poland),
+    ("PT", "PT", portugal),
+    ("RO", "RO", romania),
+    ("SE", "SE", sweden),
+    ("SI", "SI", slovenia),
+    ("SK", "SK", slovakia),
+    ("XI", "GB", united_kingdom),
+];
+
+/// Result of checking the number after the prefix.
+enum Validity {
+    Valid,
+    InvalidFormat,
+    InvalidCheckDigits,
+}
+
+impl Validity {
+    /// Valid if the format matches and, if so, the check digits are right.
+    fn check(format: bool, check_digits: impl FnOnce() -> bool) -> Self {
+        if !format {
+            Validity::InvalidFormat
+        } else if !check_digits() {
+            Validity::InvalidCheckDigits
+        } else {
+            Validity::Valid
+        }
+    }
+}
+
+/// A syntactically valid VAT identification number.
+///
+/// Parsed from the country prefix and number, with or without spaces, dots and
+/// dashes ("DE 136 695 976", "CHE-107.787.577 MWST"); displays and serializes in the
+/// compact form ("DE136695976", "CHE107787577").
+///
+/// # Examples
+///
+/// ```
+/// # use world_tax::vat_id::VatId;
+/// let id = VatId::parse("el 094 259 216").unwrap();
+/// assert_eq!(id.to_string(), "EL094259216");
+/// assert_eq!(id.country(), "GR");
+///
+/// // Wrong check digit
+/// assert!(VatId::parse("DE136695977").is_err());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct VatId {
+    prefix: &'static str,
+    country: &'static str,
+    number: String,
+}
+
+impl VatId {
+    /// Parses a VAT number, checking its format and check digits.
+    ///
+    /// # Errors
+    ///
+    /// Returns `InputValidationError::InvalidVatId` if the prefix isn't known, or the
+    /// number doesn't match its country's format or check digits.
+    pub fn parse(input: &str) -> Result<Self, InputValidationError> {
+        let compact: String = input
+            .chars()
+            .filter(|c| !matches!(c, ' ' | '.' | '-' | '/'))
+            .collect::<String>()
+            .to_uppercase();
+        let invalid = |reason: &str| InputValidationError::InvalidVatId {
+            id: input.to_string(),
+            reason: reason.to_string(),
+        };
+
+        let &(prefix, country, check) = FORMATS
+            .iter()
+            .find(|(prefix, _, _)| compact.starts_with(prefix))
+            .ok_or_else(|| invalid("unknown country prefix"))?;
+        let mut number = &compact[prefix.len()..];
+        if !number.is_ascii() {
+            return Err(invalid("invalid format"));
+        }
+        if country == "CH" {
+            number = ["MWST", "TVA", "IVA"]
+                .iter()
+                .find_map(|suffix| number.strip_suffix(suffix))
+                .unwrap_or(number);
+        } else if country == "NO" {
+            number = number.strip_suffix("MVA").unwrap_or(number);
+        }
+
+        match check(number) {
+            Validity::Valid => Ok(Self {
+                prefix,
+                country,
+                number: number.to_string(),
+            }),
+            Validity::InvalidFormat => Err(invalid("invalid format")),
+            Validity::InvalidCheckDigits => Err(invalid("invalid check digits")),
+        }
+    }
+
+    /// The prefix of the number (e.g. "DE", "EL" for Greece, "XI" for Northern Ireland).
+    pub fn prefix(&self) -> &str {
+        self.prefix
+    }
+
+    /// The ISO 3166-1 code of the issuing country (e.g. "GR" for "EL").
+    pub fn country(&self) -> &str {
+        self.country
+    }
+
+    /// The number without the prefix.
+    pub fn number(&self) -> &str {
+        &self.number
+    }
+
+    /// Returns true if the number is registered in VIES: issued by an EU member state,
+    /// or for Northern Ireland ("XI").
+    pub fn is_vies(&self) -> bool {
+        !matches!(self.prefix, "GB" | "CHE" | "NO")
+    }
+
+    /// The evidence of the format check done by `parse`.
+    pub fn format_check(&self) -> VatIdCheck {
+        VatIdCheck {
+            vat_id: self.clone(),
+            valid: true,
+            source: VatIdSource::Format,
+            date: None,
+            name: None,
+            address: None,
+            reference: None,
+        }
+    }
+}
+
+impl fmt::Display for VatId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{}", self.prefix, self.number)
+    }
+}
+
+impl Serialize for VatId {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for VatId {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let id = String::deserialize(deserializer)?;
+        Self::parse(&id).map_err(serde::de::Error::custom)
+    }
+}
+
+/// How a VAT number was checked.
+#[typeshare]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VatIdSource {
+    /// Format and check digits only
+    Format,
+    /// The EU's VAT Information Exchange System
+    Vies,
+    /// HMRC's VAT registration number check
+    Hmrc,
+}
+
+/// The result of checking a VAT number, to keep as evidence for reverse charge.
+#[typeshare]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VatIdCheck {
+    /// The number checked
+    pub vat_id: VatId,
+    /// Whether the number is valid: registered, for online checks
+    pub valid: bool,
+    /// How the number was checked
+    pub source: VatIdSource,
+    /// Date (and time) of the check, as the service states it
+    pub date: Option<String>,
+    /// Name of the registered business, if the service discloses it
+    pub name: Option<String>,
+    /// Address of the registered business, if the service discloses it
+    pub address: Option<String>,
+    /// Reference of the check (VIES request identifier, HMRC consultation number),
+    /// given if the seller's own number was part of the request
+    pub reference: Option<String>,
+}
+
+impl From<VatId> for VatIdCheck {
+    fn from(id: VatId) -> Self {
+        id.format_check()
+    }
+}
+
+/// Returns true if the country's VAT numbers are registered in VIES: the EU member
+/// states (Northern Ireland's "XI" numbers are issued by the UK).
+fn issues_vies_ids(country: &str) -> bool {
+    FORMATS
+        .iter()
+        .any(|&(prefix, code, _)| code == country && !matches!(prefix, "GB" | "CHE" | "NO" | "XI"))
+}
+
+impl TaxScenario {
+    /// Sets the buyer's VAT number: a `VatId`, checked by its format, or the
+    /// `VatIdCheck` of an online verification.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use world_tax::vat_id::VatId;
+    /// # use world_tax::{Region, TaxCalculationType, TaxDatabase, TaxScenario, TransactionType};
+    /// let db = TaxDatabase::fixture();
+    /// let sale = TaxScenario::new(
+    ///     Region::parse("DE").unwrap(),
+    ///     Region::parse("FR").unwrap(),
+    ///     TransactionType::B2B,
+    /// );
+    ///
+    /// // Without the buyer's VAT number, the sale is taxed like a B2C sale
+    /// assert_eq!(
+    ///     sale.determine_calculation_type(&db, 100.0).unwrap(),
+    ///     TaxCalculationType::Destination
+    /// );
+    ///
+    /// let sale = sale.with_buyer_vat_id(VatId::parse("FR40303265045").unwrap());
+    /// assert_eq!(
+    ///     sale.determine_calculation_type(&db, 100.0).unwrap(),
+    ///     TaxCalculationType::ReverseCharge
+    /// );
+    /// ```
+    pub fn with_buyer_vat_id(mut self, check: impl Into<VatIdCheck>) -> Self {
+        self.buyer_vat_id = Some(check.into());
+        self
+    }
+
+    /// Why a B2B sale within a customs union isn't reverse charged, or `None` if it
+    /// can be: the buyer's VAT number must be valid and issued by another member
+    /// state, and is required for buyers in the EU unless the profile trusts the
+    /// transaction type.
+    pub(crate) fn reverse_charge_rejection(
+        &self,
+        agreement: &TradeAgreement,
+        profile: &CalculationProfile,
+    ) -> Option<String> {
+        let Some(check) = &self.buyer_vat_id else {
+            return (profile.reverse_charge == ReverseChargePolicy::RequireVatId
+                && issues_vies_ids(&self.destination_region.country))
+            .then(|| "no buyer VAT ID".to_string());
+        };
+        let country = check.vat_id.country();
+        if !check.valid {
+            Some(format!("{} is not valid", check.vat_id))
+        } else if country == self.source_region.country {
+            Some(format!("{} is from the seller's country", check.vat_id))
+        } else if !agreement.members.iter().any(|member| member == country) {
+            Some(format!(
+                "{} is not from a member of {}",
+                check.vat_id, agreement.name
+            ))
+        } else {
+            None
+        }
+    }
+}
+
+fn digits(number: &str) -> Vec<u32> {
+    number.chars().filter_map(|c| c.to_digit(10)).collect()
+}
+
+fn is_digits(number: &str, lengths: &[usize]) -> bool {
+    lengths.contains(&number.len()) && number.chars().all(|c| c.is_ascii_digit())
+}
+
+/// Sum of the digits multiplied by their weights.
+fn weighted(digits: &[u32], weights: &[u32]) -> u32 {
+    digits.iter().zip(weights).map(|(d, w)| d * w).sum()
+}
+
+/// Luhn checksum, as of Italian and Swedish numbers.
+fn luhn(digits: &[u32]) -> bool {
+    let sum: u32 = digits
+        .iter()
+        .rev()
+        .enumerate()
+        .map(|(i, &d)| {
+            if i % 2 == 1 {
+                (d * 2) / 10 + (d * 2) % 10
+            } else {
+                d
+            }
+        })
+        .sum();
+    sum % 10 == 0
+}
+
+/// ISO 7064 MOD 11,10, as of German and Croatian numbers.
+fn mod_11_10(digits: &[u32]) -> bool {
+    let mut product = 10;
+    for &d in &digits[..digits.len() - 1] {
+        let sum = (d + product) % 10;
+        product = (if sum == 0 { 10 } else { sum } * 2) % 11;
+    }
+    (11 - product) % 10 == digits[digits.len() - 1]
+}
+
+/// Check digit of weights summed modulo 11 and subtracted from 11, where 10 can't
+/// be a check digit and 11 means 0 (Finland, Portugal, Slovenia, Norway).
+fn mod_11(digits: &[u32], weights: &[u32], ten_is_zero: bool) -> bool {
+    let check = match 11 - weighted(digits, weights) % 11 {
+        11 => 0,
+        10 if ten_is_zero => 0,
+        10 => return false,
+        check => check,
+    };
+    check == digits[weights.len()]
+}
+
+fn austria(number: &str) -> Validity {
+    let Some(rest) = number.strip_prefix('U') else {
+        return Validity::InvalidFormat;
+    };
+    Validity::check(is_digits(rest, &[8]), || {
+        let d = digits(rest);
+        let sum: u32 = d[..7]
+            .iter()
+            .enumerate()
+            .map(|(i, &d)| {
+                if i % 2 == 1 {
+                    (d * 2) / 10 + (d * 2) % 10
+                } else {
+                    d
+                }
+            })
+            .sum();
+        (96 - sum) % 10 == d[7]
+    })
+}
+
+fn belgium(number: &str) -> Validity {
+    // Old numbers had 9 digits, now prefixed with 0
+    let number = if number.len() == 9 {
+        format!("0{number}")
+    } else {
+        number.to_string()
+    };
+    Validity::check(
+        is_digits(&number, &[10]) && matches!(&number[..1], "0" | "1"),
+        || {
+            let base: u64 = number[..8].parse().unwrap_or_default();
+            let check: u64 = number[8..].parse().unwrap_or_default();
+            97 - base % 97 == check
+        },
+    )
+}
+
+fn bulgaria(number: &str) -> Validity {
+    Validity::check(is_digits(number, &[9, 10]), || true)
+}
+
+fn switzerland(number: &str) -> Validity {
+    Validity::check(is_digits(number, &[9]), || {
+        let d = digits(number);
+        let check = match 11 - weighted(&d, &[5, 4, 3, 2, 7, 6, 5, 4]) % 11 {
+            11 => 0,
+            check => check,
+        };
+        check == d[8]
+    })
+}
+
+fn cyprus(number: &str) -> Validity {
+    let format = number.len() == 9
+        && number[..8].chars().all(|c| c.is_ascii_digit())
+        && number[8..].chars().all(|c| c.is_ascii_uppercase())
+        && !number.starts_with("12");
+    Validity::check(format, || true)
+}
+
+fn czechia(number: &str) -> Validity {
+    Validity::check(is_digits(number, &[8, 9, 10]), || true)
+}
+
+fn germany(number: &str) -> Validity {
+    Validity::check(is_digits(number, &[9]) && !number.starts_with('0'), || {
+        mod_11_10(&digits(number))
+    })
+}
+
+fn denmark(number: &str) -> Validity {
+    Validity::check(is_digits(number, &[8]) && !number.starts_with('0'), || {
+        weighted(&digits(number), &[2, 7, 6, 5, 4, 3, 2, 1]) % 11 == 0
+    })
+}
+
+fn estonia(number: &str) -> Validity {
+    Validity::check(is_digits(number, &[9]) && number.starts_with("10"), || {
+        let d = digits(number);
+        (10 - weighted(&d, &[3, 7, 1, 3, 7, 1, 3, 7]) % 10) % 10 == d[8]
+    })
+}
+
+fn greece(number: &str) -> Validity {
+    Validity::check(is_digits(number, &[9]), || {
+        let d = digits(number);
+        weighted(&d, &[256, 128, 64, 32, 16, 8, 4, 2]) % 11 % 10 == d[8]
+    })
+}
+
+fn spain(number: &str) -> Validity {
+    let format = number.len() == 9
+        && number[1..8].chars().all(|c| c.is_ascii_digit())
+        && number
+            .chars()
+            .all(|c| c.is_ascii_digit() || c.is_ascii_uppercase());
+    Validity::check(format, || true)
+}
+
+fn finland(number: &str) -> Validity {
+    Validity::check(is_digits(number, &[8]), || {
+        mod_11(&digits(number), &[7, 9, 10, 5, 8, 4, 2], false)
+    })
+}
+
+fn france(number: &str) -> Validity {
+    let format = number.len() == 11
+        && number[2..].chars().all(|c| c.is_ascii_digit())
+        && number[..2]
+            .chars()
+            .all(|c| c.is_ascii_digit() || c.is_ascii_uppercase());
+    Validity::check(format, || {
+        // Keys with letters have no checksum that's documented
+        let Ok(key) = number[..2].parse::<u64>() else {
+            return true;
+        };
+        let siren: u64 = number[2..].parse().unwrap_or_default();
+        (12 + 3 * (siren % 97)) % 97 == key
+    })
+}
+
+fn united_kingdom(number: &str) -> Validity {
+    // Government departments and health authorities
+    if let Some(rest) = number
+        .strip_prefix("GD")
+        .or_else(|| number.strip_prefix("HA"))
+    {
+        return Validity::check(is_digits(rest, &[3]), || true);
+    }
+    Validity::check(is_digits(number, &[9, 12]), || {
+        let d = digits(&number[..9]);
+        let sum = weighted(&d[..7], &[8, 7, 6, 5, 4, 3, 2]) + d[7] * 10 + d[8];
+        sum % 97 == 0 || (sum + 55) % 97 == 0
+    })
+}
+
+fn croatia(number: &str) -> Validity {
+    Validity::check(is_digits(number, &[11]), || mod_11_10(&digits(number)))
+}
+
+fn hungary(number: &str) -> Validity {
+    Validity::check(is_digits(number, &[8]), || {
+        let d = digits(number);
+        (10 - weighted(&d, &[9, 7, 3, 1, 9, 7, 3]) % 10) % 10 == d[7]
+    })
+}
+
+fn ireland(number: &str) -> Validity {
+    let chars: Vec<char> = number.chars().collect();
+    let letter = |c: &char| c.is_ascii_uppercase();
+    let format = match chars.len() {
+        // 1234567T, 1234567TW
+        8 | 9 => {
+            chars[..7].iter().all(char::is_ascii_digit)
+                && letter(&chars[7])
+                && chars[8..].iter().all(|c| matches!(c, 'A'..='I' | 'W'))
+        }
+        _ => false,
+    } || (chars.len() == 8
+        // Old format: 1S23456T
+        && chars[0].is_ascii_digit()
+        && (letter(&chars[1]) || matches!(chars[1], '+' | '*'))
+        && chars[2..7].iter().all(char::is_ascii_digit)
+        && letter(&chars[7]));
+    Validity::check(format, || true)
+}
+
+fn italy(number: &str) -> Validity {
+    Validity::check(is_digits(number, &[11]), || luhn(&digits(number)))
+}
+
+fn lithuania(number: &str) -> Validity {
+    Validity::check(is_digits(number, &[9, 12]), || true)
+}
+
+fn luxembourg(number: &str) -> Validity {
+    Validity::check(is_digits(number, &[8]), || {
+        let base: u64 = number[..6].parse().unwrap_or_default();
+        let check: u64 = number[6..].parse().unwrap_or_default();
+        base % 89 == check
+    })
+}
+
+fn latvia(number: &str) -> Validity {
+    Validity::check(is_digits(number, &[11]), || true)
+}
+
+fn malta(number: &str) -> Validity {
+    Validity::check(is_digits(number, &[8]), || true)
+}
+
+fn netherlands(number: &str) -> Validity {
+    let format = number.len() == 12
+        && is_digits(&number[..9], &[9])
+        && &number[9..10] == "B"
+        && is_digits(&number[10..], &[2]);
+    Validity::check(format, || true)
+}
+
+fn norway(number: &str) -> Validity {
+    Validity::check(is_digits(number, &[9]), || {
+        mod_11(&digits(number), &[3, 2, 7, 6, 5, 4, 3, 2], false)
+    })
+}
+
+fn poland(number: &str) -> Validity {
+    Validity::check(is_digits(number, &[10]), || {
+        let d = digits(number);
+        weighted(&d, &[6, 5, 7, 2, 3, 4, 5, 6, 7]) % 11 == d[9]
+    })
+}
+
+fn portugal(number: &str) -> Validity {
+    Validity::check(is_digits(number, &[9]) && !number.starts_with('0'), || {
+        mod_11(&digits(number), &[9, 8, 7, 6, 5, 4, 3, 2], true)
+    })
+}
+
+fn romania(number: &str) -> Validity {
+    Validity::check(
+        (2..=10).contains(&number.len()) && is_digits(number, &[number.len()]),
+        || {
+            let d = digits(number);
+            let weights = [7, 5, 3, 2, 1, 7, 5, 3, 2];
+            let (body, check) = d.split_at(d.len() - 1);
+            let weights = &weights[weights.len() - body.len()..];
+            weighted(body, weights) * 10 % 11 % 10 == check[0]
+        },
+    )
+}
+
+fn sweden(number: &str) -> Validity {
+    Validity::check(is_digits(number, &[12]) && number.ends_with("01"), || {
+        luhn(&digits(&number[..10]))
+    })
+}
+
+fn slovenia(number: &str) -> Validity {
+    Validity::check(is_digits(number, &[8]) && !number.starts_with('0'), || {
+        mod_11(&digits(number), &[8, 7, 6, 5, 4, 3, 2], true)
+    })
+}
+
+fn slovakia(number: &str) -> Validity {
+    Validity::check(is_digits(number, &[10]) && !number.starts_with('0'), || {
+        number.parse::<u64>().unwrap_or(1) % 11 == 0
+    })
+}
+
+#[cfg(feature = "online-validation")]
+pub use online::VatIdVerifier;
+
+/// Lookups with VIES and HMRC.
+#[cfg(feature = "online-validation")]
+mod online {
+    use serde_json::Value;
+
+    use super::{VatId, VatIdCheck, VatIdSource};
+    use crate::errors::DatabaseError;
+
+    const VIES_URL: &str = "https://ec.europa.eu/taxation_customs/vies/rest-api";
+    const HMRC_URL: &str = "https://api.service.hmrc.gov.uk";
+
+    /// Checks VAT numbers with VIES (EU and Northern Ireland numbers) or HMRC (UK
+    /// numbers).
+    ///
+    /// HMRC's API requires an OAuth access token of an application registered with
+    /// HMRC (`with_hmrc_token`). With the seller's own number (`with_requester`), the
+    /// checks have a reference to keep as proof of the check.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use world_tax::vat_id::{VatId, VatIdVerifier};
+    /// let verifier = VatIdVerifier::new().with_requester(VatId::parse("DE136695976")?);
+    /// let check = verifier.verify(&VatId::parse("FR40303265045")?)?;
+    /// if check.valid {
+    ///     println!("{:?} on {:?}: {:?}", check.name, check.date, check.reference);
+    /// }
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[derive(Debug, Clone)]
+    pub struct VatIdVerifier {
+        vies_url: String,
+        hmrc_url: String,
+        hmrc_token: Option<String>,
+        requester: Option<VatId>,
+        agent: ureq::Agent,
+    }
+
+    impl Default for VatIdVerifier {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    impl VatIdVerifier {
+        /// Creates a verifier for the public VIES and HMRC services.
+        pub fn new() -> Self {
+            Self {
+                vies_url: VIES_URL.to_string(),
+                hmrc_url: HMRC_URL.to_string(),
+                hmrc_token: None,
+                requester: None,
+                agent: ureq::AgentBuilder::new()
+                    .timeout(std::time::Duration::from_secs(30))
+                    .build(),
+            }
+        }
+
+        /// Sets the seller's own VAT number, so checks have a reference.
+        pub fn with_requester(mut self, requester: VatId) -> Self {
+            self.requester = Some(requester);
+            self
+        }
+
+        /// Sets the OAuth access token for HMRC's API.
+        pub fn with_hmrc_token(mut self, token: impl Into<String>) -> Self {
+            self.hmrc_token = Some(token.into());
+            self
+        }
+
+        /// Sets the base URLs of the services (e.g. HMRC's sandbox).
+        pub fn with_urls(mut self, vies: impl Into<String>, hmrc: impl Into<String>) -> Self {
+            self.vies_url = vies.into();
+            self.hmrc_url = hmrc.into();
+            self
+        }
+
+        /// Checks whether a VAT number is registered.
+        ///
+        /// # Errors
+        ///
+        /// Returns `DatabaseError::VatIdLookupFailed` if the service can't be reached,
+        /// or can't answer (e.g. a member state's system is unavailable), and for
+        /// Swiss and Norwegian numbers, which neither service covers.
+        pub fn verify(&self, id: &VatId) -> Result<VatIdCheck, DatabaseError> {
+            match id.prefix() {
+                "GB" => self.hmrc(id),
+                "CHE" | "NO" => Err(DatabaseError::VatIdLookupFailed(format!(
+                    "no online check for {}",
+                    id
+                ))),
+                _ => self.vies(id),
+            }
+        }
+
+        fn vies(&self, id: &VatId) -> Result<VatIdCheck, DatabaseError> {
+            let body = match &self.requester {
+                Some(requester) => self
+                    .agent
+                    .post(&format!("{}/check-vat-number", self.vies_url))
+                    .send_json(serde_json::json!({
+                        "countryCode": id.prefix(),
+                        "vatNumber": id.number(),
+                        "requesterMemberStateCode": requester.prefix(),
+                        "requesterNumber": requester.number(),
+                    })),
+                None => self
+                    .agent
+                    .get(&format!(
+                        "{}/ms/{}/vat/{}",
+                        self.vies_url,
+                        id.prefix(),
+                        id.number()
+                    ))
+                    .call(),
+            }
+            .map_err(lookup_failed)?
+            .into_string()
+            .map_err(lookup_failed)?;
+            vies_check(id, &body)
+        }
+
+        fn hmrc(&self, id: &VatId) -> Result<VatIdCheck, DatabaseError> {
+            let mut url = format!(
+                "{}/organisations/vat/check-vat-number/lookup/{}",
+                self.hmrc_url,
+                id.number()
+            );
+            if let Some(requester) = self.requester.as_ref().filter(|r| r.prefix() == "GB") {
+                url = format!("{}/{}", url, requester.number());
+            }
+            let mut request = self
+                .agent
+                .get(&url)
+                .set("Accept", "application/vnd.hmrc.2.0+json");
+            if let Some(token) = &self.hmrc_token {
+                request = request.set("Authorization", &format!("Bearer {}", token));
+            }
+            match request.call() {
+                Ok(response) => hmrc_check(id, &response.into_string().map_err(lookup_failed)?),
+                // Not registered
+                Err(ureq::Error::Status(404, _)) => Ok(VatIdCheck {
+                    valid: false,
+                    source: VatIdSource::Hmrc,
+                    ..id.format_check()
+                }),
+                Err(error) => Err(lookup_failed(error)),
+            }
+        }
+    }
+
+    fn lookup_failed(error: impl std::fmt::Display) -> DatabaseError {
+        DatabaseError::VatIdLookupFailed(error.to_string())
+    }
+
+    /// Reads a VIES response; names and addresses of "---" aren't disclosed.
+    pub(crate) fn vies_check(id: &VatId, body: &str) -> Result<VatIdCheck, DatabaseError> {
+        let response: Value = serde_json::from_str(body).map_err(lookup_failed)?;
+        let text = |key: &str| {
+            response[key]
+                .as_str()
+                .filter(|value| !value.is_empty() && *value != "---")
+                .map(str::to_string)
+        };
+        match response["userError"].as_str() {
+            None | Some("VALID" | "INVALID") => {}
+            Some(error) => return Err(lookup_failed(error)),
+        }
+        let valid = response["valid"]
+            .as_bool()
+            .or_else(|| response["isValid"].as_bool())
+            .ok_or_else(|| lookup_failed("no result"))?;
+        Ok(VatIdCheck {
+            valid,
+            source: VatIdSource::Vies,
+            date: text("requestDate"),
+            name: text("name"),
+            address: text("address"),
+            reference: text("requestIdentifier"),
+            ..id.format_check()
+        })
+    }
+
+    /// Reads an HMRC response of a registered number.
+    pub(crate) fn hmrc_check(id: &VatId, body: &str) -> Result<VatIdCheck, DatabaseError> {
+        let response: Value = serde_json::from_str(body).map_err(lookup_failed)?;
+        let target = &response["target"];
+        if !target.is_object() {
+            return Err(lookup_failed("no result"));
+        }
+        let address = target["address"].as_object().map(|address| {
+            address
+                .iter()
+                .filter(|(key, _)| *key != "countryCode")
+                .filter_map(|(_, line)| line.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        });
+        Ok(VatIdCheck {
+            valid: true,
+            source: VatIdSource::Hmrc,
+            date: response["processingDate"].as_str().map(str::to_string),
+            name: target["name"].as_str().map(str::to_string),
+            address,
+            reference: response["consultationNumber"].as_str().map(str::to_string),
+            ..id.format_check()
+        })
+    }
+}
+
+#[cfg(all(test, feature = "online-validation"))]
+pub(crate) use online::{hmrc_check, vies_check};
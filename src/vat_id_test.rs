@@ -0,0 +1,227 @@
+#[cfg(test)]
+mod tests {
+    use crate::profile::{CalculationProfile, ReverseChargePolicy};
+    use crate::types::AnnualTurnover;
+    use crate::vat_id::{VatId, VatIdSource};
+    use crate::{
+        InputValidationError, Region, TaxCalculationType, TaxDatabase, TaxScenario, TransactionType,
+    };
+
+    fn sale(source: &str, destination: &str, transaction_type: TransactionType) -> TaxScenario {
+        TaxScenario::new(
+            Region::parse(source).expect("Valid region"),
+            Region::parse(destination).expect("Valid region"),
+            transaction_type,
+        )
+        // Below the distance selling threshold
+        .with_turnover(AnnualTurnover::default())
+    }
+
+    fn calculation_type(scenario: &TaxScenario, db: &TaxDatabase) -> TaxCalculationType {
+        scenario.determine_calculation_type(db, 100.0).unwrap()
+    }
+
+    /// Valid numbers of each country, as people type them.
+    const VALID: [(&str, &str); 31] = [
+        ("ATU13585627", "AT"),
+        ("BE 0403.019.261", "BE"),
+        ("BG175074752", "BG"),
+        ("CHE-107.787.577 IVA", "CH"),
+        ("CY10259033P", "CY"),
+        ("CZ25123891", "CZ"),
+        ("DE 136 695 976", "DE"),
+        ("DK 13 58 56 28", "DK"),
+        ("EE100931558", "EE"),
+        ("EL094259216", "GR"),
+        ("ESB58378431", "ES"),
+        ("FI20774740", "FI"),
+        ("FR 40 303 265 045", "FR"),
+        ("GB 980 7806 84", "GB"),
+        ("HR33392005961", "HR"),
+        ("HU12892312", "HU"),
+        ("IE6433435F", "IE"),
+        ("IT00743110157", "IT"),
+        ("LT119511515", "LT"),
+        ("LU15027442", "LU"),
+        ("LV40003521600", "LV"),
+        ("MT11679112", "MT"),
+        ("NL004495445B01", "NL"),
+        ("NO 995 525 828 MVA", "NO"),
+        ("PL8567346215", "PL"),
+        ("PT501964843", "PT"),
+        ("RO18547290", "RO"),
+        ("SE123456789701", "SE"),
+        ("SI50223054", "SI"),
+        ("SK2022749619", "SK"),
+        ("XI 980 7806 84", "GB"),
+    ];
+
+    #[test]
+    fn test_valid_numbers() {
+        for (input, country) in VALID {
+            let id = VatId::parse(input).unwrap_or_else(|error| panic!("{input}: {error}"));
+            assert_eq!(id.country(), country, "{input}");
+        }
+
+        let swiss = VatId::parse("CHE-107.787.577 IVA").unwrap();
+        assert_eq!(swiss.to_string(), "CHE107787577");
+        assert!(!swiss.is_vies());
+        assert!(VatId::parse("xi980780684").unwrap().is_vies());
+    }
+
+    #[test]
+    fn test_invalid_numbers() {
+        for (input, reason) in [
+            ("US123456789", "unknown country prefix"),
+            ("DE12345678", "invalid format"),
+            ("DE136695977", "invalid check digits"),
+            ("ATU13585626", "invalid check digits"),
+            ("BE0403019262", "invalid check digits"),
+            ("FR41303265045", "invalid check digits"),
+            ("GB980780685", "invalid check digits"),
+            ("IT00743110158", "invalid check digits"),
+            ("NL004495445A01", "invalid format"),
+            ("PL8567346216", "invalid check digits"),
+            ("SE123456789702", "invalid format"),
+        ] {
+            match VatId::parse(input) {
+                Err(InputValidationError::InvalidVatId { id, reason: actual }) => {
+                    assert_eq!((id.as_str(), actual.as_str()), (input, reason))
+                }
+                other => panic!("{input}: {other:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_serialization() {
+        let id = VatId::parse("de 136-695-976").unwrap();
+        assert_eq!(serde_json::to_string(&id).unwrap(), r#""DE136695976""#);
+        let parsed: VatId = serde_json::from_str(r#""DE136695976""#).unwrap();
+        assert_eq!(parsed, id);
+        assert!(serde_json::from_str::<VatId>(r#""DE136695977""#).is_err());
+
+        let check = id.format_check();
+        assert!(check.valid);
+        assert_eq!(check.source, VatIdSource::Format);
+    }
+
+    #[test]
+    fn test_reverse_charge_requires_buyer_vat_id() {
+        let db = TaxDatabase::new().unwrap();
+        let french = VatId::parse("FR40303265045").unwrap();
+
+        // Without a number, taxed like a B2C sale
+        let business = sale("DE", "FR", TransactionType::B2B);
+        assert_eq!(calculation_type(&business, &db), TaxCalculationType::Origin);
+        let explanation = business.explain(100.0, &db).unwrap();
+        assert!(explanation
+            .steps
+            .iter()
+            .any(|step| step.check == "buyer_vat_id" && step.outcome.contains("no buyer VAT ID")));
+
+        let with_id = business.clone().with_buyer_vat_id(french.clone());
+        assert_eq!(
+            calculation_type(&with_id, &db),
+            TaxCalculationType::ReverseCharge
+        );
+        assert_eq!(with_id.calculate_tax(100.0, &db).unwrap(), 0.0);
+
+        // Numbers of the seller's country, of non-members and failed checks don't count
+        for id in ["DE136695976", "GB980780684", "CHE107787577"] {
+            let scenario = business
+                .clone()
+                .with_buyer_vat_id(VatId::parse(id).unwrap());
+            assert_eq!(
+                calculation_type(&scenario, &db),
+                TaxCalculationType::Origin,
+                "{id}"
+            );
+        }
+        let mut unregistered = french.format_check();
+        unregistered.valid = false;
+        unregistered.source = VatIdSource::Vies;
+        let scenario = business.clone().with_buyer_vat_id(unregistered);
+        assert_eq!(calculation_type(&scenario, &db), TaxCalculationType::Origin);
+
+        // A number makes the buyer a business
+        let consumer = sale("DE", "FR", TransactionType::B2C).with_buyer_vat_id(french);
+        assert_eq!(
+            calculation_type(&consumer, &db),
+            TaxCalculationType::ReverseCharge
+        );
+    }
+
+    #[test]
+    fn test_reverse_charge_policy() {
+        let mut db = TaxDatabase::new().unwrap();
+        db.register_profile(
+            "trusting",
+            CalculationProfile {
+                reverse_charge: ReverseChargePolicy::TransactionType,
+                ..Default::default()
+            },
+        );
+        let business = sale("DE", "FR", TransactionType::B2B).with_profile("trusting");
+        assert_eq!(
+            calculation_type(&business, &db),
+            TaxCalculationType::ReverseCharge
+        );
+        let seller_country = business.with_buyer_vat_id(VatId::parse("DE136695976").unwrap());
+        assert_eq!(
+            calculation_type(&seller_country, &db),
+            TaxCalculationType::Origin
+        );
+
+        // Outside the EU, the transaction type decides
+        let gcc = sale("AE", "SA", TransactionType::B2B);
+        assert_eq!(
+            calculation_type(&gcc, &db),
+            TaxCalculationType::ReverseCharge
+        );
+    }
+
+    #[cfg(feature = "online-validation")]
+    #[test]
+    fn test_service_responses() {
+        use crate::vat_id::{hmrc_check, vies_check};
+        use crate::DatabaseError;
+
+        let id = VatId::parse("FR40303265045").unwrap();
+        let check = vies_check(
+            &id,
+            r#"{ "countryCode": "FR", "vatNumber": "40303265045", "requestDate": "2026-10-15T10:00:00.000Z",
+                 "valid": true, "requestIdentifier": "WAPIAAAAZ", "name": "SA ODIGEO FRANCE", "address": "---" }"#,
+        )
+        .unwrap();
+        assert!(check.valid);
+        assert_eq!(check.source, VatIdSource::Vies);
+        assert_eq!(check.name.as_deref(), Some("SA ODIGEO FRANCE"));
+        assert_eq!(check.address, None);
+        assert_eq!(check.reference.as_deref(), Some("WAPIAAAAZ"));
+
+        let unavailable = vies_check(
+            &id,
+            r#"{ "isValid": false, "userError": "MS_UNAVAILABLE" }"#,
+        );
+        assert!(matches!(
+            unavailable,
+            Err(DatabaseError::VatIdLookupFailed(error)) if error == "MS_UNAVAILABLE"
+        ));
+
+        let id = VatId::parse("GB980780684").unwrap();
+        let check = hmrc_check(
+            &id,
+            r#"{ "target": { "name": "Credite Sberger Donal Inc.", "vatNumber": "980780684",
+                 "address": { "line1": "131B Barton Hamlet", "postcode": "SW97 5CK", "countryCode": "GB" } },
+                 "processingDate": "2026-10-15T10:00:00+01:00", "consultationNumber": "Gfl-Dtt-Bkb" }"#,
+        )
+        .unwrap();
+        assert!(check.valid);
+        assert_eq!(
+            check.address.as_deref(),
+            Some("131B Barton Hamlet, SW97 5CK")
+        );
+        assert_eq!(check.reference.as_deref(), Some("Gfl-Dtt-Bkb"));
+    }
+}
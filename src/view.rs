@@ -0,0 +1,201 @@
+//! Read-only views of the loaded tax data.
+//!
+//! `TaxDatabase::get_country` returns a `CountryView` instead of the stored data,
+//! with typed accessors for the rates and states of a country, so the storage can
+//! change without breaking callers.
+
+use std::collections::BTreeMap;
+
+use serde_json::Value;
+
+use crate::percentage::Percentage;
+use crate::types::{
+    normalize_state_code, Country, FlatFee, PostponedAccounting, State, TaxSystemType,
+};
+use crate::VatRate;
+
+/// A country of the loaded data.
+///
+/// # Examples
+///
+/// ```
+/// # use world_tax::{TaxDatabase, VatRate};
+/// # use rust_decimal_macros::dec;
+/// let db = TaxDatabase::fixture();
+/// let germany = db.get_country("DE").unwrap();
+/// assert_eq!(germany.standard_rate().fraction(), dec!(0.19));
+/// assert_eq!(germany.reduced_rates()[0], (VatRate::Reduced, germany.rate(&VatRate::Reduced).unwrap()));
+///
+/// let canada = db.get_country("CA").unwrap();
+/// assert_eq!(canada.states().count(), 3);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct CountryView<'a> {
+    code: &'a str,
+    country: &'a Country,
+}
+
+impl<'a> CountryView<'a> {
+    pub(crate) fn new(code: &'a str, country: &'a Country) -> Self {
+        Self { code, country }
+    }
+
+    /// ISO 3166-1 alpha-2 code of the country (e.g. "DE").
+    pub fn code(&self) -> &'a str {
+        self.code
+    }
+
+    /// Type of tax system used in the country.
+    pub fn tax_system(&self) -> &'a TaxSystemType {
+        &self.country.tax_type
+    }
+
+    /// Currency code of the country (e.g. "EUR").
+    pub fn currency(&self) -> &'a str {
+        &self.country.currency
+    }
+
+    /// Full name of the VAT system, if known.
+    pub fn vat_name(&self) -> Option<&'a str> {
+        self.country.vat_name.as_deref()
+    }
+
+    /// Abbreviation of the VAT system name, if known.
+    pub fn vat_abbr(&self) -> Option<&'a str> {
+        self.country.vat_abbr.as_deref()
+    }
+
+    /// Standard tax rate.
+    pub fn standard_rate(&self) -> Percentage {
+        self.country.standard_rate
+    }
+
+    /// Legal basis of the country's rates, if recorded.
+    pub fn legal_reference(&self) -> Option<&'a str> {
+        self.country.legal_reference.as_deref()
+    }
+
+    /// Extension fields (`x-*`) of the country, like internal tax codes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use world_tax::TaxDatabase;
+    /// let countries = r#"{ "DE": { "type": "vat", "currency": "EUR", "standard_rate": 0.19, "x-sap-tax-code": "A1" } }"#;
+    /// let db = TaxDatabase::from_json(countries, "{}").unwrap();
+    ///
+    /// let germany = db.get_country("DE").unwrap();
+    /// assert_eq!(germany.extensions()["x-sap-tax-code"], "A1");
+    /// ```
+    pub fn extensions(&self) -> &'a BTreeMap<String, Value> {
+        &self.country.extensions
+    }
+
+    /// The rate of a VAT rate category, if the country has one; `Zero` is always 0%.
+    pub fn rate(&self, vat_rate: &VatRate) -> Option<Percentage> {
+        match vat_rate {
+            VatRate::Standard => Some(self.country.standard_rate),
+            VatRate::Reduced => self.country.reduced_rate,
+            VatRate::ReducedAlt => self.country.reduced_rate_alt,
+            VatRate::SuperReduced => self.country.super_reduced_rate,
+            VatRate::Zero => Some(Percentage::ZERO),
+            VatRate::Exempt | VatRate::ReverseCharge => None,
+        }
+    }
+
+    /// The reduced rates of the country (reduced, alternative reduced, super-reduced).
+    pub fn reduced_rates(&self) -> Vec<(VatRate, Percentage)> {
+        [VatRate::Reduced, VatRate::ReducedAlt, VatRate::SuperReduced]
+            .into_iter()
+            .filter_map(|vat_rate| self.rate(&vat_rate).map(|rate| (vat_rate, rate)))
+            .collect()
+    }
+
+    /// Parking rate, if the country has one.
+    pub fn parking_rate(&self) -> Option<Percentage> {
+        self.country.parking_rate
+    }
+
+    /// The scheme for accounting for import VAT in the VAT return, if the country has
+    /// one.
+    pub fn postponed_accounting(&self) -> Option<&'a PostponedAccounting> {
+        self.country
+            .import_rules
+            .as_ref()?
+            .postponed_accounting
+            .as_ref()
+    }
+
+    /// The states or provinces with their own entry, ordered by code.
+    pub fn states(&self) -> impl Iterator<Item = StateView<'a>> {
+        let mut states: Vec<StateView<'a>> = self
+            .country
+            .states
+            .iter()
+            .flatten()
+            .map(|(code, state)| StateView { code, state })
+            .collect();
+        states.sort_by_key(|state| state.code);
+        states.into_iter()
+    }
+
+    /// A state or province by code (e.g. "CA-BC"); bare or lower case codes ("bc")
+    /// are normalized.
+    pub fn state(&self, code: &str) -> Option<StateView<'a>> {
+        let states = self.country.states.as_ref()?;
+        let (code, state) = states
+            .get_key_value(code)
+            .or_else(|| states.get_key_value(&normalize_state_code(self.code, code)))?;
+        Some(StateView { code, state })
+    }
+}
+
+/// A state or province of the loaded data.
+#[derive(Debug, Clone, Copy)]
+pub struct StateView<'a> {
+    code: &'a str,
+    state: &'a State,
+}
+
+impl<'a> StateView<'a> {
+    /// ISO 3166-2 code of the state (e.g. "US-CA").
+    pub fn code(&self) -> &'a str {
+        self.code
+    }
+
+    /// Type of tax system used in the state.
+    pub fn tax_system(&self) -> &'a TaxSystemType {
+        &self.state.tax_type
+    }
+
+    /// Standard tax rate of the state.
+    pub fn standard_rate(&self) -> Percentage {
+        self.state.standard_rate
+    }
+
+    /// Flat fees levied per transaction.
+    pub fn fees(&self) -> &'a [FlatFee] {
+        &self.state.fees
+    }
+
+    /// Whether local jurisdictions levy their own sales tax.
+    pub fn has_local_taxes(&self) -> bool {
+        self.state.local_taxes
+    }
+
+    /// Whether sellers without nexus must notify buyers of their use tax and report
+    /// sales to the state.
+    pub fn has_notice_and_report(&self) -> bool {
+        self.state.notice_and_report
+    }
+
+    /// Legal basis of the state's rate, if recorded.
+    pub fn legal_reference(&self) -> Option<&'a str> {
+        self.state.legal_reference.as_deref()
+    }
+
+    /// Extension fields (`x-*`) of the state, like internal tax codes.
+    pub fn extensions(&self) -> &'a BTreeMap<String, Value> {
+        &self.state.extensions
+    }
+}
@@ -0,0 +1,43 @@
+#[cfg(test)]
+mod tests {
+    use rust_decimal_macros::dec;
+
+    use crate::types::TaxSystemType;
+    use crate::{Percentage, TaxDatabase, VatRate};
+
+    #[test]
+    fn test_country_view_rates() {
+        let db = TaxDatabase::fixture();
+        let france = db.get_country("FR").expect("Country in fixture");
+        assert_eq!(france.code(), "FR");
+        assert_eq!(france.currency(), "EUR");
+        assert_eq!(*france.tax_system(), TaxSystemType::Vat);
+        assert_eq!(france.standard_rate(), Percentage::from_fraction(dec!(0.2)));
+        assert_eq!(
+            france.rate(&VatRate::Zero),
+            Some(Percentage::from_fraction(dec!(0)))
+        );
+        assert_eq!(france.rate(&VatRate::Exempt), None);
+        assert!(france
+            .reduced_rates()
+            .iter()
+            .any(|(vat_rate, rate)| *vat_rate == VatRate::Reduced
+                && *rate == Percentage::from_fraction(dec!(0.1))));
+    }
+
+    #[test]
+    fn test_country_view_states() {
+        let db = TaxDatabase::fixture();
+        let canada = db.get_country("CA").expect("Country in fixture");
+        let codes: Vec<&str> = canada.states().map(|state| state.code()).collect();
+        assert_eq!(codes, vec!["CA-BC", "CA-ON", "CA-QC"]);
+
+        let british_columbia = canada.state("CA-BC").expect("State in fixture");
+        assert_eq!(
+            british_columbia.standard_rate(),
+            Percentage::from_fraction(dec!(0.07))
+        );
+        assert!(canada.state("CA-AB").is_none());
+        assert_eq!(db.get_country("DE").unwrap().states().count(), 0);
+    }
+}
@@ -0,0 +1,190 @@
+//! Non-fatal issues found while calculating tax.
+//!
+//! Warnings don't stop a calculation, but flag results that may not be what the
+//! caller expects, like a region that isn't in the data and silently fell back to
+//! the country's rate, or a sale without nexus where the buyer owes use tax.
+
+use serde::Serialize;
+use typeshare::typeshare;
+
+use crate::profile::NoNexusPolicy;
+use crate::{RateSource, TaxCalculationType, TaxDatabase, TaxRate, TaxScenario, TransactionType};
+
+/// A non-fatal issue with a calculation result.
+#[typeshare]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", content = "content")]
+pub enum Warning {
+    /// The region has no entry in the data; the country's rate was used instead
+    RegionDefaulted {
+        /// The requested region code (e.g. "CA-AB")
+        region: String,
+        /// The country whose rate was used
+        country: String,
+    },
+    /// Local jurisdictions of the region levy their own sales tax, but no local rate
+    /// was applied (no local rates loaded, or no postal code given)
+    LocalTaxesPossible {
+        /// The region code (e.g. "US-AK")
+        region: String,
+    },
+    /// The local rate is administered by a home-rule jurisdiction; separate
+    /// registration and remittance may be required
+    HomeRuleJurisdiction {
+        /// The region code (e.g. "US-CO")
+        region: String,
+        /// Name of the jurisdiction, if known
+        jurisdiction: Option<String>,
+    },
+    /// No tax was collected because the seller has no nexus in the destination state;
+    /// the buyer owes use tax there (see `NoNexusPolicy::UseTaxNotice`)
+    UseTaxNotice {
+        /// The destination region code (e.g. "US-CA")
+        region: String,
+    },
+    /// The scenario's currency isn't the currency of the destination; amounts are
+    /// taken as given, and only converted to compare them against thresholds
+    CurrencyMismatch {
+        /// The scenario's currency (e.g. "USD")
+        currency: String,
+        /// The destination's currency (e.g. "EUR")
+        destination_currency: String,
+    },
+    /// One party is in a country commonly taken for a member of the other party's
+    /// agreement, but isn't one (e.g. Norway and the EU); the sale was calculated as an
+    /// export or import
+    NotAgreementMember {
+        /// The country that isn't a member (e.g. "NO")
+        country: String,
+        /// Identifier of the agreement (e.g. "EU")
+        agreement: String,
+    },
+    /// The rates service was unavailable; rates of the embedded dataset were used,
+    /// which may be out of date (see `resilience::ResilientProvider`)
+    StaleRates {
+        /// Why the embedded dataset was used (e.g. "circuit open")
+        reason: String,
+    },
+    /// The country has no entry in the data; it was taxed as the database's
+    /// `MissingCountryPolicy` says
+    CountryDefaulted {
+        /// The country code (e.g. "FJ")
+        country: String,
+    },
+    /// Override rules of the same priority with different actions match the sale; the
+    /// one added first applied (see `overrides`)
+    OverrideConflict {
+        /// Name of the rule that applied
+        rule: String,
+        /// Names of the rules that didn't
+        conflicting: Vec<String>,
+    },
+}
+
+impl TaxScenario {
+    /// Lists the warnings for rates resolved with the given calculation type.
+    pub(crate) fn warnings(
+        &self,
+        calculation_type: &TaxCalculationType,
+        rates: &[TaxRate],
+        db: &TaxDatabase,
+    ) -> Vec<Warning> {
+        let mut warnings = Vec::new();
+
+        if self.is_use_tax_notice(calculation_type, db) {
+            if let Some(code) = &self.destination_region.region {
+                warnings.push(Warning::UseTaxNotice {
+                    region: code.clone(),
+                });
+            }
+        }
+
+        if let Some(currency) = self.currency {
+            let destination_currency = db.currency(&self.destination_region.country);
+            if let Some(destination_currency) = destination_currency.filter(|c| *c != currency) {
+                warnings.push(Warning::CurrencyMismatch {
+                    currency: currency.to_string(),
+                    destination_currency: destination_currency.to_string(),
+                });
+            }
+        }
+
+        if self.trade_agreement_override.is_none() {
+            let non_member = db.non_member_agreement(
+                &self.source_region.country,
+                &self.destination_region.country,
+            );
+            if let Some((agreement, country)) = non_member {
+                warnings.push(Warning::NotAgreementMember {
+                    country: country.to_string(),
+                    agreement: agreement.to_string(),
+                });
+            }
+        }
+
+        if let Some(rule) = self.override_rule(db) {
+            let conflicts = self.override_conflicts(db);
+            if !conflicts.is_empty() {
+                warnings.push(Warning::OverrideConflict {
+                    rule: rule.name.clone(),
+                    conflicting: conflicts.iter().map(|rule| rule.name.clone()).collect(),
+                });
+            }
+        }
+
+        let region = match calculation_type {
+            TaxCalculationType::Origin => &self.source_region,
+            _ => &self.destination_region,
+        };
+        if *calculation_type != TaxCalculationType::ZeroRated
+            && db.is_defaulted_country(&region.country)
+        {
+            warnings.push(Warning::CountryDefaulted {
+                country: region.country.clone(),
+            });
+        }
+        if let Some(code) = &region.region {
+            if rates.iter().any(|rate| rate.source == RateSource::Fallback) {
+                warnings.push(Warning::RegionDefaulted {
+                    region: code.clone(),
+                    country: region.country.clone(),
+                });
+            }
+
+            let collects = matches!(
+                calculation_type,
+                TaxCalculationType::Destination | TaxCalculationType::Origin
+            );
+            let has_local_rate = rates.iter().any(|rate| rate.source == RateSource::Local);
+            if collects && db.has_local_taxes(&region.country, code) && !has_local_rate {
+                warnings.push(Warning::LocalTaxesPossible {
+                    region: code.clone(),
+                });
+            }
+
+            if has_local_rate {
+                if let Some(record) = self.local_record(code, db).filter(|r| r.home_rule) {
+                    warnings.push(Warning::HomeRuleJurisdiction {
+                        region: code.clone(),
+                        jurisdiction: record.jurisdiction.clone(),
+                    });
+                }
+            }
+        }
+
+        warnings
+    }
+
+    /// Returns true if the sale is a US B2C sale without nexus, and the profile asks
+    /// for a use tax notice.
+    fn is_use_tax_notice(&self, calculation_type: &TaxCalculationType, db: &TaxDatabase) -> bool {
+        *calculation_type == TaxCalculationType::ZeroRated
+            && self.transaction_type == TransactionType::B2C
+            && self.source_region.country == "US"
+            && self.destination_region.country == "US"
+            && !self.is_same_state()
+            && self
+                .resolve_profile(db)
+                .is_ok_and(|profile| profile.no_nexus == NoNexusPolicy::UseTaxNotice)
+    }
+}